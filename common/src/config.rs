@@ -0,0 +1,31 @@
+use std::ffi::OsStr;
+
+/// Gets an expected configuration value from the environment. The error is the message each
+/// domain crate wraps into its own error type.
+pub fn config_value(key: impl AsRef<OsStr>) -> Result<String, String> {
+    let key_str = key.as_ref().to_string_lossy().to_string();
+    std::env::var(key).map_err(|_| format!("Missing config key: {key_str}"))
+}
+
+/// Gets an optional configuration value from the environment, parsed to `T`.
+/// Missing or unparsable values result in `None`.
+pub fn get_optional_config<T: std::str::FromStr>(key: impl AsRef<OsStr>) -> Option<T> {
+    std::env::var(key).ok().and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{config_value, get_optional_config};
+
+    #[test]
+    fn reports_missing_keys() {
+        assert_eq!(
+            config_value("GOLEM_AI_COMMON_TEST_UNDEFINED_KEY"),
+            Err("Missing config key: GOLEM_AI_COMMON_TEST_UNDEFINED_KEY".to_string())
+        );
+        assert_eq!(
+            get_optional_config::<u32>("GOLEM_AI_COMMON_TEST_UNDEFINED_KEY"),
+            None
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use reqwest::StatusCode;
+
+/// The kind of failure a provider reported with an HTTP status code. Each domain crate maps these
+/// to its own error codes, so the status codes are interpreted the same way everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusClass {
+    RateLimited,
+    /// Missing or invalid credentials, or an account without the required plan or credits
+    Unauthorized,
+    NotFound,
+    Conflict,
+    InvalidRequest,
+    ProviderError,
+}
+
+pub fn classify_status(status: StatusCode) -> StatusClass {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        StatusClass::RateLimited
+    } else if status == StatusCode::UNAUTHORIZED
+        || status == StatusCode::FORBIDDEN
+        || status == StatusCode::PAYMENT_REQUIRED
+    {
+        StatusClass::Unauthorized
+    } else if status == StatusCode::NOT_FOUND {
+        StatusClass::NotFound
+    } else if status == StatusCode::CONFLICT {
+        StatusClass::Conflict
+    } else if status.is_client_error() {
+        StatusClass::InvalidRequest
+    } else {
+        StatusClass::ProviderError
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{classify_status, StatusClass};
+    use reqwest::StatusCode;
+
+    #[test]
+    fn classifies_status_codes() {
+        assert_eq!(
+            classify_status(StatusCode::TOO_MANY_REQUESTS),
+            StatusClass::RateLimited
+        );
+        assert_eq!(
+            classify_status(StatusCode::PAYMENT_REQUIRED),
+            StatusClass::Unauthorized
+        );
+        assert_eq!(
+            classify_status(StatusCode::NOT_FOUND),
+            StatusClass::NotFound
+        );
+        assert_eq!(classify_status(StatusCode::CONFLICT), StatusClass::Conflict);
+        assert_eq!(
+            classify_status(StatusCode::UNPROCESSABLE_ENTITY),
+            StatusClass::InvalidRequest
+        );
+        assert_eq!(
+            classify_status(StatusCode::BAD_GATEWAY),
+            StatusClass::ProviderError
+        );
+    }
+}
@@ -0,0 +1,428 @@
+use super::stream::{LlmStream, StreamError as AwsEventStreamError};
+use crate::event_source::MessageEvent;
+use golem_rust::bindings::wasi::io::streams::{InputStream, StreamError};
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use std::task::Poll;
+
+/// The prelude holds the total length, the length of the headers and the checksum of both
+const PRELUDE_LEN: usize = 12;
+
+/// The prelude and the checksum of the whole message
+const MIN_MESSAGE_LEN: usize = PRELUDE_LEN + 4;
+
+/// The limits of the encoding, rejecting a corrupted prelude before buffering its length
+const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+const MAX_HEADERS_LEN: usize = 128 * 1024;
+
+/// A Stream of messages in the binary AWS event stream encoding
+/// (`application/vnd.amazon.eventstream`), used by the streaming APIs of AWS services
+pub struct AwsEventStream {
+    subscription: Pollable,
+    stream: InputStream,
+    buffer: Vec<u8>,
+    terminated: bool,
+    last_event_id: String,
+}
+
+impl AwsEventStream {
+    const CHUNK_SIZE: u64 = 4096;
+}
+
+impl LlmStream for AwsEventStream {
+    fn new(stream: InputStream) -> Self {
+        let subscription = stream.subscribe();
+        Self {
+            subscription,
+            stream,
+            buffer: Vec::new(),
+            terminated: false,
+            last_event_id: String::new(),
+        }
+    }
+
+    fn set_last_event_id(&mut self, id: impl Into<String>) {
+        self.last_event_id = id.into();
+    }
+
+    fn last_event_id(&self) -> &str {
+        &self.last_event_id
+    }
+
+    fn subscribe(&self) -> Pollable {
+        self.stream.subscribe()
+    }
+
+    fn poll_next(
+        &mut self,
+    ) -> Poll<Option<Result<MessageEvent, AwsEventStreamError<StreamError>>>> {
+        trace!("Polling for next AWS event stream message");
+
+        loop {
+            match decode_message(&mut self.buffer) {
+                Ok(Some(message)) => {
+                    return Poll::Ready(Some(message.into_event(&self.last_event_id)));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    self.buffer.clear();
+                    self.terminated = true;
+                    return Poll::Ready(Some(Err(AwsEventStreamError::Frame(err.to_string()))));
+                }
+            }
+
+            if self.terminated {
+                if self.buffer.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let len = self.buffer.len();
+                self.buffer.clear();
+                return Poll::Ready(Some(Err(AwsEventStreamError::Frame(format!(
+                    "stream ended within a message, {len} bytes left"
+                )))));
+            }
+
+            if !self.subscription.ready() {
+                return Poll::Pending;
+            }
+            match self.stream.read(Self::CHUNK_SIZE) {
+                Ok(bytes) => {
+                    trace!("Read {} bytes from response stream", bytes.len());
+                    self.buffer.extend_from_slice(&bytes);
+                }
+                Err(StreamError::Closed) => {
+                    trace!("Response stream closed");
+                    self.terminated = true;
+                }
+                Err(err) => return Poll::Ready(Some(Err(AwsEventStreamError::Transport(err)))),
+            }
+        }
+    }
+}
+
+/// A decoded message of an AWS event stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub headers: Vec<(String, HeaderValue)>,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    pub fn header(&self, name: &str) -> Option<&HeaderValue> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value)
+    }
+
+    fn string_header(&self, name: &str) -> Option<&str> {
+        match self.header(name) {
+            Some(HeaderValue::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The event is named after the `:event-type` of events and the `:exception-type` of
+    /// exceptions, and its data is the payload, usually a JSON document. Errors without a payload
+    /// are named after their `:error-code`, with the `:error-message` as data.
+    fn into_event<E>(self, id: &str) -> Result<MessageEvent, AwsEventStreamError<E>> {
+        let (event, data) = match self.string_header(":message-type") {
+            Some("exception") => (self.string_header(":exception-type"), None),
+            Some("error") => (
+                self.string_header(":error-code"),
+                self.string_header(":error-message"),
+            ),
+            _ => (self.string_header(":event-type"), None),
+        };
+        let event = event.unwrap_or("message").to_string();
+        let data = data.map(str::to_string);
+        let data = match data {
+            Some(data) if self.payload.is_empty() => data,
+            _ => String::from_utf8(self.payload).map_err(AwsEventStreamError::Utf8)?,
+        };
+        Ok(MessageEvent {
+            event,
+            data,
+            id: id.to_string(),
+            retry: None,
+        })
+    }
+}
+
+/// The typed values of message headers
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderValue {
+    Bool(bool),
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Bytes(Vec<u8>),
+    String(String),
+    /// Milliseconds since the Unix epoch
+    Timestamp(i64),
+    Uuid([u8; 16]),
+}
+
+/// Error raised for a message that is not valid in the AWS event stream encoding
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum FrameError {
+    #[error("invalid message length {0}")]
+    InvalidLength(usize),
+    #[error("invalid headers length {0}")]
+    InvalidHeadersLength(usize),
+    #[error("prelude checksum mismatch, expected {expected:#010x}, got {actual:#010x}")]
+    PreludeChecksum { expected: u32, actual: u32 },
+    #[error("message checksum mismatch, expected {expected:#010x}, got {actual:#010x}")]
+    MessageChecksum { expected: u32, actual: u32 },
+    #[error("invalid header: {0}")]
+    InvalidHeader(String),
+}
+
+/// Decodes the first message of the buffer and removes it from the buffer. Returns `None` while
+/// the buffer does not hold a whole message yet.
+pub fn decode_message(buffer: &mut Vec<u8>) -> Result<Option<Message>, FrameError> {
+    if buffer.len() < PRELUDE_LEN {
+        return Ok(None);
+    }
+
+    let total_len = be_u32(&buffer[0..4]) as usize;
+    let headers_len = be_u32(&buffer[4..8]) as usize;
+    let actual = crc32(&buffer[0..8]);
+    let expected = be_u32(&buffer[8..12]);
+    if actual != expected {
+        return Err(FrameError::PreludeChecksum { expected, actual });
+    }
+    if !(MIN_MESSAGE_LEN..=MAX_MESSAGE_LEN).contains(&total_len) {
+        return Err(FrameError::InvalidLength(total_len));
+    }
+    if headers_len > MAX_HEADERS_LEN || headers_len > total_len - MIN_MESSAGE_LEN {
+        return Err(FrameError::InvalidHeadersLength(headers_len));
+    }
+    if buffer.len() < total_len {
+        return Ok(None);
+    }
+
+    let message = buffer.drain(..total_len).collect::<Vec<_>>();
+    let actual = crc32(&message[..total_len - 4]);
+    let expected = be_u32(&message[total_len - 4..]);
+    if actual != expected {
+        return Err(FrameError::MessageChecksum { expected, actual });
+    }
+
+    let headers = decode_headers(&message[PRELUDE_LEN..PRELUDE_LEN + headers_len])?;
+    let payload = message[PRELUDE_LEN + headers_len..total_len - 4].to_vec();
+    Ok(Some(Message { headers, payload }))
+}
+
+fn decode_headers(mut data: &[u8]) -> Result<Vec<(String, HeaderValue)>, FrameError> {
+    let mut headers = Vec::new();
+    while !data.is_empty() {
+        let name_len = take(&mut data, 1)?[0] as usize;
+        let name = String::from_utf8(take(&mut data, name_len)?.to_vec())
+            .map_err(|_| FrameError::InvalidHeader("header name is not UTF-8".to_string()))?;
+        let value = match take(&mut data, 1)?[0] {
+            0 => HeaderValue::Bool(true),
+            1 => HeaderValue::Bool(false),
+            2 => HeaderValue::Byte(take(&mut data, 1)?[0] as i8),
+            3 => HeaderValue::Short(i16::from_be_bytes(take_array(&mut data)?)),
+            4 => HeaderValue::Int(i32::from_be_bytes(take_array(&mut data)?)),
+            5 => HeaderValue::Long(i64::from_be_bytes(take_array(&mut data)?)),
+            6 => {
+                let len = u16::from_be_bytes(take_array(&mut data)?) as usize;
+                HeaderValue::Bytes(take(&mut data, len)?.to_vec())
+            }
+            7 => {
+                let len = u16::from_be_bytes(take_array(&mut data)?) as usize;
+                let value = String::from_utf8(take(&mut data, len)?.to_vec()).map_err(|_| {
+                    FrameError::InvalidHeader(format!("value of {name} is not UTF-8"))
+                })?;
+                HeaderValue::String(value)
+            }
+            8 => HeaderValue::Timestamp(i64::from_be_bytes(take_array(&mut data)?)),
+            9 => HeaderValue::Uuid(take_array(&mut data)?),
+            value_type => {
+                return Err(FrameError::InvalidHeader(format!(
+                    "unknown value type {value_type} of {name}"
+                )))
+            }
+        };
+        headers.push((name, value));
+    }
+    Ok(headers)
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], FrameError> {
+    if data.len() < len {
+        return Err(FrameError::InvalidHeader("truncated header".to_string()));
+    }
+    let (taken, rest) = data.split_at(len);
+    *data = rest;
+    Ok(taken)
+}
+
+fn take_array<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], FrameError> {
+    Ok(take(data, N)?
+        .try_into()
+        .expect("slice of the array length"))
+}
+
+fn be_u32(data: &[u8]) -> u32 {
+    u32::from_be_bytes(data.try_into().expect("slice of 4 bytes"))
+}
+
+/// The CRC-32 (IEEE) checksum used by the prelude and the message checksums
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_source::aws_event_stream::{
+        crc32, decode_message, FrameError, HeaderValue, Message,
+    };
+    use crate::event_source::stream::StreamError;
+
+    fn encode(headers: &[(&str, &str)], payload: &[u8]) -> Vec<u8> {
+        let mut encoded_headers = Vec::new();
+        for (name, value) in headers {
+            encoded_headers.push(name.len() as u8);
+            encoded_headers.extend(name.as_bytes());
+            encoded_headers.push(7);
+            encoded_headers.extend((value.len() as u16).to_be_bytes());
+            encoded_headers.extend(value.as_bytes());
+        }
+        let total_len = 16 + encoded_headers.len() + payload.len();
+        let mut message = Vec::new();
+        message.extend((total_len as u32).to_be_bytes());
+        message.extend((encoded_headers.len() as u32).to_be_bytes());
+        message.extend(crc32(&message).to_be_bytes());
+        message.extend(encoded_headers);
+        message.extend(payload);
+        message.extend(crc32(&message).to_be_bytes());
+        message
+    }
+
+    fn event(event_type: &str, payload: &str) -> Vec<u8> {
+        encode(
+            &[(":message-type", "event"), (":event-type", event_type)],
+            payload.as_bytes(),
+        )
+    }
+
+    #[test]
+    fn computes_the_checksum() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn decodes_messages_split_across_reads() {
+        let first = event("contentBlockDelta", r#"{"delta":{"text":"Hi"}}"#);
+        let second = event("messageStop", r#"{"stopReason":"end_turn"}"#);
+        let mut stream = [first.clone(), second].concat();
+
+        let mut buffer = stream.drain(..first.len() - 1).collect::<Vec<_>>();
+        assert_eq!(decode_message(&mut buffer), Ok(None));
+
+        buffer.extend(stream);
+        let message = decode_message(&mut buffer).unwrap().unwrap();
+        assert_eq!(
+            message.header(":event-type"),
+            Some(&HeaderValue::String("contentBlockDelta".to_string()))
+        );
+        assert_eq!(message.payload, br#"{"delta":{"text":"Hi"}}"#);
+
+        let event = decode_message(&mut buffer)
+            .unwrap()
+            .unwrap()
+            .into_event::<()>("")
+            .unwrap();
+        assert_eq!(event.event, "messageStop");
+        assert_eq!(event.data, r#"{"stopReason":"end_turn"}"#);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn names_exceptions_and_errors_after_their_type() {
+        let exception = Message {
+            headers: vec![
+                (
+                    ":message-type".to_string(),
+                    HeaderValue::String("exception".to_string()),
+                ),
+                (
+                    ":exception-type".to_string(),
+                    HeaderValue::String("throttlingException".to_string()),
+                ),
+            ],
+            payload: br#"{"message":"Too many requests"}"#.to_vec(),
+        };
+        let event = exception.into_event::<()>("").unwrap();
+        assert_eq!(event.event, "throttlingException");
+        assert_eq!(event.data, r#"{"message":"Too many requests"}"#);
+
+        let mut buffer = encode(
+            &[
+                (":message-type", "error"),
+                (":error-code", "InternalFailure"),
+                (":error-message", "Something went wrong"),
+            ],
+            b"",
+        );
+        let event = decode_message(&mut buffer)
+            .unwrap()
+            .unwrap()
+            .into_event::<()>("")
+            .unwrap();
+        assert_eq!(event.event, "InternalFailure");
+        assert_eq!(event.data, "Something went wrong");
+
+        let invalid = Message {
+            headers: vec![],
+            payload: vec![0xff],
+        };
+        assert!(matches!(
+            invalid.into_event::<()>(""),
+            Err(StreamError::Utf8(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_corrupted_messages() {
+        let message = event("messageStop", "{}");
+
+        let mut prelude = message.clone();
+        prelude[3] ^= 1;
+        assert!(matches!(
+            decode_message(&mut prelude),
+            Err(FrameError::PreludeChecksum { .. })
+        ));
+
+        let mut payload = message.clone();
+        let last_payload_byte = payload.len() - 5;
+        payload[last_payload_byte] ^= 1;
+        assert!(matches!(
+            decode_message(&mut payload),
+            Err(FrameError::MessageChecksum { .. })
+        ));
+
+        // Headers longer than the message
+        let mut headers = vec![0, 0, 0, 16, 0, 0, 0, 1];
+        headers.extend(crc32(&headers).to_be_bytes());
+        assert_eq!(
+            decode_message(&mut headers),
+            Err(FrameError::InvalidHeadersLength(1))
+        );
+    }
+}
@@ -32,6 +32,9 @@ pub enum Error {
     /// Source stream is not a valid EventStream
     #[error("Protocol parser error: {0:?}")]
     Parser(NomError<String>),
+    /// Source stream is not a valid AWS event stream
+    #[error("Event stream frame error: {0}")]
+    Frame(String),
     /// The HTTP Request could not be completed
     #[error(transparent)]
     Transport(ReqwestError),
@@ -58,6 +61,7 @@ impl From<StreamError<ReqwestError>> for Error {
             StreamError::Utf8(err) => Self::Utf8(err),
             StreamError::Parser(err) => Self::Parser(err),
             StreamError::Transport(err) => Self::Transport(err),
+            StreamError::Frame(err) => Self::Frame(err),
         }
     }
 }
@@ -67,6 +71,7 @@ impl From<StreamError<WasiStreamError>> for Error {
         match err {
             StreamError::Utf8(err) => Self::Utf8(err),
             StreamError::Parser(err) => Self::Parser(err),
+            StreamError::Frame(err) => Self::Frame(err),
             StreamError::Transport(err) => match err {
                 WasiStreamError::Closed => Self::StreamEnded,
                 WasiStreamError::LastOperationFailed(err) => {
@@ -54,8 +54,10 @@ impl EventBuilder {
                         }
                     }
                     "retry" => {
-                        if let Ok(val) = val.parse::<u64>() {
-                            self.event.retry = Some(Duration::from_millis(val))
+                        if val.chars().all(|c| c.is_ascii_digit()) {
+                            if let Ok(val) = val.parse::<u64>() {
+                                self.event.retry = Some(Duration::from_millis(val))
+                            }
                         }
                     }
                     _ => {}
@@ -148,6 +150,7 @@ impl LlmStream for EventStream {
     /// last event ID
     fn set_last_event_id(&mut self, id: impl Into<String>) {
         self.last_event_id = id.into();
+        self.builder.event.id = self.last_event_id.clone();
     }
 
     /// Get the last event ID of the stream
@@ -186,11 +189,7 @@ impl LlmStream for EventStream {
                         &string
                     } else {
                         self.state = EventStreamState::Started;
-                        if is_bom(string.chars().next().unwrap()) {
-                            &string[1..]
-                        } else {
-                            &string
-                        }
+                        string.strip_prefix(is_bom).unwrap_or(&string)
                     };
                     self.buffer.push_str(slice);
 
@@ -206,7 +205,14 @@ impl LlmStream for EventStream {
                 Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err.into()))),
                 Poll::Ready(None) => {
                     self.state = EventStreamState::Terminated;
-                    return Poll::Ready(None);
+                    return match parse_last_event(&mut self.buffer, &mut self.builder) {
+                        Ok(Some(event)) => {
+                            self.last_event_id = event.id.clone();
+                            Poll::Ready(Some(Ok(event)))
+                        }
+                        Err(err) => Poll::Ready(Some(Err(err))),
+                        Ok(None) => Poll::Ready(None),
+                    };
                 }
                 Poll::Pending => return Poll::Pending,
             }
@@ -239,3 +245,157 @@ fn parse_event<E>(
         }
     }
 }
+
+/// At the end of the stream a trailing CR ends its line, instead of waiting for a LF that could
+/// make it a CRLF. An event without the blank line ending it is discarded, as the spec requires.
+fn parse_last_event<E>(
+    buffer: &mut String,
+    builder: &mut EventBuilder,
+) -> Result<Option<MessageEvent>, EventStreamError<E>> {
+    if buffer.ends_with('\u{000D}') {
+        buffer.push('\u{000A}');
+    }
+    parse_event(buffer, builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_source::event_stream::{parse_event, parse_last_event, EventBuilder};
+    use crate::event_source::MessageEvent;
+    use std::time::Duration;
+
+    /// Feeds the chunks to the parser one by one, then ends the stream
+    fn parse(chunks: &[&str]) -> Vec<MessageEvent> {
+        let mut buffer = String::new();
+        let mut builder = EventBuilder::default();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            buffer.push_str(chunk);
+            while let Some(event) = parse_event::<()>(&mut buffer, &mut builder).unwrap() {
+                events.push(event);
+            }
+        }
+        while let Some(event) = parse_last_event::<()>(&mut buffer, &mut builder).unwrap() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn parses_events() {
+        let cases: &[(&str, &[&str], &[(&str, &str, &str)])] = &[
+            (
+                "single event",
+                &["data: hello\n\n"],
+                &[("message", "hello", "")],
+            ),
+            (
+                "multi-line data",
+                &["data: first\ndata: second\n\n"],
+                &[("message", "first\nsecond", "")],
+            ),
+            (
+                "comment heartbeats",
+                &[": OPENROUTER PROCESSING\n\n", ":\n\n", "data: {}\n\n"],
+                &[("message", "{}", "")],
+            ),
+            (
+                "comment within an event",
+                &["data: a\n: keep-alive\ndata: b\n\n"],
+                &[("message", "a\nb", "")],
+            ),
+            (
+                "custom event type",
+                &["event: content_block_delta\ndata: x\n\n", "data: y\n\n"],
+                &[("content_block_delta", "x", ""), ("message", "y", "")],
+            ),
+            (
+                "event id kept for later events",
+                &["id: 1\ndata: a\n\ndata: b\n\nid\ndata: c\n\n"],
+                &[
+                    ("message", "a", "1"),
+                    ("message", "b", "1"),
+                    ("message", "c", ""),
+                ],
+            ),
+            (
+                "id with a null character ignored",
+                &["id: 1\ndata: a\n\nid: 2\u{0000}\ndata: b\n\n"],
+                &[("message", "a", "1"), ("message", "b", "1")],
+            ),
+            (
+                "crlf line endings",
+                &["data: a\r\n\r\n"],
+                &[("message", "a", "")],
+            ),
+            (
+                "crlf split across chunks",
+                &["data: a\r", "\n\r", "\n"],
+                &[("message", "a", "")],
+            ),
+            (
+                "cr line endings at the end of the stream",
+                &["data: a\rdata: b\r\r"],
+                &[("message", "a\nb", "")],
+            ),
+            (
+                "field split across chunks",
+                &["da", "ta: hel", "lo\n", "\n"],
+                &[("message", "hello", "")],
+            ),
+            (
+                "no space after the colon",
+                &["data:a\n\n"],
+                &[("message", "a", "")],
+            ),
+            (
+                "only one space removed",
+                &["data:  a\n\n"],
+                &[("message", " a", "")],
+            ),
+            (
+                "field without a colon",
+                &["data\ndata\n\n"],
+                &[("message", "\n", "")],
+            ),
+            (
+                "unknown fields ignored",
+                &["foo: bar\ndata: a\n\n"],
+                &[("message", "a", "")],
+            ),
+            (
+                "event without data not dispatched",
+                &["event: ping\n\n", "data: a\n\n"],
+                &[("message", "a", "")],
+            ),
+            ("unfinished event discarded", &["data: a\n"], &[]),
+        ];
+
+        for (name, chunks, expected) in cases {
+            let events = parse(chunks)
+                .into_iter()
+                .map(|event| (event.event, event.data, event.id))
+                .collect::<Vec<_>>();
+            let expected = expected
+                .iter()
+                .map(|(event, data, id)| (event.to_string(), data.to_string(), id.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(events, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn parses_the_retry_field() {
+        let cases: &[(&str, Option<Duration>)] = &[
+            ("retry: 3000\n", Some(Duration::from_millis(3000))),
+            ("retry: +3000\n", None),
+            ("retry: 3s\n", None),
+            ("retry:\n", None),
+        ];
+
+        for (retry, expected) in cases {
+            let events = parse(&[*retry, "data: a\n\n"]);
+            assert_eq!(events[0].retry, *expected, "{retry:?}");
+        }
+    }
+}
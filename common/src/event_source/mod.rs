@@ -1,6 +1,7 @@
 // Based on https://github.com/jpopesculian/eventsource-stream and https://github.com/jpopesculian/reqwest-eventsource
 // modified to use the wasi-http based reqwest, and wasi pollables
 
+mod aws_event_stream;
 pub mod error;
 mod event_stream;
 mod message_event;
@@ -11,6 +12,7 @@ mod utf8_stream;
 
 use crate::event_source::error::Error;
 use crate::event_source::event_stream::EventStream;
+use aws_event_stream::AwsEventStream;
 use golem_rust::wasm_rpc::Pollable;
 pub use message_event::MessageEvent;
 use ndjson_stream::NdJsonStream;
@@ -19,6 +21,9 @@ use reqwest::{Response, StatusCode};
 use std::task::Poll;
 use stream::{LlmStream, StreamType};
 
+/// The content type of the binary event stream encoding of AWS services
+const AWS_EVENT_STREAM: &str = "vnd.amazon.eventstream";
+
 /// The ready state of an [`EventSource`]
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 #[repr(u8)]
@@ -34,8 +39,18 @@ pub enum ReadyState {
 pub struct EventSource {
     /// stream is the type which implements Stream trait
     stream: StreamType,
-    response: Response,
+    status: StatusCode,
+    /// The response the stream is read from, kept open while reading it
+    _response: Option<Response>,
     is_closed: bool,
+    observer: Option<Box<dyn EventObserver>>,
+}
+
+/// Gets notified of the response status and of every event received by an [`EventSource`], for
+/// example to record them
+pub trait EventObserver {
+    fn on_status(&mut self, status: StatusCode);
+    fn on_event(&mut self, event: &MessageEvent);
 }
 
 impl EventSource {
@@ -50,28 +65,49 @@ impl EventSource {
                     >(response.get_raw_input_stream())
                 };
 
-                let stream = if response
+                let content_type = response
                     .headers()
                     .get(&reqwest::header::CONTENT_TYPE)
                     .unwrap()
                     .to_str()
-                    .unwrap()
-                    .contains("ndjson")
-                {
+                    .unwrap();
+                let stream = if content_type.contains("ndjson") {
                     StreamType::NdJsonStream(NdJsonStream::new(handle))
+                } else if content_type.contains(AWS_EVENT_STREAM) {
+                    StreamType::AwsEventStream(AwsEventStream::new(handle))
                 } else {
                     StreamType::EventStream(EventStream::new(handle))
                 };
                 Ok(Self {
-                    response,
+                    status: response.status(),
+                    _response: Some(response),
                     stream,
                     is_closed: false,
+                    observer: None,
                 })
             }
             Err(err) => Err(err),
         }
     }
 
+    /// A stream of previously recorded events, returned in order without reading any response
+    pub fn replay(events: impl IntoIterator<Item = MessageEvent>) -> Self {
+        Self {
+            stream: StreamType::Replay(events.into_iter().collect()),
+            status: StatusCode::OK,
+            _response: None,
+            is_closed: false,
+            observer: None,
+        }
+    }
+
+    /// Notifies the given observer of the response status and of every received event
+    pub fn with_observer(mut self, mut observer: impl EventObserver + 'static) -> Self {
+        observer.on_status(self.status);
+        self.observer = Some(Box::new(observer));
+        self
+    }
+
     /// Close the EventSource stream and stop trying to reconnect
     pub fn close(&mut self) {
         self.is_closed = true;
@@ -90,6 +126,11 @@ impl EventSource {
         match &self.stream {
             StreamType::EventStream(stream) => stream.subscribe(),
             StreamType::NdJsonStream(stream) => stream.subscribe(),
+            StreamType::AwsEventStream(stream) => stream.subscribe(),
+            // Replayed events are always ready
+            StreamType::Replay(_) => {
+                golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0)
+            }
         }
     }
 
@@ -100,17 +141,47 @@ impl EventSource {
 
         match &mut self.stream {
             StreamType::EventStream(stream) => match stream.poll_next() {
-                Poll::Ready(Some(Ok(event))) => Poll::Ready(Some(Ok(Event::Message(event)))),
+                Poll::Ready(Some(Ok(event))) => {
+                    if let Some(observer) = &mut self.observer {
+                        observer.on_event(&event);
+                    }
+                    Poll::Ready(Some(Ok(Event::Message(event))))
+                }
                 Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err.into()))),
                 Poll::Ready(None) => Poll::Ready(None),
                 Poll::Pending => Poll::Pending,
             },
             StreamType::NdJsonStream(stream) => match stream.poll_next() {
-                Poll::Ready(Some(Ok(event))) => Poll::Ready(Some(Ok(Event::Message(event)))),
+                Poll::Ready(Some(Ok(event))) => {
+                    if let Some(observer) = &mut self.observer {
+                        observer.on_event(&event);
+                    }
+                    Poll::Ready(Some(Ok(Event::Message(event))))
+                }
                 Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err.into()))),
                 Poll::Ready(None) => Poll::Ready(None),
                 Poll::Pending => Poll::Pending,
             },
+            StreamType::AwsEventStream(stream) => match stream.poll_next() {
+                Poll::Ready(Some(Ok(event))) => {
+                    if let Some(observer) = &mut self.observer {
+                        observer.on_event(&event);
+                    }
+                    Poll::Ready(Some(Ok(Event::Message(event))))
+                }
+                Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err.into()))),
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            },
+            StreamType::Replay(events) => match events.pop_front() {
+                Some(event) => {
+                    if let Some(observer) = &mut self.observer {
+                        observer.on_event(&event);
+                    }
+                    Poll::Ready(Some(Ok(Event::Message(event))))
+                }
+                None => Poll::Ready(None),
+            },
         }
     }
 }
@@ -141,6 +212,7 @@ fn check_response(response: Response) -> Result<Response, Error> {
                 (mime_type.type_(), mime_type.subtype()),
                 (mime::TEXT, mime::EVENT_STREAM)
             ) || mime_type.subtype().as_str().contains("ndjson")
+                || mime_type.subtype().as_str() == AWS_EVENT_STREAM
         })
         .unwrap_or(false)
     {
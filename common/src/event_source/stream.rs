@@ -1,9 +1,10 @@
 use core::fmt;
+use std::collections::VecDeque;
 use std::{string::FromUtf8Error, task::Poll};
 
 use super::{
-    event_stream::EventStream, ndjson_stream::NdJsonStream, utf8_stream::Utf8StreamError,
-    MessageEvent,
+    aws_event_stream::AwsEventStream, event_stream::EventStream, ndjson_stream::NdJsonStream,
+    utf8_stream::Utf8StreamError, MessageEvent,
 };
 use golem_rust::{
     bindings::wasi::io::streams::{InputStream, StreamError as WasiStreamError},
@@ -14,6 +15,9 @@ use nom::error::Error as NomError;
 pub enum StreamType {
     EventStream(EventStream),
     NdJsonStream(NdJsonStream),
+    AwsEventStream(AwsEventStream),
+    /// Events replayed from a recording instead of being read from a response
+    Replay(VecDeque<MessageEvent>),
 }
 
 pub trait LlmStream {
@@ -33,6 +37,8 @@ pub enum StreamError<E> {
     Parser(NomError<String>),
     /// Underlying source stream error
     Transport(E),
+    /// Source stream is not a valid AWS event stream
+    Frame(String),
 }
 
 impl<E> From<Utf8StreamError<E>> for StreamError<E> {
@@ -59,6 +65,7 @@ where
             Self::Utf8(err) => f.write_fmt(format_args!("UTF8 error: {}", err)),
             Self::Parser(err) => f.write_fmt(format_args!("Parse error: {}", err)),
             Self::Transport(err) => f.write_fmt(format_args!("Transport error: {}", err)),
+            Self::Frame(err) => f.write_fmt(format_args!("Frame error: {}", err)),
         }
     }
 }
@@ -0,0 +1,7 @@
+pub mod config;
+pub mod error;
+pub mod logging;
+pub mod retry;
+
+#[allow(dead_code)]
+pub mod event_source;
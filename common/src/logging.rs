@@ -0,0 +1,29 @@
+use std::str::FromStr;
+
+/// Initializes WASI logging once, with the maximum level taken from an environment variable
+/// (`GOLEM_LLM_LOG`, `GOLEM_VECTOR_LOG`, ...) and defaulting to `info`.
+pub struct LoggingState {
+    env_var: &'static str,
+    logging_initialized: bool,
+}
+
+impl LoggingState {
+    pub const fn new(env_var: &'static str) -> Self {
+        Self {
+            env_var,
+            logging_initialized: false,
+        }
+    }
+
+    /// Initializes WASI logging based on the state's environment variable.
+    pub fn init(&mut self) {
+        if !self.logging_initialized {
+            let _ = wasi_logger::Logger::install();
+            let max_level: log::LevelFilter =
+                log::LevelFilter::from_str(&std::env::var(self.env_var).unwrap_or_default())
+                    .unwrap_or(log::LevelFilter::Info);
+            log::set_max_level(max_level);
+            self.logging_initialized = true;
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Retrying of HTTP requests failing with transient errors.
+//!
+//! Requests rejected because of rate limiting (429), failing with a server error (5xx) or timing
+//! out are sent again after an exponentially growing delay with full jitter, waiting for the
+//! number of seconds in the `Retry-After` header instead when the provider sent one. The policy is
+//! configured with environment variables shared by all the components.
+
+use crate::config::get_optional_config;
+use log::warn;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// The number of times a request is sent at most, 1 disabling retries
+pub const MAX_ATTEMPTS_ENV_VAR: &str = "GOLEM_AI_RETRY_MAX_ATTEMPTS";
+
+/// The delay in milliseconds before the first retry, doubled for each further retry
+pub const BASE_DELAY_ENV_VAR: &str = "GOLEM_AI_RETRY_BASE_DELAY_MS";
+
+/// The longest delay in milliseconds between two attempts, including the ones asked for by
+/// `Retry-After`
+pub const MAX_DELAY_ENV_VAR: &str = "GOLEM_AI_RETRY_MAX_DELAY_MS";
+
+/// The failures that are retried, as a comma separated list of `429`, `5xx` and `timeout`
+pub const RETRY_ON_ENV_VAR: &str = "GOLEM_AI_RETRY_ON";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub retry_on_rate_limit: bool,
+    pub retry_on_server_error: bool,
+    pub retry_on_timeout: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retry_on_rate_limit: true,
+            retry_on_server_error: true,
+            retry_on_timeout: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The default policy, with the settings given in the environment applied
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let retry_on = std::env::var(RETRY_ON_ENV_VAR).ok();
+        let retries = |failure: &str| {
+            retry_on
+                .as_ref()
+                .is_none_or(|retry_on| retry_on.split(',').any(|item| item.trim() == failure))
+        };
+        Self {
+            max_attempts: get_optional_config::<u32>(MAX_ATTEMPTS_ENV_VAR)
+                .unwrap_or(default.max_attempts)
+                .max(1),
+            base_delay: get_optional_config::<u64>(BASE_DELAY_ENV_VAR)
+                .map(Duration::from_millis)
+                .unwrap_or(default.base_delay),
+            max_delay: get_optional_config::<u64>(MAX_DELAY_ENV_VAR)
+                .map(Duration::from_millis)
+                .unwrap_or(default.max_delay),
+            retry_on_rate_limit: retries("429"),
+            retry_on_server_error: retries("5xx"),
+            retry_on_timeout: retries("timeout"),
+        }
+    }
+
+    pub fn retries_status(&self, status: StatusCode) -> bool {
+        (self.retry_on_rate_limit && status == StatusCode::TOO_MANY_REQUESTS)
+            || (self.retry_on_server_error && status.is_server_error())
+    }
+
+    /// The delay before retrying after the given attempt, starting at 1. `jitter` is a random
+    /// number in `[0, 1)` scaling the exponential backoff.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>, jitter: f64) -> Duration {
+        let delay = retry_after.unwrap_or_else(|| {
+            let backoff = self
+                .base_delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+                .min(self.max_delay);
+            backoff.mul_f64(jitter)
+        });
+        delay.min(self.max_delay)
+    }
+}
+
+/// Sends a request with `send` until it succeeds, fails with an error that is not retried, or
+/// the attempts of the policy configured in the environment are used up. `send` builds the
+/// request anew for each attempt.
+pub fn send_with_retry(
+    mut send: impl FnMut() -> Result<Response, reqwest::Error>,
+) -> Result<Response, reqwest::Error> {
+    let policy = RetryPolicy::from_env();
+    let mut attempt = 1;
+    loop {
+        let result = send();
+        let retry_after = match &result {
+            Ok(response) if policy.retries_status(response.status()) => {
+                retry_after(response.headers())
+            }
+            Err(err) if policy.retry_on_timeout && err.is_timeout() => None,
+            _ => return result,
+        };
+        if attempt >= policy.max_attempts {
+            return result;
+        }
+
+        let delay = policy.delay(attempt, retry_after, jitter());
+        match &result {
+            Ok(response) => warn!(
+                "Request failed with status {}, retrying in {delay:?}",
+                response.status()
+            ),
+            Err(err) => warn!("Request failed: {err}, retrying in {delay:?}"),
+        }
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// The delay asked for by a `Retry-After` header given in seconds. HTTP dates are not supported
+/// and fall back to the exponential backoff.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+/// A random number in `[0, 1)`, taken from the randomly seeded hasher of the standard library
+fn jitter() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::retry::{jitter, retry_after, RetryPolicy};
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    #[test]
+    fn retries_rate_limits_and_server_errors() {
+        let policy = RetryPolicy::default();
+        assert!(policy.retries_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(policy.retries_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!policy.retries_status(StatusCode::BAD_REQUEST));
+
+        let policy = RetryPolicy {
+            retry_on_rate_limit: false,
+            ..RetryPolicy::default()
+        };
+        assert!(!policy.retries_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_max_delay() {
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(3),
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.delay(1, None, 0.5), Duration::from_millis(250));
+        assert_eq!(policy.delay(3, None, 0.5), Duration::from_secs(1));
+        assert_eq!(policy.delay(10, None, 0.5), Duration::from_millis(1500));
+        assert_eq!(policy.delay(1, None, 0.0), Duration::ZERO);
+
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(2)), 0.5),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(60)), 0.5),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn reads_retry_after_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn jitter_is_a_fraction() {
+        for _ in 0..100 {
+            let jitter = jitter();
+            assert!((0.0..1.0).contains(&jitter));
+        }
+    }
+}
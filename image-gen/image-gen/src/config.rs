@@ -0,0 +1,93 @@
+use crate::golem::image_gen::image_gen::{Config, Error, ErrorCode, ImageFormat, Size};
+use golem_ai_common::config::config_value;
+use std::ffi::OsStr;
+
+/// Gets an expected configuration value from the environment, and fails if its is not found
+/// using the `fail` function. Otherwise, it runs `succeed` with the configuration value.
+pub fn with_config_key<R>(
+    key: impl AsRef<OsStr>,
+    fail: impl FnOnce(Error) -> R,
+    succeed: impl FnOnce(String) -> R,
+) -> R {
+    match config_value(key) {
+        Ok(value) => succeed(value),
+        Err(message) => fail(Error {
+            code: ErrorCode::InternalError,
+            message,
+            provider_error_json: None,
+        }),
+    }
+}
+
+/// Gets the value of a provider option passed in the request's `Config`
+pub fn provider_option(config: &Config, key: &str) -> Option<String> {
+    config
+        .provider_options
+        .iter()
+        .find(|kv| kv.key == key)
+        .map(|kv| kv.value.clone())
+}
+
+/// The requested image format, defaulting to PNG
+pub fn format_or_default(config: &Config) -> ImageFormat {
+    config.format.unwrap_or(ImageFormat::Png)
+}
+
+pub fn mime_type(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "image/png",
+        ImageFormat::Jpeg => "image/jpeg",
+        ImageFormat::Webp => "image/webp",
+    }
+}
+
+pub fn format_name(format: ImageFormat) -> &'static str {
+    match format {
+        ImageFormat::Png => "png",
+        ImageFormat::Jpeg => "jpeg",
+        ImageFormat::Webp => "webp",
+    }
+}
+
+/// Selects the aspect ratio closest to `size` from the ratios supported by a provider, given as
+/// `width:height` strings
+pub fn closest_aspect_ratio<'a>(size: &Size, supported: &[&'a str]) -> Option<&'a str> {
+    let requested = size.width as f64 / size.height.max(1) as f64;
+    supported
+        .iter()
+        .filter_map(|ratio| {
+            let (width, height) = ratio.split_once(':')?;
+            let ratio_value = width.parse::<f64>().ok()? / height.parse::<f64>().ok()?;
+            Some((*ratio, (ratio_value.ln() - requested.ln()).abs()))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(ratio, _)| ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::closest_aspect_ratio;
+    use crate::golem::image_gen::image_gen::Size;
+
+    #[test]
+    fn selects_closest_aspect_ratio() {
+        let supported = ["16:9", "1:1", "2:3", "3:2", "9:16"];
+        let size = |width, height| Size { width, height };
+        assert_eq!(
+            closest_aspect_ratio(&size(1024, 1024), &supported),
+            Some("1:1")
+        );
+        assert_eq!(
+            closest_aspect_ratio(&size(1920, 1080), &supported),
+            Some("16:9")
+        );
+        assert_eq!(
+            closest_aspect_ratio(&size(1024, 1536), &supported),
+            Some("2:3")
+        );
+        assert_eq!(
+            closest_aspect_ratio(&size(720, 1440), &supported),
+            Some("9:16")
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use crate::golem::image_gen::image_gen::{Config, Error, Guest, Image};
+use std::marker::PhantomData;
+
+/// Wraps an image generation implementation with custom durability
+pub struct DurableImageGen<Impl> {
+    phantom: PhantomData<Impl>,
+}
+
+/// Trait to be implemented in addition to the image generation `Guest` trait when wrapping it
+/// with `DurableImageGen`.
+pub trait ExtendedGuest: Guest + 'static {
+    /// The maximum number of images a single call of the provider returns with the given config.
+    /// Requests for more images are split into multiple calls by `DurableImageGen`.
+    fn max_images_per_call(config: &Config) -> u32;
+}
+
+/// Splits a request for `config.n` images into the configs of the individual provider calls.
+/// The seed of each call is offset by the number of images requested before it, so the calls do
+/// not return the same images.
+pub fn split_config(config: &Config, max_images_per_call: u32) -> Vec<Config> {
+    let max_images_per_call = max_images_per_call.max(1);
+    let mut remaining = config.n.unwrap_or(1).max(1);
+    let mut offset = 0;
+    let mut configs = Vec::new();
+    while remaining > 0 {
+        let n = remaining.min(max_images_per_call);
+        configs.push(Config {
+            n: Some(n),
+            seed: config.seed.map(|seed| seed.wrapping_add(offset as u64)),
+            ..config.clone()
+        });
+        remaining -= n;
+        offset += n;
+    }
+    configs
+}
+
+/// Runs the provider calls needed for the request, stopping at the first failing one
+fn run_calls(
+    config: &Config,
+    max_images_per_call: u32,
+    mut call: impl FnMut(Config) -> Result<Vec<Image>, Error>,
+) -> Result<Vec<Image>, Error> {
+    let mut images = Vec::new();
+    for config in split_config(config, max_images_per_call) {
+        images.extend(call(config)?);
+    }
+    Ok(images)
+}
+
+/// When the durability feature flag is off, wrapping with `DurableImageGen` only splits the
+/// requests into multiple provider calls
+#[cfg(not(feature = "durability"))]
+mod passthrough_impl {
+    use crate::durability::{run_calls, DurableImageGen, ExtendedGuest};
+    use crate::golem::image_gen::image_gen::{Config, Error, Guest, Image, ImageInput};
+
+    impl<Impl: ExtendedGuest> Guest for DurableImageGen<Impl> {
+        fn generate(prompt: String, config: Config) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                Impl::generate(prompt.clone(), config)
+            })
+        }
+
+        fn edit(
+            image: ImageInput,
+            mask: Option<ImageInput>,
+            prompt: String,
+            config: Config,
+        ) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                Impl::edit(image.clone(), mask.clone(), prompt.clone(), config)
+            })
+        }
+
+        fn variation(image: ImageInput, config: Config) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                Impl::variation(image.clone(), config)
+            })
+        }
+    }
+}
+
+/// When the durability feature flag is on, wrapping with `DurableImageGen` adds custom durability
+/// on top of the provider-specific implementation using Golem's special host functions and the
+/// `golem-rust` helper library.
+///
+/// A request is handled as a job of one or more provider calls, and the images returned by each
+/// call are stored in the oplog as soon as the call completes. If the worker gets interrupted in
+/// the middle of a job, the already generated images are replayed from the oplog and only the
+/// remaining calls are sent to the provider. The input images are not stored, only their sizes.
+#[cfg(feature = "durability")]
+mod durable_impl {
+    use crate::durability::{run_calls, DurableImageGen, ExtendedGuest};
+    use crate::golem::image_gen::image_gen::{Config, Error, Guest, Image, ImageInput};
+    use golem_rust::bindings::golem::durability::durability::DurableFunctionType;
+    use golem_rust::durability::Durability;
+    use golem_rust::{with_persistence_level, IntoValue, PersistenceLevel};
+
+    impl<Impl: ExtendedGuest> Guest for DurableImageGen<Impl> {
+        fn generate(prompt: String, config: Config) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                let durability = Durability::<Vec<Image>, Error>::new(
+                    "golem_image_gen",
+                    "generate",
+                    DurableFunctionType::WriteRemote,
+                );
+                if durability.is_live() {
+                    let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                        Impl::generate(prompt.clone(), config.clone())
+                    });
+                    durability.persist(
+                        GenerateInput {
+                            prompt: prompt.clone(),
+                            config,
+                        },
+                        result,
+                    )
+                } else {
+                    durability.replay()
+                }
+            })
+        }
+
+        fn edit(
+            image: ImageInput,
+            mask: Option<ImageInput>,
+            prompt: String,
+            config: Config,
+        ) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                let durability = Durability::<Vec<Image>, Error>::new(
+                    "golem_image_gen",
+                    "edit",
+                    DurableFunctionType::WriteRemote,
+                );
+                if durability.is_live() {
+                    let input = EditInput {
+                        image_bytes: image.data.len() as u64,
+                        mask_bytes: mask.as_ref().map(|mask| mask.data.len() as u64),
+                        prompt: prompt.clone(),
+                        config: config.clone(),
+                    };
+                    let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                        Impl::edit(image.clone(), mask.clone(), prompt.clone(), config)
+                    });
+                    durability.persist(input, result)
+                } else {
+                    durability.replay()
+                }
+            })
+        }
+
+        fn variation(image: ImageInput, config: Config) -> Result<Vec<Image>, Error> {
+            run_calls(&config, Impl::max_images_per_call(&config), |config| {
+                let durability = Durability::<Vec<Image>, Error>::new(
+                    "golem_image_gen",
+                    "variation",
+                    DurableFunctionType::WriteRemote,
+                );
+                if durability.is_live() {
+                    let input = VariationInput {
+                        image_bytes: image.data.len() as u64,
+                        config: config.clone(),
+                    };
+                    let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                        Impl::variation(image.clone(), config)
+                    });
+                    durability.persist(input, result)
+                } else {
+                    durability.replay()
+                }
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct GenerateInput {
+        prompt: String,
+        config: Config,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct EditInput {
+        image_bytes: u64,
+        mask_bytes: Option<u64>,
+        prompt: String,
+        config: Config,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct VariationInput {
+        image_bytes: u64,
+        config: Config,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::golem::image_gen::image_gen::{
+            Config, Error, ErrorCode, Image, ImageFormat, Kv, Quality, Size,
+        };
+        use golem_rust::value_and_type::{FromValueAndType, IntoValueAndType};
+        use std::fmt::Debug;
+
+        fn roundtrip_test<T: Debug + Clone + PartialEq + IntoValueAndType + FromValueAndType>(
+            value: T,
+        ) {
+            let vnt = value.clone().into_value_and_type();
+            let extracted = T::from_value_and_type(vnt).unwrap();
+            assert_eq!(value, extracted);
+        }
+
+        #[test]
+        fn config_roundtrip() {
+            roundtrip_test(Config {
+                model: Some("gpt-image-1".to_string()),
+                size: Some(Size {
+                    width: 1024,
+                    height: 1536,
+                }),
+                quality: Some(Quality::High),
+                n: Some(2),
+                seed: Some(42),
+                format: Some(ImageFormat::Webp),
+                negative_prompt: None,
+                provider_options: vec![Kv {
+                    key: "background".to_string(),
+                    value: "transparent".to_string(),
+                }],
+            });
+        }
+
+        #[test]
+        fn result_roundtrip() {
+            roundtrip_test::<Result<Vec<Image>, Error>>(Ok(vec![Image {
+                data: vec![137, 80, 78, 71],
+                format: ImageFormat::Png,
+                mime_type: "image/png".to_string(),
+                revised_prompt: Some("A red fox in the snow".to_string()),
+                seed: Some(42),
+            }]));
+            roundtrip_test::<Result<Vec<Image>, Error>>(Err(Error {
+                code: ErrorCode::ContentFiltered,
+                message: "Rejected by the safety system".to_string(),
+                provider_error_json: None,
+            }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::durability::split_config;
+    use crate::golem::image_gen::image_gen::Config;
+
+    fn config(n: Option<u32>, seed: Option<u64>) -> Config {
+        Config {
+            model: None,
+            size: None,
+            quality: None,
+            n,
+            seed,
+            format: None,
+            negative_prompt: None,
+            provider_options: vec![],
+        }
+    }
+
+    #[test]
+    fn splits_requests_into_calls() {
+        let calls = split_config(&config(Some(5), Some(100)), 2);
+        assert_eq!(
+            calls
+                .iter()
+                .map(|config| (config.n, config.seed))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(2), Some(100)),
+                (Some(2), Some(102)),
+                (Some(1), Some(104))
+            ]
+        );
+    }
+
+    #[test]
+    fn requests_one_image_by_default() {
+        let calls = split_config(&config(None, None), 10);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].n, Some(1));
+        assert_eq!(calls[0].seed, None);
+    }
+}
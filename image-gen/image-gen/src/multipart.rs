@@ -0,0 +1,82 @@
+/// Builds a `multipart/form-data` request body, used by the image editing endpoints which take
+/// the images as file uploads
+pub struct MultipartForm {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartForm {
+    pub fn new() -> Self {
+        Self {
+            boundary: "----golem-image-gen-form-boundary-kX9dVqLr3TzB".to_string(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn text(&mut self, name: &str, value: &str) {
+        self.part_header(&format!("form-data; name=\"{name}\""), None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    pub fn file(&mut self, name: &str, file_name: &str, mime_type: &str, data: &[u8]) {
+        self.part_header(
+            &format!("form-data; name=\"{name}\"; filename=\"{file_name}\""),
+            Some(mime_type),
+        );
+        self.body.extend_from_slice(data);
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    fn part_header(&mut self, disposition: &str, content_type: Option<&str>) {
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body
+            .extend_from_slice(format!("Content-Disposition: {disposition}\r\n").as_bytes());
+        if let Some(content_type) = content_type {
+            self.body
+                .extend_from_slice(format!("Content-Type: {content_type}\r\n").as_bytes());
+        }
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    /// Returns the content type header value and the body
+    pub fn finish(mut self) -> (String, Vec<u8>) {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        (
+            format!("multipart/form-data; boundary={}", self.boundary),
+            self.body,
+        )
+    }
+}
+
+impl Default for MultipartForm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::multipart::MultipartForm;
+
+    #[test]
+    fn encodes_fields_and_files() {
+        let mut form = MultipartForm::new();
+        form.text("prompt", "a red fox");
+        form.file("image", "image.png", "image/png", b"PNG");
+        let (content_type, body) = form.finish();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"prompt\"\r\n\r\na red fox\r\n\
+                 --{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"image.png\"\r\nContent-Type: image/png\r\n\r\nPNG\r\n\
+                 --{boundary}--\r\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use golem_image_gen::error::{from_http_status, from_reqwest_error};
+use golem_image_gen::golem::image_gen::image_gen::{Error, ErrorCode};
+use golem_image_gen::multipart::MultipartForm;
+use golem_image_gen::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+const BASE_URL: &str = "https://api.openai.com";
+
+/// The OpenAI API client for generating and editing images.
+///
+/// Based on https://platform.openai.com/docs/api-reference/images
+pub struct ImagesApi {
+    openai_api_key: String,
+    client: Client,
+}
+
+impl ImagesApi {
+    pub fn new(openai_api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            openai_api_key,
+            client,
+        }
+    }
+
+    pub fn generate(&self, request: &GenerationRequest) -> Result<ImagesResponse, Error> {
+        trace!(
+            "Sending request to OpenAI API: model={} n={}",
+            request.model,
+            request.n
+        );
+
+        self.send(|| {
+            self.client
+                .request(Method::POST, format!("{BASE_URL}/v1/images/generations"))
+                .json(request)
+        })
+    }
+
+    pub fn edit(&self, form: MultipartForm) -> Result<ImagesResponse, Error> {
+        trace!("Sending image edit request to OpenAI API");
+        self.send_form("/v1/images/edits", form)
+    }
+
+    pub fn variation(&self, form: MultipartForm) -> Result<ImagesResponse, Error> {
+        trace!("Sending image variation request to OpenAI API");
+        self.send_form("/v1/images/variations", form)
+    }
+
+    fn send_form(&self, path: &str, form: MultipartForm) -> Result<ImagesResponse, Error> {
+        let (content_type, body) = form.finish();
+        self.send(|| {
+            self.client
+                .request(Method::POST, format!("{BASE_URL}{path}"))
+                .header(reqwest::header::CONTENT_TYPE, &content_type)
+                .body(body.clone())
+        })
+    }
+
+    /// Sends the request built by `build`, which is called again for each retry
+    fn send(&self, build: impl Fn() -> RequestBuilder) -> Result<ImagesResponse, Error> {
+        let response: Response =
+            send_with_retry(|| build().bearer_auth(&self.openai_api_key).send())
+                .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!("Received {status} response from OpenAI API: {raw_body:?}");
+        let error_code = serde_json::from_str::<ErrorResponse>(&raw_body)
+            .ok()
+            .and_then(|response| response.error.code);
+        let mut error = from_http_status(status, raw_body);
+        if matches!(
+            error_code.as_deref(),
+            Some("content_policy_violation" | "moderation_blocked")
+        ) {
+            error.code = ErrorCode::ContentFiltered;
+        }
+        Err(error)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationRequest {
+    pub model: String,
+    pub prompt: String,
+    pub n: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImagesResponse {
+    pub data: Vec<ImageData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageData {
+    pub b64_json: Option<String>,
+    #[serde(default)]
+    pub revised_prompt: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetails,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorDetails {
+    #[serde(default)]
+    code: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ImagesResponse};
+    use golem_image_gen::golem::image_gen::image_gen::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn parses_images() {
+        let response = parse_response_body::<ImagesResponse>(
+            StatusCode::OK,
+            r#"{"created": 1713833628, "data": [{"b64_json": "iVBORw0KGgo=", "revised_prompt": "A fox"}], "usage": {"total_tokens": 100}}"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(response.data[0].b64_json.as_deref(), Some("iVBORw0KGgo="));
+        assert_eq!(response.data[0].revised_prompt.as_deref(), Some("A fox"));
+    }
+
+    #[test]
+    fn maps_content_policy_violations() {
+        let error = parse_response_body::<ImagesResponse>(
+            StatusCode::BAD_REQUEST,
+            r#"{"error": {"code": "content_policy_violation", "message": "Your request was rejected", "type": "invalid_request_error"}}"#.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::ContentFiltered);
+
+        let error = parse_response_body::<ImagesResponse>(
+            StatusCode::BAD_REQUEST,
+            r#"{"error": {"code": null, "message": "Invalid size", "type": "invalid_request_error"}}"#.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::client::{GenerationRequest, ImagesApi, ImagesResponse};
+use base64::{engine::general_purpose, Engine};
+use golem_image_gen::config::{
+    format_name, format_or_default, mime_type, provider_option, with_config_key,
+};
+use golem_image_gen::durability::{DurableImageGen, ExtendedGuest};
+use golem_image_gen::error::unsupported;
+use golem_image_gen::golem::image_gen::image_gen::{
+    Config, Error, ErrorCode, Guest, Image, ImageFormat, ImageInput, Quality,
+};
+use golem_image_gen::multipart::MultipartForm;
+use golem_image_gen::LOGGING_STATE;
+use log::warn;
+
+mod client;
+
+const DEFAULT_MODEL: &str = "gpt-image-1";
+/// The only model supporting variations
+const VARIATION_MODEL: &str = "dall-e-2";
+
+struct OpenAIComponent;
+
+impl OpenAIComponent {
+    const ENV_VAR_NAME: &'static str = "OPENAI_API_KEY";
+
+    fn model(config: &Config) -> String {
+        config
+            .model
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MODEL.to_string())
+    }
+}
+
+/// The GPT image models always return base64 encoded images in the requested format, while the
+/// DALL-E models return PNG images, as URLs unless requested otherwise
+fn is_gpt_image_model(model: &str) -> bool {
+    model.starts_with("gpt-image")
+}
+
+fn quality_name(model: &str, quality: Quality) -> &'static str {
+    if model == "dall-e-3" {
+        match quality {
+            Quality::High => "hd",
+            Quality::Low | Quality::Medium => "standard",
+        }
+    } else {
+        match quality {
+            Quality::Low => "low",
+            Quality::Medium => "medium",
+            Quality::High => "high",
+        }
+    }
+}
+
+fn warn_unsupported(config: &Config) {
+    if config.seed.is_some() {
+        warn!("OpenAI does not support seeds, ignoring it");
+    }
+    if config.negative_prompt.is_some() {
+        warn!("OpenAI does not support negative prompts, ignoring it");
+    }
+}
+
+/// The format of the returned images, failing for formats the model cannot return
+fn output_format(model: &str, config: &Config) -> Result<ImageFormat, Error> {
+    let format = format_or_default(config);
+    if !is_gpt_image_model(model) && format != ImageFormat::Png {
+        Err(unsupported(format!(
+            "{} images with model {model}",
+            format_name(format)
+        )))
+    } else {
+        Ok(format)
+    }
+}
+
+/// Adds the fields shared by the edit and variation requests
+fn add_common_fields(form: &mut MultipartForm, model: &str, config: &Config, format: ImageFormat) {
+    form.text("model", model);
+    form.text("n", &config.n.unwrap_or(1).to_string());
+    if let Some(size) = &config.size {
+        form.text("size", &format!("{}x{}", size.width, size.height));
+    }
+    if is_gpt_image_model(model) {
+        form.text("output_format", format_name(format));
+        if let Some(quality) = config.quality {
+            form.text("quality", quality_name(model, quality));
+        }
+    } else {
+        form.text("response_format", "b64_json");
+    }
+}
+
+fn image_file(form: &mut MultipartForm, name: &str, image: &ImageInput) {
+    form.file(
+        name,
+        &format!("{name}.{}", format_name(image.format)),
+        mime_type(image.format),
+        &image.data,
+    );
+}
+
+fn images_from_response(
+    response: ImagesResponse,
+    format: ImageFormat,
+) -> Result<Vec<Image>, Error> {
+    response
+        .data
+        .into_iter()
+        .map(|image| {
+            let encoded = image.b64_json.ok_or_else(|| Error {
+                code: ErrorCode::InternalError,
+                message: "Response does not contain image data".to_string(),
+                provider_error_json: None,
+            })?;
+            let data = general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|err| Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Failed to decode image data: {err}"),
+                    provider_error_json: None,
+                })?;
+            Ok(Image {
+                data,
+                format,
+                mime_type: mime_type(format).to_string(),
+                revised_prompt: image.revised_prompt,
+                seed: None,
+            })
+        })
+        .collect()
+}
+
+impl Guest for OpenAIComponent {
+    fn generate(prompt: String, config: Config) -> Result<Vec<Image>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        warn_unsupported(&config);
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = ImagesApi::new(openai_api_key);
+            let model = Self::model(&config);
+            let format = output_format(&model, &config)?;
+            let gpt_image = is_gpt_image_model(&model);
+            let request = GenerationRequest {
+                prompt,
+                n: config.n.unwrap_or(1),
+                size: config
+                    .size
+                    .as_ref()
+                    .map(|size| format!("{}x{}", size.width, size.height)),
+                quality: config
+                    .quality
+                    .map(|quality| quality_name(&model, quality).to_string()),
+                response_format: (!gpt_image).then(|| "b64_json".to_string()),
+                output_format: gpt_image.then(|| format_name(format).to_string()),
+                style: provider_option(&config, "style"),
+                background: provider_option(&config, "background"),
+                model,
+            };
+            let response = client.generate(&request)?;
+            images_from_response(response, format)
+        })
+    }
+
+    fn edit(
+        image: ImageInput,
+        mask: Option<ImageInput>,
+        prompt: String,
+        config: Config,
+    ) -> Result<Vec<Image>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        warn_unsupported(&config);
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = ImagesApi::new(openai_api_key);
+            let model = Self::model(&config);
+            let format = output_format(&model, &config)?;
+
+            let mut form = MultipartForm::new();
+            image_file(&mut form, "image", &image);
+            if let Some(mask) = &mask {
+                image_file(&mut form, "mask", mask);
+            }
+            form.text("prompt", &prompt);
+            add_common_fields(&mut form, &model, &config, format);
+            if let Some(background) = provider_option(&config, "background") {
+                form.text("background", &background);
+            }
+
+            let response = client.edit(form)?;
+            images_from_response(response, format)
+        })
+    }
+
+    fn variation(image: ImageInput, config: Config) -> Result<Vec<Image>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        warn_unsupported(&config);
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = ImagesApi::new(openai_api_key);
+            let model = config
+                .model
+                .clone()
+                .unwrap_or_else(|| VARIATION_MODEL.to_string());
+            if model != VARIATION_MODEL {
+                return Err(unsupported(format!("variations with model {model}")));
+            }
+            let format = output_format(&model, &config)?;
+
+            let mut form = MultipartForm::new();
+            image_file(&mut form, "image", &image);
+            add_common_fields(&mut form, &model, &config, format);
+
+            let response = client.variation(form)?;
+            images_from_response(response, format)
+        })
+    }
+}
+
+impl ExtendedGuest for OpenAIComponent {
+    fn max_images_per_call(config: &Config) -> u32 {
+        if config.model.as_deref() == Some("dall-e-3") {
+            1
+        } else {
+            10
+        }
+    }
+}
+
+type DurableOpenAIComponent = DurableImageGen<OpenAIComponent>;
+
+golem_image_gen::export_image_gen!(DurableOpenAIComponent with_types_in golem_image_gen);
@@ -0,0 +1,132 @@
+use golem_image_gen::error::{from_http_status, from_reqwest_error};
+use golem_image_gen::golem::image_gen::image_gen::{Error, ErrorCode};
+use golem_image_gen::multipart::MultipartForm;
+use golem_image_gen::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://api.stability.ai";
+
+/// The Stability AI API client for the Stable Image services.
+///
+/// Based on https://platform.stability.ai/docs/api-reference
+pub struct StableImageApi {
+    api_key: String,
+    client: Client,
+}
+
+impl StableImageApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    /// Sends a request to one of the `/v2beta/stable-image` services, such as `generate/core` or
+    /// `edit/inpaint`
+    pub fn send(&self, service: &str, form: MultipartForm) -> Result<ImageResponse, Error> {
+        trace!("Sending request to Stability AI API: service={service}");
+
+        let (content_type, body) = form.finish();
+        let response: Response = send_with_retry(|| {
+            self.client
+                .request(
+                    Method::POST,
+                    format!("{BASE_URL}/v2beta/stable-image/{service}"),
+                )
+                .bearer_auth(&self.api_key)
+                .header(reqwest::header::ACCEPT, "application/json")
+                .header(reqwest::header::CONTENT_TYPE, &content_type)
+                .body(body.clone())
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!("Received {status} response from Stability AI API: {raw_body:?}");
+        let name = serde_json::from_str::<ErrorResponse>(&raw_body)
+            .ok()
+            .and_then(|response| response.name);
+        let mut error = from_http_status(status, raw_body);
+        if name.as_deref() == Some("content_moderation") {
+            error.code = ErrorCode::ContentFiltered;
+        }
+        Err(error)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImageResponse {
+    pub image: String,
+    pub finish_reason: String,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorResponse {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ImageResponse};
+    use golem_image_gen::golem::image_gen::image_gen::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn parses_image() {
+        let response = parse_response_body::<ImageResponse>(
+            StatusCode::OK,
+            r#"{"image": "iVBORw0KGgo=", "finish_reason": "SUCCESS", "seed": 343940597}"#
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(response.finish_reason, "SUCCESS");
+        assert_eq!(response.seed, Some(343940597));
+    }
+
+    #[test]
+    fn maps_content_moderation() {
+        let error = parse_response_body::<ImageResponse>(
+            StatusCode::FORBIDDEN,
+            r#"{"id": "ed14db44", "name": "content_moderation", "errors": ["Your request was flagged"]}"#
+                .to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::ContentFiltered);
+
+        let error = parse_response_body::<ImageResponse>(
+            StatusCode::FORBIDDEN,
+            r#"{"id": "ed14db45", "name": "forbidden", "errors": ["Invalid API key"]}"#.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::AuthenticationFailed);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::client::{ImageResponse, StableImageApi};
+use base64::{engine::general_purpose, Engine};
+use golem_image_gen::config::{
+    closest_aspect_ratio, format_name, format_or_default, mime_type, provider_option,
+    with_config_key,
+};
+use golem_image_gen::durability::{DurableImageGen, ExtendedGuest};
+use golem_image_gen::error::unsupported;
+use golem_image_gen::golem::image_gen::image_gen::{
+    Config, Error, ErrorCode, Guest, Image, ImageFormat, ImageInput,
+};
+use golem_image_gen::multipart::MultipartForm;
+use golem_image_gen::LOGGING_STATE;
+use log::warn;
+
+mod client;
+
+const DEFAULT_MODEL: &str = "core";
+
+/// The aspect ratios supported by the generate services
+const ASPECT_RATIOS: &[&str] = &[
+    "21:9", "16:9", "3:2", "5:4", "1:1", "4:5", "2:3", "9:16", "9:21",
+];
+
+struct StabilityComponent;
+
+impl StabilityComponent {
+    const ENV_VAR_NAME: &'static str = "STABILITY_API_KEY";
+
+    /// Sends the request to the service, returning the single generated image
+    fn send(service: &str, form: MultipartForm, format: ImageFormat) -> Result<Vec<Image>, Error> {
+        with_config_key(Self::ENV_VAR_NAME, Err, |api_key| {
+            let client = StableImageApi::new(api_key);
+            let response = client.send(service, form)?;
+            image_from_response(response, format).map(|image| vec![image])
+        })
+    }
+}
+
+/// Adds the fields shared by all services
+fn add_common_fields(form: &mut MultipartForm, prompt: &str, config: &Config, format: ImageFormat) {
+    form.text("prompt", prompt);
+    if let Some(negative_prompt) = &config.negative_prompt {
+        form.text("negative_prompt", negative_prompt);
+    }
+    if let Some(seed) = config.seed {
+        // Stability only accepts 32 bit seeds
+        form.text("seed", &(seed % 4_294_967_295).to_string());
+    }
+    form.text("output_format", format_name(format));
+    if let Some(style_preset) = provider_option(config, "style_preset") {
+        form.text("style_preset", &style_preset);
+    }
+    if config.quality.is_some() {
+        warn!("Stability AI does not support setting the quality, ignoring it");
+    }
+}
+
+fn image_file(form: &mut MultipartForm, name: &str, image: &ImageInput) {
+    form.file(
+        name,
+        &format!("{name}.{}", format_name(image.format)),
+        mime_type(image.format),
+        &image.data,
+    );
+}
+
+fn image_from_response(response: ImageResponse, format: ImageFormat) -> Result<Image, Error> {
+    if response.finish_reason == "CONTENT_FILTERED" {
+        return Err(Error {
+            code: ErrorCode::ContentFiltered,
+            message: "The generated image was filtered by the content moderation".to_string(),
+            provider_error_json: None,
+        });
+    }
+    let data = general_purpose::STANDARD
+        .decode(response.image)
+        .map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode image data: {err}"),
+            provider_error_json: None,
+        })?;
+    Ok(Image {
+        data,
+        format,
+        mime_type: mime_type(format).to_string(),
+        revised_prompt: None,
+        seed: response.seed,
+    })
+}
+
+impl Guest for StabilityComponent {
+    fn generate(prompt: String, config: Config) -> Result<Vec<Image>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let format = format_or_default(&config);
+        let model = config.model.as_deref().unwrap_or(DEFAULT_MODEL);
+        let mut form = MultipartForm::new();
+        add_common_fields(&mut form, &prompt, &config, format);
+        if let Some(size) = &config.size {
+            if let Some(aspect_ratio) = closest_aspect_ratio(size, ASPECT_RATIOS) {
+                form.text("aspect_ratio", aspect_ratio);
+            }
+        }
+
+        let service = if model.starts_with("sd3") {
+            form.text("model", model);
+            "generate/sd3".to_string()
+        } else {
+            format!("generate/{model}")
+        };
+        Self::send(&service, form, format)
+    }
+
+    fn edit(
+        image: ImageInput,
+        mask: Option<ImageInput>,
+        prompt: String,
+        config: Config,
+    ) -> Result<Vec<Image>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let format = format_or_default(&config);
+        let mut form = MultipartForm::new();
+        image_file(&mut form, "image", &image);
+        // Without a mask, the transparent area of the image is inpainted
+        if let Some(mask) = &mask {
+            image_file(&mut form, "mask", mask);
+        }
+        add_common_fields(&mut form, &prompt, &config, format);
+        if let Some(grow_mask) = provider_option(&config, "grow_mask") {
+            form.text("grow_mask", &grow_mask);
+        }
+        Self::send("edit/inpaint", form, format)
+    }
+
+    fn variation(_image: ImageInput, _config: Config) -> Result<Vec<Image>, Error> {
+        Err(unsupported("image variations"))
+    }
+}
+
+impl ExtendedGuest for StabilityComponent {
+    fn max_images_per_call(_config: &Config) -> u32 {
+        1
+    }
+}
+
+type DurableStabilityComponent = DurableImageGen<StabilityComponent>;
+
+golem_image_gen::export_image_gen!(DurableStabilityComponent with_types_in golem_image_gen);
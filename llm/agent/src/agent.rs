@@ -0,0 +1,372 @@
+use crate::durability::checkpoint_tool_results;
+use crate::golem::llm::llm::{
+    ChatEvent, CompleteResponse, Config, Error, ErrorCode, Message, ToolCall, ToolDefinition,
+    ToolResult,
+};
+use crate::tools::{Deadline, ToolError, Toolbox};
+use log::debug;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The LLM calls made by an agent
+pub trait ChatBackend {
+    fn send(&self, messages: &[Message], config: &Config) -> ChatEvent;
+
+    fn continue_(
+        &self,
+        messages: &[Message],
+        tool_results: &[(ToolCall, ToolResult)],
+        config: &Config,
+    ) -> ChatEvent;
+}
+
+/// Calls the `golem:llm` functions imported by the component
+pub struct ImportedLlm;
+
+impl ChatBackend for ImportedLlm {
+    fn send(&self, messages: &[Message], config: &Config) -> ChatEvent {
+        crate::golem::llm::llm::send(messages, config)
+    }
+
+    fn continue_(
+        &self,
+        messages: &[Message],
+        tool_results: &[(ToolCall, ToolResult)],
+        config: &Config,
+    ) -> ChatEvent {
+        crate::golem::llm::llm::continue_(messages, tool_results, config)
+    }
+}
+
+/// The result of a completed agent run
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentOutcome {
+    /// The final response of the LLM, which did not request more tool calls
+    pub response: CompleteResponse,
+    /// Number of LLM calls made
+    pub iterations: u32,
+    /// All tool calls made during the run, with their results
+    pub tool_results: Vec<(ToolCall, ToolResult)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentError {
+    /// The LLM returned an error
+    Llm(Error),
+    /// The LLM still requested tool calls after the maximum number of iterations
+    MaxIterationsReached {
+        tool_results: Vec<(ToolCall, ToolResult)>,
+    },
+}
+
+impl Display for AgentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentError::Llm(error) => write!(f, "{:?}: {}", error.code, error.message),
+            AgentError::MaxIterationsReached { tool_results } => write!(
+                f,
+                "Maximum number of iterations reached after {} tool calls",
+                tool_results.len()
+            ),
+        }
+    }
+}
+
+/// Runs the tool-call loop on top of `golem:llm`.
+///
+/// The messages are sent to the LLM, and as long as it responds with tool calls, the registered
+/// handlers of the called tools are run and their results are passed back with `continue`.
+pub struct Agent<B = ImportedLlm> {
+    backend: B,
+    config: Config,
+    toolbox: Toolbox,
+    max_iterations: u32,
+}
+
+impl Agent<ImportedLlm> {
+    pub fn new(config: Config) -> Self {
+        Self::with_backend(ImportedLlm, config)
+    }
+}
+
+impl<B: ChatBackend> Agent<B> {
+    const DEFAULT_MAX_ITERATIONS: u32 = 10;
+
+    pub fn with_backend(backend: B, config: Config) -> Self {
+        Self {
+            backend,
+            config,
+            toolbox: Toolbox::default(),
+            max_iterations: Self::DEFAULT_MAX_ITERATIONS,
+        }
+    }
+
+    /// Sets the maximum number of LLM calls of a run, 10 by default
+    pub fn max_iterations(mut self, max_iterations: u32) -> Self {
+        self.max_iterations = max_iterations.max(1);
+        self
+    }
+
+    /// Registers a tool. Its definition is added to the tools of the config, and its calls are
+    /// handled by `handler`.
+    pub fn tool(
+        mut self,
+        definition: ToolDefinition,
+        handler: impl Fn(&str, Deadline) -> Result<String, ToolError> + 'static,
+    ) -> Self {
+        self.toolbox.register(definition, Box::new(handler));
+        self
+    }
+
+    /// Sets the timeout of a registered tool
+    pub fn tool_timeout(mut self, name: &str, timeout: Duration) -> Self {
+        self.toolbox.set_timeout(name, timeout);
+        self
+    }
+
+    /// Sets the timeout of the registered tools without their own timeout
+    pub fn default_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.toolbox.set_default_timeout(timeout);
+        self
+    }
+
+    pub fn run(&self, messages: Vec<Message>) -> Result<AgentOutcome, AgentError> {
+        self.run_loop(messages, checkpoint_tool_results)
+    }
+
+    fn config(&self) -> Config {
+        let mut config = self.config.clone();
+        for definition in self.toolbox.definitions() {
+            if !config.tools.iter().any(|tool| tool.name == definition.name) {
+                config.tools.push(definition.clone());
+            }
+        }
+        config
+    }
+
+    fn run_loop(
+        &self,
+        messages: Vec<Message>,
+        checkpoint: impl Fn(
+            &[ToolCall],
+            &dyn Fn() -> Vec<(ToolCall, ToolResult)>,
+        ) -> Vec<(ToolCall, ToolResult)>,
+    ) -> Result<AgentOutcome, AgentError> {
+        let config = self.config();
+        let mut tool_results = Vec::new();
+        let mut iterations = 1;
+        let mut event = self.backend.send(&messages, &config);
+
+        loop {
+            let calls = match event {
+                ChatEvent::Message(response) if response.tool_calls.is_empty() => {
+                    return Ok(AgentOutcome {
+                        response,
+                        iterations,
+                        tool_results,
+                    });
+                }
+                ChatEvent::Message(response) => response.tool_calls,
+                ChatEvent::ToolRequest(calls) if calls.is_empty() => {
+                    return Err(AgentError::Llm(Error {
+                        code: ErrorCode::InternalError,
+                        message: "Tool request without tool calls".to_string(),
+                        provider_error_json: None,
+                    }));
+                }
+                ChatEvent::ToolRequest(calls) => calls,
+                ChatEvent::Error(error) => return Err(AgentError::Llm(error)),
+            };
+
+            if iterations >= self.max_iterations {
+                return Err(AgentError::MaxIterationsReached { tool_results });
+            }
+
+            debug!(
+                "Iteration {iterations} requested {} tool calls",
+                calls.len()
+            );
+            let results = checkpoint(&calls, &|| {
+                calls
+                    .iter()
+                    .map(|call| (call.clone(), self.toolbox.dispatch(call)))
+                    .collect()
+            });
+            tool_results.extend(results);
+
+            iterations += 1;
+            event = self.backend.continue_(&messages, &tool_results, &config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::agent::{Agent, AgentError, ChatBackend};
+    use crate::golem::llm::llm::{
+        ChatEvent, CompleteResponse, Config, ContentPart, Message, ResponseMetadata, Role,
+        ToolCall, ToolDefinition, ToolResult, ToolSuccess,
+    };
+    use crate::tools::ToolError;
+    use std::cell::RefCell;
+
+    /// Requests a call of the `random` tool until it has got `rounds` tool results
+    struct ScriptedBackend {
+        rounds: usize,
+        seen_tools: RefCell<Vec<String>>,
+    }
+
+    impl ScriptedBackend {
+        fn respond(&self, tool_results: &[(ToolCall, ToolResult)]) -> ChatEvent {
+            if tool_results.len() < self.rounds {
+                ChatEvent::ToolRequest(vec![ToolCall {
+                    id: format!("call-{}", tool_results.len()),
+                    name: "random".to_string(),
+                    arguments_json: r#"{"maximum": 10}"#.to_string(),
+                }])
+            } else {
+                ChatEvent::Message(CompleteResponse {
+                    id: "response".to_string(),
+                    content: vec![ContentPart::Text("sechs".to_string())],
+                    tool_calls: vec![],
+                    reasoning: None,
+                    metadata: ResponseMetadata {
+                        finish_reason: None,
+                        usage: None,
+                        provider_id: None,
+                        timestamp: None,
+                        provider_metadata_json: None,
+                        logprobs: None,
+                        cost: None,
+                    },
+                })
+            }
+        }
+    }
+
+    impl ChatBackend for ScriptedBackend {
+        fn send(&self, _messages: &[Message], config: &Config) -> ChatEvent {
+            *self.seen_tools.borrow_mut() =
+                config.tools.iter().map(|tool| tool.name.clone()).collect();
+            self.respond(&[])
+        }
+
+        fn continue_(
+            &self,
+            _messages: &[Message],
+            tool_results: &[(ToolCall, ToolResult)],
+            _config: &Config,
+        ) -> ChatEvent {
+            self.respond(tool_results)
+        }
+    }
+
+    fn agent(rounds: usize) -> Agent<ScriptedBackend> {
+        Agent::with_backend(
+            ScriptedBackend {
+                rounds,
+                seen_tools: RefCell::new(vec![]),
+            },
+            Config {
+                model: "test".to_string(),
+                temperature: None,
+                max_tokens: None,
+                stop_sequences: None,
+                tools: vec![],
+                tool_choice: None,
+                parallel_tool_calls: None,
+                response_format: None,
+                logprobs: None,
+                reasoning_effort: None,
+                thinking_budget: None,
+                timeout_ms: None,
+                connect_timeout_ms: None,
+                provider_options: vec![],
+            },
+        )
+        .tool(
+            ToolDefinition {
+                name: "random".to_string(),
+                description: None,
+                parameters_schema: "{}".to_string(),
+                built_in: None,
+            },
+            |_, _| Ok(r#"{"value": 6}"#.to_string()),
+        )
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text(
+                "Generate a random number and translate it to German".to_string(),
+            )],
+            cache_control: None,
+        }]
+    }
+
+    #[test]
+    fn runs_tool_calls_until_final_message() {
+        let agent = agent(2);
+        let outcome = agent
+            .run_loop(messages(), |_, dispatch| dispatch())
+            .unwrap();
+
+        assert_eq!(outcome.iterations, 3);
+        assert_eq!(outcome.tool_results.len(), 2);
+        assert_eq!(
+            outcome.response.content,
+            vec![ContentPart::Text("sechs".to_string())]
+        );
+        assert_eq!(*agent.backend.seen_tools.borrow(), vec!["random"]);
+    }
+
+    #[test]
+    fn stops_at_max_iterations() {
+        let result = agent(5)
+            .max_iterations(3)
+            .run_loop(messages(), |_, dispatch| dispatch());
+        match result {
+            Err(AgentError::MaxIterationsReached { tool_results }) => {
+                assert_eq!(tool_results.len(), 2)
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn uses_checkpointed_results() {
+        let agent = agent(1).tool(
+            ToolDefinition {
+                name: "random".to_string(),
+                description: None,
+                parameters_schema: "{}".to_string(),
+                built_in: None,
+            },
+            |_, _| Err(ToolError::new("Must not run when replaying")),
+        );
+        let outcome = agent
+            .run_loop(messages(), |calls, _| {
+                calls
+                    .iter()
+                    .map(|call| {
+                        (
+                            call.clone(),
+                            ToolResult::Success(ToolSuccess {
+                                id: call.id.clone(),
+                                name: call.name.clone(),
+                                result_json: r#"{"value": 6}"#.to_string(),
+                                execution_time_ms: None,
+                            }),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap();
+        assert!(matches!(
+            &outcome.tool_results[0].1,
+            ToolResult::Success(success) if success.result_json == r#"{"value": 6}"#
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(not(feature = "durability"))]
+pub use passthrough_impl::checkpoint_tool_results;
+
+#[cfg(feature = "durability")]
+pub use durable_impl::checkpoint_tool_results;
+
+/// When the durability feature flag is off, the tool handlers are just run
+#[cfg(not(feature = "durability"))]
+mod passthrough_impl {
+    use crate::golem::llm::llm::{ToolCall, ToolResult};
+
+    pub fn checkpoint_tool_results(
+        _calls: &[ToolCall],
+        dispatch: &dyn Fn() -> Vec<(ToolCall, ToolResult)>,
+    ) -> Vec<(ToolCall, ToolResult)> {
+        dispatch()
+    }
+}
+
+/// When the durability feature flag is on, the results of the tool handlers of each iteration are
+/// stored in the oplog as a checkpoint.
+///
+/// The LLM calls between the iterations are persisted by the durable `golem:llm` provider. When
+/// the worker gets recovered, the results of the completed iterations are replayed from the oplog
+/// without running their handlers again, and the loop continues from the first unfinished
+/// iteration.
+#[cfg(feature = "durability")]
+mod durable_impl {
+    use crate::golem::llm::llm::{ToolCall, ToolResult};
+    use golem_rust::bindings::golem::durability::durability::DurableFunctionType;
+    use golem_rust::durability::Durability;
+    use golem_rust::{with_persistence_level, FromValueAndType, IntoValue, PersistenceLevel};
+    use std::fmt::{Display, Formatter};
+
+    pub fn checkpoint_tool_results(
+        calls: &[ToolCall],
+        dispatch: &dyn Fn() -> Vec<(ToolCall, ToolResult)>,
+    ) -> Vec<(ToolCall, ToolResult)> {
+        let durability = Durability::<Vec<(ToolCall, ToolResult)>, UnusedError>::new(
+            "golem_llm_agent",
+            "dispatch_tools",
+            DurableFunctionType::WriteRemote,
+        );
+        if durability.is_live() {
+            let results = with_persistence_level(PersistenceLevel::PersistNothing, dispatch);
+            durability.persist_infallible(calls.to_vec(), results)
+        } else {
+            durability.replay_infallible()
+        }
+    }
+
+    #[derive(Debug, FromValueAndType, IntoValue)]
+    struct UnusedError;
+
+    impl Display for UnusedError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "UnusedError")
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub mod agent;
+mod durability;
+pub mod tools;
+
+wit_bindgen::generate!({
+    path: "wit",
+    world: "agent-host",
+    generate_all,
+    generate_unused_types: true,
+    additional_derives: [PartialEq, golem_rust::FromValueAndType, golem_rust::IntoValue],
+});
+
+pub use crate::agent::{Agent, AgentError, AgentOutcome, ChatBackend, ImportedLlm};
+pub use crate::tools::{Deadline, ToolError};
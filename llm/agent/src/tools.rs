@@ -0,0 +1,234 @@
+use crate::golem::llm::llm::{ToolCall, ToolDefinition, ToolFailure, ToolResult, ToolSuccess};
+use log::debug;
+use std::time::{Duration, Instant};
+
+/// The error returned by a tool handler, passed back to the LLM as a tool failure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolError {
+    pub message: String,
+    pub code: Option<String>,
+}
+
+impl ToolError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            code: None,
+        }
+    }
+}
+
+/// Handles the calls of a tool. It gets the arguments of the call as JSON, and returns the
+/// result as JSON.
+pub type ToolHandler = Box<dyn Fn(&str, Deadline) -> Result<String, ToolError>>;
+
+/// The deadline of a tool call.
+///
+/// A running handler cannot be interrupted in a WebAssembly component, so handlers doing long
+/// running work should check `is_expired`, or use `remaining` for the timeouts of their own
+/// requests. A result returned after the deadline is replaced by a timeout failure.
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    at: Option<Instant>,
+}
+
+impl Deadline {
+    pub fn none() -> Self {
+        Self { at: None }
+    }
+
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Some(Instant::now() + timeout),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    /// The time left until the deadline, or `None` if there is no deadline
+    pub fn remaining(&self) -> Option<Duration> {
+        self.at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+}
+
+struct Tool {
+    definition: ToolDefinition,
+    handler: ToolHandler,
+    timeout: Option<Duration>,
+}
+
+/// The tools registered in an agent
+#[derive(Default)]
+pub(crate) struct Toolbox {
+    tools: Vec<Tool>,
+    default_timeout: Option<Duration>,
+}
+
+impl Toolbox {
+    pub fn register(&mut self, definition: ToolDefinition, handler: ToolHandler) {
+        self.tools
+            .retain(|tool| tool.definition.name != definition.name);
+        self.tools.push(Tool {
+            definition,
+            handler,
+            timeout: None,
+        });
+    }
+
+    pub fn set_timeout(&mut self, name: &str, timeout: Duration) {
+        if let Some(tool) = self.tool(name) {
+            tool.timeout = Some(timeout);
+        }
+    }
+
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        self.default_timeout = Some(timeout);
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &ToolDefinition> {
+        self.tools.iter().map(|tool| &tool.definition)
+    }
+
+    fn tool(&mut self, name: &str) -> Option<&mut Tool> {
+        self.tools
+            .iter_mut()
+            .find(|tool| tool.definition.name == name)
+    }
+
+    /// Runs the handler of the called tool
+    pub fn dispatch(&self, call: &ToolCall) -> ToolResult {
+        let Some(tool) = self
+            .tools
+            .iter()
+            .find(|tool| tool.definition.name == call.name)
+        else {
+            return failure(
+                call,
+                format!("Unknown tool: {}", call.name),
+                Some("unknown_tool".to_string()),
+            );
+        };
+
+        let timeout = tool.timeout.or(self.default_timeout);
+        let deadline = timeout.map(Deadline::after).unwrap_or(Deadline::none());
+        let started_at = Instant::now();
+        let result = (tool.handler)(&call.arguments_json, deadline);
+        let elapsed = started_at.elapsed();
+        debug!(
+            "Tool {} ({}) finished in {} ms",
+            call.name,
+            call.id,
+            elapsed.as_millis()
+        );
+
+        match (result, timeout) {
+            (_, Some(timeout)) if deadline.is_expired() => failure(
+                call,
+                format!(
+                    "Tool {} did not finish within {} ms",
+                    call.name,
+                    timeout.as_millis()
+                ),
+                Some("timeout".to_string()),
+            ),
+            (Ok(result_json), _) => ToolResult::Success(ToolSuccess {
+                id: call.id.clone(),
+                name: call.name.clone(),
+                result_json,
+                execution_time_ms: Some(elapsed.as_millis().min(u32::MAX as u128) as u32),
+            }),
+            (Err(error), _) => failure(call, error.message, error.code),
+        }
+    }
+}
+
+fn failure(call: &ToolCall, error_message: String, error_code: Option<String>) -> ToolResult {
+    ToolResult::Error(ToolFailure {
+        id: call.id.clone(),
+        name: call.name.clone(),
+        error_message,
+        error_code,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{ToolCall, ToolDefinition, ToolResult};
+    use crate::tools::{ToolError, Toolbox};
+    use std::time::Duration;
+
+    fn definition(name: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: None,
+            parameters_schema: "{}".to_string(),
+            built_in: None,
+        }
+    }
+
+    fn call(name: &str) -> ToolCall {
+        ToolCall {
+            id: "call-1".to_string(),
+            name: name.to_string(),
+            arguments_json: r#"{"maximum": 10}"#.to_string(),
+        }
+    }
+
+    fn error_code(result: &ToolResult) -> Option<&str> {
+        match result {
+            ToolResult::Success(_) => None,
+            ToolResult::Error(failure) => failure.error_code.as_deref(),
+        }
+    }
+
+    #[test]
+    fn dispatches_to_the_called_tool() {
+        let mut toolbox = Toolbox::default();
+        toolbox.register(
+            definition("echo"),
+            Box::new(|arguments, _| Ok(arguments.to_string())),
+        );
+        toolbox.register(
+            definition("fail"),
+            Box::new(|_, _| Err(ToolError::new("Out of range"))),
+        );
+
+        match toolbox.dispatch(&call("echo")) {
+            ToolResult::Success(success) => {
+                assert_eq!(success.result_json, r#"{"maximum": 10}"#);
+                assert!(success.execution_time_ms.is_some());
+            }
+            ToolResult::Error(failure) => panic!("Unexpected failure: {failure:?}"),
+        }
+        match toolbox.dispatch(&call("fail")) {
+            ToolResult::Error(failure) => assert_eq!(failure.error_message, "Out of range"),
+            ToolResult::Success(success) => panic!("Unexpected success: {success:?}"),
+        }
+        assert_eq!(
+            error_code(&toolbox.dispatch(&call("missing"))),
+            Some("unknown_tool")
+        );
+    }
+
+    #[test]
+    fn replaces_late_results_with_timeout() {
+        let mut toolbox = Toolbox::default();
+        toolbox.register(
+            definition("slow"),
+            Box::new(|_, deadline| {
+                while !deadline.is_expired() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Ok("{}".to_string())
+            }),
+        );
+        toolbox.set_timeout("slow", Duration::from_millis(5));
+        assert_eq!(
+            error_code(&toolbox.dispatch(&call("slow"))),
+            Some("timeout")
+        );
+    }
+}
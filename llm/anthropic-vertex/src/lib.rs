@@ -0,0 +1,157 @@
+mod auth;
+
+use crate::auth::{access_token, ServiceAccountKey};
+use golem_llm::chat_stream::LlmChatStream;
+use golem_llm::config::{provider_option, with_config_key_or, ACCESS_TOKEN_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Error, ErrorCode, Guest, Message, StreamDelta, ToolCall,
+    ToolResult,
+};
+use golem_llm::LOGGING_STATE;
+use golem_llm_anthropic::client::{Endpoint, MessagesApi};
+use golem_llm_anthropic::conversions::{messages_to_request, tool_results_to_messages};
+use golem_llm_anthropic::{AnthropicChatStream, AnthropicComponent};
+use golem_rust::wasm_rpc::Pollable;
+
+/// Provider option overriding the Google Cloud project the model is used in
+const PROJECT_ID_OPTION: &str = "project_id";
+
+/// Provider option overriding the Google Cloud region the model is used in
+const REGION_OPTION: &str = "region";
+
+/// Anthropic's Claude models hosted on Google Vertex AI.
+///
+/// The `model` of the request is the Vertex AI model id, like `claude-sonnet-4@20250514`.
+/// Requests are authenticated with an OAuth access token of a service account that can use the
+/// model, minted from the key of the service account and refreshed before it expires. A static
+/// access token can be passed instead, overriding the key. Everything but the endpoint and the
+/// authentication is delegated to the Anthropic provider.
+pub struct AnthropicVertexComponent;
+
+impl AnthropicVertexComponent {
+    const PROJECT_ID_ENV_VAR_NAME: &'static str = "ANTHROPIC_VERTEX_PROJECT_ID";
+    const REGION_ENV_VAR_NAME: &'static str = "ANTHROPIC_VERTEX_REGION";
+    const ACCESS_TOKEN_ENV_VAR_NAME: &'static str = "ANTHROPIC_VERTEX_ACCESS_TOKEN";
+    const CLIENT_EMAIL_ENV_VAR_NAME: &'static str = "ANTHROPIC_VERTEX_CLIENT_EMAIL";
+    const PRIVATE_KEY_ENV_VAR_NAME: &'static str = "ANTHROPIC_VERTEX_PRIVATE_KEY";
+    const DEFAULT_REGION: &'static str = "global";
+
+    fn client(config: &Config) -> Result<MessagesApi, Error> {
+        with_config_key_or(
+            provider_option(config, PROJECT_ID_OPTION),
+            Self::PROJECT_ID_ENV_VAR_NAME,
+            Err,
+            |project_id| {
+                let access_token = Self::access_token(config)?;
+                let region = provider_option(config, REGION_OPTION)
+                    .or_else(|| std::env::var(Self::REGION_ENV_VAR_NAME).ok())
+                    .unwrap_or_else(|| Self::DEFAULT_REGION.to_string());
+                Ok(MessagesApi::with_endpoint(Endpoint::VertexAI {
+                    project_id,
+                    region,
+                    access_token,
+                }))
+            },
+        )
+    }
+
+    /// The static access token passed as a provider option or configured in the environment, or
+    /// else the token of the configured service account
+    fn access_token(config: &Config) -> Result<String, Error> {
+        let static_token = provider_option(config, ACCESS_TOKEN_OPTION)
+            .or_else(|| std::env::var(Self::ACCESS_TOKEN_ENV_VAR_NAME).ok());
+        if let Some(access_token) = static_token {
+            return Ok(access_token);
+        }
+        match (
+            std::env::var(Self::CLIENT_EMAIL_ENV_VAR_NAME),
+            std::env::var(Self::PRIVATE_KEY_ENV_VAR_NAME),
+        ) {
+            (Ok(client_email), Ok(private_key)) => access_token(&ServiceAccountKey {
+                client_email,
+                private_key,
+            }),
+            _ => Err(Error {
+                code: ErrorCode::AuthenticationFailed,
+                message: format!(
+                    "Missing credentials, set {} and {} to the key of a service account, or {} \
+                     to an access token",
+                    Self::CLIENT_EMAIL_ENV_VAR_NAME,
+                    Self::PRIVATE_KEY_ENV_VAR_NAME,
+                    Self::ACCESS_TOKEN_ENV_VAR_NAME,
+                ),
+                provider_error_json: None,
+            }),
+        }
+    }
+}
+
+impl Guest for AnthropicVertexComponent {
+    type ChatStream = LlmChatStream<AnthropicChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_request(messages, config) {
+                Ok(request) => AnthropicComponent::request(client, request),
+                Err(err) => ChatEvent::Error(err),
+            },
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_request(messages, config) {
+                Ok(mut request) => {
+                    request
+                        .messages
+                        .extend(tool_results_to_messages(tool_results));
+                    AnthropicComponent::request(client, request)
+                }
+                Err(err) => ChatEvent::Error(err),
+            },
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for AnthropicVertexComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> Self::ChatStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_request(messages, config) {
+                Ok(request) => AnthropicComponent::streaming_request(client, request),
+                Err(err) => AnthropicChatStream::failed(err),
+            },
+            Err(err) => AnthropicChatStream::failed(err),
+        }
+    }
+
+    fn retry_prompt(original_messages: &[Message], partial_result: &[StreamDelta]) -> Vec<Message> {
+        AnthropicComponent::retry_prompt(original_messages, partial_result)
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableAnthropicVertexComponent = golem_llm::durability::DurableLLM<AnthropicVertexComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableAnthropicVertexComponent with_types_in golem_llm);
@@ -1,72 +1,232 @@
-use golem_llm::error::{error_code_from_status, from_event_source_error, from_reqwest_error};
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, unsupported, ProviderError,
+};
 use golem_llm::event_source::EventSource;
-use golem_llm::golem::llm::llm::Error;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
 use log::trace;
-use reqwest::header::HeaderValue;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt::Debug;
 
 const BASE_URL: &str = "https://api.anthropic.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const VERTEX_ANTHROPIC_VERSION: &str = "vertex-2023-10-16";
+/// The code execution tool is still in beta and has to be enabled per request
+const CODE_EXECUTION_BETA: &str = "code-execution-2025-05-22";
+
+/// Where the Messages API is served from, and how requests to it are authenticated
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    /// The Anthropic API, authenticated with an API key
+    Anthropic { api_key: String },
+    /// Claude models published on Google Vertex AI, authenticated with an OAuth access token
+    VertexAI {
+        project_id: String,
+        region: String,
+        access_token: String,
+    },
+}
 
 /// The Anthropic API client for creating model responses.
 pub struct MessagesApi {
-    api_key: String,
+    endpoint: Endpoint,
     client: Client,
 }
 
 impl MessagesApi {
     pub fn new(api_key: String) -> Self {
+        Self::with_endpoint(Endpoint::Anthropic { api_key })
+    }
+
+    /// Creates a client for the Messages API served from `endpoint`, like Vertex AI
+    pub fn with_endpoint(endpoint: Endpoint) -> Self {
         let client = Client::builder()
             .build()
             .expect("Failed to initialize HTTP client");
-        Self { api_key, client }
+        Self { endpoint, client }
     }
 
     pub fn send_messages(&self, request: MessagesRequest) -> Result<MessagesResponse, Error> {
-        trace!("Sending request to Anthropic API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/messages"))
-            .header("anthropic-version", "2023-06-01")
-            .header("x-api-key", &self.api_key)
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
-
-        parse_response(response)
+        trace!("Sending request to Anthropic API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new(self.provider(), "send-messages", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
+
+        let response: Response =
+            interceptor::send(&self.client, self.outgoing("send-messages", request)?)?;
+
+        parse_response(response, recorder)
     }
 
     pub fn stream_send_messages(&self, request: MessagesRequest) -> Result<EventSource, Error> {
-        trace!("Sending request to Anthropic API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/messages"))
-            .header("anthropic-version", "2023-06-01")
-            .header("x-api-key", &self.api_key)
-            .header(
-                reqwest::header::ACCEPT,
-                HeaderValue::from_static("text/event-stream"),
-            )
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        trace!("Sending request to Anthropic API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new(self.provider(), "stream-send-messages", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            self.outgoing("stream-send-messages", request)?
+                .header(reqwest::header::ACCEPT, "text/event-stream")?,
+        )?;
 
         trace!("Initializing SSE stream");
 
         EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
             .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
     }
+
+    /// Creates a batch of Messages requests, processed asynchronously
+    pub fn create_batch(&self, request: CreateBatchRequest) -> Result<MessageBatch, Error> {
+        trace!("Sending request to Anthropic API: {:?}", Redacted(&request));
+        let recorder = Recorder::new(self.provider(), "create-batch", &request);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            self.batches_request("create-batch", Method::POST, "")?
+                .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn get_batch(&self, batch_id: &str) -> Result<MessageBatch, Error> {
+        trace!("Sending request to Anthropic API: get batch {batch_id}");
+        let recorder = Recorder::new(self.provider(), "get-batch", &batch_id);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            self.batches_request("get-batch", Method::GET, &format!("/{batch_id}"))?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    /// Gets the results of an ended batch, one for each of its requests
+    pub fn get_batch_results(&self, batch_id: &str) -> Result<Vec<BatchResultEntry>, Error> {
+        trace!("Sending request to Anthropic API: get results of batch {batch_id}");
+        let recorder = Recorder::new(self.provider(), "get-batch-results", &batch_id);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            self.batches_request(
+                "get-batch-results",
+                Method::GET,
+                &format!("/{batch_id}/results"),
+            )?,
+        )?;
+
+        parse_jsonl_response(response, recorder)
+    }
+
+    fn provider(&self) -> &'static str {
+        match self.endpoint {
+            Endpoint::Anthropic { .. } => "anthropic",
+            Endpoint::VertexAI { .. } => "anthropic-vertex",
+        }
+    }
+
+    fn outgoing(
+        &self,
+        operation: &str,
+        mut request: MessagesRequest,
+    ) -> Result<OutgoingRequest, Error> {
+        match &self.endpoint {
+            Endpoint::Anthropic { api_key } => {
+                let mut outgoing = OutgoingRequest::new(
+                    self.provider(),
+                    operation,
+                    Method::POST,
+                    format!("{BASE_URL}/v1/messages"),
+                )
+                .header("anthropic-version", ANTHROPIC_VERSION)?
+                .header("x-api-key", api_key)?;
+                let uses_code_execution = request
+                    .tools
+                    .iter()
+                    .flatten()
+                    .any(|tool| matches!(tool, Tool::CodeExecution { .. }));
+                if uses_code_execution {
+                    outgoing = outgoing.header("anthropic-beta", CODE_EXECUTION_BETA)?;
+                }
+                outgoing.json(&request)
+            }
+            Endpoint::VertexAI {
+                project_id,
+                region,
+                access_token,
+            } => {
+                // Vertex AI takes the model from the URL and the API version from the body
+                let model = std::mem::take(&mut request.model);
+                request.anthropic_version = Some(VERTEX_ANTHROPIC_VERSION.to_string());
+                OutgoingRequest::new(
+                    self.provider(),
+                    operation,
+                    Method::POST,
+                    vertex_url(project_id, region, &model, request.stream),
+                )
+                .bearer_auth(access_token)?
+                .json(&request)
+            }
+        }
+    }
+
+    /// A request to the Message Batches API, which is only available on the Anthropic API
+    fn batches_request(
+        &self,
+        operation: &str,
+        method: Method,
+        path: &str,
+    ) -> Result<OutgoingRequest, Error> {
+        match &self.endpoint {
+            Endpoint::Anthropic { api_key } => OutgoingRequest::new(
+                self.provider(),
+                operation,
+                method,
+                format!("{BASE_URL}/v1/messages/batches{path}"),
+            )
+            .header("anthropic-version", ANTHROPIC_VERSION)?
+            .header("x-api-key", api_key),
+            Endpoint::VertexAI { .. } => Err(unsupported("batches on Vertex AI")),
+        }
+    }
+}
+
+/// The URL of the `rawPredict` (or `streamRawPredict`) method of a Claude model on Vertex AI
+fn vertex_url(project_id: &str, region: &str, model: &str, stream: bool) -> String {
+    let host = if region == "global" {
+        "aiplatform.googleapis.com".to_string()
+    } else {
+        format!("{region}-aiplatform.googleapis.com")
+    };
+    let method = if stream {
+        "streamRawPredict"
+    } else {
+        "rawPredict"
+    };
+    format!(
+        "https://{host}/v1/projects/{project_id}/locations/{region}\
+         /publishers/anthropic/models/{model}:{method}"
+    )
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessagesRequest {
+    /// Only sent to Vertex AI, which takes it in place of the `anthropic-version` header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anthropic_version: Option<String>,
     pub max_tokens: u32,
     pub messages: Vec<Message>,
+    /// Left out for Vertex AI, which takes the model from the URL
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub model: String,
     pub metadata: Option<MessagesRequestMetadata>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -75,7 +235,8 @@ pub struct MessagesRequest {
     pub system: Vec<Content>, // can only be Text
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
-    // thinking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<Thinking>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -86,6 +247,14 @@ pub struct MessagesRequest {
     pub top_p: Option<f32>,
 }
 
+/// Extended thinking, for which `budget_tokens` must be less than `max_tokens`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Thinking {
+    #[serde(rename = "enabled")]
+    Enabled { budget_tokens: u32 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessagesRequestMetadata {
     pub user_id: Option<String>,
@@ -104,7 +273,10 @@ pub enum Content {
     Text {
         text: String,
         #[serde(skip_serializing_if = "Option::is_none")]
-        cache_control: Option<CacheControl>, // citations
+        cache_control: Option<CacheControl>,
+        /// The sources backing the text, for example the results of a web search
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        citations: Vec<Citation>,
     },
     #[serde(rename = "image")]
     Image {
@@ -128,15 +300,49 @@ pub enum Content {
         content: Vec<Content>, // can only be Text or Image
         is_error: bool,
     },
+    #[serde(rename = "thinking")]
+    Thinking {
+        thinking: String,
+        #[serde(default)]
+        signature: String,
+    },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+    /// A call of a server tool, run by Anthropic
+    #[serde(rename = "server_tool_use")]
+    ServerToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    #[serde(rename = "web_search_tool_result")]
+    WebSearchToolResult { tool_use_id: String, content: Value },
+    #[serde(rename = "code_execution_tool_result")]
+    CodeExecutionToolResult { tool_use_id: String, content: Value },
     // Document
-    // Thinking
-    // RedactedThinking
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    #[serde(rename = "type")]
+    pub typ: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cited_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum CacheControl {
     #[serde(rename = "ephemeral")]
-    Ephemeral,
+    Ephemeral {
+        /// `5m` (the default) or `1h`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ttl: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -195,6 +401,10 @@ pub enum Tool {
         #[serde(skip_serializing_if = "Option::is_none")]
         description: Option<String>,
     },
+    #[serde(rename = "web_search_20250305")]
+    WebSearch { name: String },
+    #[serde(rename = "code_execution_20250522")]
+    CodeExecution { name: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -237,11 +447,76 @@ pub enum Role {
     Assistant,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBatchRequest {
+    pub requests: Vec<BatchRequestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRequestEntry {
+    pub custom_id: String,
+    pub params: MessagesRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBatch {
+    pub id: String,
+    pub processing_status: ProcessingStatus,
+    pub request_counts: RequestCounts,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ProcessingStatus {
+    #[serde(rename = "in_progress")]
+    InProgress,
+    #[serde(rename = "canceling")]
+    Canceling,
+    #[serde(rename = "ended")]
+    Ended,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestCounts {
+    pub processing: u32,
+    pub succeeded: u32,
+    pub errored: u32,
+    pub canceled: u32,
+    pub expired: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResultEntry {
+    pub custom_id: String,
+    pub result: BatchResultOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum BatchResultOutcome {
+    #[serde(rename = "succeeded")]
+    Succeeded { message: MessagesResponse },
+    #[serde(rename = "errored")]
+    Errored { error: ErrorResponse },
+    #[serde(rename = "canceled")]
+    Canceled,
+    #[serde(rename = "expired")]
+    Expired,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
     pub error: ErrorResponseDetails,
 }
 
+impl ErrorResponse {
+    pub fn into_provider_error(self) -> ProviderError {
+        ProviderError {
+            kind: Some(self.error.typ),
+            message: Some(self.error.message),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponseDetails {
     pub message: String,
@@ -256,29 +531,106 @@ pub enum ContentBlockDelta {
     TextDelta { text: String },
     #[serde(rename = "input_json_delta")]
     InputJsonDelta { partial_json: String },
+    #[serde(rename = "thinking_delta")]
+    ThinkingDelta { thinking: String },
+    #[serde(rename = "signature_delta")]
+    SignatureDelta { signature: String },
+    #[serde(rename = "citations_delta")]
+    CitationsDelta { citation: Citation },
+}
+
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
 }
 
-fn parse_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Error> {
+/// Parses a JSON Lines response, like the results of a batch
+fn parse_jsonl_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<Vec<T>, Error> {
     let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    if status.is_success() {
+        raw_body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_response_body(status, line.to_string()))
+            .collect()
+    } else {
+        parse_response_body(status, raw_body)
+    }
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
     if status.is_success() {
-        let body = response
-            .json::<T>()
-            .map_err(|err| from_reqwest_error("Failed to decode response body", err))?;
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
 
-        trace!("Received response from Anthropic API: {body:?}");
+        trace!(
+            "Received response from Anthropic API: {:?}",
+            Redacted(&body)
+        );
 
         Ok(body)
     } else {
-        let error_body = response
-            .json::<ErrorResponse>()
-            .map_err(|err| from_reqwest_error("Failed to receive error response body", err))?;
+        trace!(
+            "Received {status} response from Anthropic API: {:?}",
+            Redacted(&raw_body)
+        );
 
-        trace!("Received {status} response from Anthropic API: {error_body:?}");
+        Err(from_error_response(status, raw_body, |raw_body| {
+            serde_json::from_str::<ErrorResponse>(raw_body)
+                .ok()
+                .map(ErrorResponse::into_provider_error)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, vertex_url, MessagesResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| {
+                format!(
+                    r#"{{"type": "error", "error": {{"type": "api_error", "message": "{message}"}}}}"#
+                )
+            },
+            |status, body| parse_response_body::<MessagesResponse>(status, body).map(|_| ()),
+        );
+    }
 
-        Err(Error {
-            code: error_code_from_status(status),
-            message: format!("Request failed with {status}: {}", error_body.error.message),
-            provider_error_json: Some(serde_json::to_string(&error_body).unwrap()),
-        })
+    #[test]
+    fn builds_vertex_urls() {
+        assert_eq!(
+            vertex_url("my-project", "us-east5", "claude-sonnet-4@20250514", false),
+            "https://us-east5-aiplatform.googleapis.com/v1/projects/my-project/locations/us-east5/publishers/anthropic/models/claude-sonnet-4@20250514:rawPredict"
+        );
+        assert_eq!(
+            vertex_url("my-project", "global", "claude-sonnet-4@20250514", true),
+            "https://aiplatform.googleapis.com/v1/projects/my-project/locations/global/publishers/anthropic/models/claude-sonnet-4@20250514:streamRawPredict"
+        );
     }
 }
@@ -1,15 +1,44 @@
 use crate::client::{
-    Content, ImageSource as ClientImageSource, MediaType, MessagesRequest, MessagesRequestMetadata,
-    MessagesResponse, StopReason, Tool, ToolChoice,
+    BatchRequestEntry, BatchResultEntry, BatchResultOutcome, CacheControl, Citation, Content,
+    CreateBatchRequest, ImageSource as ClientImageSource, MediaType, MessageBatch, MessagesRequest,
+    MessagesRequestMetadata, MessagesResponse, ProcessingStatus, StopReason, Thinking, Tool,
+    ToolChoice,
 };
 use base64::{engine::general_purpose, Engine as _};
+use golem_llm::config::parse_response_schema;
+use golem_llm::error::{classify_error, unsupported};
+use golem_llm::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult, BatchStatus};
 use golem_llm::golem::llm::llm::{
-    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason,
-    ImageReference, ImageSource, ImageUrl, Message, ResponseMetadata, Role, ToolCall,
-    ToolDefinition, ToolResult, Usage,
+    BuiltInTool, BuiltInToolResult, CacheControl as LlmCacheControl, ChatEvent,
+    Citation as LlmCitation, CitedText, CompleteResponse, Config, ContentPart, Error, ErrorCode,
+    FinishReason, ImageReference, ImageSource, ImageUrl, Message, ReasoningEffort, ResponseFormat,
+    ResponseMetadata, Role, ToolCall, ToolChoice as LlmToolChoice, ToolDefinition, ToolResult,
+    Usage,
 };
+use golem_llm::image::{ImageFormat, ImageProcessor};
+use golem_llm::system::SystemPrompt;
+use reqwest::StatusCode;
+use serde_json::json;
 use std::collections::HashMap;
 
+/// The tool Claude is forced to call when a JSON response format is requested. Its input is the
+/// JSON response, returned as the text content of the response.
+pub const RESPONSE_TOOL_NAME: &str = "json_response";
+
+const IMAGE_FORMATS: &[ImageFormat] = &[
+    ImageFormat::Jpeg,
+    ImageFormat::Png,
+    ImageFormat::Gif,
+    ImageFormat::Webp,
+];
+
+/// The size limit of images sent to the Messages API
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// The longest edge of images sent to the Messages API, larger images are downscaled by the API
+/// anyway
+const MAX_IMAGE_DIMENSION: u32 = 1568;
+
 pub fn messages_to_request(
     messages: Vec<Message>,
     config: Config,
@@ -20,41 +49,78 @@ pub fn messages_to_request(
         .map(|kv| (kv.key, kv.value))
         .collect::<HashMap<_, _>>();
 
+    let prompt = SystemPrompt::split(messages);
+
     let mut anthropic_messages = Vec::new();
-    for message in &messages {
-        if message.role != Role::System {
-            anthropic_messages.push(crate::client::Message {
-                role: match &message.role {
-                    Role::User => crate::client::Role::User,
-                    Role::Assistant => crate::client::Role::Assistant,
-                    Role::Tool => crate::client::Role::User,
-                    Role::System => unreachable!(),
-                },
-                content: message_to_content(message),
-            })
-        }
+    for message in &prompt.conversation {
+        anthropic_messages.push(crate::client::Message {
+            role: match &message.role {
+                Role::User => crate::client::Role::User,
+                Role::Assistant => crate::client::Role::Assistant,
+                Role::Tool => crate::client::Role::User,
+                Role::System => unreachable!(),
+            },
+            content: message_to_content(message)?,
+        })
     }
 
+    // The system messages are sent as the content blocks of `system`, keeping their cache
+    // breakpoints
     let mut system_messages = Vec::new();
-    for message in &messages {
-        if message.role == Role::System {
-            system_messages.extend(message_to_content(message))
-        }
+    for message in &prompt.system {
+        system_messages.extend(message_to_content(message)?)
     }
 
-    let tool_choice = config.tool_choice.map(convert_tool_choice);
-    let tools = if config.tools.is_empty() {
-        None
-    } else {
-        let mut tools = Vec::new();
-        for tool in &config.tools {
-            tools.push(tool_definition_to_tool(tool)?)
+    let thinking_budget = config
+        .thinking_budget
+        .or_else(|| config.reasoning_effort.map(thinking_budget));
+
+    let disable_parallel_tool_use = config.parallel_tool_calls.map(|parallel| !parallel);
+    let mut tool_choice = config
+        .tool_choice
+        .map(|tool_choice| convert_tool_choice(tool_choice, disable_parallel_tool_use));
+    let mut tools = Vec::new();
+    for tool in &config.tools {
+        tools.push(tool_definition_to_tool(tool)?)
+    }
+    if let Some(response_tool) = config
+        .response_format
+        .map(response_tool)
+        .transpose()?
+        .flatten()
+    {
+        tools.push(response_tool);
+        if thinking_budget.is_some() {
+            // Extended thinking cannot be combined with forcing a tool call, so Claude is asked
+            // to call it instead
+            tool_choice = Some(ToolChoice::Auto {
+                disable_parallel_tool_use: None,
+            });
+            system_messages.push(Content::Text {
+                text: format!("Respond by calling the {RESPONSE_TOOL_NAME} tool."),
+                cache_control: None,
+                citations: vec![],
+            });
+        } else {
+            tool_choice = Some(ToolChoice::Tool {
+                name: RESPONSE_TOOL_NAME.to_string(),
+                disable_parallel_tool_use: None,
+            });
         }
-        Some(tools)
-    };
+    } else if tool_choice.is_none() && disable_parallel_tool_use.is_some() && !tools.is_empty() {
+        // Parallel tool use can only be set together with a tool choice
+        tool_choice = Some(ToolChoice::Auto {
+            disable_parallel_tool_use,
+        });
+    }
+    let tools = if tools.is_empty() { None } else { Some(tools) };
 
     Ok(MessagesRequest {
-        max_tokens: config.max_tokens.unwrap_or(4096),
+        anthropic_version: None,
+        // The answer gets the default budget on top of the thinking budget
+        max_tokens: config
+            .max_tokens
+            .unwrap_or(4096 + thinking_budget.unwrap_or(0)),
         messages: anthropic_messages,
         model: config.model,
         metadata: options
@@ -66,6 +132,7 @@ pub fn messages_to_request(
         stream: false,
         system: system_messages,
         temperature: config.temperature,
+        thinking: thinking_budget.map(|budget_tokens| Thinking::Enabled { budget_tokens }),
         tool_choice,
         tools,
         top_k: options
@@ -77,32 +144,61 @@ pub fn messages_to_request(
     })
 }
 
-fn convert_tool_choice(tool_name: String) -> ToolChoice {
-    if &tool_name == "auto" {
-        ToolChoice::Auto {
-            disable_parallel_tool_use: None,
-        }
-    } else if &tool_name == "none" {
-        ToolChoice::None {}
-    } else if &tool_name == "any" {
-        ToolChoice::Any {
-            disable_parallel_tool_use: None,
-        }
-    } else {
-        ToolChoice::Tool {
-            name: tool_name,
-            disable_parallel_tool_use: None,
-        }
+/// Claude takes a thinking budget in tokens, of at least 1024
+fn thinking_budget(effort: ReasoningEffort) -> u32 {
+    match effort {
+        ReasoningEffort::Low => 1024,
+        ReasoningEffort::Medium => 4096,
+        ReasoningEffort::High => 16384,
+    }
+}
+
+/// Claude has no JSON mode, so JSON responses are emulated with a tool taking the response as its
+/// input
+fn response_tool(response_format: ResponseFormat) -> Result<Option<Tool>, Error> {
+    let input_schema = match response_format {
+        ResponseFormat::Text => return Ok(None),
+        ResponseFormat::JsonObject => json!({ "type": "object" }),
+        ResponseFormat::JsonSchema(schema) => parse_response_schema(&schema)?,
+    };
+    Ok(Some(Tool::CustomTool {
+        input_schema,
+        name: RESPONSE_TOOL_NAME.to_string(),
+        cache_control: None,
+        description: Some("Responds with a JSON document".to_string()),
+    }))
+}
+
+fn convert_tool_choice(
+    tool_choice: LlmToolChoice,
+    disable_parallel_tool_use: Option<bool>,
+) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Auto {
+            disable_parallel_tool_use,
+        },
+        LlmToolChoice::None => ToolChoice::None {},
+        LlmToolChoice::Required => ToolChoice::Any {
+            disable_parallel_tool_use,
+        },
+        LlmToolChoice::Specific(name) => ToolChoice::Tool {
+            name,
+            disable_parallel_tool_use,
+        },
     }
 }
 
 pub fn process_response(response: MessagesResponse) -> ChatEvent {
     let mut contents = Vec::new();
     let mut tool_calls = Vec::new();
+    let mut reasoning = Vec::new();
+    let mut responded = false;
 
     for content in response.content {
         match content {
-            Content::Text { text, .. } => contents.push(ContentPart::Text(text)),
+            Content::Text {
+                text, citations, ..
+            } => contents.push(text_to_content_part(text, citations)),
             Content::Image { source, .. } => match source {
                 ClientImageSource::Url { url } => {
                     contents.push(ContentPart::Image(ImageReference::Url(ImageUrl {
@@ -137,6 +233,10 @@ pub fn process_response(response: MessagesResponse) -> ChatEvent {
                     }
                 }
             },
+            Content::ToolUse { input, name, .. } if name == RESPONSE_TOOL_NAME => {
+                responded = true;
+                contents.push(ContentPart::Text(input.to_string()));
+            }
             Content::ToolUse {
                 id, input, name, ..
             } => tool_calls.push(ToolCall {
@@ -144,7 +244,16 @@ pub fn process_response(response: MessagesResponse) -> ChatEvent {
                 name,
                 arguments_json: serde_json::to_string(&input).unwrap(),
             }),
-            Content::ToolResult { .. } => {}
+            Content::Thinking { thinking, .. } => reasoning.push(thinking),
+            Content::WebSearchToolResult { content, .. } => {
+                contents.push(built_in_tool_result(BuiltInTool::WebSearch, content))
+            }
+            Content::CodeExecutionToolResult { content, .. } => {
+                contents.push(built_in_tool_result(BuiltInTool::CodeInterpreter, content))
+            }
+            Content::ToolResult { .. }
+            | Content::RedactedThinking { .. }
+            | Content::ServerToolUse { .. } => {}
         }
     }
 
@@ -152,22 +261,98 @@ pub fn process_response(response: MessagesResponse) -> ChatEvent {
         ChatEvent::ToolRequest(tool_calls)
     } else {
         let metadata = ResponseMetadata {
-            finish_reason: response.stop_reason.map(stop_reason_to_finish_reason),
+            finish_reason: if responded {
+                Some(FinishReason::Stop)
+            } else {
+                response.stop_reason.map(stop_reason_to_finish_reason)
+            },
             usage: Some(convert_usage(response.usage)),
             provider_id: None,
             timestamp: None,
             provider_metadata_json: None,
+            logprobs: None,
+            cost: None,
         };
 
         ChatEvent::Message(CompleteResponse {
             id: response.id,
             content: contents,
             tool_calls,
+            reasoning: (!reasoning.is_empty()).then(|| reasoning.join("\n\n")),
             metadata,
         })
     }
 }
 
+pub fn batch_requests_to_request(requests: Vec<BatchRequest>) -> Result<CreateBatchRequest, Error> {
+    let requests = requests
+        .into_iter()
+        .map(|request| {
+            Ok(BatchRequestEntry {
+                custom_id: request.custom_id,
+                params: messages_to_request(request.messages, request.config)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    Ok(CreateBatchRequest { requests })
+}
+
+pub fn batch_to_info(batch: MessageBatch) -> BatchInfo {
+    let counts = batch.request_counts;
+    BatchInfo {
+        id: batch.id,
+        status: match batch.processing_status {
+            // A cancelled batch ends once its requests being processed finish, and the results
+            // of all of its requests become available like for any other batch
+            ProcessingStatus::InProgress | ProcessingStatus::Canceling => BatchStatus::InProgress,
+            ProcessingStatus::Ended => BatchStatus::Completed,
+        },
+        total: counts.processing
+            + counts.succeeded
+            + counts.errored
+            + counts.canceled
+            + counts.expired,
+        succeeded: counts.succeeded,
+        failed: counts.errored + counts.canceled + counts.expired,
+    }
+}
+
+pub fn batch_entry_to_result(entry: BatchResultEntry) -> BatchResult {
+    let event = match entry.result {
+        BatchResultOutcome::Succeeded { message } => process_response(message),
+        BatchResultOutcome::Errored { error } => {
+            let provider_error_json = serde_json::to_string(&error).ok();
+            let error = error.into_provider_error();
+            // The results have no status of their own, and most of the failed requests of a
+            // batch are invalid ones
+            let status = if error.kind.as_deref() == Some("invalid_request_error") {
+                StatusCode::BAD_REQUEST
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            ChatEvent::Error(Error {
+                code: classify_error(status, &error),
+                message: error.message.unwrap_or_default(),
+                provider_error_json,
+            })
+        }
+        BatchResultOutcome::Canceled => ChatEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "The batch was cancelled before the request was processed".to_string(),
+            provider_error_json: None,
+        }),
+        BatchResultOutcome::Expired => ChatEvent::Error(Error {
+            code: ErrorCode::Timeout,
+            message: "The batch expired before the request was processed".to_string(),
+            provider_error_json: None,
+        }),
+    };
+    BatchResult {
+        custom_id: entry.custom_id,
+        event,
+    }
+}
+
 pub fn tool_results_to_messages(
     tool_results: Vec<(ToolCall, ToolResult)>,
 ) -> Vec<crate::client::Message> {
@@ -190,6 +375,7 @@ pub fn tool_results_to_messages(
                 content: vec![Content::Text {
                     text: success.result_json,
                     cache_control: None,
+                    citations: Vec::new(),
                 }],
                 is_error: false,
             },
@@ -199,6 +385,7 @@ pub fn tool_results_to_messages(
                 content: vec![Content::Text {
                     text: error.error_message,
                     cache_control: None,
+                    citations: Vec::new(),
                 }],
                 is_error: true,
             },
@@ -226,18 +413,22 @@ pub fn convert_usage(usage: crate::client::Usage) -> Usage {
         input_tokens: Some(usage.input_tokens),
         output_tokens: Some(usage.output_tokens),
         total_tokens: None,
+        cached_tokens: usage.cache_read_input_tokens,
     }
 }
 
-fn message_to_content(message: &Message) -> Vec<Content> {
+fn message_to_content(message: &Message) -> Result<Vec<Content>, Error> {
     let mut result = Vec::new();
 
     for content_part in &message.content {
         match content_part {
-            ContentPart::Text(text) => result.push(Content::Text {
-                text: text.clone(),
-                cache_control: None,
-            }),
+            // Citations can not be sent back, so cited text is sent as plain text
+            ContentPart::Text(text) | ContentPart::CitedText(CitedText { text, .. }) => result
+                .push(Content::Text {
+                    text: text.clone(),
+                    cache_control: None,
+                    citations: Vec::new(),
+                }),
             ContentPart::Image(image_reference) => match image_reference {
                 ImageReference::Url(image_url) => result.push(Content::Image {
                     source: ClientImageSource::Url {
@@ -246,13 +437,18 @@ fn message_to_content(message: &Message) -> Vec<Content> {
                     cache_control: None,
                 }),
                 ImageReference::Inline(image_source) => {
-                    let base64_data = general_purpose::STANDARD.encode(&image_source.data);
-                    let media_type = match image_source.mime_type.as_str() {
-                        "image/jpeg" => MediaType::Jpeg,
-                        "image/png" => MediaType::Png,
-                        "image/gif" => MediaType::Gif,
-                        "image/webp" => MediaType::Webp,
-                        _ => MediaType::Jpeg,
+                    let image = ImageProcessor::from_env(
+                        IMAGE_FORMATS,
+                        MAX_IMAGE_BYTES,
+                        MAX_IMAGE_DIMENSION,
+                    )
+                    .process(image_source)?;
+                    let base64_data = general_purpose::STANDARD.encode(&image.data);
+                    let media_type = match image.format {
+                        ImageFormat::Jpeg => MediaType::Jpeg,
+                        ImageFormat::Png => MediaType::Png,
+                        ImageFormat::Gif => MediaType::Gif,
+                        ImageFormat::Webp => MediaType::Webp,
                     };
 
                     result.push(Content::Image {
@@ -264,13 +460,86 @@ fn message_to_content(message: &Message) -> Vec<Content> {
                     });
                 }
             },
+            ContentPart::Audio(_) => return Err(unsupported("audio with Anthropic")),
+            // The results of server tools are only part of the response they were run for
+            ContentPart::BuiltInToolResult(_) => {}
         }
     }
 
-    result
+    if let (Some(cache_control), Some(last)) = (message.cache_control, result.last_mut()) {
+        set_cache_control(last, convert_cache_control(cache_control));
+    }
+
+    Ok(result)
+}
+
+/// A cache breakpoint on the last content block of a message caches the prompt up to and
+/// including the message
+fn set_cache_control(content: &mut Content, value: CacheControl) {
+    match content {
+        Content::Text { cache_control, .. }
+        | Content::Image { cache_control, .. }
+        | Content::ToolUse { cache_control, .. }
+        | Content::ToolResult { cache_control, .. } => *cache_control = Some(value),
+        Content::Thinking { .. }
+        | Content::RedactedThinking { .. }
+        | Content::ServerToolUse { .. }
+        | Content::WebSearchToolResult { .. }
+        | Content::CodeExecutionToolResult { .. } => {}
+    }
+}
+
+/// Text backed by citations becomes cited text
+pub fn text_to_content_part(text: String, citations: Vec<Citation>) -> ContentPart {
+    if citations.is_empty() {
+        ContentPart::Text(text)
+    } else {
+        ContentPart::CitedText(CitedText {
+            text,
+            citations: citations.into_iter().map(convert_citation).collect(),
+        })
+    }
+}
+
+pub fn convert_citation(citation: Citation) -> LlmCitation {
+    LlmCitation {
+        url: citation.url,
+        title: citation.title,
+        cited_text: citation.cited_text,
+    }
+}
+
+pub fn built_in_tool_result(tool: BuiltInTool, content: serde_json::Value) -> ContentPart {
+    ContentPart::BuiltInToolResult(BuiltInToolResult {
+        tool,
+        output_json: content.to_string(),
+    })
+}
+
+fn convert_cache_control(cache_control: LlmCacheControl) -> CacheControl {
+    CacheControl::Ephemeral {
+        ttl: match cache_control {
+            LlmCacheControl::Ephemeral => None,
+            LlmCacheControl::Extended => Some("1h".to_string()),
+        },
+    }
 }
 
 fn tool_definition_to_tool(tool: &ToolDefinition) -> Result<Tool, Error> {
+    match tool.built_in {
+        Some(BuiltInTool::WebSearch) => {
+            return Ok(Tool::WebSearch {
+                name: "web_search".to_string(),
+            })
+        }
+        Some(BuiltInTool::CodeInterpreter) => {
+            return Ok(Tool::CodeExecution {
+                name: "code_execution".to_string(),
+            })
+        }
+        Some(BuiltInTool::FileSearch) => return Err(unsupported("file search with Anthropic")),
+        None => {}
+    }
     match serde_json::from_str(&tool.parameters_schema) {
         Ok(value) => Ok(Tool::CustomTool {
             input_schema: value,
@@ -285,3 +554,426 @@ fn tool_definition_to_tool(tool: &ToolDefinition) -> Result<Tool, Error> {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{
+        BatchResultEntry, Content, MessageBatch, MessagesResponse, Tool, ToolChoice,
+    };
+    use crate::conversions::{
+        batch_entry_to_result, batch_to_info, messages_to_request, process_response,
+        RESPONSE_TOOL_NAME,
+    };
+    use golem_llm::golem::llm::batch::{BatchInfo, BatchStatus};
+    use golem_llm::golem::llm::llm::{
+        BuiltInTool, BuiltInToolResult, CacheControl, ChatEvent, Citation, CitedText, Config,
+        ContentPart, ErrorCode, FinishReason, Message, ReasoningEffort, ResponseFormat, Role,
+        ToolChoice as LlmToolChoice, ToolDefinition,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn emulates_json_responses_with_a_tool() {
+        let config = Config {
+            model: "claude-3-7-sonnet-20250219".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: Some(ResponseFormat::JsonSchema(
+                r#"{"type": "object", "properties": {"city": {"type": "string"}}}"#.to_string(),
+            )),
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let request = messages_to_request(vec![], config).unwrap();
+        assert!(matches!(
+            request.tool_choice,
+            Some(ToolChoice::Tool { ref name, .. }) if name == RESPONSE_TOOL_NAME
+        ));
+        assert!(matches!(
+            request.tools.as_deref(),
+            Some([Tool::CustomTool { name, .. }]) if name == RESPONSE_TOOL_NAME
+        ));
+
+        let response: MessagesResponse = serde_json::from_value(json!({
+            "id": "msg_1",
+            "model": "claude-3-7-sonnet-20250219",
+            "role": "assistant",
+            "stop_reason": "tool_use",
+            "usage": { "input_tokens": 10, "output_tokens": 5 },
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": RESPONSE_TOOL_NAME,
+                "input": { "city": "Paris" }
+            }]
+        }))
+        .unwrap();
+        match process_response(response) {
+            ChatEvent::Message(response) => {
+                assert_eq!(
+                    response.content,
+                    vec![ContentPart::Text(r#"{"city":"Paris"}"#.to_string())]
+                );
+                assert!(response.tool_calls.is_empty());
+                assert_eq!(response.metadata.finish_reason, Some(FinishReason::Stop));
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn asks_for_json_responses_when_thinking() {
+        let config = Config {
+            model: "claude-3-7-sonnet-20250219".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: Some(ResponseFormat::JsonObject),
+            logprobs: None,
+            reasoning_effort: Some(ReasoningEffort::Low),
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let request = messages_to_request(vec![], config).unwrap();
+        assert!(matches!(request.tool_choice, Some(ToolChoice::Auto { .. })));
+        assert!(matches!(
+            request.system.as_slice(),
+            [Content::Text { text, .. }] if text.contains(RESPONSE_TOOL_NAME)
+        ));
+    }
+
+    #[test]
+    fn sets_cache_breakpoints_on_the_last_block_of_messages() {
+        let config = Config {
+            model: "claude-3-7-sonnet-20250219".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let messages = vec![
+            Message {
+                role: Role::System,
+                name: None,
+                content: vec![ContentPart::Text("A long system prompt".to_string())],
+                cache_control: Some(CacheControl::Extended),
+            },
+            Message {
+                role: Role::User,
+                name: None,
+                content: vec![
+                    ContentPart::Text("A long document".to_string()),
+                    ContentPart::Text("A question".to_string()),
+                ],
+                cache_control: Some(CacheControl::Ephemeral),
+            },
+        ];
+        let request = messages_to_request(messages, config).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.system).unwrap(),
+            json!([{
+                "type": "text",
+                "text": "A long system prompt",
+                "cache_control": { "type": "ephemeral", "ttl": "1h" }
+            }])
+        );
+        assert_eq!(
+            serde_json::to_value(&request.messages[0].content).unwrap(),
+            json!([
+                { "type": "text", "text": "A long document" },
+                {
+                    "type": "text",
+                    "text": "A question",
+                    "cache_control": { "type": "ephemeral" }
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn disables_parallel_tool_use_in_the_tool_choice() {
+        let config = Config {
+            model: "claude-3-7-sonnet-20250219".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![ToolDefinition {
+                name: "get_weather".to_string(),
+                description: None,
+                parameters_schema: r#"{"type": "object"}"#.to_string(),
+                built_in: None,
+            }],
+            tool_choice: Some(LlmToolChoice::Required),
+            parallel_tool_calls: Some(false),
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let request = messages_to_request(vec![], config.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.tool_choice).unwrap(),
+            json!({ "type": "any", "disable_parallel_tool_use": true })
+        );
+
+        let request = messages_to_request(
+            vec![],
+            Config {
+                tool_choice: None,
+                ..config
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.tool_choice).unwrap(),
+            json!({ "type": "auto", "disable_parallel_tool_use": true })
+        );
+    }
+
+    #[test]
+    fn enables_extended_thinking() {
+        let config = Config {
+            model: "claude-sonnet-4-20250514".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: Some(ReasoningEffort::Medium),
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let request = messages_to_request(vec![], config).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.thinking).unwrap(),
+            json!({ "type": "enabled", "budget_tokens": 4096 })
+        );
+        assert_eq!(request.max_tokens, 8192);
+
+        let response: MessagesResponse = serde_json::from_value(json!({
+            "id": "msg_1",
+            "model": "claude-sonnet-4-20250514",
+            "role": "assistant",
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 10, "output_tokens": 50 },
+            "content": [
+                { "type": "thinking", "thinking": "2 + 2 is 4", "signature": "EqQB" },
+                { "type": "text", "text": "4" }
+            ]
+        }))
+        .unwrap();
+        match process_response(response) {
+            ChatEvent::Message(response) => {
+                assert_eq!(response.content, vec![ContentPart::Text("4".to_string())]);
+                assert_eq!(response.reasoning.as_deref(), Some("2 + 2 is 4"));
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn runs_server_tools() {
+        let config = Config {
+            model: "claude-sonnet-4-20250514".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![ToolDefinition {
+                name: "web_search".to_string(),
+                description: None,
+                parameters_schema: "{}".to_string(),
+                built_in: Some(BuiltInTool::WebSearch),
+            }],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let request = messages_to_request(vec![], config).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.tools).unwrap(),
+            json!([{ "type": "web_search_20250305", "name": "web_search" }])
+        );
+
+        let results = json!([{
+            "type": "web_search_result",
+            "url": "https://golem.cloud",
+            "title": "Golem Cloud",
+            "encrypted_content": "EqgfCioIARgBIiQ3"
+        }]);
+        let response: MessagesResponse = serde_json::from_value(json!({
+            "id": "msg_1",
+            "model": "claude-sonnet-4-20250514",
+            "role": "assistant",
+            "stop_reason": "end_turn",
+            "usage": { "input_tokens": 10, "output_tokens": 50 },
+            "content": [
+                {
+                    "type": "server_tool_use",
+                    "id": "srvtoolu_1",
+                    "name": "web_search",
+                    "input": { "query": "golem cloud" }
+                },
+                {
+                    "type": "web_search_tool_result",
+                    "tool_use_id": "srvtoolu_1",
+                    "content": results
+                },
+                {
+                    "type": "text",
+                    "text": "Golem runs durable agents",
+                    "citations": [{
+                        "type": "web_search_result_location",
+                        "url": "https://golem.cloud",
+                        "title": "Golem Cloud",
+                        "cited_text": "Durable agents",
+                        "encrypted_index": "Eo8BCioIAhgB"
+                    }]
+                }
+            ]
+        }))
+        .unwrap();
+        match process_response(response) {
+            ChatEvent::Message(response) => assert_eq!(
+                response.content,
+                vec![
+                    ContentPart::BuiltInToolResult(BuiltInToolResult {
+                        tool: BuiltInTool::WebSearch,
+                        output_json: results.to_string(),
+                    }),
+                    ContentPart::CitedText(CitedText {
+                        text: "Golem runs durable agents".to_string(),
+                        citations: vec![Citation {
+                            url: Some("https://golem.cloud".to_string()),
+                            title: Some("Golem Cloud".to_string()),
+                            cited_text: Some("Durable agents".to_string()),
+                        }],
+                    }),
+                ]
+            ),
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn converts_batches_and_their_results() {
+        let batch: MessageBatch = serde_json::from_value(json!({
+            "id": "msgbatch_01",
+            "type": "message_batch",
+            "processing_status": "ended",
+            "request_counts": {
+                "processing": 0,
+                "succeeded": 2,
+                "errored": 1,
+                "canceled": 0,
+                "expired": 1
+            },
+            "results_url": "https://api.anthropic.com/v1/messages/batches/msgbatch_01/results"
+        }))
+        .unwrap();
+        assert_eq!(
+            batch_to_info(batch),
+            BatchInfo {
+                id: "msgbatch_01".to_string(),
+                status: BatchStatus::Completed,
+                total: 4,
+                succeeded: 2,
+                failed: 2,
+            }
+        );
+
+        let succeeded: BatchResultEntry = serde_json::from_value(json!({
+            "custom_id": "request-1",
+            "result": {
+                "type": "succeeded",
+                "message": {
+                    "id": "msg_1",
+                    "model": "claude-sonnet-4-20250514",
+                    "role": "assistant",
+                    "stop_reason": "end_turn",
+                    "usage": { "input_tokens": 10, "output_tokens": 5 },
+                    "content": [{ "type": "text", "text": "Paris" }]
+                }
+            }
+        }))
+        .unwrap();
+        let result = batch_entry_to_result(succeeded);
+        assert_eq!(result.custom_id, "request-1");
+        match result.event {
+            ChatEvent::Message(response) => {
+                assert_eq!(
+                    response.content,
+                    vec![ContentPart::Text("Paris".to_string())]
+                );
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+
+        let errored: BatchResultEntry = serde_json::from_value(json!({
+            "custom_id": "request-2",
+            "result": {
+                "type": "errored",
+                "error": {
+                    "type": "error",
+                    "error": {
+                        "type": "invalid_request_error",
+                        "message": "max_tokens: Field required"
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        match batch_entry_to_result(errored).event {
+            ChatEvent::Error(error) => {
+                assert_eq!(error.code, ErrorCode::InvalidRequest);
+                assert_eq!(error.message, "max_tokens: Field required");
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+
+        let expired: BatchResultEntry = serde_json::from_value(json!({
+            "custom_id": "request-3",
+            "result": { "type": "expired" }
+        }))
+        .unwrap();
+        match batch_entry_to_result(expired).event {
+            ChatEvent::Error(error) => assert_eq!(error.code, ErrorCode::Timeout),
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+}
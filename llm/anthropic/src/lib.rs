@@ -1,24 +1,29 @@
-mod client;
-mod conversions;
+pub mod client;
+pub mod conversions;
 
 use crate::client::{
     Content, ContentBlockDelta, ErrorResponse, MessagesApi, MessagesRequest, StopReason, Usage,
 };
 use crate::conversions::{
-    convert_usage, messages_to_request, process_response, stop_reason_to_finish_reason,
-    tool_results_to_messages,
+    batch_entry_to_result, batch_requests_to_request, batch_to_info, built_in_tool_result,
+    convert_citation, convert_usage, messages_to_request, process_response,
+    stop_reason_to_finish_reason, tool_results_to_messages, RESPONSE_TOOL_NAME,
 };
 use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
-use golem_llm::config::with_config_key;
-use golem_llm::durability::{DurableLLM, ExtendedGuest};
+use golem_llm::config::{provider_option, with_config_key, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::error::classify_error;
 use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult};
 use golem_llm::golem::llm::llm::{
-    ChatEvent, ChatStream, Config, ContentPart, Error, ErrorCode, Guest, Message, ResponseMetadata,
-    Role, StreamDelta, StreamEvent, ToolCall, ToolResult,
+    BuiltInTool, ChatEvent, ChatStream, CitedText, Config, ContentPart, Error, FinishReason, Guest,
+    Message, ResponseMetadata, Role, StreamDelta, StreamEvent, ToolCall, ToolResult,
 };
+use golem_llm::redaction::Redacted;
 use golem_llm::LOGGING_STATE;
 use golem_rust::wasm_rpc::Pollable;
 use log::trace;
+use reqwest::StatusCode;
 use std::cell::{Ref, RefCell, RefMut};
 use std::collections::HashMap;
 
@@ -29,11 +34,13 @@ struct JsonFragment {
     json: String,
 }
 
-struct AnthropicChatStream {
+pub struct AnthropicChatStream {
     stream: RefCell<Option<EventSource>>,
     failure: Option<Error>,
     finished: RefCell<bool>,
     json_fragments: RefCell<HashMap<u64, JsonFragment>>,
+    /// The index of the content block calling the JSON response tool, streamed as text
+    response_block: RefCell<Option<u64>>,
     response_metadata: RefCell<ResponseMetadata>,
 }
 
@@ -44,12 +51,15 @@ impl AnthropicChatStream {
             failure: None,
             finished: RefCell::new(false),
             json_fragments: RefCell::new(HashMap::new()),
+            response_block: RefCell::new(None),
             response_metadata: RefCell::new(ResponseMetadata {
                 finish_reason: None,
                 usage: None,
                 provider_id: None,
                 timestamp: None,
                 provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
             }),
         })
     }
@@ -60,12 +70,15 @@ impl AnthropicChatStream {
             failure: Some(error),
             finished: RefCell::new(false),
             json_fragments: RefCell::new(HashMap::new()),
+            response_block: RefCell::new(None),
             response_metadata: RefCell::new(ResponseMetadata {
                 finish_reason: None,
                 usage: None,
                 provider_id: None,
                 timestamp: None,
                 provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
             }),
         })
     }
@@ -93,7 +106,7 @@ impl LlmChatStreamState for AnthropicChatStream {
     }
 
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
-        trace!("Received raw stream event: {raw}");
+        trace!("Received raw stream event: {}", Redacted(&raw));
         let json: serde_json::Value = serde_json::from_str(raw)
             .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
 
@@ -104,10 +117,12 @@ impl LlmChatStreamState for AnthropicChatStream {
         match typ {
             Some("error") => {
                 let error = serde_json::from_value::<ErrorResponse>(json)
-                    .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+                    .map_err(|err| format!("Failed to deserialize stream event: {err}"))?
+                    .into_provider_error();
+                // Errors in the stream have no status of their own, such as `overloaded_error`
                 Ok(Some(StreamEvent::Error(Error {
-                    code: ErrorCode::InternalError,
-                    message: error.error.message,
+                    code: classify_error(StatusCode::INTERNAL_SERVER_ERROR, &error),
+                    message: error.message.unwrap_or_default(),
                     provider_error_json: None,
                 })))
             }
@@ -131,18 +146,38 @@ impl LlmChatStreamState for AnthropicChatStream {
                 let content_block = serde_json::from_value::<Content>(raw_content_block.clone())
                     .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
 
-                if let Content::ToolUse { id, name, .. } = content_block {
-                    self.json_fragments.borrow_mut().insert(
-                        index,
-                        JsonFragment {
-                            id,
-                            name,
-                            json: String::new(),
-                        },
-                    );
-                }
-
-                Ok(None)
+                let result = match content_block {
+                    Content::ToolUse { id, name, .. } => {
+                        if name == RESPONSE_TOOL_NAME {
+                            *self.response_block.borrow_mut() = Some(index);
+                            return Ok(None);
+                        }
+                        self.json_fragments.borrow_mut().insert(
+                            index,
+                            JsonFragment {
+                                id,
+                                name,
+                                json: String::new(),
+                            },
+                        );
+                        return Ok(None);
+                    }
+                    Content::WebSearchToolResult { content, .. } => {
+                        built_in_tool_result(BuiltInTool::WebSearch, content)
+                    }
+                    Content::CodeExecutionToolResult { content, .. } => {
+                        built_in_tool_result(BuiltInTool::CodeInterpreter, content)
+                    }
+                    _ => return Ok(None),
+                };
+
+                // The results of server tools arrive whole, in the start of their block
+                Ok(Some(StreamEvent::Delta(StreamDelta {
+                    content: Some(vec![result]),
+                    tool_calls: None,
+                    reasoning: None,
+                    logprobs: None,
+                })))
             }
             Some("content_block_delta") => {
                 let raw_delta = json
@@ -159,6 +194,29 @@ impl LlmChatStreamState for AnthropicChatStream {
                         Ok(Some(StreamEvent::Delta(StreamDelta {
                             content: Some(vec![ContentPart::Text(text)]),
                             tool_calls: None,
+                            reasoning: None,
+                            logprobs: None,
+                        })))
+                    }
+                    ContentBlockDelta::ThinkingDelta { thinking } => {
+                        Ok(Some(StreamEvent::Delta(StreamDelta {
+                            content: None,
+                            tool_calls: None,
+                            reasoning: Some(thinking),
+                            logprobs: None,
+                        })))
+                    }
+                    ContentBlockDelta::SignatureDelta { .. } => Ok(None),
+                    // Citations precede the text they back
+                    ContentBlockDelta::CitationsDelta { citation } => {
+                        Ok(Some(StreamEvent::Delta(StreamDelta {
+                            content: Some(vec![ContentPart::CitedText(CitedText {
+                                text: String::new(),
+                                citations: vec![convert_citation(citation)],
+                            })]),
+                            tool_calls: None,
+                            reasoning: None,
+                            logprobs: None,
                         })))
                     }
                     ContentBlockDelta::InputJsonDelta { partial_json } => {
@@ -171,9 +229,20 @@ impl LlmChatStreamState for AnthropicChatStream {
                                     .to_string()
                             })?;
 
-                        let mut json_fragments = self.json_fragments.borrow_mut();
-                        let fragment = json_fragments.entry(index).or_default();
-                        fragment.json.push_str(&partial_json);
+                        if *self.response_block.borrow() == Some(index) {
+                            return Ok(Some(StreamEvent::Delta(StreamDelta {
+                                content: Some(vec![ContentPart::Text(partial_json)]),
+                                tool_calls: None,
+                                reasoning: None,
+                                logprobs: None,
+                            })));
+                        }
+
+                        // The input of server tools is not collected, as they are run by
+                        // Anthropic
+                        if let Some(fragment) = self.json_fragments.borrow_mut().get_mut(&index) {
+                            fragment.json.push_str(&partial_json);
+                        }
 
                         Ok(None)
                     }
@@ -196,6 +265,8 @@ impl LlmChatStreamState for AnthropicChatStream {
                             name: tool_use.name,
                             arguments_json: tool_use.json,
                         }]),
+                        reasoning: None,
+                        logprobs: None,
                     })))
                 } else {
                     Ok(None)
@@ -215,7 +286,11 @@ impl LlmChatStreamState for AnthropicChatStream {
 
                 if let Some(stop_reason) = stop_reason {
                     self.response_metadata.borrow_mut().finish_reason =
-                        Some(stop_reason_to_finish_reason(stop_reason));
+                        if self.response_block.borrow().is_some() {
+                            Some(FinishReason::Stop)
+                        } else {
+                            Some(stop_reason_to_finish_reason(stop_reason))
+                        };
                 }
                 if let Some(usage) = usage {
                     self.response_metadata.borrow_mut().usage = Some(convert_usage(usage));
@@ -232,19 +307,21 @@ impl LlmChatStreamState for AnthropicChatStream {
     }
 }
 
-struct AnthropicComponent;
+pub struct AnthropicComponent;
 
 impl AnthropicComponent {
     const ENV_VAR_NAME: &'static str = "ANTHROPIC_API_KEY";
 
-    fn request(client: MessagesApi, request: MessagesRequest) -> ChatEvent {
+    /// Sends a request through `client`, also used by the Vertex AI provider
+    pub fn request(client: MessagesApi, request: MessagesRequest) -> ChatEvent {
         match client.send_messages(request) {
             Ok(response) => process_response(response),
             Err(err) => ChatEvent::Error(err),
         }
     }
 
-    fn streaming_request(
+    /// Opens a stream through `client`, also used by the Vertex AI provider
+    pub fn streaming_request(
         client: MessagesApi,
         mut request: MessagesRequest,
     ) -> LlmChatStream<AnthropicChatStream> {
@@ -261,14 +338,19 @@ impl Guest for AnthropicComponent {
 
     fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |anthropic_api_key| {
-            let client = MessagesApi::new(anthropic_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |anthropic_api_key| {
+                let client = MessagesApi::new(anthropic_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(request) => Self::request(client, request),
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
     }
 
     fn continue_(
@@ -278,19 +360,24 @@ impl Guest for AnthropicComponent {
     ) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |anthropic_api_key| {
-            let client = MessagesApi::new(anthropic_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |anthropic_api_key| {
+                let client = MessagesApi::new(anthropic_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(mut request) => {
-                    request
-                        .messages
-                        .extend(tool_results_to_messages(tool_results));
-                    Self::request(client, request)
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
                 }
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+            },
+        )
     }
 
     fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
@@ -305,7 +392,8 @@ impl ExtendedGuest for AnthropicComponent {
     ) -> LlmChatStream<AnthropicChatStream> {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
             Self::ENV_VAR_NAME,
             AnthropicChatStream::failed,
             |anthropic_api_key| {
@@ -330,6 +418,7 @@ impl ExtendedGuest for AnthropicComponent {
                      Please continue your response from where you left off. \
                      Do not include the part of the response that was already seen.".to_string()),
             ],
+            cache_control: None,
         });
         extended_messages.push(Message {
             role: Role::User,
@@ -337,6 +426,7 @@ impl ExtendedGuest for AnthropicComponent {
             content: vec![ContentPart::Text(
                 "Here is the original question:".to_string(),
             )],
+            cache_control: None,
         });
         extended_messages.extend_from_slice(original_messages);
 
@@ -364,6 +454,7 @@ impl ExtendedGuest for AnthropicComponent {
             .into_iter()
             .chain(partial_result_as_content)
             .collect(),
+            cache_control: None,
         });
         extended_messages
     }
@@ -371,8 +462,43 @@ impl ExtendedGuest for AnthropicComponent {
     fn subscribe(stream: &Self::ChatStream) -> Pollable {
         stream.subscribe()
     }
+
+    fn submit_batch(requests: Vec<BatchRequest>) -> Result<BatchInfo, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let api_key = requests
+            .first()
+            .and_then(|request| provider_option(&request.config, API_KEY_OPTION));
+        with_config_key_or(api_key, Self::ENV_VAR_NAME, Err, |anthropic_api_key| {
+            let client = MessagesApi::new(anthropic_api_key);
+
+            let request = batch_requests_to_request(requests)?;
+            client.create_batch(request).map(batch_to_info)
+        })
+    }
+
+    fn poll_batch(batch_id: String) -> Result<BatchInfo, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |anthropic_api_key| {
+            let client = MessagesApi::new(anthropic_api_key);
+            client.get_batch(&batch_id).map(batch_to_info)
+        })
+    }
+
+    fn get_batch_results(batch_id: String) -> Result<Vec<BatchResult>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |anthropic_api_key| {
+            let client = MessagesApi::new(anthropic_api_key);
+            let entries = client.get_batch_results(&batch_id)?;
+            Ok(entries.into_iter().map(batch_entry_to_result).collect())
+        })
+    }
 }
 
-type DurableAnthropicComponent = DurableLLM<AnthropicComponent>;
+#[cfg(not(feature = "embedded"))]
+type DurableAnthropicComponent = golem_llm::durability::DurableLLM<AnthropicComponent>;
 
+#[cfg(not(feature = "embedded"))]
 golem_llm::export_llm!(DurableAnthropicComponent with_types_in golem_llm);
@@ -0,0 +1,203 @@
+use golem_llm::chat_stream::LlmChatStream;
+use golem_llm::config::{
+    provider_option, with_config_key_or, ACCESS_TOKEN_OPTION, API_KEY_OPTION, BASE_URL_OPTION,
+};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Error, ErrorCode, Guest, Message, ToolCall, ToolResult,
+};
+use golem_llm::LOGGING_STATE;
+use golem_llm_openai::client::{Credentials, ResponsesApi};
+use golem_llm_openai::conversions::{messages_to_input_items, tool_results_to_input_items};
+use golem_llm_openai::{OpenAIChatStream, OpenAIComponent};
+use golem_rust::wasm_rpc::Pollable;
+
+/// Provider option overriding the API version sent in the `api-version` query parameter
+const API_VERSION_OPTION: &str = "api_version";
+
+/// Azure OpenAI, through the Responses API of an Azure OpenAI resource.
+///
+/// The `model` of the request is the name of the deployment to route the request to. The
+/// requests, responses and stream events are the same as OpenAI's, so everything but the
+/// endpoint and the authentication is delegated to the OpenAI provider.
+pub struct AzureOpenAIComponent;
+
+impl AzureOpenAIComponent {
+    const ENDPOINT_ENV_VAR_NAME: &'static str = "AZURE_OPENAI_ENDPOINT";
+    const API_KEY_ENV_VAR_NAME: &'static str = "AZURE_OPENAI_API_KEY";
+    const AD_TOKEN_ENV_VAR_NAME: &'static str = "AZURE_OPENAI_AD_TOKEN";
+    const API_VERSION_ENV_VAR_NAME: &'static str = "AZURE_OPENAI_API_VERSION";
+    const DEFAULT_API_VERSION: &'static str = "2025-04-01-preview";
+
+    /// Creates a client for the configured resource. A Microsoft Entra ID token takes precedence
+    /// over an API key, and both can be passed with the request instead of the environment.
+    fn client(config: &Config) -> Result<ResponsesApi, Error> {
+        with_config_key_or(
+            provider_option(config, BASE_URL_OPTION),
+            Self::ENDPOINT_ENV_VAR_NAME,
+            Err,
+            |endpoint| {
+                let api_version = provider_option(config, API_VERSION_OPTION)
+                    .or_else(|| std::env::var(Self::API_VERSION_ENV_VAR_NAME).ok())
+                    .unwrap_or_else(|| Self::DEFAULT_API_VERSION.to_string());
+                let credentials = Self::credentials(config).ok_or_else(|| Error {
+                    code: ErrorCode::InternalError,
+                    message: format!(
+                        "Missing config key: {} or {}",
+                        Self::API_KEY_ENV_VAR_NAME,
+                        Self::AD_TOKEN_ENV_VAR_NAME
+                    ),
+                    provider_error_json: None,
+                })?;
+                Ok(ResponsesApi::with_url(
+                    "azure-openai",
+                    responses_url(&endpoint, &api_version),
+                    credentials,
+                ))
+            },
+        )
+    }
+
+    fn credentials(config: &Config) -> Option<Credentials> {
+        provider_option(config, ACCESS_TOKEN_OPTION)
+            .map(Credentials::Bearer)
+            .or_else(|| provider_option(config, API_KEY_OPTION).map(Credentials::ApiKey))
+            .or_else(|| {
+                std::env::var(Self::AD_TOKEN_ENV_VAR_NAME)
+                    .ok()
+                    .map(Credentials::Bearer)
+            })
+            .or_else(|| {
+                std::env::var(Self::API_KEY_ENV_VAR_NAME)
+                    .ok()
+                    .map(Credentials::ApiKey)
+            })
+    }
+}
+
+/// The URL of the Responses API of a resource, like `https://my-resource.openai.azure.com`
+fn responses_url(endpoint: &str, api_version: &str) -> String {
+    format!(
+        "{}/openai/responses?api-version={api_version}",
+        endpoint.trim_end_matches('/')
+    )
+}
+
+impl Guest for AzureOpenAIComponent {
+    type ChatStream = LlmChatStream<OpenAIChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_input_items(messages) {
+                Ok(items) => OpenAIComponent::request(client, items, config),
+                Err(err) => ChatEvent::Error(err),
+            },
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_input_items(messages) {
+                Ok(mut items) => {
+                    items.extend(tool_results_to_input_items(tool_results));
+                    OpenAIComponent::request(client, items, config)
+                }
+                Err(err) => ChatEvent::Error(err),
+            },
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for AzureOpenAIComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> Self::ChatStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        match Self::client(&config) {
+            Ok(client) => match messages_to_input_items(messages) {
+                Ok(items) => OpenAIComponent::streaming_request(client, items, config),
+                Err(err) => OpenAIChatStream::failed(err),
+            },
+            Err(err) => OpenAIChatStream::failed(err),
+        }
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableAzureOpenAIComponent = golem_llm::durability::DurableLLM<AzureOpenAIComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableAzureOpenAIComponent with_types_in golem_llm);
+
+#[cfg(test)]
+mod tests {
+    use crate::{responses_url, AzureOpenAIComponent};
+    use golem_llm::config::{ACCESS_TOKEN_OPTION, API_KEY_OPTION};
+    use golem_llm::golem::llm::llm::{Config, Kv};
+    use golem_llm_openai::client::Credentials;
+
+    fn config(options: &[(&str, &str)]) -> Config {
+        Config {
+            model: "gpt-4o-deployment".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: options
+                .iter()
+                .map(|(key, value)| Kv {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn builds_the_responses_url() {
+        assert_eq!(
+            responses_url(
+                "https://my-resource.openai.azure.com/",
+                "2025-04-01-preview"
+            ),
+            "https://my-resource.openai.azure.com/openai/responses?api-version=2025-04-01-preview"
+        );
+    }
+
+    #[test]
+    fn access_token_takes_precedence_over_api_key() {
+        let credentials = AzureOpenAIComponent::credentials(&config(&[
+            (API_KEY_OPTION, "key"),
+            (ACCESS_TOKEN_OPTION, "token"),
+        ]));
+        assert!(matches!(credentials, Some(Credentials::Bearer(token)) if token == "token"));
+
+        let credentials = AzureOpenAIComponent::credentials(&config(&[(API_KEY_OPTION, "key")]));
+        assert!(matches!(credentials, Some(Credentials::ApiKey(key)) if key == "key"));
+    }
+}
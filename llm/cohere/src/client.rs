@@ -0,0 +1,395 @@
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+
+const BASE_URL: &str = "https://api.cohere.com";
+
+/// The Cohere chat API client.
+///
+/// Based on https://docs.cohere.com/reference/chat
+pub struct ChatApi {
+    api_key: String,
+    client: Client,
+}
+
+impl ChatApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    pub fn chat(&self, request: ChatRequest) -> Result<ChatResponse, Error> {
+        trace!("Sending request to Cohere API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("cohere", "chat", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "cohere",
+                "chat",
+                Method::POST,
+                format!("{BASE_URL}/v2/chat"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn stream_chat(&self, request: ChatRequest) -> Result<EventSource, Error> {
+        trace!("Sending request to Cohere API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("cohere", "stream-chat", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "cohere",
+                "stream-chat",
+                Method::POST,
+                format!("{BASE_URL}/v2/chat"),
+            )
+            .bearer_auth(&self.api_key)?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
+
+        trace!("Initializing SSE stream");
+
+        EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
+            .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    /// Documents the model grounds its answer in, and cites in the response
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<Value>,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ToolChoice {
+    #[serde(rename = "REQUIRED")]
+    Required,
+    #[serde(rename = "NONE")]
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role")]
+pub enum ChatMessage {
+    #[serde(rename = "system")]
+    System { content: String },
+    #[serde(rename = "user")]
+    User { content: Vec<UserContent> },
+    #[serde(rename = "assistant")]
+    Assistant {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_calls: Option<Vec<ToolCall>>,
+    },
+    #[serde(rename = "tool")]
+    Tool {
+        tool_call_id: String,
+        content: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum UserContent {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(rename = "image_url")]
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Tool {
+    #[serde(rename = "function")]
+    Function { function: Function },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Function {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub parameters: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatResponse {
+    pub id: String,
+    pub finish_reason: FinishReason,
+    pub message: AssistantMessage,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssistantMessage {
+    #[serde(default)]
+    pub content: Vec<AssistantContent>,
+    #[serde(default)]
+    pub tool_plan: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default)]
+    pub citations: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AssistantContent {
+    #[serde(rename = "text")]
+    Text { text: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinishReason {
+    Complete,
+    StopSequence,
+    MaxTokens,
+    ToolCall,
+    Error,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    #[serde(default)]
+    pub billed_units: Option<Tokens>,
+    #[serde(default)]
+    pub tokens: Option<Tokens>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tokens {
+    #[serde(default)]
+    pub input_tokens: Option<f64>,
+    #[serde(default)]
+    pub output_tokens: Option<f64>,
+}
+
+/// An event of a chat stream. The deltas of all events have the same shape, holding the part of
+/// the message the event is about.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ChatStreamEvent {
+    #[serde(rename = "message-start")]
+    MessageStart { id: Option<String> },
+    #[serde(rename = "content-delta")]
+    ContentDelta { delta: Delta },
+    #[serde(rename = "tool-call-start")]
+    ToolCallStart { index: u32, delta: Delta },
+    #[serde(rename = "tool-call-delta")]
+    ToolCallDelta { index: u32, delta: Delta },
+    #[serde(rename = "tool-call-end")]
+    ToolCallEnd { index: u32 },
+    #[serde(rename = "citation-start")]
+    CitationStart { delta: Delta },
+    #[serde(rename = "message-end")]
+    MessageEnd { delta: MessageEndDelta },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Delta {
+    pub message: DeltaMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaMessage {
+    #[serde(default)]
+    pub content: Option<DeltaContent>,
+    #[serde(default)]
+    pub tool_calls: Option<DeltaToolCall>,
+    #[serde(default)]
+    pub citations: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaContent {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeltaToolCall {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<DeltaFunction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaFunction {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageEndDelta {
+    #[serde(default)]
+    pub finish_reason: Option<FinishReason>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
+
+        trace!("Received response from Cohere API: {:?}", Redacted(&body));
+
+        Ok(body)
+    } else {
+        trace!(
+            "Received {status} response from Cohere API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ChatResponse, ChatStreamEvent};
+    use golem_llm::error_contract::assert_error_mapping;
+    use serde_json::json;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| format!(r#"{{"id": "0b1c3a4e", "message": "{message}"}}"#),
+            |status, body| parse_response_body::<ChatResponse>(status, body).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn parses_stream_events() {
+        let event: ChatStreamEvent = serde_json::from_value(json!({
+            "type": "tool-call-start",
+            "index": 0,
+            "delta": {
+                "message": {
+                    "tool_calls": {
+                        "id": "get_weather_1byjy32y4hvq",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        assert!(matches!(
+            event,
+            ChatStreamEvent::ToolCallStart { index: 0, delta }
+                if delta.message.tool_calls.as_ref().and_then(|call| call.id.as_deref())
+                    == Some("get_weather_1byjy32y4hvq")
+        ));
+
+        let event: ChatStreamEvent =
+            serde_json::from_value(json!({ "type": "content-end", "index": 0 })).unwrap();
+        assert!(matches!(event, ChatStreamEvent::Other));
+    }
+}
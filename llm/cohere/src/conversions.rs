@@ -0,0 +1,372 @@
+use crate::client::{
+    AssistantContent, ChatMessage, ChatRequest, ChatResponse, Function, FunctionCall, ImageUrl,
+    Tool, ToolChoice, UserContent,
+};
+use base64::{engine::general_purpose, Engine as _};
+use golem_llm::error::unsupported;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, ImageDetail,
+    ImageReference, Message, ResponseMetadata, Role, ToolCall, ToolChoice as LlmToolChoice,
+    ToolDefinition, ToolResult, Usage,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Provider option holding the documents to ground the response in, as a JSON array of strings
+/// or of `{"id": ..., "data": {...}}` objects
+pub const DOCUMENTS_OPTION: &str = "documents";
+
+pub fn messages_to_request(messages: Vec<Message>, config: Config) -> Result<ChatRequest, Error> {
+    let options = config
+        .provider_options
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect::<HashMap<_, _>>();
+
+    let mut chat_messages = Vec::new();
+    for message in messages {
+        match message.role {
+            Role::User => chat_messages.push(ChatMessage::User {
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Assistant => chat_messages.push(ChatMessage::Assistant {
+                content: Some(text_of(message.content)),
+                tool_calls: None,
+            }),
+            Role::System => chat_messages.push(ChatMessage::System {
+                content: text_of(message.content),
+            }),
+            // Tool messages passed without `continue` have no call id, only the tool's name
+            Role::Tool => chat_messages.push(ChatMessage::Tool {
+                tool_call_id: message.name.unwrap_or_default(),
+                content: text_of(message.content),
+            }),
+        }
+    }
+
+    let mut tools = Vec::new();
+    for tool in config.tools {
+        tools.push(tool_definition_to_tool(tool)?)
+    }
+
+    // Cohere can only require some tool to be called, so a named tool is forced by only
+    // offering that one
+    let tool_choice = match config.tool_choice {
+        None | Some(LlmToolChoice::Auto) => None,
+        Some(LlmToolChoice::None) => Some(ToolChoice::None),
+        Some(LlmToolChoice::Required) => Some(ToolChoice::Required),
+        Some(LlmToolChoice::Specific(name)) => {
+            tools.retain(
+                |tool| matches!(tool, Tool::Function { function } if function.name == name),
+            );
+            if tools.is_empty() {
+                return Err(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!("Tool choice {name} is not one of the defined tools"),
+                    provider_error_json: None,
+                });
+            }
+            Some(ToolChoice::Required)
+        }
+    };
+
+    let documents = match options.get(DOCUMENTS_OPTION) {
+        Some(documents) => serde_json::from_str::<Vec<Value>>(documents).map_err(|err| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Failed to parse the {DOCUMENTS_OPTION} provider option: {err}"),
+            provider_error_json: None,
+        })?,
+        None => Vec::new(),
+    };
+
+    Ok(ChatRequest {
+        model: config.model,
+        messages: chat_messages,
+        tools,
+        documents,
+        stream: false,
+        max_tokens: config.max_tokens,
+        stop_sequences: config.stop_sequences,
+        temperature: config.temperature,
+        seed: options
+            .get("seed")
+            .and_then(|seed_s| seed_s.parse::<u32>().ok()),
+        frequency_penalty: options
+            .get("frequency_penalty")
+            .and_then(|fp_s| fp_s.parse::<f32>().ok()),
+        presence_penalty: options
+            .get("presence_penalty")
+            .and_then(|pp_s| pp_s.parse::<f32>().ok()),
+        k: options.get("top_k").and_then(|k_s| k_s.parse::<u32>().ok()),
+        p: options
+            .get("top_p")
+            .and_then(|top_p_s| top_p_s.parse::<f32>().ok()),
+        tool_choice,
+    })
+}
+
+pub fn process_response(response: ChatResponse) -> ChatEvent {
+    let content = response
+        .message
+        .content
+        .into_iter()
+        .filter_map(|content| match content {
+            AssistantContent::Text { text } => Some(ContentPart::Text(text)),
+            AssistantContent::Other => None,
+        })
+        .collect::<Vec<_>>();
+    let tool_calls = response
+        .message
+        .tool_calls
+        .unwrap_or_default()
+        .iter()
+        .map(convert_tool_call)
+        .collect::<Vec<_>>();
+
+    if content.is_empty() && !tool_calls.is_empty() {
+        ChatEvent::ToolRequest(tool_calls)
+    } else {
+        let metadata = ResponseMetadata {
+            finish_reason: Some(convert_finish_reason(response.finish_reason)),
+            usage: response.usage.as_ref().map(convert_usage),
+            provider_id: Some(response.id.clone()),
+            timestamp: None,
+            provider_metadata_json: citations_metadata(
+                response.message.citations.unwrap_or_default(),
+            ),
+            logprobs: None,
+            cost: None,
+        };
+
+        ChatEvent::Message(CompleteResponse {
+            id: response.id,
+            content,
+            tool_calls,
+            reasoning: None,
+            metadata,
+        })
+    }
+}
+
+/// The provider metadata holding the citations of the documents the response is grounded in
+pub fn citations_metadata(citations: Vec<Value>) -> Option<String> {
+    if citations.is_empty() {
+        None
+    } else {
+        Some(serde_json::json!({ "citations": citations }).to_string())
+    }
+}
+
+pub fn tool_results_to_messages(tool_results: Vec<(ToolCall, ToolResult)>) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+    for (tool_call, tool_result) in tool_results {
+        messages.push(ChatMessage::Assistant {
+            content: None,
+            tool_calls: Some(vec![crate::client::ToolCall {
+                id: tool_call.id.clone(),
+                kind: "function".to_string(),
+                function: FunctionCall {
+                    name: tool_call.name,
+                    arguments: tool_call.arguments_json,
+                },
+            }]),
+        });
+        let content = match tool_result {
+            ToolResult::Success(success) => success.result_json,
+            ToolResult::Error(failure) => failure.error_message,
+        };
+        messages.push(ChatMessage::Tool {
+            tool_call_id: tool_call.id,
+            content,
+        });
+    }
+    messages
+}
+
+pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
+    ToolCall {
+        id: tool_call.id.clone(),
+        name: tool_call.function.name.clone(),
+        arguments_json: tool_call.function.arguments.clone(),
+    }
+}
+
+pub fn convert_finish_reason(value: crate::client::FinishReason) -> FinishReason {
+    match value {
+        crate::client::FinishReason::Complete | crate::client::FinishReason::StopSequence => {
+            FinishReason::Stop
+        }
+        crate::client::FinishReason::MaxTokens => FinishReason::Length,
+        crate::client::FinishReason::ToolCall => FinishReason::ToolCalls,
+        crate::client::FinishReason::Error | crate::client::FinishReason::Timeout => {
+            FinishReason::Error
+        }
+    }
+}
+
+pub fn convert_usage(value: &crate::client::Usage) -> Usage {
+    let tokens = value.tokens.as_ref().or(value.billed_units.as_ref());
+    let input_tokens = tokens
+        .and_then(|tokens| tokens.input_tokens)
+        .map(|n| n as u32);
+    let output_tokens = tokens
+        .and_then(|tokens| tokens.output_tokens)
+        .map(|n| n as u32);
+    Usage {
+        input_tokens,
+        output_tokens,
+        total_tokens: input_tokens
+            .zip(output_tokens)
+            .map(|(input, output)| input + output),
+        cached_tokens: None,
+    }
+}
+
+/// System, assistant and tool messages only take text, so images and audio in them are left out
+fn text_of(contents: Vec<ContentPart>) -> String {
+    contents
+        .into_iter()
+        .filter_map(|content| match content {
+            ContentPart::Text(text) => Some(text),
+            ContentPart::CitedText(cited) => Some(cited.text),
+            ContentPart::Image(_) | ContentPart::Audio(_) | ContentPart::BuiltInToolResult(_) => {
+                None
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<Vec<UserContent>, Error> {
+    contents
+        .into_iter()
+        .filter_map(|content| match content {
+            ContentPart::Text(text) => Some(Ok(UserContent::Text { text })),
+            ContentPart::CitedText(cited) => Some(Ok(UserContent::Text { text: cited.text })),
+            ContentPart::Image(ImageReference::Url(image_url)) => Some(Ok(UserContent::ImageUrl {
+                image_url: ImageUrl {
+                    url: image_url.url,
+                    detail: image_url.detail.map(detail_name),
+                },
+            })),
+            ContentPart::Image(ImageReference::Inline(image_source)) => {
+                let base64_data = general_purpose::STANDARD.encode(&image_source.data);
+                Some(Ok(UserContent::ImageUrl {
+                    image_url: ImageUrl {
+                        url: format!("data:{};base64,{base64_data}", image_source.mime_type),
+                        detail: image_source.detail.map(detail_name),
+                    },
+                }))
+            }
+            ContentPart::Audio(_) => Some(Err(unsupported("audio with Cohere"))),
+            ContentPart::BuiltInToolResult(_) => None,
+        })
+        .collect()
+}
+
+fn detail_name(detail: ImageDetail) -> String {
+    match detail {
+        ImageDetail::Auto => "auto",
+        ImageDetail::Low => "low",
+        ImageDetail::High => "high",
+    }
+    .to_string()
+}
+
+fn tool_definition_to_tool(tool: ToolDefinition) -> Result<Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with Cohere"));
+    }
+    match serde_json::from_str(&tool.parameters_schema) {
+        Ok(value) => Ok(Tool::Function {
+            function: Function {
+                name: tool.name,
+                description: tool.description,
+                parameters: value,
+            },
+        }),
+        Err(error) => Err(Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to parse tool parameters for {}: {error}", tool.name),
+            provider_error_json: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{Tool, ToolChoice};
+    use crate::conversions::{messages_to_request, DOCUMENTS_OPTION};
+    use golem_llm::golem::llm::llm::{Config, Kv, ToolChoice as LlmToolChoice, ToolDefinition};
+
+    fn tool(name: &str) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: None,
+            parameters_schema: r#"{"type": "object"}"#.to_string(),
+            built_in: None,
+        }
+    }
+
+    fn config(tool_choice: LlmToolChoice, provider_options: Vec<Kv>) -> Config {
+        Config {
+            model: "command-r-plus".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![tool("get_weather"), tool("get_time")],
+            tool_choice: Some(tool_choice),
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options,
+        }
+    }
+
+    #[test]
+    fn forces_a_named_tool_by_only_offering_it() {
+        let request = messages_to_request(
+            vec![],
+            config(LlmToolChoice::Specific("get_time".to_string()), vec![]),
+        )
+        .unwrap();
+        assert!(matches!(request.tool_choice, Some(ToolChoice::Required)));
+        assert!(matches!(
+            request.tools.as_slice(),
+            [Tool::Function { function }] if function.name == "get_time"
+        ));
+    }
+
+    #[test]
+    fn passes_documents() {
+        let request = messages_to_request(
+            vec![],
+            config(
+                LlmToolChoice::Auto,
+                vec![Kv {
+                    key: DOCUMENTS_OPTION.to_string(),
+                    value: r#"[{"id": "doc-1", "data": {"text": "Paris is sunny"}}]"#.to_string(),
+                }],
+            ),
+        )
+        .unwrap();
+        assert_eq!(request.documents.len(), 1);
+        assert!(request.tool_choice.is_none());
+
+        let invalid = messages_to_request(
+            vec![],
+            config(
+                LlmToolChoice::Auto,
+                vec![Kv {
+                    key: DOCUMENTS_OPTION.to_string(),
+                    value: "not json".to_string(),
+                }],
+            ),
+        );
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,288 @@
+mod client;
+mod conversions;
+
+use crate::client::{ChatApi, ChatRequest, ChatStreamEvent};
+use crate::conversions::{
+    citations_metadata, convert_finish_reason, convert_usage, messages_to_request,
+    process_response, tool_results_to_messages,
+};
+use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, ContentPart, Error, Guest, Message, ResponseMetadata,
+    StreamDelta, StreamEvent, ToolCall, ToolResult,
+};
+use golem_llm::redaction::Redacted;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use serde_json::Value;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+
+/// A tool call being streamed, completed by its `tool-call-end` event
+#[derive(Default)]
+struct ToolCallFragment {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+pub struct CohereChatStream {
+    stream: RefCell<Option<EventSource>>,
+    failure: Option<Error>,
+    finished: RefCell<bool>,
+    id: RefCell<Option<String>>,
+    tool_calls: RefCell<HashMap<u32, ToolCallFragment>>,
+    citations: RefCell<Vec<Value>>,
+}
+
+impl CohereChatStream {
+    pub fn new(stream: EventSource) -> LlmChatStream<Self> {
+        LlmChatStream::new(CohereChatStream {
+            stream: RefCell::new(Some(stream)),
+            failure: None,
+            finished: RefCell::new(false),
+            id: RefCell::new(None),
+            tool_calls: RefCell::new(HashMap::new()),
+            citations: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn failed(error: Error) -> LlmChatStream<Self> {
+        LlmChatStream::new(CohereChatStream {
+            stream: RefCell::new(None),
+            failure: Some(error),
+            finished: RefCell::new(false),
+            id: RefCell::new(None),
+            tool_calls: RefCell::new(HashMap::new()),
+            citations: RefCell::new(Vec::new()),
+        })
+    }
+}
+
+impl LlmChatStreamState for CohereChatStream {
+    fn failure(&self) -> &Option<Error> {
+        &self.failure
+    }
+
+    fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    fn set_finished(&self) {
+        *self.finished.borrow_mut() = true;
+    }
+
+    fn stream(&self) -> Ref<Option<EventSource>> {
+        self.stream.borrow()
+    }
+
+    fn stream_mut(&self) -> RefMut<Option<EventSource>> {
+        self.stream.borrow_mut()
+    }
+
+    fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
+        trace!("Received raw stream event: {}", Redacted(&raw));
+        let event: ChatStreamEvent = serde_json::from_str(raw)
+            .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+
+        match event {
+            ChatStreamEvent::MessageStart { id } => {
+                *self.id.borrow_mut() = id;
+                Ok(None)
+            }
+            ChatStreamEvent::ContentDelta { delta } => Ok(delta
+                .message
+                .content
+                .and_then(|content| content.text)
+                .map(|text| {
+                    StreamEvent::Delta(StreamDelta {
+                        content: Some(vec![ContentPart::Text(text)]),
+                        tool_calls: None,
+                        reasoning: None,
+                        logprobs: None,
+                    })
+                })),
+            ChatStreamEvent::ToolCallStart { index, delta } => {
+                let tool_call = delta.message.tool_calls.unwrap_or_default();
+                let function = tool_call.function;
+                self.tool_calls.borrow_mut().insert(
+                    index,
+                    ToolCallFragment {
+                        id: tool_call.id.unwrap_or_default(),
+                        name: function
+                            .as_ref()
+                            .and_then(|function| function.name.clone())
+                            .unwrap_or_default(),
+                        arguments: function
+                            .and_then(|function| function.arguments)
+                            .unwrap_or_default(),
+                    },
+                );
+                Ok(None)
+            }
+            ChatStreamEvent::ToolCallDelta { index, delta } => {
+                if let Some(arguments) = delta
+                    .message
+                    .tool_calls
+                    .and_then(|tool_call| tool_call.function)
+                    .and_then(|function| function.arguments)
+                {
+                    let mut tool_calls = self.tool_calls.borrow_mut();
+                    tool_calls
+                        .entry(index)
+                        .or_default()
+                        .arguments
+                        .push_str(&arguments);
+                }
+                Ok(None)
+            }
+            ChatStreamEvent::ToolCallEnd { index } => Ok(self
+                .tool_calls
+                .borrow_mut()
+                .remove(&index)
+                .map(|tool_call| {
+                    StreamEvent::Delta(StreamDelta {
+                        content: None,
+                        tool_calls: Some(vec![ToolCall {
+                            id: tool_call.id,
+                            name: tool_call.name,
+                            arguments_json: tool_call.arguments,
+                        }]),
+                        reasoning: None,
+                        logprobs: None,
+                    })
+                })),
+            ChatStreamEvent::CitationStart { delta } => {
+                if let Some(citation) = delta.message.citations {
+                    self.citations.borrow_mut().push(citation);
+                }
+                Ok(None)
+            }
+            ChatStreamEvent::MessageEnd { delta } => {
+                Ok(Some(StreamEvent::Finish(ResponseMetadata {
+                    finish_reason: delta.finish_reason.map(convert_finish_reason),
+                    usage: delta.usage.as_ref().map(convert_usage),
+                    provider_id: self.id.borrow().clone(),
+                    timestamp: None,
+                    provider_metadata_json: citations_metadata(
+                        self.citations.borrow_mut().drain(..).collect(),
+                    ),
+                    logprobs: None,
+                    cost: None,
+                })))
+            }
+            ChatStreamEvent::Other => Ok(None),
+        }
+    }
+}
+
+pub struct CohereComponent;
+
+impl CohereComponent {
+    const ENV_VAR_NAME: &'static str = "COHERE_API_KEY";
+
+    fn request(client: ChatApi, request: ChatRequest) -> ChatEvent {
+        match client.chat(request) {
+            Ok(response) => process_response(response),
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn streaming_request(
+        client: ChatApi,
+        mut request: ChatRequest,
+    ) -> LlmChatStream<CohereChatStream> {
+        request.stream = true;
+        match client.stream_chat(request) {
+            Ok(stream) => CohereChatStream::new(stream),
+            Err(err) => CohereChatStream::failed(err),
+        }
+    }
+}
+
+impl Guest for CohereComponent {
+    type ChatStream = LlmChatStream<CohereChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |cohere_api_key| {
+                let client = ChatApi::new(cohere_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |cohere_api_key| {
+                let client = ChatApi::new(cohere_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for CohereComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> LlmChatStream<CohereChatStream> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            CohereChatStream::failed,
+            |cohere_api_key| {
+                let client = ChatApi::new(cohere_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::streaming_request(client, request),
+                    Err(err) => CohereChatStream::failed(err),
+                }
+            },
+        )
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableCohereComponent = golem_llm::durability::DurableLLM<CohereComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableCohereComponent with_types_in golem_llm);
@@ -0,0 +1,417 @@
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+const BASE_URL: &str = "https://api.deepseek.com";
+
+/// The DeepSeek chat completions API client, compatible with OpenAI's.
+///
+/// Based on https://api-docs.deepseek.com/api/create-chat-completion
+pub struct CompletionsApi {
+    api_key: String,
+    client: Client,
+}
+
+impl CompletionsApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    pub fn send_messages(&self, request: CompletionsRequest) -> Result<CompletionsResponse, Error> {
+        trace!("Sending request to DeepSeek API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("deepseek", "send-messages", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "deepseek",
+                "send-messages",
+                Method::POST,
+                format!("{BASE_URL}/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn stream_send_messages(&self, request: CompletionsRequest) -> Result<EventSource, Error> {
+        trace!("Sending request to DeepSeek API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("deepseek", "stream-send-messages", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "deepseek",
+                "stream-send-messages",
+                Method::POST,
+                format!("{BASE_URL}/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
+
+        trace!("Initializing SSE stream");
+
+        EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
+            .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionsRequest {
+    pub messages: Vec<Message>,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamOptions {
+    pub include_usage: bool,
+}
+
+/// Either one of the `auto`, `none` and `required` modes, or a specific function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(String),
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        function: FunctionName,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionName {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Tool {
+    #[serde(rename = "function")]
+    Function { function: Function },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Function {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role")]
+pub enum Message {
+    #[serde(rename = "system")]
+    System {
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    #[serde(rename = "user")]
+    User {
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    #[serde(rename = "assistant")]
+    Assistant {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        content: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_calls: Option<Vec<ToolCall>>,
+    },
+    #[serde(rename = "tool")]
+    Tool {
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_call_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ToolCall {
+    #[serde(rename = "function")]
+    Function {
+        function: FunctionCall,
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub arguments: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionsResponse {
+    pub choices: Vec<Choice>,
+    pub created: u64,
+    pub id: String,
+    pub model: String,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Choice {
+    pub finish_reason: Option<FinishReason>,
+    pub index: u32,
+    pub message: ResponseMessage,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprobs {
+    #[serde(default)]
+    pub content: Option<Vec<Logprob>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprob {
+    pub token: String,
+    pub logprob: f32,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FinishReason {
+    #[serde(rename = "stop")]
+    Stop,
+    #[serde(rename = "length")]
+    Length,
+    #[serde(rename = "content_filter")]
+    ContentFilter,
+    #[serde(rename = "tool_calls")]
+    ToolCalls,
+    /// The request was interrupted because of insufficient resources on DeepSeek's side
+    #[serde(rename = "insufficient_system_resource")]
+    InsufficientSystemResource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseMessage {
+    #[serde(default)]
+    pub content: Option<String>,
+    /// The chain of thought of `deepseek-reasoner`, which must not be sent back in later requests
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    pub completion_tokens: u32,
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+    /// Prompt tokens served from DeepSeek's context cache
+    #[serde(default)]
+    pub prompt_cache_hit_tokens: Option<u32>,
+    #[serde(default)]
+    pub prompt_cache_miss_tokens: Option<u32>,
+    #[serde(default)]
+    pub completion_tokens_details: Option<CompletionTokenDetails>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionTokenDetails {
+    #[serde(default)]
+    pub reasoning_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<ChoiceChunk>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceChunk {
+    pub index: u32,
+    pub delta: ChoiceDelta,
+    pub finish_reason: Option<FinishReason>,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+}
+
+/// A fragment of a streamed tool call. The first fragment of each call has its id and name, the
+/// following ones continue its arguments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: u32,
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<FunctionCallDelta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
+
+        trace!("Received response from DeepSeek API: {:?}", Redacted(&body));
+
+        Ok(body)
+    } else {
+        trace!(
+            "Received {status} response from DeepSeek API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ChatCompletionChunk, CompletionsResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+    use serde_json::json;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| {
+                format!(
+                    r#"{{"error": {{"message": "{message}", "type": "invalid_request_error", "param": null, "code": "invalid_request_error"}}}}"#
+                )
+            },
+            |status, body| parse_response_body::<CompletionsResponse>(status, body).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn parses_reasoning_deltas() {
+        let chunk: ChatCompletionChunk = serde_json::from_value(json!({
+            "id": "5f3a",
+            "object": "chat.completion.chunk",
+            "created": 1738000000,
+            "model": "deepseek-reasoner",
+            "system_fingerprint": "fp_7e73fd9a08",
+            "choices": [{
+                "index": 0,
+                "delta": { "content": null, "reasoning_content": "Let me think" },
+                "logprobs": null,
+                "finish_reason": null
+            }]
+        }))
+        .unwrap();
+        assert_eq!(
+            chunk.choices[0].delta.reasoning_content.as_deref(),
+            Some("Let me think")
+        );
+        assert!(chunk.choices[0].delta.content.is_none());
+    }
+}
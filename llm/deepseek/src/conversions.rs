@@ -0,0 +1,316 @@
+use crate::client::{CompletionsRequest, CompletionsResponse, FunctionName, Logprobs, ToolChoice};
+use golem_llm::error::unsupported;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, Message,
+    ResponseMetadata, Role, TokenLogprob, ToolCall, ToolChoice as LlmToolChoice, ToolDefinition,
+    ToolResult, TopLogprob, Usage,
+};
+use std::collections::HashMap;
+
+pub fn messages_to_request(
+    messages: Vec<Message>,
+    config: Config,
+) -> Result<CompletionsRequest, Error> {
+    let options = config
+        .provider_options
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect::<HashMap<_, _>>();
+
+    let mut completion_messages = Vec::new();
+    for message in messages {
+        match message.role {
+            Role::User => completion_messages.push(crate::client::Message::User {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Assistant => completion_messages.push(crate::client::Message::Assistant {
+                name: message.name,
+                content: Some(convert_content_parts(message.content)?),
+                tool_calls: None,
+            }),
+            Role::System => completion_messages.push(crate::client::Message::System {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Tool => completion_messages.push(crate::client::Message::Tool {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+                tool_call_id: None,
+            }),
+        }
+    }
+
+    let mut tools = Vec::new();
+    for tool in config.tools {
+        tools.push(tool_definition_to_tool(tool)?)
+    }
+
+    Ok(CompletionsRequest {
+        messages: completion_messages,
+        model: config.model,
+        frequency_penalty: options
+            .get("frequency_penalty")
+            .and_then(|fp_s| fp_s.parse::<f32>().ok()),
+        logprobs: config.logprobs.map(|_| true),
+        max_tokens: config.max_tokens,
+        presence_penalty: options
+            .get("presence_penalty")
+            .and_then(|pp_s| pp_s.parse::<f32>().ok()),
+        stop: config.stop_sequences,
+        stream: Some(false),
+        stream_options: None,
+        temperature: config.temperature,
+        tool_choice: config.tool_choice.map(convert_tool_choice),
+        tools,
+        top_logprobs: config.logprobs,
+        top_p: options
+            .get("top_p")
+            .and_then(|top_p_s| top_p_s.parse::<f32>().ok()),
+    })
+}
+
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Mode("auto".to_string()),
+        LlmToolChoice::None => ToolChoice::Mode("none".to_string()),
+        LlmToolChoice::Required => ToolChoice::Mode("required".to_string()),
+        LlmToolChoice::Specific(name) => ToolChoice::Function {
+            kind: "function".to_string(),
+            function: FunctionName { name },
+        },
+    }
+}
+
+pub fn convert_logprobs(logprobs: &Logprobs) -> Option<Vec<TokenLogprob>> {
+    logprobs.content.as_ref().map(|content| {
+        content
+            .iter()
+            .map(|logprob| TokenLogprob {
+                token: logprob.token.clone(),
+                logprob: logprob.logprob,
+                top_logprobs: logprob
+                    .top_logprobs
+                    .iter()
+                    .map(|top| TopLogprob {
+                        token: top.token.clone(),
+                        logprob: top.logprob,
+                    })
+                    .collect(),
+            })
+            .collect()
+    })
+}
+
+pub fn process_response(response: CompletionsResponse) -> ChatEvent {
+    let choice = response.choices.first();
+    if let Some(choice) = choice {
+        let mut contents = Vec::new();
+        let mut tool_calls = Vec::new();
+
+        if let Some(content) = &choice.message.content {
+            contents.push(ContentPart::Text(content.clone()));
+        }
+
+        let empty = Vec::new();
+        for tool_call in choice.message.tool_calls.as_ref().unwrap_or(&empty) {
+            tool_calls.push(convert_tool_call(tool_call));
+        }
+
+        if contents.is_empty() && !tool_calls.is_empty() {
+            ChatEvent::ToolRequest(tool_calls)
+        } else {
+            let metadata = ResponseMetadata {
+                finish_reason: choice.finish_reason.as_ref().map(convert_finish_reason),
+                usage: response.usage.as_ref().map(convert_usage),
+                provider_id: Some(response.model.clone()),
+                timestamp: Some(response.created.to_string()),
+                provider_metadata_json: provider_metadata(
+                    choice.message.reasoning_content.as_deref(),
+                    response.usage.as_ref(),
+                ),
+                logprobs: choice.logprobs.as_ref().and_then(convert_logprobs),
+                cost: None,
+            };
+
+            ChatEvent::Message(CompleteResponse {
+                id: response.id,
+                content: contents,
+                tool_calls,
+                reasoning: choice
+                    .message
+                    .reasoning_content
+                    .clone()
+                    .filter(|text| !text.is_empty()),
+                metadata,
+            })
+        }
+    } else {
+        ChatEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "No choices in response".to_string(),
+            provider_error_json: None,
+        })
+    }
+}
+
+pub fn tool_results_to_messages(
+    tool_results: Vec<(ToolCall, ToolResult)>,
+) -> Vec<crate::client::Message> {
+    let mut messages = Vec::new();
+    for (tool_call, tool_result) in tool_results {
+        messages.push(crate::client::Message::Assistant {
+            content: None,
+            name: None,
+            tool_calls: Some(vec![crate::client::ToolCall::Function {
+                function: crate::client::FunctionCall {
+                    arguments: tool_call.arguments_json,
+                    name: tool_call.name,
+                },
+                id: tool_call.id.clone(),
+                index: None,
+            }]),
+        });
+        let content = match tool_result {
+            ToolResult::Success(success) => success.result_json,
+            ToolResult::Error(failure) => failure.error_message,
+        };
+        messages.push(crate::client::Message::Tool {
+            name: None,
+            content,
+            tool_call_id: Some(tool_call.id),
+        });
+    }
+    messages
+}
+
+pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
+    match tool_call {
+        crate::client::ToolCall::Function { function, id, .. } => ToolCall {
+            id: id.clone(),
+            name: function.name.clone(),
+            arguments_json: function.arguments.clone(),
+        },
+    }
+}
+
+/// DeepSeek's chat models only take text
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<String, Error> {
+    let mut result = Vec::new();
+    for content in contents {
+        match content {
+            ContentPart::Text(text) => result.push(text),
+            ContentPart::CitedText(cited) => result.push(cited.text),
+            ContentPart::BuiltInToolResult(_) => {}
+            ContentPart::Image(_) => return Err(unsupported("images with DeepSeek")),
+            ContentPart::Audio(_) => return Err(unsupported("audio with DeepSeek")),
+        }
+    }
+    Ok(result.join("\n"))
+}
+
+pub fn convert_finish_reason(value: &crate::client::FinishReason) -> FinishReason {
+    match value {
+        crate::client::FinishReason::Stop => FinishReason::Stop,
+        crate::client::FinishReason::Length => FinishReason::Length,
+        crate::client::FinishReason::ContentFilter => FinishReason::ContentFilter,
+        crate::client::FinishReason::ToolCalls => FinishReason::ToolCalls,
+        crate::client::FinishReason::InsufficientSystemResource => FinishReason::Error,
+    }
+}
+
+pub fn convert_usage(value: &crate::client::Usage) -> Usage {
+    Usage {
+        input_tokens: Some(value.prompt_tokens),
+        output_tokens: Some(value.completion_tokens),
+        total_tokens: Some(value.total_tokens),
+        cached_tokens: value.prompt_cache_hit_tokens,
+    }
+}
+
+/// The provider metadata of a response: the chain of thought of `deepseek-reasoner`, and the
+/// context cache and reasoning token counts of the usage
+pub fn provider_metadata(
+    reasoning_content: Option<&str>,
+    usage: Option<&crate::client::Usage>,
+) -> Option<String> {
+    let mut metadata = serde_json::Map::new();
+    if let Some(reasoning_content) = reasoning_content.filter(|content| !content.is_empty()) {
+        metadata.insert("reasoning_content".to_string(), reasoning_content.into());
+    }
+    if let Some(usage) = usage {
+        let reasoning_tokens = usage
+            .completion_tokens_details
+            .as_ref()
+            .and_then(|details| details.reasoning_tokens);
+        for (name, count) in [
+            ("prompt_cache_hit_tokens", usage.prompt_cache_hit_tokens),
+            ("prompt_cache_miss_tokens", usage.prompt_cache_miss_tokens),
+            ("reasoning_tokens", reasoning_tokens),
+        ] {
+            if let Some(count) = count {
+                metadata.insert(name.to_string(), count.into());
+            }
+        }
+    }
+
+    if metadata.is_empty() {
+        None
+    } else {
+        Some(serde_json::Value::Object(metadata).to_string())
+    }
+}
+
+fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with DeepSeek"));
+    }
+    match serde_json::from_str(&tool.parameters_schema) {
+        Ok(value) => Ok(crate::client::Tool::Function {
+            function: crate::client::Function {
+                name: tool.name,
+                description: tool.description,
+                parameters: Some(value),
+            },
+        }),
+        Err(error) => Err(Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to parse tool parameters for {}: {error}", tool.name),
+            provider_error_json: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{CompletionTokenDetails, Usage};
+    use crate::conversions::provider_metadata;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn reports_reasoning_as_metadata() {
+        let usage = Usage {
+            completion_tokens: 40,
+            prompt_tokens: 10,
+            total_tokens: 50,
+            prompt_cache_hit_tokens: Some(0),
+            prompt_cache_miss_tokens: Some(10),
+            completion_tokens_details: Some(CompletionTokenDetails {
+                reasoning_tokens: Some(32),
+            }),
+        };
+        let metadata: Value =
+            serde_json::from_str(&provider_metadata(Some("Let me think"), Some(&usage)).unwrap())
+                .unwrap();
+        assert_eq!(
+            metadata,
+            json!({
+                "reasoning_content": "Let me think",
+                "prompt_cache_hit_tokens": 0,
+                "prompt_cache_miss_tokens": 10,
+                "reasoning_tokens": 32
+            })
+        );
+        assert_eq!(provider_metadata(Some(""), None), None);
+    }
+}
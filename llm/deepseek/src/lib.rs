@@ -0,0 +1,305 @@
+mod client;
+mod conversions;
+
+use crate::client::{ChatCompletionChunk, CompletionsApi, CompletionsRequest, StreamOptions};
+use crate::conversions::{
+    convert_finish_reason, convert_logprobs, convert_usage, messages_to_request, process_response,
+    provider_metadata, tool_results_to_messages,
+};
+use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, ContentPart, Error, FinishReason, Guest, Message,
+    ResponseMetadata, StreamDelta, StreamEvent, ToolCall, ToolResult,
+};
+use golem_llm::redaction::Redacted;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::BTreeMap;
+
+/// A tool call being streamed, completed when the choice finishes
+#[derive(Default)]
+struct ToolCallFragment {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+pub struct DeepSeekChatStream {
+    stream: RefCell<Option<EventSource>>,
+    failure: Option<Error>,
+    finished: RefCell<bool>,
+    finish_reason: RefCell<Option<FinishReason>>,
+    reasoning: RefCell<String>,
+    tool_calls: RefCell<BTreeMap<u32, ToolCallFragment>>,
+    pending: RefCell<Option<StreamEvent>>,
+}
+
+impl DeepSeekChatStream {
+    pub fn new(stream: EventSource) -> LlmChatStream<Self> {
+        LlmChatStream::new(DeepSeekChatStream {
+            stream: RefCell::new(Some(stream)),
+            failure: None,
+            finished: RefCell::new(false),
+            finish_reason: RefCell::new(None),
+            reasoning: RefCell::new(String::new()),
+            tool_calls: RefCell::new(BTreeMap::new()),
+            pending: RefCell::new(None),
+        })
+    }
+
+    pub fn failed(error: Error) -> LlmChatStream<Self> {
+        LlmChatStream::new(DeepSeekChatStream {
+            stream: RefCell::new(None),
+            failure: Some(error),
+            finished: RefCell::new(false),
+            finish_reason: RefCell::new(None),
+            reasoning: RefCell::new(String::new()),
+            tool_calls: RefCell::new(BTreeMap::new()),
+            pending: RefCell::new(None),
+        })
+    }
+
+    /// The tool calls accumulated so far, in the order of their indices
+    fn take_tool_calls(&self) -> Vec<ToolCall> {
+        std::mem::take(&mut *self.tool_calls.borrow_mut())
+            .into_values()
+            .map(|tool_call| ToolCall {
+                id: tool_call.id,
+                name: tool_call.name,
+                arguments_json: tool_call.arguments,
+            })
+            .collect()
+    }
+}
+
+impl LlmChatStreamState for DeepSeekChatStream {
+    fn failure(&self) -> &Option<Error> {
+        &self.failure
+    }
+
+    fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    fn set_finished(&self) {
+        *self.finished.borrow_mut() = true;
+    }
+
+    fn stream(&self) -> Ref<Option<EventSource>> {
+        self.stream.borrow()
+    }
+
+    fn stream_mut(&self) -> RefMut<Option<EventSource>> {
+        self.stream.borrow_mut()
+    }
+
+    fn take_pending_event(&self) -> Option<StreamEvent> {
+        self.pending.borrow_mut().take()
+    }
+
+    fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
+        trace!("Received raw stream event: {}", Redacted(&raw));
+        let json: serde_json::Value = serde_json::from_str(raw)
+            .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+
+        let typ = json
+            .as_object()
+            .and_then(|obj| obj.get("object"))
+            .and_then(|v| v.as_str());
+        match typ {
+            Some("chat.completion.chunk") => {
+                let message: ChatCompletionChunk = serde_json::from_value(json)
+                    .map_err(|err| format!("Failed to parse stream event: {err}"))?;
+
+                let mut content = None;
+                let mut tool_calls = None;
+                let mut reasoning = None;
+                let mut logprobs = None;
+                if let Some(choice) = message.choices.into_iter().next() {
+                    logprobs = choice.logprobs.as_ref().and_then(convert_logprobs);
+                    reasoning = choice
+                        .delta
+                        .reasoning_content
+                        .filter(|text| !text.is_empty());
+                    if let Some(reasoning) = &reasoning {
+                        self.reasoning.borrow_mut().push_str(reasoning);
+                    }
+                    for delta in choice.delta.tool_calls.unwrap_or_default() {
+                        let mut fragments = self.tool_calls.borrow_mut();
+                        let fragment = fragments.entry(delta.index).or_default();
+                        if let Some(id) = delta.id {
+                            fragment.id = id;
+                        }
+                        if let Some(function) = delta.function {
+                            if let Some(name) = function.name {
+                                fragment.name = name;
+                            }
+                            if let Some(arguments) = function.arguments {
+                                fragment.arguments.push_str(&arguments);
+                            }
+                        }
+                    }
+                    content = choice
+                        .delta
+                        .content
+                        .filter(|text| !text.is_empty())
+                        .map(|text| vec![ContentPart::Text(text)]);
+                    if let Some(finish_reason) = choice.finish_reason {
+                        *self.finish_reason.borrow_mut() =
+                            Some(convert_finish_reason(&finish_reason));
+                        let finished_calls = self.take_tool_calls();
+                        if !finished_calls.is_empty() {
+                            tool_calls = Some(finished_calls);
+                        }
+                    }
+                }
+
+                let finish = message.usage.as_ref().map(|usage| {
+                    StreamEvent::Finish(ResponseMetadata {
+                        finish_reason: *self.finish_reason.borrow(),
+                        usage: Some(convert_usage(usage)),
+                        provider_id: Some(message.model.clone()),
+                        timestamp: Some(message.created.to_string()),
+                        provider_metadata_json: provider_metadata(
+                            Some(self.reasoning.borrow().as_str()),
+                            Some(usage),
+                        ),
+                        logprobs: None,
+                        cost: None,
+                    })
+                });
+                if content.is_some() || tool_calls.is_some() || reasoning.is_some() {
+                    *self.pending.borrow_mut() = finish;
+                    Ok(Some(StreamEvent::Delta(StreamDelta {
+                        content,
+                        tool_calls,
+                        reasoning,
+                        logprobs,
+                    })))
+                } else {
+                    Ok(finish)
+                }
+            }
+            Some(_) => Ok(None),
+            None => Err("Unexpected stream event format, does not have 'object' field".to_string()),
+        }
+    }
+}
+
+pub struct DeepSeekComponent;
+
+impl DeepSeekComponent {
+    const ENV_VAR_NAME: &'static str = "DEEPSEEK_API_KEY";
+
+    fn request(client: CompletionsApi, request: CompletionsRequest) -> ChatEvent {
+        match client.send_messages(request) {
+            Ok(response) => process_response(response),
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn streaming_request(
+        client: CompletionsApi,
+        mut request: CompletionsRequest,
+    ) -> LlmChatStream<DeepSeekChatStream> {
+        request.stream = Some(true);
+        request.stream_options = Some(StreamOptions {
+            include_usage: true,
+        });
+        match client.stream_send_messages(request) {
+            Ok(stream) => DeepSeekChatStream::new(stream),
+            Err(err) => DeepSeekChatStream::failed(err),
+        }
+    }
+}
+
+impl Guest for DeepSeekComponent {
+    type ChatStream = LlmChatStream<DeepSeekChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |deepseek_api_key| {
+                let client = CompletionsApi::new(deepseek_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |deepseek_api_key| {
+                let client = CompletionsApi::new(deepseek_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for DeepSeekComponent {
+    fn unwrapped_stream(
+        messages: Vec<Message>,
+        config: Config,
+    ) -> LlmChatStream<DeepSeekChatStream> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            DeepSeekChatStream::failed,
+            |deepseek_api_key| {
+                let client = CompletionsApi::new(deepseek_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::streaming_request(client, request),
+                    Err(err) => DeepSeekChatStream::failed(err),
+                }
+            },
+        )
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableDeepSeekComponent = golem_llm::durability::DurableLLM<DeepSeekComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableDeepSeekComponent with_types_in golem_llm);
@@ -1,9 +1,13 @@
-use golem_llm::error::{error_code_from_status, from_event_source_error, from_reqwest_error};
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
 use golem_llm::event_source::EventSource;
-use golem_llm::golem::llm::llm::Error;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
 use log::trace;
-use reqwest::header::HeaderValue;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -26,37 +30,51 @@ impl CompletionsApi {
     }
 
     pub fn send_messages(&self, request: CompletionsRequest) -> Result<CompletionsResponse, Error> {
-        trace!("Sending request to xAI API: {request:?}");
+        trace!("Sending request to xAI API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("grok", "send-messages", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
 
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/chat/completions"))
-            .bearer_auth(self.api_key.clone())
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "grok",
+                "send-messages",
+                Method::POST,
+                format!("{BASE_URL}/v1/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
 
-        parse_response(response)
+        parse_response(response, recorder)
     }
 
     pub fn stream_send_messages(&self, request: CompletionsRequest) -> Result<EventSource, Error> {
-        trace!("Sending request to xAI API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/chat/completions"))
-            .bearer_auth(self.api_key.clone())
-            .header(
-                reqwest::header::ACCEPT,
-                HeaderValue::from_static("text/event-stream"),
+        trace!("Sending request to xAI API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("grok", "stream-send-messages", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "grok",
+                "stream-send-messages",
+                Method::POST,
+                format!("{BASE_URL}/v1/chat/completions"),
             )
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+            .bearer_auth(&self.api_key)?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
 
         trace!("Initializing SSE stream");
 
         EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
             .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
     }
 }
@@ -68,10 +86,14 @@ pub struct CompletionsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_completion_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub n: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_effort: Option<Effort>,
@@ -86,11 +108,11 @@ pub struct CompletionsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_choice: Option<String>,
+    pub tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<Tool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub top_logprobs: Option<u8>,
+    pub top_logprobs: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -102,6 +124,23 @@ pub struct StreamOptions {
     pub include_usage: bool,
 }
 
+/// Either one of the `auto`, `none` and `required` modes, or a specific function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(String),
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        function: FunctionName,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionName {
+    pub name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Tool {
@@ -239,6 +278,28 @@ pub struct Choice {
     pub finish_reason: Option<FinishReason>,
     pub index: u32,
     pub message: ResponseMessage,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprobs {
+    #[serde(default)]
+    pub content: Option<Vec<Logprob>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprob {
+    pub token: String,
+    pub logprob: f32,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -301,36 +362,74 @@ pub struct ChoiceChunk {
     pub index: u32,
     pub delta: ChoiceDelta,
     pub finish_reason: Option<FinishReason>,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChoiceDelta {
     pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
     pub tool_calls: Option<Vec<ToolCall>>,
     pub role: String,
 }
 
-fn parse_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Error> {
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
     let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
     if status.is_success() {
-        let body = response
-            .json::<T>()
-            .map_err(|err| from_reqwest_error("Failed to decode response body", err))?;
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
 
-        trace!("Received response from xAI API: {body:?}");
+        trace!("Received response from xAI API: {:?}", Redacted(&body));
 
         Ok(body)
     } else {
-        let error_body = response
-            .text()
-            .map_err(|err| from_reqwest_error("Failed to receive error response body", err))?;
-
-        trace!("Received {status} response from xAI API: {error_body:?}");
+        trace!(
+            "Received {status} response from xAI API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
 
-        Err(Error {
-            code: error_code_from_status(status),
-            message: format!("Request failed with {status}"),
-            provider_error_json: Some(serde_json::to_string(&error_body).unwrap()),
-        })
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, CompletionsResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| {
+                format!(
+                    r#"{{"code": "Client specified an invalid argument", "error": "{message}"}}"#
+                )
+            },
+            |status, body| parse_response_body::<CompletionsResponse>(status, body).map(|_| ()),
+        );
     }
 }
@@ -1,8 +1,12 @@
-use crate::client::{CompletionsRequest, CompletionsResponse, Detail, Effort};
+use crate::client::{
+    CompletionsRequest, CompletionsResponse, Detail, Effort, FunctionName, Logprobs, ToolChoice,
+};
 use base64::{engine::general_purpose, Engine as _};
+use golem_llm::error::unsupported;
 use golem_llm::golem::llm::llm::{
     ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, ImageDetail,
-    ImageReference, Message, ResponseMetadata, Role, ToolCall, ToolDefinition, ToolResult, Usage,
+    ImageReference, Message, ReasoningEffort, ResponseMetadata, Role, TokenLogprob, ToolCall,
+    ToolChoice as LlmToolChoice, ToolDefinition, ToolResult, TopLogprob, Usage,
 };
 use std::collections::HashMap;
 
@@ -21,20 +25,20 @@ pub fn messages_to_request(
         match message.role {
             Role::User => completion_messages.push(crate::client::Message::User {
                 name: message.name,
-                content: convert_content_parts(message.content),
+                content: convert_content_parts(message.content)?,
             }),
             Role::Assistant => completion_messages.push(crate::client::Message::Assistant {
                 name: message.name,
-                content: Some(convert_content_parts(message.content)),
+                content: Some(convert_content_parts(message.content)?),
                 tool_calls: None,
             }),
             Role::System => completion_messages.push(crate::client::Message::System {
                 name: message.name,
-                content: convert_content_parts(message.content),
+                content: convert_content_parts(message.content)?,
             }),
             Role::Tool => completion_messages.push(crate::client::Message::Tool {
                 name: message.name,
-                content: convert_content_parts(message.content),
+                content: convert_content_parts(message.content)?,
                 tool_call_id: None,
             }),
         }
@@ -51,14 +55,21 @@ pub fn messages_to_request(
         frequency_penalty: options
             .get("frequency_penalty")
             .and_then(|fp_s| fp_s.parse::<f32>().ok()),
+        logprobs: config.logprobs.map(|_| true),
         max_completion_tokens: config.max_tokens,
         n: options.get("n").and_then(|n_s| n_s.parse::<u32>().ok()),
+        parallel_tool_calls: config.parallel_tool_calls,
         presence_penalty: options
             .get("presence_penalty")
             .and_then(|pp_s| pp_s.parse::<f32>().ok()),
-        reasoning_effort: options
-            .get("reasoning_effort")
-            .and_then(|effort_s| effort_s.parse::<Effort>().ok()),
+        reasoning_effort: config
+            .reasoning_effort
+            .map(convert_reasoning_effort)
+            .or_else(|| {
+                options
+                    .get("reasoning_effort")
+                    .and_then(|effort_s| effort_s.parse::<Effort>().ok())
+            }),
         seed: options
             .get("seed")
             .and_then(|seed_s| seed_s.parse::<u32>().ok()),
@@ -66,11 +77,13 @@ pub fn messages_to_request(
         stream: Some(false),
         stream_options: None,
         temperature: config.temperature,
-        tool_choice: config.tool_choice,
+        tool_choice: config.tool_choice.map(convert_tool_choice),
         tools,
-        top_logprobs: options
-            .get("top_logprobs")
-            .and_then(|top_logprobs_s| top_logprobs_s.parse::<u8>().ok()),
+        top_logprobs: config.logprobs.or_else(|| {
+            options
+                .get("top_logprobs")
+                .and_then(|top_logprobs_s| top_logprobs_s.parse::<u32>().ok())
+        }),
         top_p: options
             .get("top_p")
             .and_then(|top_p_s| top_p_s.parse::<f32>().ok()),
@@ -78,6 +91,46 @@ pub fn messages_to_request(
     })
 }
 
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Mode("auto".to_string()),
+        LlmToolChoice::None => ToolChoice::Mode("none".to_string()),
+        LlmToolChoice::Required => ToolChoice::Mode("required".to_string()),
+        LlmToolChoice::Specific(name) => ToolChoice::Function {
+            kind: "function".to_string(),
+            function: FunctionName { name },
+        },
+    }
+}
+
+/// Grok only has a low and a high reasoning effort
+fn convert_reasoning_effort(effort: ReasoningEffort) -> Effort {
+    match effort {
+        ReasoningEffort::Low | ReasoningEffort::Medium => Effort::Low,
+        ReasoningEffort::High => Effort::High,
+    }
+}
+
+pub fn convert_logprobs(logprobs: &Logprobs) -> Option<Vec<TokenLogprob>> {
+    logprobs.content.as_ref().map(|content| {
+        content
+            .iter()
+            .map(|logprob| TokenLogprob {
+                token: logprob.token.clone(),
+                logprob: logprob.logprob,
+                top_logprobs: logprob
+                    .top_logprobs
+                    .iter()
+                    .map(|top| TopLogprob {
+                        token: top.token.clone(),
+                        logprob: top.logprob,
+                    })
+                    .collect(),
+            })
+            .collect()
+    })
+}
+
 pub fn process_response(response: CompletionsResponse) -> ChatEvent {
     let choice = response.choices.first();
     if let Some(choice) = choice {
@@ -102,12 +155,19 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
                 provider_id: None,
                 timestamp: Some(response.created.to_string()),
                 provider_metadata_json: None,
+                logprobs: choice.logprobs.as_ref().and_then(convert_logprobs),
+                cost: None,
             };
 
             ChatEvent::Message(CompleteResponse {
                 id: response.id,
                 content: contents,
                 tool_calls,
+                reasoning: choice
+                    .message
+                    .reasoning_content
+                    .clone()
+                    .filter(|text| !text.is_empty()),
                 metadata,
             })
         }
@@ -164,11 +224,15 @@ pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
     }
 }
 
-fn convert_content_parts(contents: Vec<ContentPart>) -> crate::client::Content {
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<crate::client::Content, Error> {
     let mut result = Vec::new();
     for content in contents {
         match content {
             ContentPart::Text(text) => result.push(crate::client::ContentPart::TextInput { text }),
+            ContentPart::CitedText(cited) => {
+                result.push(crate::client::ContentPart::TextInput { text: cited.text })
+            }
+            ContentPart::BuiltInToolResult(_) => {}
             ContentPart::Image(image_reference) => match image_reference {
                 ImageReference::Url(image_url) => {
                     result.push(crate::client::ContentPart::ImageInput {
@@ -189,9 +253,10 @@ fn convert_content_parts(contents: Vec<ContentPart>) -> crate::client::Content {
                     });
                 }
             },
+            ContentPart::Audio(_) => return Err(unsupported("audio with Grok")),
         }
     }
-    crate::client::Content::List(result)
+    Ok(crate::client::Content::List(result))
 }
 
 impl From<ImageDetail> for Detail {
@@ -218,10 +283,14 @@ pub fn convert_usage(value: &crate::client::Usage) -> Usage {
         input_tokens: Some(value.prompt_tokens),
         output_tokens: Some(value.completion_tokens),
         total_tokens: Some(value.total_tokens),
+        cached_tokens: Some(value.prompt_tokens_details.cached_tokens),
     }
 }
 
 fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with Grok"));
+    }
     match serde_json::from_str(&tool.parameters_schema) {
         Ok(value) => Ok(crate::client::Tool::Function {
             function: crate::client::Function {
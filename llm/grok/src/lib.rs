@@ -3,23 +3,24 @@ mod conversions;
 
 use crate::client::{ChatCompletionChunk, CompletionsApi, CompletionsRequest, StreamOptions};
 use crate::conversions::{
-    convert_finish_reason, convert_tool_call, convert_usage, messages_to_request, process_response,
-    tool_results_to_messages,
+    convert_finish_reason, convert_logprobs, convert_tool_call, convert_usage, messages_to_request,
+    process_response, tool_results_to_messages,
 };
 use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
-use golem_llm::config::with_config_key;
-use golem_llm::durability::{DurableLLM, ExtendedGuest};
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
 use golem_llm::event_source::EventSource;
 use golem_llm::golem::llm::llm::{
     ChatEvent, ChatStream, Config, ContentPart, Error, FinishReason, Guest, Message,
     ResponseMetadata, StreamDelta, StreamEvent, ToolCall, ToolResult,
 };
+use golem_llm::redaction::Redacted;
 use golem_llm::LOGGING_STATE;
 use golem_rust::wasm_rpc::Pollable;
 use log::trace;
 use std::cell::{Ref, RefCell, RefMut};
 
-struct GrokChatStream {
+pub struct GrokChatStream {
     stream: RefCell<Option<EventSource>>,
     failure: Option<Error>,
     finished: RefCell<bool>,
@@ -68,7 +69,7 @@ impl LlmChatStreamState for GrokChatStream {
     }
 
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
-        trace!("Received raw stream event: {raw}");
+        trace!("Received raw stream event: {}", Redacted(&raw));
         let json: serde_json::Value = serde_json::from_str(raw)
             .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
 
@@ -94,6 +95,8 @@ impl LlmChatStreamState for GrokChatStream {
                             .delta
                             .tool_calls
                             .map(|calls| calls.iter().map(convert_tool_call).collect()),
+                        reasoning: choice.delta.reasoning_content,
+                        logprobs: choice.logprobs.as_ref().and_then(convert_logprobs),
                     })))
                 } else if let Some(usage) = message.usage {
                     let finish_reason = self.finish_reason.borrow();
@@ -103,6 +106,8 @@ impl LlmChatStreamState for GrokChatStream {
                         provider_id: None,
                         timestamp: Some(message.created.to_string()),
                         provider_metadata_json: None,
+                        logprobs: None,
+                        cost: None,
                     })))
                 } else {
                     Ok(None)
@@ -114,7 +119,7 @@ impl LlmChatStreamState for GrokChatStream {
     }
 }
 
-struct GrokComponent;
+pub struct GrokComponent;
 
 impl GrokComponent {
     const ENV_VAR_NAME: &'static str = "XAI_API_KEY";
@@ -147,14 +152,19 @@ impl Guest for GrokComponent {
     fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |xai_api_key| {
-            let client = CompletionsApi::new(xai_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |xai_api_key| {
+                let client = CompletionsApi::new(xai_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(request) => Self::request(client, request),
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
     }
 
     fn continue_(
@@ -164,19 +174,24 @@ impl Guest for GrokComponent {
     ) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |xai_api_key| {
-            let client = CompletionsApi::new(xai_api_key);
-
-            match messages_to_request(messages, config) {
-                Ok(mut request) => {
-                    request
-                        .messages
-                        .extend(tool_results_to_messages(tool_results));
-                    Self::request(client, request)
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |xai_api_key| {
+                let client = CompletionsApi::new(xai_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
                 }
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+            },
+        )
     }
 
     fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
@@ -188,14 +203,19 @@ impl ExtendedGuest for GrokComponent {
     fn unwrapped_stream(messages: Vec<Message>, config: Config) -> LlmChatStream<GrokChatStream> {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, GrokChatStream::failed, |xai_api_key| {
-            let client = CompletionsApi::new(xai_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            GrokChatStream::failed,
+            |xai_api_key| {
+                let client = CompletionsApi::new(xai_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(request) => Self::streaming_request(client, request),
-                Err(err) => GrokChatStream::failed(err),
-            }
-        })
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::streaming_request(client, request),
+                    Err(err) => GrokChatStream::failed(err),
+                }
+            },
+        )
     }
 
     fn subscribe(stream: &Self::ChatStream) -> Pollable {
@@ -203,6 +223,8 @@ impl ExtendedGuest for GrokComponent {
     }
 }
 
-type DurableGrokComponent = DurableLLM<GrokComponent>;
+#[cfg(not(feature = "embedded"))]
+type DurableGrokComponent = golem_llm::durability::DurableLLM<GrokComponent>;
 
+#[cfg(not(feature = "embedded"))]
 golem_llm::export_llm!(DurableGrokComponent with_types_in golem_llm);
@@ -0,0 +1,427 @@
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+const BASE_URL: &str = "https://api.groq.com/openai";
+
+/// The Groq chat completions API client, compatible with OpenAI's.
+///
+/// Based on https://console.groq.com/docs/api-reference#chat-create
+pub struct CompletionsApi {
+    api_key: String,
+    client: Client,
+}
+
+impl CompletionsApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    pub fn send_messages(&self, request: CompletionsRequest) -> Result<CompletionsResponse, Error> {
+        trace!("Sending request to Groq API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("groq", "send-messages", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "groq",
+                "send-messages",
+                Method::POST,
+                format!("{BASE_URL}/v1/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn stream_send_messages(&self, request: CompletionsRequest) -> Result<EventSource, Error> {
+        trace!("Sending request to Groq API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new("groq", "stream-send-messages", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "groq",
+                "stream-send-messages",
+                Method::POST,
+                format!("{BASE_URL}/v1/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
+
+        trace!("Initializing SSE stream");
+
+        EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
+            .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionsRequest {
+    pub messages: Vec<Message>,
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    /// How the reasoning of reasoning models is returned: `parsed`, `raw` or `hidden`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+/// Either one of the `auto`, `none` and `required` modes, or a specific function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(String),
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        function: FunctionName,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionName {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Tool {
+    #[serde(rename = "function")]
+    Function { function: Function },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Function {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "role")]
+pub enum Message {
+    #[serde(rename = "system")]
+    System {
+        content: Content,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    #[serde(rename = "user")]
+    User {
+        content: Content,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    #[serde(rename = "assistant")]
+    Assistant {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        content: Option<Content>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_calls: Option<Vec<ToolCall>>,
+    },
+    #[serde(rename = "tool")]
+    Tool {
+        content: Content,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_call_id: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Content {
+    TextInput(String),
+    List(Vec<ContentPart>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ContentPart {
+    #[serde(rename = "text")]
+    TextInput { text: String },
+    #[serde(rename = "image_url")]
+    ImageInput { image_url: ImageUrl },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub enum Detail {
+    #[serde(rename = "auto")]
+    #[default]
+    Auto,
+    #[serde(rename = "low")]
+    Low,
+    #[serde(rename = "high")]
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Detail>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ToolCall {
+    #[serde(rename = "function")]
+    Function {
+        function: FunctionCall,
+        id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub arguments: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionsResponse {
+    pub choices: Vec<Choice>,
+    pub created: u64,
+    pub id: String,
+    pub model: String,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Choice {
+    pub finish_reason: Option<FinishReason>,
+    pub index: u32,
+    pub message: ResponseMessage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FinishReason {
+    #[serde(rename = "stop")]
+    Stop,
+    #[serde(rename = "length")]
+    Length,
+    #[serde(rename = "tool_calls")]
+    ToolCalls,
+    #[serde(rename = "function_call")]
+    FunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseMessage {
+    #[serde(default)]
+    pub content: Option<String>,
+    /// The reasoning of reasoning models, with the `parsed` reasoning format
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+/// Token usage, with the time in seconds Groq spent on each part of the request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Usage {
+    pub completion_tokens: u32,
+    pub prompt_tokens: u32,
+    pub total_tokens: u32,
+    #[serde(default)]
+    pub queue_time: Option<f64>,
+    #[serde(default)]
+    pub prompt_time: Option<f64>,
+    #[serde(default)]
+    pub completion_time: Option<f64>,
+    #[serde(default)]
+    pub total_time: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub created: u64,
+    pub model: String,
+    pub choices: Vec<ChoiceChunk>,
+    #[serde(default)]
+    pub usage: Option<Usage>,
+    /// Groq specific fields, holding the usage in the last chunk of the stream
+    #[serde(default)]
+    pub x_groq: Option<XGroq>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XGroq {
+    #[serde(default)]
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceChunk {
+    pub index: u32,
+    pub delta: ChoiceDelta,
+    pub finish_reason: Option<FinishReason>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body).map_err(|err| with_retry_after(err, retry_after))
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
+
+        trace!("Received response from Groq API: {:?}", Redacted(&body));
+
+        Ok(body)
+    } else {
+        trace!(
+            "Received {status} response from Groq API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        // Exceeded token limits are reported with a `rate_limit_exceeded` code, also when the
+        // status is 413 because a single request is above the limit
+        let mut error = from_error_response(status, raw_body, ProviderError::from_json);
+        if status.as_u16() == FLEX_CAPACITY_EXCEEDED {
+            error.code = ErrorCode::RateLimitExceeded;
+        }
+        Err(error)
+    }
+}
+
+/// The status Groq responds with when the capacity of the flex service tier is exceeded
+const FLEX_CAPACITY_EXCEEDED: u16 = 498;
+
+/// Adds the number of seconds to wait from the `retry-after` header to rate limit errors
+fn with_retry_after(mut error: Error, retry_after: Option<String>) -> Error {
+    if let (ErrorCode::RateLimitExceeded, Some(retry_after)) = (error.code, retry_after) {
+        error.message = format!("{} (retry after {retry_after} seconds)", error.message);
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, with_retry_after, CompletionsResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+    use golem_llm::golem::llm::llm::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| {
+                format!(
+                    r#"{{"error": {{"message": "{message}", "type": "invalid_request_error", "code": null}}}}"#
+                )
+            },
+            |status, body| parse_response_body::<CompletionsResponse>(status, body).map(|_| ()),
+        );
+    }
+
+    #[test]
+    fn maps_exceeded_token_limits_to_rate_limits() {
+        let body = r#"{"error": {"message": "Request too large for model on tokens per minute (TPM)", "type": "tokens", "code": "rate_limit_exceeded"}}"#;
+        let error = parse_response_body::<CompletionsResponse>(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            body.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+
+        let error = parse_response_body::<CompletionsResponse>(
+            StatusCode::from_u16(498).unwrap(),
+            "Flex tier capacity exceeded".to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+
+        let error = with_retry_after(error, Some("7".to_string()));
+        assert!(error.message.ends_with("(retry after 7 seconds)"));
+    }
+}
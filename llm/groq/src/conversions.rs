@@ -0,0 +1,321 @@
+use crate::client::{CompletionsRequest, CompletionsResponse, Detail, FunctionName, ToolChoice};
+use base64::{engine::general_purpose, Engine as _};
+use golem_llm::error::unsupported;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, ImageDetail,
+    ImageReference, Message, ReasoningEffort, ResponseMetadata, Role, ToolCall,
+    ToolChoice as LlmToolChoice, ToolDefinition, ToolResult, Usage,
+};
+use std::collections::HashMap;
+
+pub fn messages_to_request(
+    messages: Vec<Message>,
+    config: Config,
+) -> Result<CompletionsRequest, Error> {
+    let options = config
+        .provider_options
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect::<HashMap<_, _>>();
+
+    let mut completion_messages = Vec::new();
+    for message in messages {
+        match message.role {
+            Role::User => completion_messages.push(crate::client::Message::User {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Assistant => completion_messages.push(crate::client::Message::Assistant {
+                name: message.name,
+                content: Some(convert_content_parts(message.content)?),
+                tool_calls: None,
+            }),
+            Role::System => completion_messages.push(crate::client::Message::System {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Tool => completion_messages.push(crate::client::Message::Tool {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+                tool_call_id: None,
+            }),
+        }
+    }
+
+    let mut tools = Vec::new();
+    for tool in config.tools {
+        tools.push(tool_definition_to_tool(tool)?)
+    }
+
+    Ok(CompletionsRequest {
+        messages: completion_messages,
+        model: config.model,
+        frequency_penalty: options
+            .get("frequency_penalty")
+            .and_then(|fp_s| fp_s.parse::<f32>().ok()),
+        max_completion_tokens: config.max_tokens,
+        parallel_tool_calls: config.parallel_tool_calls,
+        presence_penalty: options
+            .get("presence_penalty")
+            .and_then(|pp_s| pp_s.parse::<f32>().ok()),
+        reasoning_effort: config
+            .reasoning_effort
+            .map(convert_reasoning_effort)
+            .or_else(|| options.get("reasoning_effort").cloned()),
+        reasoning_format: options.get("reasoning_format").cloned(),
+        seed: options
+            .get("seed")
+            .and_then(|seed_s| seed_s.parse::<u32>().ok()),
+        stop: config.stop_sequences,
+        stream: Some(false),
+        temperature: config.temperature,
+        tool_choice: config.tool_choice.map(convert_tool_choice),
+        tools,
+        top_p: options
+            .get("top_p")
+            .and_then(|top_p_s| top_p_s.parse::<f32>().ok()),
+        user: options.get("user_id").cloned(),
+    })
+}
+
+fn convert_reasoning_effort(effort: ReasoningEffort) -> String {
+    match effort {
+        ReasoningEffort::Low => "low".to_string(),
+        ReasoningEffort::Medium => "medium".to_string(),
+        ReasoningEffort::High => "high".to_string(),
+    }
+}
+
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Mode("auto".to_string()),
+        LlmToolChoice::None => ToolChoice::Mode("none".to_string()),
+        LlmToolChoice::Required => ToolChoice::Mode("required".to_string()),
+        LlmToolChoice::Specific(name) => ToolChoice::Function {
+            kind: "function".to_string(),
+            function: FunctionName { name },
+        },
+    }
+}
+
+pub fn process_response(response: CompletionsResponse) -> ChatEvent {
+    let choice = response.choices.first();
+    if let Some(choice) = choice {
+        let mut contents = Vec::new();
+        let mut tool_calls = Vec::new();
+
+        if let Some(content) = &choice.message.content {
+            contents.push(ContentPart::Text(content.clone()));
+        }
+
+        let empty = Vec::new();
+        for tool_call in choice.message.tool_calls.as_ref().unwrap_or(&empty) {
+            tool_calls.push(convert_tool_call(tool_call));
+        }
+
+        if contents.is_empty() && !tool_calls.is_empty() {
+            ChatEvent::ToolRequest(tool_calls)
+        } else {
+            let metadata = ResponseMetadata {
+                finish_reason: choice.finish_reason.as_ref().map(convert_finish_reason),
+                usage: response.usage.as_ref().map(convert_usage),
+                provider_id: Some(response.model.clone()),
+                timestamp: Some(response.created.to_string()),
+                provider_metadata_json: response.usage.as_ref().and_then(timing_metadata),
+                logprobs: None,
+                cost: None,
+            };
+
+            ChatEvent::Message(CompleteResponse {
+                id: response.id,
+                content: contents,
+                tool_calls,
+                reasoning: choice
+                    .message
+                    .reasoning
+                    .clone()
+                    .filter(|text| !text.is_empty()),
+                metadata,
+            })
+        }
+    } else {
+        ChatEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "No choices in response".to_string(),
+            provider_error_json: None,
+        })
+    }
+}
+
+pub fn tool_results_to_messages(
+    tool_results: Vec<(ToolCall, ToolResult)>,
+) -> Vec<crate::client::Message> {
+    let mut messages = Vec::new();
+    for (tool_call, tool_result) in tool_results {
+        messages.push(crate::client::Message::Assistant {
+            content: None,
+            name: None,
+            tool_calls: Some(vec![crate::client::ToolCall::Function {
+                function: crate::client::FunctionCall {
+                    arguments: tool_call.arguments_json,
+                    name: tool_call.name,
+                },
+                id: tool_call.id.clone(),
+                index: None,
+            }]),
+        });
+        let content = match tool_result {
+            ToolResult::Success(success) => success.result_json,
+            ToolResult::Error(failure) => failure.error_message,
+        };
+        // Groq only accepts text content in tool messages
+        messages.push(crate::client::Message::Tool {
+            name: None,
+            content: crate::client::Content::TextInput(content),
+            tool_call_id: Some(tool_call.id),
+        });
+    }
+    messages
+}
+
+pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
+    match tool_call {
+        crate::client::ToolCall::Function { function, id, .. } => ToolCall {
+            id: id.clone(),
+            name: function.name.clone(),
+            arguments_json: function.arguments.clone(),
+        },
+    }
+}
+
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<crate::client::Content, Error> {
+    let mut result = Vec::new();
+    for content in contents {
+        match content {
+            ContentPart::Text(text) => result.push(crate::client::ContentPart::TextInput { text }),
+            ContentPart::CitedText(cited) => {
+                result.push(crate::client::ContentPart::TextInput { text: cited.text })
+            }
+            ContentPart::BuiltInToolResult(_) => {}
+            ContentPart::Image(image_reference) => match image_reference {
+                ImageReference::Url(image_url) => {
+                    result.push(crate::client::ContentPart::ImageInput {
+                        image_url: crate::client::ImageUrl {
+                            url: image_url.url,
+                            detail: image_url.detail.map(|d| d.into()),
+                        },
+                    })
+                }
+                ImageReference::Inline(image_source) => {
+                    let base64_data = general_purpose::STANDARD.encode(&image_source.data);
+                    let media_type = &image_source.mime_type; // This is already a string
+                    result.push(crate::client::ContentPart::ImageInput {
+                        image_url: crate::client::ImageUrl {
+                            url: format!("data:{};base64,{}", media_type, base64_data),
+                            detail: image_source.detail.map(|d| d.into()),
+                        },
+                    });
+                }
+            },
+            ContentPart::Audio(_) => return Err(unsupported("audio with Groq")),
+        }
+    }
+    Ok(crate::client::Content::List(result))
+}
+
+impl From<ImageDetail> for Detail {
+    fn from(value: ImageDetail) -> Self {
+        match value {
+            ImageDetail::Auto => Self::Auto,
+            ImageDetail::Low => Self::Low,
+            ImageDetail::High => Self::High,
+        }
+    }
+}
+
+pub fn convert_finish_reason(value: &crate::client::FinishReason) -> FinishReason {
+    match value {
+        crate::client::FinishReason::Stop => FinishReason::Stop,
+        crate::client::FinishReason::Length => FinishReason::Length,
+        crate::client::FinishReason::ToolCalls | crate::client::FinishReason::FunctionCall => {
+            FinishReason::ToolCalls
+        }
+    }
+}
+
+pub fn convert_usage(value: &crate::client::Usage) -> Usage {
+    Usage {
+        input_tokens: Some(value.prompt_tokens),
+        output_tokens: Some(value.completion_tokens),
+        total_tokens: Some(value.total_tokens),
+        cached_tokens: None,
+    }
+}
+
+/// The time Groq spent on the request, reported in the usage, as provider metadata
+pub fn timing_metadata(usage: &crate::client::Usage) -> Option<String> {
+    let timings = [
+        ("queue_time", usage.queue_time),
+        ("prompt_time", usage.prompt_time),
+        ("completion_time", usage.completion_time),
+        ("total_time", usage.total_time),
+    ]
+    .into_iter()
+    .filter_map(|(name, time)| time.map(|time| (name.to_string(), serde_json::json!(time))))
+    .collect::<serde_json::Map<_, _>>();
+
+    if timings.is_empty() {
+        None
+    } else {
+        Some(serde_json::Value::Object(timings).to_string())
+    }
+}
+
+fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with Groq"));
+    }
+    match serde_json::from_str(&tool.parameters_schema) {
+        Ok(value) => Ok(crate::client::Tool::Function {
+            function: crate::client::Function {
+                name: tool.name,
+                description: tool.description,
+                parameters: Some(value),
+            },
+        }),
+        Err(error) => Err(Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to parse tool parameters for {}: {error}", tool.name),
+            provider_error_json: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::Usage;
+    use crate::conversions::timing_metadata;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn reports_timings_as_metadata() {
+        let mut usage = Usage {
+            completion_tokens: 10,
+            prompt_tokens: 20,
+            total_tokens: 30,
+            queue_time: Some(0.25),
+            prompt_time: None,
+            completion_time: Some(0.5),
+            total_time: None,
+        };
+        let metadata: Value = serde_json::from_str(&timing_metadata(&usage).unwrap()).unwrap();
+        assert_eq!(
+            metadata,
+            json!({ "queue_time": 0.25, "completion_time": 0.5 })
+        );
+
+        usage.queue_time = None;
+        usage.completion_time = None;
+        assert_eq!(timing_metadata(&usage), None);
+    }
+}
@@ -0,0 +1,247 @@
+mod client;
+mod conversions;
+
+use crate::client::{ChatCompletionChunk, CompletionsApi, CompletionsRequest};
+use crate::conversions::{
+    convert_finish_reason, convert_tool_call, convert_usage, messages_to_request, process_response,
+    timing_metadata, tool_results_to_messages,
+};
+use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, ContentPart, Error, Guest, Message, ResponseMetadata,
+    StreamDelta, StreamEvent, ToolCall, ToolResult,
+};
+use golem_llm::redaction::Redacted;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use std::cell::{Ref, RefCell, RefMut};
+
+pub struct GroqChatStream {
+    stream: RefCell<Option<EventSource>>,
+    failure: Option<Error>,
+    finished: RefCell<bool>,
+    pending: RefCell<Option<StreamEvent>>,
+}
+
+impl GroqChatStream {
+    pub fn new(stream: EventSource) -> LlmChatStream<Self> {
+        LlmChatStream::new(GroqChatStream {
+            stream: RefCell::new(Some(stream)),
+            failure: None,
+            finished: RefCell::new(false),
+            pending: RefCell::new(None),
+        })
+    }
+
+    pub fn failed(error: Error) -> LlmChatStream<Self> {
+        LlmChatStream::new(GroqChatStream {
+            stream: RefCell::new(None),
+            failure: Some(error),
+            finished: RefCell::new(false),
+            pending: RefCell::new(None),
+        })
+    }
+}
+
+impl LlmChatStreamState for GroqChatStream {
+    fn failure(&self) -> &Option<Error> {
+        &self.failure
+    }
+
+    fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    fn set_finished(&self) {
+        *self.finished.borrow_mut() = true;
+    }
+
+    fn stream(&self) -> Ref<Option<EventSource>> {
+        self.stream.borrow()
+    }
+
+    fn stream_mut(&self) -> RefMut<Option<EventSource>> {
+        self.stream.borrow_mut()
+    }
+
+    fn take_pending_event(&self) -> Option<StreamEvent> {
+        self.pending.borrow_mut().take()
+    }
+
+    fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
+        trace!("Received raw stream event: {}", Redacted(&raw));
+        let json: serde_json::Value = serde_json::from_str(raw)
+            .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+
+        let typ = json
+            .as_object()
+            .and_then(|obj| obj.get("object"))
+            .and_then(|v| v.as_str());
+        match typ {
+            Some("chat.completion.chunk") => {
+                let message: ChatCompletionChunk = serde_json::from_value(json)
+                    .map_err(|err| format!("Failed to parse stream event: {err}"))?;
+                // The last chunk has both the last delta and the usage, which Groq sends in its
+                // own `x_groq` field
+                let usage = message
+                    .usage
+                    .or_else(|| message.x_groq.and_then(|x_groq| x_groq.usage));
+                let finish = usage.map(|usage| {
+                    StreamEvent::Finish(ResponseMetadata {
+                        finish_reason: message
+                            .choices
+                            .first()
+                            .and_then(|choice| choice.finish_reason.as_ref())
+                            .map(convert_finish_reason),
+                        usage: Some(convert_usage(&usage)),
+                        provider_id: Some(message.model.clone()),
+                        timestamp: Some(message.created.to_string()),
+                        provider_metadata_json: timing_metadata(&usage),
+                        logprobs: None,
+                        cost: None,
+                    })
+                });
+                let delta = message.choices.into_iter().next().and_then(|choice| {
+                    let content = choice
+                        .delta
+                        .content
+                        .filter(|content| !content.is_empty())
+                        .map(|text| vec![ContentPart::Text(text)]);
+                    let tool_calls = choice
+                        .delta
+                        .tool_calls
+                        .map(|calls| calls.iter().map(convert_tool_call).collect::<Vec<_>>());
+                    let reasoning = choice.delta.reasoning.filter(|text| !text.is_empty());
+                    (content.is_some() || tool_calls.is_some() || reasoning.is_some()).then_some(
+                        StreamEvent::Delta(StreamDelta {
+                            content,
+                            tool_calls,
+                            reasoning,
+                            logprobs: None,
+                        }),
+                    )
+                });
+                match delta {
+                    Some(delta) => {
+                        *self.pending.borrow_mut() = finish;
+                        Ok(Some(delta))
+                    }
+                    None => Ok(finish),
+                }
+            }
+            Some(_) => Ok(None),
+            None => Err("Unexpected stream event format, does not have 'object' field".to_string()),
+        }
+    }
+}
+
+pub struct GroqComponent;
+
+impl GroqComponent {
+    const ENV_VAR_NAME: &'static str = "GROQ_API_KEY";
+
+    fn request(client: CompletionsApi, request: CompletionsRequest) -> ChatEvent {
+        match client.send_messages(request) {
+            Ok(response) => process_response(response),
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn streaming_request(
+        client: CompletionsApi,
+        mut request: CompletionsRequest,
+    ) -> LlmChatStream<GroqChatStream> {
+        request.stream = Some(true);
+        match client.stream_send_messages(request) {
+            Ok(stream) => GroqChatStream::new(stream),
+            Err(err) => GroqChatStream::failed(err),
+        }
+    }
+}
+
+impl Guest for GroqComponent {
+    type ChatStream = LlmChatStream<GroqChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |groq_api_key| {
+                let client = CompletionsApi::new(groq_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |groq_api_key| {
+                let client = CompletionsApi::new(groq_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for GroqComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> LlmChatStream<GroqChatStream> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            GroqChatStream::failed,
+            |groq_api_key| {
+                let client = CompletionsApi::new(groq_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::streaming_request(client, request),
+                    Err(err) => GroqChatStream::failed(err),
+                }
+            },
+        )
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableGroqComponent = golem_llm::durability::DurableLLM<GroqComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableGroqComponent with_types_in golem_llm);
@@ -0,0 +1,342 @@
+use crate::moderation::MODERATION_RULE;
+use crate::rules::{Action, Blocked, Direction, Finding, GuardrailsConfig, Screening};
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CitedText, CompleteResponse, ContentPart, Error, ErrorCode, FinishReason, Message,
+    ResponseMetadata, ToolCall, ToolResult,
+};
+use serde_json::{json, Map, Value};
+
+/// A request passed on to the backend
+pub struct ScreenedInput {
+    pub messages: Vec<Message>,
+    pub tool_results: Vec<(ToolCall, ToolResult)>,
+    pub findings: Vec<Finding>,
+}
+
+/// Screens the texts of the messages and tool results sent to the LLM
+pub fn screen_input(
+    config: &GuardrailsConfig,
+    mut messages: Vec<Message>,
+    mut tool_results: Vec<(ToolCall, ToolResult)>,
+) -> Result<ScreenedInput, Error> {
+    if !config.screens(Direction::Input) {
+        return Ok(ScreenedInput {
+            messages,
+            tool_results,
+            findings: vec![],
+        });
+    }
+
+    let mut screening = Screening::new(&config.rules, Direction::Input);
+    let result = screen_input_texts(config, &mut screening, &mut messages, &mut tool_results);
+
+    match result {
+        Ok(()) => Ok(ScreenedInput {
+            messages,
+            tool_results,
+            findings: screening.findings(),
+        }),
+        Err(Stop::Blocked(Blocked(rule))) => Err(Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("The request was blocked by the guardrail rule {rule}"),
+            provider_error_json: Some(json!({ "guardrails": screening.findings() }).to_string()),
+        }),
+        Err(Stop::Failed(error)) => Err(error),
+    }
+}
+
+/// Screens an LLM response, reporting the findings of the request and the response in its
+/// metadata
+pub fn screen_event(config: &GuardrailsConfig, event: ChatEvent, input: &[Finding]) -> ChatEvent {
+    match event {
+        ChatEvent::Message(response) => {
+            match screen_output(
+                config,
+                response.content,
+                response.tool_calls,
+                response.metadata,
+                input,
+            ) {
+                Ok((content, tool_calls, metadata)) => ChatEvent::Message(CompleteResponse {
+                    id: response.id,
+                    content,
+                    tool_calls,
+                    // The reasoning is not screened, so it is only passed on unscreened output
+                    reasoning: response
+                        .reasoning
+                        .filter(|_| !config.screens(Direction::Output)),
+                    metadata,
+                }),
+                Err(error) => ChatEvent::Error(error),
+            }
+        }
+        ChatEvent::ToolRequest(calls) => {
+            match screen_output(config, vec![], calls, empty_metadata(), input) {
+                Ok((content, _, metadata))
+                    if metadata.finish_reason == Some(FinishReason::ContentFilter) =>
+                {
+                    ChatEvent::Message(CompleteResponse {
+                        id: String::new(),
+                        content,
+                        tool_calls: vec![],
+                        reasoning: None,
+                        metadata,
+                    })
+                }
+                Ok((_, tool_calls, _)) => ChatEvent::ToolRequest(tool_calls),
+                Err(error) => ChatEvent::Error(error),
+            }
+        }
+        ChatEvent::Error(error) => ChatEvent::Error(error),
+    }
+}
+
+/// Screens the content and tool calls received from the LLM. When they are blocked, they are
+/// dropped and the finish reason is set to `content-filter`.
+pub fn screen_output(
+    config: &GuardrailsConfig,
+    mut content: Vec<ContentPart>,
+    mut tool_calls: Vec<ToolCall>,
+    mut metadata: ResponseMetadata,
+    input: &[Finding],
+) -> Result<(Vec<ContentPart>, Vec<ToolCall>, ResponseMetadata), Error> {
+    if !config.screens(Direction::Output) {
+        annotate(&mut metadata, input.to_vec());
+        return Ok((content, tool_calls, metadata));
+    }
+
+    let mut screening = Screening::new(&config.rules, Direction::Output);
+    let result = screen_output_texts(config, &mut screening, &mut content, &mut tool_calls);
+
+    let blocked = match result {
+        Ok(()) => false,
+        Err(Stop::Blocked(_)) => true,
+        Err(Stop::Failed(error)) => return Err(error),
+    };
+    let mut findings = input.to_vec();
+    findings.extend(screening.findings());
+    annotate(&mut metadata, findings);
+
+    if blocked {
+        metadata.finish_reason = Some(FinishReason::ContentFilter);
+        Ok((vec![], vec![], metadata))
+    } else {
+        Ok((content, tool_calls, metadata))
+    }
+}
+
+/// Why the screening of a request or response stopped
+enum Stop {
+    Blocked(Blocked),
+    Failed(Error),
+}
+
+impl From<Blocked> for Stop {
+    fn from(blocked: Blocked) -> Self {
+        Stop::Blocked(blocked)
+    }
+}
+
+fn screen_input_texts(
+    config: &GuardrailsConfig,
+    screening: &mut Screening,
+    messages: &mut [Message],
+    tool_results: &mut [(ToolCall, ToolResult)],
+) -> Result<(), Stop> {
+    let mut texts = Vec::new();
+    for message in messages {
+        screen_content(screening, &mut message.content, &mut texts)?;
+    }
+    for (_, tool_result) in tool_results {
+        let text = match tool_result {
+            ToolResult::Success(success) => &mut success.result_json,
+            ToolResult::Error(failure) => &mut failure.error_message,
+        };
+        *text = screening.text(text)?;
+        texts.push(text.clone());
+    }
+    moderate(config, screening, texts)
+}
+
+fn screen_output_texts(
+    config: &GuardrailsConfig,
+    screening: &mut Screening,
+    content: &mut [ContentPart],
+    tool_calls: &mut [ToolCall],
+) -> Result<(), Stop> {
+    let mut texts = Vec::new();
+    screen_content(screening, content, &mut texts)?;
+    for call in tool_calls {
+        call.arguments_json = screening.text(&call.arguments_json)?;
+        texts.push(call.arguments_json.clone());
+    }
+    moderate(config, screening, texts)
+}
+
+fn screen_content(
+    screening: &mut Screening,
+    content: &mut [ContentPart],
+    texts: &mut Vec<String>,
+) -> Result<(), Stop> {
+    for part in content {
+        if let ContentPart::Text(text) | ContentPart::CitedText(CitedText { text, .. }) = part {
+            *text = screening.text(text)?;
+            texts.push(text.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Checks the already screened texts with the moderation model, if it is configured for the
+/// direction of the screening
+fn moderate(
+    config: &GuardrailsConfig,
+    screening: &mut Screening,
+    texts: Vec<String>,
+) -> Result<(), Stop> {
+    let Some(moderation) = &config.moderation else {
+        return Ok(());
+    };
+    if !moderation.applies_to.includes(screening.direction()) {
+        return Ok(());
+    }
+
+    let categories = moderation.check(texts).map_err(Stop::Failed)?;
+    if categories.is_empty() {
+        return Ok(());
+    }
+    screening.record(MODERATION_RULE, moderation.action, 1, categories);
+    if moderation.action == Action::Block {
+        Err(Stop::Blocked(Blocked(MODERATION_RULE.to_string())))
+    } else {
+        Ok(())
+    }
+}
+
+/// Adds the findings to the provider metadata of a response, under the `guardrails` key
+fn annotate(metadata: &mut ResponseMetadata, findings: Vec<Finding>) {
+    if findings.is_empty() {
+        return;
+    }
+    let mut object = match metadata
+        .provider_metadata_json
+        .as_deref()
+        .map(serde_json::from_str::<Value>)
+    {
+        Some(Ok(Value::Object(object))) => object,
+        Some(Ok(other)) => Map::from_iter([("provider_metadata".to_string(), other)]),
+        _ => Map::new(),
+    };
+    object.insert("guardrails".to_string(), json!(findings));
+    metadata.provider_metadata_json = Some(Value::Object(object).to_string());
+}
+
+pub fn empty_metadata() -> ResponseMetadata {
+    ResponseMetadata {
+        finish_reason: None,
+        usage: None,
+        provider_id: None,
+        timestamp: None,
+        provider_metadata_json: None,
+        logprobs: None,
+        cost: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::guard::{empty_metadata, screen_event, screen_input};
+    use crate::rules::parse_config;
+    use golem_llm::golem::llm::llm::{
+        ChatEvent, CompleteResponse, ContentPart, ErrorCode, FinishReason, Message, Role, ToolCall,
+    };
+
+    const CONFIG: &str = r#"{
+        "backend": "mock",
+        "rules": [
+            { "name": "secrets", "keywords": ["titan"], "action": "block" },
+            { "name": "pii", "pii": ["email"], "action": "redact", "applies_to": "input" }
+        ]
+    }"#;
+
+    fn user(text: &str) -> Message {
+        Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text(text.to_string())],
+            cache_control: None,
+        }
+    }
+
+    fn response(text: &str) -> ChatEvent {
+        ChatEvent::Message(CompleteResponse {
+            id: "response".to_string(),
+            content: vec![ContentPart::Text(text.to_string())],
+            tool_calls: vec![],
+            reasoning: None,
+            metadata: empty_metadata(),
+        })
+    }
+
+    #[test]
+    fn redacts_and_reports_input() {
+        let config = parse_config(CONFIG).unwrap();
+        let input = screen_input(
+            &config,
+            vec![user("My address is jane@example.com")],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            input.messages[0].content,
+            vec![ContentPart::Text("My address is [REDACTED]".to_string())]
+        );
+
+        match screen_event(&config, response("Noted"), &input.findings) {
+            ChatEvent::Message(response) => {
+                let metadata: serde_json::Value = serde_json::from_str(
+                    response.metadata.provider_metadata_json.as_deref().unwrap(),
+                )
+                .unwrap();
+                assert_eq!(metadata["guardrails"][0]["rule"], "pii");
+                assert_eq!(metadata["guardrails"][0]["direction"], "input");
+            }
+            other => panic!("Unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_blocked_input() {
+        let config = parse_config(CONFIG).unwrap();
+        let error = screen_input(&config, vec![user("What is Titan?")], vec![])
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+    }
+
+    #[test]
+    fn filters_blocked_output() {
+        let config = parse_config(CONFIG).unwrap();
+        match screen_event(&config, response("The codename is Titan"), &[]) {
+            ChatEvent::Message(response) => {
+                assert!(response.content.is_empty());
+                assert_eq!(
+                    response.metadata.finish_reason,
+                    Some(FinishReason::ContentFilter)
+                );
+            }
+            other => panic!("Unexpected event: {other:?}"),
+        }
+
+        let event = screen_event(
+            &config,
+            ChatEvent::ToolRequest(vec![ToolCall {
+                id: "call-1".to_string(),
+                name: "search".to_string(),
+                arguments_json: r#"{"query": "titan"}"#.to_string(),
+            }]),
+            &[],
+        );
+        assert!(matches!(event, ChatEvent::Message(response) if response.tool_calls.is_empty()));
+    }
+}
@@ -0,0 +1,112 @@
+mod guard;
+mod moderation;
+mod pii;
+mod rules;
+mod stream;
+mod target;
+
+use crate::guard::{screen_event, screen_input};
+use crate::rules::{parse_config, GuardrailsConfig, GUARDRAILS_ENV_VAR};
+use crate::stream::GuardedChatStream;
+use golem_llm::durability::{DurableLLM, ExtendedGuest};
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Error, ErrorCode, Guest, Message, ToolCall, ToolResult,
+};
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use std::cell::RefCell;
+
+thread_local! {
+    static GUARDRAILS_CONFIG: RefCell<Option<GuardrailsConfig>> = const { RefCell::new(None) };
+}
+
+struct GuardrailsComponent;
+
+impl GuardrailsComponent {
+    fn config() -> Result<GuardrailsConfig, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        GUARDRAILS_CONFIG.with_borrow_mut(|config| {
+            if config.is_none() {
+                let raw = std::env::var(GUARDRAILS_ENV_VAR).map_err(|_| Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Missing config key: {GUARDRAILS_ENV_VAR}"),
+                    provider_error_json: None,
+                })?;
+                *config = Some(parse_config(&raw).map_err(|message| Error {
+                    code: ErrorCode::InternalError,
+                    message,
+                    provider_error_json: None,
+                })?);
+            }
+            Ok(config.clone().unwrap())
+        })
+    }
+}
+
+impl Guest for GuardrailsComponent {
+    type ChatStream = GuardedChatStream;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        let guardrails = match Self::config() {
+            Ok(guardrails) => guardrails,
+            Err(error) => return ChatEvent::Error(error),
+        };
+        match screen_input(&guardrails, messages, vec![]) {
+            Ok(input) => {
+                let event = guardrails.backend.send(input.messages, config);
+                screen_event(&guardrails, event, &input.findings)
+            }
+            Err(error) => ChatEvent::Error(error),
+        }
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        let guardrails = match Self::config() {
+            Ok(guardrails) => guardrails,
+            Err(error) => return ChatEvent::Error(error),
+        };
+        match screen_input(&guardrails, messages, tool_results) {
+            Ok(input) => {
+                let event =
+                    guardrails
+                        .backend
+                        .continue_(input.messages, input.tool_results, config);
+                screen_event(&guardrails, event, &input.findings)
+            }
+            Err(error) => ChatEvent::Error(error),
+        }
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for GuardrailsComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> GuardedChatStream {
+        let guardrails = match Self::config() {
+            Ok(guardrails) => guardrails,
+            Err(error) => return GuardedChatStream::failed(error),
+        };
+        match screen_input(&guardrails, messages, vec![]) {
+            Ok(input) => {
+                let inner = guardrails.backend.stream(input.messages, config);
+                GuardedChatStream::new(inner, guardrails, input.findings)
+            }
+            Err(error) => GuardedChatStream::failed(error),
+        }
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+type DurableGuardrailsComponent = DurableLLM<GuardrailsComponent>;
+
+golem_llm::export_llm!(DurableGuardrailsComponent with_types_in golem_llm);
@@ -0,0 +1,218 @@
+use crate::rules::{Action, AppliesTo};
+use golem_llm::config::with_config_key;
+use golem_llm::error::{from_error_response, from_reqwest_error, ProviderError};
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::redaction::Redacted;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+const BASE_URL: &str = "https://api.openai.com";
+
+const DEFAULT_MODEL: &str = "omni-moderation-latest";
+
+/// The name of the moderation in the findings
+pub const MODERATION_RULE: &str = "moderation";
+
+/// Checks the texts with the OpenAI moderation model, using the `OPENAI_API_KEY` environment
+/// variable
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModerationConfig {
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Only these categories are considered, all of them if empty
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Flags the categories with a score of at least this value, instead of relying on the
+    /// model's own decision
+    #[serde(default)]
+    pub threshold: Option<f64>,
+    #[serde(default = "default_action")]
+    pub action: Action,
+    #[serde(default)]
+    pub applies_to: AppliesTo,
+}
+
+fn default_action() -> Action {
+    Action::Block
+}
+
+impl ModerationConfig {
+    const ENV_VAR_NAME: &'static str = "OPENAI_API_KEY";
+
+    /// Returns the flagged categories of the texts. A failing moderation request fails the
+    /// screened call, so texts are never passed on unchecked.
+    pub fn check(&self, texts: Vec<String>) -> Result<Vec<String>, Error> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+        with_config_key(Self::ENV_VAR_NAME, Err, |api_key| {
+            let client = ModerationApi::new(api_key);
+            let response = client.create_moderation(ModerationRequest {
+                model: self
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+                input: texts,
+            })?;
+            Ok(self.flagged_categories(&response))
+        })
+    }
+
+    fn flagged_categories(&self, response: &ModerationResponse) -> Vec<String> {
+        let mut flagged = BTreeSet::new();
+        for result in &response.results {
+            let categories: Vec<&String> = match self.threshold {
+                Some(threshold) => result
+                    .category_scores
+                    .iter()
+                    .filter(|(_, score)| **score >= threshold)
+                    .map(|(category, _)| category)
+                    .collect(),
+                None if result.flagged => result
+                    .categories
+                    .iter()
+                    .filter(|(_, flagged)| **flagged)
+                    .map(|(category, _)| category)
+                    .collect(),
+                None => vec![],
+            };
+            flagged.extend(categories.into_iter().filter(|category| {
+                self.categories.is_empty() || self.categories.contains(category)
+            }));
+        }
+        flagged.into_iter().cloned().collect()
+    }
+}
+
+/// The OpenAI API client for the moderation endpoint.
+///
+/// Based on https://platform.openai.com/docs/api-reference/moderations
+struct ModerationApi {
+    openai_api_key: String,
+    client: Client,
+}
+
+impl ModerationApi {
+    fn new(openai_api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            openai_api_key,
+            client,
+        }
+    }
+
+    fn create_moderation(&self, request: ModerationRequest) -> Result<ModerationResponse, Error> {
+        trace!("Sending request to OpenAI moderation API: {request:?}");
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openai",
+                "create-moderation",
+                Method::POST,
+                format!("{BASE_URL}/v1/moderations"),
+            )
+            .bearer_auth(&self.openai_api_key)?
+            .json(&request)?,
+        )?;
+
+        let status = response.status();
+        let raw_body = response
+            .text()
+            .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+        parse_response_body(status, raw_body)
+    }
+}
+
+fn parse_response_body(status: StatusCode, raw_body: String) -> Result<ModerationResponse, Error> {
+    if status.is_success() {
+        serde_json::from_str(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode moderation response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!(
+            "Received {status} response from OpenAI moderation API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ModerationRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModerationResponse {
+    results: Vec<ModerationResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModerationResult {
+    flagged: bool,
+    categories: HashMap<String, bool>,
+    category_scores: HashMap<String, f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::moderation::{parse_response_body, ModerationConfig};
+    use reqwest::StatusCode;
+
+    const RESPONSE: &str = r#"{
+        "id": "modr-970d409ef3bef3b70c73d8232df86e7d",
+        "model": "omni-moderation-latest",
+        "results": [
+            {
+                "flagged": true,
+                "categories": {"harassment": true, "violence": true, "self-harm": false},
+                "category_scores": {"harassment": 0.52, "violence": 0.91, "self-harm": 0.01}
+            },
+            {
+                "flagged": false,
+                "categories": {"harassment": false, "violence": false, "self-harm": false},
+                "category_scores": {"harassment": 0.02, "violence": 0.01, "self-harm": 0.35}
+            }
+        ]
+    }"#;
+
+    fn config(json: &str) -> ModerationConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn uses_the_flags_of_the_model() {
+        let response = parse_response_body(StatusCode::OK, RESPONSE.to_string()).unwrap();
+        assert_eq!(
+            config("{}").flagged_categories(&response),
+            vec!["harassment", "violence"]
+        );
+        assert_eq!(
+            config(r#"{"categories": ["violence", "self-harm"]}"#).flagged_categories(&response),
+            vec!["violence"]
+        );
+    }
+
+    #[test]
+    fn uses_the_threshold() {
+        let response = parse_response_body(StatusCode::OK, RESPONSE.to_string()).unwrap();
+        assert_eq!(
+            config(r#"{"threshold": 0.3}"#).flagged_categories(&response),
+            vec!["harassment", "self-harm", "violence"]
+        );
+    }
+}
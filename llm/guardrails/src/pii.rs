@@ -0,0 +1,126 @@
+use regex::Regex;
+use serde::Deserialize;
+
+/// The kinds of personally identifiable information the guardrails can detect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PiiKind {
+    Email,
+    Phone,
+    CreditCard,
+    Ssn,
+    IpAddress,
+}
+
+impl PiiKind {
+    pub fn regex(self) -> Regex {
+        let pattern = match self {
+            PiiKind::Email => r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            PiiKind::Phone => {
+                r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{2,4}\)|\d{2,4})[\s.-]?\d{3,4}[\s.-]?\d{3,4}\b"
+            }
+            PiiKind::CreditCard => r"\b(?:\d[ -]?){12,18}\d\b",
+            PiiKind::Ssn => r"\b\d{3}-\d{2}-\d{4}\b",
+            PiiKind::IpAddress => {
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"
+            }
+        };
+        Regex::new(pattern).expect("Invalid built-in PII pattern")
+    }
+
+    /// Checks a match of the kind's regex, filtering out the false positives the regex alone
+    /// cannot rule out
+    pub fn accepts(self, text: &str, start: usize, matched: &str) -> bool {
+        match self {
+            PiiKind::Phone => {
+                let preceded_by_word = text[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric());
+                let digits = matched.chars().filter(|c| c.is_ascii_digit()).count();
+                !preceded_by_word && (10..=15).contains(&digits)
+            }
+            PiiKind::CreditCard => luhn_valid(matched),
+            _ => true,
+        }
+    }
+}
+
+/// Validates the check digit of a card number
+fn luhn_valid(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            if index % 2 == 1 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                *digit
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pii::PiiKind;
+
+    fn detect(kind: PiiKind, text: &str) -> Vec<String> {
+        kind.regex()
+            .find_iter(text)
+            .filter(|m| kind.accepts(text, m.start(), m.as_str()))
+            .map(|m| m.as_str().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn detects_emails_and_ssns() {
+        assert_eq!(
+            detect(PiiKind::Email, "Write to jane.doe+llm@example.co.uk today"),
+            vec!["jane.doe+llm@example.co.uk"]
+        );
+        assert_eq!(
+            detect(PiiKind::Ssn, "SSN 078-05-1120, order 2024-01-15"),
+            vec!["078-05-1120"]
+        );
+    }
+
+    #[test]
+    fn detects_valid_card_numbers_only() {
+        assert_eq!(
+            detect(
+                PiiKind::CreditCard,
+                "Card 4111 1111 1111 1111 or 4111 1111 1111 1112"
+            ),
+            vec!["4111 1111 1111 1111"]
+        );
+    }
+
+    #[test]
+    fn does_not_take_card_numbers_for_phone_numbers() {
+        assert_eq!(
+            detect(PiiKind::Phone, "Call +1 (555) 123-4567 or 555.987.6543"),
+            vec!["+1 (555) 123-4567", "555.987.6543"]
+        );
+        assert!(detect(PiiKind::Phone, "Card 4111111111111111").is_empty());
+    }
+
+    #[test]
+    fn detects_ip_addresses() {
+        assert_eq!(
+            detect(PiiKind::IpAddress, "Connect to 192.168.0.12, not 999.1.1.1"),
+            vec!["192.168.0.12"]
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use crate::moderation::ModerationConfig;
+use crate::pii::PiiKind;
+use crate::target::Target;
+use log::info;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Environment variable holding the guardrails configuration as JSON
+pub const GUARDRAILS_ENV_VAR: &str = "GOLEM_LLM_GUARDRAILS";
+
+const DEFAULT_REPLACEMENT: &str = "[REDACTED]";
+
+/// Whether a text is sent to the LLM, or received from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppliesTo {
+    Input,
+    Output,
+    #[default]
+    Both,
+}
+
+impl AppliesTo {
+    pub fn includes(self, direction: Direction) -> bool {
+        match self {
+            AppliesTo::Input => direction == Direction::Input,
+            AppliesTo::Output => direction == Direction::Output,
+            AppliesTo::Both => true,
+        }
+    }
+}
+
+/// What happens with a message matching a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    /// The request is rejected, or the response is replaced by an empty, content filtered one
+    Block,
+    /// The matching parts of the text are replaced
+    Redact,
+    /// The message is passed on unchanged, and the match is reported in the response metadata
+    Annotate,
+}
+
+#[derive(Debug, Clone)]
+pub struct GuardrailsConfig {
+    /// The provider the screened requests are forwarded to
+    pub backend: Target,
+    pub rules: Vec<Rule>,
+    pub moderation: Option<ModerationConfig>,
+}
+
+impl GuardrailsConfig {
+    /// Whether any rule applies to the texts of `direction`
+    pub fn screens(&self, direction: Direction) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.applies_to.includes(direction))
+            || self
+                .moderation
+                .as_ref()
+                .is_some_and(|moderation| moderation.applies_to.includes(direction))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawConfig {
+    backend: Target,
+    #[serde(default)]
+    rules: Vec<RawRule>,
+    #[serde(default)]
+    moderation: Option<ModerationConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RawRule {
+    name: String,
+    #[serde(default)]
+    pattern: Option<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    pii: Vec<PiiKind>,
+    action: Action,
+    #[serde(default)]
+    applies_to: AppliesTo,
+    #[serde(default)]
+    replacement: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub action: Action,
+    pub applies_to: AppliesTo,
+    replacement: String,
+    matchers: Vec<Matcher>,
+}
+
+#[derive(Debug, Clone)]
+struct Matcher {
+    regex: Regex,
+    pii: Option<PiiKind>,
+}
+
+impl Rule {
+    /// The ranges of `text` matching the rule, ordered and without overlaps
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = self
+            .matchers
+            .iter()
+            .flat_map(|matcher| {
+                matcher
+                    .regex
+                    .find_iter(text)
+                    .filter(|m| {
+                        matcher
+                            .pii
+                            .is_none_or(|kind| kind.accepts(text, m.start(), m.as_str()))
+                    })
+                    .map(|m| m.range())
+            })
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    fn redact(&self, text: &str, ranges: &[Range<usize>]) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut position = 0;
+        for range in ranges {
+            result.push_str(&text[position..range.start]);
+            result.push_str(&self.replacement);
+            position = range.end;
+        }
+        result.push_str(&text[position..]);
+        result
+    }
+}
+
+pub fn parse_config(raw: &str) -> Result<GuardrailsConfig, String> {
+    let raw_config: RawConfig =
+        serde_json::from_str(raw).map_err(|err| format!("Invalid {GUARDRAILS_ENV_VAR}: {err}"))?;
+    if raw_config
+        .moderation
+        .as_ref()
+        .is_some_and(|moderation| moderation.action == Action::Redact)
+    {
+        return Err(format!(
+            "Invalid {GUARDRAILS_ENV_VAR}: moderation can only block or annotate"
+        ));
+    }
+
+    Ok(GuardrailsConfig {
+        backend: raw_config.backend,
+        rules: raw_config
+            .rules
+            .into_iter()
+            .map(compile_rule)
+            .collect::<Result<_, _>>()?,
+        moderation: raw_config.moderation,
+    })
+}
+
+fn compile_rule(raw: RawRule) -> Result<Rule, String> {
+    let mut matchers = Vec::new();
+    if let Some(pattern) = &raw.pattern {
+        let regex = Regex::new(pattern)
+            .map_err(|err| format!("Invalid pattern of guardrail rule {}: {err}", raw.name))?;
+        matchers.push(Matcher { regex, pii: None });
+    }
+    if !raw.keywords.is_empty() {
+        matchers.push(Matcher {
+            regex: keywords_regex(&raw.keywords),
+            pii: None,
+        });
+    }
+    for kind in &raw.pii {
+        matchers.push(Matcher {
+            regex: kind.regex(),
+            pii: Some(*kind),
+        });
+    }
+    if matchers.is_empty() {
+        return Err(format!(
+            "Guardrail rule {} has no pattern, keywords or pii",
+            raw.name
+        ));
+    }
+
+    Ok(Rule {
+        name: raw.name,
+        action: raw.action,
+        applies_to: raw.applies_to,
+        replacement: raw
+            .replacement
+            .unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string()),
+        matchers,
+    })
+}
+
+/// Matches any of the keywords case-insensitively, as whole words
+fn keywords_regex(keywords: &[String]) -> Regex {
+    let alternatives: Vec<String> = keywords
+        .iter()
+        .map(|keyword| {
+            let boundary = |c: Option<char>| {
+                if c.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    r"\b"
+                } else {
+                    ""
+                }
+            };
+            format!(
+                "{}{}{}",
+                boundary(keyword.chars().next()),
+                regex::escape(keyword),
+                boundary(keyword.chars().next_back())
+            )
+        })
+        .collect();
+    Regex::new(&format!("(?i)(?:{})", alternatives.join("|")))
+        .expect("Escaped keywords must form a valid regex")
+}
+
+/// A rule, or the moderation, matching the texts of a request or response
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub rule: String,
+    pub direction: Direction,
+    pub action: Action,
+    pub matches: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
+/// The name of the rule blocking a request or response
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blocked(pub String);
+
+/// Screens the texts of one direction of a call, collecting the findings
+pub struct Screening<'a> {
+    rules: &'a [Rule],
+    direction: Direction,
+    findings: Vec<Finding>,
+}
+
+impl<'a> Screening<'a> {
+    pub fn new(rules: &'a [Rule], direction: Direction) -> Self {
+        Self {
+            rules,
+            direction,
+            findings: Vec::new(),
+        }
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Screens a text, returning it with the matches of the redacting rules replaced.
+    ///
+    /// Blocking and annotating rules are checked against the original text, and redacting rules
+    /// are applied in the order they are configured.
+    pub fn text(&mut self, text: &str) -> Result<String, Blocked> {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies_to.includes(self.direction));
+
+        let mut result = text.to_string();
+        for rule in rules {
+            match rule.action {
+                Action::Block | Action::Annotate => {
+                    let ranges = rule.find(text);
+                    if !ranges.is_empty() {
+                        self.record(&rule.name, rule.action, ranges.len(), vec![]);
+                        if rule.action == Action::Block {
+                            return Err(Blocked(rule.name.clone()));
+                        }
+                    }
+                }
+                Action::Redact => {
+                    let ranges = rule.find(&result);
+                    if !ranges.is_empty() {
+                        self.record(&rule.name, rule.action, ranges.len(), vec![]);
+                        result = rule.redact(&result, &ranges);
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn record(&mut self, rule: &str, action: Action, matches: usize, categories: Vec<String>) {
+        info!(
+            "Guardrail rule {rule} matched {matches} times in the {:?}",
+            self.direction
+        );
+        match self
+            .findings
+            .iter_mut()
+            .find(|finding| finding.rule == rule)
+        {
+            Some(finding) => {
+                finding.matches += matches;
+                for category in categories {
+                    if !finding.categories.contains(&category) {
+                        finding.categories.push(category);
+                    }
+                }
+            }
+            None => self.findings.push(Finding {
+                rule: rule.to_string(),
+                direction: self.direction,
+                action,
+                matches,
+                categories,
+            }),
+        }
+    }
+
+    pub fn findings(self) -> Vec<Finding> {
+        self.findings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::{parse_config, Action, Blocked, Direction, Screening};
+
+    const CONFIG: &str = r#"{
+        "backend": "mock",
+        "rules": [
+            { "name": "project-names", "keywords": ["Titan", "c++"], "action": "block", "applies_to": "input" },
+            { "name": "pii", "pii": ["email", "credit-card"], "action": "redact" },
+            { "name": "tickets", "pattern": "TICKET-\\d+", "action": "redact", "replacement": "[TICKET]" },
+            { "name": "competitors", "keywords": ["acme"], "action": "annotate", "applies_to": "output" }
+        ]
+    }"#;
+
+    #[test]
+    fn blocks_keywords_as_whole_words() {
+        let config = parse_config(CONFIG).unwrap();
+        let mut screening = Screening::new(&config.rules, Direction::Input);
+
+        assert_eq!(
+            screening.text("What is the titanic?"),
+            Ok("What is the titanic?".to_string())
+        );
+        assert_eq!(
+            screening.text("Tell me about project TITAN"),
+            Err(Blocked("project-names".to_string()))
+        );
+        assert_eq!(
+            screening.text("I like C++!"),
+            Err(Blocked("project-names".to_string()))
+        );
+    }
+
+    #[test]
+    fn redacts_in_order() {
+        let config = parse_config(CONFIG).unwrap();
+        let mut screening = Screening::new(&config.rules, Direction::Output);
+
+        assert_eq!(
+            screening.text("Mail jane@example.com about TICKET-42 and TICKET-43, not Acme"),
+            Ok("Mail [REDACTED] about [TICKET] and [TICKET], not Acme".to_string())
+        );
+        let findings = screening.findings();
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[1].rule, "tickets");
+        assert_eq!(findings[1].matches, 2);
+        assert_eq!(findings[2].action, Action::Annotate);
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert!(parse_config(
+            r#"{"backend": "openai", "rules": [{"name": "empty", "action": "block"}]}"#
+        )
+        .is_err());
+        assert!(parse_config(
+            r#"{"backend": "openai", "rules": [{"name": "broken", "pattern": "(", "action": "block"}]}"#
+        )
+        .is_err());
+        assert!(
+            parse_config(r#"{"backend": "openai", "moderation": {"action": "redact"}}"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::guard::{empty_metadata, screen_output};
+use crate::rules::{Direction, Finding, GuardrailsConfig};
+use golem_llm::golem::llm::llm::{
+    ContentPart, Error, GuestChatStream, ResponseMetadata, StreamDelta, StreamEvent, ToolCall,
+};
+use golem_llm_router::backend::RoutedChatStream;
+use golem_rust::wasm_rpc::Pollable;
+use std::cell::RefCell;
+
+/// The chat stream of the backend, with its output screened.
+///
+/// When rules apply to the output, the deltas are held back until the stream finishes, so
+/// matches spanning several deltas are found and nothing is emitted before it was screened. The
+/// screened response is then emitted as a single delta.
+pub struct GuardedChatStream {
+    inner: RoutedChatStream,
+    guard: Option<OutputGuard>,
+}
+
+struct OutputGuard {
+    config: GuardrailsConfig,
+    input_findings: Vec<Finding>,
+    /// The held back deltas, if the output is screened
+    buffered: Option<RefCell<Vec<StreamDelta>>>,
+}
+
+impl GuardedChatStream {
+    pub fn new(
+        inner: RoutedChatStream,
+        config: GuardrailsConfig,
+        input_findings: Vec<Finding>,
+    ) -> Self {
+        let buffered = config
+            .screens(Direction::Output)
+            .then(|| RefCell::new(Vec::new()));
+        Self {
+            inner,
+            guard: Some(OutputGuard {
+                config,
+                input_findings,
+                buffered,
+            }),
+        }
+    }
+
+    pub fn failed(error: Error) -> Self {
+        Self {
+            inner: RoutedChatStream::failed(error),
+            guard: None,
+        }
+    }
+
+    pub fn subscribe(&self) -> Pollable {
+        self.inner.subscribe()
+    }
+
+    fn guarded(&self, events: Vec<StreamEvent>) -> Vec<StreamEvent> {
+        match &self.guard {
+            Some(guard) => events
+                .into_iter()
+                .flat_map(|event| guard.absorb(event))
+                .collect(),
+            None => events,
+        }
+    }
+}
+
+impl OutputGuard {
+    fn absorb(&self, event: StreamEvent) -> Vec<StreamEvent> {
+        match (event, &self.buffered) {
+            (StreamEvent::Delta(delta), Some(buffered)) => {
+                buffered.borrow_mut().push(delta);
+                vec![]
+            }
+            (StreamEvent::Finish(metadata), _) => self.release(metadata),
+            (StreamEvent::Error(error), Some(_)) => {
+                // The screened partial response is still emitted, but not its metadata
+                let mut events: Vec<StreamEvent> = self
+                    .release(empty_metadata())
+                    .into_iter()
+                    .filter(|event| matches!(event, StreamEvent::Delta(_)))
+                    .collect();
+                events.push(StreamEvent::Error(error));
+                events
+            }
+            (event, None) => vec![event],
+        }
+    }
+
+    /// Screens the held back deltas, and emits them with the finishing metadata
+    fn release(&self, metadata: ResponseMetadata) -> Vec<StreamEvent> {
+        let deltas = self
+            .buffered
+            .as_ref()
+            .map(|buffered| buffered.take())
+            .unwrap_or_default();
+        let (content, tool_calls) = merge(deltas);
+
+        match screen_output(
+            &self.config,
+            content,
+            tool_calls,
+            metadata,
+            &self.input_findings,
+        ) {
+            Ok((content, tool_calls, metadata)) => {
+                let mut events = Vec::new();
+                if !content.is_empty() || !tool_calls.is_empty() {
+                    events.push(StreamEvent::Delta(StreamDelta {
+                        content: (!content.is_empty()).then_some(content),
+                        tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+                        reasoning: None,
+                        logprobs: None,
+                    }));
+                }
+                events.push(StreamEvent::Finish(metadata));
+                events
+            }
+            Err(error) => vec![StreamEvent::Error(error)],
+        }
+    }
+}
+
+/// Merges the deltas into a single content, joining the consecutive text parts
+fn merge(deltas: Vec<StreamDelta>) -> (Vec<ContentPart>, Vec<ToolCall>) {
+    let mut content = Vec::new();
+    let mut tool_calls = Vec::new();
+    for delta in deltas {
+        for part in delta.content.unwrap_or_default() {
+            match (content.last_mut(), part) {
+                (Some(ContentPart::Text(text)), ContentPart::Text(more)) => text.push_str(&more),
+                (_, part) => content.push(part),
+            }
+        }
+        tool_calls.extend(delta.tool_calls.unwrap_or_default());
+    }
+    (content, tool_calls)
+}
+
+impl GuestChatStream for GuardedChatStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        self.inner.get_next().map(|events| self.guarded(events))
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        loop {
+            let events = self.inner.blocking_get_next();
+            let finished = events.is_empty();
+            let guarded = self.guarded(events);
+            if finished || !guarded.is_empty() {
+                break guarded;
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        self.inner.cancel();
+        // The held back deltas are dropped, as the response is not screened anymore
+        if let Some(buffered) = self
+            .guard
+            .as_ref()
+            .and_then(|guard| guard.buffered.as_ref())
+        {
+            buffered.borrow_mut().clear();
+        }
+    }
+}
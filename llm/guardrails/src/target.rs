@@ -0,0 +1,54 @@
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{ChatEvent, Config, Guest, Message, ToolCall, ToolResult};
+use golem_llm_router::backend::{Backend, RoutedChatStream};
+use golem_llm_router::RouterComponent;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer};
+
+/// The component the screened requests are forwarded to: one of the embedded providers, or the
+/// router selecting between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Provider(Backend),
+    Router,
+}
+
+impl<'de> Deserialize<'de> for Target {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name == "router" {
+            Ok(Target::Router)
+        } else {
+            Backend::deserialize(IntoDeserializer::<D::Error>::into_deserializer(name))
+                .map(Target::Provider)
+        }
+    }
+}
+
+impl Target {
+    pub fn send(self, messages: Vec<Message>, config: Config) -> ChatEvent {
+        match self {
+            Target::Provider(backend) => backend.send(messages, config),
+            Target::Router => RouterComponent::send(messages, config),
+        }
+    }
+
+    pub fn continue_(
+        self,
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        match self {
+            Target::Provider(backend) => backend.continue_(messages, tool_results, config),
+            Target::Router => RouterComponent::continue_(messages, tool_results, config),
+        }
+    }
+
+    pub fn stream(self, messages: Vec<Message>, config: Config) -> RoutedChatStream {
+        match self {
+            Target::Provider(backend) => backend.stream(messages, config),
+            Target::Router => RouterComponent::unwrapped_stream(messages, config),
+        }
+    }
+}
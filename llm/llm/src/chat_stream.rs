@@ -1,9 +1,23 @@
 use crate::event_source::{Event, EventSource, MessageEvent};
 use crate::golem::llm::llm::{Error, ErrorCode, GuestChatStream, StreamEvent};
+use crate::interceptor;
+use crate::metering;
+use crate::telemetry::{self, CallInfo};
 use golem_rust::wasm_rpc::Pollable;
-use std::cell::{Ref, RefMut};
+use log::warn;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::VecDeque;
 use std::task::Poll;
 
+/// Environment variable setting how many events `get-next` waits for before returning them
+pub const STREAM_MIN_EVENTS_ENV_VAR: &str = "GOLEM_LLM_STREAM_MIN_EVENTS";
+
+/// Environment variable limiting how many events a single `get-next` call returns
+pub const STREAM_MAX_EVENTS_ENV_VAR: &str = "GOLEM_LLM_STREAM_MAX_EVENTS";
+
+/// Environment variable limiting how many decoded events a stream holds for its caller
+pub const STREAM_MAX_BUFFERED_EVENTS_ENV_VAR: &str = "GOLEM_LLM_STREAM_MAX_BUFFERED_EVENTS";
+
 pub trait LlmChatStreamState: 'static {
     fn failure(&self) -> &Option<Error>;
     fn is_finished(&self) -> bool;
@@ -11,17 +25,142 @@ pub trait LlmChatStreamState: 'static {
     fn stream(&self) -> Ref<Option<EventSource>>;
     fn stream_mut(&self) -> RefMut<Option<EventSource>>;
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String>;
+
+    /// Takes an event held back by the last `decode_message` call, for providers sending more
+    /// than one event in a single message, such as the last delta together with the usage
+    fn take_pending_event(&self) -> Option<StreamEvent> {
+        None
+    }
+}
+
+/// How the events of a stream are batched into the results of `get-next`. Events are decoded from
+/// the response only when the caller asks for them, and the response is not read further while
+/// `max_buffered` events are waiting, so a slow caller does not accumulate deltas in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamBatching {
+    /// The events to wait for before returning them, unless the stream finishes or fails first
+    pub min_events: usize,
+    /// The most events returned at once, the others are kept for the next call
+    pub max_events: usize,
+    /// The most decoded events held for the caller
+    pub max_buffered: usize,
+}
+
+impl Default for StreamBatching {
+    fn default() -> Self {
+        Self {
+            min_events: 1,
+            max_events: usize::MAX,
+            max_buffered: 256,
+        }
+    }
+}
+
+impl StreamBatching {
+    /// Every limit is at least one event, and a batch never waits for more events than it can
+    /// return at once
+    pub fn new(min_events: usize, max_events: usize, max_buffered: usize) -> Self {
+        let max_events = max_events.max(1);
+        Self {
+            min_events: min_events.clamp(1, max_events),
+            max_events,
+            max_buffered: max_buffered.max(1),
+        }
+    }
+
+    /// The defaults, overridden by `GOLEM_LLM_STREAM_MIN_EVENTS`, `GOLEM_LLM_STREAM_MAX_EVENTS`
+    /// and `GOLEM_LLM_STREAM_MAX_BUFFERED_EVENTS`
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self::new(
+            env_limit(STREAM_MIN_EVENTS_ENV_VAR).unwrap_or(default.min_events),
+            env_limit(STREAM_MAX_EVENTS_ENV_VAR).unwrap_or(default.max_events),
+            env_limit(STREAM_MAX_BUFFERED_EVENTS_ENV_VAR).unwrap_or(default.max_buffered),
+        )
+    }
+
+    fn should_poll(&self, buffered: usize) -> bool {
+        buffered < self.min_events.min(self.max_buffered)
+    }
+
+    /// Takes the next batch once the buffer holds enough events, an error, or the rest of a
+    /// finished stream
+    fn take_batch(
+        &self,
+        buffer: &mut VecDeque<StreamEvent>,
+        finished: bool,
+    ) -> Option<Vec<StreamEvent>> {
+        let ready = finished
+            || !self.should_poll(buffer.len())
+            || buffer
+                .iter()
+                .any(|event| matches!(event, StreamEvent::Error(_)));
+        if ready {
+            let len = buffer.len().min(self.max_events);
+            Some(buffer.drain(..len).collect())
+        } else {
+            None
+        }
+    }
+}
+
+fn env_limit(name: &str) -> Option<usize> {
+    let raw = std::env::var(name).ok()?;
+    match raw.trim().parse::<usize>() {
+        Ok(limit) => Some(limit),
+        Err(err) => {
+            warn!("Ignoring invalid {name}: {err}");
+            None
+        }
+    }
+}
+
+/// Decodes a message of the response into its event, followed by the event the provider held back
+/// from it, if any
+pub(crate) fn decode_events(
+    implementation: &impl LlmChatStreamState,
+    data: &str,
+) -> Vec<StreamEvent> {
+    match implementation.decode_message(data) {
+        Ok(Some(stream_event)) => {
+            let mut events = vec![stream_event];
+            events.extend(implementation.take_pending_event());
+            events
+        }
+        // Ignored event
+        Ok(None) => vec![],
+        Err(error) => vec![StreamEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: error,
+            provider_error_json: None,
+        })],
+    }
 }
 
 pub struct LlmChatStream<T> {
     implementation: T,
+    call: Option<CallInfo>,
+    batching: StreamBatching,
+    buffer: RefCell<VecDeque<StreamEvent>>,
 }
 
 impl<T: LlmChatStreamState> LlmChatStream<T> {
     pub fn new(implementation: T) -> Self {
-        Self { implementation }
+        Self {
+            implementation,
+            call: telemetry::current_call(),
+            batching: StreamBatching::from_env(),
+            buffer: RefCell::new(VecDeque::new()),
+        }
+    }
+
+    pub fn with_batching(mut self, batching: StreamBatching) -> Self {
+        self.batching = batching;
+        self
     }
 
+    /// Events still buffered are returned by `get-next` before the response is read further, so
+    /// callers check `get-next` before blocking on this pollable, which waits for the response
     pub fn subscribe(&self) -> Pollable {
         if let Some(stream) = self.implementation.stream().as_ref() {
             stream.subscribe()
@@ -29,26 +168,21 @@ impl<T: LlmChatStreamState> LlmChatStream<T> {
             golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0)
         }
     }
-}
-
-impl<T: LlmChatStreamState> GuestChatStream for LlmChatStream<T> {
-    fn get_next(&self) -> Option<Vec<StreamEvent>> {
-        if self.implementation.is_finished() {
-            return Some(vec![]);
-        }
 
+    /// Decodes the events of the next message of the response, if one has arrived
+    fn poll_events(&self) -> Poll<Vec<StreamEvent>> {
         let mut stream = self.implementation.stream_mut();
         if let Some(stream) = stream.as_mut() {
             match stream.poll_next() {
                 Poll::Ready(None) => {
                     self.implementation.set_finished();
-                    Some(vec![])
+                    Poll::Ready(vec![])
                 }
                 Poll::Ready(Some(Err(crate::event_source::error::Error::StreamEnded))) => {
                     self.implementation.set_finished();
-                    Some(vec![])
+                    Poll::Ready(vec![])
                 }
-                Poll::Ready(Some(Err(error))) => Some(vec![StreamEvent::Error(Error {
+                Poll::Ready(Some(Err(error))) => Poll::Ready(vec![StreamEvent::Error(Error {
                     code: ErrorCode::InternalError,
                     message: error.to_string(),
                     provider_error_json: None,
@@ -60,56 +194,171 @@ impl<T: LlmChatStreamState> GuestChatStream for LlmChatStream<T> {
                         Event::Open => {}
                         Event::Message(MessageEvent { data, .. }) => {
                             if data != "[DONE]" {
-                                match self.implementation.decode_message(&data) {
-                                    Ok(Some(stream_event)) => {
-                                        if matches!(stream_event, StreamEvent::Finish(_)) {
-                                            self.implementation.set_finished();
+                                events = decode_events(&self.implementation, &data);
+                                let provider =
+                                    self.call.as_ref().map(|call| call.provider()).unwrap_or("");
+                                for event in &mut events {
+                                    interceptor::intercept_stream_event(provider, event);
+                                    if let StreamEvent::Finish(metadata) = event {
+                                        self.implementation.set_finished();
+                                        if let Some(call) = &self.call {
+                                            let currency = metering::currency();
+                                            metadata.cost = metering::response_cost(
+                                                &metering::prices(&currency),
+                                                currency,
+                                                call.provider(),
+                                                call.model(),
+                                                metadata.usage.as_ref(),
+                                            );
+                                            telemetry::record_stream_finish(call, metadata);
                                         }
-                                        events.push(stream_event);
-                                    }
-                                    Ok(None) => {
-                                        // Ignored event
-                                    }
-                                    Err(error) => {
-                                        events.push(StreamEvent::Error(Error {
-                                            code: ErrorCode::InternalError,
-                                            message: error,
-                                            provider_error_json: None,
-                                        }));
                                     }
                                 }
                             }
                         }
                     }
 
-                    if events.is_empty() {
-                        None
-                    } else {
-                        Some(events)
-                    }
+                    Poll::Ready(events)
                 }
-                Poll::Pending => None,
+                Poll::Pending => Poll::Pending,
             }
         } else if let Some(error) = self.implementation.failure().clone() {
             self.implementation.set_finished();
-            Some(vec![StreamEvent::Error(error)])
+            Poll::Ready(vec![StreamEvent::Error(error)])
         } else {
-            None
+            Poll::Pending
+        }
+    }
+}
+
+impl<T: LlmChatStreamState> GuestChatStream for LlmChatStream<T> {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        let mut buffer = self.buffer.borrow_mut();
+        while self.batching.should_poll(buffer.len()) && !self.implementation.is_finished() {
+            match self.poll_events() {
+                Poll::Ready(events) => {
+                    let failed = events
+                        .iter()
+                        .any(|event| matches!(event, StreamEvent::Error(_)));
+                    buffer.extend(events);
+                    if failed {
+                        break;
+                    }
+                }
+                Poll::Pending => break,
+            }
         }
+        self.batching
+            .take_batch(&mut buffer, self.implementation.is_finished())
     }
 
     fn blocking_get_next(&self) -> Vec<StreamEvent> {
-        let pollable = self.subscribe();
-        let mut result = Vec::new();
         loop {
-            pollable.block();
-            match self.get_next() {
-                Some(events) => {
-                    result.extend(events);
-                    break result;
-                }
-                None => continue,
+            // Events held back by the previous call are returned without waiting for the response
+            if let Some(events) = self.get_next() {
+                break events;
             }
+            self.subscribe().block();
         }
     }
+
+    fn cancel(&self) {
+        // Dropping the event source closes the HTTP response
+        self.implementation.stream_mut().take();
+        self.implementation.set_finished();
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat_stream::StreamBatching;
+    use crate::golem::llm::llm::{ContentPart, Error, ErrorCode, StreamDelta, StreamEvent};
+    use std::collections::VecDeque;
+
+    fn delta(text: &str) -> StreamEvent {
+        StreamEvent::Delta(StreamDelta {
+            content: Some(vec![ContentPart::Text(text.to_string())]),
+            tool_calls: None,
+            reasoning: None,
+            logprobs: None,
+        })
+    }
+
+    #[test]
+    fn keeps_the_limits_consistent() {
+        assert_eq!(
+            StreamBatching::new(0, 0, 0),
+            StreamBatching {
+                min_events: 1,
+                max_events: 1,
+                max_buffered: 1
+            }
+        );
+        assert_eq!(StreamBatching::new(10, 4, 64).min_events, 4);
+    }
+
+    #[test]
+    fn returns_batches_within_the_limits() {
+        let batching = StreamBatching::new(2, 3, 64);
+        let mut buffer = VecDeque::from([delta("a")]);
+        assert_eq!(batching.take_batch(&mut buffer, false), None);
+
+        buffer.extend([delta("b"), delta("c"), delta("d")]);
+        assert_eq!(
+            batching.take_batch(&mut buffer, false),
+            Some(vec![delta("a"), delta("b"), delta("c")])
+        );
+        assert_eq!(batching.take_batch(&mut buffer, false), None);
+        // The rest of a finished stream is returned without waiting for more events
+        assert_eq!(
+            batching.take_batch(&mut buffer, true),
+            Some(vec![delta("d")])
+        );
+        assert_eq!(batching.take_batch(&mut buffer, true), Some(vec![]));
+    }
+
+    #[test]
+    fn waits_for_the_stream_with_fewer_than_min_events_left() {
+        let batching = StreamBatching::new(3, 3, 64);
+        let mut buffer = VecDeque::from([delta("a"), delta("b"), delta("c"), delta("d")]);
+        assert_eq!(
+            batching.take_batch(&mut buffer, false),
+            Some(vec![delta("a"), delta("b"), delta("c")])
+        );
+        // The leftover event is held until the stream delivers more or finishes
+        assert_eq!(batching.take_batch(&mut buffer, false), None);
+        assert!(batching.should_poll(buffer.len()));
+        assert_eq!(
+            batching.take_batch(&mut buffer, true),
+            Some(vec![delta("d")])
+        );
+    }
+
+    #[test]
+    fn returns_errors_without_waiting() {
+        let batching = StreamBatching::new(8, 8, 64);
+        let error = StreamEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "Connection lost".to_string(),
+            provider_error_json: None,
+        });
+        let mut buffer = VecDeque::from([delta("a"), error.clone()]);
+        assert_eq!(
+            batching.take_batch(&mut buffer, false),
+            Some(vec![delta("a"), error])
+        );
+    }
+
+    #[test]
+    fn stops_reading_when_the_buffer_is_full() {
+        let batching = StreamBatching::new(8, 8, 2);
+        assert!(batching.should_poll(1));
+        assert!(!batching.should_poll(2));
+        let mut buffer = VecDeque::from([delta("a"), delta("b")]);
+        assert_eq!(
+            batching.take_batch(&mut buffer, false),
+            Some(vec![delta("a"), delta("b")])
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use crate::golem::llm::llm::{Error, ErrorCode};
+use crate::golem::llm::llm::{Config, Error, ErrorCode};
+use golem_ai_common::config::config_value;
+use serde_json::Value;
 use std::ffi::OsStr;
 
 /// Gets an expected configuration value from the environment, and fails if its is not found
@@ -8,16 +10,144 @@ pub fn with_config_key<R>(
     fail: impl FnOnce(Error) -> R,
     succeed: impl FnOnce(String) -> R,
 ) -> R {
-    let key_str = key.as_ref().to_string_lossy().to_string();
-    match std::env::var(key) {
+    match config_value(key) {
         Ok(value) => succeed(value),
-        Err(_) => {
-            let error = Error {
-                code: ErrorCode::InternalError,
-                message: format!("Missing config key: {key_str}"),
-                provider_error_json: None,
-            };
-            fail(error)
+        Err(message) => fail(Error {
+            code: ErrorCode::InternalError,
+            message,
+            provider_error_json: None,
+        }),
+    }
+}
+
+/// Provider option overriding the API key configured through the provider's environment variable
+pub const API_KEY_OPTION: &str = "api_key";
+
+/// Provider option passing an OAuth access token, for providers accepting one in place of an API
+/// key, like Azure OpenAI with Microsoft Entra ID
+pub const ACCESS_TOKEN_OPTION: &str = "access_token";
+
+/// Provider option overriding the base URL of the provider's API
+pub const BASE_URL_OPTION: &str = "base_url";
+
+/// Provider options holding credentials. These are never persisted in the oplog.
+pub const CREDENTIAL_OPTIONS: &[&str] = &[API_KEY_OPTION, ACCESS_TOKEN_OPTION];
+
+/// Gets the value of a provider option passed in the request's `Config`
+pub fn provider_option(config: &Config, key: &str) -> Option<String> {
+    config
+        .provider_options
+        .iter()
+        .find(|kv| kv.key == key)
+        .map(|kv| kv.value.clone())
+}
+
+/// Like `with_config_key`, but if `value` is defined (typically a provider option passed with the
+/// request) it takes precedence over the environment variable. This allows a single worker to
+/// serve requests with different credentials.
+pub fn with_config_key_or<R>(
+    value: Option<String>,
+    key: impl AsRef<OsStr>,
+    fail: impl FnOnce(Error) -> R,
+    succeed: impl FnOnce(String) -> R,
+) -> R {
+    match value {
+        Some(value) => succeed(value),
+        None => with_config_key(key, fail, succeed),
+    }
+}
+
+/// Removes the credential provider options from a `Config`
+pub fn without_credentials(mut config: Config) -> Config {
+    config
+        .provider_options
+        .retain(|kv| !CREDENTIAL_OPTIONS.contains(&kv.key.as_str()));
+    config
+}
+
+/// Parses the schema of a `json-schema` response format
+pub fn parse_response_schema(schema: &str) -> Result<Value, Error> {
+    serde_json::from_str(schema).map_err(|err| Error {
+        code: ErrorCode::InvalidRequest,
+        message: format!("Failed to parse the JSON schema of the response format: {err}"),
+        provider_error_json: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        parse_response_schema, provider_option, with_config_key_or, without_credentials,
+        API_KEY_OPTION,
+    };
+    use crate::golem::llm::llm::{Config, ErrorCode, Kv};
+
+    fn config() -> Config {
+        Config {
+            model: "model".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![
+                Kv {
+                    key: API_KEY_OPTION.to_string(),
+                    value: "secret".to_string(),
+                },
+                Kv {
+                    key: "top_p".to_string(),
+                    value: "0.5".to_string(),
+                },
+            ],
         }
     }
+
+    #[test]
+    fn option_takes_precedence_over_environment() {
+        let result = with_config_key_or(
+            provider_option(&config(), API_KEY_OPTION),
+            "GOLEM_LLM_TEST_UNDEFINED_KEY",
+            |err| Err(err.message),
+            Ok,
+        );
+        assert_eq!(result, Ok("secret".to_string()));
+    }
+
+    #[test]
+    fn falls_back_to_environment() {
+        let result = with_config_key_or(
+            provider_option(&config(), "missing"),
+            "GOLEM_LLM_TEST_UNDEFINED_KEY",
+            |err| Err(err.message),
+            Ok,
+        );
+        assert_eq!(
+            result,
+            Err("Missing config key: GOLEM_LLM_TEST_UNDEFINED_KEY".to_string())
+        );
+    }
+
+    #[test]
+    fn credentials_are_removed() {
+        let config = without_credentials(config());
+        assert_eq!(provider_option(&config, API_KEY_OPTION), None);
+        assert_eq!(provider_option(&config, "top_p"), Some("0.5".to_string()));
+    }
+
+    #[test]
+    fn invalid_response_schema_is_an_invalid_request() {
+        assert!(parse_response_schema(r#"{"type": "object"}"#).is_ok());
+        assert_eq!(
+            parse_response_schema("{").unwrap_err().code,
+            ErrorCode::InvalidRequest
+        );
+    }
 }
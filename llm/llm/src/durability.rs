@@ -1,4 +1,6 @@
-use crate::golem::llm::llm::{Config, ContentPart, Guest, Message, Role, StreamDelta};
+use crate::error::unsupported;
+use crate::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult};
+use crate::golem::llm::llm::{Config, ContentPart, Error, Guest, Message, Role, StreamDelta};
 use golem_rust::wasm_rpc::Pollable;
 use std::marker::PhantomData;
 
@@ -27,6 +29,7 @@ pub trait ExtendedGuest: Guest + 'static {
                                         Do not include the part of the response that was already seen.".to_string()),
                 ContentPart::Text("Here is the original question:".to_string()),
             ],
+            cache_control: None,
         });
         extended_messages.extend_from_slice(original_messages);
 
@@ -54,26 +57,54 @@ pub trait ExtendedGuest: Guest + 'static {
             .into_iter()
             .chain(partial_result_as_content)
             .collect(),
+            cache_control: None,
         });
         extended_messages
     }
 
     fn subscribe(stream: &Self::ChatStream) -> Pollable;
+
+    /// Submits a batch of requests, for providers with a batch API
+    fn submit_batch(_requests: Vec<BatchRequest>) -> Result<BatchInfo, Error> {
+        Err(unsupported("batches"))
+    }
+
+    /// Gets the progress of a batch submitted with `submit_batch`
+    fn poll_batch(_batch_id: String) -> Result<BatchInfo, Error> {
+        Err(unsupported("batches"))
+    }
+
+    /// Gets the results of a completed batch submitted with `submit_batch`
+    fn get_batch_results(_batch_id: String) -> Result<Vec<BatchResult>, Error> {
+        Err(unsupported("batches"))
+    }
 }
 
 /// When the durability feature flag is off, wrapping with `DurableLLM` is just a passthrough
 #[cfg(not(feature = "durability"))]
 mod passthrough_impl {
     use crate::durability::{DurableLLM, ExtendedGuest};
+    use crate::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult, Guest as BatchGuest};
     use crate::golem::llm::llm::{
-        ChatEvent, ChatStream, Config, Guest, Message, ToolCall, ToolResult,
+        ChatEvent, ChatStream, Config, Error, Guest, GuestChatStream, Message, StreamEvent,
+        ToolCall, ToolResult,
     };
+    use crate::metering::add_cost;
+    use crate::session::StreamRecorder;
+    use crate::telemetry::Span;
+    use crate::timeout;
+    use std::cell::RefCell;
 
     impl<Impl: ExtendedGuest> Guest for DurableLLM<Impl> {
-        type ChatStream = Impl::ChatStream;
+        type ChatStream = PassthroughChatStream<Impl>;
 
         fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
-            Impl::send(messages, config)
+            let span = Span::start("send", &config.model);
+            let _timeouts = timeout::scope(&config);
+            let mut result = Impl::send(messages, config);
+            add_cost(&mut result);
+            span.finish_with_event(&result);
+            result
         }
 
         fn continue_(
@@ -81,11 +112,77 @@ mod passthrough_impl {
             tool_results: Vec<(ToolCall, ToolResult)>,
             config: Config,
         ) -> ChatEvent {
-            Impl::continue_(messages, tool_results, config)
+            let span = Span::start("continue", &config.model);
+            let _timeouts = timeout::scope(&config);
+            let mut result = Impl::continue_(messages, tool_results, config);
+            add_cost(&mut result);
+            span.finish_with_event(&result);
+            result
         }
 
         fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
-            Impl::stream(messages, config)
+            let span = Span::start("stream", &config.model);
+            let _timeouts = timeout::scope(&config);
+            let result = ChatStream::new(PassthroughChatStream::<Impl> {
+                stream: Impl::unwrapped_stream(messages, config),
+                recorder: RefCell::new(None),
+            });
+            span.finish_stream();
+            result
+        }
+    }
+
+    /// The stream of the provider, which a session can record the response of
+    pub struct PassthroughChatStream<Impl: ExtendedGuest> {
+        stream: Impl::ChatStream,
+        recorder: RefCell<Option<StreamRecorder>>,
+    }
+
+    impl<Impl: ExtendedGuest> PassthroughChatStream<Impl> {
+        pub(crate) fn record_into(&self, recorder: StreamRecorder) {
+            *self.recorder.borrow_mut() = Some(recorder);
+        }
+    }
+
+    impl<Impl: ExtendedGuest> GuestChatStream for PassthroughChatStream<Impl> {
+        fn get_next(&self) -> Option<Vec<StreamEvent>> {
+            let result = self.stream.get_next();
+            if let (Some(events), Some(recorder)) = (&result, &mut *self.recorder.borrow_mut()) {
+                recorder.record(events);
+            }
+            result
+        }
+
+        fn blocking_get_next(&self) -> Vec<StreamEvent> {
+            let events = self.stream.blocking_get_next();
+            if let Some(recorder) = &mut *self.recorder.borrow_mut() {
+                recorder.record(&events);
+            }
+            events
+        }
+
+        fn cancel(&self) {
+            self.stream.cancel();
+            if let Some(recorder) = &mut *self.recorder.borrow_mut() {
+                recorder.cancel();
+            }
+        }
+    }
+
+    impl<Impl: ExtendedGuest> BatchGuest for DurableLLM<Impl> {
+        fn submit_batch(requests: Vec<BatchRequest>) -> Result<BatchInfo, Error> {
+            let _timeouts = requests
+                .first()
+                .map(|request| timeout::scope(&request.config));
+            Impl::submit_batch(requests)
+        }
+
+        fn poll_batch(batch_id: String) -> Result<BatchInfo, Error> {
+            Impl::poll_batch(batch_id)
+        }
+
+        fn get_results(batch_id: String) -> Result<Vec<BatchResult>, Error> {
+            Impl::get_batch_results(batch_id)
         }
     }
 }
@@ -95,16 +192,22 @@ mod passthrough_impl {
 /// the `golem-rust` helper library.
 ///
 /// There will be custom durability entries saved in the oplog, with the full LLM request and configuration
-/// stored as input, and the full response stored as output. To serialize these in a way it is
+/// stored as input (except for credentials passed as provider options), and the full response stored as output. To serialize these in a way it is
 /// observable by oplog consumers, each relevant data type has to be converted to/from `ValueAndType`
 /// which is implemented using the type classes and builder in the `golem-rust` library.
 #[cfg(feature = "durability")]
 mod durable_impl {
+    use crate::config::without_credentials;
     use crate::durability::{DurableLLM, ExtendedGuest};
+    use crate::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult, Guest as BatchGuest};
     use crate::golem::llm::llm::{
-        ChatEvent, ChatStream, Config, Guest, GuestChatStream, Message, StreamDelta, StreamEvent,
-        ToolCall, ToolResult,
+        ChatEvent, ChatStream, Config, Error, Guest, GuestChatStream, Message, StreamDelta,
+        StreamEvent, ToolCall, ToolResult,
     };
+    use crate::metering::add_cost;
+    use crate::session::StreamRecorder;
+    use crate::telemetry::Span;
+    use crate::timeout;
     use golem_rust::bindings::golem::durability::durability::{
         DurableFunctionType, LazyInitializedPollable,
     };
@@ -124,9 +227,14 @@ mod durable_impl {
                 DurableFunctionType::WriteRemote,
             );
             if durability.is_live() {
-                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                let span = Span::start("send", &config.model);
+                let _timeouts = timeout::scope(&config);
+                let mut result = with_persistence_level(PersistenceLevel::PersistNothing, || {
                     Impl::send(messages.clone(), config.clone())
                 });
+                add_cost(&mut result);
+                span.finish_with_event(&result);
+                let config = without_credentials(config);
                 durability.persist_infallible(SendInput { messages, config }, result)
             } else {
                 durability.replay_infallible()
@@ -144,14 +252,18 @@ mod durable_impl {
                 DurableFunctionType::WriteRemote,
             );
             if durability.is_live() {
-                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                let span = Span::start("continue", &config.model);
+                let _timeouts = timeout::scope(&config);
+                let mut result = with_persistence_level(PersistenceLevel::PersistNothing, || {
                     Impl::continue_(messages.clone(), tool_results.clone(), config.clone())
                 });
+                add_cost(&mut result);
+                span.finish_with_event(&result);
                 durability.persist_infallible(
                     ContinueInput {
                         messages,
                         tool_results,
-                        config,
+                        config: without_credentials(config),
                     },
                     result,
                 )
@@ -167,13 +279,22 @@ mod durable_impl {
                 DurableFunctionType::WriteRemote,
             );
             if durability.is_live() {
+                let span = Span::start("stream", &config.model);
+                let _timeouts = timeout::scope(&config);
                 let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
                     ChatStream::new(DurableChatStream::<Impl>::live(Impl::unwrapped_stream(
                         messages.clone(),
                         config.clone(),
                     )))
                 });
-                let _ = durability.persist_infallible(SendInput { messages, config }, NoOutput);
+                span.finish_stream();
+                let _ = durability.persist_infallible(
+                    SendInput {
+                        messages,
+                        config: without_credentials(config),
+                    },
+                    NoOutput,
+                );
                 result
             } else {
                 let _: NoOutput = durability.replay_infallible();
@@ -182,6 +303,66 @@ mod durable_impl {
         }
     }
 
+    impl<Impl: ExtendedGuest> BatchGuest for DurableLLM<Impl> {
+        fn submit_batch(requests: Vec<BatchRequest>) -> Result<BatchInfo, Error> {
+            let durability = Durability::<BatchInfo, Error>::new(
+                "golem_llm",
+                "submit_batch",
+                DurableFunctionType::WriteRemote,
+            );
+            if durability.is_live() {
+                let _timeouts = requests
+                    .first()
+                    .map(|request| timeout::scope(&request.config));
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    Impl::submit_batch(requests.clone())
+                });
+                let requests = requests
+                    .into_iter()
+                    .map(|request| BatchRequest {
+                        config: without_credentials(request.config),
+                        ..request
+                    })
+                    .collect::<Vec<_>>();
+                durability.persist(SubmitBatchInput { requests }, result)
+            } else {
+                durability.replay()
+            }
+        }
+
+        fn poll_batch(batch_id: String) -> Result<BatchInfo, Error> {
+            let durability = Durability::<BatchInfo, Error>::new(
+                "golem_llm",
+                "poll_batch",
+                DurableFunctionType::ReadRemote,
+            );
+            if durability.is_live() {
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    Impl::poll_batch(batch_id.clone())
+                });
+                durability.persist(BatchIdInput { batch_id }, result)
+            } else {
+                durability.replay()
+            }
+        }
+
+        fn get_results(batch_id: String) -> Result<Vec<BatchResult>, Error> {
+            let durability = Durability::<Vec<BatchResult>, Error>::new(
+                "golem_llm",
+                "get_results",
+                DurableFunctionType::ReadRemote,
+            );
+            if durability.is_live() {
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    Impl::get_batch_results(batch_id.clone())
+                });
+                durability.persist(BatchIdInput { batch_id }, result)
+            } else {
+                durability.replay()
+            }
+        }
+    }
+
     /// Represents the durable chat stream's state
     ///
     /// In live mode it directly calls the underlying LLM stream which is implemented on
@@ -211,6 +392,7 @@ mod durable_impl {
     pub struct DurableChatStream<Impl: ExtendedGuest> {
         state: RefCell<Option<DurableChatStreamState<Impl>>>,
         subscription: RefCell<Option<Pollable>>,
+        recorder: RefCell<Option<StreamRecorder>>,
     }
 
     impl<Impl: ExtendedGuest> DurableChatStream<Impl> {
@@ -221,6 +403,7 @@ mod durable_impl {
                     pollables: Vec::new(),
                 })),
                 subscription: RefCell::new(None),
+                recorder: RefCell::new(None),
             }
         }
 
@@ -234,9 +417,16 @@ mod durable_impl {
                     finished: false,
                 })),
                 subscription: RefCell::new(None),
+                recorder: RefCell::new(None),
             }
         }
 
+        /// Lets a session record the response in its history. The replayed events are recorded
+        /// too, as the history of a recovered session is rebuilt by the replay.
+        pub(crate) fn record_into(&self, recorder: StreamRecorder) {
+            *self.recorder.borrow_mut() = Some(recorder);
+        }
+
         fn subscribe(&self) -> Pollable {
             let mut state = self.state.borrow_mut();
             match &mut *state {
@@ -275,8 +465,8 @@ mod durable_impl {
         }
     }
 
-    impl<Impl: ExtendedGuest> GuestChatStream for DurableChatStream<Impl> {
-        fn get_next(&self) -> Option<Vec<StreamEvent>> {
+    impl<Impl: ExtendedGuest> DurableChatStream<Impl> {
+        fn durable_get_next(&self) -> Option<Vec<StreamEvent>> {
             let durability = Durability::<Option<Vec<StreamEvent>>, UnusedError>::new(
                 "golem_llm",
                 "get_next",
@@ -300,17 +490,27 @@ mod durable_impl {
                         finished,
                     }) => {
                         if *finished {
-                            (None, None)
+                            // Like a finished or cancelled live stream, the stream has no more
+                            // events, and is not waited for by `blocking_get_next`
+                            (
+                                durability.persist_infallible(NoInput, Some(Vec::new())),
+                                None,
+                            )
                         } else {
                             let extended_messages =
                                 Impl::retry_prompt(original_messages, partial_result);
 
                             let (stream, first_live_result) =
                                 with_persistence_level(PersistenceLevel::PersistNothing, || {
+                                    let _timeouts = timeout::scope(config);
+                                    // Like in `stream`, so the resumed stream reports its usage
+                                    // and the cost of its response
+                                    let span = Span::start("stream", &config.model);
                                     let stream = <Impl as ExtendedGuest>::unwrapped_stream(
                                         extended_messages,
                                         config.clone(),
                                     );
+                                    span.finish_stream();
 
                                     for lazy_initialized_pollable in pollables {
                                         lazy_initialized_pollable.set(Impl::subscribe(&stream));
@@ -376,22 +576,46 @@ mod durable_impl {
                 result
             }
         }
+    }
+
+    impl<Impl: ExtendedGuest> GuestChatStream for DurableChatStream<Impl> {
+        fn get_next(&self) -> Option<Vec<StreamEvent>> {
+            let result = self.durable_get_next();
+            if let (Some(events), Some(recorder)) = (&result, &mut *self.recorder.borrow_mut()) {
+                recorder.record(events);
+            }
+            result
+        }
 
         fn blocking_get_next(&self) -> Vec<StreamEvent> {
-            let mut subscription = self.subscription.borrow_mut();
-            if subscription.is_none() {
-                *subscription = Some(self.subscribe());
-            }
-            let subscription = subscription.as_mut().unwrap();
-            let mut result = Vec::new();
             loop {
-                subscription.block();
-                match self.get_next() {
-                    Some(events) => {
-                        result.extend(events);
-                        break result;
-                    }
-                    None => continue,
+                // The underlying stream may hold back events it already received, which are
+                // returned without waiting, and a replayed stream is replaced by a live one
+                if let Some(events) = self.get_next() {
+                    break events;
+                }
+                // Subscribing in replay mode adds a pollable to attach to the live stream, so
+                // the stream is only subscribed to once
+                let mut subscription = self.subscription.borrow_mut();
+                subscription.get_or_insert_with(|| self.subscribe()).block();
+            }
+        }
+
+        fn cancel(&self) {
+            if let Some(recorder) = &mut *self.recorder.borrow_mut() {
+                recorder.cancel();
+            }
+            match &mut *self.state.borrow_mut() {
+                Some(DurableChatStreamState::Live { stream, .. }) => {
+                    with_persistence_level(PersistenceLevel::PersistNothing, || stream.cancel());
+                }
+                Some(DurableChatStreamState::Replay { finished, .. }) => {
+                    // A stream cancelled before the worker got interrupted is not resumed at the
+                    // end of the replay
+                    *finished = true;
+                }
+                None => {
+                    unreachable!()
                 }
             }
         }
@@ -410,6 +634,16 @@ mod durable_impl {
         config: Config,
     }
 
+    #[derive(Debug, IntoValue)]
+    struct SubmitBatchInput {
+        requests: Vec<BatchRequest>,
+    }
+
+    #[derive(Debug, IntoValue)]
+    struct BatchIdInput {
+        batch_id: String,
+    }
+
     #[derive(Debug, IntoValue)]
     struct NoInput;
 
@@ -428,10 +662,12 @@ mod durable_impl {
     #[cfg(test)]
     mod tests {
         use crate::durability::durable_impl::SendInput;
+        use crate::golem::llm::batch::{BatchInfo, BatchResult, BatchStatus};
         use crate::golem::llm::llm::{
-            ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason,
-            ImageDetail, ImageReference, ImageSource, ImageUrl, Message, ResponseMetadata, Role,
-            ToolCall, Usage,
+            AudioReference, AudioSource, BuiltInTool, BuiltInToolResult, CacheControl, ChatEvent,
+            Citation, CitedText, CompleteResponse, Config, ContentPart, Cost, Error, ErrorCode,
+            FinishReason, ImageDetail, ImageReference, ImageSource, ImageUrl, Message,
+            ReasoningEffort, ResponseMetadata, Role, ToolCall, ToolChoice, Usage,
         };
         use golem_rust::value_and_type::{FromValueAndType, IntoValueAndType};
         use golem_rust::wasm_rpc::WitTypeNode;
@@ -504,6 +740,25 @@ mod durable_impl {
                 mime_type: "image/jpeg".to_string(),
                 detail: Some(ImageDetail::Auto),
             })));
+            roundtrip_test(ContentPart::Audio(AudioReference::Url(
+                "https://example.com/clip.mp3".to_string(),
+            )));
+            roundtrip_test(ContentPart::Audio(AudioReference::Inline(AudioSource {
+                data: vec![0, 1, 2, 3, 4, 5],
+                mime_type: "audio/wav".to_string(),
+            })));
+            roundtrip_test(ContentPart::CitedText(CitedText {
+                text: "Golem runs durable agents".to_string(),
+                citations: vec![Citation {
+                    url: Some("https://golem.cloud".to_string()),
+                    title: None,
+                    cited_text: Some("Durable agents".to_string()),
+                }],
+            }));
+            roundtrip_test(ContentPart::BuiltInToolResult(BuiltInToolResult {
+                tool: BuiltInTool::CodeInterpreter,
+                output_json: r#"{"stdout":"4"}"#.to_string(),
+            }));
         }
 
         #[test]
@@ -512,11 +767,13 @@ mod durable_impl {
                 input_tokens: Some(100),
                 output_tokens: Some(200),
                 total_tokens: Some(300),
+                cached_tokens: Some(50),
             });
             roundtrip_test(Usage {
                 input_tokens: None,
                 output_tokens: None,
                 total_tokens: None,
+                cached_tokens: None,
             });
         }
 
@@ -528,10 +785,17 @@ mod durable_impl {
                     input_tokens: Some(100),
                     output_tokens: None,
                     total_tokens: Some(100),
+                    cached_tokens: None,
                 }),
                 provider_id: Some("provider_id".to_string()),
                 timestamp: Some("2023-10-01T00:00:00Z".to_string()),
                 provider_metadata_json: Some("{\"key\": \"value\"}".to_string()),
+                logprobs: None,
+                cost: Some(Cost {
+                    input_cost: 0.00025,
+                    output_cost: 0.0,
+                    currency: "USD".to_string(),
+                }),
             });
             roundtrip_test(ResponseMetadata {
                 finish_reason: None,
@@ -539,6 +803,8 @@ mod durable_impl {
                 provider_id: None,
                 timestamp: None,
                 provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
             });
         }
 
@@ -558,12 +824,15 @@ mod durable_impl {
                     name: "y".to_string(),
                     arguments_json: "\"z\"".to_string(),
                 }],
+                reasoning: Some("The user said hello".to_string()),
                 metadata: ResponseMetadata {
                     finish_reason: Some(FinishReason::Stop),
                     usage: None,
                     provider_id: None,
                     timestamp: None,
                     provider_metadata_json: None,
+                    logprobs: None,
+                    cost: None,
                 },
             });
         }
@@ -584,12 +853,15 @@ mod durable_impl {
                     name: "y".to_string(),
                     arguments_json: "\"z\"".to_string(),
                 }],
+                reasoning: None,
                 metadata: ResponseMetadata {
                     finish_reason: Some(FinishReason::Stop),
                     usage: None,
                     provider_id: None,
                     timestamp: None,
                     provider_metadata_json: None,
+                    logprobs: None,
+                    cost: None,
                 },
             }));
             roundtrip_test(ChatEvent::ToolRequest(vec![ToolCall {
@@ -604,6 +876,25 @@ mod durable_impl {
             }));
         }
 
+        #[test]
+        fn batch_roundtrip() {
+            roundtrip_test(BatchInfo {
+                id: "msgbatch_01".to_string(),
+                status: BatchStatus::InProgress,
+                total: 3,
+                succeeded: 1,
+                failed: 1,
+            });
+            roundtrip_test(BatchResult {
+                custom_id: "request-1".to_string(),
+                event: ChatEvent::Error(Error {
+                    code: ErrorCode::Timeout,
+                    message: "Request expired".to_string(),
+                    provider_error_json: None,
+                }),
+            });
+        }
+
         #[test]
         fn send_input_encoding() {
             let input = SendInput {
@@ -612,6 +903,7 @@ mod durable_impl {
                         role: Role::User,
                         name: Some("user".to_string()),
                         content: vec![ContentPart::Text("Hello".to_string())],
+                        cache_control: Some(CacheControl::Ephemeral),
                     },
                     Message {
                         role: Role::Assistant,
@@ -620,6 +912,7 @@ mod durable_impl {
                             url: "https://example.com/image.png".to_string(),
                             detail: Some(ImageDetail::High),
                         }))],
+                        cache_control: None,
                     },
                     Message {
                         role: Role::User,
@@ -632,6 +925,7 @@ mod durable_impl {
                                 detail: None,
                             })),
                         ],
+                        cache_control: None,
                     },
                 ],
                 config: Config {
@@ -640,7 +934,14 @@ mod durable_impl {
                     max_tokens: Some(100),
                     stop_sequences: Some(vec!["\n".to_string()]),
                     tools: vec![],
-                    tool_choice: None,
+                    tool_choice: Some(ToolChoice::Specific("get_weather".to_string())),
+                    parallel_tool_calls: Some(false),
+                    response_format: None,
+                    logprobs: Some(2),
+                    reasoning_effort: Some(ReasoningEffort::High),
+                    thinking_budget: Some(2048),
+                    timeout_ms: Some(30000),
+                    connect_timeout_ms: Some(5000),
                     provider_options: vec![],
                 },
             };
@@ -1,6 +1,38 @@
 use crate::event_source;
 use crate::golem::llm::llm::{Error, ErrorCode};
+use golem_ai_common::error::{classify_status, StatusClass};
 use reqwest::StatusCode;
+use serde_json::{json, Value};
+
+/// Patterns in the message of a 429 response telling that the request is to be retried later,
+/// even when it is reported as an exceeded quota, like the per minute quotas of Vertex AI
+const RATE_LIMIT_PATTERNS: &[&str] = &[
+    "rate limit",
+    "rate_limit",
+    "per minute",
+    "per second",
+    "too many requests",
+];
+
+/// Patterns in the kind or message of an error telling that the account ran out of credits
+const QUOTA_PATTERNS: &[&str] = &["quota", "credit", "billing"];
+
+/// Patterns in the kind or message of an error telling that the input is too long for the model
+const CONTEXT_LENGTH_PATTERNS: &[&str] = &[
+    "context_length",
+    "context length",
+    "context window",
+    "prompt is too long",
+];
+
+/// Patterns in the kind or message of an error telling that the content got blocked
+const CONTENT_FILTER_PATTERNS: &[&str] = &[
+    "content_filter",
+    "content filter",
+    "content_policy",
+    "content policy",
+    "content management policy",
+];
 
 /// Creates an `Error` value representing that something is unsuported
 pub fn unsupported(what: impl AsRef<str>) -> Error {
@@ -13,31 +45,262 @@ pub fn unsupported(what: impl AsRef<str>) -> Error {
 
 pub fn from_reqwest_error(details: impl AsRef<str>, err: reqwest::Error) -> Error {
     Error {
-        code: ErrorCode::InternalError,
+        code: if err.is_timeout() {
+            ErrorCode::Timeout
+        } else {
+            ErrorCode::InternalError
+        },
         message: format!("{}: {err}", details.as_ref()),
         provider_error_json: None,
     }
 }
 
+/// Streams rejected with an error status are classified like other error responses, with their
+/// body parsed as `ProviderError::from_json`
 pub fn from_event_source_error(details: impl AsRef<str>, err: event_source::error::Error) -> Error {
-    Error {
-        code: ErrorCode::InternalError,
-        message: format!("{}: {err}", details.as_ref()),
-        provider_error_json: None,
+    match err {
+        event_source::error::Error::InvalidStatusCode(status, response) => {
+            let raw_body = response.text().unwrap_or_default();
+            from_error_response(status, raw_body, ProviderError::from_json)
+        }
+        err => Error {
+            code: ErrorCode::InternalError,
+            message: format!("{}: {err}", details.as_ref()),
+            provider_error_json: None,
+        },
     }
 }
 
 pub fn error_code_from_status(status: StatusCode) -> ErrorCode {
-    if status == StatusCode::TOO_MANY_REQUESTS {
+    match classify_status(status) {
+        StatusClass::RateLimited => ErrorCode::RateLimitExceeded,
+        StatusClass::Unauthorized => ErrorCode::AuthenticationFailed,
+        StatusClass::NotFound | StatusClass::Conflict | StatusClass::InvalidRequest => {
+            ErrorCode::InvalidRequest
+        }
+        StatusClass::ProviderError => ErrorCode::InternalError,
+    }
+}
+
+/// The details of an error response, extracted from its body by the parser of the provider
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProviderError {
+    /// The type or code of the error, such as `insufficient_quota` or `overloaded_error`
+    pub kind: Option<String>,
+    pub message: Option<String>,
+}
+
+impl ProviderError {
+    /// Parses the `{"error": {"message": ..., "code": ..., "type": ...}}` format used by most
+    /// providers, also accepting these fields at the top level, or `error` holding the message
+    pub fn from_json(raw_body: &str) -> Option<Self> {
+        let value = serde_json::from_str::<Value>(raw_body).ok()?;
+        let error = value.get("error").filter(|error| error.is_object());
+        let text = |field: &str| {
+            error
+                .unwrap_or(&value)
+                .get(field)
+                .and_then(Value::as_str)
+                .map(|text| text.to_string())
+        };
+        Some(Self {
+            kind: text("code").or_else(|| text("type")),
+            message: text("message").or_else(|| {
+                value
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .map(|text| text.to_string())
+            }),
+        })
+    }
+}
+
+/// Classifies a failure by the kind and message of the error, falling back to its status
+pub fn classify_error(status: StatusCode, error: &ProviderError) -> ErrorCode {
+    let kind = error.kind.as_deref().unwrap_or_default().to_lowercase();
+    let message = error.message.as_deref().unwrap_or_default().to_lowercase();
+    let mentions = |patterns: &[&str]| {
+        patterns
+            .iter()
+            .any(|pattern| kind.contains(pattern) || message.contains(pattern))
+    };
+
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        ErrorCode::AuthenticationFailed
+    } else if status == StatusCode::TOO_MANY_REQUESTS && mentions(RATE_LIMIT_PATTERNS) {
         ErrorCode::RateLimitExceeded
-    } else if status == StatusCode::UNAUTHORIZED
-        || status == StatusCode::FORBIDDEN
-        || status == StatusCode::PAYMENT_REQUIRED
+    } else if status == StatusCode::PAYMENT_REQUIRED || mentions(QUOTA_PATTERNS) {
+        ErrorCode::QuotaExceeded
+    } else if mentions(CONTEXT_LENGTH_PATTERNS) {
+        ErrorCode::ContextLengthExceeded
+    } else if mentions(CONTENT_FILTER_PATTERNS) {
+        ErrorCode::ContentFiltered
+    } else if kind.contains("rate_limit") {
+        ErrorCode::RateLimitExceeded
+    } else if status == StatusCode::SERVICE_UNAVAILABLE
+        || status.as_u16() == 529
+        || mentions(&["overloaded"])
     {
-        ErrorCode::AuthenticationFailed
-    } else if status.is_client_error() {
-        ErrorCode::InvalidRequest
+        ErrorCode::Overloaded
     } else {
-        ErrorCode::InternalError
+        error_code_from_status(status)
+    }
+}
+
+/// Creates the `Error` of a response that failed with `status`, using the provider specific
+/// `parse` to extract the details of the error from the body. The status, the details and the
+/// body are kept in `provider_error_json` as
+/// `{"status": 429, "kind": "...", "message": "...", "body": ...}`.
+pub fn from_error_response(
+    status: StatusCode,
+    raw_body: String,
+    parse: impl FnOnce(&str) -> Option<ProviderError>,
+) -> Error {
+    let error = parse(&raw_body).unwrap_or_default();
+    let message = match &error.message {
+        Some(message) => format!("Request failed with {status}: {message}"),
+        None => format!("Request failed with {status}"),
+    };
+    let body = serde_json::from_str::<Value>(&raw_body).unwrap_or(Value::String(raw_body));
+    Error {
+        code: classify_error(status, &error),
+        message,
+        provider_error_json: Some(
+            json!({
+                "status": status.as_u16(),
+                "kind": error.kind,
+                "message": error.message,
+                "body": body,
+            })
+            .to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{classify_error, from_error_response, ProviderError};
+    use crate::golem::llm::llm::ErrorCode;
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+
+    fn error(kind: Option<&str>, message: &str) -> ProviderError {
+        ProviderError {
+            kind: kind.map(|kind| kind.to_string()),
+            message: Some(message.to_string()),
+        }
+    }
+
+    #[test]
+    fn classifies_by_kind_and_message() {
+        assert_eq!(
+            classify_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                &error(
+                    Some("insufficient_quota"),
+                    "You exceeded your current quota"
+                )
+            ),
+            ErrorCode::QuotaExceeded
+        );
+        assert_eq!(
+            classify_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                &error(
+                    Some("RESOURCE_EXHAUSTED"),
+                    "Quota exceeded for quota metric 'Generate Content API requests per minute' \
+                     and limit 'GenerateContent request limit per minute for a region' of \
+                     service 'generativelanguage.googleapis.com' for consumer \
+                     'project_number:123456789'."
+                )
+            ),
+            ErrorCode::RateLimitExceeded
+        );
+        assert_eq!(
+            classify_error(
+                StatusCode::BAD_REQUEST,
+                &error(None, "prompt is too long: 210000 tokens > 200000 maximum")
+            ),
+            ErrorCode::ContextLengthExceeded
+        );
+        assert_eq!(
+            classify_error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                &error(Some("rate_limit_exceeded"), "Request too large for model")
+            ),
+            ErrorCode::RateLimitExceeded
+        );
+        assert_eq!(
+            classify_error(
+                StatusCode::from_u16(529).unwrap(),
+                &error(Some("overloaded_error"), "Overloaded")
+            ),
+            ErrorCode::Overloaded
+        );
+        assert_eq!(
+            classify_error(
+                StatusCode::UNAUTHORIZED,
+                &error(None, "Invalid API key for the billing account")
+            ),
+            ErrorCode::AuthenticationFailed
+        );
+        assert_eq!(
+            classify_error(StatusCode::BAD_REQUEST, &ProviderError::default()),
+            ErrorCode::InvalidRequest
+        );
+    }
+
+    #[test]
+    fn parses_the_common_error_formats() {
+        assert_eq!(
+            ProviderError::from_json(
+                r#"{"error": {"message": "Bad input", "type": "invalid_request_error", "code": "context_length_exceeded"}}"#
+            ),
+            Some(error(Some("context_length_exceeded"), "Bad input"))
+        );
+        assert_eq!(
+            ProviderError::from_json(r#"{"type": "error", "message": "Bad input"}"#),
+            Some(error(Some("error"), "Bad input"))
+        );
+        assert_eq!(
+            ProviderError::from_json(r#"{"error": "model not found"}"#),
+            Some(error(None, "model not found"))
+        );
+        assert_eq!(ProviderError::from_json("<html></html>"), None);
+    }
+
+    #[test]
+    fn keeps_the_details_as_provider_error_json() {
+        let error = from_error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"error": "slow down"}"#.to_string(),
+            ProviderError::from_json,
+        );
+        assert_eq!(error.code, ErrorCode::RateLimitExceeded);
+        assert_eq!(
+            error.message,
+            "Request failed with 429 Too Many Requests: slow down"
+        );
+        assert_eq!(
+            serde_json::from_str::<Value>(&error.provider_error_json.unwrap()).unwrap(),
+            json!({
+                "status": 429,
+                "kind": null,
+                "message": "slow down",
+                "body": {"error": "slow down"}
+            })
+        );
+
+        let error = from_error_response(
+            StatusCode::BAD_GATEWAY,
+            "<html>Bad gateway</html>".to_string(),
+            ProviderError::from_json,
+        );
+        assert_eq!(error.code, ErrorCode::InternalError);
+        assert_eq!(error.message, "Request failed with 502 Bad Gateway");
+        assert!(error
+            .provider_error_json
+            .unwrap()
+            .contains("<html>Bad gateway</html>"));
     }
 }
@@ -0,0 +1,224 @@
+//! Contract tests for provider error mapping.
+//!
+//! Every provider client decodes HTTP error responses with its own parser of the error body,
+//! before classifying them with `error::from_error_response`. This module feeds the same set of
+//! canned error responses through a provider's response parser and checks that the resulting
+//! `error-code` values agree across all the providers.
+
+use crate::golem::llm::llm::{Error, ErrorCode};
+use reqwest::StatusCode;
+
+/// Which kind of body a canned error response has
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorBody {
+    /// The provider's own JSON error format, rendered by the provider's test
+    Provider,
+    /// A non-JSON body, like the HTML error pages of proxies and load balancers
+    Html,
+    /// A truncated JSON document
+    Malformed,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCase {
+    pub name: &'static str,
+    pub status: StatusCode,
+    pub message: &'static str,
+    pub body: ErrorBody,
+    pub expected: ErrorCode,
+}
+
+pub const ERROR_CASES: &[ErrorCase] = &[
+    ErrorCase {
+        name: "invalid api key",
+        status: StatusCode::UNAUTHORIZED,
+        message: "Invalid API key",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::AuthenticationFailed,
+    },
+    ErrorCase {
+        name: "forbidden",
+        status: StatusCode::FORBIDDEN,
+        message: "The API key has no access to this resource",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::AuthenticationFailed,
+    },
+    ErrorCase {
+        name: "unknown model",
+        status: StatusCode::NOT_FOUND,
+        message: "Model 'no-such-model' not found",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::InvalidRequest,
+    },
+    ErrorCase {
+        name: "rate limited",
+        status: StatusCode::TOO_MANY_REQUESTS,
+        message: "Rate limit exceeded",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::RateLimitExceeded,
+    },
+    ErrorCase {
+        name: "insufficient quota",
+        status: StatusCode::TOO_MANY_REQUESTS,
+        message: "You exceeded your current quota, please check your plan and billing details",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::QuotaExceeded,
+    },
+    ErrorCase {
+        name: "out of credits",
+        status: StatusCode::PAYMENT_REQUIRED,
+        message: "Insufficient credits",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::QuotaExceeded,
+    },
+    ErrorCase {
+        name: "context length exceeded",
+        status: StatusCode::BAD_REQUEST,
+        message: "This model's maximum context length is 128000 tokens, but the messages resulted in 130000 tokens",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::ContextLengthExceeded,
+    },
+    ErrorCase {
+        name: "content filtered",
+        status: StatusCode::BAD_REQUEST,
+        message: "The prompt was filtered because it triggered the content management policy",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::ContentFiltered,
+    },
+    ErrorCase {
+        name: "overloaded",
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        message: "The server is overloaded, please try again later",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::Overloaded,
+    },
+    ErrorCase {
+        name: "server error",
+        status: StatusCode::INTERNAL_SERVER_ERROR,
+        message: "Internal server error",
+        body: ErrorBody::Provider,
+        expected: ErrorCode::InternalError,
+    },
+    ErrorCase {
+        name: "unauthorized html page",
+        status: StatusCode::UNAUTHORIZED,
+        message: "Unauthorized",
+        body: ErrorBody::Html,
+        expected: ErrorCode::AuthenticationFailed,
+    },
+    ErrorCase {
+        name: "bad gateway html page",
+        status: StatusCode::BAD_GATEWAY,
+        message: "Bad gateway",
+        body: ErrorBody::Html,
+        expected: ErrorCode::InternalError,
+    },
+    ErrorCase {
+        name: "service unavailable html page",
+        status: StatusCode::SERVICE_UNAVAILABLE,
+        message: "Service unavailable",
+        body: ErrorBody::Html,
+        expected: ErrorCode::Overloaded,
+    },
+    ErrorCase {
+        name: "malformed success response",
+        status: StatusCode::OK,
+        message: "",
+        body: ErrorBody::Malformed,
+        expected: ErrorCode::InternalError,
+    },
+];
+
+impl ErrorCase {
+    /// Renders the body of this case, using `provider_body` for the provider's own error format
+    pub fn body(&self, provider_body: &impl Fn(StatusCode, &str) -> String) -> String {
+        match self.body {
+            ErrorBody::Provider => provider_body(self.status, self.message),
+            ErrorBody::Html => format!(
+                "<html><head><title>{status}</title></head><body><h1>{message}</h1></body></html>",
+                status = self.status,
+                message = self.message
+            ),
+            ErrorBody::Malformed => r#"{"id": "resp_123", "content": [{"#.to_string(),
+        }
+    }
+}
+
+/// Runs all the `ERROR_CASES` through `parse`, which should call the provider's response parser
+/// with the given status and raw body, and panics listing every case mapped to an unexpected
+/// error code.
+pub fn assert_error_mapping(
+    provider_body: impl Fn(StatusCode, &str) -> String,
+    parse: impl Fn(StatusCode, String) -> Result<(), Error>,
+) {
+    let mismatches: Vec<String> = ERROR_CASES
+        .iter()
+        .filter_map(|case| match parse(case.status, case.body(&provider_body)) {
+            Ok(()) => Some(format!(
+                "{}: expected {:?}, but the response was accepted",
+                case.name, case.expected
+            )),
+            Err(error) if error.code != case.expected => Some(format!(
+                "{}: expected {:?}, got {:?} ({})",
+                case.name, case.expected, error.code, error.message
+            )),
+            Err(_) => None,
+        })
+        .collect();
+
+    assert!(
+        mismatches.is_empty(),
+        "Unexpected error mapping:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{from_error_response, ProviderError};
+    use crate::error_contract::{assert_error_mapping, ERROR_CASES};
+    use crate::golem::llm::llm::{Error, ErrorCode};
+
+    #[test]
+    fn shared_mapping_satisfies_the_contract() {
+        assert_error_mapping(
+            |_, message| format!(r#"{{"error": "{message}"}}"#),
+            |status, body| {
+                if status.is_success() {
+                    serde_json::from_str::<serde_json::Value>(&body)
+                        .map(|_| ())
+                        .map_err(|err| Error {
+                            code: ErrorCode::InternalError,
+                            message: err.to_string(),
+                            provider_error_json: Some(body),
+                        })
+                } else {
+                    Err(from_error_response(status, body, ProviderError::from_json))
+                }
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown model")]
+    fn reports_mismatching_cases() {
+        assert_error_mapping(
+            |_, message| message.to_string(),
+            |status, _| {
+                Err(Error {
+                    code: if status.as_u16() == 404 {
+                        ErrorCode::Unknown
+                    } else {
+                        ERROR_CASES
+                            .iter()
+                            .find(|case| case.status == status)
+                            .unwrap()
+                            .expected
+                    },
+                    message: String::new(),
+                    provider_error_json: None,
+                })
+            },
+        );
+    }
+}
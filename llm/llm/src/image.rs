@@ -0,0 +1,489 @@
+//! Preparation of the inline images of a request for the limits of the provider.
+//!
+//! The format is detected from the image data, so a wrong MIME type does not matter. Images in
+//! formats the provider does not accept are converted to JPEG, and unknown formats like HEIC, which
+//! have no decoder in the components, are rejected with an `invalid-request` error before the
+//! request is sent, instead of failing with an opaque error of the provider. The EXIF metadata of
+//! images is stripped, applying the orientation it records. Images wider or taller than the
+//! maximum edge of the provider are downscaled, and images above its size limit are recompressed
+//! as JPEG, downscaled further until they fit. Only the first frame of an animated GIF is kept
+//! when it is re-encoded. The changes made to an image are returned, and logged at debug level.
+//! The limits can be lowered with `GOLEM_LLM_MAX_IMAGE_BYTES` and `GOLEM_LLM_MAX_IMAGE_DIMENSION`,
+//! and `GOLEM_LLM_IMAGE_QUALITY` sets the JPEG quality.
+
+use crate::golem::llm::llm::{Error, ErrorCode, ImageSource};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageError, ImageReader};
+use log::{debug, warn};
+use std::borrow::Cow;
+use std::io::Cursor;
+
+/// Environment variable lowering the size limit of inline images, in bytes
+pub const MAX_IMAGE_BYTES_ENV_VAR: &str = "GOLEM_LLM_MAX_IMAGE_BYTES";
+
+/// Environment variable limiting the width and height of inline images, in pixels
+pub const MAX_IMAGE_DIMENSION_ENV_VAR: &str = "GOLEM_LLM_MAX_IMAGE_DIMENSION";
+
+/// Environment variable setting the JPEG quality of recompressed images, from 1 to 100
+pub const IMAGE_QUALITY_ENV_VAR: &str = "GOLEM_LLM_IMAGE_QUALITY";
+
+const DEFAULT_QUALITY: u8 = 85;
+
+/// Images are not downscaled below this width and height to fit the size limit
+const MIN_DIMENSION: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl ImageFormat {
+    /// Detects the format of an image from its signature
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+            Some(Self::Jpeg)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    fn codec(self) -> image::ImageFormat {
+        match self {
+            Self::Png => image::ImageFormat::Png,
+            Self::Jpeg => image::ImageFormat::Jpeg,
+            Self::Gif => image::ImageFormat::Gif,
+            Self::Webp => image::ImageFormat::WebP,
+        }
+    }
+}
+
+/// A change made to an inline image to prepare it for a provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageTransformation {
+    /// Converted from a format the provider does not accept
+    Converted { from: ImageFormat, to: ImageFormat },
+    /// The EXIF metadata was removed, after applying the orientation it recorded
+    StrippedMetadata,
+    /// Downscaled to the given width and height
+    Downscaled { width: u32, height: u32 },
+    /// Recompressed as JPEG with the given quality to fit the size limit
+    Recompressed { quality: u8 },
+}
+
+/// An inline image prepared for a provider
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessedImage<'a> {
+    /// The image data, borrowed from the request when the image was kept as it is
+    pub data: Cow<'a, [u8]>,
+    pub format: ImageFormat,
+    pub transformations: Vec<ImageTransformation>,
+}
+
+/// The limits of a provider for inline images
+#[derive(Debug, Clone)]
+pub struct ImageProcessor {
+    formats: &'static [ImageFormat],
+    max_bytes: usize,
+    max_dimension: Option<u32>,
+    quality: u8,
+}
+
+impl ImageProcessor {
+    pub fn new(formats: &'static [ImageFormat], max_bytes: usize) -> Self {
+        Self {
+            formats,
+            max_bytes,
+            max_dimension: None,
+            quality: DEFAULT_QUALITY,
+        }
+    }
+
+    /// The limits of a provider, narrowed by `GOLEM_LLM_MAX_IMAGE_BYTES` and
+    /// `GOLEM_LLM_MAX_IMAGE_DIMENSION`, recompressing with the `GOLEM_LLM_IMAGE_QUALITY`
+    pub fn from_env(formats: &'static [ImageFormat], max_bytes: usize, max_dimension: u32) -> Self {
+        let max_bytes = match env_limit(MAX_IMAGE_BYTES_ENV_VAR) {
+            Some(limit) => max_bytes.min(limit as usize),
+            None => max_bytes,
+        };
+        let max_dimension = match env_limit(MAX_IMAGE_DIMENSION_ENV_VAR) {
+            Some(limit) => max_dimension.min(limit),
+            None => max_dimension,
+        };
+        let processor = Self::new(formats, max_bytes).with_max_dimension(Some(max_dimension));
+        match env_limit(IMAGE_QUALITY_ENV_VAR) {
+            Some(quality @ 1..=100) => processor.with_quality(quality as u8),
+            Some(quality) => {
+                warn!("Ignoring {IMAGE_QUALITY_ENV_VAR} {quality}, it must be between 1 and 100");
+                processor
+            }
+            None => processor,
+        }
+    }
+
+    pub fn with_max_dimension(mut self, max_dimension: Option<u32>) -> Self {
+        self.max_dimension = max_dimension;
+        self
+    }
+
+    /// The JPEG quality used when an image has to be recompressed to fit the size limit
+    pub fn with_quality(mut self, quality: u8) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Prepares an inline image for the provider, converting, downscaling and recompressing it
+    /// when it is beyond the limits
+    pub fn process<'a>(&self, image: &'a ImageSource) -> Result<ProcessedImage<'a>, Error> {
+        let accepts_jpeg = self.formats.contains(&ImageFormat::Jpeg);
+        let source_format = ImageFormat::detect(&image.data)
+            .filter(|format| accepts_jpeg || self.formats.contains(format))
+            .ok_or_else(|| {
+                let supported = self
+                    .formats
+                    .iter()
+                    .map(|format| format.mime_type())
+                    .collect::<Vec<_>>();
+                invalid_request(format!(
+                    "Unsupported image format ({}), supported formats are {}",
+                    image.mime_type,
+                    supported.join(", ")
+                ))
+            })?;
+        // Formats the provider does not accept are converted to JPEG
+        let format = if self.formats.contains(&source_format) {
+            source_format
+        } else {
+            ImageFormat::Jpeg
+        };
+
+        let mut decoder =
+            ImageReader::with_format(Cursor::new(&image.data[..]), source_format.codec())
+                .into_decoder()
+                .map_err(decode_error)?;
+        let (width, height) = decoder.dimensions();
+        let has_metadata = decoder.exif_metadata().map_err(decode_error)?.is_some();
+        let within_dimension = self
+            .max_dimension
+            .is_none_or(|max_dimension| width <= max_dimension && height <= max_dimension);
+        if format == source_format
+            && !has_metadata
+            && within_dimension
+            && image.data.len() <= self.max_bytes
+        {
+            return Ok(ProcessedImage {
+                data: Cow::Borrowed(&image.data),
+                format,
+                transformations: Vec::new(),
+            });
+        }
+
+        let mut transformations = Vec::new();
+        if format != source_format {
+            transformations.push(ImageTransformation::Converted {
+                from: source_format,
+                to: format,
+            });
+        }
+        let orientation = decoder.orientation().map_err(decode_error)?;
+        let mut decoded = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+        // Re-encoding drops the metadata, so the orientation is applied to the pixels
+        if has_metadata {
+            decoded.apply_orientation(orientation);
+            transformations.push(ImageTransformation::StrippedMetadata);
+        }
+        if let Some(max_dimension) = self.max_dimension {
+            if decoded.width() > max_dimension || decoded.height() > max_dimension {
+                decoded = decoded.resize(max_dimension, max_dimension, FilterType::Lanczos3);
+                transformations.push(ImageTransformation::Downscaled {
+                    width: decoded.width(),
+                    height: decoded.height(),
+                });
+            }
+        }
+
+        let data = encode(&decoded, format, self.quality)?;
+        let processed = if data.len() <= self.max_bytes {
+            ProcessedImage {
+                data: Cow::Owned(data),
+                format,
+                transformations,
+            }
+        } else {
+            self.recompress(decoded, image.data.len(), transformations)?
+        };
+        debug!(
+            "Prepared an inline {} image: {:?}",
+            source_format.mime_type(),
+            processed.transformations
+        );
+        Ok(processed)
+    }
+
+    /// Recompresses an image as JPEG, halving its size until it fits the size limit
+    fn recompress(
+        &self,
+        mut image: DynamicImage,
+        original_bytes: usize,
+        mut transformations: Vec<ImageTransformation>,
+    ) -> Result<ProcessedImage<'static>, Error> {
+        let too_large = || {
+            invalid_request(format!(
+                "Image of {original_bytes} bytes can not be compressed to the limit of {} bytes",
+                self.max_bytes
+            ))
+        };
+        if !self.formats.contains(&ImageFormat::Jpeg) {
+            return Err(too_large());
+        }
+
+        let (width, height) = (image.width(), image.height());
+        loop {
+            let data = encode(&image, ImageFormat::Jpeg, self.quality)?;
+            if data.len() <= self.max_bytes {
+                if (image.width(), image.height()) != (width, height) {
+                    transformations.push(ImageTransformation::Downscaled {
+                        width: image.width(),
+                        height: image.height(),
+                    });
+                }
+                transformations.push(ImageTransformation::Recompressed {
+                    quality: self.quality,
+                });
+                return Ok(ProcessedImage {
+                    data: Cow::Owned(data),
+                    format: ImageFormat::Jpeg,
+                    transformations,
+                });
+            }
+            if image.width().max(image.height()) <= MIN_DIMENSION {
+                return Err(too_large());
+            }
+            image = image.resize(
+                image.width().div_ceil(2),
+                image.height().div_ceil(2),
+                FilterType::Lanczos3,
+            );
+        }
+    }
+}
+
+/// Encodes an image, as JPEG with the given quality, or losslessly in the other formats
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    let result = match format {
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut data, quality)),
+        _ => image.write_to(&mut Cursor::new(&mut data), format.codec()),
+    };
+    result.map_err(|err| Error {
+        code: ErrorCode::InternalError,
+        message: format!(
+            "Could not encode the image as {}: {err}",
+            format.mime_type()
+        ),
+        provider_error_json: None,
+    })?;
+    Ok(data)
+}
+
+fn env_limit(name: &str) -> Option<u32> {
+    let raw = std::env::var(name).ok()?;
+    match raw.trim().parse::<u32>() {
+        Ok(limit) => Some(limit),
+        Err(err) => {
+            warn!("Ignoring invalid {name}: {err}");
+            None
+        }
+    }
+}
+
+fn decode_error(err: ImageError) -> Error {
+    invalid_request(format!("Could not decode the image: {err}"))
+}
+
+fn invalid_request(message: String) -> Error {
+    Error {
+        code: ErrorCode::InvalidRequest,
+        message,
+        provider_error_json: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{ErrorCode, ImageSource};
+    use crate::image::{ImageFormat, ImageProcessor, ImageTransformation};
+    use image::{DynamicImage, ImageDecoder, ImageReader, Rgb, RgbImage};
+    use std::borrow::Cow;
+    use std::io::Cursor;
+
+    const FORMATS: &[ImageFormat] = &[ImageFormat::Png, ImageFormat::Jpeg];
+
+    fn image(data: Vec<u8>, mime_type: &str) -> ImageSource {
+        ImageSource {
+            data,
+            mime_type: mime_type.to_string(),
+            detail: None,
+        }
+    }
+
+    /// An image with varying pixels, so it does not compress too well
+    fn encoded(width: u32, height: u32, format: image::ImageFormat) -> Vec<u8> {
+        let image = RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x * 7 + y * 3) as u8, (x * y) as u8, (x ^ y) as u8])
+        });
+        let mut data = Vec::new();
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut Cursor::new(&mut data), format)
+            .unwrap();
+        data
+    }
+
+    fn decoded_dimensions(data: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory(data).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn keeps_images_within_the_limits() {
+        let processor = ImageProcessor::new(FORMATS, 1024 * 1024).with_max_dimension(Some(1000));
+        // The format is detected from the data
+        let source = image(encoded(640, 480, image::ImageFormat::Png), "image/jpeg");
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Png);
+        assert!(matches!(processed.data, Cow::Borrowed(_)));
+
+        assert!(processed.transformations.is_empty());
+
+        let heic = b"\0\0\0\x18ftypheic\0\0\0\0mif1heic".to_vec();
+        let error = processor.process(&image(heic, "image/heic")).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.message,
+            "Unsupported image format (image/heic), supported formats are image/png, image/jpeg"
+        );
+    }
+
+    #[test]
+    fn converts_formats_the_provider_does_not_accept() {
+        let processor = ImageProcessor::new(FORMATS, 1024 * 1024);
+        let source = image(encoded(64, 48, image::ImageFormat::WebP), "image/webp");
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Jpeg);
+        assert_eq!(
+            processed.transformations,
+            vec![ImageTransformation::Converted {
+                from: ImageFormat::Webp,
+                to: ImageFormat::Jpeg
+            }]
+        );
+        assert_eq!(decoded_dimensions(&processed.data), (64, 48));
+
+        let processor = ImageProcessor::new(&[ImageFormat::Png], 1024 * 1024);
+        assert!(processor.process(&source).is_err());
+    }
+
+    #[test]
+    fn strips_the_exif_metadata_applying_the_orientation() {
+        let mut jpeg = encoded(64, 48, image::ImageFormat::Jpeg);
+        // An APP1 segment after the SOI marker, with an orientation rotating by 90 degrees
+        let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+        exif.extend([
+            0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0x00, 0x06, 0, 0, 0, 0, 0, 0,
+        ]);
+        let mut segment = vec![0xff, 0xe1];
+        segment.extend((exif.len() as u16 + 2).to_be_bytes());
+        segment.extend(exif);
+        jpeg.splice(2..2, segment);
+
+        let processor = ImageProcessor::new(FORMATS, 1024 * 1024);
+        let source = image(jpeg, "image/jpeg");
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Jpeg);
+        assert_eq!(
+            processed.transformations,
+            vec![ImageTransformation::StrippedMetadata]
+        );
+        assert_eq!(decoded_dimensions(&processed.data), (48, 64));
+        let mut decoder = ImageReader::new(Cursor::new(&processed.data[..]))
+            .with_guessed_format()
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        assert_eq!(decoder.exif_metadata().unwrap(), None);
+    }
+
+    #[test]
+    fn downscales_images_to_the_maximum_dimension() {
+        let processor = ImageProcessor::new(FORMATS, 1024 * 1024).with_max_dimension(Some(500));
+
+        let source = image(encoded(2000, 480, image::ImageFormat::Png), "image/png");
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Png);
+        assert_eq!(decoded_dimensions(&processed.data), (500, 120));
+        assert_eq!(
+            processed.transformations,
+            vec![ImageTransformation::Downscaled {
+                width: 500,
+                height: 120
+            }]
+        );
+
+        let source = image(encoded(300, 900, image::ImageFormat::Jpeg), "image/jpeg");
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Jpeg);
+        assert_eq!(decoded_dimensions(&processed.data), (167, 500));
+    }
+
+    #[test]
+    fn recompresses_images_above_the_size_limit() {
+        let source = image(encoded(800, 600, image::ImageFormat::Png), "image/png");
+        let max_bytes = source.data.len() / 4;
+        let processor = ImageProcessor::new(FORMATS, max_bytes).with_quality(80);
+
+        let processed = processor.process(&source).unwrap();
+        assert_eq!(processed.format, ImageFormat::Jpeg);
+        assert!(processed.data.len() <= max_bytes);
+        let (width, height) = decoded_dimensions(&processed.data);
+        assert_eq!(width * 3, height * 4);
+        assert!(width <= 800);
+        assert_eq!(
+            processed.transformations.last(),
+            Some(&ImageTransformation::Recompressed { quality: 80 })
+        );
+
+        let processor = ImageProcessor::new(FORMATS, 100);
+        let error = processor.process(&source).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.message,
+            format!(
+                "Image of {} bytes can not be compressed to the limit of 100 bytes",
+                source.data.len()
+            )
+        );
+
+        let processor = ImageProcessor::new(&[ImageFormat::Png], max_bytes);
+        assert!(processor.process(&source).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+//! Request/response interceptors for the provider clients.
+//!
+//! Every provider client sends its HTTP requests through [`send`], which runs the registered
+//! interceptors on the outgoing request before sending it. Interceptors can add or replace
+//! headers, rewrite the URL and inspect the body, so a component embedding one of the providers
+//! can inject gateway credentials, sign requests or write audit logs without forking the
+//! provider crate. Responses are reported to the interceptors by the `recording::Recorder` of the
+//! request, and the events of streaming responses by `chat_stream::LlmChatStream`.
+
+use crate::error::from_reqwest_error;
+use crate::golem::llm::llm::{Error, ErrorCode, StreamEvent};
+use crate::rate_limit;
+use crate::retry::send_with_retry;
+use crate::timeout;
+use log::warn;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Environment variable holding extra headers added to every request, as a JSON object mapping
+/// header names to values, for example `{"x-gateway-key": "..."}`
+pub const EXTRA_HEADERS_ENV_VAR: &str = "GOLEM_LLM_EXTRA_HEADERS";
+
+/// An HTTP request about to be sent to a provider
+#[derive(Debug, Clone)]
+pub struct OutgoingRequest {
+    pub provider: String,
+    pub operation: String,
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl OutgoingRequest {
+    pub fn new(provider: &str, operation: &str, method: Method, url: impl Into<String>) -> Self {
+        Self {
+            provider: provider.to_string(),
+            operation: operation.to_string(),
+            method,
+            url: url.into(),
+            headers: HeaderMap::new(),
+            body: String::new(),
+        }
+    }
+
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Result<Self, Error> {
+        self.set_header(name, value)?;
+        Ok(self)
+    }
+
+    pub fn bearer_auth(self, token: impl AsRef<str>) -> Result<Self, Error> {
+        self.header(AUTHORIZATION, format!("Bearer {}", token.as_ref()))
+    }
+
+    pub fn json(mut self, body: &impl Serialize) -> Result<Self, Error> {
+        self.body = serde_json::to_string(body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to serialize request body: {err}"),
+            provider_error_json: None,
+        })?;
+        self.header(CONTENT_TYPE, "application/json")
+    }
+
+    /// Adds or replaces a header
+    pub fn set_header(
+        &mut self,
+        name: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> Result<(), Error> {
+        let name = HeaderName::from_bytes(name.as_ref().as_bytes()).map_err(|err| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Invalid header name {}: {err}", name.as_ref()),
+            provider_error_json: None,
+        })?;
+        let value = HeaderValue::from_str(value.as_ref()).map_err(|err| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Invalid value for header {name}: {err}"),
+            provider_error_json: None,
+        })?;
+        self.headers.insert(name, value);
+        Ok(())
+    }
+}
+
+/// A response received from a provider. The body is only available for non-streaming requests.
+#[derive(Debug, Clone)]
+pub struct IncomingResponse<'a> {
+    pub provider: &'a str,
+    pub operation: &'a str,
+    pub status: StatusCode,
+    pub body: Option<&'a str>,
+}
+
+pub trait Interceptor {
+    /// Called before the request gets sent. The body can be rewritten, for example to transform
+    /// the prompt. Returning an error aborts the request.
+    fn on_request(&self, _request: &mut OutgoingRequest) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Called when the status, and for non-streaming requests the body, of the response is known
+    fn on_response(&self, _response: &IncomingResponse) {}
+
+    /// Called for each event decoded from a streaming response of `provider`, before it is
+    /// returned to the caller
+    fn on_stream_event(&self, _provider: &str, _event: &mut StreamEvent) {}
+}
+
+/// Adds a fixed set of headers to every request, for example to authenticate with an API gateway
+pub struct HeaderInjector {
+    headers: Vec<(String, String)>,
+}
+
+impl HeaderInjector {
+    pub fn new(headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            headers: headers.into_iter().collect(),
+        }
+    }
+
+    /// Creates an injector from the `GOLEM_LLM_EXTRA_HEADERS` environment variable, if it is set
+    pub fn from_env() -> Option<Self> {
+        let raw = std::env::var(EXTRA_HEADERS_ENV_VAR).ok()?;
+        match serde_json::from_str::<HashMap<String, String>>(&raw) {
+            Ok(headers) => Some(Self::new(headers)),
+            Err(err) => {
+                warn!("Invalid {EXTRA_HEADERS_ENV_VAR}: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl Interceptor for HeaderInjector {
+    fn on_request(&self, request: &mut OutgoingRequest) -> Result<(), Error> {
+        for (name, value) in &self.headers {
+            request.set_header(name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct InterceptorChain {
+    initialized: bool,
+    interceptors: Vec<Rc<dyn Interceptor>>,
+}
+
+thread_local! {
+    static INTERCEPTORS: RefCell<InterceptorChain> = RefCell::new(InterceptorChain::default());
+}
+
+/// Gets the interceptors in the order they run, initializing the chain with the ones configured
+/// through environment variables on first use.
+fn interceptors() -> Vec<Rc<dyn Interceptor>> {
+    INTERCEPTORS.with_borrow_mut(|chain| {
+        if !chain.initialized {
+            chain.initialized = true;
+            if let Some(injector) = HeaderInjector::from_env() {
+                chain.interceptors.insert(0, Rc::new(injector));
+            }
+        }
+        chain.interceptors.clone()
+    })
+}
+
+/// Appends an interceptor to the chain. Interceptors run in the order they got registered, after
+/// the ones configured through environment variables.
+pub fn register(interceptor: impl Interceptor + 'static) {
+    interceptors();
+    INTERCEPTORS.with_borrow_mut(|chain| chain.interceptors.push(Rc::new(interceptor)));
+}
+
+/// Removes all the registered interceptors, including the ones configured through environment
+/// variables
+pub fn clear() {
+    INTERCEPTORS.with_borrow_mut(|chain| {
+        chain.initialized = true;
+        chain.interceptors.clear();
+    });
+}
+
+/// Runs the request interceptors, stopping at the first one that fails
+pub fn intercept_request(request: &mut OutgoingRequest) -> Result<(), Error> {
+    for interceptor in interceptors() {
+        interceptor.on_request(request)?;
+    }
+    Ok(())
+}
+
+/// Reports a response to all the interceptors
+pub fn observe_response(response: &IncomingResponse) {
+    for interceptor in interceptors() {
+        interceptor.on_response(response);
+    }
+}
+
+/// Passes a stream event through all the interceptors, in order
+pub fn intercept_stream_event(provider: &str, event: &mut StreamEvent) {
+    for interceptor in interceptors() {
+        interceptor.on_stream_event(provider, event);
+    }
+}
+
+/// Takes the request from the rate limit budget of the model, runs the request interceptors and
+/// sends the resulting request with `client`, retrying it on transient failures. The timeouts of
+/// the active call are applied to each attempt.
+pub fn send(client: &Client, mut request: OutgoingRequest) -> Result<Response, Error> {
+    rate_limit::acquire(&request.body)?;
+    intercept_request(&mut request)?;
+    let timeouts = timeout::current();
+    // The connect timeout can only be set on a client
+    let connect_client = match timeouts.connect {
+        Some(connect_timeout) => Some(
+            Client::builder()
+                .connect_timeout(connect_timeout)
+                .build()
+                .map_err(|err| from_reqwest_error("Failed to initialize HTTP client", err))?,
+        ),
+        None => None,
+    };
+    let client = connect_client.as_ref().unwrap_or(client);
+    send_with_retry(|| {
+        let mut builder = client
+            .request(request.method.clone(), &request.url)
+            .headers(request.headers.clone())
+            .body(request.body.clone());
+        if let Some(request_timeout) = timeouts.request {
+            builder = builder.timeout(request_timeout);
+        }
+        builder.send()
+    })
+    .map_err(|err| from_reqwest_error("Request failed", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{ContentPart, Error, ErrorCode, StreamDelta, StreamEvent};
+    use crate::interceptor::{
+        clear, intercept_request, intercept_stream_event, observe_response, register,
+        HeaderInjector, IncomingResponse, Interceptor, OutgoingRequest,
+    };
+    use reqwest::{Method, StatusCode};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct UrlRewriter;
+
+    impl Interceptor for UrlRewriter {
+        fn on_request(&self, request: &mut OutgoingRequest) -> Result<(), Error> {
+            request.url = request
+                .url
+                .replace("https://api.openai.com", "https://gateway.local/openai");
+            Ok(())
+        }
+    }
+
+    struct Rejecting;
+
+    impl Interceptor for Rejecting {
+        fn on_request(&self, _request: &mut OutgoingRequest) -> Result<(), Error> {
+            Err(Error {
+                code: ErrorCode::AuthenticationFailed,
+                message: "Signing failed".to_string(),
+                provider_error_json: None,
+            })
+        }
+    }
+
+    struct Audit(Rc<RefCell<Vec<String>>>);
+
+    impl Interceptor for Audit {
+        fn on_response(&self, response: &IncomingResponse) {
+            self.0.borrow_mut().push(format!(
+                "{} {} {}",
+                response.provider,
+                response.operation,
+                response.status.as_u16()
+            ));
+        }
+    }
+
+    struct Masking;
+
+    impl Interceptor for Masking {
+        fn on_stream_event(&self, provider: &str, event: &mut StreamEvent) {
+            if let StreamEvent::Delta(StreamDelta {
+                content: Some(content),
+                ..
+            }) = event
+            {
+                for part in content {
+                    if let ContentPart::Text(text) = part {
+                        *text = text.replace("secret", &format!("[{provider}: masked]"));
+                    }
+                }
+            }
+        }
+    }
+
+    fn request() -> OutgoingRequest {
+        OutgoingRequest::new(
+            "openai",
+            "create-model-response",
+            Method::POST,
+            "https://api.openai.com/v1/responses",
+        )
+        .bearer_auth("key")
+        .unwrap()
+        .json(&serde_json::json!({ "model": "gpt-4o" }))
+        .unwrap()
+    }
+
+    #[test]
+    fn runs_request_interceptors_in_order() {
+        clear();
+        register(HeaderInjector::new([(
+            "x-gateway-key".to_string(),
+            "secret".to_string(),
+        )]));
+        register(UrlRewriter);
+
+        let mut request = request();
+        intercept_request(&mut request).unwrap();
+
+        assert_eq!(request.url, "https://gateway.local/openai/v1/responses");
+        assert_eq!(request.headers["x-gateway-key"], "secret");
+        assert_eq!(request.headers["authorization"], "Bearer key");
+        assert_eq!(request.body, r#"{"model":"gpt-4o"}"#);
+    }
+
+    #[test]
+    fn failing_interceptor_aborts_the_request() {
+        clear();
+        register(Rejecting);
+
+        let error = intercept_request(&mut request()).unwrap_err();
+        assert_eq!(error.code, ErrorCode::AuthenticationFailed);
+    }
+
+    #[test]
+    fn reports_responses() {
+        clear();
+        let log = Rc::new(RefCell::new(Vec::new()));
+        register(Audit(log.clone()));
+
+        observe_response(&IncomingResponse {
+            provider: "openai",
+            operation: "create-model-response",
+            status: StatusCode::OK,
+            body: Some("{}"),
+        });
+
+        assert_eq!(*log.borrow(), vec!["openai create-model-response 200"]);
+    }
+
+    #[test]
+    fn rewrites_stream_events() {
+        clear();
+        register(Masking);
+
+        let mut event = StreamEvent::Delta(StreamDelta {
+            content: Some(vec![ContentPart::Text("the secret is 42".to_string())]),
+            tool_calls: None,
+            reasoning: None,
+            logprobs: None,
+        });
+        intercept_stream_event("openai", &mut event);
+
+        let StreamEvent::Delta(delta) = event else {
+            panic!("expected a delta")
+        };
+        assert_eq!(
+            delta.content,
+            Some(vec![ContentPart::Text(
+                "the [openai: masked] is 42".to_string()
+            )])
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use serde_json::{Map, Value};
+
+/// Limits the depth of `$ref` resolution, so recursive schemas cannot loop forever
+const MAX_DEPTH: usize = 64;
+
+/// Validates JSON values against a JSON schema.
+///
+/// Supports the subset of JSON Schema used by structured outputs and tool parameters: `type`,
+/// `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`, `minItems`,
+/// `maxItems`, `minLength`, `maxLength`, `minimum`, `maximum`, `exclusiveMinimum`,
+/// `exclusiveMaximum`, `allOf`, `anyOf`, `oneOf` and local `$ref`s such as `#/$defs/item`. Other
+/// keywords, like `pattern` or `format`, are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonSchema {
+    root: Value,
+}
+
+impl JsonSchema {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let root: Value =
+            serde_json::from_str(raw).map_err(|err| format!("Invalid JSON schema: {err}"))?;
+        if root.is_object() || root.is_boolean() {
+            Ok(Self { root })
+        } else {
+            Err("Invalid JSON schema: it must be an object or a boolean".to_string())
+        }
+    }
+
+    /// Returns the violations of the schema, or an empty list if `value` matches it
+    pub fn validate(&self, value: &Value) -> Vec<String> {
+        let mut violations = Vec::new();
+        self.check(&self.root, value, "$", 0, &mut violations);
+        violations
+    }
+
+    fn is_valid(&self, schema: &Value, value: &Value, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.check(schema, value, "$", depth, &mut violations);
+        violations.is_empty()
+    }
+
+    fn check(
+        &self,
+        schema: &Value,
+        value: &Value,
+        path: &str,
+        depth: usize,
+        violations: &mut Vec<String>,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                violations.push(format!("{path}: no value is allowed here"));
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        if depth > MAX_DEPTH {
+            violations.push(format!("{path}: the schema is nested too deeply"));
+            return;
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(resolved) => self.check(resolved, value, path, depth + 1, violations),
+                None => violations.push(format!("{path}: cannot resolve $ref {reference}")),
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+                violations.push(format!(
+                    "{path}: expected {}, found {}",
+                    types.join(" or "),
+                    type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                violations.push(format!(
+                    "{path}: expected one of {}, found {value}",
+                    Value::Array(allowed.clone())
+                ));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                violations.push(format!("{path}: expected {constant}, found {value}"));
+            }
+        }
+
+        match value {
+            Value::String(string) => check_string(schema, string, path, violations),
+            Value::Number(_) => check_number(schema, value, path, violations),
+            Value::Object(object) => self.check_object(schema, object, path, depth, violations),
+            Value::Array(items) => self.check_array(schema, items, path, depth, violations),
+            _ => {}
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for schema in schemas {
+                self.check(schema, value, path, depth + 1, violations);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas
+                .iter()
+                .any(|schema| self.is_valid(schema, value, depth + 1))
+            {
+                violations.push(format!("{path}: does not match any of the allowed schemas"));
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matching = schemas
+                .iter()
+                .filter(|schema| self.is_valid(schema, value, depth + 1))
+                .count();
+            if matching != 1 {
+                violations.push(format!(
+                    "{path}: must match exactly one of the allowed schemas, but matches {matching}"
+                ));
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+        violations: &mut Vec<String>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    violations.push(format!("{path}: missing required property {name}"));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (name, property) in object {
+            let property_path = format!("{path}.{name}");
+            match (
+                properties.and_then(|properties| properties.get(name)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property_schema), _) => {
+                    self.check(
+                        property_schema,
+                        property,
+                        &property_path,
+                        depth + 1,
+                        violations,
+                    );
+                }
+                (None, Some(Value::Bool(false))) => {
+                    violations.push(format!("{path}: unexpected property {name}"));
+                }
+                (None, Some(additional)) => {
+                    self.check(additional, property, &property_path, depth + 1, violations);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        depth: usize,
+        violations: &mut Vec<String>,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                violations.push(format!("{path}: expected at least {min} items"));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if (items.len() as u64) > max {
+                violations.push(format!("{path}: expected at most {max} items"));
+            }
+        }
+        match schema.get("items") {
+            Some(Value::Array(schemas)) => {
+                for (index, (item, schema)) in items.iter().zip(schemas).enumerate() {
+                    self.check(
+                        schema,
+                        item,
+                        &format!("{path}[{index}]"),
+                        depth + 1,
+                        violations,
+                    );
+                }
+            }
+            Some(item_schema) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check(
+                        item_schema,
+                        item,
+                        &format!("{path}[{index}]"),
+                        depth + 1,
+                        violations,
+                    );
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        if reference == "#" {
+            Some(&self.root)
+        } else {
+            reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+        }
+    }
+}
+
+fn check_string(
+    schema: &Map<String, Value>,
+    string: &str,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let length = string.chars().count() as u64;
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < min {
+            violations.push(format!("{path}: expected at least {min} characters"));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > max {
+            violations.push(format!("{path}: expected at most {max} characters"));
+        }
+    }
+}
+
+fn check_number(
+    schema: &Map<String, Value>,
+    value: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let Some(number) = value.as_f64() else {
+        return;
+    };
+    let limit = |name: &str| schema.get(name).and_then(Value::as_f64);
+    if let Some(minimum) = limit("minimum") {
+        if number < minimum {
+            violations.push(format!(
+                "{path}: expected at least {minimum}, found {value}"
+            ));
+        }
+    }
+    if let Some(maximum) = limit("maximum") {
+        if number > maximum {
+            violations.push(format!("{path}: expected at most {maximum}, found {value}"));
+        }
+    }
+    if let Some(minimum) = limit("exclusiveMinimum") {
+        if number <= minimum {
+            violations.push(format!(
+                "{path}: expected more than {minimum}, found {value}"
+            ));
+        }
+    }
+    if let Some(maximum) = limit("exclusiveMaximum") {
+        if number >= maximum {
+            violations.push(format!(
+                "{path}: expected less than {maximum}, found {value}"
+            ));
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_schema::JsonSchema;
+    use serde_json::json;
+
+    fn schema() -> JsonSchema {
+        JsonSchema::parse(
+            r##"{
+                "type": "object",
+                "properties": {
+                    "name": {"type": "string", "minLength": 1},
+                    "age": {"type": "integer", "minimum": 0},
+                    "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}, "maxItems": 2},
+                    "contact": {"anyOf": [{"type": "null"}, {"type": "string"}]}
+                },
+                "required": ["name", "age"],
+                "additionalProperties": false,
+                "$defs": {
+                    "tag": {"type": "string", "enum": ["admin", "user"]}
+                }
+            }"##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_matching_values() {
+        assert!(schema()
+            .validate(&json!({"name": "Jane", "age": 42, "tags": ["admin"], "contact": null}))
+            .is_empty());
+        assert!(schema()
+            .validate(&json!({"name": "Jane", "age": 42.0}))
+            .is_empty());
+    }
+
+    #[test]
+    fn reports_violations_with_paths() {
+        assert_eq!(
+            schema().validate(&json!({"age": -1, "tags": ["root", "user", "admin"], "extra": 1})),
+            vec![
+                "$: missing required property name",
+                "$.age: expected at least 0, found -1",
+                "$: unexpected property extra",
+                "$.tags: expected at most 2 items",
+                "$.tags[0]: expected one of [\"admin\",\"user\"], found \"root\"",
+            ]
+        );
+        assert_eq!(
+            schema().validate(&json!({"name": "Jane", "age": "42", "contact": 5})),
+            vec![
+                "$.age: expected integer, found string",
+                "$.contact: does not match any of the allowed schemas",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(JsonSchema::parse("[]").is_err());
+        assert!(JsonSchema::parse("{").is_err());
+    }
+}
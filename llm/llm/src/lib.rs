@@ -2,9 +2,22 @@ pub mod chat_stream;
 pub mod config;
 pub mod durability;
 pub mod error;
-
-#[allow(dead_code)]
-pub mod event_source;
+#[cfg(any(test, feature = "test-kit"))]
+pub mod error_contract;
+pub mod image;
+pub mod interceptor;
+pub mod json_schema;
+pub mod metering;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod rate_limit;
+pub mod recording;
+pub mod redaction;
+pub mod session;
+pub mod system;
+pub mod telemetry;
+pub mod timeout;
+pub mod validation;
 
 wit_bindgen::generate!({
     path: "../wit",
@@ -17,30 +30,13 @@ wit_bindgen::generate!({
 
 pub use crate::exports::golem;
 pub use __export_llm_library_impl as export_llm;
+pub use golem_ai_common::event_source;
+pub use golem_ai_common::logging::LoggingState;
+pub use golem_ai_common::retry;
 use std::cell::RefCell;
-use std::str::FromStr;
-
-pub struct LoggingState {
-    logging_initialized: bool,
-}
-
-impl LoggingState {
-    /// Initializes WASI logging based on the `GOLEM_LLM_LOG` environment variable.
-    pub fn init(&mut self) {
-        if !self.logging_initialized {
-            let _ = wasi_logger::Logger::install();
-            let max_level: log::LevelFilter =
-                log::LevelFilter::from_str(&std::env::var("GOLEM_LLM_LOG").unwrap_or_default())
-                    .unwrap_or(log::LevelFilter::Info);
-            log::set_max_level(max_level);
-            self.logging_initialized = true;
-        }
-    }
-}
 
 thread_local! {
     /// This holds the state of our application.
-    pub static LOGGING_STATE: RefCell<LoggingState> = const { RefCell::new(LoggingState {
-        logging_initialized: false,
-    }) };
+    pub static LOGGING_STATE: RefCell<LoggingState> =
+        const { RefCell::new(LoggingState::new("GOLEM_LLM_LOG")) };
 }
@@ -0,0 +1,348 @@
+use crate::golem::llm::llm::{ChatEvent, Cost, Usage};
+use crate::golem::llm::metrics::{CostReport, ModelCost, Period};
+use crate::telemetry;
+use log::warn;
+use serde::Deserialize;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variable holding the unit prices of the models, as a JSON object mapping model
+/// names, optionally prefixed with the provider as in `openai/gpt-4o`, to their price per million
+/// input and output tokens, for example `{"gpt-4o": {"input": 2.5, "output": 10.0}}`
+pub const PRICES_ENV_VAR: &str = "GOLEM_LLM_PRICES";
+
+/// Environment variable holding the currency of the configured prices, `USD` by default
+pub const CURRENCY_ENV_VAR: &str = "GOLEM_LLM_PRICE_CURRENCY";
+
+/// The list prices in USD of the well known models of each provider, keyed by `provider/model`
+const BUNDLED_PRICES: &str = include_str!("../prices.json");
+
+/// The number of most recent usage entries kept in memory for cost reports
+const MAX_LEDGER_ENTRIES: usize = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct UnitPrice {
+    /// Price of one million input tokens
+    #[serde(default)]
+    pub input: f64,
+    /// Price of one million output tokens
+    #[serde(default)]
+    pub output: f64,
+}
+
+impl UnitPrice {
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input + output_tokens as f64 * self.output) / 1_000_000.0
+    }
+}
+
+pub fn parse_prices(raw: &str) -> Result<HashMap<String, UnitPrice>, String> {
+    serde_json::from_str(raw).map_err(|err| format!("Invalid {PRICES_ENV_VAR}: {err}"))
+}
+
+thread_local! {
+    /// The prices are parsed once, as the environment of a worker does not change
+    static CONFIGURED_PRICES: OnceCell<Rc<HashMap<String, UnitPrice>>> = const { OnceCell::new() };
+    static PRICES: RefCell<HashMap<String, Rc<HashMap<String, UnitPrice>>>> =
+        RefCell::new(HashMap::new());
+}
+
+pub fn configured_prices() -> Rc<HashMap<String, UnitPrice>> {
+    CONFIGURED_PRICES.with(|prices| {
+        prices
+            .get_or_init(|| {
+                let prices = match std::env::var(PRICES_ENV_VAR) {
+                    Ok(raw) => parse_prices(&raw).unwrap_or_else(|err| {
+                        warn!("{err}");
+                        HashMap::new()
+                    }),
+                    Err(_) => HashMap::new(),
+                };
+                Rc::new(prices)
+            })
+            .clone()
+    })
+}
+
+/// The configured prices, together with the bundled ones when they are in the same currency
+pub fn prices(currency: &str) -> Rc<HashMap<String, UnitPrice>> {
+    PRICES.with_borrow_mut(|cache| {
+        cache
+            .entry(currency.to_string())
+            .or_insert_with(|| {
+                let mut prices = if currency == "USD" {
+                    parse_prices(BUNDLED_PRICES).expect("Invalid bundled prices")
+                } else {
+                    HashMap::new()
+                };
+                prices.extend(configured_prices().iter().map(|(k, v)| (k.clone(), *v)));
+                Rc::new(prices)
+            })
+            .clone()
+    })
+}
+
+pub fn currency() -> String {
+    std::env::var(CURRENCY_ENV_VAR).unwrap_or_else(|_| "USD".to_string())
+}
+
+/// The unit price of a model, given either by its name or by its provider and name
+pub fn find_price<'a>(
+    prices: &'a HashMap<String, UnitPrice>,
+    provider: &str,
+    model: &str,
+) -> Option<&'a UnitPrice> {
+    prices
+        .get(model)
+        .or_else(|| prices.get(&format!("{provider}/{model}")))
+}
+
+/// The cost of a response with the given usage, when the usage and the price of the model are known
+pub fn response_cost(
+    prices: &HashMap<String, UnitPrice>,
+    currency: String,
+    provider: &str,
+    model: &str,
+    usage: Option<&Usage>,
+) -> Option<Cost> {
+    let usage =
+        usage.filter(|usage| usage.input_tokens.is_some() || usage.output_tokens.is_some())?;
+    let price = find_price(prices, provider, model)?;
+    Some(Cost {
+        input_cost: price.cost(usage.input_tokens.unwrap_or_default() as u64, 0),
+        output_cost: price.cost(0, usage.output_tokens.unwrap_or_default() as u64),
+        currency,
+    })
+}
+
+/// Sets the cost of a response returned by the currently active call
+pub fn add_cost(event: &mut ChatEvent) {
+    if let (ChatEvent::Message(response), Some(call)) = (event, telemetry::current_call()) {
+        let currency = currency();
+        response.metadata.cost = response_cost(
+            &prices(&currency),
+            currency,
+            call.provider(),
+            call.model(),
+            response.metadata.usage.as_ref(),
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct UsageEntry {
+    timestamp: u64,
+    provider: String,
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+/// Usage entries of the calls made by the worker, used to produce cost reports
+#[derive(Default)]
+pub struct Ledger {
+    entries: VecDeque<UsageEntry>,
+}
+
+impl Ledger {
+    pub fn record(
+        &mut self,
+        timestamp: u64,
+        provider: &str,
+        model: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) {
+        if self.entries.len() == MAX_LEDGER_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(UsageEntry {
+            timestamp,
+            provider: provider.to_string(),
+            model: model.to_string(),
+            input_tokens,
+            output_tokens,
+        });
+    }
+
+    pub fn report(
+        &self,
+        period: &Period,
+        prices: &HashMap<String, UnitPrice>,
+        currency: String,
+    ) -> CostReport {
+        let mut models: BTreeMap<(String, String), ModelCost> = BTreeMap::new();
+        for entry in self.entries.iter().filter(|entry| {
+            period.start.is_none_or(|start| entry.timestamp >= start)
+                && period.end.is_none_or(|end| entry.timestamp < end)
+        }) {
+            let model_cost = models
+                .entry((entry.provider.clone(), entry.model.clone()))
+                .or_insert_with(|| ModelCost {
+                    provider: entry.provider.clone(),
+                    model: entry.model.clone(),
+                    calls: 0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    cost: None,
+                });
+            model_cost.calls += 1;
+            model_cost.input_tokens += entry.input_tokens;
+            model_cost.output_tokens += entry.output_tokens;
+        }
+
+        let models: Vec<ModelCost> = models
+            .into_values()
+            .map(|mut model_cost| {
+                model_cost.cost = find_price(prices, &model_cost.provider, &model_cost.model)
+                    .map(|price| price.cost(model_cost.input_tokens, model_cost.output_tokens));
+                model_cost
+            })
+            .collect();
+
+        CostReport {
+            currency,
+            total_cost: models.iter().filter_map(|model| model.cost).sum(),
+            models,
+        }
+    }
+}
+
+thread_local! {
+    static LEDGER: RefCell<Ledger> = RefCell::new(Ledger::default());
+}
+
+/// Records the token usage of a call to the ledger used by `get-cost-report`
+pub fn record_usage(provider: &str, model: &str, input_tokens: u64, output_tokens: u64) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    LEDGER.with_borrow_mut(|ledger| {
+        ledger.record(timestamp, provider, model, input_tokens, output_tokens)
+    });
+}
+
+pub fn cost_report(period: Period) -> CostReport {
+    let currency = currency();
+    LEDGER.with_borrow(|ledger| ledger.report(&period, &prices(&currency), currency))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{Cost, Usage};
+    use crate::golem::llm::metrics::Period;
+    use crate::metering::{parse_prices, prices, response_cost, Ledger};
+
+    fn ledger() -> Ledger {
+        let mut ledger = Ledger::default();
+        ledger.record(100, "openai", "gpt-4o", 1000, 500);
+        ledger.record(200, "openai", "gpt-4o", 3000, 1500);
+        ledger.record(300, "anthropic", "claude", 2000, 0);
+        ledger.record(400, "ollama", "qwen3", 5000, 5000);
+        ledger
+    }
+
+    #[test]
+    fn parses_prices() {
+        let prices =
+            parse_prices(r#"{"gpt-4o": {"input": 2.5, "output": 10.0}, "claude": {"input": 3}}"#)
+                .unwrap();
+        assert_eq!(prices["gpt-4o"].input, 2.5);
+        assert_eq!(prices["claude"].output, 0.0);
+        assert!(parse_prices("not json").is_err());
+    }
+
+    #[test]
+    fn reports_cost_per_model() {
+        let prices =
+            parse_prices(r#"{"gpt-4o": {"input": 2.5, "output": 10.0}, "claude": {"input": 3}}"#)
+                .unwrap();
+        let report = ledger().report(
+            &Period {
+                start: None,
+                end: None,
+            },
+            &prices,
+            "USD".to_string(),
+        );
+
+        assert_eq!(report.models.len(), 3);
+        let gpt = report.models.iter().find(|m| m.model == "gpt-4o").unwrap();
+        assert_eq!(gpt.calls, 2);
+        assert_eq!(gpt.input_tokens, 4000);
+        assert_eq!(gpt.output_tokens, 2000);
+        assert_eq!(gpt.cost, Some(0.03));
+        let qwen = report.models.iter().find(|m| m.model == "qwen3").unwrap();
+        assert_eq!(qwen.cost, None);
+        assert!((report.total_cost - 0.036).abs() < 1e-9);
+    }
+
+    #[test]
+    fn filters_by_period() {
+        let report = ledger().report(
+            &Period {
+                start: Some(200),
+                end: Some(400),
+            },
+            &parse_prices("{}").unwrap(),
+            "USD".to_string(),
+        );
+
+        let calls: u32 = report.models.iter().map(|m| m.calls).sum();
+        assert_eq!(calls, 2);
+        assert_eq!(report.total_cost, 0.0);
+    }
+
+    #[test]
+    fn computes_the_cost_of_a_response() {
+        let prices = parse_prices(
+            r#"{"gpt-4o": {"input": 2.5, "output": 10.0}, "mistral/codestral": {"input": 1}}"#,
+        )
+        .unwrap();
+        let usage = Usage {
+            input_tokens: Some(2000),
+            output_tokens: Some(100),
+            total_tokens: Some(2100),
+            cached_tokens: None,
+        };
+
+        assert_eq!(
+            response_cost(&prices, "USD".to_string(), "openai", "gpt-4o", Some(&usage)),
+            Some(Cost {
+                input_cost: 0.005,
+                output_cost: 0.001,
+                currency: "USD".to_string(),
+            })
+        );
+        assert_eq!(
+            response_cost(
+                &prices,
+                "USD".to_string(),
+                "mistral",
+                "codestral",
+                Some(&usage)
+            )
+            .map(|cost| cost.input_cost),
+            Some(0.002)
+        );
+        assert_eq!(
+            response_cost(&prices, "USD".to_string(), "ollama", "qwen3", Some(&usage)),
+            None
+        );
+        assert_eq!(
+            response_cost(&prices, "USD".to_string(), "openai", "gpt-4o", None),
+            None
+        );
+    }
+
+    #[test]
+    fn bundles_prices_in_usd() {
+        let bundled = prices("USD");
+        assert!(bundled.contains_key("openai/gpt-4o"));
+        assert!(bundled.contains_key("anthropic/claude-3-7-sonnet-20250219"));
+        assert!(prices("EUR").is_empty());
+    }
+}
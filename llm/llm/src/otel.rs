@@ -0,0 +1,278 @@
+//! Export of the recorded calls as OpenTelemetry spans.
+//!
+//! When `GOLEM_LLM_OTEL_EXPORT` is set to `log`, every call recorded by the telemetry module is
+//! written to the log as an OTLP/JSON `ExportTraceServiceRequest`, with the `otel` target, so a
+//! log collector can forward it to any OTLP compatible backend. Components have no direct access
+//! to a tracing backend, so the log is the only exporter. The attributes follow the OpenTelemetry
+//! semantic conventions of generative AI client spans.
+
+use crate::golem::llm::llm::ErrorCode;
+use crate::golem::llm::metrics::CallMetrics;
+use log::{info, warn};
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Environment variable selecting the exporter of the spans. Only `log` is supported.
+pub const OTEL_EXPORT_ENV_VAR: &str = "GOLEM_LLM_OTEL_EXPORT";
+
+/// Environment variable overriding the `service.name` of the exported spans
+pub const OTEL_SERVICE_NAME_ENV_VAR: &str = "GOLEM_LLM_OTEL_SERVICE_NAME";
+
+const DEFAULT_SERVICE_NAME: &str = "golem-llm";
+
+/// The `SPAN_KIND_CLIENT` span kind of OTLP
+const SPAN_KIND_CLIENT: u32 = 3;
+
+const STATUS_CODE_OK: u32 = 1;
+const STATUS_CODE_ERROR: u32 = 2;
+
+/// The exporter configured through the environment, read on first use
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Exporter {
+    Disabled,
+    Log,
+}
+
+thread_local! {
+    static EXPORTER: RefCell<Option<Exporter>> = const { RefCell::new(None) };
+    static ID_COUNTER: Cell<u64> = const { Cell::new(0) };
+    static ID_HASHER: RandomState = RandomState::new();
+}
+
+fn exporter() -> Exporter {
+    EXPORTER.with_borrow_mut(|exporter| {
+        *exporter.get_or_insert_with(|| match std::env::var(OTEL_EXPORT_ENV_VAR) {
+            Ok(value) if value == "log" => Exporter::Log,
+            Ok(value) if value.is_empty() || value == "none" => Exporter::Disabled,
+            Ok(value) => {
+                warn!("Unsupported {OTEL_EXPORT_ENV_VAR}: {value}, spans are not exported");
+                Exporter::Disabled
+            }
+            Err(_) => Exporter::Disabled,
+        })
+    })
+}
+
+/// A random identifier, unique within the worker
+fn random_id() -> u64 {
+    let counter = ID_COUNTER.with(|counter| {
+        counter.set(counter.get() + 1);
+        counter.get()
+    });
+    ID_HASHER.with(|hasher| hasher.hash_one(counter))
+}
+
+/// The identifiers of a span, as hex strings. Every call is the root span of its own trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanIds {
+    pub trace_id: String,
+    pub span_id: String,
+}
+
+impl SpanIds {
+    pub fn generate() -> Self {
+        Self {
+            trace_id: format!("{:016x}{:016x}", random_id(), random_id()),
+            span_id: format!("{:016x}", random_id()),
+        }
+    }
+}
+
+/// The name of an error code as it appears in WIT, such as `rate-limit-exceeded`
+fn error_type(code: ErrorCode) -> String {
+    let mut name = String::new();
+    for (index, char) in format!("{code:?}").chars().enumerate() {
+        if char.is_uppercase() && index > 0 {
+            name.push('-');
+        }
+        name.push(char.to_ascii_lowercase());
+    }
+    name
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+/// Integers are encoded as strings in OTLP/JSON
+fn int_attribute(key: &str, value: impl ToString) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+/// Creates the OTLP/JSON export request of a single call, which ended at `end`
+pub fn to_otlp_json(
+    metrics: &CallMetrics,
+    end: SystemTime,
+    ids: &SpanIds,
+    service_name: &str,
+) -> Value {
+    let start = end
+        .checked_sub(Duration::from_millis(metrics.latency_ms))
+        .unwrap_or(end);
+
+    let mut attributes = vec![
+        string_attribute("gen_ai.system", &metrics.provider),
+        string_attribute("gen_ai.operation.name", &metrics.operation),
+        string_attribute("gen_ai.request.model", &metrics.model),
+    ];
+    if let Some(input_tokens) = metrics.input_tokens {
+        attributes.push(int_attribute("gen_ai.usage.input_tokens", input_tokens));
+    }
+    if let Some(output_tokens) = metrics.output_tokens {
+        attributes.push(int_attribute("gen_ai.usage.output_tokens", output_tokens));
+    }
+    if let Some(http_status) = metrics.http_status {
+        attributes.push(int_attribute("http.response.status_code", http_status));
+    }
+    let status = match metrics.error {
+        Some(code) => {
+            let error_type = error_type(code);
+            attributes.push(string_attribute("error.type", &error_type));
+            json!({ "code": STATUS_CODE_ERROR, "message": error_type })
+        }
+        None => json!({ "code": STATUS_CODE_OK }),
+    };
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [string_attribute("service.name", service_name)]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "golem-llm" },
+                "spans": [{
+                    "traceId": ids.trace_id,
+                    "spanId": ids.span_id,
+                    "name": format!("{} {}", metrics.operation, metrics.model),
+                    "kind": SPAN_KIND_CLIENT,
+                    "startTimeUnixNano": unix_nanos(start),
+                    "endTimeUnixNano": unix_nanos(end),
+                    "attributes": attributes,
+                    "status": status,
+                }]
+            }]
+        }]
+    })
+}
+
+/// Exports a recorded call with the exporter configured in `GOLEM_LLM_OTEL_EXPORT`, if any
+pub fn export(metrics: &CallMetrics) {
+    if exporter() == Exporter::Log {
+        let service_name = std::env::var(OTEL_SERVICE_NAME_ENV_VAR)
+            .unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_string());
+        let span = to_otlp_json(
+            metrics,
+            SystemTime::now(),
+            &SpanIds::generate(),
+            &service_name,
+        );
+        info!(target: "otel", "{span}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::ErrorCode;
+    use crate::golem::llm::metrics::CallMetrics;
+    use crate::otel::{to_otlp_json, SpanIds};
+    use serde_json::json;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn metrics(error: Option<ErrorCode>) -> CallMetrics {
+        CallMetrics {
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            operation: "send".to_string(),
+            latency_ms: 1500,
+            http_status: Some(200),
+            input_tokens: Some(12),
+            output_tokens: Some(34),
+            error,
+        }
+    }
+
+    fn ids() -> SpanIds {
+        SpanIds {
+            trace_id: "0af7651916cd43dd8448eb211c80319c".to_string(),
+            span_id: "b7ad6b7169203331".to_string(),
+        }
+    }
+
+    #[test]
+    fn exports_calls_as_otlp_spans() {
+        let end = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let request = to_otlp_json(&metrics(None), end, &ids(), "my-agent");
+
+        assert_eq!(
+            request,
+            json!({
+                "resourceSpans": [{
+                    "resource": {
+                        "attributes": [
+                            { "key": "service.name", "value": { "stringValue": "my-agent" } }
+                        ]
+                    },
+                    "scopeSpans": [{
+                        "scope": { "name": "golem-llm" },
+                        "spans": [{
+                            "traceId": "0af7651916cd43dd8448eb211c80319c",
+                            "spanId": "b7ad6b7169203331",
+                            "name": "send gpt-4o",
+                            "kind": 3,
+                            "startTimeUnixNano": "1699999998500000000",
+                            "endTimeUnixNano": "1700000000000000000",
+                            "attributes": [
+                                { "key": "gen_ai.system", "value": { "stringValue": "openai" } },
+                                { "key": "gen_ai.operation.name", "value": { "stringValue": "send" } },
+                                { "key": "gen_ai.request.model", "value": { "stringValue": "gpt-4o" } },
+                                { "key": "gen_ai.usage.input_tokens", "value": { "intValue": "12" } },
+                                { "key": "gen_ai.usage.output_tokens", "value": { "intValue": "34" } },
+                                { "key": "http.response.status_code", "value": { "intValue": "200" } }
+                            ],
+                            "status": { "code": 1 }
+                        }]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn marks_failed_calls_as_errors() {
+        let request = to_otlp_json(
+            &metrics(Some(ErrorCode::RateLimitExceeded)),
+            UNIX_EPOCH + Duration::from_secs(60),
+            &ids(),
+            "golem-llm",
+        );
+        let span = &request["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+
+        assert_eq!(
+            span["status"],
+            json!({ "code": 2, "message": "rate-limit-exceeded" })
+        );
+        assert_eq!(
+            span["attributes"][6],
+            json!({ "key": "error.type", "value": { "stringValue": "rate-limit-exceeded" } })
+        );
+    }
+
+    #[test]
+    fn generates_distinct_ids() {
+        let first = SpanIds::generate();
+        let second = SpanIds::generate();
+        assert_eq!(first.trace_id.len(), 32);
+        assert_eq!(first.span_id.len(), 16);
+        assert_ne!(first, second);
+    }
+}
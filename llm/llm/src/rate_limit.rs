@@ -0,0 +1,388 @@
+//! Client-side rate limiting of the requests sent to the providers.
+//!
+//! Each model can be given a budget of requests and tokens per minute in `GOLEM_LLM_RATE_LIMITS`,
+//! enforced with token buckets refilling continuously. A request exceeding the budget either waits
+//! until enough of it is available again, or fails with `rate-limit-exceeded` without reaching the
+//! provider. The tokens of a request are estimated from the size of its body when it is sent, and
+//! the estimate is replaced by the usage reported by the provider once the response arrives.
+//! Inline images are counted as a fixed number of tokens instead of by the size of their data.
+
+use crate::golem::llm::llm::{Error, ErrorCode};
+use crate::telemetry::current_call;
+use log::{debug, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Environment variable holding the budgets, as a JSON object mapping model names (or `*` for all
+/// the other models) to their limits, for example
+/// `{"gpt-4o": {"requests_per_minute": 60, "tokens_per_minute": 30000, "wait": true}}`
+pub const RATE_LIMITS_ENV_VAR: &str = "GOLEM_LLM_RATE_LIMITS";
+
+/// The key of the limits applying to the models without their own entry
+const DEFAULT_MODEL: &str = "*";
+
+/// The approximate number of bytes of a request body per token
+const BYTES_PER_TOKEN: usize = 4;
+
+/// The approximate number of tokens of an inline image, about what the providers charge for a
+/// picture of a megapixel
+const TOKENS_PER_IMAGE: u64 = 1000;
+
+/// The shortest string of a request body taken as base64 encoded inline data
+const MIN_INLINE_DATA_LEN: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimit {
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+    /// Whether requests exceeding the budget wait for it instead of failing
+    #[serde(default)]
+    pub wait: bool,
+}
+
+pub fn parse_rate_limits(raw: &str) -> Result<HashMap<String, RateLimit>, String> {
+    serde_json::from_str(raw).map_err(|err| format!("Invalid {RATE_LIMITS_ENV_VAR}: {err}"))
+}
+
+pub fn configured_rate_limits() -> HashMap<String, RateLimit> {
+    match std::env::var(RATE_LIMITS_ENV_VAR) {
+        Ok(raw) => parse_rate_limits(&raw).unwrap_or_else(|err| {
+            warn!("{err}");
+            HashMap::new()
+        }),
+        Err(_) => HashMap::new(),
+    }
+}
+
+/// A budget refilling at a constant rate up to the amount allowed per minute
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        let capacity = per_minute.max(1) as f64;
+        Self {
+            capacity,
+            available: capacity,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.available = (self.available + elapsed * self.capacity / 60.0).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    /// The time until `amount` is available. Amounts larger than the whole budget only wait for
+    /// a full bucket, so they are not rejected forever.
+    fn wait_time(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.capacity) - self.available;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.capacity)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ModelBudget {
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    /// The estimated tokens taken by the last request, replaced by the reported usage
+    estimated_tokens: f64,
+}
+
+/// The budgets of the rate limited models
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+    budgets: HashMap<String, ModelBudget>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: HashMap<String, RateLimit>) -> Self {
+        Self {
+            limits,
+            budgets: HashMap::new(),
+        }
+    }
+
+    fn limit(&self, model: &str) -> Option<&RateLimit> {
+        self.limits
+            .get(model)
+            .or_else(|| self.limits.get(DEFAULT_MODEL))
+    }
+
+    /// Whether requests of the model wait for the budget instead of failing
+    pub fn waits(&self, model: &str) -> bool {
+        self.limit(model).is_some_and(|limit| limit.wait)
+    }
+
+    /// Takes a request and `tokens` estimated tokens from the budget of the model, or returns how
+    /// long to wait until they are available
+    pub fn try_acquire(&mut self, model: &str, tokens: u64, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit(model).copied() else {
+            return Ok(());
+        };
+        let budget = self
+            .budgets
+            .entry(model.to_string())
+            .or_insert_with(|| ModelBudget {
+                requests: limit
+                    .requests_per_minute
+                    .map(|per_minute| Bucket::new(per_minute, now)),
+                tokens: limit
+                    .tokens_per_minute
+                    .map(|per_minute| Bucket::new(per_minute, now)),
+                estimated_tokens: 0.0,
+            });
+
+        let tokens = tokens as f64;
+        let mut wait = Duration::ZERO;
+        for (bucket, amount) in [(&mut budget.requests, 1.0), (&mut budget.tokens, tokens)] {
+            if let Some(bucket) = bucket {
+                bucket.refill(now);
+                wait = wait.max(bucket.wait_time(amount));
+            }
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        if let Some(bucket) = &mut budget.requests {
+            bucket.available -= 1.0;
+        }
+        if let Some(bucket) = &mut budget.tokens {
+            bucket.available -= tokens;
+        }
+        budget.estimated_tokens = tokens;
+        Ok(())
+    }
+
+    /// Charges the tokens reported by the provider for the last request of the model, in place of
+    /// the estimate taken when it was sent
+    pub fn record_usage(&mut self, model: &str, tokens: u64, now: Instant) {
+        if let Some(budget) = self.budgets.get_mut(model) {
+            if let Some(bucket) = &mut budget.tokens {
+                bucket.refill(now);
+                bucket.available = (bucket.available + budget.estimated_tokens - tokens as f64)
+                    .min(bucket.capacity);
+            }
+            budget.estimated_tokens = 0.0;
+        }
+    }
+}
+
+thread_local! {
+    static RATE_LIMITER: RefCell<Option<RateLimiter>> = const { RefCell::new(None) };
+}
+
+fn with_rate_limiter<T>(f: impl FnOnce(&mut RateLimiter) -> T) -> T {
+    RATE_LIMITER.with_borrow_mut(|rate_limiter| {
+        f(rate_limiter.get_or_insert_with(|| RateLimiter::new(configured_rate_limits())))
+    })
+}
+
+/// Takes a request from the budget of the model of the active call, waiting for it or failing
+/// with `rate-limit-exceeded` when it is used up. The tokens are estimated from `body`.
+pub fn acquire(body: &str) -> Result<(), Error> {
+    let Some(call) = current_call() else {
+        return Ok(());
+    };
+    let model = call.model();
+    let tokens = estimate_tokens(body);
+    loop {
+        let (result, waits) = with_rate_limiter(|rate_limiter| {
+            (
+                rate_limiter.try_acquire(model, tokens, Instant::now()),
+                rate_limiter.waits(model),
+            )
+        });
+        match result {
+            Ok(()) => return Ok(()),
+            Err(delay) if waits => {
+                debug!("Rate limit of {model} reached, waiting {delay:?}");
+                std::thread::sleep(delay);
+            }
+            Err(delay) => {
+                return Err(Error {
+                    code: ErrorCode::RateLimitExceeded,
+                    message: format!(
+                        "Client-side rate limit of {model} exceeded, retry in {:.1}s",
+                        delay.as_secs_f64()
+                    ),
+                    provider_error_json: None,
+                })
+            }
+        }
+    }
+}
+
+/// Estimates the tokens of a request body from its size, leaving out the base64 encoded data of
+/// inline images, which are counted as `TOKENS_PER_IMAGE` each
+fn estimate_tokens(body: &str) -> u64 {
+    let mut inline_data = Vec::new();
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        collect_inline_data(&value, &mut inline_data);
+    }
+    let data_len = inline_data.iter().sum::<usize>();
+    let text_tokens = (body.len().saturating_sub(data_len) / BYTES_PER_TOKEN) as u64;
+    text_tokens + inline_data.len() as u64 * TOKENS_PER_IMAGE
+}
+
+/// Collects the lengths of the strings holding inline data, either as `data:` URLs or as plain
+/// base64 like in the image sources of Anthropic and Gemini
+fn collect_inline_data(value: &Value, lengths: &mut Vec<usize>) {
+    match value {
+        Value::String(text) => {
+            let data = match text.split_once(";base64,") {
+                Some((prefix, data)) if prefix.starts_with("data:") => data,
+                _ => text,
+            };
+            let is_base64 = data
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'='));
+            if data.len() >= MIN_INLINE_DATA_LEN && is_base64 {
+                lengths.push(text.len());
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_inline_data(value, lengths);
+            }
+        }
+        Value::Object(fields) => {
+            for value in fields.values() {
+                collect_inline_data(value, lengths);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Charges the usage reported for a call to the token budget of its model
+pub fn record_usage(model: &str, input_tokens: u64, output_tokens: u64) {
+    with_rate_limiter(|rate_limiter| {
+        rate_limiter.record_usage(model, input_tokens + output_tokens, Instant::now())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rate_limit::{estimate_tokens, parse_rate_limits, RateLimiter, TOKENS_PER_IMAGE};
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    fn rate_limiter() -> RateLimiter {
+        RateLimiter::new(
+            parse_rate_limits(
+                r#"{
+                    "gpt-4o": {"requests_per_minute": 2, "tokens_per_minute": 600},
+                    "*": {"tokens_per_minute": 60, "wait": true}
+                }"#,
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn parses_rate_limits() {
+        let limits = parse_rate_limits(r#"{"gpt-4o": {"requests_per_minute": 10}}"#).unwrap();
+        assert_eq!(limits["gpt-4o"].requests_per_minute, Some(10));
+        assert_eq!(limits["gpt-4o"].tokens_per_minute, None);
+        assert!(!limits["gpt-4o"].wait);
+        assert!(parse_rate_limits("not json").is_err());
+    }
+
+    #[test]
+    fn limits_requests_per_minute() {
+        let mut rate_limiter = rate_limiter();
+        let now = Instant::now();
+        assert_eq!(rate_limiter.try_acquire("gpt-4o", 10, now), Ok(()));
+        assert_eq!(rate_limiter.try_acquire("gpt-4o", 10, now), Ok(()));
+        assert_eq!(
+            rate_limiter.try_acquire("gpt-4o", 10, now),
+            Err(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limiter.try_acquire("gpt-4o", 10, now + Duration::from_secs(30)),
+            Ok(())
+        );
+        assert!(!rate_limiter.waits("gpt-4o"));
+    }
+
+    #[test]
+    fn limits_tokens_per_minute() {
+        let mut rate_limiter = rate_limiter();
+        let now = Instant::now();
+        assert!(rate_limiter.waits("claude"));
+        assert_eq!(rate_limiter.try_acquire("claude", 40, now), Ok(()));
+        assert_eq!(
+            rate_limiter.try_acquire("claude", 40, now),
+            Err(Duration::from_secs(20))
+        );
+        // Requests larger than the budget only wait for a full bucket
+        assert_eq!(
+            rate_limiter.try_acquire("claude", 1000, now),
+            Err(Duration::from_secs(40))
+        );
+    }
+
+    #[test]
+    fn replaces_the_estimate_with_the_reported_usage() {
+        let mut rate_limiter = rate_limiter();
+        let now = Instant::now();
+        rate_limiter.try_acquire("claude", 40, now).unwrap();
+        rate_limiter.record_usage("claude", 10, now);
+        assert_eq!(rate_limiter.try_acquire("claude", 40, now), Ok(()));
+        rate_limiter.record_usage("claude", 100, now);
+        assert_eq!(
+            rate_limiter.try_acquire("claude", 10, now),
+            Err(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn counts_inline_images_as_a_fixed_number_of_tokens() {
+        let text = "Describe the picture ".repeat(20);
+        let image = "iVBORw0KGgo".repeat(10000);
+        let url = format!("data:image/png;base64,{image}");
+        let text_only = json!({ "messages": [{ "content": text }] }).to_string();
+        let with_images = json!({
+            "messages": [{
+                "content": [
+                    { "type": "text", "text": text },
+                    { "type": "image_url", "image_url": { "url": url } },
+                    { "type": "image", "source": { "type": "base64", "data": image } }
+                ]
+            }]
+        })
+        .to_string();
+        let text_tokens = estimate_tokens(&text_only);
+        assert_eq!(text_tokens, text_only.len() as u64 / 4);
+        let image_tokens = estimate_tokens(&with_images) - 2 * TOKENS_PER_IMAGE;
+        assert!(image_tokens > text_tokens && image_tokens < text_tokens + 50);
+    }
+
+    #[test]
+    fn does_not_limit_unconfigured_models() {
+        let mut rate_limiter = RateLimiter::new(parse_rate_limits("{}").unwrap());
+        for _ in 0..100 {
+            assert_eq!(
+                rate_limiter.try_acquire("gpt-4o", 100000, Instant::now()),
+                Ok(())
+            );
+        }
+    }
+}
@@ -0,0 +1,537 @@
+//! Recording of the exchanges with the providers as JSON fixtures, and their replay.
+//!
+//! With the `recording` feature, the exchanges are recorded to the directory given in
+//! `GOLEM_LLM_RECORD_DIR`, and the requests are answered from the fixtures of the directory given
+//! in `GOLEM_LLM_REPLAY_DIR` instead of being sent, so the test components can run without
+//! credentials or network access. Without the feature both variables are ignored.
+
+use crate::chat_stream::{decode_events, LlmChatStreamState};
+use crate::error::error_code_from_status;
+use crate::event_source::{EventObserver, EventSource, MessageEvent};
+use crate::golem::llm::llm::{Error, ErrorCode, StreamEvent};
+use crate::interceptor::{self, IncomingResponse};
+use crate::telemetry;
+use log::{trace, warn};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variable pointing to a directory where provider request/response pairs get
+/// recorded as fixture files. Recording is disabled when it is not set.
+pub const RECORD_DIR_ENV_VAR: &str = "GOLEM_LLM_RECORD_DIR";
+
+/// Environment variable pointing to a directory of fixtures replayed instead of sending the
+/// requests to the provider
+pub const REPLAY_DIR_ENV_VAR: &str = "GOLEM_LLM_REPLAY_DIR";
+
+thread_local! {
+    /// The fixtures left to replay, loaded on the first request
+    static REPLAY: RefCell<Option<Result<Replay, Error>>> = const { RefCell::new(None) };
+}
+
+/// A recorded exchange with a provider's HTTP API.
+///
+/// Only the request body is recorded, never the request headers, so API keys don't end up in
+/// the fixtures.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub provider: String,
+    pub operation: String,
+    pub request: serde_json::Value,
+    pub status: u16,
+    /// The response body of non-streaming requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// The events received from streaming requests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<RecordedEvent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub event: String,
+    pub data: String,
+}
+
+impl Fixture {
+    /// Loads a fixture previously written by a [`Recorder`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read fixture {}: {err}", path.display()))?;
+        serde_json::from_str(&raw)
+            .map_err(|err| format!("Failed to parse fixture {}: {err}", path.display()))
+    }
+
+    /// Loads all the fixtures of a directory, ordered by file name, for example the fixtures
+    /// checked in next to a provider's tests
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Vec<Self>, String> {
+        let dir = dir.as_ref();
+        let mut paths = std::fs::read_dir(dir)
+            .map_err(|err| format!("Failed to read fixture directory {}: {err}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths.into_iter().map(Self::load).collect()
+    }
+
+    /// Decodes the recorded request body, to compare it with the request built by the provider
+    pub fn request<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.request.clone())
+            .map_err(|err| format!("Failed to decode request body: {err}"))
+    }
+
+    /// Decodes the recorded response body the same way the provider client does
+    pub fn response<T: DeserializeOwned>(&self) -> Result<T, String> {
+        let body = self
+            .body
+            .as_ref()
+            .ok_or_else(|| format!("Fixture of {} has no response body", self.operation))?;
+        serde_json::from_str(body).map_err(|err| format!("Failed to decode response body: {err}"))
+    }
+
+    /// The recorded response status
+    pub fn status_code(&self) -> Result<StatusCode, Error> {
+        StatusCode::from_u16(self.status).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Invalid status in the fixture of {}: {err}", self.operation),
+            provider_error_json: None,
+        })
+    }
+
+    /// Replays the recorded stream events through a provider's stream, decoding them the same way
+    /// as `LlmChatStream`, including the events the provider holds back with
+    /// `take_pending_event`
+    pub fn replay_stream(&self, stream: &impl LlmChatStreamState) -> Vec<StreamEvent> {
+        let mut result = Vec::new();
+        for event in &self.events {
+            if event.data == "[DONE]" {
+                continue;
+            }
+            let events = decode_events(stream, &event.data);
+            let finished = events
+                .iter()
+                .any(|event| matches!(event, StreamEvent::Finish(_)));
+            result.extend(events);
+            if finished {
+                stream.set_finished();
+                break;
+            }
+        }
+        result
+    }
+}
+
+/// The fixtures of a directory, replayed in the order of their file names. Each request is
+/// answered by the first fixture left for its provider and operation.
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    fixtures: Vec<Fixture>,
+}
+
+impl Replay {
+    pub fn new(fixtures: Vec<Fixture>) -> Self {
+        Self { fixtures }
+    }
+
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, String> {
+        Fixture::load_dir(dir).map(Self::new)
+    }
+
+    /// Takes the next fixture of an operation. The recorded request only has to match when the
+    /// conversions are unchanged, so a different one is only reported.
+    pub fn next(
+        &mut self,
+        provider: &str,
+        operation: &str,
+        request: &serde_json::Value,
+    ) -> Result<Fixture, Error> {
+        let index = self
+            .fixtures
+            .iter()
+            .position(|fixture| fixture.provider == provider && fixture.operation == operation)
+            .ok_or_else(|| Error {
+                code: ErrorCode::InternalError,
+                message: format!("No recorded {operation} exchange of {provider} left to replay"),
+                provider_error_json: None,
+            })?;
+        let fixture = self.fixtures.remove(index);
+        if fixture.request != *request {
+            warn!("Replaying a {operation} exchange of {provider} recorded for another request");
+        }
+        Ok(fixture)
+    }
+}
+
+/// Records a single request/response pair into the directory given by `GOLEM_LLM_RECORD_DIR`.
+///
+/// The fixture is written when the recorder is dropped, so streaming requests get recorded
+/// together with all the events received until the stream got closed. The provider name and the
+/// response status are also reported to the `telemetry` module and the responses to the registered
+/// interceptors, whether recording is enabled or not.
+///
+/// When replaying, the recorder takes the fixture answering the request, and the provider client
+/// returns `replayed_response` or `replayed_stream` instead of sending the request.
+pub struct Recorder {
+    exchange: Option<(String, String)>,
+    fixture: Option<Fixture>,
+    replayed: Option<Result<Fixture, Error>>,
+}
+
+impl Recorder {
+    pub fn new(provider: &str, operation: &str, request: &impl Serialize) -> Self {
+        telemetry::set_provider(provider);
+        let replayed = replay_dir().map(|dir| {
+            let request = serde_json::to_value(request).unwrap_or_default();
+            REPLAY.with_borrow_mut(|replay| {
+                let replay = replay.get_or_insert_with(|| {
+                    Replay::load_dir(&dir).map_err(|message| Error {
+                        code: ErrorCode::InternalError,
+                        message,
+                        provider_error_json: None,
+                    })
+                });
+                match replay {
+                    Ok(replay) => replay.next(provider, operation, &request),
+                    Err(error) => Err(error.clone()),
+                }
+            })
+        });
+        let fixture = if replayed.is_none() && is_recording() {
+            Some(Fixture {
+                provider: provider.to_string(),
+                operation: operation.to_string(),
+                request: serde_json::to_value(request).unwrap_or_default(),
+                status: 0,
+                body: None,
+                events: Vec::new(),
+            })
+        } else {
+            None
+        };
+        Self {
+            exchange: Some((provider.to_string(), operation.to_string())),
+            fixture,
+            replayed,
+        }
+    }
+
+    /// A recorder that never writes anything
+    pub fn disabled() -> Self {
+        Self {
+            exchange: None,
+            fixture: None,
+            replayed: None,
+        }
+    }
+
+    /// The recorded status and body answering a request when replaying, reported like a received
+    /// response
+    pub fn replayed_response(&mut self) -> Result<Option<(StatusCode, String)>, Error> {
+        let Some(fixture) = self.replayed.take().transpose()? else {
+            return Ok(None);
+        };
+        let status = fixture.status_code()?;
+        let body = fixture.body.unwrap_or_default();
+        self.record_response(status, &body);
+        Ok(Some((status, body)))
+    }
+
+    /// The recorded events answering a streaming request when replaying
+    pub fn replayed_stream(&mut self) -> Result<Option<EventSource>, Error> {
+        let Some(fixture) = self.replayed.take().transpose()? else {
+            return Ok(None);
+        };
+        let status = fixture.status_code()?;
+        self.record_status(status);
+        if status != StatusCode::OK {
+            return Err(Error {
+                code: error_code_from_status(status),
+                message: format!("Recorded stream failed with status {status}"),
+                provider_error_json: None,
+            });
+        }
+        Ok(Some(EventSource::replay(fixture.events.into_iter().map(
+            |event| MessageEvent {
+                event: event.event,
+                data: event.data,
+                ..MessageEvent::default()
+            },
+        ))))
+    }
+
+    pub fn record_response(&mut self, status: StatusCode, body: &str) {
+        telemetry::record_http_status(status.as_u16());
+        self.observe(status, Some(body));
+        if let Some(fixture) = &mut self.fixture {
+            fixture.status = status.as_u16();
+            fixture.body = Some(body.to_string());
+        }
+    }
+
+    pub fn record_status(&mut self, status: StatusCode) {
+        telemetry::record_http_status(status.as_u16());
+        self.observe(status, None);
+        if let Some(fixture) = &mut self.fixture {
+            fixture.status = status.as_u16();
+        }
+    }
+
+    fn observe(&self, status: StatusCode, body: Option<&str>) {
+        if let Some((provider, operation)) = &self.exchange {
+            interceptor::observe_response(&IncomingResponse {
+                provider,
+                operation,
+                status,
+                body,
+            });
+        }
+    }
+
+    pub fn record_event(&mut self, event: &MessageEvent) {
+        if let Some(fixture) = &mut self.fixture {
+            fixture.events.push(RecordedEvent {
+                event: event.event.clone(),
+                data: event.data.clone(),
+            });
+        }
+    }
+}
+
+impl EventObserver for Recorder {
+    fn on_status(&mut self, status: StatusCode) {
+        self.record_status(status);
+    }
+
+    fn on_event(&mut self, event: &MessageEvent) {
+        self.record_event(event);
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(fixture) = self.fixture.take() {
+            if let Some(dir) = record_dir() {
+                let path = fixture_path(&dir, &fixture);
+                match serde_json::to_string_pretty(&fixture)
+                    .map_err(|err| err.to_string())
+                    .and_then(|raw| std::fs::write(&path, raw).map_err(|err| err.to_string()))
+                {
+                    Ok(()) => trace!("Recorded fixture {}", path.display()),
+                    Err(err) => warn!("Failed to record fixture {}: {err}", path.display()),
+                }
+            }
+        }
+    }
+}
+
+pub fn is_recording() -> bool {
+    record_dir().is_some()
+}
+
+fn record_dir() -> Option<PathBuf> {
+    env_dir(RECORD_DIR_ENV_VAR)
+}
+
+fn replay_dir() -> Option<PathBuf> {
+    env_dir(REPLAY_DIR_ENV_VAR)
+}
+
+#[cfg(feature = "recording")]
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(not(feature = "recording"))]
+fn env_dir(_name: &str) -> Option<PathBuf> {
+    None
+}
+
+fn fixture_path(dir: &Path, fixture: &Fixture) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    dir.join(format!(
+        "{}-{}-{timestamp}.json",
+        fixture.provider, fixture.operation
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chat_stream::LlmChatStreamState;
+    use crate::event_source::EventSource;
+    use crate::golem::llm::llm::{
+        Error, ErrorCode, FinishReason, ResponseMetadata, StreamDelta, StreamEvent,
+    };
+    use crate::recording::{Fixture, RecordedEvent, Replay};
+    use serde::Deserialize;
+    use std::cell::{Ref, RefCell, RefMut};
+
+    fn fixture() -> Fixture {
+        Fixture {
+            provider: "test".to_string(),
+            operation: "stream".to_string(),
+            request: serde_json::json!({ "model": "test-model" }),
+            status: 200,
+            body: Some(r#"{"text":"hello"}"#.to_string()),
+            events: ["hello", "world", "!", "[DONE]", "ignored"]
+                .iter()
+                .map(|data| RecordedEvent {
+                    event: String::new(),
+                    data: data.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Decodes the recorded events, holding back the finish event of the "!" message like the
+    /// providers sending the usage with the last delta
+    struct TestStream {
+        failure: Option<Error>,
+        pending: RefCell<Option<StreamEvent>>,
+        finished: RefCell<bool>,
+    }
+
+    impl LlmChatStreamState for TestStream {
+        fn failure(&self) -> &Option<Error> {
+            &self.failure
+        }
+
+        fn is_finished(&self) -> bool {
+            *self.finished.borrow()
+        }
+
+        fn set_finished(&self) {
+            *self.finished.borrow_mut() = true;
+        }
+
+        fn stream(&self) -> Ref<Option<EventSource>> {
+            unimplemented!()
+        }
+
+        fn stream_mut(&self) -> RefMut<Option<EventSource>> {
+            unimplemented!()
+        }
+
+        fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
+            match raw {
+                "world" => Ok(None),
+                "ignored" => Err("should not be decoded".to_string()),
+                _ => {
+                    if raw == "!" {
+                        *self.pending.borrow_mut() = Some(finish());
+                    }
+                    Ok(Some(StreamEvent::Delta(StreamDelta {
+                        content: None,
+                        tool_calls: None,
+                        reasoning: None,
+                        logprobs: None,
+                    })))
+                }
+            }
+        }
+
+        fn take_pending_event(&self) -> Option<StreamEvent> {
+            self.pending.borrow_mut().take()
+        }
+    }
+
+    fn finish() -> StreamEvent {
+        StreamEvent::Finish(ResponseMetadata {
+            finish_reason: Some(FinishReason::Stop),
+            usage: None,
+            provider_id: None,
+            timestamp: None,
+            provider_metadata_json: None,
+            logprobs: None,
+            cost: None,
+        })
+    }
+
+    #[test]
+    fn fixture_roundtrip() {
+        let fixture = fixture();
+        let raw = serde_json::to_string(&fixture).unwrap();
+        let parsed: Fixture = serde_json::from_str(&raw).unwrap();
+        assert_eq!(parsed, fixture);
+    }
+
+    #[test]
+    fn decodes_recorded_response() {
+        #[derive(Deserialize)]
+        struct Response {
+            text: String,
+        }
+
+        let response: Response = fixture().response().unwrap();
+        assert_eq!(response.text, "hello");
+    }
+
+    #[test]
+    fn replays_stream_until_finish() {
+        let stream = TestStream {
+            failure: None,
+            pending: RefCell::new(None),
+            finished: RefCell::new(false),
+        };
+        let events = fixture().replay_stream(&stream);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[0], StreamEvent::Delta(_)));
+        assert!(matches!(events[1], StreamEvent::Delta(_)));
+        assert!(matches!(events[2], StreamEvent::Finish(_)));
+        assert!(stream.is_finished());
+    }
+
+    #[test]
+    fn replays_the_fixtures_of_each_operation_in_order() {
+        let mut second = fixture();
+        second.body = Some(r#"{"text":"again"}"#.to_string());
+        let mut other = fixture();
+        other.operation = "send".to_string();
+        let mut replay = Replay::new(vec![fixture(), other.clone(), second.clone()]);
+        let request = serde_json::json!({ "model": "test-model" });
+
+        assert_eq!(replay.next("test", "stream", &request), Ok(fixture()));
+        // A different request is still answered
+        assert_eq!(
+            replay.next("test", "stream", &serde_json::json!({})),
+            Ok(second)
+        );
+        let error = replay.next("test", "stream", &request).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InternalError);
+        assert_eq!(
+            error.message,
+            "No recorded stream exchange of test left to replay"
+        );
+        assert_eq!(replay.next("test", "send", &request), Ok(other));
+    }
+
+    #[test]
+    fn loads_the_fixtures_of_a_directory() {
+        let dir = std::env::temp_dir().join(format!("golem-llm-fixtures-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut second = fixture();
+        second.operation = "send".to_string();
+        for (name, fixture) in [("b.json", &second), ("a.json", &fixture())] {
+            std::fs::write(dir.join(name), serde_json::to_string(fixture).unwrap()).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a fixture").unwrap();
+
+        let fixtures = Fixture::load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(fixtures, vec![fixture(), second]);
+    }
+}
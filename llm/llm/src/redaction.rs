@@ -0,0 +1,257 @@
+use std::borrow::Cow;
+use std::fmt::{Debug, Display, Formatter};
+
+/// Setting this environment variable to `true` disables redaction in the logs, for local debugging
+pub const RAW_LOG_ENV_VAR: &str = "GOLEM_LLM_LOG_RAW";
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Prefixes of API keys issued by the supported providers
+const SECRET_PREFIXES: &[&str] = &["sk-", "xai-", "gsk_", "pk-"];
+
+/// Minimum length of the part following a secret prefix for it to be considered an API key
+const MIN_SECRET_LENGTH: usize = 16;
+
+/// Names of fields, headers and query parameters holding credentials or URL signatures
+const SENSITIVE_NAMES: &[&str] = &[
+    "api_key",
+    "api-key",
+    "apikey",
+    "x-api-key",
+    "access_token",
+    "refresh_token",
+    "authorization",
+    "password",
+    "secret",
+    "client_secret",
+    "signature",
+    "sig",
+    "x-amz-signature",
+    "x-amz-credential",
+    "x-amz-security-token",
+    "x-goog-signature",
+    "x-goog-credential",
+];
+
+/// Wraps a value to be logged, scrubbing API keys, authorization headers and URL signatures
+/// from its formatted output unless `GOLEM_LLM_LOG_RAW` is set.
+pub struct Redacted<T>(pub T);
+
+impl<T: Debug> Debug for Redacted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let raw = format!("{:?}", self.0);
+        f.write_str(&redact_unless_raw(&raw))
+    }
+}
+
+impl<T: Display> Display for Redacted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let raw = self.0.to_string();
+        f.write_str(&redact_unless_raw(&raw))
+    }
+}
+
+fn redact_unless_raw(raw: &str) -> Cow<'_, str> {
+    let raw_mode = std::env::var(RAW_LOG_ENV_VAR)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if raw_mode {
+        Cow::Borrowed(raw)
+    } else {
+        redact(raw)
+    }
+}
+
+/// Scrubs API keys, bearer tokens and the values of sensitive fields from a string
+pub fn redact(input: &str) -> Cow<'_, str> {
+    let lower = input.to_ascii_lowercase();
+    let bytes = input.as_bytes();
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for prefix in SECRET_PREFIXES {
+        for (start, _) in lower.match_indices(prefix) {
+            if start > 0 && is_token_byte(bytes[start - 1]) {
+                continue;
+            }
+            let value_start = start + prefix.len();
+            let end = scan(bytes, value_start, is_token_byte);
+            if end - value_start >= MIN_SECRET_LENGTH {
+                ranges.push((value_start, end));
+            }
+        }
+    }
+
+    for (start, _) in lower.match_indices("bearer ") {
+        let value_start = start + "bearer ".len();
+        let end = scan(bytes, value_start, |b| {
+            is_token_byte(b) || matches!(b, b'.' | b'~' | b'+' | b'/' | b'=')
+        });
+        if end > value_start {
+            ranges.push((value_start, end));
+        }
+    }
+
+    for name in SENSITIVE_NAMES {
+        for (start, _) in lower.match_indices(name) {
+            let end = start + name.len();
+            let bounded_before = start == 0 || !is_token_byte(bytes[start - 1]);
+            let bounded_after = end == bytes.len() || !is_token_byte(bytes[end]);
+            if bounded_before && bounded_after {
+                if let Some(range) = value_after_name(bytes, end) {
+                    ranges.push(range);
+                }
+            }
+        }
+    }
+
+    if ranges.is_empty() {
+        return Cow::Borrowed(input);
+    }
+
+    ranges.sort();
+    let mut result = String::with_capacity(input.len());
+    let mut position = 0;
+    for (start, end) in ranges {
+        if end <= position {
+            continue;
+        }
+        let start = start.max(position);
+        result.push_str(&input[position..start]);
+        result.push_str(REDACTED);
+        position = end;
+    }
+    result.push_str(&input[position..]);
+    Cow::Owned(result)
+}
+
+fn is_token_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'_'
+}
+
+fn scan(bytes: &[u8], start: usize, accept: impl Fn(u8) -> bool) -> usize {
+    let mut end = start;
+    while end < bytes.len() && accept(bytes[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// Finds the value following a sensitive name in formats like `name: "value"`, `"name": "value"`,
+/// `name=value` and `name: Some("value")`
+fn value_after_name(bytes: &[u8], name_end: usize) -> Option<(usize, usize)> {
+    let mut i = name_end;
+    if bytes[i..].starts_with(b"\\\"") {
+        i += 2;
+    } else if i < bytes.len() && (bytes[i] == b'"' || bytes[i] == b'\'') {
+        i += 1;
+    }
+    i = scan(bytes, i, |b| b == b' ');
+    if i >= bytes.len() || !(bytes[i] == b':' || bytes[i] == b'=') {
+        return None;
+    }
+    i = scan(bytes, i + 1, |b| b == b' ');
+    if bytes[i..].starts_with(b"Some(") {
+        i += "Some(".len();
+    }
+    if bytes[i..].starts_with(b"\\\"") {
+        // Value of an escaped JSON string embedded in a debug formatted string
+        let start = i + 2;
+        let end = find(bytes, start, b"\\\"")?;
+        return Some((start, end));
+    }
+    match bytes.get(i) {
+        Some(b'"') | Some(b'\'') => {
+            let quote = bytes[i];
+            let start = i + 1;
+            let mut end = start;
+            while end < bytes.len() && bytes[end] != quote {
+                if bytes[end] == b'\\' {
+                    end += 1;
+                }
+                end += 1;
+            }
+            Some((start, end.min(bytes.len())))
+        }
+        Some(_) => {
+            let end = scan(bytes, i, |b| {
+                !matches!(
+                    b,
+                    b'&' | b' ' | b',' | b'"' | b'\'' | b'}' | b']' | b')' | b'\n' | b'\\'
+                )
+            });
+            (end > i).then_some((i, end))
+        }
+        None => None,
+    }
+}
+
+fn find(bytes: &[u8], start: usize, needle: &[u8]) -> Option<usize> {
+    bytes[start..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|position| start + position)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::redaction::redact;
+
+    #[test]
+    fn redacts_api_keys() {
+        assert_eq!(
+            redact("key is sk-proj-abcdefghijklmnopqrstuvwxyz012345 here"),
+            "key is sk-[REDACTED] here"
+        );
+        assert_eq!(redact("xai-ABCDEFGHIJKLMNOPQRSTUVWXYZ"), "xai-[REDACTED]");
+        assert_eq!(
+            redact("task-runner and disk-usage"),
+            "task-runner and disk-usage"
+        );
+    }
+
+    #[test]
+    fn redacts_bearer_tokens() {
+        assert_eq!(
+            redact(r#"{"authorization": "Bearer abc.def.ghi"}"#),
+            r#"{"authorization": "[REDACTED]"}"#
+        );
+        assert_eq!(
+            redact("header: bearer abc123=="),
+            "header: bearer [REDACTED]"
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_fields() {
+        assert_eq!(
+            redact(r#"Request { api_key: "secret-value", model: "gpt" }"#),
+            r#"Request { api_key: "[REDACTED]", model: "gpt" }"#
+        );
+        assert_eq!(
+            redact(r#"Config { password: Some("hunter2") }"#),
+            r#"Config { password: Some("[REDACTED]") }"#
+        );
+        assert_eq!(
+            redact(r#""{\"x-api-key\": \"abc\"}""#),
+            r#""{\"x-api-key\": \"[REDACTED]\"}""#
+        );
+    }
+
+    #[test]
+    fn redacts_signed_url_parameters() {
+        assert_eq!(
+            redact("https://bucket.s3.amazonaws.com/cat.png?X-Amz-Credential=AKIA%2F123&X-Amz-Signature=abcdef&x=1"),
+            "https://bucket.s3.amazonaws.com/cat.png?X-Amz-Credential=[REDACTED]&X-Amz-Signature=[REDACTED]&x=1"
+        );
+        assert_eq!(
+            redact("https://example.blob.core.windows.net/a.png?sv=2020&sig=abc%3D"),
+            "https://example.blob.core.windows.net/a.png?sv=2020&sig=[REDACTED]"
+        );
+    }
+
+    #[test]
+    fn keeps_unrelated_text() {
+        let text = r#"Message { role: User, content: "What is the signature dish of Hungary?" }"#;
+        assert_eq!(redact(text), text);
+    }
+}
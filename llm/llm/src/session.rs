@@ -0,0 +1,436 @@
+use crate::durability::{DurableLLM, ExtendedGuest};
+use crate::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, ContentPart, Guest, Message, Role, StreamDelta, StreamEvent,
+    ToolCall, ToolResult,
+};
+use crate::golem::llm::session::{Guest as SessionGuest, GuestChatSession};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+impl<Impl: ExtendedGuest> SessionGuest for DurableLLM<Impl> {
+    type ChatSession = DurableChatSession<Impl>;
+}
+
+/// A `chat-session` sending its messages through the durable functions of `DurableLLM`, so the
+/// state of a recovered session is rebuilt by replaying its calls from the oplog
+pub struct DurableChatSession<Impl> {
+    state: Rc<RefCell<SessionState>>,
+    phantom: PhantomData<Impl>,
+}
+
+impl<Impl: ExtendedGuest> GuestChatSession for DurableChatSession<Impl> {
+    fn new(config: Config, messages: Vec<Message>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(SessionState::new(config, messages))),
+            phantom: PhantomData,
+        }
+    }
+
+    fn history(&self) -> Vec<Message> {
+        self.state.borrow().messages.clone()
+    }
+
+    fn add_message(&self, message: Message) {
+        self.state.borrow_mut().add_message(message);
+    }
+
+    fn send(&self, message: Message) -> ChatEvent {
+        self.state
+            .borrow_mut()
+            .send(message, <DurableLLM<Impl> as Guest>::send)
+    }
+
+    fn add_tool_result(&self, tool_call: ToolCall, tool_result: ToolResult) {
+        self.state
+            .borrow_mut()
+            .tool_results
+            .push((tool_call, tool_result));
+    }
+
+    fn continue_(&self) -> ChatEvent {
+        self.state
+            .borrow_mut()
+            .continue_(<DurableLLM<Impl> as Guest>::continue_)
+    }
+
+    fn stream(&self, message: Message) -> ChatStream {
+        let (messages, config) = self.state.borrow_mut().stream(message);
+        let recorder = StreamRecorder::new(&self.state);
+        let stream = <DurableLLM<Impl> as Guest>::stream(messages, config);
+        stream
+            .get::<<DurableLLM<Impl> as Guest>::ChatStream>()
+            .record_into(recorder);
+        stream
+    }
+}
+
+/// Records the response of a stream started by a session in its history, once the stream
+/// finishes. If the stream fails, gets cancelled or is dropped before finishing, the message it
+/// was started with is removed from the history instead, like in `send`.
+pub(crate) struct StreamRecorder {
+    state: Weak<RefCell<SessionState>>,
+    message_index: usize,
+    deltas: Vec<StreamDelta>,
+}
+
+impl StreamRecorder {
+    /// Creates a recorder for a stream started with the last message of the session
+    fn new(state: &Rc<RefCell<SessionState>>) -> Self {
+        Self {
+            state: Rc::downgrade(state),
+            message_index: state.borrow().messages.len() - 1,
+            deltas: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, events: &[StreamEvent]) {
+        // A stream returns no events once it is finished
+        if events.is_empty() {
+            self.cancel();
+        }
+        for event in events {
+            match event {
+                StreamEvent::Delta(delta) => self.deltas.push(delta.clone()),
+                StreamEvent::Finish(_) => {
+                    if let Some(state) = self.state.upgrade() {
+                        state.borrow_mut().add_streamed_response(&self.deltas);
+                    }
+                    self.state = Weak::new();
+                }
+                StreamEvent::Error(_) => self.cancel(),
+            }
+        }
+    }
+
+    /// Removes the message of a stream ending without a response, unless the response has
+    /// already been recorded
+    pub(crate) fn cancel(&mut self) {
+        if let Some(state) = self.state.upgrade() {
+            state.borrow_mut().remove_message(self.message_index);
+        }
+        self.state = Weak::new();
+    }
+}
+
+impl Drop for StreamRecorder {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// The history of a session, and the requested tool calls of the current turn with their results
+struct SessionState {
+    config: Config,
+    messages: Vec<Message>,
+    tool_results: Vec<(ToolCall, ToolResult)>,
+}
+
+impl SessionState {
+    fn new(config: Config, messages: Vec<Message>) -> Self {
+        Self {
+            config,
+            messages,
+            tool_results: Vec::new(),
+        }
+    }
+
+    /// Adds a message, ending the current turn. Tool calls can only be passed to `continue`
+    /// together with their results after the last message, so the calls of earlier turns are not
+    /// sent again, only the final responses to them.
+    fn add_message(&mut self, message: Message) {
+        self.tool_results.clear();
+        self.messages.push(message);
+    }
+
+    fn send(
+        &mut self,
+        message: Message,
+        send: impl FnOnce(Vec<Message>, Config) -> ChatEvent,
+    ) -> ChatEvent {
+        self.add_message(message);
+        let event = send(self.messages.clone(), self.config.clone());
+        if let ChatEvent::Error(_) = event {
+            // The message can be sent again without duplicating it
+            self.messages.pop();
+        }
+        self.add_response(&event);
+        event
+    }
+
+    fn continue_(
+        &mut self,
+        continue_: impl FnOnce(Vec<Message>, Vec<(ToolCall, ToolResult)>, Config) -> ChatEvent,
+    ) -> ChatEvent {
+        let event = continue_(
+            self.messages.clone(),
+            self.tool_results.clone(),
+            self.config.clone(),
+        );
+        self.add_response(&event);
+        event
+    }
+
+    fn stream(&mut self, message: Message) -> (Vec<Message>, Config) {
+        self.add_message(message);
+        (self.messages.clone(), self.config.clone())
+    }
+
+    /// Adds a final response to the history. Responses requesting tool calls are only added once
+    /// the LLM has responded to the results of the calls.
+    fn add_response(&mut self, event: &ChatEvent) {
+        if let ChatEvent::Message(response) = event {
+            if response.tool_calls.is_empty() {
+                self.add_message(Message {
+                    role: Role::Assistant,
+                    name: None,
+                    content: response.content.clone(),
+                    cache_control: None,
+                });
+            }
+        }
+    }
+
+    /// Adds a streamed response to the history, with its text deltas joined. Like in
+    /// `add_response`, responses requesting tool calls are left to `continue`.
+    fn add_streamed_response(&mut self, deltas: &[StreamDelta]) {
+        let mut tool_calls = deltas
+            .iter()
+            .flat_map(|delta| delta.tool_calls.iter().flatten());
+        if tool_calls.next().is_some() {
+            return;
+        }
+        let mut content = Vec::new();
+        let parts = deltas
+            .iter()
+            .flat_map(|delta| delta.content.iter().flatten());
+        for part in parts {
+            match (content.last_mut(), part) {
+                (Some(ContentPart::Text(text)), ContentPart::Text(delta)) => text.push_str(delta),
+                _ => content.push(part.clone()),
+            }
+        }
+        self.add_message(Message {
+            role: Role::Assistant,
+            name: None,
+            content,
+            cache_control: None,
+        });
+    }
+
+    /// Removes a message that could not be sent, so it can be sent again without duplicating it
+    fn remove_message(&mut self, index: usize) {
+        if index < self.messages.len() {
+            self.messages.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{
+        ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, Message,
+        ResponseMetadata, Role, StreamDelta, StreamEvent, ToolCall, ToolResult, ToolSuccess,
+    };
+    use crate::session::{SessionState, StreamRecorder};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn config() -> Config {
+        Config {
+            model: "model".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        }
+    }
+
+    fn text(role: Role, text: &str) -> Message {
+        Message {
+            role,
+            name: None,
+            content: vec![ContentPart::Text(text.to_string())],
+            cache_control: None,
+        }
+    }
+
+    fn response(text: &str) -> ChatEvent {
+        ChatEvent::Message(CompleteResponse {
+            id: "response".to_string(),
+            content: vec![ContentPart::Text(text.to_string())],
+            tool_calls: vec![],
+            reasoning: None,
+            metadata: metadata(),
+        })
+    }
+
+    fn metadata() -> ResponseMetadata {
+        ResponseMetadata {
+            finish_reason: None,
+            usage: None,
+            provider_id: None,
+            timestamp: None,
+            provider_metadata_json: None,
+            logprobs: None,
+            cost: None,
+        }
+    }
+
+    fn delta(text: &str) -> StreamEvent {
+        StreamEvent::Delta(StreamDelta {
+            content: Some(vec![ContentPart::Text(text.to_string())]),
+            tool_calls: None,
+            reasoning: None,
+            logprobs: None,
+        })
+    }
+
+    fn tool_result() -> ToolResult {
+        ToolResult::Success(ToolSuccess {
+            id: "call-1".to_string(),
+            name: "get_weather".to_string(),
+            result_json: r#"{"sunny": true}"#.to_string(),
+            execution_time_ms: None,
+        })
+    }
+
+    fn tool_call() -> ToolCall {
+        ToolCall {
+            id: "call-1".to_string(),
+            name: "get_weather".to_string(),
+            arguments_json: r#"{"city": "Paris"}"#.to_string(),
+        }
+    }
+
+    #[test]
+    fn accumulates_the_history() {
+        let mut session = SessionState::new(config(), vec![text(Role::System, "Be brief")]);
+
+        session.send(text(Role::User, "Hi"), |messages, _| {
+            assert_eq!(messages.len(), 2);
+            response("Hello")
+        });
+        session.send(text(Role::User, "How are you?"), |messages, _| {
+            assert_eq!(messages.len(), 4);
+            response("Fine")
+        });
+
+        assert_eq!(
+            session.messages,
+            vec![
+                text(Role::System, "Be brief"),
+                text(Role::User, "Hi"),
+                text(Role::Assistant, "Hello"),
+                text(Role::User, "How are you?"),
+                text(Role::Assistant, "Fine"),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_streamed_responses() {
+        let session = Rc::new(RefCell::new(SessionState::new(config(), vec![])));
+
+        session.borrow_mut().stream(text(Role::User, "Hi"));
+        let mut recorder = StreamRecorder::new(&session);
+        recorder.record(&[delta("Hel")]);
+        assert_eq!(session.borrow().messages.len(), 1);
+        recorder.record(&[delta("lo"), StreamEvent::Finish(metadata())]);
+
+        session
+            .borrow_mut()
+            .stream(text(Role::User, "How are you?"));
+        let mut recorder = StreamRecorder::new(&session);
+        recorder.record(&[
+            delta("Fi"),
+            StreamEvent::Error(Error {
+                code: ErrorCode::InternalError,
+                message: "Connection lost".to_string(),
+                provider_error_json: None,
+            }),
+        ]);
+
+        assert_eq!(
+            session.borrow().messages,
+            vec![text(Role::User, "Hi"), text(Role::Assistant, "Hello")]
+        );
+    }
+
+    #[test]
+    fn failed_messages_are_not_kept() {
+        let mut session = SessionState::new(config(), vec![]);
+        session.send(text(Role::User, "Hi"), |_, _| {
+            ChatEvent::Error(Error {
+                code: ErrorCode::RateLimitExceeded,
+                message: "Slow down".to_string(),
+                provider_error_json: None,
+            })
+        });
+        assert!(session.messages.is_empty());
+    }
+
+    #[test]
+    fn sends_the_tool_results_of_the_turn_with_continue() {
+        let mut session = SessionState::new(config(), vec![]);
+        session.send(text(Role::User, "Weather in Paris?"), |_, _| {
+            ChatEvent::ToolRequest(vec![tool_call()])
+        });
+        assert_eq!(session.messages.len(), 1);
+
+        session.tool_results.push((tool_call(), tool_result()));
+        session.continue_(|messages, tool_results, _| {
+            assert_eq!(messages, vec![text(Role::User, "Weather in Paris?")]);
+            assert_eq!(tool_results, vec![(tool_call(), tool_result())]);
+            ChatEvent::ToolRequest(vec![tool_call()])
+        });
+        session.tool_results.push((tool_call(), tool_result()));
+        session.continue_(|_, tool_results, _| {
+            // The results of all the calls of the turn are sent
+            assert_eq!(tool_results.len(), 2);
+            response("It is sunny")
+        });
+
+        assert!(session.tool_results.is_empty());
+        assert_eq!(
+            session.messages,
+            vec![
+                text(Role::User, "Weather in Paris?"),
+                text(Role::Assistant, "It is sunny")
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_the_message_of_a_stream_without_response() {
+        let session = Rc::new(RefCell::new(SessionState::new(config(), vec![])));
+
+        session.borrow_mut().stream(text(Role::User, "Hi"));
+        let mut recorder = StreamRecorder::new(&session);
+        recorder.record(&[delta("Hel")]);
+        recorder.cancel();
+        assert!(session.borrow().messages.is_empty());
+
+        session.borrow_mut().stream(text(Role::User, "Hi"));
+        let mut recorder = StreamRecorder::new(&session);
+        recorder.record(&[delta("Hel")]);
+        drop(recorder);
+        assert!(session.borrow().messages.is_empty());
+
+        session.borrow_mut().stream(text(Role::User, "Hi"));
+        let mut recorder = StreamRecorder::new(&session);
+        recorder.record(&[delta("Hello"), StreamEvent::Finish(metadata())]);
+        recorder.record(&[]);
+        drop(recorder);
+        assert_eq!(session.borrow().messages.len(), 2);
+    }
+}
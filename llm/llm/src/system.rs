@@ -0,0 +1,83 @@
+use crate::golem::llm::llm::{Message, Role};
+
+/// The messages of a conversation with the system messages split from the others, for providers
+/// taking the system prompt apart from the conversation, like the `system` field of Anthropic
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemPrompt {
+    /// The system messages, in the order they were given
+    pub system: Vec<Message>,
+    /// The other messages of the conversation, in the order they were given
+    pub conversation: Vec<Message>,
+}
+
+impl SystemPrompt {
+    /// Splits the system messages from the conversation. System messages in the middle of the
+    /// conversation are moved to the system prompt as well, after the ones preceding them.
+    pub fn split(messages: Vec<Message>) -> Self {
+        let (system, conversation) = messages
+            .into_iter()
+            .partition(|message| message.role == Role::System);
+        Self {
+            system,
+            conversation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{ContentPart, ImageReference, ImageUrl, Message, Role};
+    use crate::system::SystemPrompt;
+
+    fn text(role: Role, text: &str) -> Message {
+        Message {
+            role,
+            name: None,
+            content: vec![ContentPart::Text(text.to_string())],
+            cache_control: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_order_of_both_parts() {
+        let prompt = SystemPrompt::split(vec![
+            text(Role::System, "Be brief"),
+            text(Role::User, "Hi"),
+            text(Role::Assistant, "Hello"),
+            text(Role::System, "Answer in French"),
+            text(Role::User, "How are you?"),
+        ]);
+        assert_eq!(
+            prompt.system,
+            vec![
+                text(Role::System, "Be brief"),
+                text(Role::System, "Answer in French")
+            ]
+        );
+        assert_eq!(
+            prompt.conversation,
+            vec![
+                text(Role::User, "Hi"),
+                text(Role::Assistant, "Hello"),
+                text(Role::User, "How are you?")
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_content_of_system_messages() {
+        let mut system = text(Role::System, "Describe images");
+        system
+            .content
+            .push(ContentPart::Image(ImageReference::Url(ImageUrl {
+                url: "https://example.com/image.png".to_string(),
+                detail: None,
+            })));
+        let prompt = SystemPrompt::split(vec![system.clone()]);
+        assert!(prompt.conversation.is_empty());
+        assert_eq!(prompt.system, vec![system]);
+
+        let prompt = SystemPrompt::split(vec![text(Role::User, "Hi")]);
+        assert!(prompt.system.is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::durability::{DurableLLM, ExtendedGuest};
+use crate::golem::llm::llm::{ChatEvent, ErrorCode, ResponseMetadata, Usage};
+use crate::golem::llm::metrics::{CallMetrics, CostReport, Guest as MetricsGuest, Period};
+use crate::metering;
+use crate::rate_limit;
+use log::debug;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// The number of most recent calls kept in memory for `get-metrics`
+const MAX_RECORDED_CALLS: usize = 1000;
+
+#[derive(Default)]
+struct TelemetryState {
+    calls: VecDeque<CallMetrics>,
+    provider: String,
+    http_status: Option<u16>,
+    current_model: Option<String>,
+}
+
+thread_local! {
+    static TELEMETRY: RefCell<TelemetryState> = RefCell::new(TelemetryState::default());
+}
+
+/// The provider and model of a call, captured when a stream gets opened so its final usage can be
+/// attributed once the stream finishes.
+#[derive(Debug, Clone)]
+pub struct CallInfo {
+    provider: String,
+    model: String,
+    started_at: Instant,
+}
+
+impl CallInfo {
+    pub fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+/// Measures a single call to the LLM provider.
+///
+/// The provider name and HTTP status are reported by the provider clients while the span is
+/// active, through `set_provider` and `record_http_status`.
+pub struct Span {
+    operation: &'static str,
+    model: String,
+    started_at: Instant,
+}
+
+impl Span {
+    pub fn start(operation: &'static str, model: &str) -> Self {
+        TELEMETRY.with_borrow_mut(|state| {
+            state.http_status = None;
+            state.current_model = Some(model.to_string());
+        });
+        Self {
+            operation,
+            model: model.to_string(),
+            started_at: Instant::now(),
+        }
+    }
+
+    pub fn finish_with_event(self, event: &ChatEvent) {
+        match event {
+            ChatEvent::Message(response) => self.finish(response.metadata.usage.as_ref(), None),
+            ChatEvent::ToolRequest(_) => self.finish(None, None),
+            ChatEvent::Error(error) => self.finish(None, Some(error.code)),
+        }
+    }
+
+    pub fn finish_stream(self) {
+        self.finish(None, None)
+    }
+
+    fn finish(self, usage: Option<&Usage>, error: Option<ErrorCode>) {
+        let latency_ms = self.started_at.elapsed().as_millis() as u64;
+        TELEMETRY.with_borrow_mut(|state| {
+            state.current_model = None;
+            let metrics = CallMetrics {
+                provider: state.provider.clone(),
+                model: self.model,
+                operation: self.operation.to_string(),
+                latency_ms,
+                http_status: state.http_status.take(),
+                input_tokens: usage.and_then(|usage| usage.input_tokens),
+                output_tokens: usage.and_then(|usage| usage.output_tokens),
+                error,
+            };
+            state.push(metrics);
+        });
+    }
+}
+
+impl TelemetryState {
+    fn push(&mut self, metrics: CallMetrics) {
+        debug!(
+            "llm_call provider={} model={} operation={} latency_ms={} http_status={} input_tokens={} output_tokens={} error={}",
+            metrics.provider,
+            metrics.model,
+            metrics.operation,
+            metrics.latency_ms,
+            format_option(&metrics.http_status),
+            format_option(&metrics.input_tokens),
+            format_option(&metrics.output_tokens),
+            metrics
+                .error
+                .map(|code| format!("{code:?}"))
+                .unwrap_or_else(|| "-".to_string()),
+        );
+        if metrics.input_tokens.is_some() || metrics.output_tokens.is_some() {
+            metering::record_usage(
+                &metrics.provider,
+                &metrics.model,
+                metrics.input_tokens.unwrap_or_default() as u64,
+                metrics.output_tokens.unwrap_or_default() as u64,
+            );
+            rate_limit::record_usage(
+                &metrics.model,
+                metrics.input_tokens.unwrap_or_default() as u64,
+                metrics.output_tokens.unwrap_or_default() as u64,
+            );
+        }
+        #[cfg(feature = "otel")]
+        crate::otel::export(&metrics);
+        if self.calls.len() == MAX_RECORDED_CALLS {
+            self.calls.pop_front();
+        }
+        self.calls.push_back(metrics);
+    }
+}
+
+fn format_option<T: ToString>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Sets the name of the provider making the calls
+pub fn set_provider(provider: &str) {
+    TELEMETRY.with_borrow_mut(|state| {
+        if state.provider != provider {
+            state.provider = provider.to_string();
+        }
+    });
+}
+
+/// Records the HTTP status of the response belonging to the currently active span
+pub fn record_http_status(status: u16) {
+    TELEMETRY.with_borrow_mut(|state| state.http_status = Some(status));
+}
+
+/// Gets the provider and model of the currently active span, if any
+pub fn current_call() -> Option<CallInfo> {
+    TELEMETRY.with_borrow(|state| {
+        state.current_model.as_ref().map(|model| CallInfo {
+            provider: state.provider.clone(),
+            model: model.clone(),
+            started_at: Instant::now(),
+        })
+    })
+}
+
+/// Records the final usage reported by a finished stream
+pub fn record_stream_finish(call: &CallInfo, metadata: &ResponseMetadata) {
+    let usage = metadata.usage.as_ref();
+    TELEMETRY.with_borrow_mut(|state| {
+        state.push(CallMetrics {
+            provider: call.provider.clone(),
+            model: call.model.clone(),
+            operation: "stream-finish".to_string(),
+            latency_ms: call.started_at.elapsed().as_millis() as u64,
+            http_status: None,
+            input_tokens: usage.and_then(|usage| usage.input_tokens),
+            output_tokens: usage.and_then(|usage| usage.output_tokens),
+            error: None,
+        })
+    });
+}
+
+impl<Impl: ExtendedGuest> MetricsGuest for DurableLLM<Impl> {
+    fn get_metrics() -> Vec<CallMetrics> {
+        TELEMETRY.with_borrow(|state| state.calls.iter().cloned().collect())
+    }
+
+    fn get_cost_report(period: Period) -> CostReport {
+        metering::cost_report(period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{
+        ChatEvent, CompleteResponse, Error, ErrorCode, ResponseMetadata, Usage,
+    };
+    use crate::telemetry::{
+        current_call, record_http_status, record_stream_finish, set_provider, Span, TELEMETRY,
+    };
+
+    fn calls() -> Vec<crate::golem::llm::metrics::CallMetrics> {
+        TELEMETRY.with_borrow(|state| state.calls.iter().cloned().collect())
+    }
+
+    fn usage() -> Usage {
+        Usage {
+            input_tokens: Some(10),
+            output_tokens: Some(20),
+            total_tokens: Some(30),
+            cached_tokens: None,
+        }
+    }
+
+    #[test]
+    fn records_successful_call() {
+        set_provider("test");
+        let span = Span::start("send", "model-1");
+        record_http_status(200);
+        span.finish_with_event(&ChatEvent::Message(CompleteResponse {
+            id: "1".to_string(),
+            content: vec![],
+            tool_calls: vec![],
+            reasoning: None,
+            metadata: ResponseMetadata {
+                finish_reason: None,
+                usage: Some(usage()),
+                provider_id: None,
+                timestamp: None,
+                provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
+            },
+        }));
+
+        let call = calls().pop().unwrap();
+        assert_eq!(call.provider, "test");
+        assert_eq!(call.model, "model-1");
+        assert_eq!(call.operation, "send");
+        assert_eq!(call.http_status, Some(200));
+        assert_eq!(call.input_tokens, Some(10));
+        assert_eq!(call.output_tokens, Some(20));
+        assert_eq!(call.error, None);
+    }
+
+    #[test]
+    fn records_failed_call() {
+        set_provider("test");
+        let span = Span::start("continue", "model-2");
+        record_http_status(429);
+        span.finish_with_event(&ChatEvent::Error(Error {
+            code: ErrorCode::RateLimitExceeded,
+            message: "Too many requests".to_string(),
+            provider_error_json: None,
+        }));
+
+        let call = calls().pop().unwrap();
+        assert_eq!(call.http_status, Some(429));
+        assert_eq!(call.error, Some(ErrorCode::RateLimitExceeded));
+    }
+
+    #[test]
+    fn attributes_stream_usage_to_the_opening_call() {
+        set_provider("test");
+        let span = Span::start("stream", "model-3");
+        let call = current_call().unwrap();
+        span.finish_stream();
+        assert!(current_call().is_none());
+
+        record_stream_finish(
+            &call,
+            &ResponseMetadata {
+                finish_reason: None,
+                usage: Some(usage()),
+                provider_id: None,
+                timestamp: None,
+                provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
+            },
+        );
+
+        let calls = calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].operation, "stream");
+        assert_eq!(calls[1].operation, "stream-finish");
+        assert_eq!(calls[1].model, "model-3");
+        assert_eq!(calls[1].output_tokens, Some(20));
+    }
+}
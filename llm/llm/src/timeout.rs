@@ -0,0 +1,105 @@
+//! Timeouts of the HTTP requests sent to the providers.
+//!
+//! The `timeout-ms` and `connect-timeout-ms` of a call's `config` are made available to
+//! `interceptor::send` through a scope opened by `DurableLLM` for the duration of the call, so the
+//! provider clients do not have to pass them along. A request running out of time fails with the
+//! `timeout` error code.
+
+use crate::golem::llm::llm::Config;
+use std::cell::Cell;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Timeouts {
+    /// The maximum duration of a whole request
+    pub request: Option<Duration>,
+    /// The maximum duration of establishing the connection
+    pub connect: Option<Duration>,
+}
+
+impl Timeouts {
+    pub fn from_config(config: &Config) -> Self {
+        let millis = |value: Option<u32>| value.map(|ms| Duration::from_millis(ms as u64));
+        Self {
+            request: millis(config.timeout_ms),
+            connect: millis(config.connect_timeout_ms),
+        }
+    }
+}
+
+thread_local! {
+    static TIMEOUTS: Cell<Timeouts> = Cell::new(Timeouts::default());
+}
+
+/// Restores the previous timeouts when dropped
+pub struct TimeoutScope {
+    previous: Timeouts,
+}
+
+impl Drop for TimeoutScope {
+    fn drop(&mut self) {
+        TIMEOUTS.set(self.previous);
+    }
+}
+
+/// Applies the timeouts of `config` to the requests sent until the returned scope gets dropped
+pub fn scope(config: &Config) -> TimeoutScope {
+    TimeoutScope {
+        previous: TIMEOUTS.replace(Timeouts::from_config(config)),
+    }
+}
+
+/// Gets the timeouts of the active call
+pub fn current() -> Timeouts {
+    TIMEOUTS.get()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::Config;
+    use crate::timeout::{current, scope, Timeouts};
+    use std::time::Duration;
+
+    fn config() -> Config {
+        Config {
+            model: "model".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        }
+    }
+
+    #[test]
+    fn applies_the_timeouts_within_the_scope() {
+        assert_eq!(current(), Timeouts::default());
+        {
+            let mut config = config();
+            config.timeout_ms = Some(30000);
+            let _outer = scope(&config);
+            {
+                config.connect_timeout_ms = Some(2000);
+                let _inner = scope(&config);
+                assert_eq!(
+                    current(),
+                    Timeouts {
+                        request: Some(Duration::from_secs(30)),
+                        connect: Some(Duration::from_secs(2)),
+                    }
+                );
+            }
+            assert_eq!(current().request, Some(Duration::from_secs(30)));
+            assert_eq!(current().connect, None);
+        }
+        assert_eq!(current(), Timeouts::default());
+    }
+}
@@ -0,0 +1,419 @@
+use crate::config::provider_option;
+use crate::durability::{DurableLLM, ExtendedGuest};
+use crate::golem::llm::llm::{
+    ChatEvent, CitedText, CompleteResponse, Config, ContentPart, Error, ErrorCode, Guest, Message,
+    Role, ToolCall,
+};
+use crate::golem::llm::validation::Guest as ValidationGuest;
+use crate::json_schema::JsonSchema;
+use log::{debug, warn};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Provider option setting the number of attempts of `send-validated`, including the first one
+pub const MAX_ATTEMPTS_OPTION: &str = "max_validation_attempts";
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+impl<Impl: ExtendedGuest> ValidationGuest for DurableLLM<Impl> {
+    fn send_validated(messages: Vec<Message>, config: Config, schema: String) -> ChatEvent {
+        // Each attempt goes through the durable `send`, so a recovered worker replays the
+        // completed attempts from the oplog
+        send_validated(messages, config, &schema, |messages, config| {
+            <Self as Guest>::send(messages, config)
+        })
+    }
+}
+
+/// Sends the messages with `send` until the output matches the schema, or the attempts run out
+pub fn send_validated(
+    messages: Vec<Message>,
+    mut config: Config,
+    schema: &str,
+    send: impl Fn(Vec<Message>, Config) -> ChatEvent,
+) -> ChatEvent {
+    let validator = match JsonSchema::parse(schema) {
+        Ok(validator) => validator,
+        Err(message) => return ChatEvent::Error(invalid_request(message)),
+    };
+    let max_attempts = match provider_option(&config, MAX_ATTEMPTS_OPTION) {
+        Some(value) => match value.parse::<u32>() {
+            Ok(max_attempts) if max_attempts > 0 => max_attempts,
+            _ => {
+                return ChatEvent::Error(invalid_request(format!(
+                    "Invalid {MAX_ATTEMPTS_OPTION} provider option: {value}"
+                )))
+            }
+        },
+        None => DEFAULT_MAX_ATTEMPTS,
+    };
+    config
+        .provider_options
+        .retain(|kv| kv.key != MAX_ATTEMPTS_OPTION);
+    let tool_validators = tool_validators(&config);
+
+    let mut conversation = Vec::with_capacity(messages.len() + 1);
+    conversation.push(text_message(
+        Role::System,
+        format!(
+            "Respond with a single JSON document matching the following JSON schema, without \
+             any other text:\n{schema}"
+        ),
+    ));
+    conversation.extend(messages);
+
+    let mut attempt = 1;
+    loop {
+        let event = send(conversation.clone(), config.clone());
+        let (output, violations) = match event {
+            ChatEvent::Message(response) if response.tool_calls.is_empty() => {
+                match validate_response(&validator, response) {
+                    Ok(response) => return ChatEvent::Message(response),
+                    Err(rejected) => rejected,
+                }
+            }
+            ChatEvent::Message(response) => {
+                let violations = validate_tool_calls(&tool_validators, &response.tool_calls);
+                if violations.is_empty() {
+                    return ChatEvent::Message(response);
+                }
+                (describe_tool_calls(&response.tool_calls), violations)
+            }
+            ChatEvent::ToolRequest(calls) => {
+                let violations = validate_tool_calls(&tool_validators, &calls);
+                if violations.is_empty() {
+                    return ChatEvent::ToolRequest(calls);
+                }
+                (describe_tool_calls(&calls), violations)
+            }
+            ChatEvent::Error(error) => return ChatEvent::Error(error),
+        };
+
+        if attempt >= max_attempts {
+            return ChatEvent::Error(Error {
+                code: ErrorCode::InternalError,
+                message: format!(
+                    "The response did not match the schema after {attempt} attempts: {}",
+                    violations.join("; ")
+                ),
+                provider_error_json: Some(
+                    json!({ "attempts": attempt, "violations": violations, "output": output })
+                        .to_string(),
+                ),
+            });
+        }
+
+        debug!(
+            "Attempt {attempt} of send-validated failed with {} violations, asking for a repair",
+            violations.len()
+        );
+        conversation.push(text_message(Role::Assistant, output));
+        conversation.push(text_message(Role::User, repair_prompt(schema, &violations)));
+        attempt += 1;
+    }
+}
+
+/// Returns the response with its text content replaced by the validated JSON document, or the
+/// rejected output with its violations
+fn validate_response(
+    validator: &JsonSchema,
+    mut response: CompleteResponse,
+) -> Result<CompleteResponse, (String, Vec<String>)> {
+    let text = response
+        .content
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text(text) | ContentPart::CitedText(CitedText { text, .. }) => {
+                Some(text.as_str())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("");
+
+    match extract_json(&text) {
+        Ok(value) => {
+            let violations = validator.validate(&value);
+            if violations.is_empty() {
+                response.content = vec![ContentPart::Text(value.to_string())];
+                Ok(response)
+            } else {
+                Err((text, violations))
+            }
+        }
+        Err(violation) => Err((text, vec![violation])),
+    }
+}
+
+fn tool_validators(config: &Config) -> HashMap<String, JsonSchema> {
+    config
+        .tools
+        .iter()
+        .filter_map(|tool| match JsonSchema::parse(&tool.parameters_schema) {
+            Ok(validator) => Some((tool.name.clone(), validator)),
+            Err(err) => {
+                warn!("Not validating the arguments of tool {}: {err}", tool.name);
+                None
+            }
+        })
+        .collect()
+}
+
+fn validate_tool_calls(
+    validators: &HashMap<String, JsonSchema>,
+    calls: &[ToolCall],
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    for call in calls {
+        let Some(validator) = validators.get(&call.name) else {
+            continue;
+        };
+        match serde_json::from_str::<Value>(&call.arguments_json) {
+            Ok(arguments) => violations.extend(
+                validator
+                    .validate(&arguments)
+                    .into_iter()
+                    .map(|violation| format!("arguments of {} {violation}", call.name)),
+            ),
+            Err(err) => violations.push(format!(
+                "arguments of {} are not valid JSON: {err}",
+                call.name
+            )),
+        }
+    }
+    violations
+}
+
+fn describe_tool_calls(calls: &[ToolCall]) -> String {
+    calls
+        .iter()
+        .map(|call| format!("Called tool {} with {}", call.name, call.arguments_json))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parses the JSON document of a response, allowing a Markdown code fence or surrounding text
+fn extract_json(text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    let unfenced = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .unwrap_or(trimmed)
+        .trim();
+
+    serde_json::from_str(unfenced).or_else(|err| {
+        let start = unfenced.find(['{', '[']);
+        let end = unfenced.rfind(['}', ']']);
+        let embedded = match (start, end) {
+            (Some(start), Some(end)) if start < end => {
+                serde_json::from_str(&unfenced[start..=end]).ok()
+            }
+            _ => None,
+        };
+        embedded.ok_or_else(|| format!("the response is not a JSON document: {err}"))
+    })
+}
+
+fn repair_prompt(schema: &str, violations: &[String]) -> String {
+    format!(
+        "Your previous response did not match the required JSON schema:\n- {}\n\
+         Respond again, with a single JSON document matching this schema and no other text, or \
+         with tool calls whose arguments match the parameters of the tools:\n{schema}",
+        violations.join("\n- ")
+    )
+}
+
+fn text_message(role: Role, text: String) -> Message {
+    Message {
+        role,
+        name: None,
+        content: vec![ContentPart::Text(text)],
+        cache_control: None,
+    }
+}
+
+fn invalid_request(message: String) -> Error {
+    Error {
+        code: ErrorCode::InvalidRequest,
+        message,
+        provider_error_json: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{
+        ChatEvent, CompleteResponse, Config, ContentPart, ErrorCode, Kv, Message, ResponseMetadata,
+        Role, ToolCall, ToolDefinition,
+    };
+    use crate::validation::{extract_json, send_validated, MAX_ATTEMPTS_OPTION};
+    use std::cell::RefCell;
+
+    const SCHEMA: &str = r#"{
+        "type": "object",
+        "properties": {"number": {"type": "integer"}, "german": {"type": "string"}},
+        "required": ["number", "german"]
+    }"#;
+
+    fn config(tools: Vec<ToolDefinition>) -> Config {
+        Config {
+            model: "test".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools,
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![Kv {
+                key: MAX_ATTEMPTS_OPTION.to_string(),
+                value: "2".to_string(),
+            }],
+        }
+    }
+
+    fn response(text: &str) -> ChatEvent {
+        ChatEvent::Message(CompleteResponse {
+            id: "response".to_string(),
+            content: vec![ContentPart::Text(text.to_string())],
+            tool_calls: vec![],
+            reasoning: None,
+            metadata: ResponseMetadata {
+                finish_reason: None,
+                usage: None,
+                provider_id: None,
+                timestamp: None,
+                provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
+            },
+        })
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text(
+                "Generate a random number and translate it to German".to_string(),
+            )],
+            cache_control: None,
+        }]
+    }
+
+    /// Returns the scripted events in order, recording the messages of each call
+    fn scripted(
+        events: Vec<ChatEvent>,
+        calls: &RefCell<Vec<Vec<Message>>>,
+    ) -> impl Fn(Vec<Message>, Config) -> ChatEvent + '_ {
+        let events = RefCell::new(events);
+        move |messages, config| {
+            assert!(config.provider_options.is_empty());
+            calls.borrow_mut().push(messages);
+            events.borrow_mut().remove(0)
+        }
+    }
+
+    #[test]
+    fn repairs_invalid_output() {
+        let calls = RefCell::new(vec![]);
+        let event = send_validated(
+            messages(),
+            config(vec![]),
+            SCHEMA,
+            scripted(
+                vec![
+                    response(r#"{"number": "six"}"#),
+                    response("```json\n{\"number\": 6, \"german\": \"sechs\"}\n```"),
+                ],
+                &calls,
+            ),
+        );
+
+        match event {
+            ChatEvent::Message(response) => match &response.content[..] {
+                [ContentPart::Text(text)] => assert_eq!(
+                    serde_json::from_str::<serde_json::Value>(text).unwrap(),
+                    serde_json::json!({"number": 6, "german": "sechs"})
+                ),
+                other => panic!("Unexpected content: {other:?}"),
+            },
+            other => panic!("Unexpected event: {other:?}"),
+        }
+        let calls = calls.borrow();
+        assert_eq!(calls[0].len(), 2);
+        assert_eq!(calls[1].len(), 4);
+        assert_eq!(calls[1][2].role, Role::Assistant);
+        match &calls[1][3].content[0] {
+            ContentPart::Text(prompt) => {
+                assert!(prompt.contains("$.number: expected integer, found string"));
+                assert!(prompt.contains("$: missing required property german"));
+            }
+            other => panic!("Unexpected content: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fails_after_max_attempts() {
+        let calls = RefCell::new(vec![]);
+        let event = send_validated(
+            messages(),
+            config(vec![]),
+            SCHEMA,
+            scripted(vec![response("sechs"), response("6")], &calls),
+        );
+
+        match event {
+            ChatEvent::Error(error) => {
+                assert_eq!(error.code, ErrorCode::InternalError);
+                assert!(error.message.contains("after 2 attempts"));
+            }
+            other => panic!("Unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn validates_tool_call_arguments() {
+        let calls = RefCell::new(vec![]);
+        let tool_call = |arguments: &str| ToolCall {
+            id: "call-1".to_string(),
+            name: "random".to_string(),
+            arguments_json: arguments.to_string(),
+        };
+        let event = send_validated(
+            messages(),
+            config(vec![ToolDefinition {
+                name: "random".to_string(),
+                description: None,
+                parameters_schema: r#"{"type": "object", "properties": {"maximum": {"type": "integer"}}, "required": ["maximum"]}"#.to_string(),
+                built_in: None,
+            }]),
+            SCHEMA,
+            scripted(
+                vec![
+                    ChatEvent::ToolRequest(vec![tool_call(r#"{"max": 10}"#)]),
+                    ChatEvent::ToolRequest(vec![tool_call(r#"{"maximum": 10}"#)]),
+                ],
+                &calls,
+            ),
+        );
+
+        assert!(
+            matches!(event, ChatEvent::ToolRequest(tool_calls) if tool_calls[0].arguments_json == r#"{"maximum": 10}"#)
+        );
+        assert_eq!(calls.borrow().len(), 2);
+    }
+
+    #[test]
+    fn extracts_json_from_text() {
+        assert!(extract_json(r#"Here it is: {"number": 6} Hope it helps!"#).is_ok());
+        assert!(extract_json("```\n[1, 2]\n```").is_ok());
+        assert!(extract_json("sechs").is_err());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::client::{
+    CompletionsRequest, CompletionsResponse, Content, FunctionName, ResponseChunk, ResponseContent,
+    ToolChoice,
+};
+use base64::{engine::general_purpose, Engine as _};
+use golem_llm::error::unsupported;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, ImageDetail,
+    ImageReference, Message, ResponseMetadata, Role, ToolCall, ToolChoice as LlmToolChoice,
+    ToolDefinition, ToolResult, Usage,
+};
+use std::collections::HashMap;
+
+pub fn messages_to_request(
+    messages: Vec<Message>,
+    config: Config,
+) -> Result<CompletionsRequest, Error> {
+    let options = config
+        .provider_options
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect::<HashMap<_, _>>();
+
+    let mut completion_messages = Vec::new();
+    for message in messages {
+        match message.role {
+            Role::User => completion_messages.push(crate::client::Message::User {
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Assistant => completion_messages.push(crate::client::Message::Assistant {
+                content: Some(convert_content_parts(message.content)?),
+                tool_calls: None,
+            }),
+            Role::System => completion_messages.push(crate::client::Message::System {
+                content: convert_content_parts(message.content)?,
+            }),
+            Role::Tool => completion_messages.push(crate::client::Message::Tool {
+                name: message.name,
+                content: convert_content_parts(message.content)?,
+                tool_call_id: None,
+            }),
+        }
+    }
+
+    let mut tools = Vec::new();
+    for tool in config.tools {
+        tools.push(tool_definition_to_tool(tool)?)
+    }
+
+    Ok(CompletionsRequest {
+        messages: completion_messages,
+        model: config.model,
+        frequency_penalty: options
+            .get("frequency_penalty")
+            .and_then(|fp_s| fp_s.parse::<f32>().ok()),
+        max_tokens: config.max_tokens,
+        parallel_tool_calls: config.parallel_tool_calls.or_else(|| {
+            options
+                .get("parallel_tool_calls")
+                .and_then(|ptc_s| ptc_s.parse::<bool>().ok())
+        }),
+        presence_penalty: options
+            .get("presence_penalty")
+            .and_then(|pp_s| pp_s.parse::<f32>().ok()),
+        random_seed: options
+            .get("random_seed")
+            .and_then(|seed_s| seed_s.parse::<u32>().ok()),
+        safe_prompt: options
+            .get("safe_prompt")
+            .and_then(|sp_s| sp_s.parse::<bool>().ok()),
+        stop: config.stop_sequences,
+        stream: Some(false),
+        temperature: config.temperature,
+        tool_choice: config.tool_choice.map(convert_tool_choice),
+        tools,
+        top_p: options
+            .get("top_p")
+            .and_then(|top_p_s| top_p_s.parse::<f32>().ok()),
+    })
+}
+
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Mode("auto".to_string()),
+        LlmToolChoice::None => ToolChoice::Mode("none".to_string()),
+        LlmToolChoice::Required => ToolChoice::Mode("required".to_string()),
+        LlmToolChoice::Specific(name) => ToolChoice::Function {
+            kind: "function".to_string(),
+            function: FunctionName { name },
+        },
+    }
+}
+
+pub fn process_response(response: CompletionsResponse) -> ChatEvent {
+    let choice = response.choices.first();
+    if let Some(choice) = choice {
+        let contents = choice
+            .message
+            .content
+            .as_ref()
+            .map(convert_response_content)
+            .unwrap_or_default();
+        let tool_calls = choice
+            .message
+            .tool_calls
+            .as_ref()
+            .map(|calls| calls.iter().map(convert_tool_call).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        if contents.is_empty() && !tool_calls.is_empty() {
+            ChatEvent::ToolRequest(tool_calls)
+        } else {
+            let metadata = ResponseMetadata {
+                finish_reason: choice.finish_reason.as_ref().map(convert_finish_reason),
+                usage: response.usage.as_ref().map(convert_usage),
+                provider_id: Some(response.model.clone()),
+                timestamp: Some(response.created.to_string()),
+                provider_metadata_json: None,
+                logprobs: None,
+                cost: None,
+            };
+
+            ChatEvent::Message(CompleteResponse {
+                id: response.id,
+                content: contents,
+                tool_calls,
+                reasoning: choice
+                    .message
+                    .content
+                    .as_ref()
+                    .and_then(convert_response_reasoning),
+                metadata,
+            })
+        }
+    } else {
+        ChatEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "No choices in response".to_string(),
+            provider_error_json: None,
+        })
+    }
+}
+
+/// The text of a response, leaving out non-text chunks like reasoning traces. Empty text, as
+/// sent by the last chunk of a stream, is left out as well.
+pub fn convert_response_content(content: &ResponseContent) -> Vec<ContentPart> {
+    match content {
+        ResponseContent::Text(text) => vec![ContentPart::Text(text.clone())],
+        ResponseContent::Chunks(chunks) => chunks
+            .iter()
+            .filter_map(|chunk| match chunk {
+                ResponseChunk::Text { text } => Some(ContentPart::Text(text.clone())),
+                ResponseChunk::Thinking { .. } | ResponseChunk::Other => None,
+            })
+            .collect(),
+    }
+    .into_iter()
+    .filter(|part| !matches!(part, ContentPart::Text(text) if text.is_empty()))
+    .collect()
+}
+
+/// The text of the reasoning traces of a response, if it has any
+pub fn convert_response_reasoning(content: &ResponseContent) -> Option<String> {
+    let ResponseContent::Chunks(chunks) = content else {
+        return None;
+    };
+    let reasoning = chunks
+        .iter()
+        .filter_map(|chunk| match chunk {
+            ResponseChunk::Thinking { thinking } => Some(thinking),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|chunk| match chunk {
+            ResponseChunk::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<String>();
+    (!reasoning.is_empty()).then_some(reasoning)
+}
+
+pub fn tool_results_to_messages(
+    tool_results: Vec<(ToolCall, ToolResult)>,
+) -> Vec<crate::client::Message> {
+    let mut messages = Vec::new();
+    for (tool_call, tool_result) in tool_results {
+        messages.push(crate::client::Message::Assistant {
+            content: None,
+            tool_calls: Some(vec![crate::client::ToolCall {
+                id: tool_call.id.clone(),
+                function: crate::client::FunctionCall {
+                    name: tool_call.name.clone(),
+                    arguments: tool_call.arguments_json,
+                },
+                index: None,
+            }]),
+        });
+        let content = match tool_result {
+            ToolResult::Success(success) => success.result_json,
+            ToolResult::Error(failure) => failure.error_message,
+        };
+        messages.push(crate::client::Message::Tool {
+            name: Some(tool_call.name),
+            content: Content::Text(content),
+            tool_call_id: Some(tool_call.id),
+        });
+    }
+    messages
+}
+
+pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
+    ToolCall {
+        id: tool_call.id.clone(),
+        name: tool_call.function.name.clone(),
+        arguments_json: tool_call.function.arguments.clone(),
+    }
+}
+
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<Content, Error> {
+    let mut result = Vec::new();
+    for content in contents {
+        match content {
+            ContentPart::Text(text) => result.push(crate::client::ContentPart::TextInput { text }),
+            ContentPart::CitedText(cited) => {
+                result.push(crate::client::ContentPart::TextInput { text: cited.text })
+            }
+            ContentPart::BuiltInToolResult(_) => {}
+            ContentPart::Image(image_reference) => match image_reference {
+                ImageReference::Url(image_url) => {
+                    result.push(crate::client::ContentPart::ImageInput {
+                        image_url: crate::client::ImageUrl {
+                            url: image_url.url,
+                            detail: image_url.detail.map(detail_name),
+                        },
+                    })
+                }
+                ImageReference::Inline(image_source) => {
+                    let base64_data = general_purpose::STANDARD.encode(&image_source.data);
+                    result.push(crate::client::ContentPart::ImageInput {
+                        image_url: crate::client::ImageUrl {
+                            url: format!("data:{};base64,{base64_data}", image_source.mime_type),
+                            detail: image_source.detail.map(detail_name),
+                        },
+                    });
+                }
+            },
+            ContentPart::Audio(_) => return Err(unsupported("audio with Mistral")),
+        }
+    }
+    Ok(Content::List(result))
+}
+
+fn detail_name(detail: ImageDetail) -> String {
+    match detail {
+        ImageDetail::Auto => "auto",
+        ImageDetail::Low => "low",
+        ImageDetail::High => "high",
+    }
+    .to_string()
+}
+
+pub fn convert_finish_reason(value: &crate::client::FinishReason) -> FinishReason {
+    match value {
+        crate::client::FinishReason::Stop => FinishReason::Stop,
+        crate::client::FinishReason::Length | crate::client::FinishReason::ModelLength => {
+            FinishReason::Length
+        }
+        crate::client::FinishReason::Error => FinishReason::Error,
+        crate::client::FinishReason::ToolCalls => FinishReason::ToolCalls,
+    }
+}
+
+pub fn convert_usage(value: &crate::client::Usage) -> Usage {
+    Usage {
+        input_tokens: Some(value.prompt_tokens),
+        output_tokens: Some(value.completion_tokens),
+        total_tokens: Some(value.total_tokens),
+        cached_tokens: None,
+    }
+}
+
+fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with Mistral"));
+    }
+    match serde_json::from_str(&tool.parameters_schema) {
+        Ok(value) => Ok(crate::client::Tool::Function {
+            function: crate::client::Function {
+                name: tool.name,
+                description: tool.description,
+                parameters: value,
+            },
+        }),
+        Err(error) => Err(Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to parse tool parameters for {}: {error}", tool.name),
+            provider_error_json: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{ResponseChunk, ResponseContent, ToolChoice};
+    use crate::conversions::{
+        convert_response_content, convert_response_reasoning, convert_tool_choice,
+    };
+    use golem_llm::golem::llm::llm::{ContentPart, ToolChoice as LlmToolChoice};
+    use serde_json::json;
+
+    #[test]
+    fn names_a_tool_in_tool_choice() {
+        assert_eq!(
+            serde_json::to_value(convert_tool_choice(LlmToolChoice::Required)).unwrap(),
+            json!("required")
+        );
+        assert!(matches!(
+            convert_tool_choice(LlmToolChoice::Specific("get_weather".to_string())),
+            ToolChoice::Function { function, .. } if function.name == "get_weather"
+        ));
+        assert_eq!(
+            serde_json::to_value(convert_tool_choice(LlmToolChoice::Specific(
+                "get_weather".to_string()
+            )))
+            .unwrap(),
+            json!({ "type": "function", "function": { "name": "get_weather" } })
+        );
+    }
+
+    #[test]
+    fn keeps_only_text_chunks() {
+        let content: ResponseContent = serde_json::from_value(json!([
+            { "type": "thinking", "thinking": [{ "type": "text", "text": "hmm" }] },
+            { "type": "text", "text": "Paris" }
+        ]))
+        .unwrap();
+        assert!(matches!(
+            &content,
+            ResponseContent::Chunks(chunks) if matches!(chunks[0], ResponseChunk::Thinking { .. })
+        ));
+        assert_eq!(
+            convert_response_content(&content),
+            vec![ContentPart::Text("Paris".to_string())]
+        );
+        assert_eq!(convert_response_reasoning(&content).as_deref(), Some("hmm"));
+        assert!(convert_response_content(&ResponseContent::Text(String::new())).is_empty());
+    }
+}
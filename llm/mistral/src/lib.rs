@@ -0,0 +1,250 @@
+mod client;
+mod conversions;
+
+use crate::client::{ChatCompletionChunk, CompletionsApi, CompletionsRequest};
+use crate::conversions::{
+    convert_finish_reason, convert_response_content, convert_response_reasoning, convert_tool_call,
+    convert_usage, messages_to_request, process_response, tool_results_to_messages,
+};
+use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Error, Guest, Message, ResponseMetadata, StreamDelta,
+    StreamEvent, ToolCall, ToolResult,
+};
+use golem_llm::redaction::Redacted;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use std::cell::{Ref, RefCell, RefMut};
+
+pub struct MistralChatStream {
+    stream: RefCell<Option<EventSource>>,
+    failure: Option<Error>,
+    finished: RefCell<bool>,
+    pending: RefCell<Option<StreamEvent>>,
+}
+
+impl MistralChatStream {
+    pub fn new(stream: EventSource) -> LlmChatStream<Self> {
+        LlmChatStream::new(MistralChatStream {
+            stream: RefCell::new(Some(stream)),
+            failure: None,
+            finished: RefCell::new(false),
+            pending: RefCell::new(None),
+        })
+    }
+
+    pub fn failed(error: Error) -> LlmChatStream<Self> {
+        LlmChatStream::new(MistralChatStream {
+            stream: RefCell::new(None),
+            failure: Some(error),
+            finished: RefCell::new(false),
+            pending: RefCell::new(None),
+        })
+    }
+}
+
+impl LlmChatStreamState for MistralChatStream {
+    fn failure(&self) -> &Option<Error> {
+        &self.failure
+    }
+
+    fn is_finished(&self) -> bool {
+        *self.finished.borrow()
+    }
+
+    fn set_finished(&self) {
+        *self.finished.borrow_mut() = true;
+    }
+
+    fn stream(&self) -> Ref<Option<EventSource>> {
+        self.stream.borrow()
+    }
+
+    fn stream_mut(&self) -> RefMut<Option<EventSource>> {
+        self.stream.borrow_mut()
+    }
+
+    fn take_pending_event(&self) -> Option<StreamEvent> {
+        self.pending.borrow_mut().take()
+    }
+
+    fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
+        trace!("Received raw stream event: {}", Redacted(&raw));
+        let json: serde_json::Value = serde_json::from_str(raw)
+            .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+
+        let typ = json
+            .as_object()
+            .and_then(|obj| obj.get("object"))
+            .and_then(|v| v.as_str());
+        match typ {
+            Some("chat.completion.chunk") => {
+                let message: ChatCompletionChunk = serde_json::from_value(json)
+                    .map_err(|err| format!("Failed to parse stream event: {err}"))?;
+                let finish = message.usage.as_ref().map(|usage| {
+                    StreamEvent::Finish(ResponseMetadata {
+                        finish_reason: message
+                            .choices
+                            .first()
+                            .and_then(|choice| choice.finish_reason.as_ref())
+                            .map(convert_finish_reason),
+                        usage: Some(convert_usage(usage)),
+                        provider_id: Some(message.model.clone()),
+                        timestamp: Some(message.created.to_string()),
+                        provider_metadata_json: None,
+                        logprobs: None,
+                        cost: None,
+                    })
+                });
+                let delta = message.choices.into_iter().next().and_then(|choice| {
+                    let content = choice
+                        .delta
+                        .content
+                        .as_ref()
+                        .map(convert_response_content)
+                        .filter(|content| !content.is_empty());
+                    let tool_calls = choice
+                        .delta
+                        .tool_calls
+                        .map(|calls| calls.iter().map(convert_tool_call).collect::<Vec<_>>());
+                    let reasoning = choice
+                        .delta
+                        .content
+                        .as_ref()
+                        .and_then(convert_response_reasoning);
+                    (content.is_some() || tool_calls.is_some() || reasoning.is_some()).then_some(
+                        StreamEvent::Delta(StreamDelta {
+                            content,
+                            tool_calls,
+                            reasoning,
+                            logprobs: None,
+                        }),
+                    )
+                });
+                match delta {
+                    Some(delta) => {
+                        *self.pending.borrow_mut() = finish;
+                        Ok(Some(delta))
+                    }
+                    None => Ok(finish),
+                }
+            }
+            Some(_) => Ok(None),
+            None => Err("Unexpected stream event format, does not have 'object' field".to_string()),
+        }
+    }
+}
+
+pub struct MistralComponent;
+
+impl MistralComponent {
+    const ENV_VAR_NAME: &'static str = "MISTRAL_API_KEY";
+
+    fn request(client: CompletionsApi, request: CompletionsRequest) -> ChatEvent {
+        match client.send_messages(request) {
+            Ok(response) => process_response(response),
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+
+    fn streaming_request(
+        client: CompletionsApi,
+        mut request: CompletionsRequest,
+    ) -> LlmChatStream<MistralChatStream> {
+        request.stream = Some(true);
+        match client.stream_send_messages(request) {
+            Ok(stream) => MistralChatStream::new(stream),
+            Err(err) => MistralChatStream::failed(err),
+        }
+    }
+}
+
+impl Guest for MistralComponent {
+    type ChatStream = LlmChatStream<MistralChatStream>;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |mistral_api_key| {
+                let client = CompletionsApi::new(mistral_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |mistral_api_key| {
+                let client = CompletionsApi::new(mistral_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for MistralComponent {
+    fn unwrapped_stream(
+        messages: Vec<Message>,
+        config: Config,
+    ) -> LlmChatStream<MistralChatStream> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            MistralChatStream::failed,
+            |mistral_api_key| {
+                let client = CompletionsApi::new(mistral_api_key);
+
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::streaming_request(client, request),
+                    Err(err) => MistralChatStream::failed(err),
+                }
+            },
+        )
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableMistralComponent = golem_llm::durability::DurableLLM<MistralComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableMistralComponent with_types_in golem_llm);
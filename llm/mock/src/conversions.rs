@@ -0,0 +1,219 @@
+use crate::script::ScriptedResponse;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, CompleteResponse, ContentPart, FinishReason, Message, ResponseMetadata, StreamDelta,
+    StreamEvent, ToolCall, Usage,
+};
+
+pub fn response_to_chat_event(
+    id: usize,
+    messages: &[Message],
+    response: ScriptedResponse,
+) -> ChatEvent {
+    if let Some(error) = &response.error {
+        ChatEvent::Error(error.to_error())
+    } else if !response.tool_calls.is_empty() {
+        ChatEvent::ToolRequest(tool_calls(id, &response))
+    } else {
+        let text = response.text();
+        let usage = usage(messages, &text);
+        ChatEvent::Message(CompleteResponse {
+            id: response_id(id),
+            content: vec![ContentPart::Text(text)],
+            tool_calls: vec![],
+            reasoning: None,
+            metadata: metadata(FinishReason::Stop, usage),
+        })
+    }
+}
+
+/// Converts a scripted response to the sequence of stream events. Text is streamed in its chunks,
+/// or word by word, each stream event being returned by a separate `get-next` call. A scripted
+/// error ends the stream after the chunks.
+pub fn response_to_stream_events(
+    id: usize,
+    messages: &[Message],
+    response: ScriptedResponse,
+) -> Vec<StreamEvent> {
+    let mut events = Vec::new();
+    for chunk in response.chunks() {
+        events.push(StreamEvent::Delta(StreamDelta {
+            content: Some(vec![ContentPart::Text(chunk)]),
+            tool_calls: None,
+            reasoning: None,
+            logprobs: None,
+        }));
+    }
+
+    if let Some(error) = &response.error {
+        events.push(StreamEvent::Error(error.to_error()));
+        return events;
+    }
+
+    let finish_reason = if response.tool_calls.is_empty() {
+        FinishReason::Stop
+    } else {
+        events.push(StreamEvent::Delta(StreamDelta {
+            content: None,
+            tool_calls: Some(tool_calls(id, &response)),
+            reasoning: None,
+            logprobs: None,
+        }));
+        FinishReason::ToolCalls
+    };
+
+    events.push(StreamEvent::Finish(metadata(
+        finish_reason,
+        usage(messages, &response.text()),
+    )));
+    events
+}
+
+fn tool_calls(id: usize, response: &ScriptedResponse) -> Vec<ToolCall> {
+    response
+        .tool_calls
+        .iter()
+        .enumerate()
+        .map(|(index, call)| ToolCall {
+            id: call
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("mock-call-{id}-{index}")),
+            name: call.name.clone(),
+            arguments_json: call.arguments.to_string(),
+        })
+        .collect()
+}
+
+fn response_id(id: usize) -> String {
+    format!("mock-response-{id}")
+}
+
+fn metadata(finish_reason: FinishReason, usage: Usage) -> ResponseMetadata {
+    ResponseMetadata {
+        finish_reason: Some(finish_reason),
+        usage: Some(usage),
+        provider_id: Some("mock".to_string()),
+        timestamp: None,
+        provider_metadata_json: None,
+        logprobs: None,
+        cost: None,
+    }
+}
+
+/// Deterministic token usage, counting whitespace separated words
+fn usage(messages: &[Message], output: &str) -> Usage {
+    let input_tokens = messages
+        .iter()
+        .flat_map(|message| message.content.iter())
+        .map(|part| match part {
+            ContentPart::Text(text) => text.split_whitespace().count() as u32,
+            ContentPart::CitedText(cited) => cited.text.split_whitespace().count() as u32,
+            ContentPart::Image(_) | ContentPart::Audio(_) | ContentPart::BuiltInToolResult(_) => 0,
+        })
+        .sum::<u32>();
+    let output_tokens = output.split_whitespace().count() as u32;
+    Usage {
+        input_tokens: Some(input_tokens),
+        output_tokens: Some(output_tokens),
+        total_tokens: Some(input_tokens + output_tokens),
+        cached_tokens: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conversions::{response_to_chat_event, response_to_stream_events};
+    use crate::script::{next_response, ScriptedError, ScriptedResponse, RESPONSE_OPTION};
+    use golem_llm::golem::llm::llm::{
+        ChatEvent, Config, ContentPart, ErrorCode, Kv, Message, Role, StreamEvent,
+    };
+
+    fn messages() -> Vec<Message> {
+        vec![Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text("Tell me a story".to_string())],
+            cache_control: None,
+        }]
+    }
+
+    fn texts(events: &[StreamEvent]) -> Vec<String> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                StreamEvent::Delta(delta) => match delta.content.as_deref() {
+                    Some([ContentPart::Text(text)]) => Some(text.clone()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn streams_the_scripted_chunks() {
+        let response = ScriptedResponse {
+            chunks: Some(vec!["Once upon".to_string(), " a time".to_string()]),
+            ..Default::default()
+        };
+
+        let events = response_to_stream_events(1, &messages(), response.clone());
+        assert_eq!(texts(&events), vec!["Once upon", " a time"]);
+        assert!(matches!(events.last(), Some(StreamEvent::Finish(_))));
+
+        let ChatEvent::Message(message) = response_to_chat_event(1, &messages(), response) else {
+            panic!("expected a message")
+        };
+        assert_eq!(
+            message.content,
+            vec![ContentPart::Text("Once upon a time".to_string())]
+        );
+    }
+
+    #[test]
+    fn fails_the_stream_after_the_chunks() {
+        let response = ScriptedResponse {
+            text: Some("Once upon a time".to_string()),
+            error: Some(ScriptedError {
+                code: "overloaded".to_string(),
+                message: None,
+                provider_error_json: None,
+            }),
+            ..Default::default()
+        };
+
+        let events = response_to_stream_events(1, &messages(), response);
+        assert_eq!(texts(&events), vec!["Once ", "upon ", "a ", "time"]);
+        let Some(StreamEvent::Error(error)) = events.last() else {
+            panic!("expected an error")
+        };
+        assert_eq!(error.code, ErrorCode::Overloaded);
+    }
+
+    #[test]
+    fn takes_the_response_from_the_provider_options() {
+        let config = Config {
+            model: "mock".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![Kv {
+                key: RESPONSE_OPTION.to_string(),
+                value: r#"{"text": "Scripted", "chunk_delay_ms": 10}"#.to_string(),
+            }],
+        };
+
+        let (_, response) = next_response(&messages(), &config).unwrap();
+        assert_eq!(response.text(), "Scripted");
+        assert_eq!(response.chunk_delay().as_millis(), 10);
+    }
+}
@@ -0,0 +1,128 @@
+mod conversions;
+mod script;
+
+use crate::conversions::{response_to_chat_event, response_to_stream_events};
+use crate::script::next_response;
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Guest, GuestChatStream, Message, StreamEvent, ToolCall,
+    ToolResult,
+};
+use golem_llm::telemetry::set_provider;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// A chat stream replaying a precomputed list of events, one event per `get-next` call, each
+/// event becoming available `delay` after the previous one
+pub struct MockChatStream {
+    events: RefCell<VecDeque<StreamEvent>>,
+    delay: Duration,
+    next_at: Cell<Instant>,
+}
+
+impl MockChatStream {
+    fn new(events: Vec<StreamEvent>, delay: Duration) -> Self {
+        MockChatStream {
+            events: RefCell::new(events.into()),
+            delay,
+            next_at: Cell::new(Instant::now() + delay),
+        }
+    }
+
+    fn subscribe(&self) -> Pollable {
+        let remaining = self.next_at.get().saturating_duration_since(Instant::now());
+        golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(
+            remaining.as_nanos() as u64,
+        )
+    }
+}
+
+impl GuestChatStream for MockChatStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        if Instant::now() < self.next_at.get() {
+            return None;
+        }
+        self.next_at.set(Instant::now() + self.delay);
+        Some(self.events.borrow_mut().pop_front().into_iter().collect())
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        loop {
+            self.subscribe().block();
+            if let Some(events) = self.get_next() {
+                break events;
+            }
+        }
+    }
+
+    fn cancel(&self) {
+        self.events.borrow_mut().clear();
+    }
+}
+
+pub struct MockComponent;
+
+impl MockComponent {
+    fn respond(messages: Vec<Message>, config: &Config) -> ChatEvent {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        set_provider("mock");
+
+        match next_response(&messages, config) {
+            Ok((id, response)) => {
+                trace!("Returning scripted response {id}: {response:?}");
+                response_to_chat_event(id, &messages, response)
+            }
+            Err(err) => ChatEvent::Error(err),
+        }
+    }
+}
+
+impl Guest for MockComponent {
+    type ChatStream = MockChatStream;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        Self::respond(messages, &config)
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        _tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        Self::respond(messages, &config)
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for MockComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> MockChatStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        set_provider("mock");
+
+        match next_response(&messages, &config) {
+            Ok((id, response)) => {
+                trace!("Streaming scripted response {id}: {response:?}");
+                let delay = response.chunk_delay();
+                MockChatStream::new(response_to_stream_events(id, &messages, response), delay)
+            }
+            Err(err) => MockChatStream::new(vec![StreamEvent::Error(err)], Duration::ZERO),
+        }
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableMockComponent = golem_llm::durability::DurableLLM<MockComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableMockComponent with_types_in golem_llm);
@@ -0,0 +1,241 @@
+use golem_llm::config::provider_option;
+use golem_llm::golem::llm::llm::{Config, ContentPart, Error, ErrorCode, Message, Role};
+use serde::Deserialize;
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Provider option holding a single response as JSON, returned for this request instead of the
+/// next response of the script
+pub const RESPONSE_OPTION: &str = "mock_response";
+
+/// A script of responses returned by the mock provider, in order.
+///
+/// Loaded as JSON from the `GOLEM_LLM_MOCK_SCRIPT` environment variable, or from the file
+/// given in `GOLEM_LLM_MOCK_SCRIPT_FILE` (for example a file provided through the component's
+/// initial file system).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Script {
+    #[serde(default)]
+    pub responses: Vec<ScriptedResponse>,
+    /// Start again from the first response when the script is exhausted
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScriptedResponse {
+    /// When set, the response is only returned if the last user message contains this text.
+    /// Such responses are not consumed and can be returned any number of times.
+    #[serde(default)]
+    pub when_contains: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    /// The deltas of the text when streaming, instead of splitting it into words. When `text` is
+    /// not set, the chunks also make up the text of non-streaming responses.
+    #[serde(default)]
+    pub chunks: Option<Vec<String>>,
+    /// The time to wait before each stream event
+    #[serde(default)]
+    pub chunk_delay_ms: Option<u64>,
+    #[serde(default)]
+    pub tool_calls: Vec<ScriptedToolCall>,
+    /// The error returned instead of the response. When streaming, it is emitted after the
+    /// chunks, simulating a failure in the middle of the response.
+    #[serde(default)]
+    pub error: Option<ScriptedError>,
+}
+
+impl ScriptedResponse {
+    pub fn text(&self) -> String {
+        match (&self.text, &self.chunks) {
+            (Some(text), _) => text.clone(),
+            (None, Some(chunks)) => chunks.concat(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// The deltas of the text when streaming
+    pub fn chunks(&self) -> Vec<String> {
+        match &self.chunks {
+            Some(chunks) => chunks.clone(),
+            None => self
+                .text()
+                .split_inclusive(' ')
+                .map(|word| word.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn chunk_delay(&self) -> Duration {
+        Duration::from_millis(self.chunk_delay_ms.unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptedToolCall {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptedError {
+    /// One of the `error-code` cases, for example `rate-limit-exceeded`
+    pub code: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub provider_error_json: Option<String>,
+}
+
+impl ScriptedError {
+    pub fn to_error(&self) -> Error {
+        let code = match self.code.as_str() {
+            "invalid-request" => ErrorCode::InvalidRequest,
+            "authentication-failed" => ErrorCode::AuthenticationFailed,
+            "rate-limit-exceeded" => ErrorCode::RateLimitExceeded,
+            "internal-error" => ErrorCode::InternalError,
+            "unsupported" => ErrorCode::Unsupported,
+            "quota-exceeded" => ErrorCode::QuotaExceeded,
+            "context-length-exceeded" => ErrorCode::ContextLengthExceeded,
+            "content-filtered" => ErrorCode::ContentFiltered,
+            "overloaded" => ErrorCode::Overloaded,
+            "timeout" => ErrorCode::Timeout,
+            _ => ErrorCode::Unknown,
+        };
+        Error {
+            code,
+            message: self
+                .message
+                .clone()
+                .unwrap_or_else(|| format!("Scripted error: {}", self.code)),
+            provider_error_json: self.provider_error_json.clone(),
+        }
+    }
+}
+
+struct MockState {
+    script: Option<Result<Script, String>>,
+    next: usize,
+}
+
+thread_local! {
+    static MOCK_STATE: RefCell<MockState> = const { RefCell::new(MockState {
+        script: None,
+        next: 0,
+    }) };
+}
+
+fn load_script() -> Result<Script, String> {
+    if let Ok(script) = std::env::var("GOLEM_LLM_MOCK_SCRIPT") {
+        serde_json::from_str(&script)
+            .map_err(|err| format!("Failed to parse GOLEM_LLM_MOCK_SCRIPT: {err}"))
+    } else if let Ok(path) = std::env::var("GOLEM_LLM_MOCK_SCRIPT_FILE") {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read mock script {path}: {err}"))?;
+        serde_json::from_str(&contents)
+            .map_err(|err| format!("Failed to parse mock script {path}: {err}"))
+    } else {
+        Ok(Script::default())
+    }
+}
+
+/// Selects the next response for the given conversation, unless the request passes its own
+/// response in the `mock_response` provider option.
+///
+/// Without a script, the mock echoes the last user message.
+pub fn next_response(
+    messages: &[Message],
+    config: &Config,
+) -> Result<(usize, ScriptedResponse), Error> {
+    let last_user_text = last_user_text(messages);
+
+    MOCK_STATE.with_borrow_mut(|state| {
+        if let Some(response) = provider_option(config, RESPONSE_OPTION) {
+            let response = serde_json::from_str(&response).map_err(|err| Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("Failed to parse {RESPONSE_OPTION}: {err}"),
+                provider_error_json: None,
+            })?;
+            state.next += 1;
+            return Ok((state.next, response));
+        }
+
+        let script = state
+            .script
+            .get_or_insert_with(load_script)
+            .clone()
+            .map_err(|message| Error {
+                code: ErrorCode::InvalidRequest,
+                message,
+                provider_error_json: None,
+            })?;
+
+        if script.responses.is_empty() {
+            state.next += 1;
+            return Ok((
+                state.next,
+                ScriptedResponse {
+                    text: Some(format!("Mock response to: {last_user_text}")),
+                    ..Default::default()
+                },
+            ));
+        }
+
+        if let Some(response) = script.responses.iter().find(|response| {
+            response
+                .when_contains
+                .as_ref()
+                .is_some_and(|text| last_user_text.contains(text))
+        }) {
+            state.next += 1;
+            return Ok((state.next, response.clone()));
+        }
+
+        let sequence = script
+            .responses
+            .iter()
+            .filter(|response| response.when_contains.is_none())
+            .collect::<Vec<_>>();
+        let index = if script.repeat && !sequence.is_empty() {
+            state.next % sequence.len()
+        } else {
+            state.next
+        };
+        match sequence.get(index) {
+            Some(response) => {
+                state.next += 1;
+                Ok((state.next, (*response).clone()))
+            }
+            None => Err(Error {
+                code: ErrorCode::InternalError,
+                message: format!("Mock script exhausted after {} responses", sequence.len()),
+                provider_error_json: None,
+            }),
+        }
+    })
+}
+
+fn last_user_text(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .rev()
+        .find(|message| message.role == Role::User)
+        .map(|message| {
+            message
+                .content
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text(text) => Some(text.as_str()),
+                    ContentPart::CitedText(cited) => Some(cited.text.as_str()),
+                    ContentPart::Image(_)
+                    | ContentPart::Audio(_)
+                    | ContentPart::BuiltInToolResult(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
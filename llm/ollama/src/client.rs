@@ -2,15 +2,15 @@ use std::{fmt::Debug, fs, path::Path};
 
 use base64::{engine::general_purpose, Engine};
 use golem_llm::{
-    error::{error_code_from_status, from_event_source_error},
+    error::{from_error_response, from_event_source_error, ProviderError},
     event_source::EventSource,
     golem::llm::llm::{Error, ErrorCode},
+    interceptor::{self, OutgoingRequest},
+    recording::Recorder,
+    redaction::Redacted,
 };
 use log::trace;
-use reqwest::{
-    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
-    Client, Method, Response, StatusCode,
-};
+use reqwest::{header::ACCEPT, Client, Method, Response, StatusCode};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use url::Url;
@@ -22,9 +22,12 @@ pub struct OllamaApi {
 }
 
 impl OllamaApi {
-    pub fn new(default_model: String) -> Self {
-        let base_url =
-            std::env::var("GOLEM_OLLAMA_BASE_URL").unwrap_or("http://localhost:11434".to_string());
+    /// Creates a client for the Ollama API. The base URL passed in `base_url` takes precedence over
+    /// the `GOLEM_OLLAMA_BASE_URL` environment variable.
+    pub fn new(default_model: String, base_url: Option<String>) -> Self {
+        let base_url = base_url.unwrap_or_else(|| {
+            std::env::var("GOLEM_OLLAMA_BASE_URL").unwrap_or("http://localhost:11434".to_string())
+        });
         let client = Client::builder()
             .build()
             .expect("Failed to initialize HTTP client");
@@ -36,7 +39,7 @@ impl OllamaApi {
     }
 
     pub fn send_chat(&self, params: CompletionsRequest) -> Result<CompletionsResponse, Error> {
-        trace!("Sending request to Ollama API: {params:?}");
+        trace!("Sending request to Ollama API: {:?}", Redacted(&params));
 
         let mut modified_params = params;
         modified_params.stream = Some(false);
@@ -44,23 +47,22 @@ impl OllamaApi {
             modified_params.model = Some(self.default_model.clone())
         };
 
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let mut recorder = Recorder::new("ollama", "chat", &modified_params);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return handle_response_body(status, raw_body);
+        }
 
         let url = format!("{}/api/chat", self.base_url);
-        let response: Response = self
-            .client
-            .request(Method::POST, url)
-            .headers(headers)
-            .json(&modified_params)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
-
-        handle_response::<CompletionsResponse>(response)
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new("ollama", "chat", Method::POST, url).json(&modified_params)?,
+        )?;
+
+        handle_response::<CompletionsResponse>(response, recorder)
     }
 
     pub fn send_chat_stream(&self, params: CompletionsRequest) -> Result<EventSource, Error> {
-        trace!("Sending request to Ollama API: {params:?}");
+        trace!("Sending request to Ollama API: {:?}", Redacted(&params));
 
         let mut modified_params = params;
         modified_params.stream = Some(true);
@@ -68,27 +70,125 @@ impl OllamaApi {
             modified_params.model = Some(self.default_model.clone())
         };
 
-        let json_body = serde_json::to_string(&modified_params).map_err(|e| Error {
-            code: ErrorCode::InternalError,
-            message: format!("Failed to serialize request body: {e}"),
-            provider_error_json: None,
-        })?;
-
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        headers.insert("Accept", HeaderValue::from_static("application/x-ndjson"));
+        let mut recorder = Recorder::new("ollama", "chat-stream", &modified_params);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
 
         let url = format!("{}/api/chat", self.base_url);
-        let response = self
-            .client
-            .request(Method::POST, url)
-            .headers(headers)
-            .body(json_body)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        let response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new("ollama", "chat-stream", Method::POST, url)
+                .header(ACCEPT, "application/x-ndjson")?
+                .json(&modified_params)?,
+        )?;
         EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
             .map_err(|err| from_event_source_error("Failed to create EventSource stream", err))
     }
+
+    /// Shows the details of a local model, or `None` if the model has not been pulled
+    pub fn show_model(&self, model: &str) -> Result<Option<ModelInfo>, Error> {
+        let request = ModelRequest {
+            model: model.to_string(),
+            stream: None,
+        };
+        let recorder = Recorder::new("ollama", "show", &request);
+
+        let url = format!("{}/api/show", self.base_url);
+        let response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new("ollama", "show", Method::POST, url).json(&request)?,
+        )?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            Ok(None)
+        } else {
+            handle_response::<ModelInfo>(response, recorder).map(Some)
+        }
+    }
+
+    /// Downloads a model from the Ollama library, waiting until it is available
+    pub fn pull_model(&self, model: &str) -> Result<(), Error> {
+        trace!("Pulling model {model} into Ollama");
+        let request = ModelRequest {
+            model: model.to_string(),
+            stream: Some(false),
+        };
+        let recorder = Recorder::new("ollama", "pull", &request);
+
+        let url = format!("{}/api/pull", self.base_url);
+        let response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new("ollama", "pull", Method::POST, url).json(&request)?,
+        )?;
+
+        let pulled = handle_response::<PullResponse>(response, recorder)?;
+        trace!("Pulled model {model}: {}", pulled.status);
+        Ok(())
+    }
+
+    /// Lists the models available on the Ollama host
+    pub fn list_models(&self) -> Result<Vec<LocalModel>, Error> {
+        let recorder = Recorder::new("ollama", "tags", &());
+
+        let url = format!("{}/api/tags", self.base_url);
+        let response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new("ollama", "tags", Method::GET, url),
+        )?;
+
+        handle_response::<ModelList>(response, recorder).map(|list| list.models)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelRequest {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PullResponse {
+    pub status: String,
+}
+
+/// The details of a model returned by `/api/show`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ModelDetails>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_size: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization_level: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModelList {
+    pub models: Vec<LocalModel>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LocalModel {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ModelDetails>,
 }
 
 /// GenerateOptions is Options for generating completions
@@ -161,20 +261,34 @@ pub struct CompletionsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
 
+    /// Either `"json"` or a JSON schema the response has to match
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<String>,
+    pub format: Option<serde_json::Value>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<OllamaModelOptions>,
 
+    /// Whether thinking models return their thinking separately from the answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub think: Option<bool>,
+
     /// If false the response will be returned as a single response object, rather than a stream of objects.
     /// For `chat_completion` this will be set to false.
     /// For `chat_completion_stream` this will be set to true.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
 
+    /// How long the model stays loaded after the request
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub keep_alive: Option<String>,
+    pub keep_alive: Option<KeepAlive>,
+}
+
+/// Ollama takes `keep_alive` either as a number of seconds or as a duration string like `5m`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeepAlive {
+    Seconds(i64),
+    Duration(String),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -249,6 +363,8 @@ pub struct MessageResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub images: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_calls: Option<Vec<ToolCall>>,
@@ -269,49 +385,59 @@ pub struct Function {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OllamaRequestError {
+    #[serde(default)]
     status_code: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     status: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     error_message: Option<String>,
 }
 
-pub fn handle_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Error> {
+pub fn handle_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
     let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
 
+    handle_response_body(status, raw_body)
+}
+
+pub fn handle_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
     match status {
-        StatusCode::OK => {
-            let raw_body = response
-                .text()
-                .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
-
-            match serde_json::from_str::<T>(&raw_body) {
-                Ok(body) => Ok(body),
-                Err(err) => Err(Error {
-                    code: ErrorCode::InternalError,
-                    message: format!("Failed to parse response body: {err}"),
-                    provider_error_json: Some(raw_body),
-                }),
-            }
-        }
+        StatusCode::OK => match serde_json::from_str::<T>(&raw_body) {
+            Ok(body) => Ok(body),
+            Err(err) => Err(Error {
+                code: ErrorCode::InternalError,
+                message: format!("Failed to parse response body: {err}"),
+                provider_error_json: Some(raw_body),
+            }),
+        },
         _ => {
-            let raw_error_body = response
-                .text()
-                .map_err(|err| from_reqwest_error("Failed to receive error response body", err))?;
-            trace!("Received {status} response from OpenRouter API: {raw_error_body:?}");
-
-            let error_body: OllamaRequestError =
-                serde_json::from_str(&raw_error_body).map_err(|err| Error {
-                    code: ErrorCode::InternalError,
-                    message: format!("Failed to parse error response body: {err}"),
-                    provider_error_json: Some(raw_error_body),
-                })?;
-
-            Err(Error {
-                code: error_code_from_status(status),
-                message: error_body.status.unwrap_or_default(),
-                provider_error_json: error_body.error_message,
-            })
+            trace!(
+                "Received {status} response from Ollama API: {:?}",
+                Redacted(&raw_body)
+            );
+
+            Err(from_error_response(status, raw_body, |raw_body| {
+                serde_json::from_str::<OllamaRequestError>(raw_body)
+                    .ok()
+                    .map(|error_body| ProviderError {
+                        kind: None,
+                        message: error_body
+                            .error
+                            .or(error_body.status)
+                            .or(error_body.error_message),
+                    })
+            }))
         }
     }
 }
@@ -339,3 +465,17 @@ pub fn from_reqwest_error(context: &str, err: reqwest::Error) -> Error {
         provider_error_json: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{handle_response_body, CompletionsResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| format!(r#"{{"error": "{message}"}}"#),
+            |status, body| handle_response_body::<CompletionsResponse>(status, body).map(|_| ()),
+        );
+    }
+}
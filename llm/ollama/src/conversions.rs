@@ -1,16 +1,34 @@
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
 
 use crate::client::{
-    image_to_base64, CompletionsRequest, CompletionsResponse, FunctionTool, MessageRequest,
-    MessageRole, OllamaModelOptions, Tool,
+    image_to_base64, CompletionsRequest, CompletionsResponse, FunctionTool, KeepAlive,
+    MessageRequest, MessageRole, OllamaModelOptions, Tool,
 };
 use base64::{engine::general_purpose, Engine};
+use golem_llm::config::{parse_response_schema, provider_option};
+use golem_llm::error::unsupported;
 use golem_llm::golem::llm::llm::{
-    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason,
-    ImageReference, Message, ResponseMetadata, Role, ToolCall as golem_llm_ToolCall, ToolResult,
-    Usage,
+    ChatEvent, CitedText, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason,
+    ImageReference, Message, ResponseFormat, ResponseMetadata, Role,
+    ToolCall as golem_llm_ToolCall, ToolResult, Usage,
 };
+use golem_llm::redaction::Redacted;
 use log::trace;
+use serde_json::{json, Map, Value};
+
+/// Provider option pulling the model into the Ollama host when it is missing, before sending the
+/// request
+pub const PULL_MODEL_OPTION: &str = "pull_model";
+
+/// Provider option adding the details of the model, like its family and quantization level, to
+/// the provider metadata as `model_info`
+pub const MODEL_INFO_OPTION: &str = "model_info";
+
+/// Provider option adding the names of the models available on the Ollama host to the provider
+/// metadata as `local_models`
+pub const LIST_MODELS_OPTION: &str = "list_models";
 
 pub fn messages_to_request(
     messages: Vec<Message>,
@@ -38,19 +56,23 @@ pub fn messages_to_request(
 
         for content_part in message.content {
             match content_part {
-                ContentPart::Text(text) => {
+                ContentPart::Text(text) | ContentPart::CitedText(CitedText { text, .. }) => {
                     if !message_content.is_empty() {
                         message_content.push('\n');
                     }
                     message_content.push_str(&text);
                 }
+                ContentPart::BuiltInToolResult(_) => {}
                 ContentPart::Image(reference) => match reference {
                     ImageReference::Url(image_url) => {
                         let url = &image_url.url;
                         match image_to_base64(url) {
                             Ok(image) => attached_image.push(image),
                             Err(err) => {
-                                trace!("Failed to encode image: {url}\nError: {err}\n");
+                                trace!(
+                                    "Failed to encode image: {}\nError: {err}\n",
+                                    Redacted(&url)
+                                );
                             }
                         }
                     }
@@ -59,6 +81,7 @@ pub fn messages_to_request(
                         attached_image.push(base64_data);
                     }
                 },
+                ContentPart::Audio(_) => return Err(unsupported("audio with Ollama")),
             }
         }
 
@@ -80,6 +103,9 @@ pub fn messages_to_request(
 
     let mut tools = Vec::new();
     for tool in config.tools {
+        if tool.built_in.is_some() {
+            return Err(unsupported("built-in tools with Ollama"));
+        }
         let param = serde_json::from_str(&tool.parameters_schema).map_err(|err| Error {
             code: ErrorCode::InternalError,
             message: format!("Failed to parse tool parameters for {}: {err}", tool.name),
@@ -96,39 +122,65 @@ pub fn messages_to_request(
     }
 
     let ollama_options = OllamaModelOptions {
-        min_p: parse_option(&options, "min_p"),
+        min_p: parse_option(&options, "min_p")?,
         temperature: config.temperature,
-        top_p: parse_option(&options, "top_p"),
-        top_k: parse_option(&options, "top_k"),
-        num_predict: parse_option(&options, "num_predict"),
+        top_p: parse_option(&options, "top_p")?,
+        top_k: parse_option(&options, "top_k")?,
+        num_predict: parse_option(&options, "num_predict")?,
         stop: config.stop_sequences.clone(),
-        repeat_penalty: parse_option(&options, "repeat_penalty"),
-        num_ctx: parse_option(&options, "num_ctx"),
-        seed: parse_option(&options, "seed"),
-        mirostat: parse_option(&options, "mirostat"),
-        mirostat_eta: parse_option(&options, "mirostat_eta"),
-        mirostat_tau: parse_option(&options, "mirostat_tau"),
-        num_gpu: parse_option(&options, "num_gpu"),
-        num_thread: parse_option(&options, "num_thread"),
-        penalize_newline: parse_option(&options, "penalize_newline"),
-        num_keep: parse_option(&options, "num_keep"),
-        typical_p: parse_option(&options, "typical_p"),
-        repeat_last_n: parse_option(&options, "repeat_last_n"),
-        presence_penalty: parse_option(&options, "presence_penalty"),
-        frequency_penalty: parse_option(&options, "frequency_penalty"),
-        numa: parse_option(&options, "numa"),
-        num_batch: parse_option(&options, "num_batch"),
-        main_gpu: parse_option(&options, "main_gpu"),
-        use_mmap: parse_option(&options, "use_mmap"),
+        repeat_penalty: parse_option(&options, "repeat_penalty")?,
+        num_ctx: parse_option(&options, "num_ctx")?,
+        seed: parse_option(&options, "seed")?,
+        mirostat: parse_option(&options, "mirostat")?,
+        mirostat_eta: parse_option(&options, "mirostat_eta")?,
+        mirostat_tau: parse_option(&options, "mirostat_tau")?,
+        num_gpu: parse_option(&options, "num_gpu")?,
+        num_thread: parse_option(&options, "num_thread")?,
+        penalize_newline: parse_option(&options, "penalize_newline")?,
+        num_keep: parse_option(&options, "num_keep")?,
+        typical_p: parse_option(&options, "typical_p")?,
+        repeat_last_n: parse_option(&options, "repeat_last_n")?,
+        presence_penalty: parse_option(&options, "presence_penalty")?,
+        frequency_penalty: parse_option(&options, "frequency_penalty")?,
+        numa: parse_option(&options, "numa")?,
+        num_batch: parse_option(&options, "num_batch")?,
+        main_gpu: parse_option(&options, "main_gpu")?,
+        use_mmap: parse_option(&options, "use_mmap")?,
+    };
+    if let Some(mirostat) = ollama_options
+        .mirostat
+        .filter(|value| !(0..=2).contains(value))
+    {
+        return Err(invalid_option("mirostat", mirostat, "expected 0, 1 or 2"));
+    }
+    if let Some(num_ctx) = ollama_options.num_ctx.filter(|value| *value <= 0) {
+        return Err(invalid_option(
+            "num_ctx",
+            num_ctx,
+            "expected a positive number of tokens",
+        ));
+    }
+
+    // The `format` provider option is still accepted when no response format is requested
+    let format = match config.response_format {
+        Some(ResponseFormat::Text) => None,
+        Some(ResponseFormat::JsonObject) => Some(Value::String("json".to_string())),
+        Some(ResponseFormat::JsonSchema(schema)) => Some(parse_response_schema(&schema)?),
+        None => options.get("format").cloned().map(Value::String),
     };
 
     Ok(CompletionsRequest {
         model: Some(config.model),
         messages: Some(request_message),
         tools: Some(tools),
-        format: options.get("format").cloned(),
+        format,
         options: Some(ollama_options),
-        keep_alive: options.get("keep_alive").cloned(),
+        think: (config.reasoning_effort.is_some() || config.thinking_budget.is_some())
+            .then_some(true),
+        keep_alive: options
+            .get("keep_alive")
+            .map(|value| parse_keep_alive(value))
+            .transpose()?,
         stream: Some(false),
     })
 }
@@ -166,11 +218,87 @@ fn tool_results_to_messages(
     messages
 }
 
-fn parse_option<T: std::str::FromStr>(options: &HashMap<String, String>, key: &str) -> Option<T> {
-    options.get(key).and_then(|v| v.parse::<T>().ok())
+/// Parses a typed model option, rejecting values of the wrong type instead of leaving them out
+fn parse_option<T>(options: &HashMap<String, String>, key: &str) -> Result<Option<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    options
+        .get(key)
+        .map(|value| {
+            value
+                .trim()
+                .parse::<T>()
+                .map_err(|err| invalid_option(key, value, err))
+        })
+        .transpose()
 }
 
-pub fn process_response(response: CompletionsResponse) -> ChatEvent {
+/// `keep_alive` is either a number of seconds or a Go duration like `5m` or `1h30m`, negative to
+/// keep the model loaded indefinitely
+fn parse_keep_alive(value: &str) -> Result<KeepAlive, Error> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<i64>() {
+        Ok(KeepAlive::Seconds(seconds))
+    } else if is_duration(value) {
+        Ok(KeepAlive::Duration(value.to_string()))
+    } else {
+        Err(invalid_option(
+            "keep_alive",
+            value,
+            "expected a number of seconds or a duration like 5m or 1h30m",
+        ))
+    }
+}
+
+fn is_duration(value: &str) -> bool {
+    const UNITS: [&str; 7] = ["ns", "us", "µs", "ms", "s", "m", "h"];
+    let mut rest = value.strip_prefix('-').unwrap_or(value);
+    if rest.is_empty() {
+        return false;
+    }
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        if rest[..number_len].parse::<f64>().is_err() {
+            return false;
+        }
+        rest = &rest[number_len..];
+        match UNITS.iter().find(|unit| rest.starts_with(*unit)) {
+            Some(unit) => rest = &rest[unit.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn invalid_option(key: &str, value: impl Display, details: impl Display) -> Error {
+    Error {
+        code: ErrorCode::InvalidRequest,
+        message: format!("Invalid {key} provider option: {value}, {details}"),
+        provider_error_json: None,
+    }
+}
+
+/// Parses a provider option enabling a feature, either `true` or `false`
+pub fn flag_option(config: &Config, key: &str) -> Result<bool, Error> {
+    match provider_option(config, key) {
+        None => Ok(false),
+        Some(value) => value
+            .trim()
+            .parse::<bool>()
+            .map_err(|_| invalid_option(key, &value, "expected true or false")),
+    }
+}
+
+/// `model_metadata` holds the model management results requested with provider options, added
+/// to the provider metadata of the response
+pub fn process_response(
+    response: CompletionsResponse,
+    model_metadata: &Map<String, Value>,
+) -> ChatEvent {
     if let Some(ref message) = response.message {
         let mut content = Vec::<ContentPart>::new();
         let mut tool_calls = Vec::<golem_llm_ToolCall>::new();
@@ -201,6 +329,7 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
             input_tokens,
             output_tokens,
             total_tokens: Some(input_tokens.unwrap_or(0) + output_tokens.unwrap_or(0)),
+            cached_tokens: None,
         };
 
         let timestamp = response.created_at.clone();
@@ -210,13 +339,16 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
             usage: Some(usage),
             provider_id: Some("ollama".to_string()),
             timestamp: Some(timestamp.clone()),
-            provider_metadata_json: Some(get_provider_metadata(&response)),
+            provider_metadata_json: Some(get_provider_metadata(&response, model_metadata)),
+            logprobs: None,
+            cost: None,
         };
 
         ChatEvent::Message(CompleteResponse {
             id: format!("ollama-{}", timestamp),
             content,
             tool_calls,
+            reasoning: message.thinking.clone().filter(|text| !text.is_empty()),
             metadata,
         })
     } else {
@@ -228,19 +360,172 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
     }
 }
 
-pub fn get_provider_metadata(response: &CompletionsResponse) -> String {
-    format!(
-        r#"{{
-    "total_duration":"{}",
-    "load_duration":"{}",
-    "prompt_eval_duration":{},
-    "eval_duration":{},
-    "context":{},
-    }}"#,
-        response.total_duration.unwrap_or(0),
-        response.load_duration.unwrap_or(0),
-        response.prompt_eval_duration.unwrap_or(0),
-        response.eval_duration.unwrap_or(0),
-        response.eval_count.unwrap_or(0)
+pub fn get_provider_metadata(
+    response: &CompletionsResponse,
+    model_metadata: &Map<String, Value>,
+) -> String {
+    provider_metadata(
+        json!({
+            "total_duration": response.total_duration.unwrap_or(0),
+            "load_duration": response.load_duration.unwrap_or(0),
+            "prompt_eval_duration": response.prompt_eval_duration.unwrap_or(0),
+            "eval_duration": response.eval_duration.unwrap_or(0),
+            "context": response.eval_count.unwrap_or(0),
+        }),
+        model_metadata,
     )
 }
+
+/// The timings of a response, extended with the results of the model management operations
+pub fn provider_metadata(timings: Value, model_metadata: &Map<String, Value>) -> String {
+    let mut metadata = match timings {
+        Value::Object(timings) => timings,
+        _ => Map::new(),
+    };
+    metadata.extend(model_metadata.clone());
+    Value::Object(metadata).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conversions::{
+        flag_option, messages_to_request, provider_metadata, PULL_MODEL_OPTION,
+    };
+    use golem_llm::golem::llm::llm::{Config, ContentPart, ErrorCode, Kv, Message, Role};
+    use serde_json::{json, Map, Value};
+
+    fn config(provider_options: Vec<Kv>) -> Config {
+        Config {
+            model: "llama3.2".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options,
+        }
+    }
+
+    #[test]
+    fn parses_flag_options() {
+        assert_eq!(flag_option(&config(vec![]), PULL_MODEL_OPTION), Ok(false));
+        let option = |value: &str| {
+            config(vec![Kv {
+                key: PULL_MODEL_OPTION.to_string(),
+                value: value.to_string(),
+            }])
+        };
+        assert_eq!(flag_option(&option("true"), PULL_MODEL_OPTION), Ok(true));
+
+        let error = flag_option(&option("yes"), PULL_MODEL_OPTION).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.message,
+            "Invalid pull_model provider option: yes, expected true or false"
+        );
+    }
+
+    #[test]
+    fn adds_model_metadata_to_the_timings() {
+        let mut model_metadata = Map::new();
+        model_metadata.insert("local_models".to_string(), json!(["llama3.2:latest"]));
+        let metadata: Value = serde_json::from_str(&provider_metadata(
+            json!({ "total_duration": 1200, "eval_duration": 800 }),
+            &model_metadata,
+        ))
+        .unwrap();
+        assert_eq!(
+            metadata,
+            json!({
+                "total_duration": 1200,
+                "eval_duration": 800,
+                "local_models": ["llama3.2:latest"]
+            })
+        );
+    }
+
+    fn options(options: &[(&str, &str)]) -> Config {
+        config(
+            options
+                .iter()
+                .map(|(key, value)| Kv {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    fn request_error(options: &[(&str, &str)]) -> String {
+        let error = messages_to_request(vec![message()], self::options(options), None).unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        error.message
+    }
+
+    fn message() -> Message {
+        Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text("Hi".to_string())],
+            cache_control: None,
+        }
+    }
+
+    #[test]
+    fn maps_typed_model_options() {
+        let request = messages_to_request(
+            vec![message()],
+            options(&[
+                ("num_ctx", "8192"),
+                ("num_gpu", "0"),
+                ("mirostat", "2"),
+                ("repeat_penalty", "1.1"),
+                ("keep_alive", "1h30m"),
+            ]),
+            None,
+        )
+        .unwrap();
+        let request = serde_json::to_value(request).unwrap();
+        assert_eq!(request["keep_alive"], json!("1h30m"));
+        assert_eq!(request["options"]["num_ctx"], json!(8192));
+        assert_eq!(request["options"]["num_gpu"], json!(0));
+        assert_eq!(request["options"]["mirostat"], json!(2));
+        assert!((request["options"]["repeat_penalty"].as_f64().unwrap() - 1.1).abs() < 1e-6);
+
+        // A number of seconds is sent as a number, a negative one keeps the model loaded
+        let request =
+            messages_to_request(vec![message()], options(&[("keep_alive", "-1")]), None).unwrap();
+        assert_eq!(
+            serde_json::to_value(request).unwrap()["keep_alive"],
+            json!(-1)
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_model_options() {
+        assert_eq!(
+            request_error(&[("num_ctx", "8k")]),
+            "Invalid num_ctx provider option: 8k, invalid digit found in string"
+        );
+        assert_eq!(
+            request_error(&[("num_ctx", "0")]),
+            "Invalid num_ctx provider option: 0, expected a positive number of tokens"
+        );
+        assert_eq!(
+            request_error(&[("mirostat", "3")]),
+            "Invalid mirostat provider option: 3, expected 0, 1 or 2"
+        );
+        assert_eq!(
+            request_error(&[("keep_alive", "5 minutes")]),
+            "Invalid keep_alive provider option: 5 minutes, expected a number of seconds or a \
+             duration like 5m or 1h30m"
+        );
+    }
+}
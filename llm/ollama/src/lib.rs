@@ -1,35 +1,44 @@
 use std::cell::{Ref, RefCell, RefMut};
 
 use client::{CompletionsRequest, OllamaApi};
-use conversions::{messages_to_request, process_response};
+use conversions::{
+    flag_option, messages_to_request, process_response, provider_metadata, LIST_MODELS_OPTION,
+    MODEL_INFO_OPTION, PULL_MODEL_OPTION,
+};
 use golem_llm::{
     chat_stream::{LlmChatStream, LlmChatStreamState},
-    durability::{DurableLLM, ExtendedGuest},
+    config::{provider_option, BASE_URL_OPTION},
+    durability::ExtendedGuest,
     event_source::EventSource,
     golem::llm::llm::{
         ChatEvent, ChatStream, Config, ContentPart, Error, FinishReason, Guest, Message,
         ResponseMetadata, Role, StreamDelta, StreamEvent, ToolCall, ToolResult, Usage,
     },
+    redaction::Redacted,
     LOGGING_STATE,
 };
 use golem_rust::wasm_rpc::Pollable;
 use log::trace;
+use serde_json::{Map, Value};
 
 mod client;
 mod conversions;
 
-struct OllamaChatStream {
+pub struct OllamaChatStream {
     stream: RefCell<Option<EventSource>>,
     failure: Option<Error>,
     finished: RefCell<bool>,
+    /// Added to the provider metadata of the finish event
+    model_metadata: Map<String, Value>,
 }
 
 impl OllamaChatStream {
-    pub fn new(stream: EventSource) -> LlmChatStream<Self> {
+    pub fn new(stream: EventSource, model_metadata: Map<String, Value>) -> LlmChatStream<Self> {
         LlmChatStream::new(OllamaChatStream {
             stream: RefCell::new(Some(stream)),
             failure: None,
             finished: RefCell::new(false),
+            model_metadata,
         })
     }
 
@@ -38,6 +47,7 @@ impl OllamaChatStream {
             stream: RefCell::new(None),
             failure: Some(error),
             finished: RefCell::new(false),
+            model_metadata: Map::new(),
         })
     }
 }
@@ -63,7 +73,7 @@ impl LlmChatStreamState for OllamaChatStream {
     }
 
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
-        trace!("Parsing NDJSON line: {raw}");
+        trace!("Parsing NDJSON line: {}", Redacted(&raw));
         let json: serde_json::Value =
             serde_json::from_str(raw.trim()).map_err(|e| format!("JSON parse error: {e}"))?;
 
@@ -81,6 +91,7 @@ impl LlmChatStreamState for OllamaChatStream {
                 input_tokens: Some(input_tokens),
                 output_tokens: Some(input_tokens),
                 total_tokens: Some(input_tokens + output_tokens),
+                cached_tokens: None,
             };
 
             let total_duration = json
@@ -104,14 +115,16 @@ impl LlmChatStreamState for OllamaChatStream {
                 .cloned()
                 .unwrap_or(serde_json::json!(null));
 
-            let provider_metadata = serde_json::json!({
-                "total_duration": total_duration,
-                "load_duration": load_duration,
-                "prompt_eval_duration": prompt_eval_duration,
-                "eval_duration": eval_duration,
-                "context": context
-            })
-            .to_string();
+            let provider_metadata = provider_metadata(
+                serde_json::json!({
+                    "total_duration": total_duration,
+                    "load_duration": load_duration,
+                    "prompt_eval_duration": prompt_eval_duration,
+                    "eval_duration": eval_duration,
+                    "context": context
+                }),
+                &self.model_metadata,
+            );
 
             return Ok(Some(StreamEvent::Finish(ResponseMetadata {
                 finish_reason: Some(FinishReason::Stop),
@@ -119,6 +132,8 @@ impl LlmChatStreamState for OllamaChatStream {
                 provider_id: Some("ollama".to_string()),
                 timestamp,
                 provider_metadata_json: Some(provider_metadata),
+                logprobs: None,
+                cost: None,
             })));
         }
 
@@ -132,6 +147,12 @@ impl LlmChatStreamState for OllamaChatStream {
                 }
             }
 
+            let reasoning = message
+                .get("thinking")
+                .and_then(|t| t.as_str())
+                .filter(|text| !text.is_empty())
+                .map(|text| text.to_string());
+
             if let Some(calls) = message.get("tool_calls").and_then(|tc| tc.as_array()) {
                 for call in calls {
                     if let Some(function) = call.get("function") {
@@ -171,18 +192,24 @@ impl LlmChatStreamState for OllamaChatStream {
                 } else {
                     Some(tool_calls)
                 },
+                reasoning,
+                logprobs: None,
             })));
         }
         Ok(None)
     }
 }
 
-struct OllamaComponent;
+pub struct OllamaComponent;
 
 impl OllamaComponent {
-    fn request(client: &OllamaApi, request: CompletionsRequest) -> ChatEvent {
+    fn request(client: &OllamaApi, request: CompletionsRequest, config: &Config) -> ChatEvent {
+        let model_metadata = match Self::manage_model(client, config) {
+            Ok(model_metadata) => model_metadata,
+            Err(err) => return ChatEvent::Error(err),
+        };
         match client.send_chat(request) {
-            Ok(response) => process_response(response),
+            Ok(response) => process_response(response, &model_metadata),
             Err(err) => ChatEvent::Error(err),
         }
     }
@@ -190,13 +217,53 @@ impl OllamaComponent {
     fn streaming_request(
         client: &OllamaApi,
         mut request: CompletionsRequest,
+        config: &Config,
     ) -> LlmChatStream<OllamaChatStream> {
+        let model_metadata = match Self::manage_model(client, config) {
+            Ok(model_metadata) => model_metadata,
+            Err(err) => return OllamaChatStream::failed(err),
+        };
         request.stream = Some(true);
         match client.send_chat_stream(request) {
-            Ok(stream) => OllamaChatStream::new(stream),
+            Ok(stream) => OllamaChatStream::new(stream, model_metadata),
             Err(err) => OllamaChatStream::failed(err),
         }
     }
+
+    /// Runs the model management operations enabled by provider options before a request: pulls
+    /// the model when it is missing, and collects the model details and the local models to be
+    /// added to the provider metadata
+    fn manage_model(client: &OllamaApi, config: &Config) -> Result<Map<String, Value>, Error> {
+        let pull_model = flag_option(config, PULL_MODEL_OPTION)?;
+        let model_info = flag_option(config, MODEL_INFO_OPTION)?;
+        let list_models = flag_option(config, LIST_MODELS_OPTION)?;
+
+        let mut metadata = Map::new();
+        if pull_model || model_info {
+            let info = match client.show_model(&config.model)? {
+                None if pull_model => {
+                    client.pull_model(&config.model)?;
+                    client.show_model(&config.model)?
+                }
+                info => info,
+            };
+            if let Some(info) = info.filter(|_| model_info) {
+                metadata.insert(
+                    "model_info".to_string(),
+                    serde_json::to_value(info).unwrap_or_default(),
+                );
+            }
+        }
+        if list_models {
+            let names = client
+                .list_models()?
+                .into_iter()
+                .map(|model| Value::String(model.name))
+                .collect();
+            metadata.insert("local_models".to_string(), Value::Array(names));
+        }
+        Ok(metadata)
+    }
 }
 
 impl Guest for OllamaComponent {
@@ -205,9 +272,12 @@ impl Guest for OllamaComponent {
     fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        let client = OllamaApi::new(config.model.clone());
+        let client = OllamaApi::new(
+            config.model.clone(),
+            provider_option(&config, BASE_URL_OPTION),
+        );
         match messages_to_request(messages, config.clone(), None) {
-            Ok(request) => Self::request(&client, request),
+            Ok(request) => Self::request(&client, request, &config),
             Err(err) => ChatEvent::Error(err),
         }
     }
@@ -219,10 +289,13 @@ impl Guest for OllamaComponent {
     ) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        let client = OllamaApi::new(config.model.clone());
+        let client = OllamaApi::new(
+            config.model.clone(),
+            provider_option(&config, BASE_URL_OPTION),
+        );
 
         match messages_to_request(messages, config.clone(), Some(tool_results)) {
-            Ok(request) => Self::request(&client, request),
+            Ok(request) => Self::request(&client, request, &config),
             Err(err) => ChatEvent::Error(err),
         }
     }
@@ -236,9 +309,12 @@ impl ExtendedGuest for OllamaComponent {
     fn unwrapped_stream(messages: Vec<Message>, config: Config) -> LlmChatStream<OllamaChatStream> {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        let client = OllamaApi::new(config.model.clone());
+        let client = OllamaApi::new(
+            config.model.clone(),
+            provider_option(&config, BASE_URL_OPTION),
+        );
         match messages_to_request(messages, config.clone(), None) {
-            Ok(request) => Self::streaming_request(&client, request),
+            Ok(request) => Self::streaming_request(&client, request, &config),
             Err(err) => OllamaChatStream::failed(err),
         }
     }
@@ -255,6 +331,7 @@ impl ExtendedGuest for OllamaComponent {
                  Do not include the part of the response that was already seen."
                     .to_string(),
             )],
+            cache_control: None,
         });
 
         extended_messages.push(Message {
@@ -263,6 +340,7 @@ impl ExtendedGuest for OllamaComponent {
             content: vec![ContentPart::Text(
                 "Here is the original question:".to_string(),
             )],
+            cache_control: None,
         });
 
         extended_messages.extend_from_slice(original_messages);
@@ -291,6 +369,7 @@ impl ExtendedGuest for OllamaComponent {
             .into_iter()
             .chain(partial_result_as_content)
             .collect(),
+            cache_control: None,
         });
 
         extended_messages
@@ -301,6 +380,8 @@ impl ExtendedGuest for OllamaComponent {
     }
 }
 
-type DurableOllamaComponent = DurableLLM<OllamaComponent>;
+#[cfg(not(feature = "embedded"))]
+type DurableOllamaComponent = golem_llm::durability::DurableLLM<OllamaComponent>;
 
+#[cfg(not(feature = "embedded"))]
 golem_llm::export_llm!(DurableOllamaComponent with_types_in golem_llm);
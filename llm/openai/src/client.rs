@@ -1,30 +1,57 @@
-use golem_llm::error::{error_code_from_status, from_event_source_error, from_reqwest_error};
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
 use golem_llm::event_source::EventSource;
-use golem_llm::golem::llm::llm::Error;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
 use log::trace;
-use reqwest::header::HeaderValue;
-use reqwest::{Client, Method, Response};
+use reqwest::{Client, Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 const BASE_URL: &str = "https://api.openai.com";
 
+/// How requests to the Responses API are authenticated
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    /// An API key or an OAuth token, sent in the `Authorization` header
+    Bearer(String),
+    /// An Azure OpenAI API key, sent in the `api-key` header
+    ApiKey(String),
+}
+
 /// The OpenAI API client for creating model responses.
 ///
 /// Based on https://platform.openai.com/docs/api-reference/responses/create
 pub struct ResponsesApi {
-    openai_api_key: String,
+    provider: &'static str,
+    url: String,
+    credentials: Credentials,
     client: Client,
 }
 
 impl ResponsesApi {
     pub fn new(openai_api_key: String) -> Self {
+        Self::with_url(
+            "openai",
+            format!("{BASE_URL}/v1/responses"),
+            Credentials::Bearer(openai_api_key),
+        )
+    }
+
+    /// Creates a client for another deployment of the Responses API, like Azure OpenAI. `url` is
+    /// the full URL of the create endpoint, including any query parameters.
+    pub fn with_url(provider: &'static str, url: String, credentials: Credentials) -> Self {
         let client = Client::builder()
             .build()
             .expect("Failed to initialize HTTP client");
         Self {
-            openai_api_key,
+            provider,
+            url,
+            credentials,
             client,
         }
     }
@@ -33,44 +60,181 @@ impl ResponsesApi {
         &self,
         request: CreateModelResponseRequest,
     ) -> Result<CreateModelResponseResponse, Error> {
-        trace!("Sending request to OpenAI API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/responses"))
-            .bearer_auth(&self.openai_api_key)
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        trace!("Sending request to OpenAI API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new(self.provider, "create-model-response", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
 
-        parse_response(response)
+        let response: Response = interceptor::send(
+            &self.client,
+            self.authenticate(OutgoingRequest::new(
+                self.provider,
+                "create-model-response",
+                Method::POST,
+                &self.url,
+            ))?
+            .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
     }
 
     pub fn stream_model_response(
         &self,
         request: CreateModelResponseRequest,
     ) -> Result<EventSource, Error> {
-        trace!("Sending request to OpenAI API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/v1/responses"))
-            .bearer_auth(&self.openai_api_key)
-            .header(
-                reqwest::header::ACCEPT,
-                HeaderValue::from_static("text/event-stream"),
-            )
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        trace!("Sending request to OpenAI API: {:?}", Redacted(&request));
+        let mut recorder = Recorder::new(self.provider, "stream-model-response", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            self.authenticate(OutgoingRequest::new(
+                self.provider,
+                "stream-model-response",
+                Method::POST,
+                &self.url,
+            ))?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
 
         trace!("Initializing SSE stream");
 
         EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
             .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
     }
+
+    fn authenticate(&self, request: OutgoingRequest) -> Result<OutgoingRequest, Error> {
+        match &self.credentials {
+            Credentials::Bearer(token) => request.bearer_auth(token),
+            Credentials::ApiKey(api_key) => request.header("api-key", api_key),
+        }
+    }
 }
 
+/// The OpenAI Batch API client, running Responses API requests asynchronously.
+///
+/// Based on https://platform.openai.com/docs/api-reference/batch
+pub struct BatchesApi {
+    api_key: String,
+    client: Client,
+}
+
+impl BatchesApi {
+    pub fn new(openai_api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            api_key: openai_api_key,
+            client,
+        }
+    }
+
+    /// Uploads the requests of a batch, as a JSON Lines file of `BatchInputLine`s
+    pub fn upload_batch_file(&self, lines: &[BatchInputLine]) -> Result<FileObject, Error> {
+        trace!("Sending request to OpenAI API: {:?}", Redacted(&lines));
+        let recorder = Recorder::new("openai", "upload-batch-file", &lines);
+
+        let mut jsonl = String::new();
+        for line in lines {
+            let line = serde_json::to_string(line).map_err(|err| Error {
+                code: ErrorCode::InternalError,
+                message: format!("Failed to serialize request body: {err}"),
+                provider_error_json: None,
+            })?;
+            jsonl.push_str(&line);
+            jsonl.push('\n');
+        }
+
+        let mut request = OutgoingRequest::new(
+            "openai",
+            "upload-batch-file",
+            Method::POST,
+            format!("{BASE_URL}/v1/files"),
+        )
+        .bearer_auth(&self.api_key)?
+        .header(
+            reqwest::header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={MULTIPART_BOUNDARY}"),
+        )?;
+        request.body = format!(
+            "--{MULTIPART_BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"purpose\"\r\n\r\nbatch\r\n\
+             --{MULTIPART_BOUNDARY}\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"batch.jsonl\"\r\n\
+             Content-Type: application/jsonl\r\n\r\n{jsonl}\r\n\
+             --{MULTIPART_BOUNDARY}--\r\n"
+        );
+        let response: Response = interceptor::send(&self.client, request)?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn create_batch(&self, request: CreateBatchRequest) -> Result<Batch, Error> {
+        trace!("Sending request to OpenAI API: {:?}", Redacted(&request));
+        let recorder = Recorder::new("openai", "create-batch", &request);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openai",
+                "create-batch",
+                Method::POST,
+                format!("{BASE_URL}/v1/batches"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    pub fn retrieve_batch(&self, batch_id: &str) -> Result<Batch, Error> {
+        trace!("Sending request to OpenAI API: retrieve batch {batch_id}");
+        let recorder = Recorder::new("openai", "retrieve-batch", &batch_id);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openai",
+                "retrieve-batch",
+                Method::GET,
+                format!("{BASE_URL}/v1/batches/{batch_id}"),
+            )
+            .bearer_auth(&self.api_key)?,
+        )?;
+
+        parse_response(response, recorder)
+    }
+
+    /// Gets the output or error file of a batch, one `BatchOutputLine` for each request
+    pub fn retrieve_batch_file(&self, file_id: &str) -> Result<Vec<BatchOutputLine>, Error> {
+        trace!("Sending request to OpenAI API: retrieve file content {file_id}");
+        let recorder = Recorder::new("openai", "retrieve-file-content", &file_id);
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openai",
+                "retrieve-file-content",
+                Method::GET,
+                format!("{BASE_URL}/v1/files/{file_id}/content"),
+            )
+            .bearer_auth(&self.api_key)?,
+        )?;
+
+        parse_jsonl_response(response, recorder)
+    }
+}
+
+const MULTIPART_BOUNDARY: &str = "----golem-llm-form-boundary-Qm7xTzLp2RvW";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateModelResponseRequest {
     pub input: Input,
@@ -82,12 +246,64 @@ pub struct CreateModelResponseRequest {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<Tool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tool_choice: Option<String>,
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+    /// Additional output to include in the response, like `message.output_text.logprobs`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<String>,
+    /// Whether and how detailed a summary of the reasoning is returned: `auto`, `concise` or
+    /// `detailed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Either one of the `auto`, `none` and `required` modes, or a specific function
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ToolChoice {
+    Mode(String),
+    Function {
+        #[serde(rename = "type")]
+        kind: String,
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextConfig {
+    pub format: TextFormat,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TextFormat {
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "json_object")]
+    JsonObject,
+    #[serde(rename = "json_schema")]
+    JsonSchema {
+        name: String,
+        schema: serde_json::Value,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,17 +336,66 @@ pub enum OutputItem {
         id: String,
         status: Status,
     },
+    /// The reasoning of o-series models, of which only a summary is returned
+    #[serde(rename = "reasoning")]
+    Reasoning {
+        id: String,
+        #[serde(default)]
+        summary: Vec<ReasoningSummary>,
+    },
+    /// A call of a built-in tool run by OpenAI, like a `web_search_call` or `file_search_call`
+    #[serde(untagged)]
+    BuiltInToolCall(serde_json::Value),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningSummary {
+    pub text: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum OutputMessageContent {
     #[serde(rename = "output_text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(default)]
+        logprobs: Vec<Logprob>,
+        /// The sources cited by the text, like the results of a web or file search
+        #[serde(default)]
+        annotations: Vec<Annotation>,
+    },
     #[serde(rename = "refusal")]
     Refusal { refusal: String },
 }
 
+/// An annotation of the output text, like a `url_citation` or `file_citation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(rename = "type")]
+    pub typ: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprob {
+    pub token: String,
+    pub logprob: f32,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorObject {
     pub code: String,
@@ -233,6 +498,9 @@ pub enum Tool {
         parameters: Option<serde_json::Value>,
         strict: bool,
     },
+    /// A built-in tool run by OpenAI, like `{"type": "web_search_preview"}`, sent as is
+    #[serde(untagged)]
+    BuiltIn(serde_json::Value),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -260,6 +528,23 @@ pub struct ResponseOutputTextDelta {
     pub delta: String,
     pub item_id: String,
     pub output_index: u32,
+    #[serde(default)]
+    pub logprobs: Vec<Logprob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseOutputTextAnnotationAdded {
+    pub annotation: Annotation,
+    pub item_id: String,
+    pub output_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseReasoningSummaryTextDelta {
+    pub delta: String,
+    pub item_id: String,
+    pub output_index: u32,
+    pub summary_index: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -268,27 +553,157 @@ pub struct ResponseOutputItemDone {
     pub output_index: u32,
 }
 
-fn parse_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Error> {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchInputLine {
+    pub custom_id: String,
+    pub method: String,
+    pub url: String,
+    pub body: CreateModelResponseRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileObject {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateBatchRequest {
+    pub input_file_id: String,
+    pub endpoint: String,
+    pub completion_window: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    pub status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_counts: Option<BatchRequestCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_file_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_file_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BatchStatus {
+    #[serde(rename = "validating")]
+    Validating,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "in_progress")]
+    InProgress,
+    #[serde(rename = "finalizing")]
+    Finalizing,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "expired")]
+    Expired,
+    #[serde(rename = "cancelling")]
+    Cancelling,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRequestCounts {
+    pub total: u32,
+    pub completed: u32,
+    pub failed: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOutputLine {
+    pub custom_id: String,
+    pub response: Option<BatchOutputResponse>,
+    pub error: Option<ErrorObject>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOutputResponse {
+    pub status_code: u16,
+    pub body: serde_json::Value,
+}
+
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
+}
+
+/// Parses a JSON Lines response, like the output file of a batch
+fn parse_jsonl_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<Vec<T>, Error> {
     let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
     if status.is_success() {
-        let body = response
-            .json::<T>()
-            .map_err(|err| from_reqwest_error("Failed to decode response body", err))?;
+        raw_body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| parse_response_body(status, line.to_string()))
+            .collect()
+    } else {
+        parse_response_body(status, raw_body)
+    }
+}
 
-        trace!("Received response from OpenAI API: {body:?}");
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        let body = serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })?;
+
+        trace!("Received response from OpenAI API: {:?}", Redacted(&body));
 
         Ok(body)
     } else {
-        let body = response
-            .text()
-            .map_err(|err| from_reqwest_error("Failed to receive error response body", err))?;
-
-        trace!("Received {status} response from OpenAI API: {body:?}");
+        trace!(
+            "Received {status} response from OpenAI API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
 
-        Err(Error {
-            code: error_code_from_status(status),
-            message: format!("Request failed with {status}"),
-            provider_error_json: Some(body),
-        })
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, CreateModelResponseResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |_, message| {
+                format!(
+                    r#"{{"error": {{"message": "{message}", "type": "invalid_request_error", "param": null, "code": null}}}}"#
+                )
+            },
+            |status, body| {
+                parse_response_body::<CreateModelResponseResponse>(status, body).map(|_| ())
+            },
+        );
     }
 }
@@ -1,35 +1,80 @@
 use crate::client::{
+    Annotation, Batch, BatchInputLine, BatchOutputLine, BatchStatus as ClientBatchStatus,
     CreateModelResponseRequest, CreateModelResponseResponse, Detail, InnerInput, InnerInputItem,
-    Input, InputItem, OutputItem, OutputMessageContent, Tool,
+    Input, InputItem, Logprob, OutputItem, OutputMessageContent, ReasoningConfig, TextConfig,
+    TextFormat, Tool, ToolChoice,
 };
 use base64::{engine::general_purpose, Engine as _};
-use golem_llm::error::error_code_from_status;
+use golem_llm::config::{parse_response_schema, provider_option};
+use golem_llm::error::{
+    classify_error, error_code_from_status, from_error_response, unsupported, ProviderError,
+};
+use golem_llm::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult, BatchStatus};
 use golem_llm::golem::llm::llm::{
-    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, ImageDetail,
-    ImageReference, Message, ResponseMetadata, Role, ToolCall, ToolDefinition, ToolResult, Usage,
+    BuiltInTool, BuiltInToolResult, ChatEvent, Citation, CitedText, CompleteResponse, Config,
+    ContentPart, Error, ErrorCode, ImageDetail, ImageReference, ImageSource, Message,
+    ReasoningEffort, ResponseFormat, ResponseMetadata, Role, TokenLogprob, ToolCall,
+    ToolChoice as LlmToolChoice, ToolDefinition, ToolResult, TopLogprob, Usage,
 };
+use golem_llm::image::{ImageFormat, ImageProcessor};
 use reqwest::StatusCode;
+use serde_json::{json, Map, Value};
 use std::collections::HashMap;
 use std::str::FromStr;
 
+/// Provider option enabling built-in tools of the Responses API, as a JSON array of tool
+/// definitions like `[{"type": "web_search_preview"}]`
+pub const BUILTIN_TOOLS_OPTION: &str = "builtin_tools";
+
+const IMAGE_FORMATS: &[ImageFormat] = &[
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Webp,
+    ImageFormat::Gif,
+];
+
+/// The size limit of images sent to the Responses API
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// The longest edge of images sent to the Responses API, larger images are downscaled by the API
+/// anyway
+const MAX_IMAGE_DIMENSION: u32 = 2048;
+
+/// Provider option listing the vector stores searched by the file search tool, separated by commas
+pub const VECTOR_STORE_IDS_OPTION: &str = "vector_store_ids";
+
 pub fn create_request(
     items: Vec<InputItem>,
     config: Config,
-    tools: Vec<Tool>,
-) -> CreateModelResponseRequest {
+    mut tools: Vec<Tool>,
+) -> Result<CreateModelResponseRequest, Error> {
+    let text = config
+        .response_format
+        .map(convert_response_format)
+        .transpose()?;
     let options = config
         .provider_options
         .into_iter()
         .map(|kv| (kv.key, kv.value))
         .collect::<HashMap<_, _>>();
+    if let Some(builtin_tools) = options.get(BUILTIN_TOOLS_OPTION) {
+        let builtin_tools =
+            serde_json::from_str::<Vec<Value>>(builtin_tools).map_err(|err| Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("Invalid {BUILTIN_TOOLS_OPTION} provider option: {err}"),
+                provider_error_json: None,
+            })?;
+        tools.extend(builtin_tools.into_iter().map(Tool::BuiltIn));
+    }
 
-    CreateModelResponseRequest {
+    Ok(CreateModelResponseRequest {
         input: Input::List(items),
         model: config.model,
         temperature: config.temperature,
         max_output_tokens: config.max_tokens,
         tools,
-        tool_choice: config.tool_choice,
+        tool_choice: config.tool_choice.map(convert_tool_choice),
+        parallel_tool_calls: config.parallel_tool_calls,
         stream: false,
         top_p: options
             .get("top_p")
@@ -37,16 +82,59 @@ pub fn create_request(
         user: options
             .get("user")
             .and_then(|user_s| user_s.parse::<String>().ok()),
+        text,
+        top_logprobs: config.logprobs,
+        include: if config.logprobs.is_some() {
+            vec!["message.output_text.logprobs".to_string()]
+        } else {
+            vec![]
+        },
+        reasoning: config.reasoning_effort.map(|effort| ReasoningConfig {
+            effort: Some(convert_reasoning_effort(effort)),
+            summary: Some("auto".to_string()),
+        }),
+    })
+}
+
+fn convert_reasoning_effort(effort: ReasoningEffort) -> String {
+    match effort {
+        ReasoningEffort::Low => "low".to_string(),
+        ReasoningEffort::Medium => "medium".to_string(),
+        ReasoningEffort::High => "high".to_string(),
+    }
+}
+
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => ToolChoice::Mode("auto".to_string()),
+        LlmToolChoice::None => ToolChoice::Mode("none".to_string()),
+        LlmToolChoice::Required => ToolChoice::Mode("required".to_string()),
+        LlmToolChoice::Specific(name) => ToolChoice::Function {
+            kind: "function".to_string(),
+            name,
+        },
     }
 }
 
-pub fn messages_to_input_items(messages: Vec<Message>) -> Vec<InputItem> {
+fn convert_response_format(response_format: ResponseFormat) -> Result<TextConfig, Error> {
+    let format = match response_format {
+        ResponseFormat::Text => TextFormat::Text,
+        ResponseFormat::JsonObject => TextFormat::JsonObject,
+        ResponseFormat::JsonSchema(schema) => TextFormat::JsonSchema {
+            name: "response".to_string(),
+            schema: parse_response_schema(&schema)?,
+        },
+    };
+    Ok(TextConfig { format })
+}
+
+pub fn messages_to_input_items(messages: Vec<Message>) -> Result<Vec<InputItem>, Error> {
     let mut items = Vec::new();
     for message in messages {
         let role = to_openai_role_name(message.role).to_string();
         let mut input_items = Vec::new();
         for content_part in message.content {
-            input_items.push(content_part_to_inner_input_item(content_part));
+            input_items.extend(content_part_to_inner_input_item(content_part)?);
         }
 
         items.push(InputItem::InputMessage {
@@ -54,7 +142,7 @@ pub fn messages_to_input_items(messages: Vec<Message>) -> Vec<InputItem> {
             content: InnerInput::List(input_items),
         });
     }
-    items
+    Ok(items)
 }
 
 pub fn tool_results_to_input_items(tool_results: Vec<(ToolCall, ToolResult)>) -> Vec<InputItem> {
@@ -85,9 +173,13 @@ pub fn tool_results_to_input_items(tool_results: Vec<(ToolCall, ToolResult)>) ->
     items
 }
 
-pub fn tool_defs_to_tools(tool_definitions: &[ToolDefinition]) -> Result<Vec<Tool>, Error> {
+pub fn tool_defs_to_tools(config: &Config) -> Result<Vec<Tool>, Error> {
     let mut tools = Vec::new();
-    for tool_def in tool_definitions {
+    for tool_def in &config.tools {
+        if let Some(built_in) = tool_def.built_in {
+            tools.push(built_in_tool(built_in, config)?);
+            continue;
+        }
         match serde_json::from_str(&tool_def.parameters_schema) {
             Ok(value) => {
                 let tool = Tool::Function {
@@ -113,6 +205,32 @@ pub fn tool_defs_to_tools(tool_definitions: &[ToolDefinition]) -> Result<Vec<Too
     Ok(tools)
 }
 
+fn built_in_tool(built_in: BuiltInTool, config: &Config) -> Result<Tool, Error> {
+    let definition = match built_in {
+        BuiltInTool::WebSearch => json!({ "type": "web_search_preview" }),
+        BuiltInTool::CodeInterpreter => {
+            json!({ "type": "code_interpreter", "container": { "type": "auto" } })
+        }
+        BuiltInTool::FileSearch => {
+            let vector_store_ids =
+                provider_option(config, VECTOR_STORE_IDS_OPTION).ok_or_else(|| Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!(
+                        "Missing {VECTOR_STORE_IDS_OPTION} provider option for file search"
+                    ),
+                    provider_error_json: None,
+                })?;
+            let vector_store_ids = vector_store_ids
+                .split(',')
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect::<Vec<_>>();
+            json!({ "type": "file_search", "vector_store_ids": vector_store_ids })
+        }
+    };
+    Ok(Tool::BuiltIn(definition))
+}
+
 pub fn to_openai_role_name(role: Role) -> &'static str {
     match role {
         Role::User => "user",
@@ -122,9 +240,17 @@ pub fn to_openai_role_name(role: Role) -> &'static str {
     }
 }
 
-pub fn content_part_to_inner_input_item(content_part: ContentPart) -> InnerInputItem {
-    match content_part {
+pub fn content_part_to_inner_input_item(
+    content_part: ContentPart,
+) -> Result<Option<InnerInputItem>, Error> {
+    Ok(Some(match content_part {
         ContentPart::Text(msg) => InnerInputItem::TextInput { text: msg },
+        // Annotations can not be sent back, so cited text is sent as plain text
+        ContentPart::CitedText(cited_text) => InnerInputItem::TextInput {
+            text: cited_text.text,
+        },
+        // Built-in tools are run again by OpenAI when needed
+        ContentPart::BuiltInToolResult(_) => return Ok(None),
         ContentPart::Image(image_reference) => match image_reference {
             ImageReference::Url(image_url) => InnerInputItem::ImageInput {
                 image_url: image_url.url,
@@ -136,9 +262,11 @@ pub fn content_part_to_inner_input_item(content_part: ContentPart) -> InnerInput
                 },
             },
             ImageReference::Inline(image_source) => {
-                let base64_data = general_purpose::STANDARD.encode(&image_source.data);
-                let mime_type = &image_source.mime_type; // This is already a string
-                let data_url = format!("data:{};base64,{}", mime_type, base64_data);
+                let image =
+                    ImageProcessor::from_env(IMAGE_FORMATS, MAX_IMAGE_BYTES, MAX_IMAGE_DIMENSION)
+                        .process(&image_source)?;
+                let base64_data = general_purpose::STANDARD.encode(&image.data);
+                let data_url = format!("data:{};base64,{}", image.format.mime_type(), base64_data);
 
                 InnerInputItem::ImageInput {
                     image_url: data_url,
@@ -151,6 +279,92 @@ pub fn content_part_to_inner_input_item(content_part: ContentPart) -> InnerInput
                 }
             }
         },
+        // The Responses API has no audio input, audio models are only available through Chat
+        // Completions
+        ContentPart::Audio(_) => return Err(unsupported("audio input with the Responses API")),
+    }))
+}
+
+/// The endpoint the requests of batches are sent to
+pub const BATCH_ENDPOINT: &str = "/v1/responses";
+
+pub fn batch_requests_to_input_lines(
+    requests: Vec<BatchRequest>,
+) -> Result<Vec<BatchInputLine>, Error> {
+    requests
+        .into_iter()
+        .map(|request| {
+            let items = messages_to_input_items(request.messages)?;
+            let tools = tool_defs_to_tools(&request.config)?;
+            Ok(BatchInputLine {
+                custom_id: request.custom_id,
+                method: "POST".to_string(),
+                url: BATCH_ENDPOINT.to_string(),
+                body: create_request(items, request.config, tools)?,
+            })
+        })
+        .collect()
+}
+
+pub fn batch_to_info(batch: Batch) -> BatchInfo {
+    let (total, succeeded, failed) = batch
+        .request_counts
+        .map(|counts| (counts.total, counts.completed, counts.failed))
+        .unwrap_or_default();
+    BatchInfo {
+        id: batch.id,
+        status: match batch.status {
+            ClientBatchStatus::Validating
+            | ClientBatchStatus::InProgress
+            | ClientBatchStatus::Finalizing
+            | ClientBatchStatus::Cancelling => BatchStatus::InProgress,
+            ClientBatchStatus::Completed => BatchStatus::Completed,
+            ClientBatchStatus::Cancelled => BatchStatus::Cancelled,
+            ClientBatchStatus::Expired => BatchStatus::Expired,
+            ClientBatchStatus::Failed => BatchStatus::Failed,
+        },
+        total,
+        succeeded,
+        failed,
+    }
+}
+
+pub fn batch_output_to_result(line: BatchOutputLine) -> BatchResult {
+    let event = match (line.response, line.error) {
+        (_, Some(error)) => ChatEvent::Error(Error {
+            code: parse_error_code(error.code),
+            message: error.message,
+            provider_error_json: None,
+        }),
+        (Some(response), None) => {
+            let status = StatusCode::from_u16(response.status_code)
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            if status.is_success() {
+                match serde_json::from_value::<CreateModelResponseResponse>(response.body) {
+                    Ok(response) => process_model_response(response),
+                    Err(err) => ChatEvent::Error(Error {
+                        code: ErrorCode::InternalError,
+                        message: format!("Failed to decode response body: {err}"),
+                        provider_error_json: None,
+                    }),
+                }
+            } else {
+                ChatEvent::Error(from_error_response(
+                    status,
+                    response.body.to_string(),
+                    ProviderError::from_json,
+                ))
+            }
+        }
+        (None, None) => ChatEvent::Error(Error {
+            code: ErrorCode::InternalError,
+            message: "The batch has no response for the request".to_string(),
+            provider_error_json: None,
+        }),
+    };
+    BatchResult {
+        custom_id: line.custom_id,
+        event,
     }
 }
 
@@ -161,7 +375,13 @@ pub fn parse_error_code(code: String) -> ErrorCode {
     {
         error_code_from_status(code)
     } else {
-        ErrorCode::InternalError
+        classify_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &ProviderError {
+                kind: Some(code),
+                message: None,
+            },
+        )
     }
 }
 
@@ -176,15 +396,22 @@ pub fn process_model_response(response: CreateModelResponseResponse) -> ChatEven
         let mut contents = Vec::new();
         let mut tool_calls = Vec::new();
 
-        let metadata = create_response_metadata(&response);
+        let mut metadata = create_response_metadata(&response);
+        let mut logprobs = Vec::new();
+        let mut reasoning = Vec::new();
 
         for output_item in response.output {
             match output_item {
                 OutputItem::Message { content, .. } => {
                     for content in content {
                         match content {
-                            OutputMessageContent::Text { text, .. } => {
-                                contents.push(ContentPart::Text(text));
+                            OutputMessageContent::Text {
+                                text,
+                                logprobs: text_logprobs,
+                                annotations,
+                            } => {
+                                contents.push(text_to_content_part(text, annotations));
+                                logprobs.extend(convert_logprobs(text_logprobs));
                             }
                             OutputMessageContent::Refusal { refusal, .. } => {
                                 contents.push(ContentPart::Text(format!("Refusal: {refusal}")));
@@ -205,9 +432,20 @@ pub fn process_model_response(response: CreateModelResponseResponse) -> ChatEven
                     };
                     tool_calls.push(tool_call);
                 }
+                OutputItem::Reasoning { summary, .. } => {
+                    reasoning.extend(summary.into_iter().map(|summary| summary.text));
+                }
+                // Already run by OpenAI, and also listed in the provider metadata
+                OutputItem::BuiltInToolCall(call) => {
+                    contents.extend(built_in_tool_call_to_content_part(call));
+                }
             }
         }
 
+        if !logprobs.is_empty() {
+            metadata.logprobs = Some(logprobs);
+        }
+
         if contents.is_empty() {
             ChatEvent::ToolRequest(tool_calls)
         } else {
@@ -215,12 +453,119 @@ pub fn process_model_response(response: CreateModelResponseResponse) -> ChatEven
                 id: response.id,
                 content: contents,
                 tool_calls,
+                reasoning: (!reasoning.is_empty()).then(|| reasoning.join("\n\n")),
                 metadata,
             })
         }
     }
 }
 
+/// Text annotated with citations becomes cited text
+fn text_to_content_part(text: String, annotations: Vec<Annotation>) -> ContentPart {
+    if annotations.is_empty() {
+        ContentPart::Text(text)
+    } else {
+        ContentPart::CitedText(CitedText {
+            text,
+            citations: annotations.into_iter().map(convert_annotation).collect(),
+        })
+    }
+}
+
+pub fn convert_annotation(annotation: Annotation) -> Citation {
+    Citation {
+        url: annotation.url,
+        title: annotation.title.or(annotation.filename),
+        cited_text: None,
+    }
+}
+
+/// The call of a built-in tool of the LLM API, like a `web_search_call`, as the result of the
+/// tool, and the image of an `image_generation_call`. Calls of other tools, given with the
+/// `builtin_tools` provider option, are only listed in the provider metadata.
+pub fn built_in_tool_call_to_content_part(call: Value) -> Option<ContentPart> {
+    let tool = match call.get("type").and_then(Value::as_str) {
+        Some("image_generation_call") => return generated_image(&call),
+        Some("web_search_call") => BuiltInTool::WebSearch,
+        Some("code_interpreter_call") => BuiltInTool::CodeInterpreter,
+        Some("file_search_call") => BuiltInTool::FileSearch,
+        _ => return None,
+    };
+    Some(ContentPart::BuiltInToolResult(BuiltInToolResult {
+        tool,
+        output_json: call.to_string(),
+    }))
+}
+
+/// The base64 encoded image generated by the `image_generation` tool
+fn generated_image(call: &Value) -> Option<ContentPart> {
+    let data = general_purpose::STANDARD
+        .decode(call.get("result")?.as_str()?)
+        .ok()?;
+    let format = call
+        .get("output_format")
+        .and_then(Value::as_str)
+        .unwrap_or("png");
+    Some(ContentPart::Image(ImageReference::Inline(ImageSource {
+        data,
+        mime_type: format!("image/{format}"),
+        detail: None,
+    })))
+}
+
+pub fn convert_logprobs(logprobs: Vec<Logprob>) -> Vec<TokenLogprob> {
+    logprobs
+        .into_iter()
+        .map(|logprob| TokenLogprob {
+            token: logprob.token,
+            logprob: logprob.logprob,
+            top_logprobs: logprob
+                .top_logprobs
+                .into_iter()
+                .map(|top| TopLogprob {
+                    token: top.token,
+                    logprob: top.logprob,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The provider metadata of a response: the `metadata` of the request, and the calls of built-in
+/// tools made by the model under `builtin_tool_calls`
+fn provider_metadata(response: &CreateModelResponseResponse) -> Option<Value> {
+    let builtin_tool_calls = response
+        .output
+        .iter()
+        .filter_map(|item| match item {
+            OutputItem::BuiltInToolCall(call) => {
+                let mut call = call.clone();
+                // Generated images are returned in the content, and not repeated here
+                if let Value::Object(call) = &mut call {
+                    if call.get("type").and_then(Value::as_str) == Some("image_generation_call") {
+                        call.remove("result");
+                    }
+                }
+                Some(call)
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if builtin_tool_calls.is_empty() {
+        response.metadata.clone()
+    } else {
+        let mut metadata = match &response.metadata {
+            Some(Value::Object(metadata)) => metadata.clone(),
+            _ => Map::new(),
+        };
+        metadata.insert(
+            "builtin_tool_calls".to_string(),
+            Value::Array(builtin_tool_calls),
+        );
+        Some(Value::Object(metadata))
+    }
+}
+
 pub fn create_response_metadata(response: &CreateModelResponseResponse) -> ResponseMetadata {
     ResponseMetadata {
         finish_reason: None,
@@ -228,9 +573,445 @@ pub fn create_response_metadata(response: &CreateModelResponseResponse) -> Respo
             input_tokens: Some(usage.input_tokens),
             output_tokens: Some(usage.output_tokens),
             total_tokens: Some(usage.total_tokens),
+            cached_tokens: Some(usage.input_tokens_details.cached_tokens),
         }),
         provider_id: Some(response.id.clone()),
         timestamp: Some(response.created_at.to_string()),
-        provider_metadata_json: response.metadata.as_ref().map(|m| m.to_string()),
+        provider_metadata_json: provider_metadata(response).map(|m| m.to_string()),
+        logprobs: None,
+        cost: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{Batch, BatchOutputLine, CreateModelResponseResponse};
+    use crate::conversions::{
+        batch_output_to_result, batch_to_info, convert_response_format, convert_tool_choice,
+        create_request, process_model_response, tool_defs_to_tools, BUILTIN_TOOLS_OPTION,
+        VECTOR_STORE_IDS_OPTION,
+    };
+    use golem_llm::golem::llm::batch::BatchStatus;
+    use golem_llm::golem::llm::llm::{
+        BuiltInTool, BuiltInToolResult, ChatEvent, Citation, CitedText, Config, ContentPart,
+        ErrorCode, ImageReference, ImageSource, Kv, ResponseFormat, TokenLogprob, ToolChoice,
+        ToolDefinition, TopLogprob,
+    };
+    use serde_json::{json, Value};
+
+    #[test]
+    fn converts_the_response_format() {
+        let text = convert_response_format(ResponseFormat::JsonSchema(
+            r#"{"type": "object"}"#.to_string(),
+        ))
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(text).unwrap(),
+            json!({
+                "format": {
+                    "type": "json_schema",
+                    "name": "response",
+                    "schema": { "type": "object" }
+                }
+            })
+        );
+        assert!(convert_response_format(ResponseFormat::JsonSchema("{".to_string())).is_err());
+    }
+
+    #[test]
+    fn converts_the_tool_choice() {
+        assert_eq!(
+            serde_json::to_value(convert_tool_choice(ToolChoice::Required)).unwrap(),
+            json!("required")
+        );
+        assert_eq!(
+            serde_json::to_value(convert_tool_choice(ToolChoice::Specific(
+                "get_weather".to_string()
+            )))
+            .unwrap(),
+            json!({ "type": "function", "name": "get_weather" })
+        );
+    }
+
+    #[test]
+    fn returns_the_logprobs_of_the_output_text() {
+        let response: CreateModelResponseResponse = serde_json::from_value(json!({
+            "id": "resp_1",
+            "created_at": 1700000000,
+            "error": null,
+            "incomplete_details": null,
+            "status": "completed",
+            "usage": null,
+            "metadata": null,
+            "output": [{
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "status": "completed",
+                "content": [{
+                    "type": "output_text",
+                    "text": "Yes",
+                    "annotations": [],
+                    "logprobs": [{
+                        "token": "Yes",
+                        "logprob": -0.25,
+                        "bytes": [89, 101, 115],
+                        "top_logprobs": [{ "token": "No", "logprob": -1.5, "bytes": [78, 111] }]
+                    }]
+                }]
+            }]
+        }))
+        .unwrap();
+        match process_model_response(response) {
+            ChatEvent::Message(response) => assert_eq!(
+                response.metadata.logprobs,
+                Some(vec![TokenLogprob {
+                    token: "Yes".to_string(),
+                    logprob: -0.25,
+                    top_logprobs: vec![TopLogprob {
+                        token: "No".to_string(),
+                        logprob: -1.5,
+                    }],
+                }])
+            ),
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn separates_the_reasoning_summary_from_the_answer() {
+        let response: CreateModelResponseResponse = serde_json::from_value(json!({
+            "id": "resp_1",
+            "created_at": 1700000000,
+            "error": null,
+            "incomplete_details": null,
+            "status": "completed",
+            "usage": null,
+            "metadata": null,
+            "output": [
+                {
+                    "type": "reasoning",
+                    "id": "rs_1",
+                    "summary": [
+                        { "type": "summary_text", "text": "The user asks for a sum." },
+                        { "type": "summary_text", "text": "It is 4." }
+                    ]
+                },
+                {
+                    "type": "message",
+                    "id": "msg_1",
+                    "role": "assistant",
+                    "status": "completed",
+                    "content": [{ "type": "output_text", "text": "4", "annotations": [] }]
+                }
+            ]
+        }))
+        .unwrap();
+        match process_model_response(response) {
+            ChatEvent::Message(response) => {
+                assert_eq!(response.content, vec![ContentPart::Text("4".to_string())]);
+                assert_eq!(
+                    response.reasoning.as_deref(),
+                    Some("The user asks for a sum.\n\nIt is 4.")
+                );
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn runs_builtin_tools() {
+        let config = Config {
+            model: "gpt-4o".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![Kv {
+                key: BUILTIN_TOOLS_OPTION.to_string(),
+                value: r#"[{"type": "web_search_preview"}]"#.to_string(),
+            }],
+        };
+        let request = create_request(vec![], config, vec![]).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request.tools).unwrap(),
+            json!([{ "type": "web_search_preview" }])
+        );
+
+        let response: CreateModelResponseResponse = serde_json::from_value(json!({
+            "id": "resp_1",
+            "created_at": 1700000000,
+            "error": null,
+            "incomplete_details": null,
+            "status": "completed",
+            "usage": null,
+            "metadata": {},
+            "output": [
+                {
+                    "type": "web_search_call",
+                    "id": "ws_1",
+                    "status": "completed"
+                },
+                {
+                    "type": "message",
+                    "id": "msg_1",
+                    "role": "assistant",
+                    "status": "completed",
+                    "content": [{ "type": "output_text", "text": "Sunny", "annotations": [] }]
+                }
+            ]
+        }))
+        .unwrap();
+        match process_model_response(response) {
+            ChatEvent::Message(response) => {
+                assert_eq!(
+                    response.content,
+                    vec![
+                        ContentPart::BuiltInToolResult(BuiltInToolResult {
+                            tool: BuiltInTool::WebSearch,
+                            output_json: json!({
+                                "type": "web_search_call",
+                                "id": "ws_1",
+                                "status": "completed"
+                            })
+                            .to_string(),
+                        }),
+                        ContentPart::Text("Sunny".to_string())
+                    ]
+                );
+                let metadata: Value =
+                    serde_json::from_str(&response.metadata.provider_metadata_json.unwrap())
+                        .unwrap();
+                assert_eq!(
+                    metadata,
+                    json!({
+                        "builtin_tool_calls": [
+                            { "type": "web_search_call", "id": "ws_1", "status": "completed" }
+                        ]
+                    })
+                );
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn requests_built_in_tools_and_cites_their_sources() {
+        let built_in = |tool| ToolDefinition {
+            name: String::new(),
+            description: None,
+            parameters_schema: String::new(),
+            built_in: Some(tool),
+        };
+        let config = Config {
+            model: "gpt-4o".to_string(),
+            temperature: None,
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![
+                built_in(BuiltInTool::CodeInterpreter),
+                built_in(BuiltInTool::FileSearch),
+            ],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![Kv {
+                key: VECTOR_STORE_IDS_OPTION.to_string(),
+                value: "vs_1, vs_2".to_string(),
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(tool_defs_to_tools(&config).unwrap()).unwrap(),
+            json!([
+                { "type": "code_interpreter", "container": { "type": "auto" } },
+                { "type": "file_search", "vector_store_ids": ["vs_1", "vs_2"] }
+            ])
+        );
+
+        let response: CreateModelResponseResponse = serde_json::from_value(json!({
+            "id": "resp_1",
+            "created_at": 1700000000,
+            "error": null,
+            "incomplete_details": null,
+            "status": "completed",
+            "usage": null,
+            "metadata": {},
+            "output": [{
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "status": "completed",
+                "content": [{
+                    "type": "output_text",
+                    "text": "Golem runs durable agents",
+                    "annotations": [{
+                        "type": "url_citation",
+                        "start_index": 0,
+                        "end_index": 25,
+                        "url": "https://golem.cloud",
+                        "title": "Golem Cloud"
+                    }]
+                }]
+            }]
+        }))
+        .unwrap();
+        match process_model_response(response) {
+            ChatEvent::Message(response) => assert_eq!(
+                response.content,
+                vec![ContentPart::CitedText(CitedText {
+                    text: "Golem runs durable agents".to_string(),
+                    citations: vec![Citation {
+                        url: Some("https://golem.cloud".to_string()),
+                        title: Some("Golem Cloud".to_string()),
+                        cited_text: None,
+                    }],
+                })]
+            ),
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn returns_generated_images() {
+        let response: CreateModelResponseResponse = serde_json::from_value(json!({
+            "id": "resp_1",
+            "created_at": 1700000000,
+            "error": null,
+            "incomplete_details": null,
+            "status": "completed",
+            "usage": null,
+            "metadata": {},
+            "output": [{
+                "type": "image_generation_call",
+                "id": "ig_1",
+                "status": "completed",
+                "output_format": "webp",
+                "result": "UklGRg=="
+            }]
+        }))
+        .unwrap();
+        match process_model_response(response) {
+            ChatEvent::Message(response) => {
+                assert_eq!(
+                    response.content,
+                    vec![ContentPart::Image(ImageReference::Inline(ImageSource {
+                        data: b"RIFF".to_vec(),
+                        mime_type: "image/webp".to_string(),
+                        detail: None,
+                    }))]
+                );
+                let metadata: Value =
+                    serde_json::from_str(&response.metadata.provider_metadata_json.unwrap())
+                        .unwrap();
+                assert_eq!(
+                    metadata["builtin_tool_calls"][0],
+                    json!({
+                        "type": "image_generation_call",
+                        "id": "ig_1",
+                        "status": "completed",
+                        "output_format": "webp"
+                    })
+                );
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn converts_batches_and_their_results() {
+        let batch: Batch = serde_json::from_value(json!({
+            "id": "batch_abc123",
+            "object": "batch",
+            "endpoint": "/v1/responses",
+            "status": "finalizing",
+            "output_file_id": null,
+            "error_file_id": null,
+            "request_counts": { "total": 3, "completed": 2, "failed": 1 }
+        }))
+        .unwrap();
+        let info = batch_to_info(batch);
+        assert_eq!(info.status, BatchStatus::InProgress);
+        assert_eq!((info.total, info.succeeded, info.failed), (3, 2, 1));
+
+        let succeeded: BatchOutputLine = serde_json::from_value(json!({
+            "id": "batch_req_1",
+            "custom_id": "request-1",
+            "response": {
+                "status_code": 200,
+                "request_id": "req_1",
+                "body": {
+                    "id": "resp_1",
+                    "object": "response",
+                    "created_at": 1741476542,
+                    "status": "completed",
+                    "error": null,
+                    "incomplete_details": null,
+                    "model": "gpt-4o-mini",
+                    "output": [{
+                        "type": "message",
+                        "id": "msg_1",
+                        "status": "completed",
+                        "role": "assistant",
+                        "content": [{ "type": "output_text", "text": "Paris", "annotations": [] }]
+                    }],
+                    "usage": {
+                        "input_tokens": 14,
+                        "input_tokens_details": { "cached_tokens": 0 },
+                        "output_tokens": 2,
+                        "output_tokens_details": { "reasoning_tokens": 0 },
+                        "total_tokens": 16
+                    }
+                }
+            },
+            "error": null
+        }))
+        .unwrap();
+        let result = batch_output_to_result(succeeded);
+        assert_eq!(result.custom_id, "request-1");
+        match result.event {
+            ChatEvent::Message(response) => {
+                assert_eq!(
+                    response.content,
+                    vec![ContentPart::Text("Paris".to_string())]
+                );
+            }
+            other => panic!("Unexpected response: {other:?}"),
+        }
+
+        let failed: BatchOutputLine = serde_json::from_value(json!({
+            "id": "batch_req_2",
+            "custom_id": "request-2",
+            "response": {
+                "status_code": 400,
+                "request_id": "req_2",
+                "body": {
+                    "error": {
+                        "message": "Invalid model",
+                        "type": "invalid_request_error",
+                        "param": "model",
+                        "code": null
+                    }
+                }
+            },
+            "error": null
+        }))
+        .unwrap();
+        match batch_output_to_result(failed).event {
+            ChatEvent::Error(error) => assert_eq!(error.code, ErrorCode::InvalidRequest),
+            other => panic!("Unexpected response: {other:?}"),
+        }
     }
 }
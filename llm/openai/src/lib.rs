@@ -1,28 +1,33 @@
 use crate::client::{
-    CreateModelResponseResponse, InputItem, OutputItem, ResponseOutputItemDone,
-    ResponseOutputTextDelta, ResponsesApi,
+    BatchesApi, CreateBatchRequest, CreateModelResponseResponse, InputItem, OutputItem,
+    ResponseOutputItemDone, ResponseOutputTextAnnotationAdded, ResponseOutputTextDelta,
+    ResponseReasoningSummaryTextDelta, ResponsesApi,
 };
 use crate::conversions::{
-    create_request, create_response_metadata, messages_to_input_items, parse_error_code,
-    process_model_response, tool_defs_to_tools, tool_results_to_input_items,
+    batch_output_to_result, batch_requests_to_input_lines, batch_to_info,
+    built_in_tool_call_to_content_part, convert_annotation, convert_logprobs, create_request,
+    create_response_metadata, messages_to_input_items, parse_error_code, process_model_response,
+    tool_defs_to_tools, tool_results_to_input_items, BATCH_ENDPOINT,
 };
 use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
-use golem_llm::config::with_config_key;
-use golem_llm::durability::{DurableLLM, ExtendedGuest};
+use golem_llm::config::{provider_option, with_config_key, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
 use golem_llm::event_source::EventSource;
+use golem_llm::golem::llm::batch::{BatchInfo, BatchRequest, BatchResult};
 use golem_llm::golem::llm::llm::{
-    ChatEvent, ChatStream, Config, ContentPart, Error, ErrorCode, Guest, Message, StreamDelta,
-    StreamEvent, ToolCall, ToolResult,
+    ChatEvent, ChatStream, CitedText, Config, ContentPart, Error, ErrorCode, Guest, Message,
+    StreamDelta, StreamEvent, ToolCall, ToolResult,
 };
+use golem_llm::redaction::Redacted;
 use golem_llm::LOGGING_STATE;
 use golem_rust::wasm_rpc::Pollable;
 use log::trace;
 use std::cell::{Ref, RefCell, RefMut};
 
-mod client;
-mod conversions;
+pub mod client;
+pub mod conversions;
 
-struct OpenAIChatStream {
+pub struct OpenAIChatStream {
     stream: RefCell<Option<EventSource>>,
     failure: Option<Error>,
     finished: RefCell<bool>,
@@ -68,7 +73,7 @@ impl LlmChatStreamState for OpenAIChatStream {
     }
 
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
-        trace!("Received raw stream event: {raw}");
+        trace!("Received raw stream event: {}", Redacted(&raw));
         let json: serde_json::Value = serde_json::from_str(raw)
             .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
 
@@ -126,28 +131,68 @@ impl LlmChatStreamState for OpenAIChatStream {
                 Ok(Some(StreamEvent::Delta(StreamDelta {
                     content: Some(vec![ContentPart::Text(decoded.delta)]),
                     tool_calls: None,
+                    reasoning: None,
+                    logprobs: if decoded.logprobs.is_empty() {
+                        None
+                    } else {
+                        Some(convert_logprobs(decoded.logprobs))
+                    },
+                })))
+            }
+            // Annotations follow the text they belong to
+            Some("response.output_text.annotation.added") => {
+                let decoded = serde_json::from_value::<ResponseOutputTextAnnotationAdded>(json)
+                    .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+                Ok(Some(StreamEvent::Delta(StreamDelta {
+                    content: Some(vec![ContentPart::CitedText(CitedText {
+                        text: String::new(),
+                        citations: vec![convert_annotation(decoded.annotation)],
+                    })]),
+                    tool_calls: None,
+                    reasoning: None,
+                    logprobs: None,
+                })))
+            }
+            Some("response.reasoning_summary_text.delta") => {
+                let decoded = serde_json::from_value::<ResponseReasoningSummaryTextDelta>(json)
+                    .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+                Ok(Some(StreamEvent::Delta(StreamDelta {
+                    content: None,
+                    tool_calls: None,
+                    reasoning: Some(decoded.delta),
+                    logprobs: None,
                 })))
             }
             Some("response.output_item.done") => {
                 let decoded = serde_json::from_value::<ResponseOutputItemDone>(json)
                     .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
-                if let OutputItem::ToolCall {
-                    arguments,
-                    call_id,
-                    name,
-                    ..
-                } = decoded.item
-                {
-                    Ok(Some(StreamEvent::Delta(StreamDelta {
+                match decoded.item {
+                    OutputItem::ToolCall {
+                        arguments,
+                        call_id,
+                        name,
+                        ..
+                    } => Ok(Some(StreamEvent::Delta(StreamDelta {
                         content: None,
                         tool_calls: Some(vec![ToolCall {
                             id: call_id,
                             name,
                             arguments_json: arguments,
                         }]),
-                    })))
-                } else {
-                    Ok(None)
+                        reasoning: None,
+                        logprobs: None,
+                    }))),
+                    OutputItem::BuiltInToolCall(call) => {
+                        Ok(built_in_tool_call_to_content_part(call).map(|result| {
+                            StreamEvent::Delta(StreamDelta {
+                                content: Some(vec![result]),
+                                tool_calls: None,
+                                reasoning: None,
+                                logprobs: None,
+                            })
+                        }))
+                    }
+                    _ => Ok(None),
                 }
             }
             Some(_) => Ok(None),
@@ -156,32 +201,34 @@ impl LlmChatStreamState for OpenAIChatStream {
     }
 }
 
-struct OpenAIComponent;
+pub struct OpenAIComponent;
 
 impl OpenAIComponent {
     const ENV_VAR_NAME: &'static str = "OPENAI_API_KEY";
 
-    fn request(client: ResponsesApi, items: Vec<InputItem>, config: Config) -> ChatEvent {
-        match tool_defs_to_tools(&config.tools) {
-            Ok(tools) => {
-                let request = create_request(items, config, tools);
-                match client.create_model_response(request) {
-                    Ok(response) => process_model_response(response),
-                    Err(error) => ChatEvent::Error(error),
-                }
-            }
+    /// Sends a request through `client`, also used by the Azure OpenAI provider
+    pub fn request(client: ResponsesApi, items: Vec<InputItem>, config: Config) -> ChatEvent {
+        let request =
+            tool_defs_to_tools(&config).and_then(|tools| create_request(items, config, tools));
+        match request {
+            Ok(request) => match client.create_model_response(request) {
+                Ok(response) => process_model_response(response),
+                Err(error) => ChatEvent::Error(error),
+            },
             Err(error) => ChatEvent::Error(error),
         }
     }
 
-    fn streaming_request(
+    /// Opens a stream through `client`, also used by the Azure OpenAI provider
+    pub fn streaming_request(
         client: ResponsesApi,
         items: Vec<InputItem>,
         config: Config,
     ) -> LlmChatStream<OpenAIChatStream> {
-        match tool_defs_to_tools(&config.tools) {
-            Ok(tools) => {
-                let mut request = create_request(items, config, tools);
+        let request =
+            tool_defs_to_tools(&config).and_then(|tools| create_request(items, config, tools));
+        match request {
+            Ok(mut request) => {
                 request.stream = true;
                 match client.stream_model_response(request) {
                     Ok(stream) => OpenAIChatStream::new(stream),
@@ -199,12 +246,19 @@ impl Guest for OpenAIComponent {
     fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |openai_api_key| {
-            let client = ResponsesApi::new(openai_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |openai_api_key| {
+                let client = ResponsesApi::new(openai_api_key);
 
-            let items = messages_to_input_items(messages);
-            Self::request(client, items, config)
-        })
+                match messages_to_input_items(messages) {
+                    Ok(items) => Self::request(client, items, config),
+                    Err(error) => ChatEvent::Error(error),
+                }
+            },
+        )
     }
 
     fn continue_(
@@ -214,13 +268,22 @@ impl Guest for OpenAIComponent {
     ) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |openai_api_key| {
-            let client = ResponsesApi::new(openai_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |openai_api_key| {
+                let client = ResponsesApi::new(openai_api_key);
 
-            let mut items = messages_to_input_items(messages);
-            items.extend(tool_results_to_input_items(tool_results));
-            Self::request(client, items, config)
-        })
+                match messages_to_input_items(messages) {
+                    Ok(mut items) => {
+                        items.extend(tool_results_to_input_items(tool_results));
+                        Self::request(client, items, config)
+                    }
+                    Err(error) => ChatEvent::Error(error),
+                }
+            },
+        )
     }
 
     fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
@@ -232,14 +295,17 @@ impl ExtendedGuest for OpenAIComponent {
     fn unwrapped_stream(messages: Vec<Message>, config: Config) -> Self::ChatStream {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
             Self::ENV_VAR_NAME,
             OpenAIChatStream::failed,
             |openai_api_key| {
                 let client = ResponsesApi::new(openai_api_key);
 
-                let items = messages_to_input_items(messages);
-                Self::streaming_request(client, items, config)
+                match messages_to_input_items(messages) {
+                    Ok(items) => Self::streaming_request(client, items, config),
+                    Err(error) => OpenAIChatStream::failed(error),
+                }
             },
         )
     }
@@ -247,8 +313,178 @@ impl ExtendedGuest for OpenAIComponent {
     fn subscribe(stream: &Self::ChatStream) -> Pollable {
         stream.subscribe()
     }
+
+    fn submit_batch(requests: Vec<BatchRequest>) -> Result<BatchInfo, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let api_key = requests
+            .first()
+            .and_then(|request| provider_option(&request.config, API_KEY_OPTION));
+        with_config_key_or(api_key, Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = BatchesApi::new(openai_api_key);
+
+            let lines = batch_requests_to_input_lines(requests)?;
+            let file = client.upload_batch_file(&lines)?;
+            client
+                .create_batch(CreateBatchRequest {
+                    input_file_id: file.id,
+                    endpoint: BATCH_ENDPOINT.to_string(),
+                    completion_window: "24h".to_string(),
+                })
+                .map(batch_to_info)
+        })
+    }
+
+    fn poll_batch(batch_id: String) -> Result<BatchInfo, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = BatchesApi::new(openai_api_key);
+            client.retrieve_batch(&batch_id).map(batch_to_info)
+        })
+    }
+
+    fn get_batch_results(batch_id: String) -> Result<Vec<BatchResult>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = BatchesApi::new(openai_api_key);
+
+            // The successful and the failed requests are written to separate files
+            let batch = client.retrieve_batch(&batch_id)?;
+            if batch.output_file_id.is_none() && batch.error_file_id.is_none() {
+                return Err(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!("Batch {batch_id} has no results yet"),
+                    provider_error_json: None,
+                });
+            }
+            let mut results = Vec::new();
+            for file_id in batch.output_file_id.iter().chain(&batch.error_file_id) {
+                let lines = client.retrieve_batch_file(file_id)?;
+                results.extend(lines.into_iter().map(batch_output_to_result));
+            }
+            Ok(results)
+        })
+    }
 }
 
-type DurableOpenAIComponent = DurableLLM<OpenAIComponent>;
+#[cfg(not(feature = "embedded"))]
+type DurableOpenAIComponent = golem_llm::durability::DurableLLM<OpenAIComponent>;
 
+#[cfg(not(feature = "embedded"))]
 golem_llm::export_llm!(DurableOpenAIComponent with_types_in golem_llm);
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{CreateModelResponseRequest, CreateModelResponseResponse};
+    use crate::conversions::{create_request, messages_to_input_items, process_model_response};
+    use crate::OpenAIChatStream;
+    use golem_llm::golem::llm::llm::{
+        ChatEvent, Config, ContentPart, Message, Role, StreamEvent, Usage,
+    };
+    use golem_llm::recording::Fixture;
+    use std::cell::RefCell;
+
+    fn fixture(operation: &str) -> Fixture {
+        Fixture::load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"))
+            .unwrap()
+            .into_iter()
+            .find(|fixture| fixture.operation == operation)
+            .unwrap()
+    }
+
+    fn request(stream: bool) -> CreateModelResponseRequest {
+        let messages = vec![Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text(
+                "What is the capital of France?".to_string(),
+            )],
+            cache_control: None,
+        }];
+        let config = Config {
+            model: "gpt-4o-mini".to_string(),
+            temperature: None,
+            max_tokens: Some(100),
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+        let mut request =
+            create_request(messages_to_input_items(messages).unwrap(), config, vec![]).unwrap();
+        request.stream = stream;
+        request
+    }
+
+    fn usage() -> Option<Usage> {
+        Some(Usage {
+            input_tokens: Some(14),
+            output_tokens: Some(8),
+            total_tokens: Some(22),
+            cached_tokens: Some(0),
+        })
+    }
+
+    #[test]
+    fn matches_the_recorded_response() {
+        let fixture = fixture("create-model-response");
+        assert_eq!(
+            serde_json::to_value(request(false)).unwrap(),
+            fixture.request
+        );
+
+        let response = fixture.response::<CreateModelResponseResponse>().unwrap();
+        let ChatEvent::Message(response) = process_model_response(response) else {
+            panic!("expected a message")
+        };
+        assert_eq!(
+            response.content,
+            vec![ContentPart::Text(
+                "The capital of France is Paris.".to_string()
+            )]
+        );
+        assert_eq!(response.metadata.usage, usage());
+    }
+
+    #[test]
+    fn matches_the_recorded_stream() {
+        let fixture = fixture("stream-model-response");
+        assert_eq!(
+            serde_json::to_value(request(true)).unwrap(),
+            fixture.request
+        );
+
+        let stream = OpenAIChatStream {
+            stream: RefCell::new(None),
+            failure: None,
+            finished: RefCell::new(false),
+        };
+        let events = fixture.replay_stream(&stream);
+
+        let mut text = String::new();
+        for event in &events[..events.len() - 1] {
+            let StreamEvent::Delta(delta) = event else {
+                panic!("expected a delta, got {event:?}")
+            };
+            for part in delta.content.iter().flatten() {
+                if let ContentPart::Text(delta) = part {
+                    text.push_str(delta);
+                }
+            }
+        }
+        assert_eq!(text, "The capital of France is Paris.");
+        let Some(StreamEvent::Finish(metadata)) = events.last() else {
+            panic!("expected the stream to finish")
+        };
+        assert_eq!(metadata.usage, usage());
+    }
+}
@@ -1,8 +1,12 @@
-use golem_llm::error::{error_code_from_status, from_event_source_error, from_reqwest_error};
+use golem_llm::error::{
+    from_error_response, from_event_source_error, from_reqwest_error, ProviderError,
+};
 use golem_llm::event_source::EventSource;
 use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::interceptor::{self, OutgoingRequest};
+use golem_llm::recording::Recorder;
+use golem_llm::redaction::Redacted;
 use log::trace;
-use reqwest::header::HeaderValue;
 use reqwest::{Client, Method, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -25,37 +29,57 @@ impl CompletionsApi {
     }
 
     pub fn send_messages(&self, request: CompletionsRequest) -> Result<CompletionsResponse, Error> {
-        trace!("Sending request to OpenRouter API: {request:?}");
+        trace!(
+            "Sending request to OpenRouter API: {:?}",
+            Redacted(&request)
+        );
+        let mut recorder = Recorder::new("openrouter", "send-messages", &request);
+        if let Some((status, raw_body)) = recorder.replayed_response()? {
+            return parse_response_body(status, raw_body);
+        }
 
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/api/v1/chat/completions"))
-            .bearer_auth(self.api_key.clone())
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openrouter",
+                "send-messages",
+                Method::POST,
+                format!("{BASE_URL}/api/v1/chat/completions"),
+            )
+            .bearer_auth(&self.api_key)?
+            .json(&request)?,
+        )?;
 
-        parse_response(response)
+        parse_response(response, recorder)
     }
 
     pub fn stream_send_messages(&self, request: CompletionsRequest) -> Result<EventSource, Error> {
-        trace!("Sending request to OpenRouter API: {request:?}");
-
-        let response: Response = self
-            .client
-            .request(Method::POST, format!("{BASE_URL}/api/v1/chat/completions"))
-            .bearer_auth(self.api_key.clone())
-            .header(
-                reqwest::header::ACCEPT,
-                HeaderValue::from_static("text/event-stream"),
+        trace!(
+            "Sending request to OpenRouter API: {:?}",
+            Redacted(&request)
+        );
+        let mut recorder = Recorder::new("openrouter", "stream-send-messages", &request);
+        if let Some(stream) = recorder.replayed_stream()? {
+            return Ok(stream);
+        }
+
+        let response: Response = interceptor::send(
+            &self.client,
+            OutgoingRequest::new(
+                "openrouter",
+                "stream-send-messages",
+                Method::POST,
+                format!("{BASE_URL}/api/v1/chat/completions"),
             )
-            .json(&request)
-            .send()
-            .map_err(|err| from_reqwest_error("Request failed", err))?;
+            .bearer_auth(&self.api_key)?
+            .header(reqwest::header::ACCEPT, "text/event-stream")?
+            .json(&request)?,
+        )?;
 
         trace!("Initializing SSE stream");
 
         EventSource::new(response)
+            .map(|stream| stream.with_observer(recorder))
             .map_err(|err| from_event_source_error("Failed to create SSE stream", err))
     }
 }
@@ -69,6 +93,8 @@ pub struct CompletionsRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub presence_penalty: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repetition_penalty: Option<f32>,
@@ -92,6 +118,24 @@ pub struct CompletionsRequest {
     pub min_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub top_a: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_logprobs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningConfig>,
+    /// The kinds of output, `["image", "text"]` for models generating images
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<String>>,
+}
+
+/// Reasoning tokens are configured with either an effort level or a token budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,7 +196,7 @@ pub enum Content {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ToolChoice {
-    String(String), // none or auto
+    String(String), // none, auto or required
     Function(ToolChoiceFunction),
 }
 
@@ -175,6 +219,15 @@ pub enum ContentPart {
     TextInput { text: String },
     #[serde(rename = "image_url")]
     ImageInput { image_url: ImageUrl },
+    #[serde(rename = "input_audio")]
+    AudioInput { input_audio: InputAudio },
+}
+
+/// A base64 encoded audio clip, with its format (like `wav` or `mp3`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputAudio {
+    pub data: String,
+    pub format: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -230,6 +283,28 @@ pub struct Choice {
     pub native_finish_reason: Option<FinishReason>,
     pub message: ResponseMessage,
     pub error: Option<ErrorResponse>,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprobs {
+    #[serde(default)]
+    pub content: Option<Vec<Logprob>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logprob {
+    pub token: String,
+    pub logprob: f32,
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopLogprob {
+    pub token: String,
+    pub logprob: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -249,8 +324,18 @@ pub enum FinishReason {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseMessage {
     pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
     pub role: String,
     pub tool_calls: Option<Vec<ToolCall>>,
+    #[serde(default)]
+    pub images: Vec<OutputImage>,
+}
+
+/// An image generated by the model, typically as a base64 encoded `data:` URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputImage {
+    pub image_url: ImageUrl,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -288,25 +373,49 @@ pub struct ChoiceChunk {
     pub finish_reason: Option<FinishReason>,
     pub native_finish_reason: Option<String>,
     pub error: Option<ErrorResponse>,
+    #[serde(default)]
+    pub logprobs: Option<Logprobs>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChoiceDelta {
     pub content: Option<String>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
     pub tool_calls: Option<Vec<ToolCall>>,
     pub role: Option<String>,
+    #[serde(default)]
+    pub images: Vec<OutputImage>,
 }
 
-fn parse_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T, Error> {
+fn parse_response<T: DeserializeOwned + Debug>(
+    response: Response,
+    mut recorder: Recorder,
+) -> Result<T, Error> {
     let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    recorder.record_response(status, &raw_body);
+
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned + Debug>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
     if status.is_success() {
-        let raw_body = response
-            .text()
-            .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
-        trace!("Received response from OpenRouter API: {raw_body:?}");
+        trace!(
+            "Received response from OpenRouter API: {:?}",
+            Redacted(&raw_body)
+        );
 
         if let Ok(body) = serde_json::from_str::<T>(&raw_body) {
-            trace!("Received response from OpenRouter API: {body:?}");
+            trace!(
+                "Received response from OpenRouter API: {:?}",
+                Redacted(&body)
+            );
             Ok(body)
         } else {
             let error_body: ErrorResponseBody =
@@ -320,35 +429,41 @@ fn parse_response<T: DeserializeOwned + Debug>(response: Response) -> Result<T,
                 .ok()
                 .and_then(|code| StatusCode::from_u16(code).ok())
                 .unwrap_or(status);
-            Err(Error {
-                code: error_code_from_status(status),
-                message: error_body.error.message,
-                provider_error_json: error_body
-                    .error
-                    .metadata
-                    .map(|value| serde_json::to_string(&value).unwrap()),
-            })
+            Err(from_error_response(
+                status,
+                raw_body,
+                ProviderError::from_json,
+            ))
         }
     } else {
-        let raw_error_body = response
-            .text()
-            .map_err(|err| from_reqwest_error("Failed to receive error response body", err))?;
-        trace!("Received {status} response from OpenRouter API: {raw_error_body:?}");
-
-        let error_body: ErrorResponseBody =
-            serde_json::from_str(&raw_error_body).map_err(|err| Error {
-                code: ErrorCode::InternalError,
-                message: format!("Failed to parse error response body: {err}"),
-                provider_error_json: Some(raw_error_body),
-            })?;
-
-        Err(Error {
-            code: error_code_from_status(status),
-            message: error_body.error.message,
-            provider_error_json: error_body
-                .error
-                .metadata
-                .map(|value| serde_json::to_string(&value).unwrap()),
-        })
+        trace!(
+            "Received {status} response from OpenRouter API: {:?}",
+            Redacted(&raw_body)
+        );
+
+        Err(from_error_response(
+            status,
+            raw_body,
+            ProviderError::from_json,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, CompletionsResponse};
+    use golem_llm::error_contract::assert_error_mapping;
+
+    #[test]
+    fn error_mapping_contract() {
+        assert_error_mapping(
+            |status, message| {
+                format!(
+                    r#"{{"error": {{"code": {}, "message": "{message}"}}}}"#,
+                    status.as_u16()
+                )
+            },
+            |status, body| parse_response_body::<CompletionsResponse>(status, body).map(|_| ()),
+        );
     }
 }
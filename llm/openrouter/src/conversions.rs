@@ -1,10 +1,14 @@
 use crate::client::{
-    CompletionsRequest, CompletionsResponse, Detail, FunctionName, ToolChoiceFunction,
+    CompletionsRequest, CompletionsResponse, Detail, FunctionName, InputAudio, Logprobs,
+    OutputImage, ReasoningConfig, ToolChoiceFunction,
 };
 use base64::{engine::general_purpose, Engine as _};
+use golem_llm::error::unsupported;
 use golem_llm::golem::llm::llm::{
-    ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode, FinishReason, ImageDetail,
-    ImageReference, Message, ResponseMetadata, Role, ToolCall, ToolDefinition, ToolResult, Usage,
+    AudioReference, ChatEvent, CompleteResponse, Config, ContentPart, Error, ErrorCode,
+    FinishReason, ImageDetail, ImageReference, ImageSource, ImageUrl, Message, ReasoningEffort,
+    ResponseMetadata, Role, TokenLogprob, ToolCall, ToolChoice as LlmToolChoice, ToolDefinition,
+    ToolResult, TopLogprob, Usage,
 };
 use std::collections::HashMap;
 
@@ -23,16 +27,16 @@ pub fn messages_to_request(
         match message.role {
             Role::User => completion_messages.push(crate::client::Message::User {
                 name: message.name,
-                content: convert_content_parts(message.content),
+                content: convert_content_parts(message.content)?,
             }),
             Role::Assistant => completion_messages.push(crate::client::Message::Assistant {
                 name: message.name,
-                content: Some(convert_content_parts(message.content)),
+                content: Some(convert_content_parts(message.content)?),
                 tool_calls: None,
             }),
             Role::System => completion_messages.push(crate::client::Message::System {
                 name: message.name,
-                content: convert_content_parts(message.content),
+                content: convert_content_parts(message.content)?,
             }),
             Role::Tool => completion_messages.push(crate::client::Message::Tool {
                 name: message.name,
@@ -54,6 +58,7 @@ pub fn messages_to_request(
             .get("frequency_penalty")
             .and_then(|fp_s| fp_s.parse::<f32>().ok()),
         max_tokens: config.max_tokens,
+        parallel_tool_calls: config.parallel_tool_calls,
         presence_penalty: options
             .get("presence_penalty")
             .and_then(|pp_s| pp_s.parse::<f32>().ok()),
@@ -80,6 +85,35 @@ pub fn messages_to_request(
         top_a: options
             .get("top_a")
             .and_then(|top_a_s| top_a_s.parse::<f32>().ok()),
+        logprobs: config.logprobs.map(|_| true),
+        top_logprobs: config.logprobs,
+        reasoning: convert_reasoning(config.reasoning_effort, config.thinking_budget),
+        modalities: options.get("modalities").map(|modalities| {
+            modalities
+                .split(',')
+                .map(|modality| modality.trim().to_string())
+                .collect()
+        }),
+    })
+}
+
+pub fn convert_logprobs(logprobs: &Logprobs) -> Option<Vec<TokenLogprob>> {
+    logprobs.content.as_ref().map(|content| {
+        content
+            .iter()
+            .map(|logprob| TokenLogprob {
+                token: logprob.token.clone(),
+                logprob: logprob.logprob,
+                top_logprobs: logprob
+                    .top_logprobs
+                    .iter()
+                    .map(|top| TopLogprob {
+                        token: top.token.clone(),
+                        logprob: top.logprob,
+                    })
+                    .collect(),
+            })
+            .collect()
     })
 }
 
@@ -92,6 +126,14 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
         if let Some(content) = &choice.message.content {
             contents.push(ContentPart::Text(content.clone()));
         }
+        contents.extend(
+            choice
+                .message
+                .images
+                .iter()
+                .cloned()
+                .map(convert_output_image),
+        );
 
         let empty = Vec::new();
         for tool_call in choice.message.tool_calls.as_ref().unwrap_or(&empty) {
@@ -107,12 +149,19 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
                 provider_id: None,
                 timestamp: Some(response.created.to_string()),
                 provider_metadata_json: None,
+                logprobs: choice.logprobs.as_ref().and_then(convert_logprobs),
+                cost: None,
             };
 
             ChatEvent::Message(CompleteResponse {
                 id: response.id,
                 content: contents,
                 tool_calls,
+                reasoning: choice
+                    .message
+                    .reasoning
+                    .clone()
+                    .filter(|text| !text.is_empty()),
                 metadata,
             })
         }
@@ -125,6 +174,24 @@ pub fn process_response(response: CompletionsResponse) -> ChatEvent {
     }
 }
 
+/// Generated images are returned as `data:` URLs, decoded to inline images
+pub fn convert_output_image(image: OutputImage) -> ContentPart {
+    let url = image.image_url.url;
+    let inline = url.strip_prefix("data:").and_then(|data_url| {
+        let (mime_type, data) = data_url.split_once(";base64,")?;
+        let data = general_purpose::STANDARD.decode(data).ok()?;
+        Some(ImageSource {
+            data,
+            mime_type: mime_type.to_string(),
+            detail: None,
+        })
+    });
+    match inline {
+        Some(image_source) => ContentPart::Image(ImageReference::Inline(image_source)),
+        None => ContentPart::Image(ImageReference::Url(ImageUrl { url, detail: None })),
+    }
+}
+
 pub fn tool_results_to_messages(
     tool_results: Vec<(ToolCall, ToolResult)>,
 ) -> Vec<crate::client::Message> {
@@ -165,11 +232,15 @@ pub fn convert_tool_call(tool_call: &crate::client::ToolCall) -> ToolCall {
     }
 }
 
-fn convert_content_parts(contents: Vec<ContentPart>) -> crate::client::Content {
+fn convert_content_parts(contents: Vec<ContentPart>) -> Result<crate::client::Content, Error> {
     let mut result = Vec::new();
     for content in contents {
         match content {
             ContentPart::Text(text) => result.push(crate::client::ContentPart::TextInput { text }),
+            ContentPart::CitedText(cited) => {
+                result.push(crate::client::ContentPart::TextInput { text: cited.text })
+            }
+            ContentPart::BuiltInToolResult(_) => {}
             ContentPart::Image(image_reference) => match image_reference {
                 ImageReference::Url(image_url) => {
                     result.push(crate::client::ContentPart::ImageInput {
@@ -190,9 +261,32 @@ fn convert_content_parts(contents: Vec<ContentPart>) -> crate::client::Content {
                     });
                 }
             },
+            ContentPart::Audio(AudioReference::Url(_)) => {
+                return Err(unsupported("audio URLs with OpenRouter"))
+            }
+            ContentPart::Audio(AudioReference::Inline(audio_source)) => {
+                result.push(crate::client::ContentPart::AudioInput {
+                    input_audio: InputAudio {
+                        data: general_purpose::STANDARD.encode(&audio_source.data),
+                        format: audio_format(&audio_source.mime_type),
+                    },
+                })
+            }
         }
     }
-    crate::client::Content::List(result)
+    Ok(crate::client::Content::List(result))
+}
+
+/// The audio format OpenRouter expects for a MIME type, like `wav` for `audio/wav`
+fn audio_format(mime_type: &str) -> String {
+    match mime_type {
+        "audio/mpeg" | "audio/mp3" => "mp3".to_string(),
+        "audio/wav" | "audio/wave" | "audio/x-wav" => "wav".to_string(),
+        _ => mime_type
+            .strip_prefix("audio/")
+            .unwrap_or(mime_type)
+            .to_string(),
+    }
 }
 
 fn convert_content_parts_to_string(contents: Vec<ContentPart>) -> String {
@@ -200,7 +294,9 @@ fn convert_content_parts_to_string(contents: Vec<ContentPart>) -> String {
     for content in contents {
         match content {
             ContentPart::Text(text) => result.push_str(&text),
-            ContentPart::Image(_) => {} // Correctly ignores any image content
+            ContentPart::CitedText(cited) => result.push_str(&cited.text),
+            // Only the text is kept
+            ContentPart::Image(_) | ContentPart::Audio(_) | ContentPart::BuiltInToolResult(_) => {}
         }
     }
     result
@@ -231,10 +327,14 @@ pub fn convert_usage(value: &crate::client::Usage) -> Usage {
         input_tokens: Some(value.prompt_tokens),
         output_tokens: Some(value.completion_tokens),
         total_tokens: Some(value.total_tokens),
+        cached_tokens: None,
     }
 }
 
 fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool, Error> {
+    if tool.built_in.is_some() {
+        return Err(unsupported("built-in tools with OpenRouter"));
+    }
     match serde_json::from_str(&tool.parameters_schema) {
         Ok(value) => Ok(crate::client::Tool::Function {
             function: crate::client::Function {
@@ -251,11 +351,134 @@ fn tool_definition_to_tool(tool: ToolDefinition) -> Result<crate::client::Tool,
     }
 }
 
-fn convert_tool_choice(tool_choice: String) -> crate::client::ToolChoice {
-    match tool_choice.as_str() {
-        "auto" | "none" => crate::client::ToolChoice::String(tool_choice),
-        _ => crate::client::ToolChoice::Function(ToolChoiceFunction::Function {
-            function: FunctionName { name: tool_choice },
+/// The token budget takes precedence, as OpenRouter only accepts one of the two
+fn convert_reasoning(
+    effort: Option<ReasoningEffort>,
+    thinking_budget: Option<u32>,
+) -> Option<ReasoningConfig> {
+    match (effort, thinking_budget) {
+        (_, Some(max_tokens)) => Some(ReasoningConfig {
+            effort: None,
+            max_tokens: Some(max_tokens),
+        }),
+        (Some(effort), None) => Some(ReasoningConfig {
+            effort: Some(
+                match effort {
+                    ReasoningEffort::Low => "low",
+                    ReasoningEffort::Medium => "medium",
+                    ReasoningEffort::High => "high",
+                }
+                .to_string(),
+            ),
+            max_tokens: None,
         }),
+        (None, None) => None,
+    }
+}
+
+fn convert_tool_choice(tool_choice: LlmToolChoice) -> crate::client::ToolChoice {
+    match tool_choice {
+        LlmToolChoice::Auto => crate::client::ToolChoice::String("auto".to_string()),
+        LlmToolChoice::None => crate::client::ToolChoice::String("none".to_string()),
+        LlmToolChoice::Required => crate::client::ToolChoice::String("required".to_string()),
+        LlmToolChoice::Specific(name) => {
+            crate::client::ToolChoice::Function(ToolChoiceFunction::Function {
+                function: FunctionName { name },
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::CompletionsResponse;
+    use crate::conversions::{audio_format, process_response};
+    use golem_llm::golem::llm::llm::{
+        ChatEvent, ContentPart, ImageReference, ImageSource, TokenLogprob, TopLogprob,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn converts_mime_types_to_audio_formats() {
+        assert_eq!(audio_format("audio/mpeg"), "mp3");
+        assert_eq!(audio_format("audio/x-wav"), "wav");
+        assert_eq!(audio_format("audio/flac"), "flac");
+    }
+
+    #[test]
+    fn returns_the_logprobs_of_the_choice() {
+        let response: CompletionsResponse = serde_json::from_value(json!({
+            "id": "gen-1",
+            "created": 1750000000,
+            "model": "openai/gpt-4o",
+            "system_fingerprint": null,
+            "choices": [{
+                "finish_reason": null,
+                "native_finish_reason": null,
+                "message": { "role": "assistant", "content": "Yes" },
+                "error": null,
+                "logprobs": {
+                    "content": [{
+                        "token": "Yes",
+                        "logprob": -0.25,
+                        "bytes": [89, 101, 115],
+                        "top_logprobs": [{ "token": "No", "logprob": -1.5, "bytes": [78, 111] }]
+                    }]
+                }
+            }]
+        }))
+        .unwrap();
+        match process_response(response) {
+            ChatEvent::Message(response) => assert_eq!(
+                response.metadata.logprobs,
+                Some(vec![TokenLogprob {
+                    token: "Yes".to_string(),
+                    logprob: -0.25,
+                    top_logprobs: vec![TopLogprob {
+                        token: "No".to_string(),
+                        logprob: -1.5,
+                    }],
+                }])
+            ),
+            other => panic!("Unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_generated_images() {
+        let response: CompletionsResponse = serde_json::from_value(json!({
+            "id": "gen-1",
+            "created": 1750000000,
+            "model": "google/gemini-2.5-flash-image-preview",
+            "system_fingerprint": null,
+            "choices": [{
+                "finish_reason": "stop",
+                "native_finish_reason": "STOP",
+                "message": {
+                    "role": "assistant",
+                    "content": "Here is your image",
+                    "images": [{
+                        "type": "image_url",
+                        "image_url": { "url": "data:image/png;base64,iVBORw0K" }
+                    }]
+                },
+                "error": null
+            }]
+        }))
+        .unwrap();
+        match process_response(response) {
+            ChatEvent::Message(response) => assert_eq!(
+                response.content,
+                vec![
+                    ContentPart::Text("Here is your image".to_string()),
+                    ContentPart::Image(ImageReference::Inline(ImageSource {
+                        data: vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a],
+                        mime_type: "image/png".to_string(),
+                        detail: None,
+                    })),
+                ]
+            ),
+            other => panic!("Unexpected event: {other:?}"),
+        }
     }
 }
@@ -3,18 +3,19 @@ mod conversions;
 
 use crate::client::{ChatCompletionChunk, CompletionsApi, CompletionsRequest, FunctionCall};
 use crate::conversions::{
-    convert_finish_reason, convert_usage, messages_to_request, process_response,
-    tool_results_to_messages,
+    convert_finish_reason, convert_logprobs, convert_output_image, convert_usage,
+    messages_to_request, process_response, tool_results_to_messages,
 };
 use golem_llm::chat_stream::{LlmChatStream, LlmChatStreamState};
-use golem_llm::config::with_config_key;
-use golem_llm::durability::{DurableLLM, ExtendedGuest};
-use golem_llm::error::error_code_from_status;
+use golem_llm::config::{provider_option, with_config_key_or, API_KEY_OPTION};
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::error::{classify_error, ProviderError};
 use golem_llm::event_source::EventSource;
 use golem_llm::golem::llm::llm::{
     ChatEvent, ChatStream, Config, ContentPart, Error, FinishReason, Guest, Message,
     ResponseMetadata, Role, StreamDelta, StreamEvent, ToolCall, ToolResult,
 };
+use golem_llm::redaction::Redacted;
 use golem_llm::LOGGING_STATE;
 use golem_rust::wasm_rpc::Pollable;
 use log::trace;
@@ -29,7 +30,7 @@ struct JsonFragment {
     json: String,
 }
 
-struct OpenRouterChatStream {
+pub struct OpenRouterChatStream {
     stream: RefCell<Option<EventSource>>,
     failure: Option<Error>,
     finished: RefCell<bool>,
@@ -81,7 +82,7 @@ impl LlmChatStreamState for OpenRouterChatStream {
     }
 
     fn decode_message(&self, raw: &str) -> Result<Option<StreamEvent>, String> {
-        trace!("Received raw stream event: {raw}");
+        trace!("Received raw stream event: {}", Redacted(&raw));
         if raw.starts_with(": ") {
             Ok(None) // comment
         } else {
@@ -104,6 +105,8 @@ impl LlmChatStreamState for OpenRouterChatStream {
                             provider_id: None,
                             timestamp: Some(message.created.to_string()),
                             provider_metadata_json: None,
+                            logprobs: None,
+                            cost: None,
                         })))
                     } else if let Some(choice) = message.choices.into_iter().next() {
                         if let Some(finish_reason) = choice.finish_reason {
@@ -112,11 +115,15 @@ impl LlmChatStreamState for OpenRouterChatStream {
                         }
                         if let Some(error) = choice.error {
                             Ok(Some(StreamEvent::Error(Error {
-                                code: error_code_from_status(
+                                code: classify_error(
                                     TryInto::<u16>::try_into(error.code)
                                         .ok()
                                         .and_then(|code| StatusCode::from_u16(code).ok())
                                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                                    &ProviderError {
+                                        kind: None,
+                                        message: Some(error.message.clone()),
+                                    },
                                 ),
                                 message: error.message,
                                 provider_error_json: error
@@ -124,10 +131,15 @@ impl LlmChatStreamState for OpenRouterChatStream {
                                     .map(|value| serde_json::to_string(&value).unwrap()),
                             })))
                         } else {
+                            // Generated images arrive whole, base64 encoded in a single delta
                             let content = choice
                                 .delta
                                 .content
-                                .map(|text| vec![ContentPart::Text(text)]);
+                                .map(ContentPart::Text)
+                                .into_iter()
+                                .chain(choice.delta.images.into_iter().map(convert_output_image))
+                                .collect::<Vec<_>>();
+                            let content = (!content.is_empty()).then_some(content);
 
                             let mut seen_indices = HashSet::new();
                             let mut tool_calls = Vec::new();
@@ -210,6 +222,8 @@ impl LlmChatStreamState for OpenRouterChatStream {
                                 } else {
                                     Some(tool_calls)
                                 },
+                                reasoning: choice.delta.reasoning,
+                                logprobs: choice.logprobs.as_ref().and_then(convert_logprobs),
                             })))
                         }
                     } else {
@@ -225,7 +239,7 @@ impl LlmChatStreamState for OpenRouterChatStream {
     }
 }
 
-struct OpenRouterComponent;
+pub struct OpenRouterComponent;
 
 impl OpenRouterComponent {
     const ENV_VAR_NAME: &'static str = "OPENROUTER_API_KEY";
@@ -255,14 +269,19 @@ impl Guest for OpenRouterComponent {
     fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |openrouter_api_key| {
-            let client = CompletionsApi::new(openrouter_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |openrouter_api_key| {
+                let client = CompletionsApi::new(openrouter_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(request) => Self::request(client, request),
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+                match messages_to_request(messages, config) {
+                    Ok(request) => Self::request(client, request),
+                    Err(err) => ChatEvent::Error(err),
+                }
+            },
+        )
     }
 
     fn continue_(
@@ -272,19 +291,24 @@ impl Guest for OpenRouterComponent {
     ) -> ChatEvent {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(Self::ENV_VAR_NAME, ChatEvent::Error, |openrouter_api_key| {
-            let client = CompletionsApi::new(openrouter_api_key);
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
+            Self::ENV_VAR_NAME,
+            ChatEvent::Error,
+            |openrouter_api_key| {
+                let client = CompletionsApi::new(openrouter_api_key);
 
-            match messages_to_request(messages, config) {
-                Ok(mut request) => {
-                    request
-                        .messages
-                        .extend(tool_results_to_messages(tool_results));
-                    Self::request(client, request)
+                match messages_to_request(messages, config) {
+                    Ok(mut request) => {
+                        request
+                            .messages
+                            .extend(tool_results_to_messages(tool_results));
+                        Self::request(client, request)
+                    }
+                    Err(err) => ChatEvent::Error(err),
                 }
-                Err(err) => ChatEvent::Error(err),
-            }
-        })
+            },
+        )
     }
 
     fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
@@ -299,7 +323,8 @@ impl ExtendedGuest for OpenRouterComponent {
     ) -> LlmChatStream<OpenRouterChatStream> {
         LOGGING_STATE.with_borrow_mut(|state| state.init());
 
-        with_config_key(
+        with_config_key_or(
+            provider_option(&config, API_KEY_OPTION),
             Self::ENV_VAR_NAME,
             OpenRouterChatStream::failed,
             |openrouter_api_key| {
@@ -324,6 +349,7 @@ impl ExtendedGuest for OpenRouterComponent {
                      Please continue your response from where you left off. \
                      Do not include the part of the response that was already seen.".to_string()),
             ],
+            cache_control: None,
         });
         extended_messages.push(Message {
             role: Role::User,
@@ -331,6 +357,7 @@ impl ExtendedGuest for OpenRouterComponent {
             content: vec![ContentPart::Text(
                 "Here is the original question:".to_string(),
             )],
+            cache_control: None,
         });
         extended_messages.extend_from_slice(original_messages);
 
@@ -358,6 +385,7 @@ impl ExtendedGuest for OpenRouterComponent {
             .into_iter()
             .chain(partial_result_as_content)
             .collect(),
+            cache_control: None,
         });
         extended_messages
     }
@@ -367,6 +395,8 @@ impl ExtendedGuest for OpenRouterComponent {
     }
 }
 
-type DurableOpenRouterComponent = DurableLLM<OpenRouterComponent>;
+#[cfg(not(feature = "embedded"))]
+type DurableOpenRouterComponent = golem_llm::durability::DurableLLM<OpenRouterComponent>;
 
+#[cfg(not(feature = "embedded"))]
 golem_llm::export_llm!(DurableOpenRouterComponent with_types_in golem_llm);
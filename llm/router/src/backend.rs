@@ -0,0 +1,186 @@
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, Config, Error, Guest, GuestChatStream, Message, StreamEvent, ToolCall, ToolResult,
+};
+use golem_llm_anthropic::AnthropicComponent;
+use golem_llm_grok::GrokComponent;
+use golem_llm_mock::MockComponent;
+use golem_llm_ollama::OllamaComponent;
+use golem_llm_openai::OpenAIComponent;
+use golem_llm_openrouter::OpenRouterComponent;
+use golem_rust::wasm_rpc::Pollable;
+use serde::Deserialize;
+use std::cell::RefCell;
+
+/// The LLM providers the router can forward requests to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Anthropic,
+    Grok,
+    Mock,
+    Ollama,
+    #[serde(rename = "openai")]
+    OpenAI,
+    #[serde(rename = "openrouter")]
+    OpenRouter,
+}
+
+impl Backend {
+    pub fn send(self, messages: Vec<Message>, config: Config) -> ChatEvent {
+        match self {
+            Backend::Anthropic => AnthropicComponent::send(messages, config),
+            Backend::Grok => GrokComponent::send(messages, config),
+            Backend::Mock => MockComponent::send(messages, config),
+            Backend::Ollama => OllamaComponent::send(messages, config),
+            Backend::OpenAI => OpenAIComponent::send(messages, config),
+            Backend::OpenRouter => OpenRouterComponent::send(messages, config),
+        }
+    }
+
+    pub fn continue_(
+        self,
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        match self {
+            Backend::Anthropic => AnthropicComponent::continue_(messages, tool_results, config),
+            Backend::Grok => GrokComponent::continue_(messages, tool_results, config),
+            Backend::Mock => MockComponent::continue_(messages, tool_results, config),
+            Backend::Ollama => OllamaComponent::continue_(messages, tool_results, config),
+            Backend::OpenAI => OpenAIComponent::continue_(messages, tool_results, config),
+            Backend::OpenRouter => OpenRouterComponent::continue_(messages, tool_results, config),
+        }
+    }
+
+    pub fn stream(self, messages: Vec<Message>, config: Config) -> RoutedChatStream {
+        match self {
+            Backend::Anthropic => RoutedChatStream::open::<AnthropicComponent>(messages, config),
+            Backend::Grok => RoutedChatStream::open::<GrokComponent>(messages, config),
+            Backend::Mock => RoutedChatStream::open::<MockComponent>(messages, config),
+            Backend::Ollama => RoutedChatStream::open::<OllamaComponent>(messages, config),
+            Backend::OpenAI => RoutedChatStream::open::<OpenAIComponent>(messages, config),
+            Backend::OpenRouter => RoutedChatStream::open::<OpenRouterComponent>(messages, config),
+        }
+    }
+}
+
+/// Object safe view of a backend's chat stream
+trait BackendStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>>;
+    fn blocking_get_next(&self) -> Vec<StreamEvent>;
+    fn cancel(&self);
+    fn subscribe(&self) -> Pollable;
+}
+
+struct Backed<Impl: ExtendedGuest>(Impl::ChatStream);
+
+impl<Impl: ExtendedGuest> BackendStream for Backed<Impl> {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        self.0.get_next()
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        self.0.blocking_get_next()
+    }
+
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    fn subscribe(&self) -> Pollable {
+        Impl::subscribe(&self.0)
+    }
+}
+
+/// A stream that failed before reaching any backend, emitting a single error event
+struct Failed(RefCell<Option<Error>>);
+
+impl BackendStream for Failed {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        Some(
+            self.0
+                .borrow_mut()
+                .take()
+                .map(StreamEvent::Error)
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        self.get_next().unwrap_or_default()
+    }
+
+    fn cancel(&self) {
+        self.0.borrow_mut().take();
+    }
+
+    fn subscribe(&self) -> Pollable {
+        golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0)
+    }
+}
+
+/// The chat stream of the backend selected for a request
+pub struct RoutedChatStream {
+    inner: Box<dyn BackendStream>,
+    /// Events received while checking for an early error, returned by the next call
+    pending: RefCell<Option<Vec<StreamEvent>>>,
+}
+
+impl RoutedChatStream {
+    fn open<Impl: ExtendedGuest>(messages: Vec<Message>, config: Config) -> Self {
+        Self::new(Box::new(Backed::<Impl>(Impl::unwrapped_stream(
+            messages, config,
+        ))))
+    }
+
+    pub fn failed(error: Error) -> Self {
+        Self::new(Box::new(Failed(RefCell::new(Some(error)))))
+    }
+
+    fn new(inner: Box<dyn BackendStream>) -> Self {
+        Self {
+            inner,
+            pending: RefCell::new(None),
+        }
+    }
+
+    /// The error the stream failed with before producing anything, like a rejected request. The
+    /// stream is polled once, and the events received instead are kept for the caller.
+    pub fn early_error(&self) -> Option<Error> {
+        let events = self.inner.get_next()?;
+        let error = match events.as_slice() {
+            [StreamEvent::Error(error)] => Some(error.clone()),
+            _ => None,
+        };
+        *self.pending.borrow_mut() = Some(events);
+        error
+    }
+
+    pub fn subscribe(&self) -> Pollable {
+        if self.pending.borrow().is_some() {
+            golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0)
+        } else {
+            self.inner.subscribe()
+        }
+    }
+}
+
+impl GuestChatStream for RoutedChatStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        let pending = self.pending.borrow_mut().take();
+        pending.or_else(|| self.inner.get_next())
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        let pending = self.pending.borrow_mut().take();
+        pending.unwrap_or_else(|| self.inner.blocking_get_next())
+    }
+
+    fn cancel(&self) {
+        self.pending.borrow_mut().take();
+        self.inner.cancel()
+    }
+}
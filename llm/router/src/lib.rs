@@ -0,0 +1,212 @@
+pub mod backend;
+mod rules;
+
+use crate::backend::{Backend, RoutedChatStream};
+use crate::rules::{
+    parse_config, select, should_fail_over, RouterConfig, MAX_COST_OPTION, ROUTES_ENV_VAR,
+};
+use golem_llm::config::provider_option;
+use golem_llm::durability::ExtendedGuest;
+use golem_llm::golem::llm::llm::{
+    ChatEvent, ChatStream, Config, Error, ErrorCode, Guest, Message, ToolCall, ToolResult,
+};
+use golem_llm::metering::configured_prices;
+use golem_llm::LOGGING_STATE;
+use golem_rust::wasm_rpc::Pollable;
+use log::{debug, warn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Default)]
+struct RouterState {
+    config: Option<RouterConfig>,
+    /// Moving average of the latency of each backend, in milliseconds
+    latencies: HashMap<Backend, u64>,
+}
+
+thread_local! {
+    static ROUTER_STATE: RefCell<RouterState> = RefCell::new(RouterState::default());
+}
+
+pub struct RouterComponent;
+
+impl RouterComponent {
+    /// Selects the backends of a request in the order they are tried, each with the config of the
+    /// request rewritten for it
+    fn route(mut config: Config) -> Result<Vec<(Backend, Config)>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let max_cost = match provider_option(&config, MAX_COST_OPTION) {
+            Some(value) => Some(value.parse::<f64>().map_err(|err| Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("Invalid {MAX_COST_OPTION} provider option: {err}"),
+                provider_error_json: None,
+            })?),
+            None => None,
+        };
+        config
+            .provider_options
+            .retain(|kv| kv.key != MAX_COST_OPTION);
+
+        ROUTER_STATE.with_borrow_mut(|state| {
+            if state.config.is_none() {
+                let raw = std::env::var(ROUTES_ENV_VAR).map_err(|_| Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Missing config key: {ROUTES_ENV_VAR}"),
+                    provider_error_json: None,
+                })?;
+                state.config = Some(parse_config(&raw).map_err(|message| Error {
+                    code: ErrorCode::InternalError,
+                    message,
+                    provider_error_json: None,
+                })?);
+            }
+            let router_config = state.config.as_ref().unwrap();
+
+            let routes = select(
+                router_config,
+                &config.model,
+                max_cost,
+                &configured_prices(),
+                &state.latencies,
+                roll(),
+            )
+            .map_err(|message| Error {
+                code: ErrorCode::InvalidRequest,
+                message,
+                provider_error_json: None,
+            })?;
+
+            debug!(
+                "Routing request for model {} to {:?}",
+                config.model,
+                routes.iter().map(|route| route.backend).collect::<Vec<_>>()
+            );
+            Ok(routes
+                .into_iter()
+                .map(|route| {
+                    let mut config = config.clone();
+                    config.model = route.target_model(&config.model).to_string();
+                    (route.backend, config)
+                })
+                .collect())
+        })
+    }
+
+    /// Calls the routes in order, moving on to the next one while `call` fails right away with an
+    /// error another backend may not have. The error of the last route is returned as is.
+    fn with_fallback<T>(
+        routes: Vec<(Backend, Config)>,
+        call: impl Fn(Backend, Config) -> T,
+        early_error: impl Fn(&T) -> Option<Error>,
+    ) -> T {
+        let mut routes = routes.into_iter();
+        let (mut backend, mut config) = routes.next().expect("select returns at least one route");
+        loop {
+            let started_at = Instant::now();
+            let result = call(backend, config);
+            match early_error(&result) {
+                Some(error) if should_fail_over(&error) => match routes.next() {
+                    Some(next) => {
+                        warn!(
+                            "{backend:?} failed with {:?}, trying the next route: {}",
+                            error.code, error.message
+                        );
+                        (backend, config) = next;
+                    }
+                    None => return result,
+                },
+                _ => {
+                    Self::record_latency(backend, started_at);
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Updates the moving average latency of `backend`
+    fn record_latency(backend: Backend, started_at: Instant) {
+        let latency = started_at.elapsed().as_millis() as u64;
+        ROUTER_STATE.with_borrow_mut(|state| {
+            state
+                .latencies
+                .entry(backend)
+                .and_modify(|average| *average = (*average * 3 + latency) / 4)
+                .or_insert(latency);
+        });
+    }
+}
+
+/// A random number for the weighted selection of routes. The routing decision itself is not
+/// persisted, only the result of the call, so it does not have to be deterministic.
+fn roll() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos() as u64)
+        .unwrap_or_default()
+}
+
+fn chat_error(event: &ChatEvent) -> Option<Error> {
+    match event {
+        ChatEvent::Error(error) => Some(error.clone()),
+        _ => None,
+    }
+}
+
+impl Guest for RouterComponent {
+    type ChatStream = RoutedChatStream;
+
+    fn send(messages: Vec<Message>, config: Config) -> ChatEvent {
+        match Self::route(config) {
+            Ok(routes) => Self::with_fallback(
+                routes,
+                |backend, config| backend.send(messages.clone(), config),
+                chat_error,
+            ),
+            Err(error) => ChatEvent::Error(error),
+        }
+    }
+
+    fn continue_(
+        messages: Vec<Message>,
+        tool_results: Vec<(ToolCall, ToolResult)>,
+        config: Config,
+    ) -> ChatEvent {
+        match Self::route(config) {
+            Ok(routes) => Self::with_fallback(
+                routes,
+                |backend, config| backend.continue_(messages.clone(), tool_results.clone(), config),
+                chat_error,
+            ),
+            Err(error) => ChatEvent::Error(error),
+        }
+    }
+
+    fn stream(messages: Vec<Message>, config: Config) -> ChatStream {
+        ChatStream::new(Self::unwrapped_stream(messages, config))
+    }
+}
+
+impl ExtendedGuest for RouterComponent {
+    fn unwrapped_stream(messages: Vec<Message>, config: Config) -> RoutedChatStream {
+        match Self::route(config) {
+            Ok(routes) => Self::with_fallback(
+                routes,
+                |backend, config| backend.stream(messages.clone(), config),
+                RoutedChatStream::early_error,
+            ),
+            Err(error) => RoutedChatStream::failed(error),
+        }
+    }
+
+    fn subscribe(stream: &Self::ChatStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+#[cfg(not(feature = "embedded"))]
+type DurableRouterComponent = golem_llm::durability::DurableLLM<RouterComponent>;
+
+#[cfg(not(feature = "embedded"))]
+golem_llm::export_llm!(DurableRouterComponent with_types_in golem_llm);
@@ -0,0 +1,314 @@
+use crate::backend::Backend;
+use golem_llm::golem::llm::llm::{Error, ErrorCode};
+use golem_llm::metering::UnitPrice;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Environment variable holding the routing rules as JSON
+pub const ROUTES_ENV_VAR: &str = "GOLEM_LLM_ROUTES";
+
+/// Provider option setting the cost ceiling of a single request, overriding the configured
+/// `max_cost`
+pub const MAX_COST_OPTION: &str = "router_max_cost";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RouterConfig {
+    /// The default cost ceiling, as the maximum price per million input or output tokens
+    #[serde(default)]
+    pub max_cost: Option<f64>,
+    #[serde(default)]
+    pub policy: Policy,
+    pub routes: Vec<Route>,
+}
+
+/// How the first route of a request is chosen. The other eligible routes are tried in their
+/// configured order when it fails with an error another backend may not have.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Policy {
+    /// Weighted random selection, spreading the requests over the routes
+    #[default]
+    Weighted,
+    /// The routes in their configured order, using the others only as fallbacks
+    Fallback,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Route {
+    pub backend: Backend,
+    /// The route only applies to requested models starting with this prefix
+    #[serde(default)]
+    pub model_prefix: Option<String>,
+    /// Replaces the requested model when forwarding to the backend
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Relative weight of the route when several routes are eligible
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    /// The route is avoided while the average latency of its backend exceeds this value, as long
+    /// as there are other eligible routes
+    #[serde(default)]
+    pub latency_slo_ms: Option<u64>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl Route {
+    /// The model to request from the backend
+    pub fn target_model<'a>(&'a self, requested: &'a str) -> &'a str {
+        self.model.as_deref().unwrap_or(requested)
+    }
+
+    fn matches(&self, model: &str) -> bool {
+        self.model_prefix
+            .as_ref()
+            .is_none_or(|prefix| model.starts_with(prefix))
+    }
+
+    fn within_cost(&self, model: &str, max_cost: f64, prices: &HashMap<String, UnitPrice>) -> bool {
+        prices
+            .get(self.target_model(model))
+            .is_some_and(|price| price.input <= max_cost && price.output <= max_cost)
+    }
+
+    fn within_slo(&self, latencies: &HashMap<Backend, u64>) -> bool {
+        match (self.latency_slo_ms, latencies.get(&self.backend)) {
+            (Some(slo), Some(latency)) => *latency <= slo,
+            _ => true,
+        }
+    }
+}
+
+pub fn parse_config(raw: &str) -> Result<RouterConfig, String> {
+    serde_json::from_str(raw).map_err(|err| format!("Invalid {ROUTES_ENV_VAR}: {err}"))
+}
+
+/// Selects the routes of a request for `model`, in the order they are tried.
+///
+/// Routes are first filtered by model prefix and cost ceiling, then the ones whose backend is
+/// currently slower than their latency SLO are moved last. With the weighted policy the first route
+/// is picked from the others, or from all of them if every backend is slow, by weighted random
+/// selection, using `roll` as the random number.
+pub fn select<'a>(
+    config: &'a RouterConfig,
+    model: &str,
+    max_cost: Option<f64>,
+    prices: &HashMap<String, UnitPrice>,
+    latencies: &HashMap<Backend, u64>,
+    roll: u64,
+) -> Result<Vec<&'a Route>, String> {
+    let matching: Vec<&Route> = config
+        .routes
+        .iter()
+        .filter(|route| route.matches(model))
+        .collect();
+    if matching.is_empty() {
+        return Err(format!("No route matches model {model}"));
+    }
+
+    let affordable: Vec<&Route> = match max_cost.or(config.max_cost) {
+        Some(max_cost) => matching
+            .into_iter()
+            .filter(|route| route.within_cost(model, max_cost, prices))
+            .collect(),
+        None => matching,
+    };
+    if affordable.is_empty() {
+        return Err(format!(
+            "No route for model {model} is within the cost ceiling"
+        ));
+    }
+
+    let (healthy, slow): (Vec<&Route>, Vec<&Route>) = affordable
+        .into_iter()
+        .partition(|route| route.within_slo(latencies));
+    let candidates = if healthy.is_empty() {
+        slow.len()
+    } else {
+        healthy.len()
+    };
+    let mut routes: Vec<&Route> = healthy.into_iter().chain(slow).collect();
+
+    if config.policy == Policy::Weighted {
+        let first = routes.remove(pick_weighted(&routes[..candidates], roll));
+        routes.insert(0, first);
+    }
+    Ok(routes)
+}
+
+/// The index of the route picked by weighted random selection
+fn pick_weighted(candidates: &[&Route], roll: u64) -> usize {
+    let total: u64 = candidates.iter().map(|route| route.weight as u64).sum();
+    if total == 0 {
+        return 0;
+    }
+    let mut remaining = roll % total;
+    for (index, route) in candidates.iter().enumerate() {
+        let weight = route.weight as u64;
+        if remaining < weight {
+            return index;
+        }
+        remaining -= weight;
+    }
+    candidates.len() - 1
+}
+
+/// Whether a request failing with `error` is retried with the next route. Rate limits, exhausted
+/// quotas, outages and timeouts are specific to a backend, while for example an invalid request
+/// would fail everywhere.
+pub fn should_fail_over(error: &Error) -> bool {
+    matches!(
+        error.code,
+        ErrorCode::RateLimitExceeded
+            | ErrorCode::QuotaExceeded
+            | ErrorCode::Overloaded
+            | ErrorCode::InternalError
+            | ErrorCode::Timeout
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::Backend;
+    use crate::rules::{parse_config, select, should_fail_over, Policy, RouterConfig};
+    use golem_llm::golem::llm::llm::{Error, ErrorCode};
+    use golem_llm::metering::parse_prices;
+    use std::collections::HashMap;
+
+    fn config() -> RouterConfig {
+        parse_config(
+            r#"{
+                "routes": [
+                    { "backend": "anthropic", "model_prefix": "claude-" },
+                    { "backend": "openai", "model_prefix": "gpt-", "weight": 3, "latency_slo_ms": 2000 },
+                    { "backend": "openrouter", "model_prefix": "gpt-", "model": "openai/gpt-4o-mini" }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn routes_by_model_prefix() {
+        let config = config();
+        let route = select(
+            &config,
+            "claude-sonnet-4",
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            0,
+        )
+        .unwrap()[0];
+        assert_eq!(route.backend, Backend::Anthropic);
+        assert!(select(&config, "llama3", None, &HashMap::new(), &HashMap::new(), 0).is_err());
+    }
+
+    #[test]
+    fn picks_by_weight() {
+        let config = config();
+        let backends: Vec<Backend> = (0..4)
+            .map(|roll| {
+                select(
+                    &config,
+                    "gpt-4o",
+                    None,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                    roll,
+                )
+                .unwrap()[0]
+                    .backend
+            })
+            .collect();
+        assert_eq!(
+            backends,
+            vec![
+                Backend::OpenAI,
+                Backend::OpenAI,
+                Backend::OpenAI,
+                Backend::OpenRouter
+            ]
+        );
+    }
+
+    #[test]
+    fn avoids_backends_over_their_latency_slo() {
+        let config = config();
+        let latencies = HashMap::from([(Backend::OpenAI, 5000)]);
+        let route = select(&config, "gpt-4o", None, &HashMap::new(), &latencies, 0).unwrap()[0];
+        assert_eq!(route.backend, Backend::OpenRouter);
+        assert_eq!(route.target_model("gpt-4o"), "openai/gpt-4o-mini");
+    }
+
+    #[test]
+    fn applies_cost_ceiling() {
+        let config = config();
+        let prices = parse_prices(
+            r#"{"gpt-4o": {"input": 2.5, "output": 10.0}, "openai/gpt-4o-mini": {"input": 0.15, "output": 0.6}}"#,
+        )
+        .unwrap();
+
+        let route = select(&config, "gpt-4o", Some(1.0), &prices, &HashMap::new(), 0).unwrap()[0];
+        assert_eq!(route.backend, Backend::OpenRouter);
+        assert!(select(&config, "gpt-4o", Some(0.1), &prices, &HashMap::new(), 0).is_err());
+    }
+
+    fn backends(config: &RouterConfig, model: &str, roll: u64) -> Vec<Backend> {
+        select(config, model, None, &HashMap::new(), &HashMap::new(), roll)
+            .unwrap()
+            .iter()
+            .map(|route| route.backend)
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_other_routes_as_fallbacks() {
+        let mut config = parse_config(
+            r#"{
+                "routes": [
+                    { "backend": "openai", "weight": 1 },
+                    { "backend": "anthropic", "weight": 1 },
+                    { "backend": "openrouter", "weight": 1 }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.policy, Policy::Weighted);
+        assert_eq!(
+            backends(&config, "gpt-4o", 2),
+            vec![Backend::OpenRouter, Backend::OpenAI, Backend::Anthropic]
+        );
+
+        config.policy = Policy::Fallback;
+        assert_eq!(
+            backends(&config, "gpt-4o", 2),
+            vec![Backend::OpenAI, Backend::Anthropic, Backend::OpenRouter]
+        );
+
+        // Backends over their latency SLO are tried last
+        config.routes[0].latency_slo_ms = Some(2000);
+        let latencies = HashMap::from([(Backend::OpenAI, 5000)]);
+        let routes = select(&config, "gpt-4o", None, &HashMap::new(), &latencies, 0).unwrap();
+        assert_eq!(
+            routes.iter().map(|route| route.backend).collect::<Vec<_>>(),
+            vec![Backend::Anthropic, Backend::OpenRouter, Backend::OpenAI]
+        );
+    }
+
+    #[test]
+    fn fails_over_on_backend_specific_errors() {
+        let error = |code| Error {
+            code,
+            message: "error".to_string(),
+            provider_error_json: None,
+        };
+        assert!(should_fail_over(&error(ErrorCode::RateLimitExceeded)));
+        assert!(should_fail_over(&error(ErrorCode::Overloaded)));
+        assert!(should_fail_over(&error(ErrorCode::Timeout)));
+        assert!(!should_fail_over(&error(ErrorCode::InvalidRequest)));
+        assert!(!should_fail_over(&error(ErrorCode::ContextLengthExceeded)));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::golem::llm::llm::{ContentPart, Message, Role};
+
+/// Tokens counted for each message on top of its content, for the role and the separators
+const MESSAGE_OVERHEAD_TOKENS: u32 = 4;
+
+/// Tokens counted for each image. Providers bill images by resolution, this is the cost of a
+/// high detail 1024x1024 image on OpenAI.
+const IMAGE_TOKENS: u32 = 765;
+
+/// Tokens counted for each audio clip, the cost of a minute of audio input on OpenAI
+const AUDIO_TOKENS: u32 = 600;
+
+/// Marks the system message holding the summary written by a previous compaction
+const SUMMARY_HEADER: &str = "Summary of the earlier part of the conversation:\n";
+
+/// Estimates the number of tokens of the messages, counting four characters per token.
+///
+/// Every provider tokenizes differently, so this is only meant for deciding when to compact,
+/// with `max-context-tokens` leaving some room below the model's actual limit.
+pub fn estimate_tokens(messages: &[Message]) -> u32 {
+    messages
+        .iter()
+        .map(|message| {
+            let content: u32 = message
+                .content
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text(text) => (text.chars().count() as u32).div_ceil(4),
+                    ContentPart::CitedText(cited) => {
+                        (cited.text.chars().count() as u32).div_ceil(4)
+                    }
+                    ContentPart::Image(_) => IMAGE_TOKENS,
+                    ContentPart::Audio(_) => AUDIO_TOKENS,
+                    ContentPart::BuiltInToolResult(result) => {
+                        (result.output_json.chars().count() as u32).div_ceil(4)
+                    }
+                })
+                .sum();
+            MESSAGE_OVERHEAD_TOKENS + content
+        })
+        .sum()
+}
+
+/// The split of a conversation which got too long
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactionPlan {
+    /// The leading system messages, kept in front of the summary
+    pub system: Vec<Message>,
+    /// The summary written by a previous compaction, if the messages already start with one
+    pub previous_summary: Option<String>,
+    /// The messages to be replaced by the summary
+    pub summarized: Vec<Message>,
+    /// The most recent messages, kept after the summary
+    pub recent: Vec<Message>,
+}
+
+impl CompactionPlan {
+    /// Replaces the summarized messages, and the summary of a previous compaction, with a single
+    /// system message holding the new summary
+    pub fn apply(self, summary: &str) -> Vec<Message> {
+        let mut messages = self.system;
+        messages.push(summary_message(summary));
+        messages.extend(self.recent);
+        messages
+    }
+}
+
+/// Decides which messages to summarize, if the messages exceed `max_context_tokens`.
+///
+/// The leading system messages and the `keep_recent` most recent messages are always kept.
+/// Returns `None` if the messages fit, or if there is nothing left to summarize between them.
+pub fn plan_compaction(
+    messages: &[Message],
+    max_context_tokens: u32,
+    keep_recent: u32,
+) -> Option<CompactionPlan> {
+    if estimate_tokens(messages) <= max_context_tokens {
+        return None;
+    }
+
+    let leading = messages
+        .iter()
+        .take_while(|message| message.role == Role::System)
+        .count();
+    let mut system = Vec::new();
+    let mut previous_summary = None;
+    for message in &messages[..leading] {
+        match summary_of(message) {
+            Some(summary) => previous_summary = Some(summary),
+            None => system.push(message.clone()),
+        }
+    }
+
+    let recent_start = messages
+        .len()
+        .saturating_sub(keep_recent as usize)
+        .max(leading);
+    if recent_start == leading {
+        return None;
+    }
+
+    Some(CompactionPlan {
+        system,
+        previous_summary,
+        summarized: messages[leading..recent_start].to_vec(),
+        recent: messages[recent_start..].to_vec(),
+    })
+}
+
+fn summary_message(summary: &str) -> Message {
+    Message {
+        role: Role::System,
+        name: None,
+        content: vec![ContentPart::Text(format!("{SUMMARY_HEADER}{summary}"))],
+        cache_control: None,
+    }
+}
+
+fn summary_of(message: &Message) -> Option<String> {
+    match message.content.as_slice() {
+        [ContentPart::Text(text)] => text.strip_prefix(SUMMARY_HEADER).map(str::to_string),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compaction::{estimate_tokens, plan_compaction, summary_message};
+    use crate::golem::llm::llm::{ContentPart, Message, Role};
+
+    fn message(role: Role, text: &str) -> Message {
+        Message {
+            role,
+            name: None,
+            content: vec![ContentPart::Text(text.to_string())],
+            cache_control: None,
+        }
+    }
+
+    fn conversation() -> Vec<Message> {
+        let mut messages = vec![message(Role::System, "You are a travel agent.")];
+        for index in 0..6 {
+            messages.push(message(
+                Role::User,
+                &format!("Question {index} {}", "x".repeat(40)),
+            ));
+            messages.push(message(Role::Assistant, &format!("Answer {index}")));
+        }
+        messages
+    }
+
+    #[test]
+    fn estimates_four_characters_per_token() {
+        assert_eq!(estimate_tokens(&[message(Role::User, "12345678")]), 6);
+        assert_eq!(estimate_tokens(&[message(Role::User, "123456789")]), 7);
+    }
+
+    #[test]
+    fn keeps_messages_fitting_the_context_window() {
+        let messages = conversation();
+        assert_eq!(
+            plan_compaction(&messages, estimate_tokens(&messages), 2),
+            None
+        );
+    }
+
+    #[test]
+    fn summarizes_the_middle_of_the_conversation() {
+        let messages = conversation();
+        let plan = plan_compaction(&messages, 50, 4).unwrap();
+        assert_eq!(plan.system, messages[..1].to_vec());
+        assert_eq!(plan.previous_summary, None);
+        assert_eq!(plan.summarized, messages[1..9].to_vec());
+        assert_eq!(plan.recent, messages[9..].to_vec());
+
+        let compacted = plan.apply("The user plans a trip.");
+        assert_eq!(compacted.len(), 6);
+        assert_eq!(compacted[1], summary_message("The user plans a trip."));
+        assert_eq!(compacted[2..], messages[9..]);
+    }
+
+    #[test]
+    fn replaces_the_summary_of_a_previous_compaction() {
+        let mut messages = conversation();
+        messages.insert(1, summary_message("The user plans a trip."));
+        let plan = plan_compaction(&messages, 50, 4).unwrap();
+        assert_eq!(plan.system, messages[..1].to_vec());
+        assert_eq!(
+            plan.previous_summary.as_deref(),
+            Some("The user plans a trip.")
+        );
+        assert_eq!(plan.summarized, messages[2..10].to_vec());
+    }
+
+    #[test]
+    fn cannot_compact_only_recent_messages() {
+        let messages = conversation();
+        assert_eq!(plan_compaction(&messages, 10, 20), None);
+    }
+}
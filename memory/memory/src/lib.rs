@@ -0,0 +1,157 @@
+mod compaction;
+mod store;
+mod summarizer;
+
+wit_bindgen::generate!({
+    path: "../wit",
+    world: "memory-library",
+    generate_all,
+    generate_unused_types: true,
+    additional_derives: [PartialEq],
+});
+
+use crate::compaction::plan_compaction;
+use crate::exports::golem::memory::memory::{
+    Compaction, CompactionOptions, Guest, Memory, MemoryError, RecalledMemory,
+};
+use crate::golem::llm::llm::{Config, Message};
+use crate::store::{memory_id, MemoryStore, RESERVED_FIELDS};
+use golem_ai_common::logging::LoggingState;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    /// This holds the state of our application.
+    static LOGGING_STATE: RefCell<LoggingState> =
+        const { RefCell::new(LoggingState::new("GOLEM_MEMORY_LOG")) };
+
+    /// The latest summary of each conversation. Golem restores it by replaying the worker's
+    /// invocations, with the LLM calls answered from the oplog.
+    static SUMMARIES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+struct MemoryComponent;
+
+impl MemoryComponent {
+    fn init() {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+    }
+
+    fn set_summary(conversation_id: String, summary: String) {
+        SUMMARIES.with_borrow_mut(|summaries| summaries.insert(conversation_id, summary));
+    }
+}
+
+impl Guest for MemoryComponent {
+    fn summarize(
+        conversation_id: String,
+        messages: Vec<Message>,
+        config: Config,
+    ) -> Result<String, MemoryError> {
+        Self::init();
+
+        let previous = Self::get_summary(conversation_id.clone());
+        let summary = summarizer::summarize(previous.as_deref(), &messages, &config)?;
+        Self::set_summary(conversation_id, summary.clone());
+        Ok(summary)
+    }
+
+    fn get_summary(conversation_id: String) -> Option<String> {
+        SUMMARIES.with_borrow(|summaries| summaries.get(&conversation_id).cloned())
+    }
+
+    fn clear_summary(conversation_id: String) {
+        SUMMARIES.with_borrow_mut(|summaries| summaries.remove(&conversation_id));
+    }
+
+    fn remember(
+        conversation_id: String,
+        text: String,
+        embedding: Vec<f32>,
+        metadata: Vec<(String, String)>,
+    ) -> Result<String, MemoryError> {
+        Self::init();
+
+        if text.trim().is_empty() {
+            return Err(MemoryError::InvalidRequest(
+                "The text of a memory cannot be empty".to_string(),
+            ));
+        }
+        if embedding.is_empty() {
+            return Err(MemoryError::InvalidRequest(
+                "The embedding of a memory cannot be empty".to_string(),
+            ));
+        }
+        if let Some((key, _)) = metadata
+            .iter()
+            .find(|(key, _)| RESERVED_FIELDS.contains(&key.as_str()))
+        {
+            return Err(MemoryError::InvalidRequest(format!(
+                "The metadata key {key} is reserved"
+            )));
+        }
+
+        let memory = Memory {
+            id: memory_id(&conversation_id, &text),
+            conversation_id,
+            text,
+            metadata,
+        };
+        MemoryStore::open()?.upsert(&memory, embedding)?;
+        Ok(memory.id)
+    }
+
+    fn recall(
+        conversation_id: Option<String>,
+        embedding: Vec<f32>,
+        limit: u32,
+    ) -> Result<Vec<RecalledMemory>, MemoryError> {
+        Self::init();
+
+        MemoryStore::open()?.search(conversation_id.as_deref(), embedding, limit)
+    }
+
+    fn forget(ids: Vec<String>) -> Result<(), MemoryError> {
+        Self::init();
+
+        if ids.is_empty() {
+            return Ok(());
+        }
+        MemoryStore::open()?.delete(&ids)
+    }
+
+    fn compact(
+        conversation_id: String,
+        messages: Vec<Message>,
+        options: CompactionOptions,
+    ) -> Result<Compaction, MemoryError> {
+        Self::init();
+
+        let Some(plan) =
+            plan_compaction(&messages, options.max_context_tokens, options.keep_recent)
+        else {
+            return Ok(Compaction {
+                messages,
+                summarized_count: 0,
+                summary: None,
+            });
+        };
+
+        let previous = plan
+            .previous_summary
+            .clone()
+            .or_else(|| Self::get_summary(conversation_id.clone()));
+        let summary =
+            summarizer::summarize(previous.as_deref(), &plan.summarized, &options.summarizer)?;
+        Self::set_summary(conversation_id, summary.clone());
+
+        let summarized_count = plan.summarized.len() as u32;
+        Ok(Compaction {
+            messages: plan.apply(&summary),
+            summarized_count,
+            summary: Some(summary),
+        })
+    }
+}
+
+export!(MemoryComponent);
@@ -0,0 +1,256 @@
+use crate::exports::golem::memory::memory::{Memory, MemoryError, RecalledMemory};
+use crate::golem::vector::collections::create_collection;
+use crate::golem::vector::connection::{connect, Connection};
+use crate::golem::vector::search::search_vectors;
+use crate::golem::vector::types::{
+    CollectionConfig, ConnectionConfig, DistanceMetric, FilterCondition, FilterExpression,
+    FilterNode, MetadataValue, SearchQuery, SearchResult, VectorData, VectorError, VectorRecord,
+};
+use crate::golem::vector::vectors::{delete_vectors, upsert_vectors};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Name of the golem:vector collection storing the memories
+pub const COLLECTION_ENV_VAR: &str = "GOLEM_MEMORY_COLLECTION";
+
+/// Endpoint of the vector database, when not using the golem:vector provider's default
+pub const ENDPOINT_ENV_VAR: &str = "GOLEM_MEMORY_VECTOR_ENDPOINT";
+
+const DEFAULT_COLLECTION: &str = "golem-memory";
+
+const TEXT_FIELD: &str = "text";
+const CONVERSATION_FIELD: &str = "conversation_id";
+
+/// Metadata keys used by the store itself, which cannot be set by the callers
+pub const RESERVED_FIELDS: &[&str] = &[TEXT_FIELD, CONVERSATION_FIELD];
+
+/// The memories stored in a golem:vector collection, with the memory's text and conversation
+/// stored in the points' metadata
+pub struct MemoryStore {
+    connection: Connection,
+    collection: String,
+}
+
+impl MemoryStore {
+    pub fn open() -> Result<Self, MemoryError> {
+        let connection = connect(&ConnectionConfig {
+            endpoint: std::env::var(ENDPOINT_ENV_VAR).ok(),
+            credentials: None,
+            provider_options: vec![],
+        })
+        .map_err(store_failed)?;
+        let collection =
+            std::env::var(COLLECTION_ENV_VAR).unwrap_or_else(|_| DEFAULT_COLLECTION.to_string());
+        Ok(Self {
+            connection,
+            collection,
+        })
+    }
+
+    /// Inserts or replaces a memory. The collection is created with the embedding's dimension
+    /// when the first memory is stored.
+    pub fn upsert(&self, memory: &Memory, embedding: Vec<f32>) -> Result<(), MemoryError> {
+        let dimension = embedding.len() as u32;
+        let records = [VectorRecord {
+            id: memory.id.clone(),
+            vector: VectorData::Dense(embedding),
+            sparse: None,
+            metadata: Some(to_metadata(memory)),
+        }];
+
+        let result = match self.upsert_records(&records) {
+            Err(VectorError::NotFound(_)) => {
+                create_collection(
+                    &self.connection,
+                    &self.collection,
+                    CollectionConfig {
+                        dimension,
+                        metric: DistanceMetric::Cosine,
+                        quantization: None,
+                    },
+                )
+                .map_err(store_failed)?;
+                self.upsert_records(&records)
+            }
+            result => result,
+        };
+        result.map_err(store_failed)
+    }
+
+    fn upsert_records(&self, records: &[VectorRecord]) -> Result<(), VectorError> {
+        let result = upsert_vectors(&self.connection, &self.collection, records, None, None)?;
+        match result.failures.into_iter().next() {
+            Some(failure) => Err(failure.error),
+            None => Ok(()),
+        }
+    }
+
+    /// Finds the memories most similar to the embedding. Nothing is found before the first
+    /// memory got stored.
+    pub fn search(
+        &self,
+        conversation_id: Option<&str>,
+        embedding: Vec<f32>,
+        limit: u32,
+    ) -> Result<Vec<RecalledMemory>, MemoryError> {
+        let query = SearchQuery {
+            vector: embedding,
+            vector_name: None,
+            limit,
+            offset: None,
+            filter: conversation_id.map(conversation_filter),
+            include_vectors: false,
+            include_metadata: true,
+            keywords: None,
+            sparse: None,
+        };
+        match search_vectors(&self.connection, &self.collection, &query, None) {
+            Ok(results) => Ok(results.into_iter().filter_map(from_search_result).collect()),
+            Err(VectorError::NotFound(_)) => Ok(vec![]),
+            Err(error) => Err(store_failed(error)),
+        }
+    }
+
+    pub fn delete(&self, ids: &[String]) -> Result<(), MemoryError> {
+        match delete_vectors(&self.connection, &self.collection, ids, None, None) {
+            Ok(()) | Err(VectorError::NotFound(_)) => Ok(()),
+            Err(error) => Err(store_failed(error)),
+        }
+    }
+}
+
+/// The id of a memory, derived from its conversation and text so remembering the same fact
+/// again replaces it instead of storing a duplicate. It is formatted as a UUID, as some vector
+/// databases only accept UUIDs or integers as ids.
+pub fn memory_id(conversation_id: &str, text: &str) -> String {
+    let hash = |seed: u8| {
+        let mut hasher = DefaultHasher::new();
+        (seed, conversation_id, text).hash(&mut hasher);
+        hasher.finish()
+    };
+    let id = ((hash(0) as u128) << 64) | hash(1) as u128;
+    let hex = format!("{id:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn conversation_filter(conversation_id: &str) -> FilterExpression {
+    FilterExpression {
+        nodes: vec![FilterNode::Condition(FilterCondition::Eq((
+            CONVERSATION_FIELD.to_string(),
+            MetadataValue::StringVal(conversation_id.to_string()),
+        )))],
+    }
+}
+
+fn to_metadata(memory: &Memory) -> Vec<(String, MetadataValue)> {
+    let mut metadata = vec![
+        (
+            TEXT_FIELD.to_string(),
+            MetadataValue::StringVal(memory.text.clone()),
+        ),
+        (
+            CONVERSATION_FIELD.to_string(),
+            MetadataValue::StringVal(memory.conversation_id.clone()),
+        ),
+    ];
+    metadata.extend(
+        memory
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), MetadataValue::StringVal(value.clone()))),
+    );
+    metadata
+}
+
+/// Converts a search result back to a memory, skipping points which were not stored as memories
+fn from_search_result(result: SearchResult) -> Option<RecalledMemory> {
+    let mut text = None;
+    let mut conversation_id = None;
+    let mut metadata = Vec::new();
+    for (key, value) in result.metadata.unwrap_or_default() {
+        if let MetadataValue::StringVal(value) = value {
+            match key.as_str() {
+                TEXT_FIELD => text = Some(value),
+                CONVERSATION_FIELD => conversation_id = Some(value),
+                _ => metadata.push((key, value)),
+            }
+        }
+    }
+    Some(RecalledMemory {
+        memory: Memory {
+            id: result.id,
+            conversation_id: conversation_id?,
+            text: text?,
+            metadata,
+        },
+        score: result.score,
+    })
+}
+
+fn store_failed(error: VectorError) -> MemoryError {
+    let message = match error {
+        VectorError::NotFound(message)
+        | VectorError::AlreadyExists(message)
+        | VectorError::InvalidParams(message)
+        | VectorError::UnsupportedFeature(message)
+        | VectorError::DimensionMismatch(message)
+        | VectorError::Unauthorized(message)
+        | VectorError::RateLimited(message)
+        | VectorError::ProviderError(message)
+        | VectorError::ConnectionError(message) => message,
+    };
+    MemoryError::StoreFailed(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exports::golem::memory::memory::Memory;
+    use crate::golem::vector::types::SearchResult;
+    use crate::store::{from_search_result, memory_id, to_metadata};
+
+    #[test]
+    fn derives_ids_from_the_conversation_and_text() {
+        let id = memory_id("trip", "The user prefers window seats");
+        assert_eq!(id.len(), 36);
+        assert_eq!(id.matches('-').count(), 4);
+        assert_eq!(id, memory_id("trip", "The user prefers window seats"));
+        assert_ne!(id, memory_id("trip", "The user prefers aisle seats"));
+        assert_ne!(id, memory_id("other", "The user prefers window seats"));
+    }
+
+    #[test]
+    fn restores_memories_from_search_results() {
+        let memory = Memory {
+            id: "id".to_string(),
+            conversation_id: "trip".to_string(),
+            text: "The user prefers window seats".to_string(),
+            metadata: vec![("source".to_string(), "chat".to_string())],
+        };
+        let recalled = from_search_result(SearchResult {
+            id: "id".to_string(),
+            score: 0.9,
+            vector: None,
+            metadata: Some(to_metadata(&memory)),
+        })
+        .unwrap();
+        assert_eq!(recalled.memory, memory);
+        assert_eq!(recalled.score, 0.9);
+
+        assert_eq!(
+            from_search_result(SearchResult {
+                id: "other".to_string(),
+                score: 0.5,
+                vector: None,
+                metadata: None,
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::exports::golem::memory::memory::MemoryError;
+use crate::golem::llm::llm::{ChatEvent, Config, ContentPart, Message, Role};
+
+const INSTRUCTIONS: &str = "You maintain the memory of a long-running conversation. Write a \
+    concise summary of the conversation you are given, keeping the facts, decisions, preferences \
+    and open questions needed to continue it. If a previous summary is given, extend it with the \
+    new messages. Reply with the summary only.";
+
+/// Summarizes the messages with the imported `golem:llm` provider, extending `previous`
+pub fn summarize(
+    previous: Option<&str>,
+    messages: &[Message],
+    config: &Config,
+) -> Result<String, MemoryError> {
+    if messages.is_empty() {
+        return previous.map(str::to_string).ok_or_else(|| {
+            MemoryError::InvalidRequest("There are no messages to summarize".to_string())
+        });
+    }
+
+    // The summarizer must answer with text, so the tools, the response format and the logprobs
+    // of the chat's config are not used
+    let config = Config {
+        tools: vec![],
+        tool_choice: None,
+        parallel_tool_calls: None,
+        response_format: None,
+        logprobs: None,
+        ..config.clone()
+    };
+    match crate::golem::llm::llm::send(&summary_request(previous, messages), &config) {
+        ChatEvent::Message(response) => {
+            let summary = response
+                .content
+                .iter()
+                .filter_map(|part| match part {
+                    ContentPart::Text(text) => Some(text.as_str()),
+                    ContentPart::CitedText(cited) => Some(cited.text.as_str()),
+                    ContentPart::Image(_)
+                    | ContentPart::Audio(_)
+                    | ContentPart::BuiltInToolResult(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let summary = summary.trim();
+            if summary.is_empty() {
+                Err(MemoryError::SummarizerFailed(
+                    "The LLM returned an empty summary".to_string(),
+                ))
+            } else {
+                Ok(summary.to_string())
+            }
+        }
+        ChatEvent::ToolRequest(_) => Err(MemoryError::SummarizerFailed(
+            "The LLM requested tool calls instead of writing a summary".to_string(),
+        )),
+        ChatEvent::Error(error) => Err(MemoryError::SummarizerFailed(error.message)),
+    }
+}
+
+fn summary_request(previous: Option<&str>, messages: &[Message]) -> Vec<Message> {
+    let mut prompt = String::new();
+    if let Some(previous) = previous {
+        prompt.push_str(&format!("Previous summary:\n{previous}\n\n"));
+    }
+    prompt.push_str(&format!("Conversation:\n{}", transcript(messages)));
+    vec![
+        text_message(Role::System, INSTRUCTIONS.to_string()),
+        text_message(Role::User, prompt),
+    ]
+}
+
+/// Renders the messages as plain text, one message per paragraph
+fn transcript(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+                Role::System => "system",
+                Role::Tool => "tool",
+            };
+            let speaker = match &message.name {
+                Some(name) => format!("{role} ({name})"),
+                None => role.to_string(),
+            };
+            let content = message
+                .content
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text(text) => text.as_str(),
+                    ContentPart::CitedText(cited) => cited.text.as_str(),
+                    ContentPart::Image(_) => "[image]",
+                    ContentPart::Audio(_) => "[audio]",
+                    ContentPart::BuiltInToolResult(_) => "[tool result]",
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!("{speaker}: {content}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn text_message(role: Role, text: String) -> Message {
+    Message {
+        role,
+        name: None,
+        content: vec![ContentPart::Text(text)],
+        cache_control: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::llm::llm::{ContentPart, ImageReference, ImageUrl, Message, Role};
+    use crate::summarizer::{summary_request, transcript};
+
+    #[test]
+    fn renders_transcripts() {
+        let messages = vec![
+            Message {
+                role: Role::User,
+                name: Some("jane".to_string()),
+                content: vec![
+                    ContentPart::Text("What is on this picture?".to_string()),
+                    ContentPart::Image(ImageReference::Url(ImageUrl {
+                        url: "https://example.com/cat.png".to_string(),
+                        detail: None,
+                    })),
+                ],
+                cache_control: None,
+            },
+            Message {
+                role: Role::Assistant,
+                name: None,
+                content: vec![ContentPart::Text("A cat.".to_string())],
+                cache_control: None,
+            },
+        ];
+        assert_eq!(
+            transcript(&messages),
+            "user (jane): What is on this picture? [image]\n\nassistant: A cat."
+        );
+    }
+
+    #[test]
+    fn includes_the_previous_summary() {
+        let messages = vec![Message {
+            role: Role::User,
+            name: None,
+            content: vec![ContentPart::Text("Book the flight.".to_string())],
+            cache_control: None,
+        }];
+        let request = summary_request(Some("The user plans a trip."), &messages);
+        assert_eq!(request.len(), 2);
+        assert_eq!(
+            request[1].content,
+            vec![ContentPart::Text(
+                "Previous summary:\nThe user plans a trip.\n\nConversation:\nuser: Book the flight."
+                    .to_string()
+            )]
+        );
+    }
+}
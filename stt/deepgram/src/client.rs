@@ -0,0 +1,176 @@
+use golem_stt::error::{from_http_status, from_reqwest_error};
+use golem_stt::golem::stt::stt::{Error, ErrorCode};
+use golem_stt::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://api.deepgram.com";
+
+/// The Deepgram API client for transcribing pre-recorded audio.
+///
+/// Based on https://developers.deepgram.com/reference/speech-to-text-api/listen
+pub struct ListenApi {
+    api_key: String,
+    client: Client,
+}
+
+impl ListenApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    /// Transcribes the audio. `query` holds the feature parameters of the request, and
+    /// `content_type` the MIME type of the audio.
+    pub fn listen(
+        &self,
+        audio: Vec<u8>,
+        content_type: &str,
+        query: &[(String, String)],
+    ) -> Result<ListenResponse, Error> {
+        trace!(
+            "Sending request to Deepgram API: {query:?} content_type={content_type} bytes={}",
+            audio.len()
+        );
+
+        let response: Response = send_with_retry(|| {
+            self.client
+                .request(Method::POST, format!("{BASE_URL}/v1/listen"))
+                .query(query)
+                .header("Authorization", format!("Token {}", self.api_key))
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(audio.clone())
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!("Received {status} response from Deepgram API: {raw_body:?}");
+        Err(from_http_status(status, raw_body))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListenResponse {
+    pub metadata: Metadata,
+    pub results: Results,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Metadata {
+    #[serde(default)]
+    pub duration: Option<f32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Results {
+    pub channels: Vec<Channel>,
+    #[serde(default)]
+    pub utterances: Vec<Utterance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Channel {
+    #[serde(default)]
+    pub detected_language: Option<String>,
+    pub alternatives: Vec<Alternative>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Alternative {
+    pub transcript: String,
+    #[serde(default)]
+    pub words: Vec<WordResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Utterance {
+    pub start: f32,
+    pub end: f32,
+    pub transcript: String,
+    #[serde(default)]
+    pub speaker: Option<u32>,
+    #[serde(default)]
+    pub words: Vec<WordResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WordResponse {
+    pub word: String,
+    #[serde(default)]
+    pub punctuated_word: Option<String>,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+    #[serde(default)]
+    pub speaker: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ListenResponse};
+    use golem_stt::golem::stt::stt::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn parses_response_with_utterances() {
+        let response = parse_response_body::<ListenResponse>(
+            StatusCode::OK,
+            r#"{
+                "metadata": {"request_id": "1", "duration": 2.5, "channels": 1},
+                "results": {
+                    "channels": [{"detected_language": "en", "alternatives": [{"transcript": "Hello there.", "confidence": 0.99, "words": [
+                        {"word": "hello", "start": 0.1, "end": 0.5, "confidence": 0.99, "speaker": 0, "punctuated_word": "Hello"},
+                        {"word": "there", "start": 0.6, "end": 1.0, "confidence": 0.97, "speaker": 0, "punctuated_word": "there."}
+                    ]}]}],
+                    "utterances": [{"start": 0.1, "end": 1.0, "confidence": 0.98, "channel": 0, "transcript": "Hello there.", "speaker": 0, "id": "a", "words": []}]
+                }
+            }"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(response.metadata.duration, Some(2.5));
+        assert_eq!(response.results.utterances[0].speaker, Some(0));
+        assert_eq!(
+            response.results.channels[0].alternatives[0].words[1]
+                .punctuated_word
+                .as_deref(),
+            Some("there.")
+        );
+    }
+
+    #[test]
+    fn maps_errors() {
+        let error = parse_response_body::<ListenResponse>(
+            StatusCode::UNAUTHORIZED,
+            r#"{"err_code":"INVALID_AUTH","err_msg":"Invalid credentials."}"#.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::AuthenticationFailed);
+    }
+}
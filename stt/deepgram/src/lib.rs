@@ -0,0 +1,201 @@
+use crate::client::{ListenApi, ListenResponse, WordResponse};
+use golem_rust::wasm_rpc::Pollable;
+use golem_stt::config::{mime_type, with_config_key};
+use golem_stt::durability::{DurableSTT, ExtendedGuest};
+use golem_stt::golem::stt::stt::{
+    Audio, AudioFormat, Config, Error, ErrorCode, Guest, Segment, Transcription,
+    TranscriptionStream as WitTranscriptionStream, Word,
+};
+use golem_stt::transcription_stream::TranscriptionStream;
+use golem_stt::LOGGING_STATE;
+use log::warn;
+
+mod client;
+
+const DEFAULT_MODEL: &str = "nova-3";
+
+struct DeepgramComponent;
+
+impl DeepgramComponent {
+    const ENV_VAR_NAME: &'static str = "DEEPGRAM_API_KEY";
+
+    /// The query parameters of the request. Provider options are passed as additional query
+    /// parameters, so any Deepgram feature can be enabled with them.
+    fn query(audio: &Audio, config: &Config) -> Result<Vec<(String, String)>, Error> {
+        let mut query = vec![
+            (
+                "model".to_string(),
+                config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            ),
+            ("smart_format".to_string(), "true".to_string()),
+            ("utterances".to_string(), "true".to_string()),
+        ];
+        match &config.language {
+            Some(language) => query.push(("language".to_string(), language.clone())),
+            None => query.push(("detect_language".to_string(), "true".to_string())),
+        }
+        if config.diarization {
+            query.push(("diarize".to_string(), "true".to_string()));
+        }
+        if config.prompt.is_some() {
+            warn!("Deepgram does not support prompts, ignoring it");
+        }
+
+        if audio.format == AudioFormat::Pcm {
+            let (Some(sample_rate), Some(channels)) = (audio.sample_rate, audio.channels) else {
+                return Err(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: "Raw PCM audio requires sample-rate and channels".to_string(),
+                    provider_error_json: None,
+                });
+            };
+            query.push(("encoding".to_string(), "linear16".to_string()));
+            query.push(("sample_rate".to_string(), sample_rate.to_string()));
+            query.push(("channels".to_string(), channels.to_string()));
+        }
+
+        query.extend(
+            config
+                .provider_options
+                .iter()
+                .map(|kv| (kv.key.clone(), kv.value.clone())),
+        );
+        Ok(query)
+    }
+
+    fn listen(audio: Audio, config: &Config) -> Result<Transcription, Error> {
+        with_config_key(Self::ENV_VAR_NAME, Err, |api_key| {
+            let client = ListenApi::new(api_key);
+            let query = Self::query(&audio, config)?;
+            let response = client.listen(audio.data, mime_type(audio.format), &query)?;
+            Ok(transcription_from_response(response, config))
+        })
+    }
+}
+
+fn word_from_response(word: WordResponse) -> Word {
+    Word {
+        text: word.punctuated_word.unwrap_or(word.word),
+        start: word.start,
+        end: word.end,
+        speaker: word.speaker.map(|speaker| speaker.to_string()),
+        confidence: Some(word.confidence),
+    }
+}
+
+fn transcription_from_response(response: ListenResponse, config: &Config) -> Transcription {
+    let channel = response.results.channels.into_iter().next();
+    let language = config
+        .language
+        .clone()
+        .or_else(|| channel.as_ref().and_then(|c| c.detected_language.clone()));
+    let text = channel
+        .and_then(|channel| channel.alternatives.into_iter().next())
+        .map(|alternative| alternative.transcript)
+        .unwrap_or_default();
+
+    let segments = response
+        .results
+        .utterances
+        .into_iter()
+        .map(|utterance| Segment {
+            text: utterance.transcript,
+            start: utterance.start,
+            end: utterance.end,
+            speaker: utterance
+                .speaker
+                .filter(|_| config.diarization)
+                .map(|speaker| speaker.to_string()),
+            words: if config.word_timestamps {
+                utterance
+                    .words
+                    .into_iter()
+                    .map(word_from_response)
+                    .collect()
+            } else {
+                vec![]
+            },
+        })
+        .collect();
+
+    Transcription {
+        text,
+        language,
+        duration: response.metadata.duration,
+        segments,
+    }
+}
+
+impl Guest for DeepgramComponent {
+    type TranscriptionStream = TranscriptionStream;
+
+    fn transcribe(audio: Audio, config: Config) -> Result<Transcription, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        Self::listen(audio, &config)
+    }
+
+    fn transcribe_stream(audio: Audio, config: Config) -> WitTranscriptionStream {
+        WitTranscriptionStream::new(Self::unwrapped_transcribe_stream(audio, config))
+    }
+}
+
+impl ExtendedGuest for DeepgramComponent {
+    /// Deepgram only streams live transcripts through WebSockets, which are not available to
+    /// components, so the stream returns the utterances of a pre-recorded transcription.
+    fn unwrapped_transcribe_stream(audio: Audio, config: Config) -> TranscriptionStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+        match Self::listen(audio, &config) {
+            Ok(transcription) => TranscriptionStream::from_transcription(transcription),
+            Err(error) => TranscriptionStream::failed(error),
+        }
+    }
+
+    fn subscribe(stream: &Self::TranscriptionStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+type DurableDeepgramComponent = DurableSTT<DeepgramComponent>;
+
+golem_stt::export_stt!(DurableDeepgramComponent with_types_in golem_stt);
+
+#[cfg(test)]
+mod tests {
+    use crate::client::ListenResponse;
+    use crate::transcription_from_response;
+    use golem_stt::golem::stt::stt::Config;
+
+    #[test]
+    fn converts_utterances_to_segments() {
+        let response: ListenResponse = serde_json::from_str(
+            r#"{
+                "metadata": {"duration": 2.0},
+                "results": {
+                    "channels": [{"detected_language": "de", "alternatives": [{"transcript": "Hallo. Ja.", "words": []}]}],
+                    "utterances": [
+                        {"start": 0.0, "end": 0.8, "transcript": "Hallo.", "speaker": 0, "words": [{"word": "hallo", "punctuated_word": "Hallo.", "start": 0.0, "end": 0.8, "confidence": 0.9, "speaker": 0}]},
+                        {"start": 1.0, "end": 1.5, "transcript": "Ja.", "speaker": 1, "words": []}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        let config = Config {
+            model: None,
+            language: None,
+            diarization: true,
+            word_timestamps: true,
+            prompt: None,
+            provider_options: vec![],
+        };
+
+        let transcription = transcription_from_response(response, &config);
+        assert_eq!(transcription.language.as_deref(), Some("de"));
+        assert_eq!(transcription.segments.len(), 2);
+        assert_eq!(transcription.segments[1].speaker.as_deref(), Some("1"));
+        assert_eq!(transcription.segments[0].words[0].text, "Hallo.");
+    }
+}
@@ -0,0 +1,289 @@
+use golem_stt::error::{from_http_status, from_reqwest_error};
+use golem_stt::golem::stt::stt::{Error, ErrorCode};
+use golem_stt::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const BASE_URL: &str = "https://api.openai.com";
+
+/// The OpenAI API client for transcribing audio.
+///
+/// Based on https://platform.openai.com/docs/api-reference/audio/createTranscription
+pub struct TranscriptionApi {
+    openai_api_key: String,
+    client: Client,
+}
+
+impl TranscriptionApi {
+    pub fn new(openai_api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            openai_api_key,
+            client,
+        }
+    }
+
+    /// Sends the request and returns the response. When `request.stream` is set, the response is
+    /// a stream of server-sent events.
+    pub fn create_transcription(&self, request: &TranscriptionRequest) -> Result<Response, Error> {
+        trace!(
+            "Sending request to OpenAI API: model={} format={} stream={} bytes={}",
+            request.model,
+            request.response_format,
+            request.stream,
+            request.file.len()
+        );
+
+        let (content_type, body) = request.to_form().finish();
+        send_with_retry(|| {
+            self.client
+                .request(Method::POST, format!("{BASE_URL}/v1/audio/transcriptions"))
+                .bearer_auth(&self.openai_api_key)
+                .header(reqwest::header::CONTENT_TYPE, &content_type)
+                .body(body.clone())
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))
+    }
+
+    pub fn transcribe(
+        &self,
+        request: &TranscriptionRequest,
+    ) -> Result<TranscriptionResponse, Error> {
+        let response = self.create_transcription(request)?;
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!("Received {status} response from OpenAI API: {raw_body:?}");
+        Err(from_http_status(status, raw_body))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscriptionRequest {
+    pub file: Vec<u8>,
+    pub file_name: String,
+    pub mime_type: String,
+    pub model: String,
+    pub language: Option<String>,
+    pub prompt: Option<String>,
+    pub response_format: String,
+    pub timestamp_granularities: Vec<String>,
+    pub temperature: Option<String>,
+    pub chunking_strategy: Option<String>,
+    pub stream: bool,
+}
+
+impl TranscriptionRequest {
+    fn to_form(&self) -> MultipartForm {
+        let mut form = MultipartForm::new();
+        form.file("file", &self.file_name, &self.mime_type, &self.file);
+        form.text("model", &self.model);
+        form.text("response_format", &self.response_format);
+        if let Some(language) = &self.language {
+            form.text("language", language);
+        }
+        if let Some(prompt) = &self.prompt {
+            form.text("prompt", prompt);
+        }
+        for granularity in &self.timestamp_granularities {
+            form.text("timestamp_granularities[]", granularity);
+        }
+        if let Some(temperature) = &self.temperature {
+            form.text("temperature", temperature);
+        }
+        if let Some(chunking_strategy) = &self.chunking_strategy {
+            form.text("chunking_strategy", chunking_strategy);
+        }
+        if self.stream {
+            form.text("stream", "true");
+        }
+        form
+    }
+}
+
+/// Builds a `multipart/form-data` request body
+struct MultipartForm {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartForm {
+    fn new() -> Self {
+        Self {
+            boundary: "----golem-stt-form-boundary-7MA4YWxkTrZu0gW".to_string(),
+            body: Vec::new(),
+        }
+    }
+
+    fn text(&mut self, name: &str, value: &str) {
+        self.part_header(&format!("form-data; name=\"{name}\""), None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    fn file(&mut self, name: &str, file_name: &str, mime_type: &str, data: &[u8]) {
+        self.part_header(
+            &format!("form-data; name=\"{name}\"; filename=\"{file_name}\""),
+            Some(mime_type),
+        );
+        self.body.extend_from_slice(data);
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    fn part_header(&mut self, disposition: &str, content_type: Option<&str>) {
+        self.body
+            .extend_from_slice(format!("--{}\r\n", self.boundary).as_bytes());
+        self.body
+            .extend_from_slice(format!("Content-Disposition: {disposition}\r\n").as_bytes());
+        if let Some(content_type) = content_type {
+            self.body
+                .extend_from_slice(format!("Content-Type: {content_type}\r\n").as_bytes());
+        }
+        self.body.extend_from_slice(b"\r\n");
+    }
+
+    /// Returns the content type header value and the body
+    fn finish(mut self) -> (String, Vec<u8>) {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        (
+            format!("multipart/form-data; boundary={}", self.boundary),
+            self.body,
+        )
+    }
+}
+
+/// The response of the `json`, `verbose_json` and `diarized_json` formats
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscriptionResponse {
+    pub text: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub duration: Option<f32>,
+    #[serde(default)]
+    pub segments: Vec<SegmentResponse>,
+    #[serde(default)]
+    pub words: Vec<WordResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SegmentResponse {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    #[serde(default)]
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WordResponse {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum TranscriptionStreamEvent {
+    #[serde(rename = "transcript.text.delta")]
+    Delta { delta: String },
+    #[serde(rename = "transcript.text.segment")]
+    Segment(SegmentResponse),
+    #[serde(rename = "transcript.text.done")]
+    Done { text: String },
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{
+        parse_response_body, TranscriptionRequest, TranscriptionResponse, TranscriptionStreamEvent,
+    };
+    use reqwest::StatusCode;
+
+    #[test]
+    fn encodes_multipart_form() {
+        let request = TranscriptionRequest {
+            file: b"RIFF".to_vec(),
+            file_name: "audio.wav".to_string(),
+            mime_type: "audio/wav".to_string(),
+            model: "whisper-1".to_string(),
+            language: None,
+            prompt: None,
+            response_format: "verbose_json".to_string(),
+            timestamp_granularities: vec!["word".to_string()],
+            temperature: None,
+            chunking_strategy: None,
+            stream: false,
+        };
+        let (content_type, body) = request.to_form().finish();
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\nContent-Type: audio/wav\r\n\r\nRIFF\r\n"
+        )));
+        assert!(body.contains(
+            "Content-Disposition: form-data; name=\"timestamp_granularities[]\"\r\n\r\nword\r\n"
+        ));
+        assert!(body.ends_with(&format!("--{boundary}--\r\n")));
+    }
+
+    #[test]
+    fn parses_verbose_response() {
+        let response = parse_response_body::<TranscriptionResponse>(
+            StatusCode::OK,
+            r#"{"task":"transcribe","language":"english","duration":1.5,"text":"Hello there.","segments":[{"id":0,"seek":0,"start":0.0,"end":1.5,"text":" Hello there.","tokens":[50364],"temperature":0.0}],"words":[{"word":"Hello","start":0.0,"end":0.6},{"word":"there","start":0.6,"end":1.2}]}"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(response.language.as_deref(), Some("english"));
+        assert_eq!(response.segments.len(), 1);
+        assert_eq!(response.words[1].word, "there");
+    }
+
+    #[test]
+    fn parses_stream_events() {
+        let delta: TranscriptionStreamEvent =
+            serde_json::from_str(r#"{"type":"transcript.text.delta","delta":"Hel","logprobs":[]}"#)
+                .unwrap();
+        assert!(matches!(delta, TranscriptionStreamEvent::Delta { delta } if delta == "Hel"));
+        let segment: TranscriptionStreamEvent = serde_json::from_str(
+            r#"{"type":"transcript.text.segment","id":"seg_0","start":0.0,"end":1.2,"text":"Hello","speaker":"A"}"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(segment, TranscriptionStreamEvent::Segment(segment) if segment.speaker.as_deref() == Some("A"))
+        );
+        let other: TranscriptionStreamEvent =
+            serde_json::from_str(r#"{"type":"transcript.text.usage"}"#).unwrap();
+        assert!(matches!(other, TranscriptionStreamEvent::Other));
+    }
+}
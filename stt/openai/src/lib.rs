@@ -0,0 +1,187 @@
+use crate::client::{
+    SegmentResponse, TranscriptionApi, TranscriptionRequest, TranscriptionResponse,
+    TranscriptionStreamEvent, WordResponse,
+};
+use golem_rust::wasm_rpc::Pollable;
+use golem_stt::config::{file_extension, mime_type, provider_option, with_config_key};
+use golem_stt::durability::{DurableSTT, ExtendedGuest};
+use golem_stt::error::unsupported;
+use golem_stt::golem::stt::stt::{
+    Audio, AudioFormat, Config, Error, Guest, Segment, StreamEvent, Transcription,
+    TranscriptionStream as WitTranscriptionStream, Word,
+};
+use golem_stt::transcription_stream::TranscriptionStream;
+use golem_stt::LOGGING_STATE;
+
+mod client;
+
+/// The default model of `transcribe`, the only one returning timestamps
+const DEFAULT_MODEL: &str = "whisper-1";
+/// The default model of `transcribe-stream`, as `whisper-1` does not support streaming
+const DEFAULT_STREAMING_MODEL: &str = "gpt-4o-mini-transcribe";
+/// The default model when diarization is requested
+const DEFAULT_DIARIZATION_MODEL: &str = "gpt-4o-transcribe-diarize";
+
+struct OpenAIComponent;
+
+impl OpenAIComponent {
+    const ENV_VAR_NAME: &'static str = "OPENAI_API_KEY";
+
+    fn request(audio: Audio, config: &Config, stream: bool) -> Result<TranscriptionRequest, Error> {
+        if audio.format == AudioFormat::Pcm {
+            return Err(unsupported("raw PCM audio"));
+        }
+        if config.diarization && config.word_timestamps {
+            return Err(unsupported("word timestamps together with diarization"));
+        }
+
+        let model = match &config.model {
+            Some(model) => model.clone(),
+            None if config.diarization => DEFAULT_DIARIZATION_MODEL.to_string(),
+            None if stream => DEFAULT_STREAMING_MODEL.to_string(),
+            None => DEFAULT_MODEL.to_string(),
+        };
+        let diarize = model.contains("diarize");
+        if config.diarization && !diarize {
+            return Err(unsupported(format!("diarization with model {model}")));
+        }
+        if config.word_timestamps && model != "whisper-1" {
+            return Err(unsupported(format!("word timestamps with model {model}")));
+        }
+
+        let response_format = if model == "whisper-1" {
+            "verbose_json"
+        } else if diarize {
+            "diarized_json"
+        } else {
+            "json"
+        };
+        let timestamp_granularities = if config.word_timestamps {
+            vec!["segment".to_string(), "word".to_string()]
+        } else {
+            vec![]
+        };
+
+        Ok(TranscriptionRequest {
+            file_name: format!("audio.{}", file_extension(audio.format)),
+            mime_type: mime_type(audio.format).to_string(),
+            file: audio.data,
+            stream: stream && model != "whisper-1",
+            model,
+            language: config.language.clone(),
+            prompt: config.prompt.clone(),
+            response_format: response_format.to_string(),
+            timestamp_granularities,
+            temperature: provider_option(config, "temperature"),
+            chunking_strategy: diarize.then(|| "auto".to_string()),
+        })
+    }
+}
+
+fn segment_from_response(segment: SegmentResponse, words: &[WordResponse]) -> Segment {
+    Segment {
+        text: segment.text.trim().to_string(),
+        start: segment.start,
+        end: segment.end,
+        words: words
+            .iter()
+            .filter(|word| word.start >= segment.start && word.start < segment.end)
+            .map(|word| Word {
+                text: word.word.clone(),
+                start: word.start,
+                end: word.end,
+                speaker: segment.speaker.clone(),
+                confidence: None,
+            })
+            .collect(),
+        speaker: segment.speaker,
+    }
+}
+
+fn transcription_from_response(response: TranscriptionResponse) -> Transcription {
+    let segments = response
+        .segments
+        .into_iter()
+        .map(|segment| segment_from_response(segment, &response.words))
+        .collect();
+    Transcription {
+        text: response.text,
+        language: response.language,
+        duration: response.duration,
+        segments,
+    }
+}
+
+fn decode_stream_event(raw: &str) -> Result<Vec<StreamEvent>, String> {
+    let event = serde_json::from_str::<TranscriptionStreamEvent>(raw)
+        .map_err(|err| format!("Failed to deserialize stream event: {err}"))?;
+    Ok(match event {
+        TranscriptionStreamEvent::Delta { delta } => vec![StreamEvent::Partial(delta)],
+        TranscriptionStreamEvent::Segment(segment) => {
+            vec![StreamEvent::Segment(segment_from_response(segment, &[]))]
+        }
+        TranscriptionStreamEvent::Done { .. } => vec![StreamEvent::Finish],
+        TranscriptionStreamEvent::Other => vec![],
+    })
+}
+
+impl Guest for OpenAIComponent {
+    type TranscriptionStream = TranscriptionStream;
+
+    fn transcribe(audio: Audio, config: Config) -> Result<Transcription, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = TranscriptionApi::new(openai_api_key);
+            let request = Self::request(audio, &config, false)?;
+            let response = client.transcribe(&request)?;
+            Ok(transcription_from_response(response))
+        })
+    }
+
+    fn transcribe_stream(audio: Audio, config: Config) -> WitTranscriptionStream {
+        WitTranscriptionStream::new(Self::unwrapped_transcribe_stream(audio, config))
+    }
+}
+
+impl ExtendedGuest for OpenAIComponent {
+    fn unwrapped_transcribe_stream(audio: Audio, config: Config) -> TranscriptionStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(
+            Self::ENV_VAR_NAME,
+            TranscriptionStream::failed,
+            |openai_api_key| {
+                let client = TranscriptionApi::new(openai_api_key);
+                let request = match Self::request(audio, &config, true) {
+                    Ok(request) => request,
+                    Err(error) => return TranscriptionStream::failed(error),
+                };
+                if request.stream {
+                    match client.create_transcription(&request) {
+                        Ok(response) => {
+                            TranscriptionStream::from_events(response, decode_stream_event)
+                        }
+                        Err(error) => TranscriptionStream::failed(error),
+                    }
+                } else {
+                    // The model does not support streaming, so the segments are returned at once
+                    match client.transcribe(&request) {
+                        Ok(response) => TranscriptionStream::from_transcription(
+                            transcription_from_response(response),
+                        ),
+                        Err(error) => TranscriptionStream::failed(error),
+                    }
+                }
+            },
+        )
+    }
+
+    fn subscribe(stream: &Self::TranscriptionStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+type DurableOpenAIComponent = DurableSTT<OpenAIComponent>;
+
+golem_stt::export_stt!(DurableOpenAIComponent with_types_in golem_stt);
@@ -0,0 +1,55 @@
+use crate::golem::stt::stt::{AudioFormat, Config, Error, ErrorCode};
+use golem_ai_common::config::config_value;
+use std::ffi::OsStr;
+
+/// Gets an expected configuration value from the environment, and fails if its is not found
+/// using the `fail` function. Otherwise, it runs `succeed` with the configuration value.
+pub fn with_config_key<R>(
+    key: impl AsRef<OsStr>,
+    fail: impl FnOnce(Error) -> R,
+    succeed: impl FnOnce(String) -> R,
+) -> R {
+    match config_value(key) {
+        Ok(value) => succeed(value),
+        Err(message) => fail(Error {
+            code: ErrorCode::InternalError,
+            message,
+            provider_error_json: None,
+        }),
+    }
+}
+
+/// Gets the value of a provider option passed in the request's `Config`
+pub fn provider_option(config: &Config, key: &str) -> Option<String> {
+    config
+        .provider_options
+        .iter()
+        .find(|kv| kv.key == key)
+        .map(|kv| kv.value.clone())
+}
+
+pub fn mime_type(format: AudioFormat) -> &'static str {
+    match format {
+        AudioFormat::Wav => "audio/wav",
+        AudioFormat::Mp3 => "audio/mpeg",
+        AudioFormat::Flac => "audio/flac",
+        AudioFormat::Ogg => "audio/ogg",
+        AudioFormat::Webm => "audio/webm",
+        AudioFormat::M4a => "audio/mp4",
+        AudioFormat::Pcm => "audio/pcm",
+    }
+}
+
+/// File name extension of the audio format, for providers detecting the format from an uploaded
+/// file's name
+pub fn file_extension(format: AudioFormat) -> &'static str {
+    match format {
+        AudioFormat::Wav => "wav",
+        AudioFormat::Mp3 => "mp3",
+        AudioFormat::Flac => "flac",
+        AudioFormat::Ogg => "ogg",
+        AudioFormat::Webm => "webm",
+        AudioFormat::M4a => "m4a",
+        AudioFormat::Pcm => "pcm",
+    }
+}
@@ -0,0 +1,492 @@
+use crate::golem::stt::stt::{Audio, Config, Guest};
+use golem_rust::wasm_rpc::Pollable;
+use std::marker::PhantomData;
+
+/// Wraps an STT implementation with custom durability
+pub struct DurableSTT<Impl> {
+    phantom: PhantomData<Impl>,
+}
+
+/// Trait to be implemented in addition to the STT `Guest` trait when wrapping it with `DurableSTT`.
+pub trait ExtendedGuest: Guest + 'static {
+    /// Creates an instance of the STT specific `TranscriptionStream` without wrapping it in a
+    /// `Resource`
+    fn unwrapped_transcribe_stream(audio: Audio, config: Config) -> Self::TranscriptionStream;
+
+    fn subscribe(stream: &Self::TranscriptionStream) -> Pollable;
+}
+
+/// When the durability feature flag is off, wrapping with `DurableSTT` is just a passthrough
+#[cfg(not(feature = "durability"))]
+mod passthrough_impl {
+    use crate::durability::{DurableSTT, ExtendedGuest};
+    use crate::golem::stt::stt::{Audio, Config, Error, Guest, Transcription, TranscriptionStream};
+
+    impl<Impl: ExtendedGuest> Guest for DurableSTT<Impl> {
+        type TranscriptionStream = Impl::TranscriptionStream;
+
+        fn transcribe(audio: Audio, config: Config) -> Result<Transcription, Error> {
+            Impl::transcribe(audio, config)
+        }
+
+        fn transcribe_stream(audio: Audio, config: Config) -> TranscriptionStream {
+            Impl::transcribe_stream(audio, config)
+        }
+    }
+}
+
+/// When the durability feature flag is on, wrapping with `DurableSTT` adds custom durability
+/// on top of the provider-specific STT implementation using Golem's special host functions and
+/// the `golem-rust` helper library.
+///
+/// The transcription is stored in the oplog as the result of `transcribe`, and the stream events
+/// as the results of the `get-next` calls. The audio itself is not stored, only its format and
+/// size, as the worker passes it again to the replayed calls. If the worker gets interrupted in
+/// the middle of a stream, the transcription is requested again when the replay reaches the end
+/// of the recorded events, and the partial text and segments already returned are skipped.
+#[cfg(feature = "durability")]
+mod durable_impl {
+    use crate::durability::{DurableSTT, ExtendedGuest};
+    use crate::golem::stt::stt::{
+        Audio, AudioFormat, Config, Error, Guest, GuestTranscriptionStream, StreamEvent,
+        Transcription, TranscriptionStream,
+    };
+    use golem_rust::bindings::golem::durability::durability::{
+        DurableFunctionType, LazyInitializedPollable,
+    };
+    use golem_rust::durability::Durability;
+    use golem_rust::wasm_rpc::Pollable;
+    use golem_rust::{with_persistence_level, FromValueAndType, IntoValue, PersistenceLevel};
+    use std::cell::RefCell;
+    use std::fmt::{Display, Formatter};
+
+    impl<Impl: ExtendedGuest> Guest for DurableSTT<Impl> {
+        type TranscriptionStream = DurableTranscriptionStream<Impl>;
+
+        fn transcribe(audio: Audio, config: Config) -> Result<Transcription, Error> {
+            let durability = Durability::<Transcription, Error>::new(
+                "golem_stt",
+                "transcribe",
+                DurableFunctionType::WriteRemote,
+            );
+            if durability.is_live() {
+                let input = TranscribeInput::new(&audio, &config);
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    Impl::transcribe(audio, config)
+                });
+                durability.persist(input, result)
+            } else {
+                durability.replay()
+            }
+        }
+
+        fn transcribe_stream(audio: Audio, config: Config) -> TranscriptionStream {
+            let durability = Durability::<NoOutput, UnusedError>::new(
+                "golem_stt",
+                "transcribe_stream",
+                DurableFunctionType::WriteRemote,
+            );
+            if durability.is_live() {
+                let input = TranscribeInput::new(&audio, &config);
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    TranscriptionStream::new(DurableTranscriptionStream::<Impl>::live(
+                        Impl::unwrapped_transcribe_stream(audio, config),
+                        Progress::default(),
+                    ))
+                });
+                let _ = durability.persist_infallible(input, NoOutput);
+                result
+            } else {
+                let _: NoOutput = durability.replay_infallible();
+                TranscriptionStream::new(DurableTranscriptionStream::<Impl>::replay(audio, config))
+            }
+        }
+    }
+
+    /// The amount of transcript already returned by a stream
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    struct Progress {
+        /// Number of characters returned in `partial` events
+        partial_chars: usize,
+        /// Number of `segment` events returned
+        segments: usize,
+    }
+
+    impl Progress {
+        fn is_empty(&self) -> bool {
+            self.partial_chars == 0 && self.segments == 0
+        }
+
+        fn record(&mut self, event: &StreamEvent) {
+            match event {
+                StreamEvent::Partial(text) => self.partial_chars += text.chars().count(),
+                StreamEvent::Segment(_) => self.segments += 1,
+                StreamEvent::Finish | StreamEvent::Error(_) => {}
+            }
+        }
+    }
+
+    /// Represents the durable transcription stream's state
+    ///
+    /// In live mode it directly reads the underlying provider stream, skipping the transcript
+    /// which was already returned before the worker got interrupted.
+    ///
+    /// In replay mode it counts the replayed transcript, and tracks the created pollables to be
+    /// able to reattach them to the new live stream when the switch to live mode happens.
+    enum DurableTranscriptionStreamState<Impl: ExtendedGuest> {
+        Live {
+            stream: Impl::TranscriptionStream,
+            pollables: Vec<LazyInitializedPollable>,
+            skip: Progress,
+        },
+        Replay {
+            audio: Audio,
+            config: Config,
+            pollables: Vec<LazyInitializedPollable>,
+            replayed: Progress,
+            finished: bool,
+        },
+    }
+
+    pub struct DurableTranscriptionStream<Impl: ExtendedGuest> {
+        state: RefCell<Option<DurableTranscriptionStreamState<Impl>>>,
+        subscription: RefCell<Option<Pollable>>,
+    }
+
+    impl<Impl: ExtendedGuest> DurableTranscriptionStream<Impl> {
+        fn live(stream: Impl::TranscriptionStream, skip: Progress) -> Self {
+            Self {
+                state: RefCell::new(Some(DurableTranscriptionStreamState::Live {
+                    stream,
+                    pollables: Vec::new(),
+                    skip,
+                })),
+                subscription: RefCell::new(None),
+            }
+        }
+
+        fn replay(audio: Audio, config: Config) -> Self {
+            Self {
+                state: RefCell::new(Some(DurableTranscriptionStreamState::Replay {
+                    audio,
+                    config,
+                    pollables: Vec::new(),
+                    replayed: Progress::default(),
+                    finished: false,
+                })),
+                subscription: RefCell::new(None),
+            }
+        }
+
+        fn subscribe(&self) -> Pollable {
+            let mut state = self.state.borrow_mut();
+            match &mut *state {
+                Some(DurableTranscriptionStreamState::Live { stream, .. }) => {
+                    Impl::subscribe(stream)
+                }
+                Some(DurableTranscriptionStreamState::Replay { pollables, .. }) => {
+                    let lazy_pollable = LazyInitializedPollable::new();
+                    let pollable = lazy_pollable.subscribe();
+                    pollables.push(lazy_pollable);
+                    pollable
+                }
+                None => {
+                    unreachable!()
+                }
+            }
+        }
+    }
+
+    /// Drops the transcript already returned from `events`, decreasing `skip` by the dropped
+    /// amount
+    fn skip_transcript(events: Vec<StreamEvent>, skip: &mut Progress) -> Vec<StreamEvent> {
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                StreamEvent::Partial(text) if skip.partial_chars > 0 => {
+                    let skipped = text.chars().count().min(skip.partial_chars);
+                    skip.partial_chars -= skipped;
+                    let rest: String = text.chars().skip(skipped).collect();
+                    (!rest.is_empty()).then_some(StreamEvent::Partial(rest))
+                }
+                StreamEvent::Segment(_) if skip.segments > 0 => {
+                    skip.segments -= 1;
+                    None
+                }
+                other => Some(other),
+            })
+            .collect()
+    }
+
+    fn read_live<Impl: ExtendedGuest>(
+        stream: &Impl::TranscriptionStream,
+        skip: &mut Progress,
+    ) -> Option<Vec<StreamEvent>> {
+        with_persistence_level(PersistenceLevel::PersistNothing, || {
+            stream.get_next().and_then(|events| {
+                let events = skip_transcript(events, skip);
+                // A read consisting only of skipped events is reported as not ready yet
+                (!events.is_empty() || skip.is_empty()).then_some(events)
+            })
+        })
+    }
+
+    impl<Impl: ExtendedGuest> Drop for DurableTranscriptionStream<Impl> {
+        fn drop(&mut self) {
+            let _ = self.subscription.take();
+            match self.state.take() {
+                Some(DurableTranscriptionStreamState::Live {
+                    mut pollables,
+                    stream,
+                    ..
+                }) => {
+                    with_persistence_level(PersistenceLevel::PersistNothing, move || {
+                        pollables.clear();
+                        drop(stream);
+                    });
+                }
+                Some(DurableTranscriptionStreamState::Replay { mut pollables, .. }) => {
+                    pollables.clear();
+                }
+                None => {}
+            }
+        }
+    }
+
+    impl<Impl: ExtendedGuest> GuestTranscriptionStream for DurableTranscriptionStream<Impl> {
+        fn get_next(&self) -> Option<Vec<StreamEvent>> {
+            let durability = Durability::<Option<Vec<StreamEvent>>, UnusedError>::new(
+                "golem_stt",
+                "get_next",
+                DurableFunctionType::ReadRemote,
+            );
+            if durability.is_live() {
+                let mut state = self.state.borrow_mut();
+                let (result, new_live_stream) = match &mut *state {
+                    Some(DurableTranscriptionStreamState::Live { stream, skip, .. }) => {
+                        let result = read_live::<Impl>(stream, skip);
+                        (durability.persist_infallible(NoInput, result), None)
+                    }
+                    Some(DurableTranscriptionStreamState::Replay {
+                        audio,
+                        config,
+                        pollables,
+                        replayed,
+                        finished,
+                    }) => {
+                        if *finished {
+                            (durability.persist_infallible(NoInput, Some(vec![])), None)
+                        } else {
+                            let mut skip = *replayed;
+                            let (stream, first_live_result) =
+                                with_persistence_level(PersistenceLevel::PersistNothing, || {
+                                    let stream = Impl::unwrapped_transcribe_stream(
+                                        audio.clone(),
+                                        config.clone(),
+                                    );
+                                    for lazy_initialized_pollable in pollables.iter() {
+                                        lazy_initialized_pollable.set(Impl::subscribe(&stream));
+                                    }
+                                    let next = read_live::<Impl>(&stream, &mut skip);
+                                    (stream, next)
+                                });
+                            let result = durability.persist_infallible(NoInput, first_live_result);
+                            (result, Some((stream, skip)))
+                        }
+                    }
+                    None => {
+                        unreachable!()
+                    }
+                };
+
+                if let Some((stream, skip)) = new_live_stream {
+                    let pollables = match state.take() {
+                        Some(DurableTranscriptionStreamState::Live { pollables, .. }) => pollables,
+                        Some(DurableTranscriptionStreamState::Replay { pollables, .. }) => {
+                            pollables
+                        }
+                        None => {
+                            unreachable!()
+                        }
+                    };
+                    *state = Some(DurableTranscriptionStreamState::Live {
+                        stream,
+                        pollables,
+                        skip,
+                    });
+                }
+
+                result
+            } else {
+                let result: Option<Vec<StreamEvent>> = durability.replay_infallible();
+                let mut state = self.state.borrow_mut();
+                match &mut *state {
+                    Some(DurableTranscriptionStreamState::Live { .. }) => {
+                        unreachable!(
+                            "Durable transcription stream cannot be in live mode during replay"
+                        )
+                    }
+                    Some(DurableTranscriptionStreamState::Replay {
+                        replayed, finished, ..
+                    }) => {
+                        for event in result.iter().flatten() {
+                            replayed.record(event);
+                            if matches!(event, StreamEvent::Finish | StreamEvent::Error(_)) {
+                                *finished = true;
+                            }
+                        }
+                    }
+                    None => {
+                        unreachable!()
+                    }
+                }
+                result
+            }
+        }
+
+        fn blocking_get_next(&self) -> Vec<StreamEvent> {
+            let mut subscription = self.subscription.borrow_mut();
+            if subscription.is_none() {
+                *subscription = Some(self.subscribe());
+            }
+            let subscription = subscription.as_mut().unwrap();
+            loop {
+                subscription.block();
+                if let Some(events) = self.get_next() {
+                    break events;
+                }
+            }
+        }
+    }
+
+    /// The persisted input of the transcription calls, leaving out the audio data
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct TranscribeInput {
+        format: AudioFormat,
+        audio_bytes: u64,
+        config: Config,
+    }
+
+    impl TranscribeInput {
+        fn new(audio: &Audio, config: &Config) -> Self {
+            Self {
+                format: audio.format,
+                audio_bytes: audio.data.len() as u64,
+                config: config.clone(),
+            }
+        }
+    }
+
+    #[derive(Debug, IntoValue)]
+    struct NoInput;
+
+    #[derive(Debug, Clone, FromValueAndType, IntoValue)]
+    struct NoOutput;
+
+    #[derive(Debug, FromValueAndType, IntoValue)]
+    struct UnusedError;
+
+    impl Display for UnusedError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "UnusedError")
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::durability::durable_impl::{skip_transcript, Progress};
+        use crate::golem::stt::stt::{
+            Config, Error, ErrorCode, Kv, Segment, StreamEvent, Transcription, Word,
+        };
+        use golem_rust::value_and_type::{FromValueAndType, IntoValueAndType};
+        use std::fmt::Debug;
+
+        fn roundtrip_test<T: Debug + Clone + PartialEq + IntoValueAndType + FromValueAndType>(
+            value: T,
+        ) {
+            let vnt = value.clone().into_value_and_type();
+            let extracted = T::from_value_and_type(vnt).unwrap();
+            assert_eq!(value, extracted);
+        }
+
+        fn segment(text: &str) -> Segment {
+            Segment {
+                text: text.to_string(),
+                start: 0.5,
+                end: 1.75,
+                speaker: Some("0".to_string()),
+                words: vec![Word {
+                    text: text.to_string(),
+                    start: 0.5,
+                    end: 1.75,
+                    speaker: Some("0".to_string()),
+                    confidence: Some(0.98),
+                }],
+            }
+        }
+
+        #[test]
+        fn config_roundtrip() {
+            roundtrip_test(Config {
+                model: Some("whisper-1".to_string()),
+                language: Some("en".to_string()),
+                diarization: true,
+                word_timestamps: false,
+                prompt: None,
+                provider_options: vec![Kv {
+                    key: "temperature".to_string(),
+                    value: "0.2".to_string(),
+                }],
+            });
+        }
+
+        #[test]
+        fn transcription_roundtrip() {
+            roundtrip_test(Transcription {
+                text: "Hello".to_string(),
+                language: Some("en".to_string()),
+                duration: Some(1.75),
+                segments: vec![segment("Hello")],
+            });
+        }
+
+        #[test]
+        fn stream_event_roundtrip() {
+            roundtrip_test(StreamEvent::Partial("Hel".to_string()));
+            roundtrip_test(StreamEvent::Segment(segment("Hello")));
+            roundtrip_test(StreamEvent::Finish);
+            roundtrip_test(StreamEvent::Error(Error {
+                code: ErrorCode::RateLimitExceeded,
+                message: "Too many requests".to_string(),
+                provider_error_json: None,
+            }));
+        }
+
+        #[test]
+        fn skips_already_returned_transcript() {
+            let mut skip = Progress {
+                partial_chars: 4,
+                segments: 1,
+            };
+            let events = skip_transcript(
+                vec![
+                    StreamEvent::Partial("Hé".to_string()),
+                    StreamEvent::Partial("llo".to_string()),
+                    StreamEvent::Segment(segment("Hello")),
+                    StreamEvent::Segment(segment("world")),
+                    StreamEvent::Finish,
+                ],
+                &mut skip,
+            );
+            assert_eq!(skip, Progress::default());
+            assert_eq!(
+                events,
+                vec![
+                    StreamEvent::Partial("o".to_string()),
+                    StreamEvent::Segment(segment("world")),
+                    StreamEvent::Finish
+                ]
+            );
+        }
+    }
+}
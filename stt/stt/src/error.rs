@@ -0,0 +1,84 @@
+use crate::golem::stt::stt::{Error, ErrorCode};
+use golem_ai_common::error::{classify_status, StatusClass};
+use reqwest::StatusCode;
+use std::fmt::{Display, Formatter};
+
+/// Creates an `Error` value representing that something is unsupported
+pub fn unsupported(what: impl AsRef<str>) -> Error {
+    Error {
+        code: ErrorCode::Unsupported,
+        message: format!("Unsupported: {}", what.as_ref()),
+        provider_error_json: None,
+    }
+}
+
+pub fn from_reqwest_error(details: impl AsRef<str>, err: reqwest::Error) -> Error {
+    Error {
+        code: ErrorCode::InternalError,
+        message: format!("{}: {err}", details.as_ref()),
+        provider_error_json: None,
+    }
+}
+
+pub fn error_code_from_status(status: StatusCode) -> ErrorCode {
+    match classify_status(status) {
+        StatusClass::RateLimited => ErrorCode::RateLimitExceeded,
+        StatusClass::Unauthorized => ErrorCode::AuthenticationFailed,
+        StatusClass::NotFound | StatusClass::Conflict | StatusClass::InvalidRequest => {
+            ErrorCode::InvalidRequest
+        }
+        StatusClass::ProviderError => ErrorCode::InternalError,
+    }
+}
+
+/// Maps a failed HTTP response of a provider to an `Error`, keeping the raw response body
+pub fn from_http_status(status: StatusCode, body: String) -> Error {
+    Error {
+        code: error_code_from_status(status),
+        message: format!("Request failed with {status}"),
+        provider_error_json: Some(body),
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{error_code_from_status, from_http_status};
+    use crate::golem::stt::stt::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn maps_status_codes() {
+        assert_eq!(
+            error_code_from_status(StatusCode::UNAUTHORIZED),
+            ErrorCode::AuthenticationFailed
+        );
+        assert_eq!(
+            error_code_from_status(StatusCode::TOO_MANY_REQUESTS),
+            ErrorCode::RateLimitExceeded
+        );
+        assert_eq!(
+            error_code_from_status(StatusCode::UNPROCESSABLE_ENTITY),
+            ErrorCode::InvalidRequest
+        );
+        assert_eq!(
+            error_code_from_status(StatusCode::BAD_GATEWAY),
+            ErrorCode::InternalError
+        );
+    }
+
+    #[test]
+    fn keeps_the_response_body() {
+        let error = from_http_status(StatusCode::BAD_REQUEST, r#"{"detail":"bad model"}"#.into());
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.provider_error_json.as_deref(),
+            Some(r#"{"detail":"bad model"}"#)
+        );
+    }
+}
@@ -0,0 +1,80 @@
+/// Incremental parser of a `text/event-stream` response body.
+///
+/// Only the `data` fields are kept, as the transcription providers identify their events by a
+/// `type` field of the JSON payload. Multi-line data fields are joined with newlines.
+#[derive(Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next chunk of the body, returning the data of the events completed by it
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+
+            if line.is_empty() {
+                if !self.data.is_empty() {
+                    events.push(self.data.join("\n"));
+                    self.data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                self.data
+                    .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+        events
+    }
+
+    /// Returns the data of an event not terminated by an empty line at the end of the body
+    pub fn finish(&mut self) -> Option<String> {
+        // The buffer never contains a complete line, so it is the beginning of the last one
+        let rest = std::mem::take(&mut self.buffer);
+        let line = String::from_utf8_lossy(&rest);
+        if let Some(value) = line.trim_end_matches('\r').strip_prefix("data:") {
+            self.data
+                .push(value.strip_prefix(' ').unwrap_or(value).to_string());
+        }
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.data).join("\n"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_stream::SseParser;
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = SseParser::new();
+        assert!(parser.feed(b"data: {\"type\":\"tran").is_empty());
+        assert_eq!(
+            parser.feed(b"script.text.delta\"}\r\n\r\nevent: x\ndata: a\ndata: b\n\n"),
+            vec![
+                r#"{"type":"transcript.text.delta"}"#.to_string(),
+                "a\nb".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_unterminated_last_event() {
+        let mut parser = SseParser::new();
+        assert!(parser.feed(b": keep-alive\n\ndata: [DONE]").is_empty());
+        assert_eq!(parser.finish(), Some("[DONE]".to_string()));
+        assert_eq!(parser.finish(), None);
+    }
+}
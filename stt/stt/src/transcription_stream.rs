@@ -0,0 +1,195 @@
+use crate::error::from_http_status;
+use crate::event_stream::SseParser;
+use crate::golem::stt::stt::{
+    Error, ErrorCode, GuestTranscriptionStream, StreamEvent, Transcription,
+};
+use golem_rust::bindings::wasi::io::streams::{InputStream, StreamError};
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use reqwest::{Response, StatusCode};
+use std::cell::{Cell, RefCell};
+
+/// Decodes the data of a single server-sent event of a provider into stream events
+pub type DecodeEvent = fn(&str) -> Result<Vec<StreamEvent>, String>;
+
+/// A stream of transcription events, either decoded from a provider's server-sent events or
+/// produced from an already completed transcription.
+pub struct TranscriptionStream {
+    source: Source,
+    finished: Cell<bool>,
+}
+
+enum Source {
+    Events {
+        // The response has to be kept alive while its body stream is read
+        _response: Response,
+        stream: InputStream,
+        subscription: Pollable,
+        parser: RefCell<SseParser>,
+        decode: DecodeEvent,
+    },
+    Ready(RefCell<Option<Vec<StreamEvent>>>),
+}
+
+impl TranscriptionStream {
+    const CHUNK_SIZE: u64 = 8 * 1024;
+
+    /// Creates a stream from a `text/event-stream` response. Failed responses are read entirely
+    /// and turned into a failed stream.
+    pub fn from_events(response: Response, decode: DecodeEvent) -> Self {
+        let status = response.status();
+        if status != StatusCode::OK {
+            let body = response.text().unwrap_or_default();
+            return Self::failed(from_http_status(status, body));
+        }
+
+        let mut response = response;
+        let stream = unsafe {
+            std::mem::transmute::<reqwest::InputStream, InputStream>(
+                response.get_raw_input_stream(),
+            )
+        };
+        let subscription = stream.subscribe();
+        Self {
+            source: Source::Events {
+                _response: response,
+                stream,
+                subscription,
+                parser: RefCell::new(SseParser::new()),
+                decode,
+            },
+            finished: Cell::new(false),
+        }
+    }
+
+    /// Creates a stream returning the given events at once, for providers which cannot stream
+    /// their results
+    pub fn ready(events: Vec<StreamEvent>) -> Self {
+        Self {
+            source: Source::Ready(RefCell::new(Some(events))),
+            finished: Cell::new(false),
+        }
+    }
+
+    /// Creates a stream returning the segments of a completed transcription
+    pub fn from_transcription(transcription: Transcription) -> Self {
+        Self::ready(events_from_transcription(transcription))
+    }
+
+    pub fn failed(error: Error) -> Self {
+        Self::ready(vec![StreamEvent::Error(error)])
+    }
+
+    pub fn subscribe(&self) -> Pollable {
+        match &self.source {
+            Source::Events { stream, .. } => stream.subscribe(),
+            Source::Ready(_) => {
+                golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0)
+            }
+        }
+    }
+
+    fn decode_all(&self, decode: DecodeEvent, data: Vec<String>) -> Vec<StreamEvent> {
+        let mut events = Vec::new();
+        for data in data {
+            if data == "[DONE]" {
+                continue;
+            }
+            match decode(&data) {
+                Ok(decoded) => events.extend(decoded),
+                Err(message) => events.push(StreamEvent::Error(Error {
+                    code: ErrorCode::InternalError,
+                    message,
+                    provider_error_json: Some(data),
+                })),
+            }
+        }
+        if events
+            .iter()
+            .any(|event| matches!(event, StreamEvent::Finish | StreamEvent::Error(_)))
+        {
+            self.finished.set(true);
+        }
+        events
+    }
+}
+
+/// Converts a completed transcription to stream events, with one `segment` event per segment.
+/// Transcriptions without segments are returned as a single `partial` event.
+pub fn events_from_transcription(transcription: Transcription) -> Vec<StreamEvent> {
+    let mut events = if transcription.segments.is_empty() {
+        vec![StreamEvent::Partial(transcription.text)]
+    } else {
+        transcription
+            .segments
+            .into_iter()
+            .map(StreamEvent::Segment)
+            .collect()
+    };
+    events.push(StreamEvent::Finish);
+    events
+}
+
+impl GuestTranscriptionStream for TranscriptionStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        if self.finished.get() {
+            return Some(vec![]);
+        }
+
+        match &self.source {
+            Source::Events {
+                stream,
+                subscription,
+                parser,
+                decode,
+                ..
+            } => {
+                if !subscription.ready() {
+                    return None;
+                }
+                match stream.read(Self::CHUNK_SIZE) {
+                    Ok(bytes) => {
+                        trace!("Read {} bytes of transcription events", bytes.len());
+                        let data = parser.borrow_mut().feed(&bytes);
+                        let events = self.decode_all(*decode, data);
+                        (!events.is_empty()).then_some(events)
+                    }
+                    Err(StreamError::Closed) => {
+                        let data = parser.borrow_mut().finish().into_iter().collect();
+                        let mut events = self.decode_all(*decode, data);
+                        if !self.finished.get() {
+                            self.finished.set(true);
+                            events.push(StreamEvent::Finish);
+                        }
+                        Some(events)
+                    }
+                    Err(StreamError::LastOperationFailed(error)) => {
+                        self.finished.set(true);
+                        Some(vec![StreamEvent::Error(Error {
+                            code: ErrorCode::InternalError,
+                            message: format!(
+                                "Failed to read transcription stream: {}",
+                                error.to_debug_string()
+                            ),
+                            provider_error_json: None,
+                        })])
+                    }
+                }
+            }
+            Source::Ready(events) => {
+                self.finished.set(true);
+                Some(events.borrow_mut().take().unwrap_or_default())
+            }
+        }
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        let pollable = self.subscribe();
+        loop {
+            pollable.block();
+            if let Some(events) = self.get_next() {
+                break events;
+            }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Cross-provider conformance suite.
+//!
+//! Runs the same scenario matrix against whichever `golem:llm` implementation the test component
+//! got plugged with, and summarizes the results as a JSON capability report. New scenarios can be
+//! added by appending to `SCENARIOS`.
+
+use crate::bindings::golem::llm::llm;
+use crate::bindings::golem::llm::llm::StreamEvent;
+use serde::Serialize;
+
+pub struct Models {
+    pub model: &'static str,
+    pub image_model: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", content = "details", rename_all = "kebab-case")]
+pub enum Outcome {
+    Passed(String),
+    Failed(String),
+    Unsupported(String),
+}
+
+#[derive(Serialize)]
+pub struct ScenarioReport {
+    pub scenario: &'static str,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+#[derive(Serialize)]
+pub struct CapabilityReport {
+    pub model: &'static str,
+    pub image_model: &'static str,
+    pub passed: usize,
+    pub failed: usize,
+    pub unsupported: usize,
+    pub scenarios: Vec<ScenarioReport>,
+}
+
+struct Scenario {
+    name: &'static str,
+    run: fn(&Models) -> Outcome,
+}
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        name: "plain-chat",
+        run: plain_chat,
+    },
+    Scenario {
+        name: "tools",
+        run: tools,
+    },
+    Scenario {
+        name: "streaming",
+        run: streaming,
+    },
+    Scenario {
+        name: "streaming-tools",
+        run: streaming_tools,
+    },
+    Scenario {
+        name: "vision-url",
+        run: vision_url,
+    },
+    Scenario {
+        name: "vision-inline",
+        run: vision_inline,
+    },
+    Scenario {
+        name: "error-mapping",
+        run: error_mapping,
+    },
+];
+
+pub fn run(models: &Models) -> CapabilityReport {
+    let scenarios: Vec<ScenarioReport> = SCENARIOS
+        .iter()
+        .map(|scenario| {
+            println!("Running conformance scenario {}...", scenario.name);
+            ScenarioReport {
+                scenario: scenario.name,
+                outcome: (scenario.run)(models),
+            }
+        })
+        .collect();
+
+    let count = |f: fn(&Outcome) -> bool| scenarios.iter().filter(|r| f(&r.outcome)).count();
+    CapabilityReport {
+        model: models.model,
+        image_model: models.image_model,
+        passed: count(|o| matches!(o, Outcome::Passed(_))),
+        failed: count(|o| matches!(o, Outcome::Failed(_))),
+        unsupported: count(|o| matches!(o, Outcome::Unsupported(_))),
+        scenarios,
+    }
+}
+
+fn config(model: &str, tools: Vec<llm::ToolDefinition>) -> llm::Config {
+    llm::Config {
+        model: model.to_string(),
+        temperature: Some(0.2),
+        max_tokens: None,
+        stop_sequences: None,
+        tool_choice: if tools.is_empty() {
+            None
+        } else {
+            Some(llm::ToolChoice::Auto)
+        },
+        parallel_tool_calls: None,
+        tools,
+        response_format: None,
+        logprobs: None,
+        reasoning_effort: None,
+        thinking_budget: None,
+        timeout_ms: None,
+        connect_timeout_ms: None,
+        provider_options: vec![],
+    }
+}
+
+fn test_tool() -> llm::ToolDefinition {
+    llm::ToolDefinition {
+        name: "test-tool".to_string(),
+        description: Some("Test tool for generating test values".to_string()),
+        parameters_schema: r#"{
+                "type": "object",
+                "properties": {
+                    "maximum": {
+                        "type": "number",
+                        "description": "Upper bound for the test value"
+                    }
+                },
+                "required": [
+                    "maximum"
+                ],
+                "additionalProperties": false
+            }"#
+        .to_string(),
+        built_in: None,
+    }
+}
+
+fn user_message(content: Vec<llm::ContentPart>) -> llm::Message {
+    llm::Message {
+        role: llm::Role::User,
+        name: None,
+        content,
+        cache_control: None,
+    }
+}
+
+fn text(content: &[llm::ContentPart]) -> String {
+    content
+        .iter()
+        .filter_map(|part| match part {
+            llm::ContentPart::Text(txt) => Some(txt.as_str()),
+            llm::ContentPart::CitedText(cited) => Some(cited.text.as_str()),
+            llm::ContentPart::Image(_)
+            | llm::ContentPart::Audio(_)
+            | llm::ContentPart::BuiltInToolResult(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+fn error_outcome(error: llm::Error) -> Outcome {
+    let details = format!("{:?} {}", error.code, error.message);
+    if error.code == llm::ErrorCode::Unsupported {
+        Outcome::Unsupported(details)
+    } else {
+        Outcome::Failed(details)
+    }
+}
+
+fn expect_text(response: llm::ChatEvent) -> Outcome {
+    match response {
+        llm::ChatEvent::Message(msg) => {
+            let text = text(&msg.content);
+            if text.trim().is_empty() {
+                Outcome::Failed("Response contained no text".to_string())
+            } else {
+                Outcome::Passed(format!("Received {} characters", text.len()))
+            }
+        }
+        llm::ChatEvent::ToolRequest(request) => {
+            Outcome::Failed(format!("Unexpected tool request: {request:?}"))
+        }
+        llm::ChatEvent::Error(error) => error_outcome(error),
+    }
+}
+
+/// Collects all the events of a stream until it gets closed
+fn collect_stream(stream: llm::ChatStream) -> Vec<StreamEvent> {
+    let mut result = Vec::new();
+    loop {
+        let events = stream.blocking_get_next();
+        if events.is_empty() {
+            break;
+        }
+        result.extend(events);
+    }
+    result
+}
+
+fn plain_chat(models: &Models) -> Outcome {
+    let response = llm::send(
+        &[user_message(vec![llm::ContentPart::Text(
+            "What is the usual weather on the Vršič pass in the beginning of May?".to_string(),
+        )])],
+        &config(models.model, vec![]),
+    );
+    expect_text(response)
+}
+
+fn tools(models: &Models) -> Outcome {
+    let config = config(models.model, vec![test_tool()]);
+    let messages = [user_message(vec![llm::ContentPart::Text(
+        "Generate a random number between 1 and 10 using the test tool, then translate it to German."
+            .to_string(),
+    )])];
+
+    let tool_calls = match llm::send(&messages, &config) {
+        llm::ChatEvent::Message(msg) => msg.tool_calls,
+        llm::ChatEvent::ToolRequest(calls) => calls,
+        llm::ChatEvent::Error(error) => return error_outcome(error),
+    };
+    if tool_calls.is_empty() {
+        return Outcome::Failed("The model did not request any tool calls".to_string());
+    }
+
+    let results: Vec<(llm::ToolCall, llm::ToolResult)> = tool_calls
+        .into_iter()
+        .map(|call| {
+            let result = llm::ToolResult::Success(llm::ToolSuccess {
+                id: call.id.clone(),
+                name: call.name.clone(),
+                result_json: r#"{ "value": 6 }"#.to_string(),
+                execution_time_ms: None,
+            });
+            (call, result)
+        })
+        .collect();
+
+    expect_text(llm::continue_(&messages, &results, &config))
+}
+
+fn streaming(models: &Models) -> Outcome {
+    let stream = llm::stream(
+        &[user_message(vec![llm::ContentPart::Text(
+            "What is the usual weather on the Vršič pass in the beginning of May?".to_string(),
+        )])],
+        &config(models.model, vec![]),
+    );
+
+    let mut text_length = 0;
+    let mut finished = false;
+    for event in collect_stream(stream) {
+        match event {
+            StreamEvent::Delta(delta) => {
+                text_length += text(&delta.content.unwrap_or_default()).len();
+            }
+            StreamEvent::Finish(_) => finished = true,
+            StreamEvent::Error(error) => return error_outcome(error),
+        }
+    }
+
+    match (text_length, finished) {
+        (0, _) => Outcome::Failed("The stream contained no text".to_string()),
+        (_, false) => Outcome::Failed("The stream ended without a finish event".to_string()),
+        (length, true) => Outcome::Passed(format!("Streamed {length} characters")),
+    }
+}
+
+fn streaming_tools(models: &Models) -> Outcome {
+    let stream = llm::stream(
+        &[user_message(vec![llm::ContentPart::Text(
+            "Generate a random number between 1 and 10 using the test tool.".to_string(),
+        )])],
+        &config(models.model, vec![test_tool()]),
+    );
+
+    let mut tool_calls = 0;
+    for event in collect_stream(stream) {
+        match event {
+            StreamEvent::Delta(delta) => tool_calls += delta.tool_calls.unwrap_or_default().len(),
+            StreamEvent::Finish(_) => {}
+            StreamEvent::Error(error) => return error_outcome(error),
+        }
+    }
+
+    if tool_calls == 0 {
+        Outcome::Failed("The stream contained no tool calls".to_string())
+    } else {
+        Outcome::Passed(format!("Streamed {tool_calls} tool call deltas"))
+    }
+}
+
+fn vision_url(models: &Models) -> Outcome {
+    let response = llm::send(
+        &[user_message(vec![
+            llm::ContentPart::Text("What is on this image?".to_string()),
+            llm::ContentPart::Image(llm::ImageReference::Url(llm::ImageUrl {
+                url: "https://blog.vigoo.dev/images/blog-zio-kafka-debugging-3.png".to_string(),
+                detail: Some(llm::ImageDetail::High),
+            })),
+        ])],
+        &config(models.image_model, vec![]),
+    );
+    expect_text(response)
+}
+
+fn vision_inline(models: &Models) -> Outcome {
+    let data = match std::fs::read("/data/cat.png") {
+        Ok(data) => data,
+        Err(err) => return Outcome::Failed(format!("Failed to read cat.png: {err}")),
+    };
+
+    let response = llm::send(
+        &[user_message(vec![
+            llm::ContentPart::Text("What animal is on this image?".to_string()),
+            llm::ContentPart::Image(llm::ImageReference::Inline(llm::ImageSource {
+                data,
+                mime_type: "image/png".to_string(),
+                detail: None,
+            })),
+        ])],
+        &config(models.image_model, vec![]),
+    );
+    expect_text(response)
+}
+
+/// Requests a model that does not exist, which every provider should report as an invalid request
+fn error_mapping(_models: &Models) -> Outcome {
+    let response = llm::send(
+        &[user_message(vec![llm::ContentPart::Text(
+            "Hello!".to_string(),
+        )])],
+        &config("golem-conformance-nonexistent-model", vec![]),
+    );
+
+    match response {
+        llm::ChatEvent::Error(error) if error.code == llm::ErrorCode::InvalidRequest => {
+            Outcome::Passed(error.message)
+        }
+        llm::ChatEvent::Error(error) => Outcome::Failed(format!(
+            "Expected InvalidRequest, got {:?} {}",
+            error.code, error.message
+        )),
+        other => Outcome::Failed(format!("Expected an error, got {other:?}")),
+    }
+}
@@ -1,10 +1,14 @@
 #[allow(static_mut_refs)]
 mod bindings;
+#[cfg(feature = "conformance")]
+mod conformance;
 
 use golem_rust::atomically;
 use crate::bindings::exports::test::llm_exports::test_llm_api::*;
 use crate::bindings::golem::llm::llm;
 use crate::bindings::golem::llm::llm::StreamEvent;
+use crate::bindings::golem::llm::session;
+use crate::bindings::golem::llm::validation;
 use crate::bindings::test::helper_client::test_helper_client::TestHelperApi;
 
 struct Component;
@@ -13,23 +17,51 @@ struct Component;
 const MODEL: &'static str = "gpt-3.5-turbo";
 #[cfg(feature = "anthropic")]
 const MODEL: &'static str = "claude-3-7-sonnet-20250219";
+#[cfg(feature = "anthropic-vertex")]
+const MODEL: &'static str = "claude-sonnet-4@20250514";
+#[cfg(feature = "azure-openai")]
+const MODEL: &'static str = "gpt-4o-mini";
+#[cfg(feature = "cohere")]
+const MODEL: &'static str = "command-r-plus";
 #[cfg(feature = "grok")]
 const MODEL: &'static str = "grok-3-beta";
+#[cfg(feature = "groq")]
+const MODEL: &'static str = "llama-3.3-70b-versatile";
+#[cfg(feature = "deepseek")]
+const MODEL: &'static str = "deepseek-chat";
+#[cfg(feature = "mistral")]
+const MODEL: &'static str = "mistral-large-latest";
 #[cfg(feature = "openrouter")]
 const MODEL: &'static str = "openrouter/auto";
 #[cfg(feature = "ollama")]  
 const MODEL: &'static str = "qwen3:1.7b";
+#[cfg(feature = "mock")]
+const MODEL: &'static str = "mock";
 
 #[cfg(feature = "openai")]
 const IMAGE_MODEL: &'static str = "gpt-4o-mini";
 #[cfg(feature = "anthropic")]
 const IMAGE_MODEL: &'static str = "claude-3-7-sonnet-20250219";
+#[cfg(feature = "anthropic-vertex")]
+const IMAGE_MODEL: &'static str = "claude-sonnet-4@20250514";
+#[cfg(feature = "azure-openai")]
+const IMAGE_MODEL: &'static str = "gpt-4o-mini";
+#[cfg(feature = "cohere")]
+const IMAGE_MODEL: &'static str = "command-a-vision-07-2025";
 #[cfg(feature = "grok")]
 const IMAGE_MODEL: &'static str = "grok-2-vision-latest";
+#[cfg(feature = "groq")]
+const IMAGE_MODEL: &'static str = "meta-llama/llama-4-scout-17b-16e-instruct";
+#[cfg(feature = "deepseek")]
+const IMAGE_MODEL: &'static str = "deepseek-chat";
+#[cfg(feature = "mistral")]
+const IMAGE_MODEL: &'static str = "pixtral-large-latest";
 #[cfg(feature = "openrouter")]
 const IMAGE_MODEL: &'static str = "openrouter/auto";
 #[cfg(feature = "ollama")]  
 const IMAGE_MODEL: &'static str = "gemma3:4b";
+#[cfg(feature = "mock")]
+const IMAGE_MODEL: &'static str = "mock";
 
 impl Guest for Component {
     /// test1 demonstrates a simple, non-streaming text question-answer interaction with the LLM.
@@ -41,6 +73,13 @@ impl Guest for Component {
             stop_sequences: None,
             tools: vec![],
             tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -53,6 +92,7 @@ impl Guest for Component {
                     "What is the usual weather on the Vršič pass in the beginning of May?"
                         .to_string(),
                 )],
+                cache_control: None,
             }],
             &config,
         );
@@ -70,6 +110,9 @@ impl Guest for Component {
                                 llm::ImageReference::Url(url_data) => format!("[IMAGE URL: {}]", url_data.url),
                                 llm::ImageReference::Inline(inline_data) => format!("[INLINE IMAGE: {} bytes, mime: {}]", inline_data.data.len(), inline_data.mime_type),
                             }
+                            llm::ContentPart::Audio(_) => "[AUDIO]".to_string(),
+                            llm::ContentPart::CitedText(cited) => cited.text,
+                            llm::ContentPart::BuiltInToolResult(result) => format!("[BUILT-IN TOOL RESULT: {}]", result.output_json),
                         })
                         .collect::<Vec<_>>()
                         .join(", ")
@@ -114,8 +157,16 @@ impl Guest for Component {
                         "additionalProperties": false
                     }"#
                 .to_string(),
+                built_in: None,
             }],
-            tool_choice: Some("auto".to_string()),
+            tool_choice: Some(llm::ToolChoice::Auto),
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -132,6 +183,7 @@ impl Guest for Component {
                 role: llm::Role::User,
                 name: Some("vigoo".to_string()),
                 content: input.clone(),
+                cache_control: None,
             }],
             &config,
         );
@@ -174,6 +226,7 @@ impl Guest for Component {
                     role: llm::Role::User,
                     name: Some("vigoo".to_string()),
                     content: input.clone(),
+                    cache_control: None,
                 }],
                 &calls,
                 &config,
@@ -209,6 +262,13 @@ impl Guest for Component {
             stop_sequences: None,
             tools: vec![],
             tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -221,6 +281,7 @@ impl Guest for Component {
                     "What is the usual weather on the Vršič pass in the beginning of May?"
                         .to_string(),
                 )],
+                cache_control: None,
             }],
             &config,
         );
@@ -282,8 +343,16 @@ impl Guest for Component {
                         "additionalProperties": false
                     }"#
                 .to_string(),
+                built_in: None,
             }],
-            tool_choice: Some("auto".to_string()),
+            tool_choice: Some(llm::ToolChoice::Auto),
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -300,6 +369,7 @@ impl Guest for Component {
                 role: llm::Role::User,
                 name: Some("vigoo".to_string()),
                 content: input,
+                cache_control: None,
             }],
             &config,
         );
@@ -346,6 +416,13 @@ impl Guest for Component {
             stop_sequences: None,
             tools: vec![],
             tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -363,6 +440,7 @@ impl Guest for Component {
                             detail: Some(llm::ImageDetail::High),
                         })),
                     ],
+                    cache_control: None,
                 },
                 llm::Message {
                     role: llm::Role::System,
@@ -370,6 +448,7 @@ impl Guest for Component {
                     content: vec![llm::ContentPart::Text(
                         "Produce the output in both English and Hungarian".to_string(),
                     )],
+                    cache_control: None,
                 },
             ],
             &config,
@@ -388,6 +467,9 @@ impl Guest for Component {
                                 llm::ImageReference::Url(url_data) => format!("[IMAGE URL: {}]", url_data.url),
                                 llm::ImageReference::Inline(inline_data) => format!("[INLINE IMAGE: {} bytes, mime: {}]", inline_data.data.len(), inline_data.mime_type),
                             }
+                            llm::ContentPart::Audio(_) => "[AUDIO]".to_string(),
+                            llm::ContentPart::CitedText(cited) => cited.text,
+                            llm::ContentPart::BuiltInToolResult(result) => format!("[BUILT-IN TOOL RESULT: {}]", result.output_json),
                         })
                         .collect::<Vec<_>>()
                         .join(", ")
@@ -417,6 +499,13 @@ impl Guest for Component {
             stop_sequences: None,
             tools: vec![],
             tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -429,6 +518,7 @@ impl Guest for Component {
                     "What is the usual weather on the Vršič pass in the beginning of May?"
                         .to_string(),
                 )],
+                cache_control: None,
             }],
             &config,
         );
@@ -462,6 +552,15 @@ impl Guest for Component {
                                         result.push_str(&format!("INLINE IMAGE: {} bytes, mime: {}, detail: {:?}\n", inline_data.data.len(), inline_data.mime_type, inline_data.detail));
                                     }
                                 }
+                                llm::ContentPart::Audio(_) => {
+                                    result.push_str("AUDIO\n");
+                                }
+                                llm::ContentPart::CitedText(cited) => {
+                                    result.push_str(&cited.text);
+                                }
+                                llm::ContentPart::BuiltInToolResult(tool_result) => {
+                                    result.push_str(&format!("BUILT-IN TOOL RESULT: {}\n", tool_result.output_json));
+                                }
                             }
                         }
                     }
@@ -507,6 +606,13 @@ impl Guest for Component {
             stop_sequences: None,
             tools: vec![],
             tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
             provider_options: vec![],
         };
 
@@ -535,6 +641,7 @@ impl Guest for Component {
                         detail: None,
                     })),
                 ],
+                cache_control: None,
             }],
             &config,
         );
@@ -552,6 +659,9 @@ impl Guest for Component {
                                 llm::ImageReference::Url(url_data) => format!("[IMAGE URL: {}]", url_data.url),
                                 llm::ImageReference::Inline(inline_data) => format!("[INLINE IMAGE: {} bytes, mime: {}]", inline_data.data.len(), inline_data.mime_type),
                             }
+                            llm::ContentPart::Audio(_) => "[AUDIO]".to_string(),
+                            llm::ContentPart::CitedText(cited) => cited.text,
+                            llm::ContentPart::BuiltInToolResult(result) => format!("[BUILT-IN TOOL RESULT: {}]", result.output_json),
                         })
                         .collect::<Vec<_>>()
                         .join(", ")
@@ -570,6 +680,292 @@ impl Guest for Component {
             }
         }
     }
+
+    /// test8 demonstrates how to get a JSON response matching a schema with `send-validated`,
+    /// which asks the LLM to repair its output when it does not match
+    fn test8() -> String {
+        let config = llm::Config {
+            model: MODEL.to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![llm::Kv {
+                key: "max_validation_attempts".to_string(),
+                value: "3".to_string(),
+            }],
+        };
+
+        let schema = r#"{
+            "type": "object",
+            "properties": {
+                "pass": {"type": "string"},
+                "elevation_m": {"type": "integer", "minimum": 0},
+                "countries": {"type": "array", "items": {"type": "string"}, "minItems": 1}
+            },
+            "required": ["pass", "elevation_m", "countries"],
+            "additionalProperties": false
+        }"#;
+
+        println!("Sending validated request to LLM...");
+        let response = validation::send_validated(
+            &[llm::Message {
+                role: llm::Role::User,
+                name: None,
+                content: vec![llm::ContentPart::Text(
+                    "Describe the Vršič pass: its name, elevation in meters and the countries it is in."
+                        .to_string(),
+                )],
+                cache_control: None,
+            }],
+            &config,
+            schema,
+        );
+        println!("Response: {:?}", response);
+
+        match response {
+            llm::ChatEvent::Message(msg) => msg
+                .content
+                .into_iter()
+                .filter_map(|content| match content {
+                    llm::ContentPart::Text(txt) => Some(txt),
+                    llm::ContentPart::CitedText(cited) => Some(cited.text),
+                    llm::ContentPart::Image(_)
+                    | llm::ContentPart::Audio(_)
+                    | llm::ContentPart::BuiltInToolResult(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join(""),
+            llm::ChatEvent::ToolRequest(request) => {
+                format!("Tool request: {:?}", request)
+            }
+            llm::ChatEvent::Error(error) => {
+                format!(
+                    "ERROR: {:?} {} ({})",
+                    error.code,
+                    error.message,
+                    error.provider_error_json.unwrap_or_default()
+                )
+            }
+        }
+    }
+
+    /// test9 demonstrates how to get a JSON response matching a schema with the `response-format`
+    /// of the config, using the provider's structured output support
+    fn test9() -> String {
+        let config = llm::Config {
+            model: MODEL.to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: Some(llm::ResponseFormat::JsonSchema(
+                r#"{
+                    "type": "object",
+                    "properties": {
+                        "pass": {"type": "string"},
+                        "elevation_m": {"type": "integer"},
+                        "countries": {"type": "array", "items": {"type": "string"}}
+                    },
+                    "required": ["pass", "elevation_m", "countries"],
+                    "additionalProperties": false
+                }"#
+                .to_string(),
+            )),
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+
+        println!("Sending request with a response format to LLM...");
+        let response = llm::send(
+            &[llm::Message {
+                role: llm::Role::User,
+                name: None,
+                content: vec![llm::ContentPart::Text(
+                    "Describe the Vršič pass: its name, elevation in meters and the countries it is in."
+                        .to_string(),
+                )],
+                cache_control: None,
+            }],
+            &config,
+        );
+        println!("Response: {:?}", response);
+
+        match response {
+            llm::ChatEvent::Message(msg) => msg
+                .content
+                .into_iter()
+                .filter_map(|content| match content {
+                    llm::ContentPart::Text(txt) => Some(txt),
+                    llm::ContentPart::CitedText(cited) => Some(cited.text),
+                    llm::ContentPart::Image(_)
+                    | llm::ContentPart::Audio(_)
+                    | llm::ContentPart::BuiltInToolResult(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join(""),
+            llm::ChatEvent::ToolRequest(request) => {
+                format!("Tool request: {:?}", request)
+            }
+            llm::ChatEvent::Error(error) => {
+                format!(
+                    "ERROR: {:?} {} ({})",
+                    error.code,
+                    error.message,
+                    error.provider_error_json.unwrap_or_default()
+                )
+            }
+        }
+    }
+
+    /// test10 has a conversation of two turns in a chat session, the second question only making
+    /// sense with the history kept by the session
+    fn test10() -> String {
+        let config = llm::Config {
+            model: MODEL.to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+
+        let chat = session::ChatSession::new(&config, &[]);
+        let mut result = String::new();
+
+        for question in [
+            "What is the highest mountain of Slovenia? Answer with its name only.",
+            "How high is it in meters?",
+        ] {
+            println!("Sending {question} in the session...");
+            let response = chat.send(&llm::Message {
+                role: llm::Role::User,
+                name: None,
+                content: vec![llm::ContentPart::Text(question.to_string())],
+                cache_control: None,
+            });
+            println!("Response: {:?}", response);
+
+            match response {
+                llm::ChatEvent::Message(msg) => {
+                    for content in msg.content {
+                        if let llm::ContentPart::Text(txt) = content {
+                            result.push_str(&txt);
+                        }
+                    }
+                    result.push('\n');
+                }
+                llm::ChatEvent::ToolRequest(request) => {
+                    result.push_str(&format!("Tool request: {:?}\n", request));
+                }
+                llm::ChatEvent::Error(error) => {
+                    result.push_str(&format!(
+                        "ERROR: {:?} {} ({})\n",
+                        error.code,
+                        error.message,
+                        error.provider_error_json.unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        result.push_str(&format!("History: {} messages", chat.history().len()));
+        result
+    }
+
+    /// test11 cancels a streaming response and simulates a crash afterwards, but only first time.
+    /// After the automatic recovery the cancelled stream is replayed, and has no more events.
+    fn test11() -> String {
+        let config = llm::Config {
+            model: MODEL.to_string(),
+            temperature: Some(0.2),
+            max_tokens: None,
+            stop_sequences: None,
+            tools: vec![],
+            tool_choice: None,
+            parallel_tool_calls: None,
+            response_format: None,
+            logprobs: None,
+            reasoning_effort: None,
+            thinking_budget: None,
+            timeout_ms: None,
+            connect_timeout_ms: None,
+            provider_options: vec![],
+        };
+
+        println!("Starting streaming request to LLM...");
+        let stream = llm::stream(
+            &[llm::Message {
+                role: llm::Role::User,
+                name: None,
+                content: vec![llm::ContentPart::Text(
+                    "Describe the Julian Alps in ten paragraphs.".to_string(),
+                )],
+                cache_control: None,
+            }],
+            &config,
+        );
+
+        let events = stream.blocking_get_next();
+        println!("Received {} events before cancelling", events.len());
+        stream.cancel();
+
+        let name = std::env::var("GOLEM_WORKER_NAME").unwrap();
+        atomically(|| {
+            let client = TestHelperApi::new(&name);
+            let answer = client.blocking_inc_and_get();
+            if answer == 1 {
+                panic!("Simulating crash")
+            }
+        });
+
+        let after_cancel = stream.blocking_get_next();
+        let next = stream.get_next();
+        format!(
+            "Events after cancelling: {} (blocking), {:?} (get-next)",
+            after_cancel.len(),
+            next.map(|events| events.len())
+        )
+    }
+
+    /// conformance runs the same scenario matrix (plain chat, tools, streaming, vision, error mapping)
+    /// against the plugged in provider, and returns a JSON capability report
+    fn conformance() -> String {
+        #[cfg(feature = "conformance")]
+        {
+            let report = conformance::run(&conformance::Models {
+                model: MODEL,
+                image_model: IMAGE_MODEL,
+            });
+            serde_json::to_string_pretty(&report).unwrap()
+        }
+        #[cfg(not(feature = "conformance"))]
+        {
+            "The conformance suite is not enabled, build with the `conformance` feature".to_string()
+        }
+    }
 }
 
 bindings::export!(Component with_types_in bindings);
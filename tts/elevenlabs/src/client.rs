@@ -0,0 +1,178 @@
+use golem_tts::error::{from_http_status, from_reqwest_error};
+use golem_tts::golem::tts::tts::{Error, ErrorCode};
+use golem_tts::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const BASE_URL: &str = "https://api.elevenlabs.io";
+
+/// The ElevenLabs API client for text-to-speech.
+///
+/// Based on https://elevenlabs.io/docs/api-reference/text-to-speech/convert
+pub struct TextToSpeechApi {
+    api_key: String,
+    client: Client,
+}
+
+impl TextToSpeechApi {
+    pub fn new(api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self { api_key, client }
+    }
+
+    /// Sends the request and returns the response, whose body is the encoded audio. When `stream`
+    /// is true, the streaming endpoint is used which starts returning audio before the whole text
+    /// got synthesized.
+    pub fn convert(
+        &self,
+        voice_id: &str,
+        output_format: &str,
+        request: &TextToSpeechRequest,
+        stream: bool,
+    ) -> Result<Response, Error> {
+        trace!(
+            "Sending request to ElevenLabs API: voice={voice_id} model={} format={output_format} characters={}",
+            request.model_id,
+            request.text.len()
+        );
+
+        let path = if stream { "/stream" } else { "" };
+        send_with_retry(|| {
+            self.client
+                .request(
+                    Method::POST,
+                    format!("{BASE_URL}/v1/text-to-speech/{voice_id}{path}"),
+                )
+                .query(&[("output_format", output_format)])
+                .header("xi-api-key", &self.api_key)
+                .json(request)
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))
+    }
+
+    /// Reads the whole synthesized audio
+    pub fn convert_bytes(
+        &self,
+        voice_id: &str,
+        output_format: &str,
+        request: &TextToSpeechRequest,
+    ) -> Result<Vec<u8>, Error> {
+        let response = self.convert(voice_id, output_format, request, false)?;
+        let status = response.status();
+        if status == StatusCode::OK {
+            response
+                .bytes()
+                .map(|bytes| bytes.to_vec())
+                .map_err(|err| from_reqwest_error("Failed to receive response body", err))
+        } else {
+            let body = response
+                .text()
+                .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+            trace!("Received {status} response from ElevenLabs API: {body:?}");
+            Err(from_http_status(status, body))
+        }
+    }
+
+    pub fn list_voices(&self) -> Result<ListVoicesResponse, Error> {
+        trace!("Listing voices of the ElevenLabs API");
+
+        let response = send_with_retry(|| {
+            self.client
+                .request(Method::GET, format!("{BASE_URL}/v2/voices"))
+                .header("xi-api-key", &self.api_key)
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    parse_response_body(status, raw_body)
+}
+
+fn parse_response_body<T: DeserializeOwned>(
+    status: StatusCode,
+    raw_body: String,
+) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_str::<T>(&raw_body).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: format!("Failed to decode response body: {err}"),
+            provider_error_json: Some(raw_body),
+        })
+    } else {
+        trace!("Received {status} response from ElevenLabs API: {raw_body:?}");
+        Err(from_http_status(status, raw_body))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TextToSpeechRequest {
+    pub text: String,
+    pub model_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voice_settings: Option<VoiceSettings>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceSettings {
+    pub speed: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListVoicesResponse {
+    pub voices: Vec<VoiceResponse>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VoiceResponse {
+    pub voice_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{parse_response_body, ListVoicesResponse};
+    use golem_tts::golem::tts::tts::ErrorCode;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn parses_voices() {
+        let voices = parse_response_body::<ListVoicesResponse>(
+            StatusCode::OK,
+            r#"{"voices": [{"voice_id": "21m00Tcm4TlvDq8ikWAM", "name": "Rachel", "labels": {"language": "en", "accent": "american"}}], "has_more": false}"#.to_string(),
+        )
+        .unwrap();
+        assert_eq!(voices.voices[0].name, "Rachel");
+        assert_eq!(voices.voices[0].labels["language"], "en");
+    }
+
+    #[test]
+    fn maps_errors() {
+        let error = parse_response_body::<ListVoicesResponse>(
+            StatusCode::UNAUTHORIZED,
+            r#"{"detail": {"status": "invalid_api_key", "message": "Invalid API key"}}"#
+                .to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ErrorCode::AuthenticationFailed);
+    }
+}
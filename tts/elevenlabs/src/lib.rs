@@ -0,0 +1,124 @@
+use crate::client::{TextToSpeechApi, TextToSpeechRequest, VoiceSettings};
+use golem_rust::wasm_rpc::Pollable;
+use golem_tts::audio_stream::AudioStream;
+use golem_tts::config::{format_or_default, mime_type, provider_option, with_config_key};
+use golem_tts::durability::{DurableTTS, ExtendedGuest};
+use golem_tts::error::unsupported;
+use golem_tts::golem::tts::tts::{
+    Audio, AudioFormat, AudioStream as WitAudioStream, Config, Error, Guest, Voice,
+};
+use golem_tts::LOGGING_STATE;
+
+mod client;
+
+const DEFAULT_MODEL: &str = "eleven_multilingual_v2";
+
+struct ElevenLabsComponent;
+
+impl ElevenLabsComponent {
+    const ENV_VAR_NAME: &'static str = "ELEVENLABS_API_KEY";
+
+    fn request(text: String, config: &Config) -> TextToSpeechRequest {
+        TextToSpeechRequest {
+            text,
+            model_id: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            language_code: config.language.clone(),
+            voice_settings: config.speed.map(|speed| VoiceSettings { speed }),
+        }
+    }
+}
+
+/// The `output_format` query parameter for the requested format. The `output_format` provider
+/// option can be used to select a specific sample rate and bitrate.
+fn output_format(config: &Config) -> Result<String, Error> {
+    if let Some(output_format) = provider_option(config, "output_format") {
+        return Ok(output_format);
+    }
+    match format_or_default(config) {
+        AudioFormat::Mp3 => Ok("mp3_44100_128".to_string()),
+        AudioFormat::Opus => Ok("opus_48000_128".to_string()),
+        AudioFormat::Pcm => Ok("pcm_44100".to_string()),
+        other => Err(unsupported(format!("{other:?} audio format"))),
+    }
+}
+
+impl Guest for ElevenLabsComponent {
+    type AudioStream = AudioStream;
+
+    fn synthesize(text: String, config: Config) -> Result<Audio, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |api_key| {
+            let client = TextToSpeechApi::new(api_key);
+            let format = format_or_default(&config);
+            let data = client.convert_bytes(
+                &config.voice,
+                &output_format(&config)?,
+                &Self::request(text, &config),
+            )?;
+            Ok(Audio {
+                data,
+                format,
+                mime_type: mime_type(format).to_string(),
+            })
+        })
+    }
+
+    fn synthesize_stream(text: String, config: Config) -> WitAudioStream {
+        WitAudioStream::new(Self::unwrapped_synthesize_stream(text, config))
+    }
+
+    fn list_voices() -> Result<Vec<Voice>, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |api_key| {
+            let client = TextToSpeechApi::new(api_key);
+            let response = client.list_voices()?;
+            Ok(response
+                .voices
+                .into_iter()
+                .map(|mut voice| Voice {
+                    id: voice.voice_id,
+                    name: voice.name,
+                    language: voice.labels.remove("language"),
+                    description: voice
+                        .description
+                        .or_else(|| voice.labels.remove("description")),
+                })
+                .collect())
+        })
+    }
+}
+
+impl ExtendedGuest for ElevenLabsComponent {
+    fn unwrapped_synthesize_stream(text: String, config: Config) -> AudioStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, AudioStream::failed, |api_key| {
+            let client = TextToSpeechApi::new(api_key);
+            let result = output_format(&config).and_then(|output_format| {
+                client.convert(
+                    &config.voice,
+                    &output_format,
+                    &Self::request(text, &config),
+                    true,
+                )
+            });
+            match result {
+                Ok(response) => AudioStream::new(response),
+                Err(error) => AudioStream::failed(error),
+            }
+        })
+    }
+
+    fn subscribe(stream: &Self::AudioStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+type DurableElevenLabsComponent = DurableTTS<ElevenLabsComponent>;
+
+golem_tts::export_tts!(DurableElevenLabsComponent with_types_in golem_tts);
@@ -0,0 +1,78 @@
+use golem_tts::error::{from_http_status, from_reqwest_error};
+use golem_tts::golem::tts::tts::Error;
+use golem_tts::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::Serialize;
+
+const BASE_URL: &str = "https://api.openai.com";
+
+/// The OpenAI API client for generating audio from text.
+///
+/// Based on https://platform.openai.com/docs/api-reference/audio/createSpeech
+pub struct SpeechApi {
+    openai_api_key: String,
+    client: Client,
+}
+
+impl SpeechApi {
+    pub fn new(openai_api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            openai_api_key,
+            client,
+        }
+    }
+
+    /// Sends the request and returns the response, whose body is the encoded audio
+    pub fn create_speech(&self, request: &CreateSpeechRequest) -> Result<Response, Error> {
+        trace!(
+            "Sending request to OpenAI API: model={} voice={} format={} characters={}",
+            request.model,
+            request.voice,
+            request.response_format,
+            request.input.len()
+        );
+
+        send_with_retry(|| {
+            self.client
+                .request(Method::POST, format!("{BASE_URL}/v1/audio/speech"))
+                .bearer_auth(&self.openai_api_key)
+                .json(request)
+                .send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))
+    }
+
+    /// Reads the whole synthesized audio
+    pub fn create_speech_bytes(&self, request: &CreateSpeechRequest) -> Result<Vec<u8>, Error> {
+        let response = self.create_speech(request)?;
+        let status = response.status();
+        if status == StatusCode::OK {
+            response
+                .bytes()
+                .map(|bytes| bytes.to_vec())
+                .map_err(|err| from_reqwest_error("Failed to receive response body", err))
+        } else {
+            let body = response
+                .text()
+                .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+            trace!("Received {status} response from OpenAI API: {body:?}");
+            Err(from_http_status(status, body))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateSpeechRequest {
+    pub model: String,
+    pub input: String,
+    pub voice: String,
+    pub response_format: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
@@ -0,0 +1,106 @@
+use crate::client::{CreateSpeechRequest, SpeechApi};
+use golem_rust::wasm_rpc::Pollable;
+use golem_tts::audio_stream::AudioStream;
+use golem_tts::config::{format_or_default, mime_type, provider_option, with_config_key};
+use golem_tts::durability::{DurableTTS, ExtendedGuest};
+use golem_tts::golem::tts::tts::{
+    Audio, AudioFormat, AudioStream as WitAudioStream, Config, Error, Guest, Voice,
+};
+use golem_tts::LOGGING_STATE;
+
+mod client;
+
+const DEFAULT_MODEL: &str = "gpt-4o-mini-tts";
+
+/// The built-in voices, as OpenAI has no endpoint listing them
+const VOICES: &[&str] = &[
+    "alloy", "ash", "ballad", "coral", "echo", "fable", "nova", "onyx", "sage", "shimmer", "verse",
+];
+
+struct OpenAIComponent;
+
+impl OpenAIComponent {
+    const ENV_VAR_NAME: &'static str = "OPENAI_API_KEY";
+
+    fn request(text: String, config: &Config) -> CreateSpeechRequest {
+        CreateSpeechRequest {
+            model: config
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            input: text,
+            voice: config.voice.clone(),
+            response_format: format_name(format_or_default(config)).to_string(),
+            speed: config.speed,
+            instructions: provider_option(config, "instructions"),
+        }
+    }
+}
+
+fn format_name(format: AudioFormat) -> &'static str {
+    match format {
+        AudioFormat::Mp3 => "mp3",
+        AudioFormat::Opus => "opus",
+        AudioFormat::Aac => "aac",
+        AudioFormat::Flac => "flac",
+        AudioFormat::Wav => "wav",
+        AudioFormat::Pcm => "pcm",
+    }
+}
+
+impl Guest for OpenAIComponent {
+    type AudioStream = AudioStream;
+
+    fn synthesize(text: String, config: Config) -> Result<Audio, Error> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, Err, |openai_api_key| {
+            let client = SpeechApi::new(openai_api_key);
+            let format = format_or_default(&config);
+            let data = client.create_speech_bytes(&Self::request(text, &config))?;
+            Ok(Audio {
+                data,
+                format,
+                mime_type: mime_type(format).to_string(),
+            })
+        })
+    }
+
+    fn synthesize_stream(text: String, config: Config) -> WitAudioStream {
+        WitAudioStream::new(Self::unwrapped_synthesize_stream(text, config))
+    }
+
+    fn list_voices() -> Result<Vec<Voice>, Error> {
+        Ok(VOICES
+            .iter()
+            .map(|voice| Voice {
+                id: voice.to_string(),
+                name: voice.to_string(),
+                language: None,
+                description: None,
+            })
+            .collect())
+    }
+}
+
+impl ExtendedGuest for OpenAIComponent {
+    fn unwrapped_synthesize_stream(text: String, config: Config) -> AudioStream {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        with_config_key(Self::ENV_VAR_NAME, AudioStream::failed, |openai_api_key| {
+            let client = SpeechApi::new(openai_api_key);
+            match client.create_speech(&Self::request(text, &config)) {
+                Ok(response) => AudioStream::new(response),
+                Err(error) => AudioStream::failed(error),
+            }
+        })
+    }
+
+    fn subscribe(stream: &Self::AudioStream) -> Pollable {
+        stream.subscribe()
+    }
+}
+
+type DurableOpenAIComponent = DurableTTS<OpenAIComponent>;
+
+golem_tts::export_tts!(DurableOpenAIComponent with_types_in golem_tts);
@@ -0,0 +1,129 @@
+use crate::error::from_http_status;
+use crate::golem::tts::tts::{Error, ErrorCode, GuestAudioStream, StreamEvent};
+use golem_rust::bindings::wasi::io::streams::{InputStream, StreamError};
+use golem_rust::wasm_rpc::Pollable;
+use log::trace;
+use reqwest::{Response, StatusCode};
+use std::cell::{Cell, RefCell};
+
+/// Streams the body of a provider's HTTP response as audio chunks.
+///
+/// Providers return the encoded audio as a plain (chunked) response body, so the same stream
+/// implementation can be used for all of them.
+pub struct AudioStream {
+    body: Option<ResponseBody>,
+    failure: RefCell<Option<Error>>,
+    finished: Cell<bool>,
+}
+
+struct ResponseBody {
+    // The response has to be kept alive while its body stream is read
+    _response: Response,
+    stream: InputStream,
+    subscription: Pollable,
+}
+
+impl AudioStream {
+    const CHUNK_SIZE: u64 = 16 * 1024;
+
+    /// Creates a stream from the response of a synthesis request. Failed responses are read
+    /// entirely and turned into a failed stream.
+    pub fn new(response: Response) -> Self {
+        let status = response.status();
+        if status != StatusCode::OK {
+            let body = response.text().unwrap_or_default();
+            return Self::failed(from_http_status(status, body));
+        }
+
+        let mut response = response;
+        let stream = unsafe {
+            std::mem::transmute::<reqwest::InputStream, InputStream>(
+                response.get_raw_input_stream(),
+            )
+        };
+        let subscription = stream.subscribe();
+        Self {
+            body: Some(ResponseBody {
+                _response: response,
+                stream,
+                subscription,
+            }),
+            failure: RefCell::new(None),
+            finished: Cell::new(false),
+        }
+    }
+
+    pub fn failed(error: Error) -> Self {
+        Self {
+            body: None,
+            failure: RefCell::new(Some(error)),
+            finished: Cell::new(false),
+        }
+    }
+
+    pub fn subscribe(&self) -> Pollable {
+        match &self.body {
+            Some(body) => body.stream.subscribe(),
+            None => golem_rust::bindings::wasi::clocks::monotonic_clock::subscribe_duration(0),
+        }
+    }
+}
+
+impl GuestAudioStream for AudioStream {
+    fn get_next(&self) -> Option<Vec<StreamEvent>> {
+        if self.finished.get() {
+            return Some(vec![]);
+        }
+
+        match &self.body {
+            Some(body) => {
+                if !body.subscription.ready() {
+                    return None;
+                }
+                match body.stream.read(Self::CHUNK_SIZE) {
+                    Ok(bytes) if bytes.is_empty() => None,
+                    Ok(bytes) => {
+                        trace!("Read {} bytes of audio", bytes.len());
+                        Some(vec![StreamEvent::Chunk(bytes)])
+                    }
+                    Err(StreamError::Closed) => {
+                        self.finished.set(true);
+                        Some(vec![StreamEvent::Finish])
+                    }
+                    Err(StreamError::LastOperationFailed(error)) => {
+                        self.finished.set(true);
+                        Some(vec![StreamEvent::Error(Error {
+                            code: ErrorCode::InternalError,
+                            message: format!(
+                                "Failed to read audio stream: {}",
+                                error.to_debug_string()
+                            ),
+                            provider_error_json: None,
+                        })])
+                    }
+                }
+            }
+            None => {
+                self.finished.set(true);
+                Some(
+                    self.failure
+                        .borrow_mut()
+                        .take()
+                        .map(StreamEvent::Error)
+                        .into_iter()
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    fn blocking_get_next(&self) -> Vec<StreamEvent> {
+        let pollable = self.subscribe();
+        loop {
+            pollable.block();
+            if let Some(events) = self.get_next() {
+                break events;
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::golem::tts::tts::{AudioFormat, Config, Error, ErrorCode};
+use golem_ai_common::config::config_value;
+use std::ffi::OsStr;
+
+/// Gets an expected configuration value from the environment, and fails if its is not found
+/// using the `fail` function. Otherwise, it runs `succeed` with the configuration value.
+pub fn with_config_key<R>(
+    key: impl AsRef<OsStr>,
+    fail: impl FnOnce(Error) -> R,
+    succeed: impl FnOnce(String) -> R,
+) -> R {
+    match config_value(key) {
+        Ok(value) => succeed(value),
+        Err(message) => fail(Error {
+            code: ErrorCode::InternalError,
+            message,
+            provider_error_json: None,
+        }),
+    }
+}
+
+/// Gets the value of a provider option passed in the request's `Config`
+pub fn provider_option(config: &Config, key: &str) -> Option<String> {
+    config
+        .provider_options
+        .iter()
+        .find(|kv| kv.key == key)
+        .map(|kv| kv.value.clone())
+}
+
+/// The requested audio format, defaulting to MP3
+pub fn format_or_default(config: &Config) -> AudioFormat {
+    config.format.unwrap_or(AudioFormat::Mp3)
+}
+
+pub fn mime_type(format: AudioFormat) -> &'static str {
+    match format {
+        AudioFormat::Mp3 => "audio/mpeg",
+        AudioFormat::Opus => "audio/opus",
+        AudioFormat::Aac => "audio/aac",
+        AudioFormat::Flac => "audio/flac",
+        AudioFormat::Wav => "audio/wav",
+        AudioFormat::Pcm => "audio/pcm",
+    }
+}
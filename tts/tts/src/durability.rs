@@ -0,0 +1,438 @@
+use crate::golem::tts::tts::{Config, Guest};
+use golem_rust::wasm_rpc::Pollable;
+use std::marker::PhantomData;
+
+/// Wraps a TTS implementation with custom durability
+pub struct DurableTTS<Impl> {
+    phantom: PhantomData<Impl>,
+}
+
+/// Trait to be implemented in addition to the TTS `Guest` trait when wrapping it with `DurableTTS`.
+pub trait ExtendedGuest: Guest + 'static {
+    /// Creates an instance of the TTS specific `AudioStream` without wrapping it in a `Resource`
+    fn unwrapped_synthesize_stream(text: String, config: Config) -> Self::AudioStream;
+
+    fn subscribe(stream: &Self::AudioStream) -> Pollable;
+}
+
+/// When the durability feature flag is off, wrapping with `DurableTTS` is just a passthrough
+#[cfg(not(feature = "durability"))]
+mod passthrough_impl {
+    use crate::durability::{DurableTTS, ExtendedGuest};
+    use crate::golem::tts::tts::{Audio, AudioStream, Config, Error, Guest, Voice};
+
+    impl<Impl: ExtendedGuest> Guest for DurableTTS<Impl> {
+        type AudioStream = Impl::AudioStream;
+
+        fn synthesize(text: String, config: Config) -> Result<Audio, Error> {
+            Impl::synthesize(text, config)
+        }
+
+        fn synthesize_stream(text: String, config: Config) -> AudioStream {
+            Impl::synthesize_stream(text, config)
+        }
+
+        fn list_voices() -> Result<Vec<Voice>, Error> {
+            Impl::list_voices()
+        }
+    }
+}
+
+/// When the durability feature flag is on, wrapping with `DurableTTS` adds custom durability
+/// on top of the provider-specific TTS implementation using Golem's special host functions and
+/// the `golem-rust` helper library.
+///
+/// The synthesized audio is stored in the oplog as the result of `synthesize`, and every chunk
+/// of a stream as the result of its `get-next` calls. If the worker gets interrupted in the middle
+/// of a stream, the synthesis is requested again when the replay reaches the end of the recorded
+/// chunks, and the audio already returned to the caller is skipped.
+#[cfg(feature = "durability")]
+mod durable_impl {
+    use crate::durability::{DurableTTS, ExtendedGuest};
+    use crate::golem::tts::tts::{
+        Audio, AudioStream, Config, Error, Guest, GuestAudioStream, StreamEvent, Voice,
+    };
+    use golem_rust::bindings::golem::durability::durability::{
+        DurableFunctionType, LazyInitializedPollable,
+    };
+    use golem_rust::durability::Durability;
+    use golem_rust::wasm_rpc::Pollable;
+    use golem_rust::{with_persistence_level, FromValueAndType, IntoValue, PersistenceLevel};
+    use std::cell::RefCell;
+    use std::fmt::{Display, Formatter};
+
+    impl<Impl: ExtendedGuest> Guest for DurableTTS<Impl> {
+        type AudioStream = DurableAudioStream<Impl>;
+
+        fn synthesize(text: String, config: Config) -> Result<Audio, Error> {
+            let durability = Durability::<Audio, Error>::new(
+                "golem_tts",
+                "synthesize",
+                DurableFunctionType::WriteRemote,
+            );
+            if durability.is_live() {
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    Impl::synthesize(text.clone(), config.clone())
+                });
+                durability.persist(SynthesizeInput { text, config }, result)
+            } else {
+                durability.replay()
+            }
+        }
+
+        fn synthesize_stream(text: String, config: Config) -> AudioStream {
+            let durability = Durability::<NoOutput, UnusedError>::new(
+                "golem_tts",
+                "synthesize_stream",
+                DurableFunctionType::WriteRemote,
+            );
+            if durability.is_live() {
+                let result = with_persistence_level(PersistenceLevel::PersistNothing, || {
+                    AudioStream::new(DurableAudioStream::<Impl>::live(
+                        Impl::unwrapped_synthesize_stream(text.clone(), config.clone()),
+                        0,
+                    ))
+                });
+                let _ = durability.persist_infallible(SynthesizeInput { text, config }, NoOutput);
+                result
+            } else {
+                let _: NoOutput = durability.replay_infallible();
+                AudioStream::new(DurableAudioStream::<Impl>::replay(text, config))
+            }
+        }
+
+        fn list_voices() -> Result<Vec<Voice>, Error> {
+            let durability = Durability::<Vec<Voice>, Error>::new(
+                "golem_tts",
+                "list_voices",
+                DurableFunctionType::ReadRemote,
+            );
+            if durability.is_live() {
+                let result =
+                    with_persistence_level(PersistenceLevel::PersistNothing, Impl::list_voices);
+                durability.persist(NoInput, result)
+            } else {
+                durability.replay()
+            }
+        }
+    }
+
+    /// Represents the durable audio stream's state
+    ///
+    /// In live mode it directly reads the underlying provider stream, skipping the first
+    /// `skip_bytes` bytes which were already returned before the worker got interrupted.
+    ///
+    /// In replay mode it counts the replayed audio bytes, and tracks the created pollables to be
+    /// able to reattach them to the new live stream when the switch to live mode happens.
+    enum DurableAudioStreamState<Impl: ExtendedGuest> {
+        Live {
+            stream: Impl::AudioStream,
+            pollables: Vec<LazyInitializedPollable>,
+            skip_bytes: usize,
+        },
+        Replay {
+            text: String,
+            config: Config,
+            pollables: Vec<LazyInitializedPollable>,
+            replayed_bytes: usize,
+            finished: bool,
+        },
+    }
+
+    pub struct DurableAudioStream<Impl: ExtendedGuest> {
+        state: RefCell<Option<DurableAudioStreamState<Impl>>>,
+        subscription: RefCell<Option<Pollable>>,
+    }
+
+    impl<Impl: ExtendedGuest> DurableAudioStream<Impl> {
+        fn live(stream: Impl::AudioStream, skip_bytes: usize) -> Self {
+            Self {
+                state: RefCell::new(Some(DurableAudioStreamState::Live {
+                    stream,
+                    pollables: Vec::new(),
+                    skip_bytes,
+                })),
+                subscription: RefCell::new(None),
+            }
+        }
+
+        fn replay(text: String, config: Config) -> Self {
+            Self {
+                state: RefCell::new(Some(DurableAudioStreamState::Replay {
+                    text,
+                    config,
+                    pollables: Vec::new(),
+                    replayed_bytes: 0,
+                    finished: false,
+                })),
+                subscription: RefCell::new(None),
+            }
+        }
+
+        fn subscribe(&self) -> Pollable {
+            let mut state = self.state.borrow_mut();
+            match &mut *state {
+                Some(DurableAudioStreamState::Live { stream, .. }) => Impl::subscribe(stream),
+                Some(DurableAudioStreamState::Replay { pollables, .. }) => {
+                    let lazy_pollable = LazyInitializedPollable::new();
+                    let pollable = lazy_pollable.subscribe();
+                    pollables.push(lazy_pollable);
+                    pollable
+                }
+                None => {
+                    unreachable!()
+                }
+            }
+        }
+    }
+
+    /// Drops the first `skip_bytes` bytes of audio from `events`, decreasing `skip_bytes` by the
+    /// number of dropped bytes
+    fn skip_audio(events: Vec<StreamEvent>, skip_bytes: &mut usize) -> Vec<StreamEvent> {
+        events
+            .into_iter()
+            .filter_map(|event| match event {
+                StreamEvent::Chunk(mut chunk) if *skip_bytes > 0 => {
+                    let skipped = chunk.len().min(*skip_bytes);
+                    *skip_bytes -= skipped;
+                    chunk.drain(..skipped);
+                    (!chunk.is_empty()).then_some(StreamEvent::Chunk(chunk))
+                }
+                other => Some(other),
+            })
+            .collect()
+    }
+
+    fn read_live<Impl: ExtendedGuest>(
+        stream: &Impl::AudioStream,
+        skip_bytes: &mut usize,
+    ) -> Option<Vec<StreamEvent>> {
+        with_persistence_level(PersistenceLevel::PersistNothing, || {
+            stream.get_next().and_then(|events| {
+                let events = skip_audio(events, skip_bytes);
+                // A read consisting only of skipped audio is reported as not ready yet
+                (!events.is_empty() || *skip_bytes == 0).then_some(events)
+            })
+        })
+    }
+
+    impl<Impl: ExtendedGuest> Drop for DurableAudioStream<Impl> {
+        fn drop(&mut self) {
+            let _ = self.subscription.take();
+            match self.state.take() {
+                Some(DurableAudioStreamState::Live {
+                    mut pollables,
+                    stream,
+                    ..
+                }) => {
+                    with_persistence_level(PersistenceLevel::PersistNothing, move || {
+                        pollables.clear();
+                        drop(stream);
+                    });
+                }
+                Some(DurableAudioStreamState::Replay { mut pollables, .. }) => {
+                    pollables.clear();
+                }
+                None => {}
+            }
+        }
+    }
+
+    impl<Impl: ExtendedGuest> GuestAudioStream for DurableAudioStream<Impl> {
+        fn get_next(&self) -> Option<Vec<StreamEvent>> {
+            let durability = Durability::<Option<Vec<StreamEvent>>, UnusedError>::new(
+                "golem_tts",
+                "get_next",
+                DurableFunctionType::ReadRemote,
+            );
+            if durability.is_live() {
+                let mut state = self.state.borrow_mut();
+                let (result, new_live_stream) = match &mut *state {
+                    Some(DurableAudioStreamState::Live {
+                        stream, skip_bytes, ..
+                    }) => {
+                        let result = read_live::<Impl>(stream, skip_bytes);
+                        (durability.persist_infallible(NoInput, result), None)
+                    }
+                    Some(DurableAudioStreamState::Replay {
+                        text,
+                        config,
+                        pollables,
+                        replayed_bytes,
+                        finished,
+                    }) => {
+                        if *finished {
+                            (durability.persist_infallible(NoInput, Some(vec![])), None)
+                        } else {
+                            let mut skip_bytes = *replayed_bytes;
+                            let (stream, first_live_result) =
+                                with_persistence_level(PersistenceLevel::PersistNothing, || {
+                                    let stream = Impl::unwrapped_synthesize_stream(
+                                        text.clone(),
+                                        config.clone(),
+                                    );
+                                    for lazy_initialized_pollable in pollables.iter() {
+                                        lazy_initialized_pollable.set(Impl::subscribe(&stream));
+                                    }
+                                    let next = read_live::<Impl>(&stream, &mut skip_bytes);
+                                    (stream, next)
+                                });
+                            let result = durability.persist_infallible(NoInput, first_live_result);
+                            (result, Some((stream, skip_bytes)))
+                        }
+                    }
+                    None => {
+                        unreachable!()
+                    }
+                };
+
+                if let Some((stream, skip_bytes)) = new_live_stream {
+                    let pollables = match state.take() {
+                        Some(DurableAudioStreamState::Live { pollables, .. }) => pollables,
+                        Some(DurableAudioStreamState::Replay { pollables, .. }) => pollables,
+                        None => {
+                            unreachable!()
+                        }
+                    };
+                    *state = Some(DurableAudioStreamState::Live {
+                        stream,
+                        pollables,
+                        skip_bytes,
+                    });
+                }
+
+                result
+            } else {
+                let result: Option<Vec<StreamEvent>> = durability.replay_infallible();
+                let mut state = self.state.borrow_mut();
+                match &mut *state {
+                    Some(DurableAudioStreamState::Live { .. }) => {
+                        unreachable!("Durable audio stream cannot be in live mode during replay")
+                    }
+                    Some(DurableAudioStreamState::Replay {
+                        replayed_bytes,
+                        finished,
+                        ..
+                    }) => {
+                        for event in result.iter().flatten() {
+                            match event {
+                                StreamEvent::Chunk(chunk) => *replayed_bytes += chunk.len(),
+                                StreamEvent::Finish | StreamEvent::Error(_) => *finished = true,
+                            }
+                        }
+                    }
+                    None => {
+                        unreachable!()
+                    }
+                }
+                result
+            }
+        }
+
+        fn blocking_get_next(&self) -> Vec<StreamEvent> {
+            let mut subscription = self.subscription.borrow_mut();
+            if subscription.is_none() {
+                *subscription = Some(self.subscribe());
+            }
+            let subscription = subscription.as_mut().unwrap();
+            loop {
+                subscription.block();
+                if let Some(events) = self.get_next() {
+                    break events;
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct SynthesizeInput {
+        text: String,
+        config: Config,
+    }
+
+    #[derive(Debug, IntoValue)]
+    struct NoInput;
+
+    #[derive(Debug, Clone, FromValueAndType, IntoValue)]
+    struct NoOutput;
+
+    #[derive(Debug, FromValueAndType, IntoValue)]
+    struct UnusedError;
+
+    impl Display for UnusedError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "UnusedError")
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::durability::durable_impl::skip_audio;
+        use crate::golem::tts::tts::{
+            Audio, AudioFormat, Config, Error, ErrorCode, Kv, StreamEvent,
+        };
+        use golem_rust::value_and_type::{FromValueAndType, IntoValueAndType};
+        use std::fmt::Debug;
+
+        fn roundtrip_test<T: Debug + Clone + PartialEq + IntoValueAndType + FromValueAndType>(
+            value: T,
+        ) {
+            let vnt = value.clone().into_value_and_type();
+            let extracted = T::from_value_and_type(vnt).unwrap();
+            assert_eq!(value, extracted);
+        }
+
+        #[test]
+        fn config_roundtrip() {
+            roundtrip_test(Config {
+                voice: "alloy".to_string(),
+                model: Some("gpt-4o-mini-tts".to_string()),
+                format: Some(AudioFormat::Opus),
+                speed: Some(1.25),
+                language: None,
+                provider_options: vec![Kv {
+                    key: "instructions".to_string(),
+                    value: "Speak calmly".to_string(),
+                }],
+            });
+        }
+
+        #[test]
+        fn audio_roundtrip() {
+            roundtrip_test(Audio {
+                data: vec![0, 1, 2, 3, 255],
+                format: AudioFormat::Mp3,
+                mime_type: "audio/mpeg".to_string(),
+            });
+        }
+
+        #[test]
+        fn stream_event_roundtrip() {
+            roundtrip_test(StreamEvent::Chunk(vec![1, 2, 3]));
+            roundtrip_test(StreamEvent::Finish);
+            roundtrip_test(StreamEvent::Error(Error {
+                code: ErrorCode::RateLimitExceeded,
+                message: "Too many requests".to_string(),
+                provider_error_json: None,
+            }));
+        }
+
+        #[test]
+        fn skips_already_returned_audio() {
+            let mut skip_bytes = 5;
+            let events = skip_audio(
+                vec![
+                    StreamEvent::Chunk(vec![1, 2, 3]),
+                    StreamEvent::Chunk(vec![4, 5, 6, 7]),
+                    StreamEvent::Finish,
+                ],
+                &mut skip_bytes,
+            );
+            assert_eq!(skip_bytes, 0);
+            assert_eq!(
+                events,
+                vec![StreamEvent::Chunk(vec![6, 7]), StreamEvent::Finish]
+            );
+        }
+    }
+}
@@ -0,0 +1,25 @@
+pub mod audio_stream;
+pub mod config;
+pub mod durability;
+pub mod error;
+
+wit_bindgen::generate!({
+    path: "../wit",
+    world: "tts-library",
+    generate_all,
+    generate_unused_types: true,
+    additional_derives: [PartialEq, golem_rust::FromValueAndType, golem_rust::IntoValue],
+    pub_export_macro: true,
+});
+
+pub use crate::exports::golem;
+pub use __export_tts_library_impl as export_tts;
+pub use golem_ai_common::logging::LoggingState;
+pub use golem_ai_common::retry;
+use std::cell::RefCell;
+
+thread_local! {
+    /// This holds the state of our application.
+    pub static LOGGING_STATE: RefCell<LoggingState> =
+        const { RefCell::new(LoggingState::new("GOLEM_TTS_LOG")) };
+}
@@ -0,0 +1,321 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The Chroma API v2 client, for a single tenant and database
+///
+/// Based on https://docs.trychroma.com/reference/python/client
+pub struct ChromaApi {
+    endpoint: String,
+    tenant: String,
+    database: String,
+    headers: Vec<(String, String)>,
+    client: Client,
+}
+
+impl ChromaApi {
+    pub fn new(
+        endpoint: String,
+        tenant: String,
+        database: String,
+        headers: Vec<(String, String)>,
+    ) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            endpoint,
+            tenant,
+            database,
+            headers,
+            client,
+        }
+    }
+
+    pub fn create_collection(
+        &self,
+        request: &CreateCollectionRequest,
+    ) -> Result<Collection, VectorError> {
+        self.call(Method::POST, "/collections", Some(request))
+    }
+
+    pub fn list_collections(&self) -> Result<Vec<Collection>, VectorError> {
+        self.call(Method::GET, "/collections", None::<&()>)
+    }
+
+    /// Gets a collection by name
+    pub fn get_collection(&self, name: &str) -> Result<Collection, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/collections/{}", encode(name)),
+            None::<&()>,
+        )
+    }
+
+    pub fn delete_collection(&self, name: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::DELETE,
+            &format!("/collections/{}", encode(name)),
+            None::<&()>,
+        )
+        .map(|_| ())
+    }
+
+    pub fn upsert(&self, collection_id: &str, request: &RecordsRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/collections/{collection_id}/upsert"),
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    /// Replaces the given embeddings and merges the given metadata into the existing records
+    pub fn update(&self, collection_id: &str, request: &RecordsRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/collections/{collection_id}/update"),
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn delete(&self, collection_id: &str, request: &DeleteRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/collections/{collection_id}/delete"),
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn get(&self, collection_id: &str, request: &GetRequest) -> Result<GetResult, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{collection_id}/get"),
+            Some(request),
+        )
+    }
+
+    pub fn query(
+        &self,
+        collection_id: &str,
+        request: &QueryRequest,
+    ) -> Result<QueryResult, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{collection_id}/query"),
+            Some(request),
+        )
+    }
+
+    pub fn count(&self, collection_id: &str) -> Result<u64, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/collections/{collection_id}/count"),
+            None::<&()>,
+        )
+    }
+
+    /// Every path is relative to the tenant and database of the client
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<T, VectorError> {
+        trace!("Sending {method} {path} request to Chroma");
+
+        let url = format!(
+            "{}/api/v2/tenants/{}/databases/{}{path}",
+            self.endpoint,
+            encode(&self.tenant),
+            encode(&self.database)
+        );
+        let response = send_with_retry(|| {
+            let mut builder = self.client.request(method.clone(), &url);
+            for (name, value) in &self.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = body {
+                builder = builder.json(body);
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+/// Percent-encodes a name used as a path segment
+fn encode(segment: &str) -> String {
+    let mut encoded = String::new();
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if status.is_success() {
+        serde_json::from_str(&raw_body).map_err(|err| {
+            VectorError::ProviderError(format!("Failed to decode response body: {err}: {raw_body}"))
+        })
+    } else {
+        trace!("Received {status} response from Chroma: {raw_body:?}");
+        Err(from_chroma_error(status, raw_body))
+    }
+}
+
+/// Chroma reports some missing collections and conflicts with generic statuses, so these
+/// are recognized by their message
+fn from_chroma_error(status: StatusCode, body: String) -> VectorError {
+    let message = serde_json::from_str::<ErrorResponse>(&body)
+        .ok()
+        .map(|error| format!("{}: {}", error.error, error.message))
+        .unwrap_or(body);
+    if message.contains("does not exist") || message.contains("NotFoundError") {
+        VectorError::NotFound(message)
+    } else if message.contains("already exists") || message.contains("UniqueConstraintError") {
+        VectorError::AlreadyExists(message)
+    } else if message.contains("dimension") {
+        VectorError::DimensionMismatch(message)
+    } else {
+        from_http_status(status, message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Map<String, Value>>,
+    pub get_or_create: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub metadata: Option<Map<String, Value>>,
+    /// Set once the first embedding has been added
+    #[serde(default)]
+    pub dimension: Option<u32>,
+}
+
+/// The records of an upsert or update. Missing embeddings or metadata are kept as they are
+/// by updates.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordsRequest {
+    pub ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddings: Option<Vec<Vec<f32>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadatas: Option<Vec<Option<Map<String, Value>>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteRequest {
+    pub ids: Vec<String>,
+    #[serde(rename = "where", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GetRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<String>>,
+    #[serde(rename = "where", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    pub include: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetResult {
+    pub ids: Vec<String>,
+    #[serde(default)]
+    pub embeddings: Option<Vec<Vec<f32>>>,
+    #[serde(default)]
+    pub metadatas: Option<Vec<Option<Map<String, Value>>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryRequest {
+    pub query_embeddings: Vec<Vec<f32>>,
+    pub n_results: u32,
+    #[serde(rename = "where", skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub include: Vec<String>,
+}
+
+/// The results of a query, with one list per query embedding in every field
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryResult {
+    pub ids: Vec<Vec<String>>,
+    #[serde(default)]
+    pub distances: Option<Vec<Vec<Option<f32>>>>,
+    #[serde(default)]
+    pub embeddings: Option<Vec<Vec<Vec<f32>>>>,
+    #[serde(default)]
+    pub metadatas: Option<Vec<Vec<Option<Map<String, Value>>>>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorResponse {
+    error: String,
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::from_chroma_error;
+    use golem_vector::golem::vector::types::VectorError;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn maps_error_messages() {
+        assert!(matches!(
+            from_chroma_error(
+                StatusCode::NOT_FOUND,
+                r#"{"error":"NotFoundError","message":"Collection [docs] does not exist"}"#
+                    .to_string()
+            ),
+            VectorError::NotFound(_)
+        ));
+        assert!(matches!(
+            from_chroma_error(
+                StatusCode::CONFLICT,
+                r#"{"error":"UniqueConstraintError","message":"Collection [docs] already exists"}"#
+                    .to_string()
+            ),
+            VectorError::AlreadyExists(_)
+        ));
+        assert!(matches!(
+            from_chroma_error(
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"InvalidArgumentError","message":"Collection expecting embedding with dimension of 3, got 2"}"#
+                    .to_string()
+            ),
+            VectorError::DimensionMismatch(_)
+        ));
+    }
+}
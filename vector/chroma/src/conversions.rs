@@ -0,0 +1,217 @@
+use crate::client::{Collection, GetResult, QueryResult};
+use golem_vector::golem::vector::types::{
+    DistanceMetric, Metadata, MetadataValue, Point, SearchResult, VectorData, VectorError,
+};
+use golem_vector::metadata::{json_to_metadata, metadata_value_to_json};
+use golem_vector::namespace::strip_namespace;
+use serde_json::{Map, Value};
+
+/// The collection metadata key of the distance function of Chroma's HNSW index
+pub const SPACE_KEY: &str = "hnsw:space";
+
+pub fn space_name(metric: DistanceMetric) -> &'static str {
+    match metric {
+        DistanceMetric::Cosine => "cosine",
+        DistanceMetric::Euclidean => "l2",
+        DistanceMetric::DotProduct => "ip",
+    }
+}
+
+/// The metric of a collection, Chroma defaulting to squared L2 distance
+pub fn collection_metric(collection: &Collection) -> DistanceMetric {
+    match collection
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get(SPACE_KEY))
+        .and_then(Value::as_str)
+    {
+        Some("cosine") => DistanceMetric::Cosine,
+        Some("ip") => DistanceMetric::DotProduct,
+        _ => DistanceMetric::Euclidean,
+    }
+}
+
+/// Converts a distance reported by Chroma to a score. Chroma's cosine and inner product
+/// distances are one minus the similarity, so these scores are similarities as in the other
+/// providers. Euclidean scores are squared distances.
+pub fn score(metric: DistanceMetric, distance: f32) -> f32 {
+    match metric {
+        DistanceMetric::Cosine | DistanceMetric::DotProduct => 1.0 - distance,
+        DistanceMetric::Euclidean => distance,
+    }
+}
+
+/// The `include` list of get and query requests
+pub fn include(include_vectors: bool, include_metadata: bool, distances: bool) -> Vec<String> {
+    [
+        ("distances", distances),
+        ("embeddings", include_vectors),
+        ("metadatas", include_metadata),
+    ]
+    .into_iter()
+    .filter(|(_, included)| *included)
+    .map(|(field, _)| field.to_string())
+    .collect()
+}
+
+/// Converts metadata to Chroma's flat metadata, which can only hold strings, numbers and
+/// booleans. Updates remove the fields set to null, otherwise null fields are left out.
+pub fn chroma_metadata(
+    id: &str,
+    metadata: Option<&Metadata>,
+    keep_nulls: bool,
+) -> Result<Option<Map<String, Value>>, VectorError> {
+    let mut result = Map::new();
+    for (key, value) in metadata.into_iter().flatten() {
+        match value {
+            MetadataValue::NullVal if !keep_nulls => {}
+            MetadataValue::StringList(_) | MetadataValue::GeoVal(_) => {
+                return Err(VectorError::InvalidParams(format!(
+                    "Chroma only supports string, number and boolean metadata, but field '{key}' of point '{id}' is not one"
+                )))
+            }
+            value => {
+                result.insert(key.clone(), metadata_value_to_json(value));
+            }
+        }
+    }
+    // Chroma rejects empty metadata objects
+    Ok((!result.is_empty()).then_some(result))
+}
+
+fn point(
+    id: String,
+    embedding: Option<Vec<f32>>,
+    metadata: Option<Map<String, Value>>,
+    include_metadata: bool,
+) -> Point {
+    let metadata = include_metadata.then(|| {
+        metadata
+            .map(|metadata| json_to_metadata(&Value::Object(metadata)))
+            .unwrap_or_default()
+    });
+    Point {
+        id,
+        vector: embedding.map(VectorData::Dense),
+        sparse: None,
+        metadata: strip_namespace(metadata),
+    }
+}
+
+/// Splits the parallel lists of a get result into points
+pub fn points_from_get(result: GetResult, include_metadata: bool) -> Vec<Point> {
+    let mut embeddings = result.embeddings.unwrap_or_default().into_iter();
+    let mut metadatas = result.metadatas.unwrap_or_default().into_iter();
+    result
+        .ids
+        .into_iter()
+        .map(|id| {
+            point(
+                id,
+                embeddings.next(),
+                metadatas.next().flatten(),
+                include_metadata,
+            )
+        })
+        .collect()
+}
+
+/// Splits the nested lists of a query result into the hits of every query embedding
+pub fn results_from_query(
+    result: QueryResult,
+    metric: DistanceMetric,
+    include_metadata: bool,
+) -> Vec<Vec<SearchResult>> {
+    let mut distances = result.distances.unwrap_or_default().into_iter();
+    let mut embeddings = result.embeddings.unwrap_or_default().into_iter();
+    let mut metadatas = result.metadatas.unwrap_or_default().into_iter();
+    result
+        .ids
+        .into_iter()
+        .map(|ids| {
+            let mut distances = distances.next().unwrap_or_default().into_iter();
+            let mut embeddings = embeddings.next().unwrap_or_default().into_iter();
+            let mut metadatas = metadatas.next().unwrap_or_default().into_iter();
+            ids.into_iter()
+                .map(|id| {
+                    let distance = distances.next().flatten().unwrap_or_default();
+                    let point = point(
+                        id,
+                        embeddings.next(),
+                        metadatas.next().flatten(),
+                        include_metadata,
+                    );
+                    SearchResult {
+                        id: point.id,
+                        score: score(metric, distance),
+                        vector: point.vector,
+                        metadata: point.metadata,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::QueryResult;
+    use crate::conversions::{chroma_metadata, include, results_from_query};
+    use golem_vector::golem::vector::types::{DistanceMetric, MetadataValue, VectorData};
+    use serde_json::json;
+
+    #[test]
+    fn rejects_list_metadata() {
+        let metadata = vec![(
+            "tags".to_string(),
+            MetadataValue::StringList(vec!["a".to_string()]),
+        )];
+        assert!(chroma_metadata("a", Some(&metadata), false).is_err());
+    }
+
+    #[test]
+    fn keeps_nulls_only_for_updates() {
+        let metadata = vec![("year".to_string(), MetadataValue::NullVal)];
+        assert_eq!(chroma_metadata("a", Some(&metadata), false).unwrap(), None);
+        assert_eq!(
+            chroma_metadata("a", Some(&metadata), true)
+                .unwrap()
+                .unwrap()
+                .get("year"),
+            Some(&json!(null))
+        );
+    }
+
+    #[test]
+    fn lists_included_fields() {
+        assert_eq!(include(true, false, true), vec!["distances", "embeddings"]);
+    }
+
+    #[test]
+    fn converts_query_results() {
+        let result: QueryResult = serde_json::from_value(json!({
+            "ids": [["a", "b"], []],
+            "distances": [[0.25, 0.5], []],
+            "embeddings": [[[1.0, 0.0], [0.0, 1.0]], []],
+            "metadatas": [[{ "genre": "drama", "_golem_namespace": "x" }, null], []],
+        }))
+        .unwrap();
+        let results = results_from_query(result, DistanceMetric::Cosine, true);
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_empty());
+        assert_eq!(results[0][0].id, "a");
+        assert_eq!(results[0][0].score, 0.75);
+        assert_eq!(
+            results[0][0].vector,
+            Some(VectorData::Dense(vec![1.0, 0.0]))
+        );
+        assert_eq!(
+            results[0][0].metadata,
+            Some(vec![(
+                "genre".to_string(),
+                MetadataValue::StringVal("drama".to_string())
+            )])
+        );
+        assert_eq!(results[0][1].metadata, Some(vec![]));
+    }
+}
@@ -0,0 +1,874 @@
+use crate::client::{
+    ChromaApi, Collection, CreateCollectionRequest, DeleteRequest, GetRequest, QueryRequest,
+    RecordsRequest,
+};
+use crate::conversions::{
+    chroma_metadata, collection_metric, include, points_from_get, results_from_query, space_name,
+    SPACE_KEY,
+};
+use golem_vector::alias::{alias_from_point, alias_record, ALIAS_COLLECTION};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::{ApiKeyStyle, VectorConnection};
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::chroma::to_chroma_where;
+use golem_vector::filter::decode_filter;
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, CollectionInfo, FilterExpression, Id, Metadata,
+    PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::named::expect_dense;
+use golem_vector::namespace::{add_namespace, namespace_filter};
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::recommend::{exclude_examples, recommendation_vector};
+use golem_vector::search::{reject_keywords, search_by_vector_of, search_each};
+use golem_vector::sparse::{reject_sparse_query, reject_sparse_records};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::LOGGING_STATE;
+use log::warn;
+use serde_json::{json, Map, Value};
+
+mod client;
+mod conversions;
+
+/// Chroma's default maximum batch size is much larger, this keeps a batch of 1536 dimensional
+/// vectors at a reasonable request size
+const UPSERT_BATCH_SIZE: usize = 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+struct ChromaComponent;
+
+impl ChromaComponent {
+    const ENDPOINT_ENV_VAR: &'static str = "CHROMA_URL";
+    const API_KEY_ENV_VAR: &'static str = "CHROMA_API_KEY";
+    const DEFAULT_ENDPOINT: &'static str = "http://localhost:8000";
+
+    /// Provider options selecting the Chroma tenant and database holding the collections
+    const TENANT_OPTION: &'static str = "tenant";
+    const DATABASE_OPTION: &'static str = "database";
+    const DEFAULT_TENANT: &'static str = "default_tenant";
+    const DEFAULT_DATABASE: &'static str = "default_database";
+
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<ChromaApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let endpoint =
+            connection.endpoint_or_env(Self::ENDPOINT_ENV_VAR, Some(Self::DEFAULT_ENDPOINT))?;
+        let headers = connection.auth_headers(
+            "Chroma",
+            ApiKeyStyle::Header("x-chroma-token"),
+            Self::API_KEY_ENV_VAR,
+        )?;
+        let tenant = connection
+            .provider_option(Self::TENANT_OPTION)
+            .unwrap_or(Self::DEFAULT_TENANT);
+        let database = connection
+            .provider_option(Self::DATABASE_OPTION)
+            .unwrap_or(Self::DEFAULT_DATABASE);
+        Ok(ChromaApi::new(
+            endpoint,
+            tenant.to_string(),
+            database.to_string(),
+            headers,
+        ))
+    }
+}
+
+/// Gets a collection, resolving the name as an alias if there is no collection with it
+fn collection(api: &ChromaApi, name: &str) -> Result<Collection, VectorError> {
+    match api.get_collection(name) {
+        Err(VectorError::NotFound(message)) => match alias_target(api, name)? {
+            Some(target) => api.get_collection(&target),
+            None => Err(VectorError::NotFound(message)),
+        },
+        result => result,
+    }
+}
+
+/// The alias collection, or `None` if no alias has been created yet
+fn alias_collection(api: &ChromaApi) -> Result<Option<Collection>, VectorError> {
+    match api.get_collection(ALIAS_COLLECTION) {
+        Ok(collection) => Ok(Some(collection)),
+        Err(VectorError::NotFound(_)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn alias_target(api: &ChromaApi, alias: &str) -> Result<Option<String>, VectorError> {
+    let Some(aliases) = alias_collection(api)? else {
+        return Ok(None);
+    };
+    let result = api.get(
+        &aliases.id,
+        &GetRequest {
+            ids: Some(vec![alias.to_string()]),
+            include: include(false, true, false),
+            ..Default::default()
+        },
+    )?;
+    Ok(points_from_get(result, true)
+        .iter()
+        .find_map(alias_from_point)
+        .map(|alias| alias.collection))
+}
+
+fn upsert_alias(api: &ChromaApi, alias: &str, collection: &str) -> Result<(), VectorError> {
+    let aliases = api.create_collection(&CreateCollectionRequest {
+        name: ALIAS_COLLECTION.to_string(),
+        metadata: None,
+        get_or_create: true,
+    })?;
+    let record = alias_record(alias, collection);
+    api.upsert(
+        &aliases.id,
+        &RecordsRequest {
+            ids: vec![record.id.clone()],
+            embeddings: Some(vec![expect_dense(&record, "Chroma")?.to_vec()]),
+            metadatas: Some(vec![chroma_metadata(
+                &record.id,
+                record.metadata.as_ref(),
+                false,
+            )?]),
+        },
+    )
+}
+
+fn chroma_where(
+    filter: Option<FilterExpression>,
+    namespace: Option<&str>,
+) -> Result<Option<Value>, VectorError> {
+    namespace_filter(decode_filter(filter)?, namespace)
+        .map(|filter| to_chroma_where(&filter))
+        .transpose()
+}
+
+/// Chroma writes are applied by the time they are acknowledged, and it has no tunable
+/// ordering or consistency
+fn ignore_write_options(options: Option<&WriteOptions>) {
+    if options.is_some_and(|options| options.ordering.is_some() || options.consistency.is_some()) {
+        warn!("Chroma does not support write ordering and consistency options, ignoring them");
+    }
+}
+
+/// Returns the given ids which exist in the namespace
+fn existing_ids(
+    api: &ChromaApi,
+    collection: &Collection,
+    ids: Vec<Id>,
+    namespace: Option<&str>,
+) -> Result<Vec<Id>, VectorError> {
+    if ids.is_empty() {
+        return Ok(ids);
+    }
+    let result = api.get(
+        &collection.id,
+        &GetRequest {
+            ids: Some(ids),
+            filter: chroma_where(None, namespace)?,
+            include: vec![],
+            ..Default::default()
+        },
+    )?;
+    Ok(result.ids)
+}
+
+/// Gets points by id, in the order of the ids
+fn fetch_points(
+    api: &ChromaApi,
+    collection: &Collection,
+    ids: &[Id],
+    namespace: Option<&str>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Result<Vec<Point>, VectorError> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let result = api.get(
+        &collection.id,
+        &GetRequest {
+            ids: Some(ids.to_vec()),
+            filter: chroma_where(None, namespace)?,
+            include: include(include_vectors, include_metadata, false),
+            ..Default::default()
+        },
+    )?;
+    let mut points = points_from_get(result, include_metadata);
+    points.sort_by_key(|point| ids.iter().position(|id| *id == point.id));
+    Ok(points)
+}
+
+/// Runs searches sharing their filter, limit, offset and included fields with a single query
+/// request. Chroma queries have no offset, so the skipped results are fetched and dropped.
+fn query(
+    api: &ChromaApi,
+    collection: &Collection,
+    queries: Vec<SearchQuery>,
+    namespace: Option<&str>,
+) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+    let Some(first) = queries.first() else {
+        return Ok(vec![]);
+    };
+    for query in &queries {
+        reject_keywords("Chroma", query)?;
+        reject_sparse_query("Chroma", query)?;
+        if query.vector_name.is_some() {
+            return Err(VectorError::UnsupportedFeature(
+                "Chroma does not support named vectors".to_string(),
+            ));
+        }
+    }
+
+    let offset = first.offset.unwrap_or_default();
+    let result = api.query(
+        &collection.id,
+        &QueryRequest {
+            query_embeddings: queries.iter().map(|query| query.vector.clone()).collect(),
+            n_results: first.limit + offset,
+            filter: chroma_where(first.filter.clone(), namespace)?,
+            include: include(first.include_vectors, first.include_metadata, true),
+        },
+    )?;
+    Ok(results_from_query(
+        result,
+        collection_metric(collection),
+        first.include_metadata,
+    )
+    .into_iter()
+    .map(|results| results.into_iter().skip(offset as usize).collect())
+    .collect())
+}
+
+fn search(
+    api: &ChromaApi,
+    collection: &Collection,
+    query: SearchQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<SearchResult>, VectorError> {
+    Ok(self::query(api, collection, vec![query], namespace)?
+        .pop()
+        .unwrap_or_default())
+}
+
+fn same_request(a: &SearchQuery, b: &SearchQuery) -> bool {
+    a.filter == b.filter
+        && a.limit == b.limit
+        && a.offset == b.offset
+        && a.include_vectors == b.include_vectors
+        && a.include_metadata == b.include_metadata
+}
+
+impl CollectionsGuest for ChromaComponent {
+    /// Chroma takes the dimension of a collection from the first embedding added to it, and
+    /// does not support quantization
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        if config.quantization.is_some() {
+            return Err(VectorError::UnsupportedFeature(
+                "Chroma does not support quantization".to_string(),
+            ));
+        }
+        Self::api(&connection)?
+            .create_collection(&CreateCollectionRequest {
+                name,
+                metadata: Some(Map::from_iter([(
+                    SPACE_KEY.to_string(),
+                    json!(space_name(config.metric)),
+                )])),
+                get_or_create: false,
+            })
+            .map(|_| ())
+    }
+
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.delete_collection(&name)
+    }
+
+    /// The alias collection is not listed
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        let collections = Self::api(&connection)?.list_collections()?;
+        Ok(collections
+            .into_iter()
+            .map(|collection| collection.name)
+            .filter(|name| name != ALIAS_COLLECTION)
+            .collect())
+    }
+
+    /// Collections have no dimension until the first embedding is added to them
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<CollectionInfo, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &name)?;
+        let point_count = api.count(&collection.id)?;
+        Ok(CollectionInfo {
+            metric: Some(collection_metric(&collection)),
+            name: collection.name,
+            dimension: collection.dimension,
+            point_count: Some(point_count),
+        })
+    }
+
+    fn create_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma does not support snapshots".to_string(),
+        ))
+    }
+
+    fn restore_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+        _snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma does not support snapshots".to_string(),
+        ))
+    }
+
+    fn list_snapshots(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma does not support snapshots".to_string(),
+        ))
+    }
+
+    fn get_task_status(
+        _connection: ConnectionBorrow<'_>,
+        _task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma does not support snapshots, so there are no tasks to check".to_string(),
+        ))
+    }
+
+    fn create_payload_index(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+        _field: String,
+        _index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma indexes every metadata field, payload indexes cannot be configured".to_string(),
+        ))
+    }
+
+    fn list_payload_indexes(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Chroma indexes every metadata field, payload indexes cannot be configured".to_string(),
+        ))
+    }
+
+    /// Aliases are emulated with the `golem_vector_aliases` collection
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        if alias_target(&api, &alias)?.is_some() {
+            return Err(VectorError::AlreadyExists(format!(
+                "Alias {alias} already exists"
+            )));
+        }
+        upsert_alias(&api, &alias, &collection)
+    }
+
+    /// Switching an alias replaces its single record, so it is atomic
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        upsert_alias(&Self::api(&connection)?, &alias, &collection)
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let not_found = || VectorError::NotFound(format!("Alias {alias} does not exist"));
+        let aliases = alias_collection(&api)?.ok_or_else(not_found)?;
+        if alias_target(&api, &alias)?.is_none() {
+            return Err(not_found());
+        }
+        api.delete(
+            &aliases.id,
+            &DeleteRequest {
+                ids: vec![alias.clone()],
+                filter: None,
+            },
+        )
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let api = Self::api(&connection)?;
+        let Some(aliases) = alias_collection(&api)? else {
+            return Ok(vec![]);
+        };
+        let result = api.get(
+            &aliases.id,
+            &GetRequest {
+                include: include(false, true, false),
+                ..Default::default()
+            },
+        )?;
+        Ok(points_from_get(result, true)
+            .iter()
+            .filter_map(alias_from_point)
+            .collect())
+    }
+}
+
+/// Writes the records of a batch with a single upsert request
+struct ChromaUpsert {
+    api: ChromaApi,
+    collection: Collection,
+    namespace: Option<String>,
+}
+
+impl BatchWriter for ChromaUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        reject_sparse_records("Chroma", batch)?;
+        let mut ids = Vec::with_capacity(batch.len());
+        let mut embeddings = Vec::with_capacity(batch.len());
+        let mut metadatas = Vec::with_capacity(batch.len());
+        for record in batch {
+            let metadata = add_namespace(record.metadata.clone(), self.namespace.as_deref());
+            ids.push(record.id.clone());
+            embeddings.push(expect_dense(record, "Chroma")?.to_vec());
+            metadatas.push(chroma_metadata(&record.id, metadata.as_ref(), false)?);
+        }
+        self.api.upsert(
+            &self.collection.id,
+            &RecordsRequest {
+                ids,
+                embeddings: Some(embeddings),
+                metadatas: Some(metadatas),
+            },
+        )
+    }
+}
+
+impl VectorsGuest for ChromaComponent {
+    type UpsertStream = VectorUpsertStream<ChromaUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    /// Chroma merges updated metadata into the existing metadata, and removes the fields set
+    /// to null. Ids that do not exist are skipped.
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+
+        let ids = existing_ids(&api, &collection, ids, namespace.as_deref())?;
+        let Some(first) = ids.first() else {
+            return Ok(());
+        };
+        let Some(metadata) = chroma_metadata(first, Some(&metadata), true)? else {
+            return Ok(());
+        };
+        api.update(
+            &collection.id,
+            &RecordsRequest {
+                metadatas: Some(vec![Some(metadata); ids.len()]),
+                ids,
+                embeddings: None,
+            },
+        )
+    }
+
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+
+        let VectorData::Dense(vector) = vector else {
+            return Err(VectorError::UnsupportedFeature(
+                "Chroma does not support named vectors".to_string(),
+            ));
+        };
+        if existing_ids(&api, &collection, vec![id.clone()], namespace.as_deref())?.is_empty() {
+            return Err(VectorError::NotFound(format!("Point {id} does not exist")));
+        }
+        api.update(
+            &collection.id,
+            &RecordsRequest {
+                ids: vec![id],
+                embeddings: Some(vec![vector]),
+                metadatas: None,
+            },
+        )
+    }
+
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        fetch_points(
+            &api,
+            &collection,
+            &ids,
+            namespace.as_deref(),
+            include_vectors,
+            include_metadata,
+        )
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        api.delete(
+            &collection.id,
+            &DeleteRequest {
+                ids,
+                filter: chroma_where(None, namespace.as_deref())?,
+            },
+        )
+    }
+}
+
+impl SearchGuest for ChromaComponent {
+    type PointStream = VectorPointStream<ChromaPointPager>;
+
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        search(&api, &collection, query, namespace.as_deref())
+    }
+
+    /// Chroma queries take multiple embeddings with a single filter and limit, so the queries
+    /// are sent in one request when these are the same, and one by one otherwise
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        if queries
+            .windows(2)
+            .all(|pair| same_request(&pair[0], &pair[1]))
+        {
+            query(&api, &collection, queries, namespace.as_deref())
+        } else {
+            search_each(queries, |query| {
+                search(&api, &collection, query, namespace.as_deref())
+            })
+        }
+    }
+
+    /// Chroma has no recommendation API, so the examples' vectors are combined into a query
+    /// vector, which is searched with the example points excluded from the results
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+
+        let examples = |ids: &[Id]| -> Result<Vec<Vec<f32>>, VectorError> {
+            let points = fetch_points(&api, &collection, ids, namespace.as_deref(), true, false)?;
+            ids.iter()
+                .map(|id| {
+                    match points
+                        .iter()
+                        .find(|point| point.id == *id)
+                        .and_then(|point| point.vector.as_ref())
+                    {
+                        Some(VectorData::Dense(values)) => Ok(values.clone()),
+                        _ => Err(VectorError::NotFound(format!(
+                            "Example point {id} does not exist"
+                        ))),
+                    }
+                })
+                .collect()
+        };
+        let vector = recommendation_vector(&examples(&positive)?, &examples(&negative)?)?;
+
+        let results = search(
+            &api,
+            &collection,
+            SearchQuery {
+                vector,
+                vector_name: None,
+                limit: limit + (positive.len() + negative.len()) as u32,
+                offset: None,
+                filter,
+                include_vectors: false,
+                include_metadata: true,
+                keywords: None,
+                sparse: None,
+            },
+            namespace.as_deref(),
+        )?;
+        Ok(exclude_examples(results, &positive, &negative, limit))
+    }
+
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+
+        search_by_vector_of(
+            &id,
+            limit,
+            |id| {
+                let point = fetch_points(
+                    &api,
+                    &collection,
+                    std::slice::from_ref(id),
+                    namespace.as_deref(),
+                    true,
+                    false,
+                )?
+                .pop();
+                match point.and_then(|point| point.vector) {
+                    Some(VectorData::Dense(values)) => Ok(values),
+                    _ => Err(VectorError::NotFound(format!("Point {id} does not exist"))),
+                }
+            },
+            |vector, limit| {
+                search(
+                    &api,
+                    &collection,
+                    SearchQuery {
+                        vector,
+                        vector_name: None,
+                        limit,
+                        offset: None,
+                        filter,
+                        include_vectors: false,
+                        include_metadata: true,
+                        keywords: None,
+                        sparse: None,
+                    },
+                    namespace.as_deref(),
+                )
+            },
+        )
+    }
+
+    /// Chroma can only count whole collections, filtered counts fetch the ids of the
+    /// matching records
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        match chroma_where(filter, namespace.as_deref())? {
+            None => api.count(&collection.id),
+            Some(filter) => {
+                let result = api.get(
+                    &collection.id,
+                    &GetRequest {
+                        filter: Some(filter),
+                        include: vec![],
+                        ..Default::default()
+                    },
+                )?;
+                Ok(result.ids.len() as u64)
+            }
+        }
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for ChromaComponent {
+    type BatchWriter = ChromaUpsert;
+    type PointPager = ChromaPointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<ChromaUpsert, VectorError> {
+        let api = Self::api(&connection)?;
+        let collection = self::collection(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+        Ok(ChromaUpsert {
+            api,
+            collection,
+            namespace,
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> ChromaPointPager {
+        let scroll = Self::api(&connection).and_then(|api| {
+            let collection = self::collection(&api, &collection)?;
+            let filter = chroma_where(options.filter.clone(), namespace.as_deref())?;
+            Ok(ChromaScroll {
+                api,
+                collection,
+                filter,
+                options,
+            })
+        });
+        ChromaPointPager { scroll }
+    }
+}
+
+struct ChromaScroll {
+    api: ChromaApi,
+    collection: Collection,
+    filter: Option<Value>,
+    options: ScrollOptions,
+}
+
+/// Pages through records by offset, in the order Chroma stores them. The cursor is the offset
+/// of the next page.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct ChromaPointPager {
+    scroll: Result<ChromaScroll, VectorError>,
+}
+
+impl PointPager for ChromaPointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let limit = scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+        let offset = cursor
+            .map(|cursor| cursor.parse::<u32>())
+            .transpose()
+            .map_err(|_| VectorError::InvalidParams(format!("Invalid cursor {cursor:?}")))?
+            .unwrap_or_default();
+
+        let result = scroll.api.get(
+            &scroll.collection.id,
+            &GetRequest {
+                ids: None,
+                filter: scroll.filter.clone(),
+                limit: Some(limit),
+                offset: Some(offset),
+                include: include(
+                    scroll.options.include_vectors,
+                    scroll.options.include_metadata,
+                    false,
+                ),
+            },
+        )?;
+        let points = points_from_get(result, scroll.options.include_metadata);
+        let next_cursor = (points.len() as u32 >= limit).then(|| (offset + limit).to_string());
+        Ok(PointPage {
+            points,
+            next_cursor,
+        })
+    }
+}
+
+type DurableChromaComponent = DurableVector<ChromaComponent>;
+
+golem_vector::export_vector!(DurableChromaComponent with_types_in golem_vector);
@@ -0,0 +1,474 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The Milvus RESTful API v2 client, also used for Zilliz Cloud clusters
+///
+/// Based on https://milvus.io/api-reference/restful/v2.5.x/About.md
+pub struct MilvusApi {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    client: Client,
+}
+
+impl MilvusApi {
+    pub fn new(endpoint: String, headers: Vec<(String, String)>) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            endpoint,
+            headers,
+            client,
+        }
+    }
+
+    pub fn create_collection(&self, request: &CreateCollectionRequest) -> Result<(), VectorError> {
+        self.call::<Value>("/v2/vectordb/collections/create", request)
+            .map(|_| ())
+    }
+
+    pub fn drop_collection(&self, collection: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            "/v2/vectordb/collections/drop",
+            &CollectionRequest::new(collection),
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_collections(&self) -> Result<Vec<String>, VectorError> {
+        self.call("/v2/vectordb/collections/list", &Map::new())
+    }
+
+    /// Describes a collection, or the collection an alias refers to
+    pub fn describe_collection(
+        &self,
+        collection: &str,
+    ) -> Result<CollectionDescription, VectorError> {
+        self.call(
+            "/v2/vectordb/collections/describe",
+            &CollectionRequest::new(collection),
+        )
+    }
+
+    pub fn row_count(&self, collection: &str) -> Result<u64, VectorError> {
+        self.call::<CollectionStats>(
+            "/v2/vectordb/collections/get_stats",
+            &CollectionRequest::new(collection),
+        )
+        .map(|stats| stats.row_count)
+    }
+
+    pub fn has_partition(&self, collection: &str, partition: &str) -> Result<bool, VectorError> {
+        self.call::<HasResult>(
+            "/v2/vectordb/partitions/has",
+            &PartitionRequest::new(collection, partition),
+        )
+        .map(|result| result.has)
+    }
+
+    pub fn create_partition(&self, collection: &str, partition: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            "/v2/vectordb/partitions/create",
+            &PartitionRequest::new(collection, partition),
+        )
+        .map(|_| ())
+    }
+
+    pub fn create_index(&self, request: &CreateIndexRequest) -> Result<(), VectorError> {
+        self.call::<Value>("/v2/vectordb/indexes/create", request)
+            .map(|_| ())
+    }
+
+    pub fn list_indexes(&self, collection: &str) -> Result<Vec<String>, VectorError> {
+        self.call(
+            "/v2/vectordb/indexes/list",
+            &CollectionRequest::new(collection),
+        )
+    }
+
+    pub fn describe_index(
+        &self,
+        collection: &str,
+        index: &str,
+    ) -> Result<Vec<IndexDescription>, VectorError> {
+        self.call(
+            "/v2/vectordb/indexes/describe",
+            &DescribeIndexRequest {
+                collection_name: collection.to_string(),
+                index_name: index.to_string(),
+            },
+        )
+    }
+
+    pub fn create_alias(&self, alias: &str, collection: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            "/v2/vectordb/aliases/create",
+            &AliasRequest {
+                alias_name: alias.to_string(),
+                collection_name: Some(collection.to_string()),
+            },
+        )
+        .map(|_| ())
+    }
+
+    pub fn alter_alias(&self, alias: &str, collection: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            "/v2/vectordb/aliases/alter",
+            &AliasRequest {
+                alias_name: alias.to_string(),
+                collection_name: Some(collection.to_string()),
+            },
+        )
+        .map(|_| ())
+    }
+
+    pub fn drop_alias(&self, alias: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            "/v2/vectordb/aliases/drop",
+            &AliasRequest {
+                alias_name: alias.to_string(),
+                collection_name: None,
+            },
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_aliases(&self) -> Result<Vec<String>, VectorError> {
+        self.call("/v2/vectordb/aliases/list", &Map::new())
+    }
+
+    pub fn describe_alias(&self, alias: &str) -> Result<AliasDescription, VectorError> {
+        self.call(
+            "/v2/vectordb/aliases/describe",
+            &AliasRequest {
+                alias_name: alias.to_string(),
+                collection_name: None,
+            },
+        )
+    }
+
+    pub fn upsert(&self, request: &UpsertRequest) -> Result<(), VectorError> {
+        self.call::<Value>("/v2/vectordb/entities/upsert", request)
+            .map(|_| ())
+    }
+
+    pub fn delete(&self, request: &DeleteRequest) -> Result<(), VectorError> {
+        self.call::<Value>("/v2/vectordb/entities/delete", request)
+            .map(|_| ())
+    }
+
+    pub fn query(&self, request: &QueryRequest) -> Result<Vec<Map<String, Value>>, VectorError> {
+        self.call("/v2/vectordb/entities/query", request)
+    }
+
+    pub fn search(&self, request: &SearchRequest) -> Result<Vec<Map<String, Value>>, VectorError> {
+        self.call("/v2/vectordb/entities/search", request)
+    }
+
+    /// Every endpoint of the v2 API is a POST request, answered with a `code` and `data` envelope
+    fn call<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, VectorError> {
+        trace!("Sending {path} request to Milvus");
+
+        let response = send_with_retry(|| {
+            let mut builder = self
+                .client
+                .request(Method::POST, format!("{}{path}", self.endpoint))
+                .json(body);
+            for (name, value) in &self.headers {
+                builder = builder.header(name, value);
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+/// Milvus reports most errors with a 200 status and a non-zero `code` in the response body
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if !status.is_success() {
+        trace!("Received {status} response from Milvus: {raw_body:?}");
+        return Err(from_http_status(status, raw_body));
+    }
+
+    let envelope: MilvusResponse = serde_json::from_str(&raw_body).map_err(|err| {
+        VectorError::ProviderError(format!("Failed to decode response body: {err}: {raw_body}"))
+    })?;
+    if envelope.code != 0 {
+        trace!("Received error response from Milvus: {raw_body:?}");
+        return Err(from_milvus_error(
+            envelope.code,
+            envelope.message.unwrap_or_default(),
+        ));
+    }
+    serde_json::from_value(envelope.data.unwrap_or(Value::Null)).map_err(|err| {
+        VectorError::ProviderError(format!("Failed to decode response data: {err}: {raw_body}"))
+    })
+}
+
+/// Maps the error codes of Milvus, falling back to the message for errors reported with
+/// generic codes
+fn from_milvus_error(code: i64, message: String) -> VectorError {
+    let message = format!("Milvus error {code}: {message}");
+    let lowercase = message.to_lowercase();
+    match code {
+        8 => VectorError::RateLimited(message),
+        1100 => VectorError::InvalidParams(message),
+        1800 => VectorError::Unauthorized(message),
+        _ if lowercase.contains("not found") || lowercase.contains("not exist") => {
+            VectorError::NotFound(message)
+        }
+        _ if lowercase.contains("already exist") => VectorError::AlreadyExists(message),
+        _ if lowercase.contains("dimension") => VectorError::DimensionMismatch(message),
+        _ => VectorError::ProviderError(message),
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MilvusResponse {
+    code: i64,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectionRequest {
+    collection_name: String,
+}
+
+impl CollectionRequest {
+    fn new(collection: &str) -> Self {
+        Self {
+            collection_name: collection.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionDescription {
+    pub collection_name: String,
+    #[serde(default)]
+    pub fields: Vec<FieldDescription>,
+    #[serde(default)]
+    pub indexes: Vec<IndexSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldDescription {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    #[serde(default)]
+    pub params: Vec<FieldParam>,
+}
+
+/// A type parameter of a field, such as `dim`, whose value Milvus reports as a string
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldParam {
+    pub key: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexSummary {
+    pub field_name: String,
+    #[serde(default)]
+    pub metric_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectionStats {
+    row_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PartitionRequest {
+    collection_name: String,
+    partition_name: String,
+}
+
+impl PartitionRequest {
+    fn new(collection: &str, partition: &str) -> Self {
+        Self {
+            collection_name: collection.to_string(),
+            partition_name: partition.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HasResult {
+    has: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSchema {
+    pub field_name: String,
+    pub data_type: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub is_primary: bool,
+    pub element_type_params: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionSchema {
+    pub auto_id: bool,
+    pub enable_dynamic_field: bool,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCollectionRequest {
+    pub collection_name: String,
+    pub schema: CollectionSchema,
+    pub index_params: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateIndexRequest {
+    pub collection_name: String,
+    pub index_params: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DescribeIndexRequest {
+    collection_name: String,
+    index_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexDescription {
+    pub field_name: String,
+    pub index_type: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AliasRequest {
+    alias_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasDescription {
+    pub alias_name: String,
+    pub collection_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertRequest {
+    pub collection_name: String,
+    pub data: Vec<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRequest {
+    pub collection_name: String,
+    pub filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partition_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub collection_name: String,
+    pub filter: String,
+    pub output_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub partition_names: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consistency_level: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequest {
+    pub collection_name: String,
+    pub data: Vec<Vec<f32>>,
+    pub anns_field: String,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    pub output_fields: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub partition_names: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{from_milvus_error, FieldSchema};
+    use golem_vector::golem::vector::types::VectorError;
+    use serde_json::{json, Map};
+
+    #[test]
+    fn maps_error_codes_and_messages() {
+        assert!(matches!(
+            from_milvus_error(8, "rate limit exceeded".to_string()),
+            VectorError::RateLimited(_)
+        ));
+        assert!(matches!(
+            from_milvus_error(100, "collection not found[collection=docs]".to_string()),
+            VectorError::NotFound(_)
+        ));
+        assert!(matches!(
+            from_milvus_error(65535, "alias already exists".to_string()),
+            VectorError::AlreadyExists(_)
+        ));
+    }
+
+    #[test]
+    fn omits_primary_flag_of_other_fields() {
+        let field = FieldSchema {
+            field_name: "vector".to_string(),
+            data_type: "FloatVector".to_string(),
+            is_primary: false,
+            element_type_params: Map::from_iter([("dim".to_string(), json!("3"))]),
+        };
+        assert_eq!(
+            serde_json::to_value(field).unwrap(),
+            json!({
+                "fieldName": "vector",
+                "dataType": "FloatVector",
+                "elementTypeParams": { "dim": "3" }
+            })
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::client::{CollectionDescription, FieldSchema};
+use golem_vector::filter::milvus::milvus_string;
+use golem_vector::golem::vector::types::{
+    CollectionInfo, DistanceMetric, Id, Metadata, PayloadIndexType, Point, SearchResult,
+    VectorData, VectorError,
+};
+use golem_vector::metadata::{json_to_metadata, metadata_value_to_json};
+use serde_json::{Map, Value};
+
+/// The primary key field of the collections created by this provider
+pub const ID_FIELD: &str = "id";
+
+/// The vector field of collections storing a single vector per point
+pub const DEFAULT_VECTOR_FIELD: &str = "vector";
+
+/// The field holding the score of search hits
+const DISTANCE_FIELD: &str = "distance";
+
+/// Milvus limits the length of VarChar values, ids longer than this are rejected
+const MAX_ID_LENGTH: u32 = 512;
+
+/// The fields of a new collection: a string primary key and a float vector field per vector
+/// name. Metadata is stored in the dynamic field, so it does not need to be declared.
+pub fn collection_fields(vector_fields: &[String], dimension: u32) -> Vec<FieldSchema> {
+    let mut fields = vec![FieldSchema {
+        field_name: ID_FIELD.to_string(),
+        data_type: "VarChar".to_string(),
+        is_primary: true,
+        element_type_params: Map::from_iter([(
+            "max_length".to_string(),
+            Value::from(MAX_ID_LENGTH.to_string()),
+        )]),
+    }];
+    fields.extend(vector_fields.iter().map(|name| FieldSchema {
+        field_name: name.clone(),
+        data_type: "FloatVector".to_string(),
+        is_primary: false,
+        element_type_params: Map::from_iter([(
+            "dim".to_string(),
+            Value::from(dimension.to_string()),
+        )]),
+    }));
+    fields
+}
+
+/// Builds the row of an entity, with its vectors and metadata as top level fields
+pub fn entity(
+    id: &str,
+    vector: &VectorData,
+    metadata: Option<&Metadata>,
+) -> Result<Map<String, Value>, VectorError> {
+    let mut row = Map::new();
+    row.insert(ID_FIELD.to_string(), Value::String(id.to_string()));
+    match vector {
+        VectorData::Dense(values) => {
+            row.insert(
+                DEFAULT_VECTOR_FIELD.to_string(),
+                Value::from(values.clone()),
+            );
+        }
+        VectorData::Named(vectors) => {
+            if vectors.is_empty() {
+                return Err(VectorError::InvalidParams(format!(
+                    "Point '{id}' has no vectors"
+                )));
+            }
+            for (name, values) in vectors {
+                row.insert(name.clone(), Value::from(values.clone()));
+            }
+        }
+    }
+    for (key, value) in metadata.into_iter().flatten() {
+        if row.contains_key(key) || key == DISTANCE_FIELD {
+            return Err(VectorError::InvalidParams(format!(
+                "Metadata field '{key}' of point '{id}' conflicts with a Milvus collection field"
+            )));
+        }
+        row.insert(key.clone(), metadata_value_to_json(value));
+    }
+    Ok(row)
+}
+
+/// Splits a returned row into the id, vectors and metadata of a point.
+///
+/// Metadata never contains arrays of numbers, so these are the vector fields of the
+/// collection. A single field called `vector` is a dense vector, anything else is returned as
+/// named vectors.
+pub fn point_from_entity(
+    mut row: Map<String, Value>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Point {
+    let id = row.remove(ID_FIELD).map(id_from_json).unwrap_or_default();
+    row.remove(DISTANCE_FIELD);
+
+    let vector_fields = row
+        .iter()
+        .filter(|(_, value)| dense_from_json(value).is_some())
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    let mut vectors = vector_fields
+        .into_iter()
+        .filter_map(|name| {
+            let values = row.remove(&name).as_ref().and_then(dense_from_json)?;
+            Some((name, values))
+        })
+        .collect::<Vec<_>>();
+    let vector = if vectors.is_empty() {
+        None
+    } else if vectors.len() == 1 && vectors[0].0 == DEFAULT_VECTOR_FIELD {
+        vectors.pop().map(|(_, values)| VectorData::Dense(values))
+    } else {
+        Some(VectorData::Named(vectors))
+    };
+
+    Point {
+        id,
+        vector: vector.filter(|_| include_vectors),
+        sparse: None,
+        metadata: include_metadata.then(|| json_to_metadata(&Value::Object(row))),
+    }
+}
+
+/// Converts a search hit. The score is the `distance` reported by Milvus, which is a
+/// similarity for the cosine and inner product metrics and a distance for L2.
+pub fn search_result_from_entity(
+    row: Map<String, Value>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> SearchResult {
+    let score = row
+        .get(DISTANCE_FIELD)
+        .and_then(Value::as_f64)
+        .unwrap_or_default() as f32;
+    let point = point_from_entity(row, include_vectors, include_metadata);
+    SearchResult {
+        id: point.id,
+        score,
+        vector: point.vector,
+        metadata: point.metadata,
+    }
+}
+
+/// Describes a collection. Collections with multiple vector fields only have a dimension and
+/// metric if all of their fields share them, as they do when created with `vector_names`.
+pub fn collection_info(description: CollectionDescription, row_count: u64) -> CollectionInfo {
+    let vector_fields: Vec<_> = description
+        .fields
+        .iter()
+        .filter(|field| field.data_type == "FloatVector")
+        .collect();
+    let dimensions = vector_fields.iter().map(|field| {
+        field
+            .params
+            .iter()
+            .find(|param| param.key == "dim")
+            .and_then(|param| match &param.value {
+                Value::String(dim) => dim.parse().ok(),
+                value => value.as_u64().map(|dim| dim as u32),
+            })
+    });
+    // Scalar indexes of metadata fields have no metric
+    let metrics = description
+        .indexes
+        .iter()
+        .filter(|index| {
+            vector_fields
+                .iter()
+                .any(|field| field.name == index.field_name)
+        })
+        .map(|index| index.metric_type.as_deref().and_then(metric_from_milvus));
+    CollectionInfo {
+        name: description.collection_name,
+        dimension: shared(dimensions),
+        metric: shared(metrics),
+        point_count: Some(row_count),
+    }
+}
+
+fn metric_from_milvus(metric: &str) -> Option<DistanceMetric> {
+    match metric {
+        "COSINE" => Some(DistanceMetric::Cosine),
+        "L2" => Some(DistanceMetric::Euclidean),
+        "IP" => Some(DistanceMetric::DotProduct),
+        _ => None,
+    }
+}
+
+/// The value all of the items have, if they have the same one
+fn shared<T: PartialEq>(mut values: impl Iterator<Item = Option<T>>) -> Option<T> {
+    let first = values.next().flatten()?;
+    values
+        .all(|value| value.as_ref() == Some(&first))
+        .then_some(first)
+}
+
+/// The output fields to request for the given inclusion flags
+pub fn output_fields(
+    vector_fields: &[String],
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Vec<String> {
+    let mut fields = Vec::new();
+    if include_metadata {
+        fields.push("*".to_string());
+    }
+    if include_vectors {
+        fields.extend(vector_fields.iter().cloned());
+    }
+    fields
+}
+
+/// A filter expression selecting entities by id
+pub fn ids_expr(ids: &[Id]) -> String {
+    let ids = ids
+        .iter()
+        .map(|id| milvus_string(id))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{ID_FIELD} in [{ids}]")
+}
+
+/// Returns the `json_cast_type` of an index on a dynamic field
+pub fn json_cast_type(index_type: PayloadIndexType) -> &'static str {
+    match index_type {
+        PayloadIndexType::Keyword | PayloadIndexType::Text => "varchar",
+        PayloadIndexType::Integer | PayloadIndexType::Float => "double",
+        PayloadIndexType::Boolean | PayloadIndexType::Geo => "bool",
+    }
+}
+
+/// Maps the index type of a scalar index back to a payload index type
+pub fn from_milvus_index_type(index_type: &str) -> Option<PayloadIndexType> {
+    match index_type {
+        "INVERTED" | "Trie" => Some(PayloadIndexType::Keyword),
+        "STL_SORT" => Some(PayloadIndexType::Float),
+        "BITMAP" => Some(PayloadIndexType::Boolean),
+        _ => None,
+    }
+}
+
+fn id_from_json(id: Value) -> String {
+    match id {
+        Value::String(id) => id,
+        other => other.to_string(),
+    }
+}
+
+fn dense_from_json(value: &Value) -> Option<Vec<f32>> {
+    value
+        .as_array()
+        .filter(|values| !values.is_empty())
+        .and_then(|values| {
+            values
+                .iter()
+                .map(|value| value.as_f64().map(|value| value as f32))
+                .collect()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::CollectionDescription;
+    use crate::conversions::{collection_info, entity, ids_expr, point_from_entity};
+    use golem_vector::golem::vector::types::{
+        DistanceMetric, MetadataValue, VectorData, VectorError,
+    };
+    use serde_json::{json, Value};
+
+    fn row(value: Value) -> serde_json::Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn builds_entity_rows() {
+        let metadata = vec![(
+            "genre".to_string(),
+            MetadataValue::StringVal("drama".to_string()),
+        )];
+        let row = entity("a", &VectorData::Dense(vec![1.0, 2.0]), Some(&metadata)).unwrap();
+        assert_eq!(
+            Value::Object(row),
+            json!({ "id": "a", "vector": [1.0, 2.0], "genre": "drama" })
+        );
+    }
+
+    #[test]
+    fn rejects_metadata_conflicting_with_fields() {
+        let metadata = vec![("vector".to_string(), MetadataValue::IntegerVal(1))];
+        assert!(matches!(
+            entity("a", &VectorData::Dense(vec![1.0]), Some(&metadata)),
+            Err(VectorError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn splits_vectors_from_metadata() {
+        let point = point_from_entity(
+            row(json!({ "id": "a", "vector": [0.5], "tags": ["x"], "year": 2001 })),
+            true,
+            true,
+        );
+        assert_eq!(point.id, "a");
+        assert_eq!(point.vector, Some(VectorData::Dense(vec![0.5])));
+        assert_eq!(
+            point.metadata,
+            Some(vec![
+                (
+                    "tags".to_string(),
+                    MetadataValue::StringList(vec!["x".to_string()])
+                ),
+                ("year".to_string(), MetadataValue::IntegerVal(2001)),
+            ])
+        );
+
+        let point = point_from_entity(
+            row(json!({ "id": "b", "title": [1.0], "body": [2.0] })),
+            true,
+            false,
+        );
+        assert_eq!(
+            point.vector,
+            Some(VectorData::Named(vec![
+                ("body".to_string(), vec![2.0]),
+                ("title".to_string(), vec![1.0]),
+            ]))
+        );
+        assert_eq!(point.metadata, None);
+    }
+
+    #[test]
+    fn quotes_ids() {
+        assert_eq!(
+            ids_expr(&["a".to_string(), "b\"".to_string()]),
+            "id in [\"a\", \"b\\\"\"]"
+        );
+    }
+
+    #[test]
+    fn describes_collections() {
+        let description: CollectionDescription = serde_json::from_value(json!({
+            "collectionName": "docs",
+            "fields": [
+                { "name": "id", "type": "VarChar" },
+                {
+                    "name": "vector",
+                    "type": "FloatVector",
+                    "params": [{ "key": "dim", "value": "4" }]
+                }
+            ],
+            "indexes": [
+                { "fieldName": "vector", "metricType": "IP" },
+                { "fieldName": "genre" }
+            ]
+        }))
+        .unwrap();
+        let info = collection_info(description, 10);
+        assert_eq!(info.name, "docs");
+        assert_eq!(info.dimension, Some(4));
+        assert_eq!(info.metric, Some(DistanceMetric::DotProduct));
+        assert_eq!(info.point_count, Some(10));
+    }
+}
@@ -0,0 +1,872 @@
+use crate::client::{
+    CollectionSchema, CreateCollectionRequest, CreateIndexRequest, DeleteRequest, MilvusApi,
+    QueryRequest, SearchRequest, UpsertRequest,
+};
+use crate::conversions::{
+    collection_fields, collection_info, entity, from_milvus_index_type, ids_expr, json_cast_type,
+    output_fields, point_from_entity, search_result_from_entity, DEFAULT_VECTOR_FIELD, ID_FIELD,
+};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::{ApiKeyStyle, VectorConnection};
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::decode_filter;
+use golem_vector::filter::milvus::{milvus_string, to_milvus_expr};
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, CollectionInfo, Credentials, FilterExpression, Id,
+    Metadata, PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::metadata::merge_metadata;
+use golem_vector::payload_index::milvus_scalar_index_type;
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::quantization::to_milvus_index_params;
+use golem_vector::recommend::{exclude_examples, recommendation_vector};
+use golem_vector::search::{reject_keywords, search_by_vector_of, search_each};
+use golem_vector::sparse::{reject_sparse_query, reject_sparse_records};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::write_options::milvus_consistency_level;
+use golem_vector::LOGGING_STATE;
+use log::warn;
+use serde_json::{json, Map, Value};
+
+mod client;
+mod conversions;
+
+/// Milvus limits the size of a single insert request to 64MB, which this keeps a batch of
+/// 1536 dimensional vectors well below
+const UPSERT_BATCH_SIZE: usize = 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+struct MilvusComponent;
+
+impl MilvusComponent {
+    const ENDPOINT_ENV_VAR: &'static str = "MILVUS_URL";
+    const TOKEN_ENV_VAR: &'static str = "MILVUS_TOKEN";
+    const DEFAULT_ENDPOINT: &'static str = "http://localhost:19530";
+
+    /// Provider option listing the names of the vector fields of a collection, separated by
+    /// commas. Every field gets the dimension and metric of the collection config.
+    const VECTOR_NAMES_OPTION: &'static str = "vector_names";
+
+    /// Milvus expects both API keys and user credentials as a bearer token, the latter in
+    /// the form `user:password`
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<MilvusApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let endpoint =
+            connection.endpoint_or_env(Self::ENDPOINT_ENV_VAR, Some(Self::DEFAULT_ENDPOINT))?;
+        let headers = match &connection.config().credentials {
+            Some(Credentials::Basic(basic)) => vec![(
+                "Authorization".to_string(),
+                format!("Bearer {}:{}", basic.username, basic.password),
+            )],
+            _ => connection.auth_headers("Milvus", ApiKeyStyle::Bearer, Self::TOKEN_ENV_VAR)?,
+        };
+        Ok(MilvusApi::new(endpoint, headers))
+    }
+
+    fn vector_fields(connection: &ConnectionBorrow<'_>) -> Vec<String> {
+        let names = connection
+            .get::<VectorConnection>()
+            .provider_option(Self::VECTOR_NAMES_OPTION)
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if names.is_empty() {
+            vec![DEFAULT_VECTOR_FIELD.to_string()]
+        } else {
+            names
+        }
+    }
+}
+
+fn milvus_filter(filter: Option<FilterExpression>) -> Result<Option<String>, VectorError> {
+    decode_filter(filter)?
+        .map(|filter| to_milvus_expr(&filter))
+        .transpose()
+}
+
+/// Namespaces are stored as partitions of the collection
+fn partition_names(namespace: Option<&str>) -> Vec<String> {
+    namespace.into_iter().map(|name| name.to_string()).collect()
+}
+
+/// Creates the partition of a namespace the first time it is written to
+fn ensure_partition(
+    api: &MilvusApi,
+    collection: &str,
+    namespace: Option<&str>,
+) -> Result<(), VectorError> {
+    match namespace {
+        Some(partition) if !api.has_partition(collection, partition)? => {
+            api.create_partition(collection, partition)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Milvus writes are acknowledged once they are in the write-ahead log, and visibility is
+/// controlled by the consistency level of reads, so only the consistency option is used
+fn ignore_write_ordering(options: Option<&WriteOptions>) {
+    if options.is_some_and(|options| options.ordering.is_some()) {
+        warn!("Milvus does not support write ordering options, ignoring them");
+    }
+}
+
+/// Fetches entities by id, with all their vectors and metadata
+fn fetch_points(
+    api: &MilvusApi,
+    collection: &str,
+    ids: &[Id],
+    vector_fields: &[String],
+    namespace: Option<&str>,
+    options: Option<&WriteOptions>,
+) -> Result<Vec<Point>, VectorError> {
+    let rows = api.query(&QueryRequest {
+        collection_name: collection.to_string(),
+        filter: ids_expr(ids),
+        output_fields: output_fields(vector_fields, true, true),
+        limit: Some(ids.len() as u32),
+        partition_names: partition_names(namespace),
+        consistency_level: milvus_consistency_level(options).map(|level| level.to_string()),
+    })?;
+    Ok(rows
+        .into_iter()
+        .map(|row| point_from_entity(row, true, true))
+        .collect())
+}
+
+fn upsert_points(
+    api: &MilvusApi,
+    collection: &str,
+    points: Vec<(Id, VectorData, Metadata)>,
+    namespace: Option<&str>,
+) -> Result<(), VectorError> {
+    let data = points
+        .iter()
+        .map(|(id, vector, metadata)| entity(id, vector, Some(metadata)))
+        .collect::<Result<Vec<_>, _>>()?;
+    api.upsert(&UpsertRequest {
+        collection_name: collection.to_string(),
+        data,
+        partition_name: namespace.map(|name| name.to_string()),
+    })
+}
+
+fn search(
+    api: &MilvusApi,
+    collection: &str,
+    vector_fields: &[String],
+    query: SearchQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<SearchResult>, VectorError> {
+    reject_keywords("Milvus", &query)?;
+    reject_sparse_query("Milvus", &query)?;
+    let rows = api.search(&SearchRequest {
+        collection_name: collection.to_string(),
+        data: vec![query.vector],
+        anns_field: query
+            .vector_name
+            .unwrap_or_else(|| DEFAULT_VECTOR_FIELD.to_string()),
+        limit: query.limit,
+        offset: query.offset,
+        filter: milvus_filter(query.filter)?,
+        output_fields: output_fields(vector_fields, query.include_vectors, query.include_metadata),
+        partition_names: partition_names(namespace),
+    })?;
+    Ok(rows
+        .into_iter()
+        .map(|row| search_result_from_entity(row, query.include_vectors, query.include_metadata))
+        .collect())
+}
+
+impl CollectionsGuest for MilvusComponent {
+    /// Creates a collection with a string primary key and dynamic fields for metadata, so
+    /// that points can have arbitrary metadata
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+
+        let index_params = vector_fields
+            .iter()
+            .map(|field| {
+                to_milvus_index_params(
+                    field,
+                    config.dimension,
+                    config.metric,
+                    config.quantization.as_ref(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        api.create_collection(&CreateCollectionRequest {
+            collection_name: name,
+            schema: CollectionSchema {
+                auto_id: false,
+                enable_dynamic_field: true,
+                fields: collection_fields(&vector_fields, config.dimension),
+            },
+            index_params,
+        })
+    }
+
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.drop_collection(&name)
+    }
+
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        Self::api(&connection)?.list_collections()
+    }
+
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<CollectionInfo, VectorError> {
+        let api = Self::api(&connection)?;
+        let description = api.describe_collection(&name)?;
+        let row_count = api.row_count(&description.collection_name)?;
+        Ok(collection_info(description, row_count))
+    }
+
+    fn create_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Milvus snapshots are made with the external milvus-backup tool".to_string(),
+        ))
+    }
+
+    fn restore_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+        _snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Milvus snapshots are restored with the external milvus-backup tool".to_string(),
+        ))
+    }
+
+    fn list_snapshots(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Milvus snapshots are listed with the external milvus-backup tool".to_string(),
+        ))
+    }
+
+    fn get_task_status(
+        _connection: ConnectionBorrow<'_>,
+        _task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Milvus does not support snapshots, so there are no tasks to check".to_string(),
+        ))
+    }
+
+    /// Indexes a metadata field of the dynamic field by its JSON path
+    fn create_payload_index(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        field: String,
+        index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        let milvus_index_type = milvus_scalar_index_type(index_type)?;
+        Self::api(&connection)?.create_index(&CreateIndexRequest {
+            collection_name: collection,
+            index_params: vec![json!({
+                "fieldName": field,
+                "indexName": field,
+                "indexType": milvus_index_type,
+                "params": {
+                    "json_path": field,
+                    "json_cast_type": json_cast_type(index_type),
+                },
+            })],
+        })
+    }
+
+    /// Lists the scalar indexes of the collection, skipping the indexes of vector fields
+    fn list_payload_indexes(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        let api = Self::api(&connection)?;
+        let mut indexes = Vec::new();
+        for index in api.list_indexes(&collection)? {
+            for description in api.describe_index(&collection, &index)? {
+                if let Some(index_type) = from_milvus_index_type(&description.index_type) {
+                    indexes.push(PayloadIndexInfo {
+                        field: description.field_name,
+                        index_type,
+                    });
+                }
+            }
+        }
+        indexes.sort_by(|a, b| a.field.cmp(&b.field));
+        Ok(indexes)
+    }
+
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.create_alias(&alias, &collection)
+    }
+
+    /// Milvus moves an existing alias to another collection atomically
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.alter_alias(&alias, &collection)
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        Self::api(&connection)?.drop_alias(&alias)
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let api = Self::api(&connection)?;
+        api.list_aliases()?
+            .iter()
+            .map(|alias| {
+                let description = api.describe_alias(alias)?;
+                Ok(AliasInfo {
+                    alias: description.alias_name,
+                    collection: description.collection_name,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Writes the entities of a batch with a single upsert request
+struct MilvusUpsert {
+    api: MilvusApi,
+    collection: String,
+    namespace: Option<String>,
+}
+
+impl BatchWriter for MilvusUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        reject_sparse_records("Milvus", batch)?;
+        let data = batch
+            .iter()
+            .map(|record| entity(&record.id, &record.vector, record.metadata.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.api.upsert(&UpsertRequest {
+            collection_name: self.collection.clone(),
+            data,
+            partition_name: self.namespace.clone(),
+        })
+    }
+}
+
+impl VectorsGuest for MilvusComponent {
+    type UpsertStream = VectorUpsertStream<MilvusUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    /// Milvus can only replace whole entities, so the points are fetched and upserted with the
+    /// merged metadata. Ids that do not exist are skipped.
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+        ignore_write_ordering(options.as_ref());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let points = fetch_points(
+            &api,
+            &collection,
+            &ids,
+            &vector_fields,
+            namespace.as_deref(),
+            options.as_ref(),
+        )?
+        .into_iter()
+        .map(|point| {
+            let vector = point.vector.ok_or_else(|| {
+                VectorError::ProviderError(format!("Point {} has no vectors", point.id))
+            })?;
+            let metadata = merge_metadata(point.metadata, metadata.clone());
+            Ok((point.id, vector, metadata))
+        })
+        .collect::<Result<Vec<_>, VectorError>>()?;
+        if points.is_empty() {
+            return Ok(());
+        }
+        upsert_points(&api, &collection, points, namespace.as_deref())
+    }
+
+    /// Replaces the vectors of a point, keeping its metadata. Named vectors which are not
+    /// given keep their current values.
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+        ignore_write_ordering(options.as_ref());
+
+        let point = fetch_points(
+            &api,
+            &collection,
+            std::slice::from_ref(&id),
+            &vector_fields,
+            namespace.as_deref(),
+            options.as_ref(),
+        )?
+        .pop()
+        .ok_or_else(|| VectorError::NotFound(format!("Point {id} does not exist")))?;
+
+        let vector = match (point.vector, vector) {
+            (Some(VectorData::Named(mut existing)), VectorData::Named(updates)) => {
+                for (name, values) in updates {
+                    match existing.iter_mut().find(|(existing, _)| *existing == name) {
+                        Some((_, existing_values)) => *existing_values = values,
+                        None => existing.push((name, values)),
+                    }
+                }
+                VectorData::Named(existing)
+            }
+            (_, vector) => vector,
+        };
+        upsert_points(
+            &api,
+            &collection,
+            vec![(id, vector, point.metadata.unwrap_or_default())],
+            namespace.as_deref(),
+        )
+    }
+
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // The id is always returned, the output fields only add vectors and metadata
+        let mut fields = output_fields(&vector_fields, include_vectors, include_metadata);
+        if fields.is_empty() {
+            fields.push(ID_FIELD.to_string());
+        }
+        let rows = api.query(&QueryRequest {
+            collection_name: collection,
+            filter: ids_expr(&ids),
+            output_fields: fields,
+            limit: Some(ids.len() as u32),
+            partition_names: partition_names(namespace.as_deref()),
+            consistency_level: None,
+        })?;
+        let mut points: Vec<Point> = rows
+            .into_iter()
+            .map(|row| point_from_entity(row, include_vectors, include_metadata))
+            .collect();
+        points.sort_by_key(|point| ids.iter().position(|id| *id == point.id));
+        Ok(points)
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        ignore_write_ordering(options.as_ref());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        api.delete(&DeleteRequest {
+            collection_name: collection,
+            filter: ids_expr(&ids),
+            partition_name: namespace,
+        })
+    }
+}
+
+impl SearchGuest for MilvusComponent {
+    type PointStream = VectorPointStream<MilvusPointPager>;
+
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+        search(
+            &api,
+            &collection,
+            &vector_fields,
+            query,
+            namespace.as_deref(),
+        )
+    }
+
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+        search_each(queries, |query| {
+            search(
+                &api,
+                &collection,
+                &vector_fields,
+                query,
+                namespace.as_deref(),
+            )
+        })
+    }
+
+    /// Searches with the vector of the point, for collections with a single vector field
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+
+        search_by_vector_of(
+            &id,
+            limit,
+            |id| {
+                let point = fetch_points(
+                    &api,
+                    &collection,
+                    std::slice::from_ref(id),
+                    &vector_fields,
+                    namespace.as_deref(),
+                    None,
+                )?
+                .pop();
+                match point.and_then(|point| point.vector) {
+                    Some(VectorData::Dense(values)) => Ok(values),
+                    Some(VectorData::Named(_)) => Err(VectorError::UnsupportedFeature(
+                        "Milvus searches by id are not supported for named vectors".to_string(),
+                    )),
+                    None => Err(VectorError::NotFound(format!("Point {id} does not exist"))),
+                }
+            },
+            |vector, limit| {
+                search(
+                    &api,
+                    &collection,
+                    &vector_fields,
+                    SearchQuery {
+                        vector,
+                        vector_name: None,
+                        limit,
+                        offset: None,
+                        filter,
+                        include_vectors: false,
+                        include_metadata: true,
+                        keywords: None,
+                        sparse: None,
+                    },
+                    namespace.as_deref(),
+                )
+            },
+        )
+    }
+
+    /// Milvus has no recommendation API, so the examples' vectors are combined into a query
+    /// vector, which is searched with the example points excluded from the results
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let vector_fields = Self::vector_fields(&connection);
+
+        let examples = |ids: &[Id]| -> Result<Vec<Vec<f32>>, VectorError> {
+            if ids.is_empty() {
+                return Ok(vec![]);
+            }
+            let points = fetch_points(
+                &api,
+                &collection,
+                ids,
+                &vector_fields,
+                namespace.as_deref(),
+                None,
+            )?;
+            ids.iter()
+                .map(|id| {
+                    match points
+                        .iter()
+                        .find(|point| point.id == *id)
+                        .and_then(|point| point.vector.as_ref())
+                    {
+                        Some(VectorData::Dense(values)) => Ok(values.clone()),
+                        Some(VectorData::Named(_)) => Err(VectorError::UnsupportedFeature(
+                            "Milvus recommendations are not supported for named vectors"
+                                .to_string(),
+                        )),
+                        None => Err(VectorError::NotFound(format!(
+                            "Example point {id} does not exist"
+                        ))),
+                    }
+                })
+                .collect()
+        };
+        let vector = recommendation_vector(&examples(&positive)?, &examples(&negative)?)?;
+
+        let results = search(
+            &api,
+            &collection,
+            &vector_fields,
+            SearchQuery {
+                vector,
+                vector_name: None,
+                limit: limit + (positive.len() + negative.len()) as u32,
+                offset: None,
+                filter,
+                include_vectors: false,
+                include_metadata: true,
+                keywords: None,
+                sparse: None,
+            },
+            namespace.as_deref(),
+        )?;
+        Ok(exclude_examples(results, &positive, &negative, limit))
+    }
+
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        let rows = Self::api(&connection)?.query(&QueryRequest {
+            collection_name: collection,
+            filter: milvus_filter(filter)?.unwrap_or_default(),
+            output_fields: vec!["count(*)".to_string()],
+            limit: None,
+            partition_names: partition_names(namespace.as_deref()),
+            consistency_level: None,
+        })?;
+        rows.first()
+            .and_then(|row| row.get("count(*)"))
+            .and_then(Value::as_u64)
+            .ok_or_else(|| {
+                VectorError::ProviderError("Milvus did not return the count".to_string())
+            })
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for MilvusComponent {
+    type BatchWriter = MilvusUpsert;
+    type PointPager = MilvusPointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<MilvusUpsert, VectorError> {
+        let api = Self::api(&connection)?;
+        ignore_write_ordering(options.as_ref());
+        ensure_partition(&api, &collection, namespace.as_deref())?;
+        Ok(MilvusUpsert {
+            api,
+            collection,
+            namespace,
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> MilvusPointPager {
+        let vector_fields = Self::vector_fields(&connection);
+        let scroll = Self::api(&connection).and_then(|api| {
+            let filter = milvus_filter(options.filter.clone())?;
+            Ok(MilvusScroll {
+                api,
+                collection,
+                filter,
+                vector_fields,
+                namespace,
+                options,
+            })
+        });
+        MilvusPointPager { scroll }
+    }
+}
+
+struct MilvusScroll {
+    api: MilvusApi,
+    collection: String,
+    filter: Option<String>,
+    vector_fields: Vec<String>,
+    namespace: Option<String>,
+    options: ScrollOptions,
+}
+
+/// Pages through entities in primary key order. The cursor is the id of the last point of the
+/// previous page, and every page queries the entities with greater ids, which unlike offsets
+/// is not limited to the first 16384 entities.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct MilvusPointPager {
+    scroll: Result<MilvusScroll, VectorError>,
+}
+
+impl PointPager for MilvusPointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let limit = scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+        let after = cursor.map(|id| format!("{ID_FIELD} > {}", milvus_string(id)));
+        let filter = match (&scroll.filter, after) {
+            (Some(filter), Some(after)) => format!("{filter} and ({after})"),
+            (Some(filter), None) => filter.clone(),
+            (None, Some(after)) => after,
+            (None, None) => String::new(),
+        };
+
+        // The id is always returned, the output fields only add vectors and metadata
+        let mut fields = output_fields(
+            &scroll.vector_fields,
+            scroll.options.include_vectors,
+            scroll.options.include_metadata,
+        );
+        if fields.is_empty() {
+            fields.push(ID_FIELD.to_string());
+        }
+
+        let rows: Vec<Map<String, Value>> = scroll.api.query(&QueryRequest {
+            collection_name: scroll.collection.clone(),
+            filter,
+            output_fields: fields,
+            limit: Some(limit),
+            partition_names: partition_names(scroll.namespace.as_deref()),
+            consistency_level: None,
+        })?;
+        let full_page = rows.len() as u32 >= limit;
+
+        let mut points: Vec<Point> = rows
+            .into_iter()
+            .map(|row| {
+                point_from_entity(
+                    row,
+                    scroll.options.include_vectors,
+                    scroll.options.include_metadata,
+                )
+            })
+            .collect();
+        points.sort_by(|a, b| a.id.cmp(&b.id));
+        let next_cursor = points
+            .last()
+            .filter(|_| full_page)
+            .map(|point| point.id.clone());
+        Ok(PointPage {
+            points,
+            next_cursor,
+        })
+    }
+}
+
+type DurableMilvusComponent = DurableVector<MilvusComponent>;
+
+golem_vector::export_vector!(DurableMilvusComponent with_types_in golem_vector);
@@ -0,0 +1,217 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Client of a Postgres SQL-over-HTTP endpoint, as served by Neon and by the open source Neon
+/// proxy in front of any Postgres server.
+///
+/// Every statement is sent with its parameters as text. Results are requested in raw text
+/// output mode, so every returned value is the Postgres text representation of the column, or
+/// null.
+///
+/// Based on https://neon.com/docs/serverless/serverless-driver
+pub struct PgvectorApi {
+    endpoint: String,
+    connection_string: String,
+    client: Client,
+}
+
+impl PgvectorApi {
+    pub fn new(endpoint: String, connection_string: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            endpoint,
+            connection_string,
+            client,
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn execute(&self, statement: &Statement) -> Result<QueryResult, VectorError> {
+        trace!("Executing SQL statement: {}", statement.query);
+        self.call(statement, false)
+    }
+
+    /// Executes statements in a single transaction, which is rolled back if any of them fails
+    pub fn transaction(&self, statements: Vec<Statement>) -> Result<Vec<QueryResult>, VectorError> {
+        trace!(
+            "Executing a transaction of {} SQL statements",
+            statements.len()
+        );
+        self.call::<TransactionResult>(
+            &TransactionRequest {
+                queries: statements,
+            },
+            true,
+        )
+        .map(|result| result.results)
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        body: &impl Serialize,
+        transaction: bool,
+    ) -> Result<T, VectorError> {
+        let response = send_with_retry(|| {
+            let mut builder = self
+                .client
+                .request(Method::POST, format!("{}/sql", self.endpoint))
+                .header("Neon-Connection-String", &self.connection_string)
+                .header("Neon-Raw-Text-Output", "true")
+                .header("Neon-Array-Mode", "false")
+                .json(body);
+            if transaction {
+                builder = builder.header("Neon-Batch-Isolation-Level", "ReadCommitted");
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if status.is_success() {
+        trace!("Received response from the SQL endpoint: {body}");
+        serde_json::from_str(&body).map_err(|err| {
+            VectorError::ProviderError(format!("Failed to decode response body: {err}: {body}"))
+        })
+    } else {
+        trace!("Received {status} response from the SQL endpoint: {body}");
+        Err(from_postgres_error(status, body))
+    }
+}
+
+/// Maps the SQLSTATE code of a failed statement, falling back to the HTTP status for errors
+/// of the endpoint itself
+fn from_postgres_error(status: StatusCode, body: String) -> VectorError {
+    let Ok(error) = serde_json::from_str::<PostgresError>(&body) else {
+        return from_http_status(status, body);
+    };
+    let Some(code) = error.code else {
+        return from_http_status(status, body);
+    };
+    let message = format!("Postgres error {code}: {}", error.message);
+    match code.as_str() {
+        "42P01" | "42704" => VectorError::NotFound(message),
+        "42P07" | "42710" | "23505" => VectorError::AlreadyExists(message),
+        "28000" | "28P01" | "42501" => VectorError::Unauthorized(message),
+        "53300" => VectorError::RateLimited(message),
+        _ if error.message.contains("dimensions") => VectorError::DimensionMismatch(message),
+        _ if code.starts_with("22") || code.starts_with("42") || code.starts_with("2B") => {
+            VectorError::InvalidParams(message)
+        }
+        _ => VectorError::ProviderError(message),
+    }
+}
+
+/// A SQL statement with `$n` placeholders, and the text values of its parameters
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statement {
+    pub query: String,
+    pub params: Vec<Option<String>>,
+}
+
+impl Statement {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Adds a parameter, returning its placeholder
+    pub fn param(&mut self, value: impl Into<String>) -> String {
+        self.optional_param(Some(value.into()))
+    }
+
+    /// Adds a parameter which is `NULL` when there is no value, returning its placeholder
+    pub fn optional_param(&mut self, value: Option<String>) -> String {
+        self.params.push(value);
+        format!("${}", self.params.len())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    #[serde(default)]
+    pub row_count: Option<u64>,
+    #[serde(default)]
+    pub rows: Vec<Map<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TransactionRequest {
+    queries: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct TransactionResult {
+    results: Vec<QueryResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PostgresError {
+    message: String,
+    #[serde(default)]
+    code: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{from_postgres_error, Statement};
+    use golem_vector::golem::vector::types::VectorError;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn maps_sqlstate_codes() {
+        let error = |code: &str, message: &str| {
+            from_postgres_error(
+                StatusCode::BAD_REQUEST,
+                format!(r#"{{"message":"{message}","code":"{code}"}}"#),
+            )
+        };
+        assert!(matches!(
+            error("42P01", "relation \\\"docs\\\" does not exist"),
+            VectorError::NotFound(_)
+        ));
+        assert!(matches!(
+            error("22000", "different vector dimensions 3 and 2"),
+            VectorError::DimensionMismatch(_)
+        ));
+        assert!(matches!(
+            error("22P02", "invalid input syntax"),
+            VectorError::InvalidParams(_)
+        ));
+        assert!(matches!(
+            from_postgres_error(StatusCode::UNAUTHORIZED, "denied".to_string()),
+            VectorError::Unauthorized(_)
+        ));
+    }
+
+    #[test]
+    fn numbers_parameters_in_order() {
+        let mut statement = Statement::new("");
+        assert_eq!(statement.param("a"), "$1");
+        assert_eq!(statement.param("b"), "$2");
+        assert_eq!(
+            statement.params,
+            vec![Some("a".to_string()), Some("b".to_string())]
+        );
+    }
+}
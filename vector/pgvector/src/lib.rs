@@ -0,0 +1,1056 @@
+use crate::client::{PgvectorApi, Statement};
+use crate::conversions::{
+    dimension_from_column_type, endpoint_from_connection_string, index_expression,
+    index_type_from_name, index_type_name, metric_from_name, metric_name, point_from_row,
+    quote_ident, quote_literal, search_result_from_row, vector_to_text, CollectionComment,
+    IndexComment, VectorColumn, METADATA_COLUMN, VECTOR_COLUMN,
+};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::VectorConnection;
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::pgvector::to_pgvector_where;
+use golem_vector::filter::{decode_filter, Filter};
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::CollectionInfo as VectorCollectionInfo;
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, DistanceMetric, FilterExpression, Id, Metadata,
+    PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::metadata::metadata_to_json;
+use golem_vector::named::expect_dense;
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::quantization::pgvector_column_type;
+use golem_vector::recommend::{exclude_examples, recommendation_vector};
+use golem_vector::search::{reject_keywords, search_by_vector_of, search_each};
+use golem_vector::sparse::{reject_sparse_query, reject_sparse_records};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::LOGGING_STATE;
+use log::warn;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+mod client;
+mod conversions;
+
+/// Every point of a batch takes four statement parameters, so this stays well below the
+/// limit of 65535 parameters of a Postgres statement
+const UPSERT_BATCH_SIZE: usize = 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+const DEFAULT_HNSW_M: u32 = 16;
+const DEFAULT_HNSW_EF_CONSTRUCTION: u32 = 64;
+const DEFAULT_IVFFLAT_LISTS: u32 = 100;
+
+/// The metric and vector column type of a collection, needed to build its search queries
+#[derive(Debug, Clone, Copy)]
+struct CollectionInfo {
+    metric: DistanceMetric,
+    column: VectorColumn,
+}
+
+thread_local! {
+    /// Collections used by the worker, by endpoint and collection name. The metric and column
+    /// type of a collection are fixed when it is created.
+    static COLLECTIONS: RefCell<HashMap<(String, String), CollectionInfo>> =
+        RefCell::new(HashMap::new());
+}
+
+struct PgvectorComponent;
+
+impl PgvectorComponent {
+    const ENDPOINT_ENV_VAR: &'static str = "PGVECTOR_URL";
+    const CONNECTION_STRING_ENV_VAR: &'static str = "PGVECTOR_CONNECTION_STRING";
+
+    /// Provider option selecting the vector index of new collections: `hnsw` (the default),
+    /// `ivfflat` or `none`
+    const INDEX_TYPE_OPTION: &'static str = "index_type";
+    const HNSW_M_OPTION: &'static str = "hnsw_m";
+    const HNSW_EF_CONSTRUCTION_OPTION: &'static str = "hnsw_ef_construction";
+    const IVFFLAT_LISTS_OPTION: &'static str = "ivfflat_lists";
+
+    /// The Postgres connection string is the API key of the connection. Without an endpoint,
+    /// requests are sent to the SQL-over-HTTP endpoint of the connection string's host, as
+    /// served by Neon.
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<PgvectorApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let connection_string =
+            connection.api_key_or_env("pgvector", Self::CONNECTION_STRING_ENV_VAR)?;
+        let endpoint = match connection.endpoint_or_env(Self::ENDPOINT_ENV_VAR, None) {
+            Ok(endpoint) => endpoint,
+            Err(_) => endpoint_from_connection_string(&connection_string)?,
+        };
+        Ok(PgvectorApi::new(endpoint, connection_string))
+    }
+
+    fn index_option(
+        connection: &ConnectionBorrow<'_>,
+        key: &str,
+        default: u32,
+    ) -> Result<u32, VectorError> {
+        match connection.get::<VectorConnection>().provider_option(key) {
+            Some(value) => value.parse().map_err(|_| {
+                VectorError::InvalidParams(format!(
+                    "Invalid {key} provider option '{value}', expected a positive integer"
+                ))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    /// Builds the statement creating the vector index of a new collection
+    fn vector_index(
+        connection: &ConnectionBorrow<'_>,
+        table: &str,
+        info: CollectionInfo,
+    ) -> Result<Option<Statement>, VectorError> {
+        let operator_class = info.column.operator_class(info.metric);
+        let index_type = connection
+            .get::<VectorConnection>()
+            .provider_option(Self::INDEX_TYPE_OPTION)
+            .unwrap_or("hnsw")
+            .to_string();
+        match index_type.as_str() {
+            "hnsw" => {
+                let m = Self::index_option(connection, Self::HNSW_M_OPTION, DEFAULT_HNSW_M)?;
+                let ef_construction = Self::index_option(
+                    connection,
+                    Self::HNSW_EF_CONSTRUCTION_OPTION,
+                    DEFAULT_HNSW_EF_CONSTRUCTION,
+                )?;
+                Ok(Some(Statement::new(format!(
+                    "CREATE INDEX ON {table} USING hnsw ({VECTOR_COLUMN} {operator_class}) WITH (m = {m}, ef_construction = {ef_construction})"
+                ))))
+            }
+            "ivfflat" => {
+                let lists = Self::index_option(
+                    connection,
+                    Self::IVFFLAT_LISTS_OPTION,
+                    DEFAULT_IVFFLAT_LISTS,
+                )?;
+                Ok(Some(Statement::new(format!(
+                    "CREATE INDEX ON {table} USING ivfflat ({VECTOR_COLUMN} {operator_class}) WITH (lists = {lists})"
+                ))))
+            }
+            "none" => Ok(None),
+            other => Err(VectorError::InvalidParams(format!(
+                "Invalid {} provider option '{other}', expected hnsw, ivfflat or none",
+                Self::INDEX_TYPE_OPTION
+            ))),
+        }
+    }
+}
+
+/// Gets the metric and vector column type of a collection, reading them from the database the
+/// first time a collection is used
+fn collection_info(api: &PgvectorApi, collection: &str) -> Result<CollectionInfo, VectorError> {
+    let key = (api.endpoint().to_string(), collection.to_string());
+    if let Some(info) = COLLECTIONS.with_borrow(|collections| collections.get(&key).copied()) {
+        return Ok(info);
+    }
+
+    let mut statement = Statement::new("");
+    let table = statement.param(quote_ident(collection)?);
+    statement.query = format!(
+        "SELECT obj_description(c.oid, 'pg_class') AS comment, format_type(a.atttypid, a.atttypmod) AS column_type \
+         FROM pg_class c JOIN pg_attribute a ON a.attrelid = c.oid AND a.attname = '{VECTOR_COLUMN}' \
+         WHERE c.oid = to_regclass({table})"
+    );
+    let result = api.execute(&statement)?;
+    let row = result
+        .rows
+        .first()
+        .ok_or_else(|| VectorError::NotFound(format!("Collection {collection} does not exist")))?;
+
+    let not_a_collection = || {
+        VectorError::InvalidParams(format!(
+            "Table {collection} was not created as a golem:vector collection"
+        ))
+    };
+    let metric = row
+        .get("comment")
+        .and_then(Value::as_str)
+        .and_then(|comment| serde_json::from_str::<CollectionComment>(comment).ok())
+        .and_then(|comment| metric_from_name(&comment.metric))
+        .ok_or_else(not_a_collection)?;
+    let column = row
+        .get("column_type")
+        .and_then(Value::as_str)
+        .and_then(VectorColumn::from_column_type)
+        .ok_or_else(not_a_collection)?;
+
+    let info = CollectionInfo { metric, column };
+    COLLECTIONS.with_borrow_mut(|collections| collections.insert(key, info));
+    Ok(info)
+}
+
+fn forget_collection(api: &PgvectorApi, collection: &str) {
+    COLLECTIONS.with_borrow_mut(|collections| {
+        collections.remove(&(api.endpoint().to_string(), collection.to_string()))
+    });
+}
+
+fn comment_literal(comment: &impl serde::Serialize) -> String {
+    quote_literal(&serde_json::to_string(comment).unwrap_or_default())
+}
+
+/// Restricts a statement to the points of a namespace
+fn namespace_condition(statement: &mut Statement, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) => format!(" AND namespace = {}", statement.param(namespace)),
+        None => String::new(),
+    }
+}
+
+fn filter_condition(
+    statement: &mut Statement,
+    filter: Option<&Filter>,
+) -> Result<String, VectorError> {
+    match filter {
+        Some(filter) => {
+            let sql = to_pgvector_where(filter, METADATA_COLUMN, statement.params.len() + 1)?;
+            statement.params.extend(sql.params.into_iter().map(Some));
+            Ok(format!(" AND {}", sql.clause))
+        }
+        None => Ok(String::new()),
+    }
+}
+
+/// Selects points by id, passed as a single JSON array parameter
+fn ids_condition(statement: &mut Statement, ids: &[Id]) -> String {
+    let ids = statement.param(Value::from(ids.to_vec()).to_string());
+    format!("id IN (SELECT jsonb_array_elements_text({ids}::jsonb))")
+}
+
+fn select_columns(include_vectors: bool, include_metadata: bool) -> String {
+    let mut columns = vec!["id"];
+    if include_vectors {
+        columns.push(VECTOR_COLUMN);
+    }
+    if include_metadata {
+        columns.push(METADATA_COLUMN);
+    }
+    columns.join(", ")
+}
+
+/// Gets points by id, in the order of the ids
+fn fetch_points(
+    api: &PgvectorApi,
+    table: &str,
+    ids: &[Id],
+    namespace: Option<&str>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Result<Vec<Point>, VectorError> {
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut statement = Statement::new("");
+    let condition = ids_condition(&mut statement, ids);
+    let namespace = namespace_condition(&mut statement, namespace);
+    statement.query = format!(
+        "SELECT {} FROM {table} WHERE {condition}{namespace}",
+        select_columns(include_vectors, include_metadata)
+    );
+    let mut points = api
+        .execute(&statement)?
+        .rows
+        .into_iter()
+        .map(|row| point_from_row(row, include_vectors, include_metadata))
+        .collect::<Vec<_>>();
+    points.sort_by_key(|point| ids.iter().position(|id| *id == point.id));
+    Ok(points)
+}
+
+/// Postgres commits every statement before the endpoint responds, so there is nothing to
+/// wait for or tune
+fn ignore_write_options(options: Option<&WriteOptions>) {
+    if options.is_some_and(|options| options.ordering.is_some() || options.consistency.is_some()) {
+        warn!("pgvector does not support write ordering and consistency options, ignoring them");
+    }
+}
+
+fn search(
+    api: &PgvectorApi,
+    collection: &str,
+    query: SearchQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<SearchResult>, VectorError> {
+    reject_keywords("pgvector", &query)?;
+    reject_sparse_query("pgvector", &query)?;
+    if query.vector_name.is_some() {
+        return Err(VectorError::UnsupportedFeature(
+            "pgvector does not support named vectors".to_string(),
+        ));
+    }
+    let table = quote_ident(collection)?;
+    let info = collection_info(api, collection)?;
+    let filter = decode_filter(query.filter)?;
+
+    let mut statement = Statement::new("");
+    let vector = info
+        .column
+        .value(&statement.param(vector_to_text(&query.vector)));
+    let distance = info.column.distance(info.metric, &vector);
+    let score = info.column.score(info.metric, &distance);
+    let namespace = namespace_condition(&mut statement, namespace);
+    let filter = filter_condition(&mut statement, filter.as_ref())?;
+    let offset = match query.offset {
+        Some(offset) => format!(" OFFSET {offset}"),
+        None => String::new(),
+    };
+    statement.query = format!(
+        "SELECT {}, {score} AS score FROM {table} WHERE TRUE{namespace}{filter} ORDER BY {distance} LIMIT {}{offset}",
+        select_columns(query.include_vectors, query.include_metadata),
+        query.limit
+    );
+
+    let result = api.execute(&statement)?;
+    Ok(result
+        .rows
+        .into_iter()
+        .map(|row| search_result_from_row(row, query.include_vectors, query.include_metadata))
+        .collect())
+}
+
+impl CollectionsGuest for PgvectorComponent {
+    /// Creates a table with the point id as primary key, the namespace, the vector and the
+    /// metadata as `jsonb`, and an index of the vectors for the collection's metric
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let table = quote_ident(&name)?;
+        let column_type = pgvector_column_type(config.dimension, config.quantization.as_ref())?;
+        let info = CollectionInfo {
+            metric: config.metric,
+            column: VectorColumn::from_column_type(&column_type).ok_or_else(|| {
+                VectorError::ProviderError(format!("Unexpected column type {column_type}"))
+            })?,
+        };
+        let comment = CollectionComment {
+            metric: metric_name(config.metric).to_string(),
+            alias_of: None,
+        };
+
+        let mut statements = vec![
+            Statement::new("CREATE EXTENSION IF NOT EXISTS vector"),
+            Statement::new(format!(
+                "CREATE TABLE {table} (id text PRIMARY KEY, namespace text, {VECTOR_COLUMN} {column_type} NOT NULL, {METADATA_COLUMN} jsonb NOT NULL DEFAULT '{{}}')"
+            )),
+            Statement::new(format!(
+                "COMMENT ON TABLE {table} IS {}",
+                comment_literal(&comment)
+            )),
+            Statement::new(format!("CREATE INDEX ON {table} (namespace)")),
+        ];
+        statements.extend(Self::vector_index(&connection, &table, info)?);
+        api.transaction(statements)?;
+
+        COLLECTIONS.with_borrow_mut(|collections| {
+            collections.insert((api.endpoint().to_string(), name), info)
+        });
+        Ok(())
+    }
+
+    /// Collections with aliases cannot be deleted, as Postgres does not drop the alias views
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        api.execute(&Statement::new(format!(
+            "DROP TABLE {}",
+            quote_ident(&name)?
+        )))?;
+        forget_collection(&api, &name);
+        Ok(())
+    }
+
+    /// Collections are the tables commented as collections, which leaves out the alias views
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        let result = Self::api(&connection)?.execute(&Statement::new(
+            "SELECT c.relname AS name, obj_description(c.oid, 'pg_class') AS comment FROM pg_class c \
+             WHERE c.relkind = 'r' AND c.relnamespace = current_schema()::regnamespace ORDER BY c.relname",
+        ))?;
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let name = row.get("name").and_then(Value::as_str)?;
+                let comment = row.get("comment").and_then(Value::as_str)?;
+                let comment = serde_json::from_str::<CollectionComment>(comment).ok()?;
+                comment.alias_of.is_none().then(|| name.to_string())
+            })
+            .collect())
+    }
+
+    /// Aliases are described as the collection they point to
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<VectorCollectionInfo, VectorError> {
+        let api = Self::api(&connection)?;
+        let info = collection_info(&api, &name)?;
+
+        let mut statement = Statement::new("");
+        let table = quote_ident(&name)?;
+        let regclass = statement.param(table.clone());
+        statement.query = format!(
+            "SELECT obj_description(c.oid, 'pg_class') AS comment, format_type(a.atttypid, a.atttypmod) AS column_type, \
+             (SELECT count(*) FROM {table}) AS count \
+             FROM pg_class c JOIN pg_attribute a ON a.attrelid = c.oid AND a.attname = '{VECTOR_COLUMN}' \
+             WHERE c.oid = to_regclass({regclass})"
+        );
+        let result = api.execute(&statement)?;
+        let row = result
+            .rows
+            .first()
+            .ok_or_else(|| VectorError::NotFound(format!("Collection {name} does not exist")))?;
+
+        let alias_of = row
+            .get("comment")
+            .and_then(Value::as_str)
+            .and_then(|comment| serde_json::from_str::<CollectionComment>(comment).ok())
+            .and_then(|comment| comment.alias_of);
+        Ok(VectorCollectionInfo {
+            name: alias_of.unwrap_or(name),
+            dimension: row
+                .get("column_type")
+                .and_then(Value::as_str)
+                .and_then(dimension_from_column_type),
+            metric: Some(info.metric),
+            point_count: row
+                .get("count")
+                .and_then(Value::as_str)
+                .and_then(|count| count.parse().ok()),
+        })
+    }
+
+    fn create_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "pgvector collections are Postgres tables, back them up with the database's tools"
+                .to_string(),
+        ))
+    }
+
+    fn restore_snapshot(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+        _snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "pgvector collections are Postgres tables, restore them with the database's tools"
+                .to_string(),
+        ))
+    }
+
+    fn list_snapshots(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "pgvector collections are Postgres tables, which do not have snapshots".to_string(),
+        ))
+    }
+
+    fn get_task_status(
+        _connection: ConnectionBorrow<'_>,
+        _task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "pgvector does not support snapshots, so there are no tasks to check".to_string(),
+        ))
+    }
+
+    /// Creates an expression index on the metadata field, commented with the field and index
+    /// type so that it can be listed
+    fn create_payload_index(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        field: String,
+        index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        let table = quote_ident(&collection)?;
+        let index = quote_ident(&format!("{collection}_{field}_idx"))?;
+        let expression = index_expression(&field, index_type)?;
+        let comment = IndexComment {
+            field,
+            index_type: index_type_name(index_type).to_string(),
+        };
+
+        Self::api(&connection)?.transaction(vec![
+            Statement::new(format!("CREATE INDEX {index} ON {table} ({expression})")),
+            Statement::new(format!(
+                "COMMENT ON INDEX {index} IS {}",
+                comment_literal(&comment)
+            )),
+        ])?;
+        Ok(())
+    }
+
+    fn list_payload_indexes(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        let mut statement = Statement::new("");
+        let table = statement.param(quote_ident(&collection)?);
+        statement.query = format!(
+            "SELECT obj_description(i.indexrelid, 'pg_class') AS comment FROM pg_index i WHERE i.indrelid = to_regclass({table})"
+        );
+
+        let result = Self::api(&connection)?.execute(&statement)?;
+        let mut indexes = result
+            .rows
+            .iter()
+            .filter_map(|row| row.get("comment").and_then(Value::as_str))
+            .filter_map(|comment| serde_json::from_str::<IndexComment>(comment).ok())
+            .filter_map(|comment| {
+                index_type_from_name(&comment.index_type).map(|index_type| PayloadIndexInfo {
+                    field: comment.field,
+                    index_type,
+                })
+            })
+            .collect::<Vec<_>>();
+        indexes.sort_by(|a, b| a.field.cmp(&b.field));
+        Ok(indexes)
+    }
+
+    /// Aliases are views of the collection's table, which Postgres can write through
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let info = collection_info(&api, &collection)?;
+        api.transaction(alias_statements(&alias, &collection, info)?)?;
+        Ok(())
+    }
+
+    /// The view is replaced in a single transaction, as its columns may have a different type
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let info = collection_info(&api, &collection)?;
+        let mut statements = vec![Statement::new(format!(
+            "DROP VIEW {}",
+            quote_ident(&alias)?
+        ))];
+        statements.extend(alias_statements(&alias, &collection, info)?);
+        api.transaction(statements)?;
+        forget_collection(&api, &alias);
+        Ok(())
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        api.execute(&Statement::new(format!(
+            "DROP VIEW {}",
+            quote_ident(&alias)?
+        )))?;
+        forget_collection(&api, &alias);
+        Ok(())
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let result = Self::api(&connection)?.execute(&Statement::new(
+            "SELECT c.relname AS alias, obj_description(c.oid, 'pg_class') AS comment FROM pg_class c \
+             WHERE c.relkind = 'v' AND c.relnamespace = current_schema()::regnamespace",
+        ))?;
+        Ok(result
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let alias = row.get("alias").and_then(Value::as_str)?;
+                let comment = row.get("comment").and_then(Value::as_str)?;
+                let comment = serde_json::from_str::<CollectionComment>(comment).ok()?;
+                Some(AliasInfo {
+                    alias: alias.to_string(),
+                    collection: comment.alias_of?,
+                })
+            })
+            .collect())
+    }
+}
+
+fn alias_statements(
+    alias: &str,
+    collection: &str,
+    info: CollectionInfo,
+) -> Result<Vec<Statement>, VectorError> {
+    let view = quote_ident(alias)?;
+    let comment = CollectionComment {
+        metric: metric_name(info.metric).to_string(),
+        alias_of: Some(collection.to_string()),
+    };
+    Ok(vec![
+        Statement::new(format!(
+            "CREATE VIEW {view} AS SELECT * FROM {}",
+            quote_ident(collection)?
+        )),
+        Statement::new(format!(
+            "COMMENT ON VIEW {view} IS {}",
+            comment_literal(&comment)
+        )),
+    ])
+}
+
+/// Writes the points of a batch with a single `INSERT ... ON CONFLICT` statement
+struct PgvectorUpsert {
+    api: PgvectorApi,
+    table: String,
+    info: CollectionInfo,
+    namespace: Option<String>,
+}
+
+impl BatchWriter for PgvectorUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        reject_sparse_records("pgvector", batch)?;
+        let mut statement = Statement::new("");
+        let rows = batch
+            .iter()
+            .map(|record| {
+                let vector = expect_dense(record, "pgvector")?;
+                let metadata = record
+                    .metadata
+                    .as_ref()
+                    .map(metadata_to_json)
+                    .unwrap_or_else(|| Value::Object(Default::default()));
+                Ok(format!(
+                    "({}, {}, {}, {}::jsonb)",
+                    statement.param(record.id.clone()),
+                    statement.optional_param(self.namespace.clone()),
+                    self.info
+                        .column
+                        .value(&statement.param(vector_to_text(vector))),
+                    statement.param(metadata.to_string()),
+                ))
+            })
+            .collect::<Result<Vec<_>, VectorError>>()?;
+        statement.query = format!(
+            "INSERT INTO {} (id, namespace, {VECTOR_COLUMN}, {METADATA_COLUMN}) VALUES {} \
+             ON CONFLICT (id) DO UPDATE SET namespace = EXCLUDED.namespace, \
+             {VECTOR_COLUMN} = EXCLUDED.{VECTOR_COLUMN}, {METADATA_COLUMN} = EXCLUDED.{METADATA_COLUMN}",
+            self.table,
+            rows.join(", ")
+        );
+        self.api.execute(&statement).map(|_| ())
+    }
+}
+
+impl VectorsGuest for PgvectorComponent {
+    type UpsertStream = VectorUpsertStream<PgvectorUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    /// Merges the metadata into the points' `jsonb` metadata
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        ignore_write_options(options.as_ref());
+        let table = quote_ident(&collection)?;
+
+        let mut statement = Statement::new("");
+        let updates = statement.param(metadata_to_json(&metadata).to_string());
+        let ids = ids_condition(&mut statement, &ids);
+        let namespace = namespace_condition(&mut statement, namespace.as_deref());
+        statement.query = format!(
+            "UPDATE {table} SET {METADATA_COLUMN} = {METADATA_COLUMN} || {updates}::jsonb WHERE {ids}{namespace}"
+        );
+        api.execute(&statement)?;
+        Ok(())
+    }
+
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let VectorData::Dense(values) = vector else {
+            return Err(VectorError::UnsupportedFeature(
+                "pgvector does not support named vectors".to_string(),
+            ));
+        };
+        let api = Self::api(&connection)?;
+        ignore_write_options(options.as_ref());
+        let table = quote_ident(&collection)?;
+        let info = collection_info(&api, &collection)?;
+
+        let mut statement = Statement::new("");
+        let value = info.column.value(&statement.param(vector_to_text(&values)));
+        let id_param = statement.param(id.clone());
+        let namespace_filter = namespace_condition(&mut statement, namespace.as_deref());
+        statement.query = format!(
+            "UPDATE {table} SET {VECTOR_COLUMN} = {value} WHERE id = {id_param}{namespace_filter}"
+        );
+        let result = api.execute(&statement)?;
+        if result.row_count == Some(0) {
+            return Err(VectorError::NotFound(format!("Point {id} does not exist")));
+        }
+        Ok(())
+    }
+
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let api = Self::api(&connection)?;
+        let table = quote_ident(&collection)?;
+        fetch_points(
+            &api,
+            &table,
+            &ids,
+            namespace.as_deref(),
+            include_vectors,
+            include_metadata,
+        )
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        ignore_write_options(options.as_ref());
+        let table = quote_ident(&collection)?;
+
+        let mut statement = Statement::new("");
+        let ids = ids_condition(&mut statement, &ids);
+        let namespace = namespace_condition(&mut statement, namespace.as_deref());
+        statement.query = format!("DELETE FROM {table} WHERE {ids}{namespace}");
+        api.execute(&statement)?;
+        Ok(())
+    }
+}
+
+impl SearchGuest for PgvectorComponent {
+    type PointStream = VectorPointStream<PgvectorPointPager>;
+
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        search(&api, &collection, query, namespace.as_deref())
+    }
+
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        search_each(queries, |query| {
+            search(&api, &collection, query, namespace.as_deref())
+        })
+    }
+
+    /// pgvector has no recommendation support, so the examples' vectors are combined into a
+    /// query vector, which is searched with the example points excluded from the results
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let table = quote_ident(&collection)?;
+
+        let examples = |ids: &[Id]| -> Result<Vec<Vec<f32>>, VectorError> {
+            let points = fetch_points(&api, &table, ids, namespace.as_deref(), true, false)?;
+            ids.iter()
+                .map(|id| {
+                    match points
+                        .iter()
+                        .find(|point| point.id == *id)
+                        .and_then(|point| point.vector.as_ref())
+                    {
+                        Some(VectorData::Dense(values)) => Ok(values.clone()),
+                        _ => Err(VectorError::NotFound(format!(
+                            "Example point {id} does not exist"
+                        ))),
+                    }
+                })
+                .collect()
+        };
+        let vector = recommendation_vector(&examples(&positive)?, &examples(&negative)?)?;
+
+        let results = search(
+            &api,
+            &collection,
+            SearchQuery {
+                vector,
+                vector_name: None,
+                limit: limit + (positive.len() + negative.len()) as u32,
+                offset: None,
+                filter,
+                include_vectors: false,
+                include_metadata: true,
+                keywords: None,
+                sparse: None,
+            },
+            namespace.as_deref(),
+        )?;
+        Ok(exclude_examples(results, &positive, &negative, limit))
+    }
+
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let table = quote_ident(&collection)?;
+
+        search_by_vector_of(
+            &id,
+            limit,
+            |id| {
+                let point = fetch_points(
+                    &api,
+                    &table,
+                    std::slice::from_ref(id),
+                    namespace.as_deref(),
+                    true,
+                    false,
+                )?
+                .pop();
+                match point.and_then(|point| point.vector) {
+                    Some(VectorData::Dense(values)) => Ok(values),
+                    _ => Err(VectorError::NotFound(format!("Point {id} does not exist"))),
+                }
+            },
+            |vector, limit| {
+                search(
+                    &api,
+                    &collection,
+                    SearchQuery {
+                        vector,
+                        vector_name: None,
+                        limit,
+                        offset: None,
+                        filter,
+                        include_vectors: false,
+                        include_metadata: true,
+                        keywords: None,
+                        sparse: None,
+                    },
+                    namespace.as_deref(),
+                )
+            },
+        )
+    }
+
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        let table = quote_ident(&collection)?;
+        let filter = decode_filter(filter)?;
+
+        let mut statement = Statement::new("");
+        let namespace = namespace_condition(&mut statement, namespace.as_deref());
+        let filter = filter_condition(&mut statement, filter.as_ref())?;
+        statement.query =
+            format!("SELECT count(*) AS count FROM {table} WHERE TRUE{namespace}{filter}");
+
+        let result = Self::api(&connection)?.execute(&statement)?;
+        result
+            .rows
+            .first()
+            .and_then(|row| row.get("count"))
+            .and_then(Value::as_str)
+            .and_then(|count| count.parse().ok())
+            .ok_or_else(|| {
+                VectorError::ProviderError("Postgres did not return the count".to_string())
+            })
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for PgvectorComponent {
+    type BatchWriter = PgvectorUpsert;
+    type PointPager = PgvectorPointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<PgvectorUpsert, VectorError> {
+        let api = Self::api(&connection)?;
+        ignore_write_options(options.as_ref());
+        let table = quote_ident(&collection)?;
+        let info = collection_info(&api, &collection)?;
+        Ok(PgvectorUpsert {
+            api,
+            table,
+            info,
+            namespace,
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PgvectorPointPager {
+        let scroll = Self::api(&connection).and_then(|api| {
+            let table = quote_ident(&collection)?;
+            let filter = decode_filter(options.filter.clone())?;
+            Ok(PgvectorScroll {
+                api,
+                table,
+                filter,
+                namespace,
+                options,
+            })
+        });
+        PgvectorPointPager { scroll }
+    }
+}
+
+struct PgvectorScroll {
+    api: PgvectorApi,
+    table: String,
+    filter: Option<Filter>,
+    namespace: Option<String>,
+    options: ScrollOptions,
+}
+
+/// Pages through points in id order. The cursor is the id of the last point of the previous
+/// page, so that pages stay fast regardless of how far the scroll is.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct PgvectorPointPager {
+    scroll: Result<PgvectorScroll, VectorError>,
+}
+
+impl PointPager for PgvectorPointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let limit = scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+        let mut statement = Statement::new("");
+        let namespace = namespace_condition(&mut statement, scroll.namespace.as_deref());
+        let filter = filter_condition(&mut statement, scroll.filter.as_ref())?;
+        let after = match cursor {
+            Some(cursor) => format!(" AND id > {}", statement.param(cursor)),
+            None => String::new(),
+        };
+        statement.query = format!(
+            "SELECT {} FROM {} WHERE TRUE{namespace}{filter}{after} ORDER BY id LIMIT {limit}",
+            select_columns(
+                scroll.options.include_vectors,
+                scroll.options.include_metadata
+            ),
+            scroll.table
+        );
+
+        let rows = scroll.api.execute(&statement)?.rows;
+        let full_page = rows.len() as u32 >= limit;
+        let points: Vec<Point> = rows
+            .into_iter()
+            .map(|row| {
+                point_from_row(
+                    row,
+                    scroll.options.include_vectors,
+                    scroll.options.include_metadata,
+                )
+            })
+            .collect();
+        let next_cursor = points
+            .last()
+            .filter(|_| full_page)
+            .map(|point| point.id.clone());
+        Ok(PointPage {
+            points,
+            next_cursor,
+        })
+    }
+}
+
+type DurablePgvectorComponent = DurableVector<PgvectorComponent>;
+
+golem_vector::export_vector!(DurablePgvectorComponent with_types_in golem_vector);
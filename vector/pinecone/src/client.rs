@@ -0,0 +1,538 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Version of the Pinecone API the requests are written for, the first one supporting backups
+const API_VERSION: &str = "2025-04";
+
+/// The Pinecone REST API client.
+///
+/// Index management requests are sent to the control plane, while data requests are sent to
+/// the host of the index they operate on.
+///
+/// Based on https://docs.pinecone.io/reference/api/introduction
+pub struct PineconeApi {
+    controller: String,
+    api_key: String,
+    client: Client,
+}
+
+impl PineconeApi {
+    pub fn new(controller: String, api_key: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            controller,
+            api_key,
+            client,
+        }
+    }
+
+    pub fn controller(&self) -> &str {
+        &self.controller
+    }
+
+    pub fn create_index(&self, request: &CreateIndexRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &self.controller_url("/indexes"),
+            &[],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_indexes(&self) -> Result<Vec<IndexDescription>, VectorError> {
+        self.call::<IndexList>(
+            Method::GET,
+            &self.controller_url("/indexes"),
+            &[],
+            None::<&()>,
+        )
+        .map(|list| list.indexes)
+    }
+
+    pub fn describe_index(&self, name: &str) -> Result<IndexDescription, VectorError> {
+        self.call(
+            Method::GET,
+            &self.controller_url(&format!("/indexes/{name}")),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn delete_index(&self, name: &str) -> Result<(), VectorError> {
+        self.call_without_response(
+            Method::DELETE,
+            &self.controller_url(&format!("/indexes/{name}")),
+            None::<&()>,
+        )
+    }
+
+    pub fn create_backup(&self, index: &str) -> Result<Backup, VectorError> {
+        self.call(
+            Method::POST,
+            &self.controller_url(&format!("/indexes/{index}/backups")),
+            &[],
+            Some(&CreateBackupRequest {}),
+        )
+    }
+
+    pub fn list_backups(&self, index: &str) -> Result<Vec<Backup>, VectorError> {
+        self.call::<BackupList>(
+            Method::GET,
+            &self.controller_url(&format!("/indexes/{index}/backups")),
+            &[],
+            None::<&()>,
+        )
+        .map(|list| list.data)
+    }
+
+    pub fn describe_backup(&self, backup_id: &str) -> Result<Backup, VectorError> {
+        self.call(
+            Method::GET,
+            &self.controller_url(&format!("/backups/{backup_id}")),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn create_index_from_backup(
+        &self,
+        backup_id: &str,
+        index: &str,
+    ) -> Result<RestoreResponse, VectorError> {
+        self.call(
+            Method::POST,
+            &self.controller_url(&format!("/backups/{backup_id}/create-index")),
+            &[],
+            Some(&CreateIndexFromBackupRequest {
+                name: index.to_string(),
+            }),
+        )
+    }
+
+    pub fn describe_restore_job(&self, job_id: &str) -> Result<RestoreJob, VectorError> {
+        self.call(
+            Method::GET,
+            &self.controller_url(&format!("/restore-jobs/{job_id}")),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn upsert(&self, host: &str, request: &UpsertRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &data_url(host, "/vectors/upsert"),
+            &[],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn update(&self, host: &str, request: &UpdateRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &data_url(host, "/vectors/update"),
+            &[],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn delete(&self, host: &str, request: &DeleteRequest) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &data_url(host, "/vectors/delete"),
+            &[],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn fetch(
+        &self,
+        host: &str,
+        ids: &[String],
+        namespace: Option<&str>,
+    ) -> Result<FetchResponse, VectorError> {
+        let mut params: Vec<(&str, String)> = ids.iter().map(|id| ("ids", id.clone())).collect();
+        if let Some(namespace) = namespace {
+            params.push(("namespace", namespace.to_string()));
+        }
+        self.call(
+            Method::GET,
+            &data_url(host, "/vectors/fetch"),
+            &params,
+            None::<&()>,
+        )
+    }
+
+    pub fn query(&self, host: &str, request: &QueryRequest) -> Result<QueryResponse, VectorError> {
+        self.call(Method::POST, &data_url(host, "/query"), &[], Some(request))
+    }
+
+    pub fn list(
+        &self,
+        host: &str,
+        namespace: Option<&str>,
+        limit: u32,
+        pagination_token: Option<&str>,
+    ) -> Result<ListResponse, VectorError> {
+        let mut params = vec![("limit", limit.to_string())];
+        if let Some(namespace) = namespace {
+            params.push(("namespace", namespace.to_string()));
+        }
+        if let Some(token) = pagination_token {
+            params.push(("paginationToken", token.to_string()));
+        }
+        self.call(
+            Method::GET,
+            &data_url(host, "/vectors/list"),
+            &params,
+            None::<&()>,
+        )
+    }
+
+    pub fn describe_index_stats(&self, host: &str) -> Result<IndexStats, VectorError> {
+        self.call(
+            Method::POST,
+            &data_url(host, "/describe_index_stats"),
+            &[],
+            Some(&DescribeIndexStatsRequest {}),
+        )
+    }
+
+    fn controller_url(&self, path: &str) -> String {
+        format!("{}{path}", self.controller)
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        url: &str,
+        params: &[(&str, String)],
+        body: Option<&impl Serialize>,
+    ) -> Result<Response, VectorError> {
+        trace!("Sending {method} {url} request to Pinecone");
+
+        send_with_retry(|| {
+            let mut builder = self
+                .client
+                .request(method.clone(), url)
+                .header("Api-Key", &self.api_key)
+                .header("X-Pinecone-API-Version", API_VERSION);
+            if !params.is_empty() {
+                builder = builder.query(params);
+            }
+            if let Some(body) = body {
+                builder = builder.json(body);
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        params: &[(&str, String)],
+        body: Option<&impl Serialize>,
+    ) -> Result<T, VectorError> {
+        let response = self.request(method, url, params, body)?;
+        parse_response(response)
+    }
+
+    /// Sends a request which is answered with an empty body on success
+    fn call_without_response(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<(), VectorError> {
+        let response = self.request(method, url, &[], body)?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let body = response
+                .text()
+                .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+            trace!("Received {status} response from Pinecone: {body:?}");
+            Err(from_http_status(status, body))
+        }
+    }
+}
+
+/// Index hosts are returned without a scheme
+fn data_url(host: &str, path: &str) -> String {
+    if host.starts_with("http://") || host.starts_with("https://") {
+        format!("{host}{path}")
+    } else {
+        format!("https://{host}{path}")
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if status.is_success() {
+        serde_json::from_str(&raw_body).map_err(|err| {
+            VectorError::ProviderError(format!("Failed to decode response body: {err}: {raw_body}"))
+        })
+    } else {
+        trace!("Received {status} response from Pinecone: {raw_body:?}");
+        Err(from_http_status(status, raw_body))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerlessSpec {
+    pub cloud: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexSpec {
+    pub serverless: ServerlessSpec,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateIndexRequest {
+    pub name: String,
+    pub dimension: u32,
+    pub metric: String,
+    pub spec: IndexSpec,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct IndexList {
+    #[serde(default)]
+    indexes: Vec<IndexDescription>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexDescription {
+    pub name: String,
+    #[serde(default)]
+    pub dimension: Option<u32>,
+    pub metric: String,
+    pub host: String,
+    pub status: IndexStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexStatus {
+    #[serde(default)]
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CreateBackupRequest {}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Backup {
+    pub backup_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BackupList {
+    data: Vec<Backup>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CreateIndexFromBackupRequest {
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestoreResponse {
+    pub restore_job_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RestoreJob {
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PineconeVector {
+    pub id: String,
+    #[serde(default)]
+    pub values: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_values: Option<SparseValues>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// The non-zero elements of a sparse vector, scored by dot product in hybrid queries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseValues {
+    pub indices: Vec<u32>,
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpsertRequest {
+    pub vectors: Vec<PineconeVector>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRequest {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_metadata: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeleteRequest {
+    pub ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FetchResponse {
+    #[serde(default)]
+    pub vectors: HashMap<String, PineconeVector>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+/// A query by vector, or by the id of a stored vector
+pub struct QueryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparse_vector: Option<SparseValues>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub top_k: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub include_values: bool,
+    pub include_metadata: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QueryResponse {
+    #[serde(default)]
+    pub matches: Vec<ScoredVector>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoredVector {
+    pub id: String,
+    #[serde(default)]
+    pub score: f32,
+    #[serde(default)]
+    pub values: Vec<f32>,
+    #[serde(default)]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListResponse {
+    #[serde(default)]
+    pub vectors: Vec<ListItem>,
+    #[serde(default)]
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListItem {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Pagination {
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DescribeIndexStatsRequest {}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexStats {
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceStats>,
+    #[serde(default)]
+    pub total_vector_count: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceStats {
+    #[serde(default)]
+    pub vector_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{data_url, IndexStats, UpdateRequest};
+    use serde_json::json;
+
+    #[test]
+    fn data_urls_default_to_https() {
+        assert_eq!(
+            data_url("docs-abc.svc.pinecone.io", "/query"),
+            "https://docs-abc.svc.pinecone.io/query"
+        );
+        assert_eq!(
+            data_url("http://localhost:5080", "/query"),
+            "http://localhost:5080/query"
+        );
+    }
+
+    #[test]
+    fn uses_camel_case_fields() {
+        assert_eq!(
+            serde_json::to_value(UpdateRequest {
+                id: "1".to_string(),
+                values: None,
+                set_metadata: Some(json!({ "a": 1 })),
+                namespace: None,
+            })
+            .unwrap(),
+            json!({ "id": "1", "setMetadata": { "a": 1 } })
+        );
+
+        let stats: IndexStats = serde_json::from_value(json!({
+            "namespaces": { "tenant-1": { "vectorCount": 3 } },
+            "totalVectorCount": 5,
+            "dimension": 2
+        }))
+        .unwrap();
+        assert_eq!(stats.total_vector_count, 5);
+        assert_eq!(stats.namespaces["tenant-1"].vector_count, 3);
+    }
+}
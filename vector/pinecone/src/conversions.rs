@@ -0,0 +1,193 @@
+use crate::client::{IndexDescription, PineconeVector, ScoredVector, SparseValues};
+use golem_vector::golem::vector::types::{
+    CollectionInfo, DistanceMetric, Metadata, MetadataValue, Point, SearchResult, SparseVector,
+    TaskStatus, VectorData, VectorError,
+};
+use golem_vector::metadata::{json_to_metadata, metadata_value_to_json};
+use golem_vector::sparse::validate_sparse;
+use serde_json::{Map, Value};
+
+pub fn metric_name(metric: DistanceMetric) -> &'static str {
+    match metric {
+        DistanceMetric::Cosine => "cosine",
+        DistanceMetric::Euclidean => "euclidean",
+        DistanceMetric::DotProduct => "dotproduct",
+    }
+}
+
+fn metric_from_name(name: &str) -> Option<DistanceMetric> {
+    match name {
+        "cosine" => Some(DistanceMetric::Cosine),
+        "euclidean" => Some(DistanceMetric::Euclidean),
+        "dotproduct" => Some(DistanceMetric::DotProduct),
+        _ => None,
+    }
+}
+
+pub fn collection_info(index: IndexDescription, point_count: u64) -> CollectionInfo {
+    CollectionInfo {
+        metric: metric_from_name(&index.metric),
+        name: index.name,
+        dimension: index.dimension,
+        point_count: Some(point_count),
+    }
+}
+
+/// Converts metadata to a Pinecone metadata object.
+///
+/// Pinecone only stores strings, numbers, booleans and lists of strings. Null fields are left
+/// out, as Pinecone treats missing fields the same way, and geo points are rejected.
+pub fn to_pinecone_metadata(metadata: &Metadata) -> Result<Value, VectorError> {
+    let mut object = Map::new();
+    for (key, value) in metadata {
+        match value {
+            MetadataValue::NullVal => {}
+            MetadataValue::GeoVal(_) => {
+                return Err(VectorError::UnsupportedFeature(format!(
+                    "Pinecone does not support geo metadata values, used in field '{key}'"
+                )))
+            }
+            value => {
+                object.insert(key.clone(), metadata_value_to_json(value));
+            }
+        }
+    }
+    Ok(Value::Object(object))
+}
+
+pub fn to_sparse_values(sparse: &SparseVector) -> Result<SparseValues, VectorError> {
+    validate_sparse(sparse)?;
+    Ok(SparseValues {
+        indices: sparse.indices.clone(),
+        values: sparse.values.clone(),
+    })
+}
+
+fn from_sparse_values(sparse: SparseValues) -> SparseVector {
+    SparseVector {
+        indices: sparse.indices,
+        values: sparse.values,
+    }
+}
+
+fn from_pinecone_metadata(metadata: Option<&Value>) -> Option<Metadata> {
+    metadata.map(json_to_metadata)
+}
+
+pub fn search_result_from_match(result: ScoredVector, include_vectors: bool) -> SearchResult {
+    SearchResult {
+        id: result.id,
+        score: result.score,
+        vector: include_vectors.then_some(VectorData::Dense(result.values)),
+        metadata: from_pinecone_metadata(result.metadata.as_ref()),
+    }
+}
+
+pub fn point_from_vector(
+    vector: PineconeVector,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Point {
+    Point {
+        id: vector.id,
+        vector: include_vectors.then_some(VectorData::Dense(vector.values)),
+        sparse: vector
+            .sparse_values
+            .filter(|_| include_vectors)
+            .map(from_sparse_values),
+        metadata: if include_metadata {
+            from_pinecone_metadata(vector.metadata.as_ref())
+        } else {
+            None
+        },
+    }
+}
+
+/// Maps the status of a backup, which is `Ready` once it can be restored
+pub fn backup_status(backup_id: String, status: &str) -> TaskStatus {
+    match status.to_ascii_lowercase().as_str() {
+        "ready" => TaskStatus::Succeeded(Some(backup_id)),
+        "failed" => TaskStatus::Failed(format!("Backup {backup_id} failed")),
+        _ => TaskStatus::InProgress,
+    }
+}
+
+/// Maps the status of a restore job, which is `Completed` once the index is restored
+pub fn restore_status(job_id: &str, status: &str) -> TaskStatus {
+    match status.to_ascii_lowercase().as_str() {
+        "completed" => TaskStatus::Succeeded(None),
+        "failed" => TaskStatus::Failed(format!("Restore job {job_id} failed")),
+        _ => TaskStatus::InProgress,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::PineconeVector;
+    use crate::conversions::{backup_status, point_from_vector, to_pinecone_metadata};
+    use golem_vector::golem::vector::types::{
+        GeoPoint, MetadataValue, SparseVector, TaskStatus, VectorError,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn skips_null_metadata_values() {
+        let metadata = vec![
+            ("a".to_string(), MetadataValue::IntegerVal(1)),
+            ("b".to_string(), MetadataValue::NullVal),
+            (
+                "c".to_string(),
+                MetadataValue::StringList(vec!["x".to_string()]),
+            ),
+        ];
+        assert_eq!(
+            to_pinecone_metadata(&metadata).unwrap(),
+            json!({ "a": 1, "c": ["x"] })
+        );
+    }
+
+    #[test]
+    fn rejects_geo_metadata_values() {
+        let metadata = vec![(
+            "location".to_string(),
+            MetadataValue::GeoVal(GeoPoint {
+                latitude: 1.0,
+                longitude: 2.0,
+            }),
+        )];
+        assert!(matches!(
+            to_pinecone_metadata(&metadata),
+            Err(VectorError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn maps_backup_statuses() {
+        assert_eq!(
+            backup_status("b1".to_string(), "Ready"),
+            TaskStatus::Succeeded(Some("b1".to_string()))
+        );
+        assert_eq!(
+            backup_status("b1".to_string(), "Initializing"),
+            TaskStatus::InProgress
+        );
+    }
+
+    #[test]
+    fn returns_sparse_values_with_the_vectors() {
+        let vector: PineconeVector = serde_json::from_value(json!({
+            "id": "a",
+            "values": [1.0],
+            "sparseValues": { "indices": [4, 9], "values": [0.5, 0.25] }
+        }))
+        .unwrap();
+        assert_eq!(
+            point_from_vector(vector.clone(), true, false).sparse,
+            Some(SparseVector {
+                indices: vec![4, 9],
+                values: vec![0.5, 0.25]
+            })
+        );
+        assert_eq!(point_from_vector(vector, false, false).sparse, None);
+    }
+}
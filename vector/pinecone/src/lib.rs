@@ -0,0 +1,874 @@
+use crate::client::{
+    CreateIndexRequest, DeleteRequest, IndexSpec, PineconeApi, PineconeVector, QueryRequest,
+    ServerlessSpec, UpdateRequest, UpsertRequest,
+};
+use crate::conversions::{
+    backup_status, collection_info, metric_name, point_from_vector, restore_status,
+    search_result_from_match, to_pinecone_metadata, to_sparse_values,
+};
+use golem_vector::alias::{alias_from_point, alias_record, ALIAS_VECTOR_DIMENSION};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::VectorConnection;
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::decode_filter;
+use golem_vector::filter::pinecone::to_pinecone_filter;
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, CollectionInfo, DistanceMetric, FilterExpression, Id,
+    Metadata, PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::named::expect_dense;
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::recommend::{exclude_examples, recommendation_vector};
+use golem_vector::search::{reject_keywords, search_each, search_with_offset};
+use golem_vector::task::{Task, TaskKind};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::LOGGING_STATE;
+use log::warn;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+mod client;
+mod conversions;
+
+/// Pinecone recommends upserting in batches of 100 vectors
+const UPSERT_BATCH_SIZE: usize = 100;
+
+/// Maximum number of ids in a single delete or fetch request
+const ID_BATCH_SIZE: usize = 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+/// Pinecone index names can only contain lowercase letters, digits and hyphens, so the alias
+/// index cannot use the shared `ALIAS_COLLECTION` name
+const ALIAS_INDEX: &str = "golem-vector-aliases";
+
+/// Number of times the readiness of a newly created alias index is checked, once per second
+const ALIAS_INDEX_READY_CHECKS: u32 = 60;
+
+thread_local! {
+    /// Hosts of the indexes used by the worker, by controller URL and index name. Every data
+    /// request is sent to the index's own host, which never changes for an existing index.
+    static INDEX_HOSTS: RefCell<HashMap<(String, String), String>> = RefCell::new(HashMap::new());
+}
+
+struct PineconeComponent;
+
+impl PineconeComponent {
+    const CONTROLLER_ENV_VAR: &'static str = "PINECONE_CONTROLLER_URL";
+    const API_KEY_ENV_VAR: &'static str = "PINECONE_API_KEY";
+    const DEFAULT_CONTROLLER: &'static str = "https://api.pinecone.io";
+
+    const CLOUD_OPTION: &'static str = "cloud";
+    const REGION_OPTION: &'static str = "region";
+    const DEFAULT_CLOUD: &'static str = "aws";
+    const DEFAULT_REGION: &'static str = "us-east-1";
+
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<PineconeApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let controller =
+            connection.endpoint_or_env(Self::CONTROLLER_ENV_VAR, Some(Self::DEFAULT_CONTROLLER))?;
+        let api_key = connection.api_key_or_env("Pinecone", Self::API_KEY_ENV_VAR)?;
+        Ok(PineconeApi::new(controller, api_key))
+    }
+
+    /// Indexes are created as serverless indexes, in the cloud and region given in the
+    /// provider options
+    fn index_spec(connection: &ConnectionBorrow<'_>) -> IndexSpec {
+        let connection = connection.get::<VectorConnection>();
+        IndexSpec {
+            serverless: ServerlessSpec {
+                cloud: connection
+                    .provider_option(Self::CLOUD_OPTION)
+                    .unwrap_or(Self::DEFAULT_CLOUD)
+                    .to_string(),
+                region: connection
+                    .provider_option(Self::REGION_OPTION)
+                    .unwrap_or(Self::DEFAULT_REGION)
+                    .to_string(),
+            },
+        }
+    }
+}
+
+/// Gets the host of an index, resolving the name as an alias if there is no index with it
+fn index_host(api: &PineconeApi, name: &str) -> Result<String, VectorError> {
+    match describe_host(api, name) {
+        Err(VectorError::NotFound(message)) => match alias_target(api, name)? {
+            Some(target) => describe_host(api, &target),
+            None => Err(VectorError::NotFound(message)),
+        },
+        result => result,
+    }
+}
+
+fn describe_host(api: &PineconeApi, name: &str) -> Result<String, VectorError> {
+    let key = (api.controller().to_string(), name.to_string());
+    if let Some(host) = INDEX_HOSTS.with_borrow(|hosts| hosts.get(&key).cloned()) {
+        return Ok(host);
+    }
+    let host = api.describe_index(name)?.host;
+    INDEX_HOSTS.with_borrow_mut(|hosts| hosts.insert(key, host.clone()));
+    Ok(host)
+}
+
+fn forget_host(api: &PineconeApi, name: &str) {
+    let key = (api.controller().to_string(), name.to_string());
+    INDEX_HOSTS.with_borrow_mut(|hosts| hosts.remove(&key));
+}
+
+/// The host of the alias index, or `None` if no alias has been created yet
+fn alias_index_host(api: &PineconeApi) -> Result<Option<String>, VectorError> {
+    match describe_host(api, ALIAS_INDEX) {
+        Ok(host) => Ok(Some(host)),
+        Err(VectorError::NotFound(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+fn alias_target(api: &PineconeApi, alias: &str) -> Result<Option<String>, VectorError> {
+    let Some(host) = alias_index_host(api)? else {
+        return Ok(None);
+    };
+    let response = api.fetch(&host, &[alias.to_string()], None)?;
+    Ok(response
+        .vectors
+        .into_values()
+        .map(|vector| point_from_vector(vector, false, true))
+        .find_map(|point| alias_from_point(&point))
+        .map(|alias| alias.collection))
+}
+
+/// Creates the alias index if it does not exist yet, and waits until it accepts writes
+fn ensure_alias_index(api: &PineconeApi, spec: IndexSpec) -> Result<String, VectorError> {
+    if let Some(host) = alias_index_host(api)? {
+        return Ok(host);
+    }
+
+    match api.create_index(&CreateIndexRequest {
+        name: ALIAS_INDEX.to_string(),
+        dimension: ALIAS_VECTOR_DIMENSION,
+        metric: metric_name(DistanceMetric::Cosine).to_string(),
+        spec,
+    }) {
+        Ok(()) | Err(VectorError::AlreadyExists(_)) => {}
+        Err(error) => return Err(error),
+    }
+    for _ in 0..ALIAS_INDEX_READY_CHECKS {
+        let description = api.describe_index(ALIAS_INDEX)?;
+        if description.status.ready {
+            return describe_host(api, ALIAS_INDEX);
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    Err(VectorError::ProviderError(format!(
+        "The alias index {ALIAS_INDEX} did not become ready in time"
+    )))
+}
+
+fn upsert_alias(
+    api: &PineconeApi,
+    host: &str,
+    alias: &str,
+    collection: &str,
+) -> Result<(), VectorError> {
+    let record = alias_record(alias, collection);
+    api.upsert(
+        host,
+        &UpsertRequest {
+            vectors: vec![to_pinecone_vector(&record)?],
+            namespace: None,
+        },
+    )
+}
+
+fn to_pinecone_vector(record: &VectorRecord) -> Result<PineconeVector, VectorError> {
+    Ok(PineconeVector {
+        id: record.id.clone(),
+        values: expect_dense(record, "Pinecone")?.to_vec(),
+        sparse_values: record.sparse.as_ref().map(to_sparse_values).transpose()?,
+        metadata: record
+            .metadata
+            .as_ref()
+            .map(to_pinecone_metadata)
+            .transpose()?,
+    })
+}
+
+fn pinecone_filter(filter: Option<FilterExpression>) -> Result<Option<Value>, VectorError> {
+    decode_filter(filter)?
+        .map(|filter| to_pinecone_filter(&filter))
+        .transpose()
+}
+
+fn ignore_write_options(options: Option<&WriteOptions>) {
+    if options.is_some_and(|options| options.ordering.is_some() || options.consistency.is_some()) {
+        warn!("Pinecone does not support write ordering and consistency options, ignoring them");
+    }
+}
+
+/// Queries with a sparse vector are hybrid queries, scored by the sum of the dense and sparse
+/// dot products, which Pinecone only supports on indexes with the dot product metric
+fn search(
+    api: &PineconeApi,
+    host: &str,
+    query: SearchQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<SearchResult>, VectorError> {
+    reject_keywords("Pinecone", &query)?;
+    if query.vector_name.is_some() {
+        return Err(VectorError::UnsupportedFeature(
+            "Pinecone does not support named vectors".to_string(),
+        ));
+    }
+
+    // Pinecone queries have no offset
+    search_with_offset(query, |query| {
+        let response = api.query(
+            host,
+            &QueryRequest {
+                vector: Some(query.vector),
+                sparse_vector: query.sparse.as_ref().map(to_sparse_values).transpose()?,
+                id: None,
+                top_k: query.limit,
+                filter: pinecone_filter(query.filter)?,
+                include_values: query.include_vectors,
+                include_metadata: query.include_metadata,
+                namespace: namespace.map(str::to_string),
+            },
+        )?;
+        Ok(response
+            .matches
+            .into_iter()
+            .map(|result| search_result_from_match(result, query.include_vectors))
+            .collect())
+    })
+}
+
+impl CollectionsGuest for PineconeComponent {
+    /// Index creation is asynchronous, the index accepts writes once Pinecone reports it ready
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        if config.quantization.is_some() {
+            return Err(VectorError::UnsupportedFeature(
+                "Pinecone does not support quantization settings".to_string(),
+            ));
+        }
+
+        api.create_index(&CreateIndexRequest {
+            name,
+            dimension: config.dimension,
+            metric: metric_name(config.metric).to_string(),
+            spec: Self::index_spec(&connection),
+        })
+    }
+
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        forget_host(&api, &name);
+        api.delete_index(&name)
+    }
+
+    /// The alias index is not listed
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        let indexes = Self::api(&connection)?.list_indexes()?;
+        Ok(indexes
+            .into_iter()
+            .map(|index| index.name)
+            .filter(|name| name != ALIAS_INDEX)
+            .collect())
+    }
+
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<CollectionInfo, VectorError> {
+        let api = Self::api(&connection)?;
+        let index = match api.describe_index(&name) {
+            Err(VectorError::NotFound(message)) => match alias_target(&api, &name)? {
+                Some(target) => api.describe_index(&target)?,
+                None => return Err(VectorError::NotFound(message)),
+            },
+            result => result?,
+        };
+        let stats = api.describe_index_stats(&index.host)?;
+        Ok(collection_info(index, stats.total_vector_count))
+    }
+
+    /// Snapshots are Pinecone backups, identified by their backup id
+    fn create_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<TaskId, VectorError> {
+        let backup = Self::api(&connection)?.create_backup(&collection)?;
+        Ok(Task::new(TaskKind::CreateSnapshot, collection, backup.backup_id).encode())
+    }
+
+    /// Restores a backup into a new index called `collection`, which must not exist yet
+    fn restore_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        let api = Self::api(&connection)?;
+        let response = api.create_index_from_backup(&snapshot, &collection)?;
+        forget_host(&api, &collection);
+        Ok(Task::new(
+            TaskKind::RestoreSnapshot,
+            collection,
+            response.restore_job_id,
+        )
+        .encode())
+    }
+
+    fn list_snapshots(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        let backups = Self::api(&connection)?.list_backups(&collection)?;
+        Ok(backups
+            .into_iter()
+            .map(|backup| SnapshotInfo {
+                name: backup.backup_id,
+                created_at: backup.created_at,
+                size_bytes: backup.size_bytes,
+            })
+            .collect())
+    }
+
+    fn get_task_status(
+        connection: ConnectionBorrow<'_>,
+        task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        let api = Self::api(&connection)?;
+        let task = Task::decode(&task)?;
+        match task.kind {
+            TaskKind::CreateSnapshot => {
+                let backup = api.describe_backup(&task.provider_id)?;
+                Ok(backup_status(backup.backup_id, &backup.status))
+            }
+            TaskKind::RestoreSnapshot => {
+                let job = api.describe_restore_job(&task.provider_id)?;
+                Ok(restore_status(&task.provider_id, &job.status))
+            }
+        }
+    }
+
+    /// Serverless indexes always index every metadata field
+    fn create_payload_index(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+        _field: String,
+        _index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Pinecone indexes all metadata fields, payload indexes cannot be created".to_string(),
+        ))
+    }
+
+    fn list_payload_indexes(
+        _connection: ConnectionBorrow<'_>,
+        _collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        Err(VectorError::UnsupportedFeature(
+            "Pinecone indexes all metadata fields, payload indexes cannot be listed".to_string(),
+        ))
+    }
+
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let host = ensure_alias_index(&api, Self::index_spec(&connection))?;
+        if alias_target(&api, &alias)?.is_some() {
+            return Err(VectorError::AlreadyExists(format!(
+                "Alias {alias} already exists"
+            )));
+        }
+        upsert_alias(&api, &host, &alias, &collection)
+    }
+
+    /// Switching an alias replaces its single record, so it is atomic
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let host = ensure_alias_index(&api, Self::index_spec(&connection))?;
+        upsert_alias(&api, &host, &alias, &collection)
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        match alias_index_host(&api)? {
+            Some(host) if alias_target(&api, &alias)?.is_some() => api.delete(
+                &host,
+                &DeleteRequest {
+                    ids: vec![alias],
+                    namespace: None,
+                },
+            ),
+            _ => Err(VectorError::NotFound(format!(
+                "Alias {alias} does not exist"
+            ))),
+        }
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let api = Self::api(&connection)?;
+        let Some(host) = alias_index_host(&api)? else {
+            return Ok(vec![]);
+        };
+
+        let mut aliases = Vec::new();
+        let mut token = None;
+        loop {
+            let page = api.list(&host, None, DEFAULT_PAGE_SIZE, token.as_deref())?;
+            let ids: Vec<String> = page.vectors.into_iter().map(|item| item.id).collect();
+            if !ids.is_empty() {
+                let response = api.fetch(&host, &ids, None)?;
+                aliases.extend(
+                    response
+                        .vectors
+                        .into_values()
+                        .map(|vector| point_from_vector(vector, false, true))
+                        .filter_map(|point| alias_from_point(&point)),
+                );
+            }
+            token = page.pagination.and_then(|pagination| pagination.next);
+            if token.is_none() {
+                break;
+            }
+        }
+        aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+        Ok(aliases)
+    }
+}
+
+/// Writes the vectors of a batch with a single upsert request to the index host
+struct PineconeUpsert {
+    api: PineconeApi,
+    host: String,
+    namespace: Option<String>,
+}
+
+impl BatchWriter for PineconeUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        let vectors = batch
+            .iter()
+            .map(to_pinecone_vector)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.api.upsert(
+            &self.host,
+            &UpsertRequest {
+                vectors,
+                namespace: self.namespace.clone(),
+            },
+        )
+    }
+}
+
+impl VectorsGuest for PineconeComponent {
+    type UpsertStream = VectorUpsertStream<PineconeUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    /// Pinecone merges the given fields into the metadata of one vector per request
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+
+        let set_metadata = to_pinecone_metadata(&metadata)?;
+        for id in ids {
+            api.update(
+                &host,
+                &UpdateRequest {
+                    id,
+                    values: None,
+                    set_metadata: Some(set_metadata.clone()),
+                    namespace: namespace.clone(),
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let VectorData::Dense(values) = vector else {
+            return Err(VectorError::UnsupportedFeature(
+                "Pinecone does not support named vectors".to_string(),
+            ));
+        };
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+
+        api.update(
+            &host,
+            &UpdateRequest {
+                id,
+                values: Some(values),
+                set_metadata: None,
+                namespace,
+            },
+        )
+    }
+
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+
+        let mut points = Vec::with_capacity(ids.len());
+        for ids in ids.chunks(ID_BATCH_SIZE) {
+            let mut response = api.fetch(&host, ids, namespace.as_deref())?;
+            points.extend(
+                ids.iter()
+                    .filter_map(|id| response.vectors.remove(id))
+                    .map(|vector| point_from_vector(vector, include_vectors, include_metadata)),
+            );
+        }
+        Ok(points)
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+
+        for ids in ids.chunks(ID_BATCH_SIZE) {
+            api.delete(
+                &host,
+                &DeleteRequest {
+                    ids: ids.to_vec(),
+                    namespace: namespace.clone(),
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl SearchGuest for PineconeComponent {
+    type PointStream = VectorPointStream<PineconePointPager>;
+
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        search(&api, &host, query, namespace.as_deref())
+    }
+
+    /// Pinecone has no batch query endpoint, the queries are sent one after the other
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        search_each(queries, |query| {
+            search(&api, &host, query, namespace.as_deref())
+        })
+    }
+
+    /// Pinecone queries by id include the queried vector itself, so it is removed from the
+    /// results
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        let response = api.query(
+            &host,
+            &QueryRequest {
+                vector: None,
+                sparse_vector: None,
+                id: Some(id.clone()),
+                top_k: limit + 1,
+                filter: pinecone_filter(filter)?,
+                include_values: false,
+                include_metadata: true,
+                namespace,
+            },
+        )?;
+        Ok(response
+            .matches
+            .into_iter()
+            .filter(|result| result.id != id)
+            .take(limit as usize)
+            .map(|result| search_result_from_match(result, false))
+            .collect())
+    }
+
+    /// Emulated by querying with the combination of the example vectors
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+
+        let examples = |ids: &[Id]| -> Result<Vec<Vec<f32>>, VectorError> {
+            if ids.is_empty() {
+                return Ok(vec![]);
+            }
+            let mut response = api.fetch(&host, ids, namespace.as_deref())?;
+            ids.iter()
+                .map(|id| {
+                    response
+                        .vectors
+                        .remove(id)
+                        .map(|vector| vector.values)
+                        .ok_or_else(|| {
+                            VectorError::NotFound(format!("Example point {id} does not exist"))
+                        })
+                })
+                .collect()
+        };
+        let vector = recommendation_vector(&examples(&positive)?, &examples(&negative)?)?;
+
+        let results = search(
+            &api,
+            &host,
+            SearchQuery {
+                vector,
+                vector_name: None,
+                limit: limit + (positive.len() + negative.len()) as u32,
+                offset: None,
+                filter,
+                include_vectors: false,
+                include_metadata: true,
+                keywords: None,
+                sparse: None,
+            },
+            namespace.as_deref(),
+        )?;
+        Ok(exclude_examples(results, &positive, &negative, limit))
+    }
+
+    /// Counts are taken from the index statistics, which cannot be filtered on serverless indexes
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        if filter.is_some() {
+            return Err(VectorError::UnsupportedFeature(
+                "Pinecone cannot count vectors matching a filter".to_string(),
+            ));
+        }
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        let stats = api.describe_index_stats(&host)?;
+        match namespace {
+            Some(namespace) => Ok(stats
+                .namespaces
+                .get(&namespace)
+                .map(|stats| stats.vector_count)
+                .unwrap_or(0)),
+            None => Ok(stats.total_vector_count),
+        }
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for PineconeComponent {
+    type BatchWriter = PineconeUpsert;
+    type PointPager = PineconePointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<PineconeUpsert, VectorError> {
+        let api = Self::api(&connection)?;
+        let host = index_host(&api, &collection)?;
+        ignore_write_options(options.as_ref());
+        Ok(PineconeUpsert {
+            api,
+            host,
+            namespace,
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PineconePointPager {
+        let scroll = Self::api(&connection).and_then(|api| {
+            if options.filter.is_some() {
+                return Err(VectorError::UnsupportedFeature(
+                    "Pinecone cannot list vectors matching a filter".to_string(),
+                ));
+            }
+            let host = index_host(&api, &collection)?;
+            Ok(PineconeScroll {
+                api,
+                host,
+                namespace,
+                options,
+            })
+        });
+        PineconePointPager { scroll }
+    }
+}
+
+struct PineconeScroll {
+    api: PineconeApi,
+    host: String,
+    namespace: Option<String>,
+    options: ScrollOptions,
+}
+
+/// Pages through the ids of an index with Pinecone's list endpoint, fetching the vectors and
+/// metadata of each page when they are requested. The cursor is Pinecone's pagination token.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct PineconePointPager {
+    scroll: Result<PineconeScroll, VectorError>,
+}
+
+impl PointPager for PineconePointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let namespace = scroll.namespace.as_deref();
+        let include_vectors = scroll.options.include_vectors;
+        let include_metadata = scroll.options.include_metadata;
+
+        let page = scroll.api.list(
+            &scroll.host,
+            namespace,
+            scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+            cursor,
+        )?;
+        let ids: Vec<String> = page.vectors.into_iter().map(|item| item.id).collect();
+
+        let points = if ids.is_empty() || !(include_vectors || include_metadata) {
+            ids.into_iter()
+                .map(|id| Point {
+                    id,
+                    vector: None,
+                    sparse: None,
+                    metadata: None,
+                })
+                .collect()
+        } else {
+            let mut response = scroll.api.fetch(&scroll.host, &ids, namespace)?;
+            ids.iter()
+                .filter_map(|id| response.vectors.remove(id))
+                .map(|vector| point_from_vector(vector, include_vectors, include_metadata))
+                .collect()
+        };
+
+        Ok(PointPage {
+            points,
+            next_cursor: page.pagination.and_then(|pagination| pagination.next),
+        })
+    }
+}
+
+type DurablePineconeComponent = DurableVector<PineconeComponent>;
+
+golem_vector::export_vector!(DurablePineconeComponent with_types_in golem_vector);
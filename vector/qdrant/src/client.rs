@@ -0,0 +1,655 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The Qdrant REST API client
+///
+/// Based on https://api.qdrant.tech/api-reference
+pub struct QdrantApi {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    client: Client,
+}
+
+impl QdrantApi {
+    pub fn new(endpoint: String, headers: Vec<(String, String)>) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            endpoint,
+            headers,
+            client,
+        }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn create_collection(
+        &self,
+        name: &str,
+        request: &CreateCollectionRequest,
+    ) -> Result<(), VectorError> {
+        self.call::<bool>(
+            Method::PUT,
+            &format!("/collections/{}", encode(name)),
+            &[],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn delete_collection(&self, name: &str) -> Result<(), VectorError> {
+        self.call::<bool>(
+            Method::DELETE,
+            &format!("/collections/{}", encode(name)),
+            &[],
+            None::<&()>,
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_collections(&self) -> Result<Vec<String>, VectorError> {
+        self.call::<CollectionsResponse>(Method::GET, "/collections", &[], None::<&()>)
+            .map(|response| {
+                response
+                    .collections
+                    .into_iter()
+                    .map(|collection| collection.name)
+                    .collect()
+            })
+    }
+
+    pub fn get_collection(&self, name: &str) -> Result<CollectionInfo, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/collections/{}", encode(name)),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn create_snapshot(&self, collection: &str) -> Result<SnapshotDescription, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/snapshots", encode(collection)),
+            &[("wait", "true".to_string())],
+            None::<&()>,
+        )
+    }
+
+    pub fn recover_snapshot(&self, collection: &str, location: &str) -> Result<(), VectorError> {
+        self.call::<bool>(
+            Method::PUT,
+            &format!("/collections/{}/snapshots/recover", encode(collection)),
+            &[("wait", "true".to_string())],
+            Some(&RecoverSnapshotRequest {
+                location: location.to_string(),
+            }),
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_snapshots(
+        &self,
+        collection: &str,
+    ) -> Result<Vec<SnapshotDescription>, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/collections/{}/snapshots", encode(collection)),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn create_payload_index(
+        &self,
+        collection: &str,
+        request: &CreateIndexRequest,
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::PUT,
+            &format!("/collections/{}/index", encode(collection)),
+            &[("wait", "true".to_string())],
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn update_aliases(&self, actions: Vec<AliasAction>) -> Result<(), VectorError> {
+        self.call::<bool>(
+            Method::POST,
+            "/collections/aliases",
+            &[],
+            Some(&UpdateAliasesRequest { actions }),
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_aliases(&self) -> Result<AliasesResponse, VectorError> {
+        self.call(Method::GET, "/aliases", &[], None::<&()>)
+    }
+
+    pub fn upsert_points(
+        &self,
+        collection: &str,
+        request: &UpsertPointsRequest,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::PUT,
+            &format!("/collections/{}/points", encode(collection)),
+            params,
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn set_payload(
+        &self,
+        collection: &str,
+        request: &SetPayloadRequest,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/collections/{}/points/payload", encode(collection)),
+            params,
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn update_vectors(
+        &self,
+        collection: &str,
+        request: &UpdateVectorsRequest,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::PUT,
+            &format!("/collections/{}/points/vectors", encode(collection)),
+            params,
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn delete_points(
+        &self,
+        collection: &str,
+        request: &PointsSelector,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/collections/{}/points/delete", encode(collection)),
+            params,
+            Some(request),
+        )
+        .map(|_| ())
+    }
+
+    pub fn get_points(
+        &self,
+        collection: &str,
+        request: &GetPointsRequest,
+    ) -> Result<Vec<ScoredPoint>, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/points", encode(collection)),
+            &[],
+            Some(request),
+        )
+    }
+
+    pub fn search(
+        &self,
+        collection: &str,
+        request: &SearchRequest,
+    ) -> Result<Vec<ScoredPoint>, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/points/search", encode(collection)),
+            &[],
+            Some(request),
+        )
+    }
+
+    pub fn search_batch(
+        &self,
+        collection: &str,
+        request: &SearchBatchRequest,
+    ) -> Result<Vec<Vec<ScoredPoint>>, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/points/search/batch", encode(collection)),
+            &[],
+            Some(request),
+        )
+    }
+
+    /// Runs a query of the universal query API, used for hybrid searches
+    pub fn query(
+        &self,
+        collection: &str,
+        request: &QueryRequest,
+    ) -> Result<Vec<ScoredPoint>, VectorError> {
+        self.call::<QueryResult>(
+            Method::POST,
+            &format!("/collections/{}/points/query", encode(collection)),
+            &[],
+            Some(request),
+        )
+        .map(|result| result.points)
+    }
+
+    pub fn recommend(
+        &self,
+        collection: &str,
+        request: &RecommendRequest,
+    ) -> Result<Vec<ScoredPoint>, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/points/recommend", encode(collection)),
+            &[],
+            Some(request),
+        )
+    }
+
+    pub fn count(&self, collection: &str, request: &CountRequest) -> Result<u64, VectorError> {
+        self.call::<CountResult>(
+            Method::POST,
+            &format!("/collections/{}/points/count", encode(collection)),
+            &[],
+            Some(request),
+        )
+        .map(|result| result.count)
+    }
+
+    pub fn scroll(
+        &self,
+        collection: &str,
+        request: &ScrollRequest,
+    ) -> Result<ScrollResult, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/collections/{}/points/scroll", encode(collection)),
+            &[],
+            Some(request),
+        )
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: &[(&str, String)],
+        body: Option<&impl Serialize>,
+    ) -> Result<T, VectorError> {
+        trace!("Sending {method} {path} request to Qdrant");
+
+        let response = send_with_retry(|| {
+            let mut builder = self
+                .client
+                .request(method.clone(), format!("{}{path}", self.endpoint));
+            if !params.is_empty() {
+                builder = builder.query(params);
+            }
+            for (name, value) in &self.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = body {
+                builder = builder.json(body);
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+/// Percent-encodes a collection name used as a path segment
+fn encode(segment: &str) -> String {
+    let mut encoded = String::new();
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if status.is_success() {
+        serde_json::from_str::<QdrantResponse<T>>(&raw_body)
+            .map(|response| response.result)
+            .map_err(|err| {
+                VectorError::ProviderError(format!(
+                    "Failed to decode response body: {err}: {raw_body}"
+                ))
+            })
+    } else {
+        trace!("Received {status} response from Qdrant: {raw_body:?}");
+        let message = serde_json::from_str::<ErrorResponse>(&raw_body)
+            .ok()
+            .and_then(|response| response.status.error)
+            .unwrap_or(raw_body);
+        Err(from_http_status(status, message))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct QdrantResponse<T> {
+    result: T,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorResponse {
+    status: ErrorStatus,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorStatus {
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorParams {
+    pub size: u32,
+    pub distance: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum VectorsConfig {
+    Single(VectorParams),
+    Named(HashMap<String, VectorParams>),
+}
+
+/// Sparse vectors take no parameters, their index defaults are kept
+#[derive(Debug, Clone, Serialize)]
+pub struct SparseVectorParams {}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateCollectionRequest {
+    pub vectors: VectorsConfig,
+    pub sparse_vectors: HashMap<String, SparseVectorParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantization_config: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CollectionsResponse {
+    collections: Vec<CollectionDescription>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CollectionDescription {
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionInfo {
+    #[serde(default)]
+    pub points_count: Option<u64>,
+    pub config: CollectionConfigInfo,
+    #[serde(default)]
+    pub payload_schema: HashMap<String, PayloadSchemaInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionConfigInfo {
+    pub params: CollectionParams,
+}
+
+/// The vector parameters are a single `VectorParams` object, or an object of them by name
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionParams {
+    pub vectors: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PayloadSchemaInfo {
+    pub data_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotDescription {
+    pub name: String,
+    #[serde(default)]
+    pub creation_time: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RecoverSnapshotRequest {
+    location: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateIndexRequest {
+    pub field_name: String,
+    pub field_schema: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateAliasesRequest {
+    actions: Vec<AliasAction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AliasAction {
+    CreateAlias {
+        collection_name: String,
+        alias_name: String,
+    },
+    DeleteAlias {
+        alias_name: String,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AliasesResponse {
+    pub aliases: Vec<AliasDescription>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AliasDescription {
+    pub alias_name: String,
+    pub collection_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PointStruct {
+    pub id: Value,
+    pub vector: Value,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpsertPointsRequest {
+    pub points: Vec<PointStruct>,
+}
+
+/// Selects points by their ids or by a filter
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum PointsSelector {
+    Points { points: Vec<Value> },
+    Filter { filter: Value },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetPayloadRequest {
+    pub payload: Value,
+    #[serde(flatten)]
+    pub selector: PointsSelector,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PointVectors {
+    pub id: Value,
+    pub vector: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateVectorsRequest {
+    pub points: Vec<PointVectors>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GetPointsRequest {
+    pub ids: Vec<Value>,
+    pub with_payload: bool,
+    pub with_vector: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchRequest {
+    pub vector: Value,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub with_payload: bool,
+    pub with_vector: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchBatchRequest {
+    pub searches: Vec<SearchRequest>,
+}
+
+/// A search of one vector, run ahead of the main query to provide its candidates
+#[derive(Debug, Clone, Serialize)]
+pub struct Prefetch {
+    pub query: Value,
+    /// The named vector searched, the unnamed vector if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub using: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryRequest {
+    pub prefetch: Vec<Prefetch>,
+    pub query: Value,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
+    pub with_payload: bool,
+    pub with_vector: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct QueryResult {
+    points: Vec<ScoredPoint>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendRequest {
+    pub positive: Vec<Value>,
+    pub negative: Vec<Value>,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub with_payload: bool,
+    pub with_vector: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CountRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub exact: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CountResult {
+    count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrollRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    pub limit: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Value>,
+    pub with_payload: bool,
+    pub with_vector: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScrollResult {
+    pub points: Vec<ScoredPoint>,
+    #[serde(default)]
+    pub next_page_offset: Option<Value>,
+}
+
+/// A point returned by Qdrant. Points returned by searches also have a score.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoredPoint {
+    pub id: Value,
+    #[serde(default)]
+    pub score: Option<f32>,
+    #[serde(default)]
+    pub payload: Option<Value>,
+    #[serde(default)]
+    pub vector: Option<Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{encode, AliasAction, PointsSelector, SetPayloadRequest};
+    use serde_json::json;
+
+    #[test]
+    fn encodes_collection_names() {
+        assert_eq!(encode("my-collection_1"), "my-collection_1");
+        assert_eq!(encode("a b/c"), "a%20b%2Fc");
+    }
+
+    #[test]
+    fn serializes_alias_actions_and_selectors() {
+        assert_eq!(
+            serde_json::to_value(AliasAction::DeleteAlias {
+                alias_name: "products".to_string()
+            })
+            .unwrap(),
+            json!({ "delete_alias": { "alias_name": "products" } })
+        );
+        assert_eq!(
+            serde_json::to_value(SetPayloadRequest {
+                payload: json!({ "a": 1 }),
+                selector: PointsSelector::Points {
+                    points: vec![json!(1)]
+                },
+            })
+            .unwrap(),
+            json!({ "payload": { "a": 1 }, "points": [1] })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::client::{
+    CollectionInfo as QdrantCollectionInfo, Prefetch, QueryRequest, ScoredPoint, SearchRequest,
+};
+use golem_vector::golem::vector::types::{
+    CollectionInfo, DistanceMetric, Metadata, Point, SearchQuery, SearchResult, SparseVector,
+    VectorData, VectorError,
+};
+use golem_vector::metadata::{json_to_metadata, metadata_to_json};
+use golem_vector::namespace::{add_namespace, strip_namespace};
+use golem_vector::sparse::validate_sparse;
+use serde_json::{json, Map, Value};
+
+/// The sparse vector every collection is created with, holding the sparse vectors of points
+pub const SPARSE_VECTOR: &str = "sparse";
+
+pub fn distance_name(metric: DistanceMetric) -> &'static str {
+    match metric {
+        DistanceMetric::Cosine => "Cosine",
+        DistanceMetric::Euclidean => "Euclid",
+        DistanceMetric::DotProduct => "Dot",
+    }
+}
+
+fn distance_from_name(name: &str) -> Option<DistanceMetric> {
+    match name {
+        "Cosine" => Some(DistanceMetric::Cosine),
+        "Euclid" => Some(DistanceMetric::Euclidean),
+        "Dot" => Some(DistanceMetric::DotProduct),
+        _ => None,
+    }
+}
+
+/// Describes a collection. Collections with named vectors only have a dimension and metric if
+/// all of their dense vectors share them, as they do when created with `vector_names`.
+pub fn collection_info(name: String, info: QdrantCollectionInfo) -> CollectionInfo {
+    let vectors = &info.config.params.vectors;
+    let params = if vectors.get("size").is_some() {
+        vec![vectors]
+    } else {
+        vectors
+            .as_object()
+            .map(|vectors| vectors.values().collect())
+            .unwrap_or_default()
+    };
+    let shared = |field: &str| {
+        let mut values = params.iter().map(|params| params.get(field));
+        let first = values.next().flatten()?;
+        values.all(|value| value == Some(first)).then_some(first)
+    };
+    CollectionInfo {
+        name,
+        dimension: shared("size")
+            .and_then(Value::as_u64)
+            .map(|size| size as u32),
+        metric: shared("distance")
+            .and_then(Value::as_str)
+            .and_then(distance_from_name),
+        point_count: info.points_count,
+    }
+}
+
+/// Converts an id to a Qdrant point id. Qdrant only accepts unsigned integers and UUIDs.
+pub fn point_id(id: &str) -> Result<Value, VectorError> {
+    if let Ok(number) = id.parse::<u64>() {
+        Ok(Value::from(number))
+    } else if is_uuid(id) {
+        Ok(Value::String(id.to_string()))
+    } else {
+        Err(VectorError::InvalidParams(format!(
+            "Invalid point id {id}, Qdrant point ids must be unsigned integers or UUIDs"
+        )))
+    }
+}
+
+pub fn point_ids(ids: &[String]) -> Result<Vec<Value>, VectorError> {
+    ids.iter().map(|id| point_id(id)).collect()
+}
+
+pub fn id_from_json(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn is_uuid(id: &str) -> bool {
+    let groups: Vec<&str> = id.split('-').collect();
+    groups.len() == 5
+        && groups.iter().zip([8, 4, 4, 4, 12]).all(|(group, length)| {
+            group.len() == length && group.chars().all(|c| c.is_ascii_hexdigit())
+        })
+}
+
+pub fn vector_to_json(vector: &VectorData) -> Value {
+    match vector {
+        VectorData::Dense(values) => Value::from(values.clone()),
+        VectorData::Named(vectors) => Value::Object(
+            vectors
+                .iter()
+                .map(|(name, values)| (name.clone(), Value::from(values.clone())))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+/// The vectors of a written point. A sparse vector turns an unnamed dense vector into the
+/// vector named `""`, Qdrant's name of the unnamed vector.
+pub fn point_vector(
+    vector: &VectorData,
+    sparse: Option<&SparseVector>,
+) -> Result<Value, VectorError> {
+    let Some(sparse) = sparse else {
+        return Ok(vector_to_json(vector));
+    };
+    validate_sparse(sparse)?;
+    let mut vectors = match vector_to_json(vector) {
+        Value::Object(vectors) => vectors,
+        dense => Map::from_iter([(String::new(), dense)]),
+    };
+    vectors.insert(SPARSE_VECTOR.to_string(), sparse_to_json(sparse));
+    Ok(Value::Object(vectors))
+}
+
+fn sparse_to_json(sparse: &SparseVector) -> Value {
+    json!({ "indices": sparse.indices, "values": sparse.values })
+}
+
+/// Parses the vector of a returned point. Sparse vectors are skipped, and an unnamed vector
+/// returned next to sparse ones is a dense vector.
+pub fn vector_from_json(vector: &Value) -> Option<VectorData> {
+    match vector {
+        Value::Array(_) => dense_from_json(vector).map(VectorData::Dense),
+        Value::Object(vectors) => {
+            let mut named = vectors
+                .iter()
+                .filter_map(|(name, values)| {
+                    dense_from_json(values).map(|values| (name.clone(), values))
+                })
+                .collect::<Vec<_>>();
+            if named.len() == 1 && named[0].0.is_empty() {
+                named.pop().map(|(_, values)| VectorData::Dense(values))
+            } else {
+                Some(VectorData::Named(named))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Parses the sparse vector of a returned point
+pub fn sparse_from_json(vector: &Value) -> Option<SparseVector> {
+    let sparse = vector.get(SPARSE_VECTOR)?;
+    let indices = sparse
+        .get("indices")?
+        .as_array()?
+        .iter()
+        .map(|index| index.as_u64().map(|index| index as u32))
+        .collect::<Option<Vec<_>>>()?;
+    let values = dense_from_json(sparse.get("values")?)?;
+    Some(SparseVector { indices, values })
+}
+
+fn dense_from_json(vector: &Value) -> Option<Vec<f32>> {
+    vector.as_array().and_then(|values| {
+        values
+            .iter()
+            .map(|value| value.as_f64().map(|value| value as f32))
+            .collect()
+    })
+}
+
+/// The payload of a written point, with the namespace field added
+pub fn payload(metadata: Option<Metadata>, namespace: Option<&str>) -> Value {
+    match add_namespace(metadata, namespace) {
+        Some(metadata) => metadata_to_json(&metadata),
+        None => Value::Object(Map::new()),
+    }
+}
+
+pub fn search_request(query: &SearchQuery, filter: Option<Value>) -> SearchRequest {
+    let vector = Value::from(query.vector.clone());
+    let vector = match &query.vector_name {
+        Some(name) => {
+            let mut named = Map::new();
+            named.insert("name".to_string(), Value::String(name.clone()));
+            named.insert("vector".to_string(), vector);
+            Value::Object(named)
+        }
+        None => vector,
+    };
+    SearchRequest {
+        vector,
+        limit: query.limit,
+        offset: query.offset,
+        filter,
+        with_payload: query.include_metadata,
+        with_vector: query.include_vectors,
+    }
+}
+
+/// A hybrid search, searching the dense and the sparse vector separately and fusing the two
+/// rankings with reciprocal rank fusion. Both searches fetch enough candidates for the offset.
+pub fn hybrid_request(
+    query: &SearchQuery,
+    sparse: &SparseVector,
+    filter: Option<Value>,
+) -> Result<QueryRequest, VectorError> {
+    validate_sparse(sparse)?;
+    let candidates = query.limit + query.offset.unwrap_or_default();
+    Ok(QueryRequest {
+        prefetch: vec![
+            Prefetch {
+                query: Value::from(query.vector.clone()),
+                using: query.vector_name.clone(),
+                filter: filter.clone(),
+                limit: candidates,
+            },
+            Prefetch {
+                query: sparse_to_json(sparse),
+                using: Some(SPARSE_VECTOR.to_string()),
+                filter,
+                limit: candidates,
+            },
+        ],
+        query: json!({ "fusion": "rrf" }),
+        limit: query.limit,
+        offset: query.offset,
+        with_payload: query.include_metadata,
+        with_vector: query.include_vectors,
+    })
+}
+
+pub fn search_result_from_point(point: ScoredPoint) -> SearchResult {
+    SearchResult {
+        id: id_from_json(&point.id),
+        score: point.score.unwrap_or_default(),
+        vector: point.vector.as_ref().and_then(vector_from_json),
+        metadata: strip_namespace(point.payload.as_ref().map(json_to_metadata)),
+    }
+}
+
+pub fn point_from_qdrant(point: ScoredPoint) -> Point {
+    Point {
+        id: id_from_json(&point.id),
+        vector: point.vector.as_ref().and_then(vector_from_json),
+        sparse: point.vector.as_ref().and_then(sparse_from_json),
+        metadata: strip_namespace(point.payload.as_ref().map(json_to_metadata)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{CollectionInfo, ScoredPoint};
+    use crate::conversions::{
+        collection_info, point_id, point_vector, search_result_from_point, sparse_from_json,
+        vector_from_json,
+    };
+    use golem_vector::golem::vector::types::{
+        DistanceMetric, MetadataValue, SparseVector, VectorData, VectorError,
+    };
+    use golem_vector::namespace::NAMESPACE_FIELD;
+    use serde_json::json;
+
+    #[test]
+    fn accepts_integer_and_uuid_ids() {
+        assert_eq!(point_id("42").unwrap(), json!(42));
+        assert_eq!(
+            point_id("5c56c793-69f3-4fbf-87e6-c4bf54c28c26").unwrap(),
+            json!("5c56c793-69f3-4fbf-87e6-c4bf54c28c26")
+        );
+        assert!(matches!(
+            point_id("doc-1"),
+            Err(VectorError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn parses_dense_and_named_vectors() {
+        assert_eq!(
+            vector_from_json(&json!([1.0, 2.0])),
+            Some(VectorData::Dense(vec![1.0, 2.0]))
+        );
+        assert_eq!(
+            vector_from_json(&json!({
+                "title": [1.0],
+                "keywords": { "indices": [1], "values": [0.5] }
+            })),
+            Some(VectorData::Named(vec![("title".to_string(), vec![1.0])]))
+        );
+    }
+
+    #[test]
+    fn writes_and_reads_sparse_vectors() {
+        let sparse = SparseVector {
+            indices: vec![3, 7],
+            values: vec![0.5, 0.25],
+        };
+        let vector = point_vector(&VectorData::Dense(vec![1.0, 2.0]), Some(&sparse)).unwrap();
+        assert_eq!(
+            vector,
+            json!({ "": [1.0, 2.0], "sparse": { "indices": [3, 7], "values": [0.5, 0.25] } })
+        );
+        assert_eq!(
+            vector_from_json(&vector),
+            Some(VectorData::Dense(vec![1.0, 2.0]))
+        );
+        assert_eq!(sparse_from_json(&vector), Some(sparse));
+    }
+
+    #[test]
+    fn strips_the_namespace_from_results() {
+        let result = search_result_from_point(ScoredPoint {
+            id: json!(7),
+            score: Some(0.5),
+            payload: Some(json!({ "a": "b", (NAMESPACE_FIELD): "tenant-1" })),
+            vector: None,
+        });
+        assert_eq!(result.id, "7");
+        assert_eq!(
+            result.metadata,
+            Some(vec![(
+                "a".to_string(),
+                MetadataValue::StringVal("b".to_string())
+            )])
+        );
+    }
+
+    #[test]
+    fn describes_named_vector_collections() {
+        let info: CollectionInfo = serde_json::from_value(json!({
+            "points_count": 3,
+            "config": { "params": { "vectors": {
+                "title": { "size": 4, "distance": "Dot" },
+                "body": { "size": 4, "distance": "Dot" }
+            } } }
+        }))
+        .unwrap();
+        let info = collection_info("docs".to_string(), info);
+        assert_eq!(info.dimension, Some(4));
+        assert_eq!(info.metric, Some(DistanceMetric::DotProduct));
+        assert_eq!(info.point_count, Some(3));
+    }
+}
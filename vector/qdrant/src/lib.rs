@@ -0,0 +1,721 @@
+use crate::client::ScoredPoint;
+use crate::client::{
+    AliasAction, CountRequest, CreateCollectionRequest, CreateIndexRequest, GetPointsRequest,
+    PointStruct, PointVectors, PointsSelector, QdrantApi, RecommendRequest, ScrollRequest,
+    SearchBatchRequest, SetPayloadRequest, SparseVectorParams, UpdateVectorsRequest,
+    UpsertPointsRequest, VectorParams, VectorsConfig,
+};
+use crate::conversions::{
+    collection_info, distance_name, hybrid_request, id_from_json, payload, point_from_qdrant,
+    point_id, point_ids, point_vector, search_request, search_result_from_point, vector_to_json,
+    SPARSE_VECTOR,
+};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::{ApiKeyStyle, VectorConnection};
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::decode_filter;
+use golem_vector::filter::qdrant::to_qdrant_filter;
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, CollectionInfo, FilterExpression, Id, Metadata,
+    PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::metadata::metadata_to_json;
+use golem_vector::namespace::{namespace_filter, NAMESPACE_FIELD};
+use golem_vector::payload_index::{from_qdrant_field_schema, qdrant_field_schema};
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::quantization::to_qdrant_quantization;
+use golem_vector::search::{reject_keywords, search_each};
+use golem_vector::task::{Task, TaskKind};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::write_options::qdrant_write_params;
+use golem_vector::LOGGING_STATE;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+mod client;
+mod conversions;
+
+/// Qdrant accepts large upserts, this keeps a batch of 1536 dimensional vectors well below
+/// the default 32MB request size limit
+const UPSERT_BATCH_SIZE: usize = 1000;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+struct QdrantComponent;
+
+impl QdrantComponent {
+    const ENDPOINT_ENV_VAR: &'static str = "QDRANT_URL";
+    const API_KEY_ENV_VAR: &'static str = "QDRANT_API_KEY";
+    const DEFAULT_ENDPOINT: &'static str = "http://localhost:6333";
+
+    /// Provider option listing the names of the vectors of a new collection, separated by commas.
+    /// Every named vector gets the dimension and metric of the collection config.
+    const VECTOR_NAMES_OPTION: &'static str = "vector_names";
+
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<QdrantApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let endpoint =
+            connection.endpoint_or_env(Self::ENDPOINT_ENV_VAR, Some(Self::DEFAULT_ENDPOINT))?;
+        let headers = connection.auth_headers(
+            "Qdrant",
+            ApiKeyStyle::Header("api-key"),
+            Self::API_KEY_ENV_VAR,
+        )?;
+        Ok(QdrantApi::new(endpoint, headers))
+    }
+
+    fn vector_names(connection: &ConnectionBorrow<'_>) -> Vec<String> {
+        connection
+            .get::<VectorConnection>()
+            .provider_option(Self::VECTOR_NAMES_OPTION)
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Translates the filter of an operation, restricted to the namespace's points
+fn qdrant_filter(
+    filter: Option<FilterExpression>,
+    namespace: Option<&str>,
+) -> Result<Option<Value>, VectorError> {
+    namespace_filter(decode_filter(filter)?, namespace)
+        .map(|filter| to_qdrant_filter(&filter))
+        .transpose()
+}
+
+/// Runs a search, or a hybrid search of the dense and sparse vectors if the query has a
+/// sparse vector
+fn search(
+    api: &QdrantApi,
+    collection: &str,
+    query: &SearchQuery,
+    namespace: Option<&str>,
+) -> Result<Vec<SearchResult>, VectorError> {
+    reject_keywords("Qdrant", query)?;
+    let filter = qdrant_filter(query.filter.clone(), namespace)?;
+    let points = match &query.sparse {
+        Some(sparse) => api.query(collection, &hybrid_request(query, sparse, filter)?)?,
+        None => api.search(collection, &search_request(query, filter))?,
+    };
+    Ok(points.into_iter().map(search_result_from_point).collect())
+}
+
+/// Selects points by id. Within a namespace the ids are combined with the namespace filter,
+/// so points of other namespaces are never touched.
+fn ids_selector(ids: &[Id], namespace: Option<&str>) -> Result<PointsSelector, VectorError> {
+    let points = point_ids(ids)?;
+    match qdrant_filter(None, namespace)? {
+        Some(filter) => Ok(PointsSelector::Filter {
+            filter: json!({ "must": [ { "has_id": points }, filter ] }),
+        }),
+        None => Ok(PointsSelector::Points { points }),
+    }
+}
+
+/// Whether a point fetched with its payload belongs to the namespace
+fn in_namespace(point: &ScoredPoint, namespace: Option<&str>) -> bool {
+    namespace.is_none_or(|namespace| {
+        point
+            .payload
+            .as_ref()
+            .and_then(|payload| payload.get(NAMESPACE_FIELD))
+            .and_then(Value::as_str)
+            == Some(namespace)
+    })
+}
+
+impl CollectionsGuest for QdrantComponent {
+    /// Every collection also gets a sparse vector, which stays empty unless points are written
+    /// with sparse vectors
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+
+        let params = VectorParams {
+            size: config.dimension,
+            distance: distance_name(config.metric).to_string(),
+        };
+        let vector_names = Self::vector_names(&connection);
+        let vectors = if vector_names.is_empty() {
+            VectorsConfig::Single(params)
+        } else {
+            VectorsConfig::Named(
+                vector_names
+                    .into_iter()
+                    .map(|name| (name, params.clone()))
+                    .collect(),
+            )
+        };
+        let quantization_config = config
+            .quantization
+            .as_ref()
+            .map(to_qdrant_quantization)
+            .transpose()?;
+
+        api.create_collection(
+            &name,
+            &CreateCollectionRequest {
+                vectors,
+                sparse_vectors: HashMap::from([(SPARSE_VECTOR.to_string(), SparseVectorParams {})]),
+                quantization_config,
+            },
+        )
+    }
+
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.delete_collection(&name)
+    }
+
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        Self::api(&connection)?.list_collections()
+    }
+
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<CollectionInfo, VectorError> {
+        let info = Self::api(&connection)?.get_collection(&name)?;
+        Ok(collection_info(name, info))
+    }
+
+    fn create_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<TaskId, VectorError> {
+        let snapshot = Self::api(&connection)?.create_snapshot(&collection)?;
+        Ok(Task::new(TaskKind::CreateSnapshot, collection, snapshot.name).encode())
+    }
+
+    /// Snapshots are given by name, and are recovered from this Qdrant instance's snapshot
+    /// download URL. Full URLs, such as `file://` or `https://` locations, are passed to
+    /// Qdrant unchanged.
+    fn restore_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        let api = Self::api(&connection)?;
+        let location = if snapshot.contains("://") {
+            snapshot.clone()
+        } else {
+            format!(
+                "{}/collections/{collection}/snapshots/{snapshot}",
+                api.endpoint()
+            )
+        };
+        api.recover_snapshot(&collection, &location)?;
+        Ok(Task::new(TaskKind::RestoreSnapshot, collection, snapshot).encode())
+    }
+
+    fn list_snapshots(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        let snapshots = Self::api(&connection)?.list_snapshots(&collection)?;
+        Ok(snapshots
+            .into_iter()
+            .map(|snapshot| SnapshotInfo {
+                name: snapshot.name,
+                created_at: snapshot.creation_time,
+                size_bytes: snapshot.size,
+            })
+            .collect())
+    }
+
+    /// Snapshots are created and recovered synchronously, so tasks are finished by the time
+    /// their id is returned. Polling a snapshot task checks that the snapshot still exists.
+    fn get_task_status(
+        connection: ConnectionBorrow<'_>,
+        task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        let task = Task::decode(&task)?;
+        match task.kind {
+            TaskKind::CreateSnapshot => {
+                let snapshots = Self::api(&connection)?.list_snapshots(&task.collection)?;
+                if snapshots
+                    .iter()
+                    .any(|snapshot| snapshot.name == task.provider_id)
+                {
+                    Ok(TaskStatus::Succeeded(Some(task.provider_id)))
+                } else {
+                    Ok(TaskStatus::Failed(format!(
+                        "Snapshot {} of collection {} does not exist",
+                        task.provider_id, task.collection
+                    )))
+                }
+            }
+            TaskKind::RestoreSnapshot => Ok(TaskStatus::Succeeded(None)),
+        }
+    }
+
+    fn create_payload_index(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        field: String,
+        index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.create_payload_index(
+            &collection,
+            &CreateIndexRequest {
+                field_name: field,
+                field_schema: qdrant_field_schema(index_type).to_string(),
+            },
+        )
+    }
+
+    fn list_payload_indexes(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        let info = Self::api(&connection)?.get_collection(&collection)?;
+        let mut indexes = info
+            .payload_schema
+            .into_iter()
+            .filter(|(field, _)| field != NAMESPACE_FIELD)
+            .filter_map(|(field, schema)| {
+                from_qdrant_field_schema(&schema.data_type)
+                    .map(|index_type| PayloadIndexInfo { field, index_type })
+            })
+            .collect::<Vec<_>>();
+        indexes.sort_by(|a, b| a.field.cmp(&b.field));
+        Ok(indexes)
+    }
+
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.update_aliases(vec![AliasAction::CreateAlias {
+            collection_name: collection,
+            alias_name: alias,
+        }])
+    }
+
+    /// Qdrant applies the actions of a single request atomically
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.update_aliases(vec![
+            AliasAction::DeleteAlias {
+                alias_name: alias.clone(),
+            },
+            AliasAction::CreateAlias {
+                collection_name: collection,
+                alias_name: alias,
+            },
+        ])
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        Self::api(&connection)?.update_aliases(vec![AliasAction::DeleteAlias { alias_name: alias }])
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let response = Self::api(&connection)?.list_aliases()?;
+        Ok(response
+            .aliases
+            .into_iter()
+            .map(|alias| AliasInfo {
+                alias: alias.alias_name,
+                collection: alias.collection_name,
+            })
+            .collect())
+    }
+}
+
+/// Writes the points of a batch with a single upsert request
+struct QdrantUpsert {
+    api: QdrantApi,
+    collection: String,
+    namespace: Option<String>,
+    params: Vec<(&'static str, String)>,
+}
+
+impl BatchWriter for QdrantUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        let points = batch
+            .iter()
+            .map(|record| {
+                Ok(PointStruct {
+                    id: point_id(&record.id)?,
+                    vector: point_vector(&record.vector, record.sparse.as_ref())?,
+                    payload: payload(record.metadata.clone(), self.namespace.as_deref()),
+                })
+            })
+            .collect::<Result<Vec<_>, VectorError>>()?;
+        self.api.upsert_points(
+            &self.collection,
+            &UpsertPointsRequest { points },
+            &self.params,
+        )
+    }
+}
+
+impl VectorsGuest for QdrantComponent {
+    type UpsertStream = VectorUpsertStream<QdrantUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        if metadata.iter().any(|(key, _)| key == NAMESPACE_FIELD) {
+            return Err(VectorError::InvalidParams(format!(
+                "The metadata field {NAMESPACE_FIELD} is reserved for namespaces"
+            )));
+        }
+
+        Self::api(&connection)?.set_payload(
+            &collection,
+            &SetPayloadRequest {
+                payload: metadata_to_json(&metadata),
+                selector: ids_selector(&ids, namespace.as_deref())?,
+            },
+            &qdrant_write_params(options.as_ref()),
+        )
+    }
+
+    /// Qdrant cannot update vectors by filter, so within a namespace the point is checked to
+    /// belong to it first
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let id = point_id(&id)?;
+
+        if let Some(namespace) = &namespace {
+            let points = api.get_points(
+                &collection,
+                &GetPointsRequest {
+                    ids: vec![id.clone()],
+                    with_payload: true,
+                    with_vector: false,
+                },
+            )?;
+            if !points
+                .iter()
+                .any(|point| in_namespace(point, Some(namespace)))
+            {
+                return Err(VectorError::NotFound(format!(
+                    "Point {} does not exist in namespace {namespace}",
+                    id_from_json(&id)
+                )));
+            }
+        }
+
+        api.update_vectors(
+            &collection,
+            &UpdateVectorsRequest {
+                points: vec![PointVectors {
+                    id,
+                    vector: vector_to_json(&vector),
+                }],
+            },
+            &qdrant_write_params(options.as_ref()),
+        )
+    }
+
+    /// Qdrant cannot get points by filter, so points of other namespaces are left out after
+    /// fetching them with their payload
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let points = Self::api(&connection)?.get_points(
+            &collection,
+            &GetPointsRequest {
+                ids: point_ids(&ids)?,
+                with_payload: include_metadata || namespace.is_some(),
+                with_vector: include_vectors,
+            },
+        )?;
+        Ok(points
+            .into_iter()
+            .filter(|point| in_namespace(point, namespace.as_deref()))
+            .map(|point| {
+                let point = point_from_qdrant(point);
+                Point {
+                    metadata: point.metadata.filter(|_| include_metadata),
+                    ..point
+                }
+            })
+            .collect())
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.delete_points(
+            &collection,
+            &ids_selector(&ids, namespace.as_deref())?,
+            &qdrant_write_params(options.as_ref()),
+        )
+    }
+}
+
+impl SearchGuest for QdrantComponent {
+    type PointStream = VectorPointStream<QdrantPointPager>;
+
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        search(&api, &collection, &query, namespace.as_deref())
+    }
+
+    /// Batches with hybrid queries are run one query at a time
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        if queries.iter().any(|query| query.sparse.is_some()) {
+            return search_each(queries, |query| {
+                search(&api, &collection, &query, namespace.as_deref())
+            });
+        }
+
+        let searches = queries
+            .iter()
+            .map(|query| {
+                reject_keywords("Qdrant", query)?;
+                let filter = qdrant_filter(query.filter.clone(), namespace.as_deref())?;
+                Ok(search_request(query, filter))
+            })
+            .collect::<Result<Vec<_>, VectorError>>()?;
+        let results = api.search_batch(&collection, &SearchBatchRequest { searches })?;
+        Ok(results
+            .into_iter()
+            .map(|points| points.into_iter().map(search_result_from_point).collect())
+            .collect())
+    }
+
+    /// A recommendation with the point as the only positive example, which searches with the
+    /// point's vector
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        Self::recommend(
+            connection,
+            collection,
+            vec![id],
+            vec![],
+            limit,
+            filter,
+            namespace,
+        )
+    }
+
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let points = api.recommend(
+            &collection,
+            &RecommendRequest {
+                positive: point_ids(&positive)?,
+                negative: point_ids(&negative)?,
+                limit,
+                filter: qdrant_filter(filter, namespace.as_deref())?,
+                with_payload: true,
+                with_vector: false,
+            },
+        )?;
+        Ok(points.into_iter().map(search_result_from_point).collect())
+    }
+
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        Self::api(&connection)?.count(
+            &collection,
+            &CountRequest {
+                filter: qdrant_filter(filter, namespace.as_deref())?,
+                exact: true,
+            },
+        )
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for QdrantComponent {
+    type BatchWriter = QdrantUpsert;
+    type PointPager = QdrantPointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<QdrantUpsert, VectorError> {
+        Ok(QdrantUpsert {
+            api: Self::api(&connection)?,
+            collection,
+            namespace,
+            params: qdrant_write_params(options.as_ref()),
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> QdrantPointPager {
+        let scroll = Self::api(&connection).and_then(|api| {
+            let filter = qdrant_filter(options.filter.clone(), namespace.as_deref())?;
+            Ok(QdrantScroll {
+                api,
+                collection,
+                filter,
+                options,
+            })
+        });
+        QdrantPointPager { scroll }
+    }
+}
+
+struct QdrantScroll {
+    api: QdrantApi,
+    collection: String,
+    filter: Option<Value>,
+    options: ScrollOptions,
+}
+
+/// Pages through points with Qdrant's scroll API. The cursor is the JSON encoded
+/// `next_page_offset` of the previous page.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct QdrantPointPager {
+    scroll: Result<QdrantScroll, VectorError>,
+}
+
+impl PointPager for QdrantPointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let offset = cursor
+            .map(serde_json::from_str::<Value>)
+            .transpose()
+            .map_err(|err| VectorError::InvalidParams(format!("Invalid scroll offset: {err}")))?;
+
+        let result = scroll.api.scroll(
+            &scroll.collection,
+            &ScrollRequest {
+                filter: scroll.filter.clone(),
+                limit: scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+                offset,
+                with_payload: scroll.options.include_metadata,
+                with_vector: scroll.options.include_vectors,
+            },
+        )?;
+
+        let points: Vec<Point> = result.points.into_iter().map(point_from_qdrant).collect();
+        Ok(PointPage {
+            points,
+            next_cursor: result
+                .next_page_offset
+                .filter(|offset| !offset.is_null())
+                .map(|offset| offset.to_string()),
+        })
+    }
+}
+
+type DurableQdrantComponent = DurableVector<QdrantComponent>;
+
+golem_vector::export_vector!(DurableQdrantComponent with_types_in golem_vector);
@@ -0,0 +1,73 @@
+//! Emulation of collection aliases for providers without native alias support.
+//!
+//! Aliases are stored as points of a reserved collection, one per alias, with the target
+//! collection in their metadata. Providers resolve collection names through this collection
+//! before every operation, so switching an alias is a single point upsert.
+
+use crate::golem::vector::types::{
+    AliasInfo, Metadata, MetadataValue, Point, VectorData, VectorRecord,
+};
+
+/// The reserved collection holding the alias documents
+pub const ALIAS_COLLECTION: &str = "golem_vector_aliases";
+
+/// Dimension of the placeholder vectors stored in the alias collection
+pub const ALIAS_VECTOR_DIMENSION: u32 = 1;
+
+const ALIAS_FIELD: &str = "alias";
+const TARGET_FIELD: &str = "collection";
+
+/// Creates the document describing an alias
+pub fn alias_record(alias: &str, collection: &str) -> VectorRecord {
+    VectorRecord {
+        id: alias.to_string(),
+        vector: VectorData::Dense(vec![1.0; ALIAS_VECTOR_DIMENSION as usize]),
+        sparse: None,
+        metadata: Some(vec![
+            (
+                ALIAS_FIELD.to_string(),
+                MetadataValue::StringVal(alias.to_string()),
+            ),
+            (
+                TARGET_FIELD.to_string(),
+                MetadataValue::StringVal(collection.to_string()),
+            ),
+        ]),
+    }
+}
+
+/// Reads an alias document returned by the provider
+pub fn alias_from_point(point: &Point) -> Option<AliasInfo> {
+    let metadata = point.metadata.as_ref()?;
+    Some(AliasInfo {
+        alias: string_field(metadata, ALIAS_FIELD).unwrap_or_else(|| point.id.clone()),
+        collection: string_field(metadata, TARGET_FIELD)?,
+    })
+}
+
+fn string_field(metadata: &Metadata, field: &str) -> Option<String> {
+    metadata.iter().find_map(|(key, value)| match value {
+        MetadataValue::StringVal(s) if key == field => Some(s.clone()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alias::{alias_from_point, alias_record};
+    use crate::golem::vector::types::Point;
+
+    #[test]
+    fn alias_document_roundtrip() {
+        let record = alias_record("products", "products_v2");
+        let point = Point {
+            id: record.id,
+            vector: None,
+            sparse: None,
+            metadata: record.metadata,
+        };
+        let alias = alias_from_point(&point).unwrap();
+        assert_eq!(alias.alias, "products");
+        assert_eq!(alias.collection, "products_v2");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::golem::vector::types::{BatchFailure, BatchResult, VectorError, VectorRecord};
+
+/// Writes `records` in consecutive chunks of at most `batch_size` elements using `write_batch`.
+///
+/// Every batch is attempted even if a previous one failed, and failures are reported per batch
+/// with the range of input records they cover, so callers can retry only the failed parts.
+///
+/// The outgoing HTTP client used by the providers is blocking, so batches are sent one after
+/// the other. Keeping each batch at the provider's size limit is what keeps the number of
+/// roundtrips low for large ingests.
+pub fn write_in_batches<F>(
+    records: &[VectorRecord],
+    batch_size: usize,
+    mut write_batch: F,
+) -> BatchResult
+where
+    F: FnMut(&[VectorRecord]) -> Result<(), VectorError>,
+{
+    let batch_size = batch_size.max(1);
+    let mut success_count = 0;
+    let mut failures = Vec::new();
+
+    for (batch_index, batch) in records.chunks(batch_size).enumerate() {
+        let start_index = batch_index * batch_size;
+        match write_batch(batch) {
+            Ok(()) => success_count += batch.len() as u32,
+            Err(error) => {
+                log::warn!(
+                    "Failed to write batch of {} vectors starting at {start_index}: {error:?}",
+                    batch.len()
+                );
+                failures.push(BatchFailure {
+                    start_index: start_index as u32,
+                    count: batch.len() as u32,
+                    error,
+                });
+            }
+        }
+    }
+
+    BatchResult {
+        success_count,
+        failures,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::write_in_batches;
+    use crate::golem::vector::types::{VectorData, VectorError, VectorRecord};
+
+    fn records(n: usize) -> Vec<VectorRecord> {
+        (0..n)
+            .map(|i| VectorRecord {
+                id: i.to_string(),
+                vector: VectorData::Dense(vec![i as f32]),
+                sparse: None,
+                metadata: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_to_batch_size() {
+        let mut sizes = Vec::new();
+        let result = write_in_batches(&records(250), 100, |batch| {
+            sizes.push(batch.len());
+            Ok(())
+        });
+        assert_eq!(sizes, vec![100, 100, 50]);
+        assert_eq!(result.success_count, 250);
+        assert!(result.failures.is_empty());
+    }
+
+    #[test]
+    fn reports_failed_batches_and_continues() {
+        let mut calls = 0;
+        let result = write_in_batches(&records(25), 10, |batch| {
+            calls += 1;
+            if batch[0].id == "10" {
+                Err(VectorError::RateLimited("slow down".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(calls, 3);
+        assert_eq!(result.success_count, 15);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].start_index, 10);
+        assert_eq!(result.failures[0].count, 10);
+    }
+}
@@ -0,0 +1,17 @@
+use crate::golem::vector::types::VectorError;
+use golem_ai_common::config::config_value;
+pub use golem_ai_common::config::get_optional_config;
+use std::ffi::OsStr;
+
+/// Gets an expected configuration value from the environment, and fails if its is not found
+/// using the `fail` function. Otherwise, it runs `succeed` with the configuration value.
+pub fn with_config_key<R>(
+    key: impl AsRef<OsStr>,
+    fail: impl FnOnce(VectorError) -> R,
+    succeed: impl FnOnce(String) -> R,
+) -> R {
+    match config_value(key) {
+        Ok(value) => succeed(value),
+        Err(message) => fail(VectorError::InvalidParams(message)),
+    }
+}
@@ -0,0 +1,188 @@
+use crate::config::with_config_key;
+use crate::exports::golem::vector::connection::GuestConnection;
+use crate::golem::vector::types::{ConnectionConfig, Credentials, VectorError};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// The way a provider expects to receive API keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyStyle {
+    /// In a provider specific header, such as `api-key` for Qdrant
+    Header(&'static str),
+    /// As an `Authorization: Bearer` header
+    Bearer,
+}
+
+/// Implementation of the `connection` resource, shared by all the vector providers.
+///
+/// Providers get the connection of each operation as a borrowed handle, and can access
+/// this type with `connection.get::<VectorConnection>()`.
+#[derive(Debug, Clone)]
+pub struct VectorConnection {
+    config: ConnectionConfig,
+}
+
+impl VectorConnection {
+    pub fn new(config: ConnectionConfig) -> Result<Self, VectorError> {
+        if let Some(endpoint) = &config.endpoint {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                return Err(VectorError::InvalidParams(format!(
+                    "Invalid endpoint {endpoint}, expected an http:// or https:// URL"
+                )));
+            }
+        }
+        Ok(Self { config })
+    }
+
+    pub fn config(&self) -> &ConnectionConfig {
+        &self.config
+    }
+
+    /// Gets the endpoint of the connection, falling back to the `env_var` environment variable
+    /// and then to the provider's `default` endpoint
+    pub fn endpoint_or_env(
+        &self,
+        env_var: &str,
+        default: Option<&str>,
+    ) -> Result<String, VectorError> {
+        match &self.config.endpoint {
+            Some(endpoint) => Ok(endpoint.trim_end_matches('/').to_string()),
+            None => match (std::env::var(env_var), default) {
+                (Ok(endpoint), _) => Ok(endpoint.trim_end_matches('/').to_string()),
+                (Err(_), Some(default)) => Ok(default.to_string()),
+                (Err(_), None) => Err(VectorError::InvalidParams(format!(
+                    "No endpoint given in the connection config or in {env_var}"
+                ))),
+            },
+        }
+    }
+
+    /// Builds the authentication headers of the connection for a provider.
+    ///
+    /// When the connection has no credentials, the API key is taken from the `env_var`
+    /// environment variable if it is set. Client certificates are rejected, because the WASI
+    /// HTTP client does not support configuring TLS client authentication.
+    pub fn auth_headers(
+        &self,
+        provider: &str,
+        api_key_style: ApiKeyStyle,
+        env_var: &str,
+    ) -> Result<Vec<(String, String)>, VectorError> {
+        match &self.config.credentials {
+            Some(Credentials::ApiKey(api_key)) => Ok(vec![api_key_header(api_key_style, api_key)]),
+            Some(Credentials::BearerToken(token)) => Ok(vec![(
+                "Authorization".to_string(),
+                format!("Bearer {token}"),
+            )]),
+            Some(Credentials::Basic(basic)) => {
+                let encoded = STANDARD.encode(format!("{}:{}", basic.username, basic.password));
+                Ok(vec![(
+                    "Authorization".to_string(),
+                    format!("Basic {encoded}"),
+                )])
+            }
+            Some(Credentials::ClientCertificate(_)) => Err(VectorError::UnsupportedFeature(
+                format!("{provider} connections cannot use client certificates, as TLS client authentication is not supported by the WASI HTTP client"),
+            )),
+            None => match std::env::var(env_var) {
+                Ok(api_key) => Ok(vec![api_key_header(api_key_style, &api_key)]),
+                Err(_) => Ok(vec![]),
+            },
+        }
+    }
+
+    /// Gets the API key of the connection, falling back to the `env_var` environment variable.
+    /// Fails if the connection has a different kind of credentials.
+    pub fn api_key_or_env(&self, provider: &str, env_var: &str) -> Result<String, VectorError> {
+        match &self.config.credentials {
+            Some(Credentials::ApiKey(api_key)) => Ok(api_key.clone()),
+            Some(_) => Err(VectorError::UnsupportedFeature(format!(
+                "{provider} only supports API key credentials"
+            ))),
+            None => with_config_key(env_var, Err, Ok),
+        }
+    }
+
+    pub fn provider_option(&self, key: &str) -> Option<&str> {
+        self.config
+            .provider_options
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn api_key_header(style: ApiKeyStyle, api_key: &str) -> (String, String) {
+    match style {
+        ApiKeyStyle::Header(name) => (name.to_string(), api_key.to_string()),
+        ApiKeyStyle::Bearer => ("Authorization".to_string(), format!("Bearer {api_key}")),
+    }
+}
+
+impl GuestConnection for VectorConnection {
+    fn endpoint(&self) -> Option<String> {
+        self.config.endpoint.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::connection::{ApiKeyStyle, VectorConnection};
+    use crate::golem::vector::types::{
+        BasicAuth, ClientCertificate, ConnectionConfig, Credentials, VectorError,
+    };
+
+    fn connection(credentials: Credentials) -> VectorConnection {
+        VectorConnection::new(ConnectionConfig {
+            endpoint: Some("https://localhost:6333".to_string()),
+            credentials: Some(credentials),
+            provider_options: vec![],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn api_key_uses_provider_header() {
+        let headers = connection(Credentials::ApiKey("secret".to_string()))
+            .auth_headers("Qdrant", ApiKeyStyle::Header("api-key"), "UNUSED")
+            .unwrap();
+        assert_eq!(headers, vec![("api-key".to_string(), "secret".to_string())]);
+    }
+
+    #[test]
+    fn basic_auth_is_encoded() {
+        let headers = connection(Credentials::Basic(BasicAuth {
+            username: "user".to_string(),
+            password: "pass".to_string(),
+        }))
+        .auth_headers("Milvus", ApiKeyStyle::Bearer, "UNUSED")
+        .unwrap();
+        assert_eq!(
+            headers,
+            vec![(
+                "Authorization".to_string(),
+                "Basic dXNlcjpwYXNz".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn client_certificates_are_unsupported() {
+        let result = connection(Credentials::ClientCertificate(ClientCertificate {
+            certificate_pem: "cert".to_string(),
+            private_key_pem: "key".to_string(),
+        }))
+        .auth_headers("Qdrant", ApiKeyStyle::Header("api-key"), "UNUSED");
+        assert!(matches!(result, Err(VectorError::UnsupportedFeature(_))));
+    }
+
+    #[test]
+    fn rejects_non_http_endpoints() {
+        assert!(VectorConnection::new(ConnectionConfig {
+            endpoint: Some("localhost:6333".to_string()),
+            credentials: None,
+            provider_options: vec![],
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,1050 @@
+use crate::connection::VectorConnection;
+use crate::exports::golem::vector::collections::Guest as CollectionsGuest;
+use crate::exports::golem::vector::connection::{
+    Connection, ConnectionBorrow, Guest as ConnectionGuest,
+};
+use crate::exports::golem::vector::search::Guest as SearchGuest;
+use crate::exports::golem::vector::vectors::Guest as VectorsGuest;
+use crate::golem::vector::types::{ConnectionConfig, ScrollOptions, VectorError, WriteOptions};
+use crate::point_stream::PointPager;
+use crate::upsert_stream::BatchWriter;
+use std::marker::PhantomData;
+
+/// Wraps a vector database implementation with custom durability
+pub struct DurableVector<Impl> {
+    phantom: PhantomData<Impl>,
+}
+
+/// Trait to be implemented by the vector database providers wrapped with `DurableVector`,
+/// in addition to all the exported interfaces.
+pub trait ExtendedGuest: CollectionsGuest + VectorsGuest + SearchGuest + 'static {
+    type BatchWriter: BatchWriter;
+    type PointPager: PointPager;
+
+    /// Creates the writer of upserts to a collection without wrapping it in an `UpsertStream`,
+    /// so that `DurableVector` can persist the batches it writes
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<Self::BatchWriter, VectorError>;
+
+    /// Creates the pager of a `scroll-points` stream without wrapping it in a `PointStream`,
+    /// so that `DurableVector` can persist the pages it fetches
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> Self::PointPager;
+}
+
+/// Connections only hold configuration and opening them does not perform any requests,
+/// so they are the same for every provider and do not need custom durability.
+impl<Impl: ExtendedGuest> ConnectionGuest for DurableVector<Impl> {
+    type Connection = VectorConnection;
+
+    fn connect(config: ConnectionConfig) -> Result<Connection, VectorError> {
+        VectorConnection::new(config).map(Connection::new)
+    }
+}
+
+/// When the durability feature flag is off, wrapping with `DurableVector` is just a passthrough
+#[cfg(not(feature = "durability"))]
+mod passthrough_impl {
+    use crate::durability::{DurableVector, ExtendedGuest};
+    use crate::exports::golem::vector::collections::Guest as CollectionsGuest;
+    use crate::exports::golem::vector::connection::ConnectionBorrow;
+    use crate::exports::golem::vector::search::{Guest as SearchGuest, PointStream};
+    use crate::exports::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+    use crate::golem::vector::types::{
+        AliasInfo, BatchResult, CollectionConfig, CollectionInfo, FilterExpression, Id, Metadata,
+        PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+        SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+    };
+
+    impl<Impl: ExtendedGuest> CollectionsGuest for DurableVector<Impl> {
+        fn create_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+            config: CollectionConfig,
+        ) -> Result<(), VectorError> {
+            Impl::create_collection(connection, name, config)
+        }
+
+        fn delete_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+        ) -> Result<(), VectorError> {
+            Impl::delete_collection(connection, name)
+        }
+
+        fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+            Impl::list_collections(connection)
+        }
+
+        fn describe_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+        ) -> Result<CollectionInfo, VectorError> {
+            Impl::describe_collection(connection, name)
+        }
+
+        fn create_snapshot(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<TaskId, VectorError> {
+            Impl::create_snapshot(connection, collection)
+        }
+
+        fn restore_snapshot(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            snapshot: String,
+        ) -> Result<TaskId, VectorError> {
+            Impl::restore_snapshot(connection, collection, snapshot)
+        }
+
+        fn list_snapshots(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<Vec<SnapshotInfo>, VectorError> {
+            Impl::list_snapshots(connection, collection)
+        }
+
+        fn get_task_status(
+            connection: ConnectionBorrow<'_>,
+            task: TaskId,
+        ) -> Result<TaskStatus, VectorError> {
+            Impl::get_task_status(connection, task)
+        }
+
+        fn create_payload_index(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            field: String,
+            index_type: PayloadIndexType,
+        ) -> Result<(), VectorError> {
+            Impl::create_payload_index(connection, collection, field, index_type)
+        }
+
+        fn list_payload_indexes(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+            Impl::list_payload_indexes(connection, collection)
+        }
+
+        fn create_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+            collection: String,
+        ) -> Result<(), VectorError> {
+            Impl::create_alias(connection, alias, collection)
+        }
+
+        fn switch_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+            collection: String,
+        ) -> Result<(), VectorError> {
+            Impl::switch_alias(connection, alias, collection)
+        }
+
+        fn delete_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+        ) -> Result<(), VectorError> {
+            Impl::delete_alias(connection, alias)
+        }
+
+        fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+            Impl::list_aliases(connection)
+        }
+    }
+
+    impl<Impl: ExtendedGuest> VectorsGuest for DurableVector<Impl> {
+        type UpsertStream = Impl::UpsertStream;
+
+        fn upsert_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            vectors: Vec<VectorRecord>,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<BatchResult, VectorError> {
+            Impl::upsert_vectors(connection, collection, vectors, namespace, options)
+        }
+
+        fn open_upsert_stream(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> UpsertStream {
+            Impl::open_upsert_stream(connection, collection, namespace, options)
+        }
+
+        fn set_payload(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            metadata: Metadata,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            Impl::set_payload(connection, collection, ids, metadata, namespace, options)
+        }
+
+        fn update_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            id: Id,
+            vector: VectorData,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            Impl::update_vectors(connection, collection, id, vector, namespace, options)
+        }
+
+        fn get_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            namespace: Option<String>,
+            include_vectors: bool,
+            include_metadata: bool,
+        ) -> Result<Vec<Point>, VectorError> {
+            Impl::get_vectors(
+                connection,
+                collection,
+                ids,
+                namespace,
+                include_vectors,
+                include_metadata,
+            )
+        }
+
+        fn delete_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            Impl::delete_vectors(connection, collection, ids, namespace, options)
+        }
+    }
+
+    impl<Impl: ExtendedGuest> SearchGuest for DurableVector<Impl> {
+        type PointStream = Impl::PointStream;
+
+        fn search_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            query: SearchQuery,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            Impl::search_vectors(connection, collection, query, namespace)
+        }
+
+        fn query_batch(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            queries: Vec<SearchQuery>,
+            namespace: Option<String>,
+        ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+            Impl::query_batch(connection, collection, queries, namespace)
+        }
+
+        fn search_by_id(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            id: Id,
+            limit: u32,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            Impl::search_by_id(connection, collection, id, limit, filter, namespace)
+        }
+
+        fn recommend(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            positive: Vec<Id>,
+            negative: Vec<Id>,
+            limit: u32,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            Impl::recommend(
+                connection, collection, positive, negative, limit, filter, namespace,
+            )
+        }
+
+        fn count(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<u64, VectorError> {
+            Impl::count(connection, collection, filter, namespace)
+        }
+
+        fn scroll_points(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            options: ScrollOptions,
+            namespace: Option<String>,
+        ) -> PointStream {
+            Impl::scroll_points(connection, collection, options, namespace)
+        }
+    }
+}
+
+/// When the durability feature flag is on, wrapping with `DurableVector` adds custom durability
+/// on top of the provider-specific implementation using Golem's special host functions and
+/// the `golem-rust` helper library.
+///
+/// Every operation is saved in the oplog with its full input and result, so writes are not
+/// repeated and queries return the same results when the worker is recovered.
+///
+/// Every page of a point stream is saved with the cursor of the next page, so a recovered
+/// stream continues fetching from the provider where the persisted pages end.
+#[cfg(feature = "durability")]
+mod durable_impl {
+    use crate::connection::VectorConnection;
+    use crate::durability::{DurableVector, ExtendedGuest};
+    use crate::exports::golem::vector::collections::Guest as CollectionsGuest;
+    use crate::exports::golem::vector::connection::ConnectionBorrow;
+    use crate::exports::golem::vector::search::{Guest as SearchGuest, PointStream};
+    use crate::exports::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+    use crate::golem::vector::types::{
+        AliasInfo, BatchResult, CollectionConfig, CollectionInfo, FilterExpression, Id, Metadata,
+        PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+        SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+    };
+    use crate::point_stream::{PointPage, PointPager, VectorPointStream};
+    use crate::upsert_stream::{BatchWriter, VectorUpsertStream};
+    use golem_rust::bindings::golem::durability::durability::DurableFunctionType;
+    use golem_rust::durability::Durability;
+    use golem_rust::{with_persistence_level, FromValueAndType, IntoValue, PersistenceLevel};
+    use std::fmt::Debug;
+
+    /// Runs `f` as a durable function, persisting `input` and its result in the oplog in live
+    /// mode, and returning the persisted result in replay mode
+    fn durable<SIn, SOk>(
+        function: &'static str,
+        function_type: DurableFunctionType,
+        input: SIn,
+        f: impl FnOnce() -> Result<SOk, VectorError>,
+    ) -> Result<SOk, VectorError>
+    where
+        SIn: Debug + IntoValue,
+        SOk: Debug + Clone + FromValueAndType + IntoValue,
+    {
+        let durability =
+            Durability::<SOk, VectorError>::new("golem_vector", function, function_type);
+        if durability.is_live() {
+            let result = with_persistence_level(PersistenceLevel::PersistNothing, f);
+            durability.persist(input, result)
+        } else {
+            durability.replay()
+        }
+    }
+
+    /// Only the endpoint of the connection is persisted, so credentials are never written to the oplog
+    fn endpoint(connection: &ConnectionBorrow<'_>) -> Option<String> {
+        connection
+            .get::<VectorConnection>()
+            .config()
+            .endpoint
+            .clone()
+    }
+
+    /// Same as `durable` for operations without a result value
+    fn durable_unit<SIn>(
+        function: &'static str,
+        input: SIn,
+        f: impl FnOnce() -> Result<(), VectorError>,
+    ) -> Result<(), VectorError>
+    where
+        SIn: Debug + IntoValue,
+    {
+        durable(function, DurableFunctionType::WriteRemote, input, || {
+            f().map(|_| NoOutput)
+        })
+        .map(|_| ())
+    }
+
+    impl<Impl: ExtendedGuest> CollectionsGuest for DurableVector<Impl> {
+        fn create_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+            config: CollectionConfig,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "create_collection",
+                CreateCollectionInput {
+                    endpoint: endpoint(&connection),
+                    name: name.clone(),
+                    config: config.clone(),
+                },
+                || Impl::create_collection(connection, name, config),
+            )
+        }
+
+        fn delete_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "delete_collection",
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: name.clone(),
+                },
+                || Impl::delete_collection(connection, name),
+            )
+        }
+
+        fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+            durable(
+                "list_collections",
+                DurableFunctionType::ReadRemote,
+                EndpointInput {
+                    endpoint: endpoint(&connection),
+                },
+                || Impl::list_collections(connection),
+            )
+        }
+
+        fn describe_collection(
+            connection: ConnectionBorrow<'_>,
+            name: String,
+        ) -> Result<CollectionInfo, VectorError> {
+            durable(
+                "describe_collection",
+                DurableFunctionType::ReadRemote,
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: name.clone(),
+                },
+                || Impl::describe_collection(connection, name),
+            )
+        }
+
+        fn create_snapshot(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<TaskId, VectorError> {
+            durable(
+                "create_snapshot",
+                DurableFunctionType::WriteRemote,
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: collection.clone(),
+                },
+                || Impl::create_snapshot(connection, collection),
+            )
+        }
+
+        fn restore_snapshot(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            snapshot: String,
+        ) -> Result<TaskId, VectorError> {
+            durable(
+                "restore_snapshot",
+                DurableFunctionType::WriteRemote,
+                RestoreSnapshotInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    snapshot: snapshot.clone(),
+                },
+                || Impl::restore_snapshot(connection, collection, snapshot),
+            )
+        }
+
+        fn list_snapshots(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<Vec<SnapshotInfo>, VectorError> {
+            durable(
+                "list_snapshots",
+                DurableFunctionType::ReadRemote,
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: collection.clone(),
+                },
+                || Impl::list_snapshots(connection, collection),
+            )
+        }
+
+        fn get_task_status(
+            connection: ConnectionBorrow<'_>,
+            task: TaskId,
+        ) -> Result<TaskStatus, VectorError> {
+            durable(
+                "get_task_status",
+                DurableFunctionType::ReadRemote,
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: task.clone(),
+                },
+                || Impl::get_task_status(connection, task),
+            )
+        }
+
+        fn create_payload_index(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            field: String,
+            index_type: PayloadIndexType,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "create_payload_index",
+                CreatePayloadIndexInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    field: field.clone(),
+                    index_type,
+                },
+                || Impl::create_payload_index(connection, collection, field, index_type),
+            )
+        }
+
+        fn list_payload_indexes(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+        ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+            durable(
+                "list_payload_indexes",
+                DurableFunctionType::ReadRemote,
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: collection.clone(),
+                },
+                || Impl::list_payload_indexes(connection, collection),
+            )
+        }
+
+        fn create_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+            collection: String,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "create_alias",
+                AliasInput {
+                    endpoint: endpoint(&connection),
+                    alias: alias.clone(),
+                    collection: collection.clone(),
+                },
+                || Impl::create_alias(connection, alias, collection),
+            )
+        }
+
+        fn switch_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+            collection: String,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "switch_alias",
+                AliasInput {
+                    endpoint: endpoint(&connection),
+                    alias: alias.clone(),
+                    collection: collection.clone(),
+                },
+                || Impl::switch_alias(connection, alias, collection),
+            )
+        }
+
+        fn delete_alias(
+            connection: ConnectionBorrow<'_>,
+            alias: String,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "delete_alias",
+                NameInput {
+                    endpoint: endpoint(&connection),
+                    name: alias.clone(),
+                },
+                || Impl::delete_alias(connection, alias),
+            )
+        }
+
+        fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+            durable(
+                "list_aliases",
+                DurableFunctionType::ReadRemote,
+                EndpointInput {
+                    endpoint: endpoint(&connection),
+                },
+                || Impl::list_aliases(connection),
+            )
+        }
+    }
+
+    impl<Impl: ExtendedGuest> VectorsGuest for DurableVector<Impl> {
+        type UpsertStream = VectorUpsertStream<DurableBatchWriter<Impl::BatchWriter>>;
+
+        fn upsert_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            vectors: Vec<VectorRecord>,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<BatchResult, VectorError> {
+            durable(
+                "upsert_vectors",
+                DurableFunctionType::WriteRemote,
+                UpsertVectorsInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    vectors: vectors.clone(),
+                    namespace: namespace.clone(),
+                    options: options.clone(),
+                },
+                || Impl::upsert_vectors(connection, collection, vectors, namespace, options),
+            )
+        }
+
+        fn open_upsert_stream(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> UpsertStream {
+            let writer = Impl::batch_writer(connection, collection, namespace, options)
+                .map(|writer| DurableBatchWriter { writer });
+            UpsertStream::new(VectorUpsertStream::new(writer))
+        }
+
+        fn set_payload(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            metadata: Metadata,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "set_payload",
+                SetPayloadInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    ids: ids.clone(),
+                    metadata: metadata.clone(),
+                    namespace: namespace.clone(),
+                    options: options.clone(),
+                },
+                || Impl::set_payload(connection, collection, ids, metadata, namespace, options),
+            )
+        }
+
+        fn update_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            id: Id,
+            vector: VectorData,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "update_vectors",
+                UpdateVectorsInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    id: id.clone(),
+                    vector: vector.clone(),
+                    namespace: namespace.clone(),
+                    options: options.clone(),
+                },
+                || Impl::update_vectors(connection, collection, id, vector, namespace, options),
+            )
+        }
+
+        fn get_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            namespace: Option<String>,
+            include_vectors: bool,
+            include_metadata: bool,
+        ) -> Result<Vec<Point>, VectorError> {
+            durable(
+                "get_vectors",
+                DurableFunctionType::ReadRemote,
+                GetVectorsInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    ids: ids.clone(),
+                    namespace: namespace.clone(),
+                    include_vectors,
+                    include_metadata,
+                },
+                || {
+                    Impl::get_vectors(
+                        connection,
+                        collection,
+                        ids,
+                        namespace,
+                        include_vectors,
+                        include_metadata,
+                    )
+                },
+            )
+        }
+
+        fn delete_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            ids: Vec<Id>,
+            namespace: Option<String>,
+            options: Option<WriteOptions>,
+        ) -> Result<(), VectorError> {
+            durable_unit(
+                "delete_vectors",
+                DeleteVectorsInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    ids: ids.clone(),
+                    namespace: namespace.clone(),
+                    options: options.clone(),
+                },
+                || Impl::delete_vectors(connection, collection, ids, namespace, options),
+            )
+        }
+    }
+
+    impl<Impl: ExtendedGuest> SearchGuest for DurableVector<Impl> {
+        type PointStream = VectorPointStream<DurablePointPager<Impl::PointPager>>;
+
+        fn search_vectors(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            query: SearchQuery,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            durable(
+                "search_vectors",
+                DurableFunctionType::ReadRemote,
+                SearchVectorsInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    query: query.clone(),
+                    namespace: namespace.clone(),
+                },
+                || Impl::search_vectors(connection, collection, query, namespace),
+            )
+        }
+
+        fn query_batch(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            queries: Vec<SearchQuery>,
+            namespace: Option<String>,
+        ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+            durable(
+                "query_batch",
+                DurableFunctionType::ReadRemote,
+                QueryBatchInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    queries: queries.clone(),
+                    namespace: namespace.clone(),
+                },
+                || Impl::query_batch(connection, collection, queries, namespace),
+            )
+        }
+
+        fn search_by_id(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            id: Id,
+            limit: u32,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            durable(
+                "search_by_id",
+                DurableFunctionType::ReadRemote,
+                SearchByIdInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    id: id.clone(),
+                    limit,
+                    filter: filter.clone(),
+                    namespace: namespace.clone(),
+                },
+                || Impl::search_by_id(connection, collection, id, limit, filter, namespace),
+            )
+        }
+
+        fn recommend(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            positive: Vec<Id>,
+            negative: Vec<Id>,
+            limit: u32,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<Vec<SearchResult>, VectorError> {
+            durable(
+                "recommend",
+                DurableFunctionType::ReadRemote,
+                RecommendInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    positive: positive.clone(),
+                    negative: negative.clone(),
+                    limit,
+                    filter: filter.clone(),
+                    namespace: namespace.clone(),
+                },
+                || {
+                    Impl::recommend(
+                        connection, collection, positive, negative, limit, filter, namespace,
+                    )
+                },
+            )
+        }
+
+        fn count(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            filter: Option<FilterExpression>,
+            namespace: Option<String>,
+        ) -> Result<u64, VectorError> {
+            durable(
+                "count",
+                DurableFunctionType::ReadRemote,
+                CountInput {
+                    endpoint: endpoint(&connection),
+                    collection: collection.clone(),
+                    filter: filter.clone(),
+                    namespace: namespace.clone(),
+                },
+                || Impl::count(connection, collection, filter, namespace),
+            )
+        }
+
+        fn scroll_points(
+            connection: ConnectionBorrow<'_>,
+            collection: String,
+            options: ScrollOptions,
+            namespace: Option<String>,
+        ) -> PointStream {
+            PointStream::new(VectorPointStream::new(DurablePointPager {
+                pager: Impl::point_pager(connection, collection, options, namespace),
+            }))
+        }
+    }
+
+    /// Persists every written batch, so batches are not written again during recovery. The
+    /// stream's buffer is rebuilt by the replayed `write` calls.
+    pub struct DurableBatchWriter<W> {
+        writer: W,
+    }
+
+    impl<W: BatchWriter> BatchWriter for DurableBatchWriter<W> {
+        fn batch_size(&self) -> usize {
+            self.writer.batch_size()
+        }
+
+        fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+            durable_unit(
+                "write_batch",
+                WriteBatchInput {
+                    vectors: batch.to_vec(),
+                },
+                || self.writer.write_batch(batch),
+            )
+        }
+    }
+
+    /// Persists every fetched page. In replay mode the pages are read from the oplog, and as
+    /// they include the cursor of the next page, the first page fetched live afterwards is
+    /// the one following them.
+    pub struct DurablePointPager<P> {
+        pager: P,
+    }
+
+    impl<P: PointPager> PointPager for DurablePointPager<P> {
+        fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+            durable(
+                "fetch_page",
+                DurableFunctionType::ReadRemote,
+                FetchPageInput {
+                    cursor: cursor.map(str::to_string),
+                },
+                || {
+                    self.pager.fetch_page(cursor).map(|page| PersistedPage {
+                        points: page.points,
+                        next_cursor: page.next_cursor,
+                    })
+                },
+            )
+            .map(|page| PointPage {
+                points: page.points,
+                next_cursor: page.next_cursor,
+            })
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct CreateCollectionInput {
+        endpoint: Option<String>,
+        name: String,
+        config: CollectionConfig,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct NameInput {
+        endpoint: Option<String>,
+        name: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct RestoreSnapshotInput {
+        endpoint: Option<String>,
+        collection: String,
+        snapshot: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct CreatePayloadIndexInput {
+        endpoint: Option<String>,
+        collection: String,
+        field: String,
+        index_type: PayloadIndexType,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct AliasInput {
+        endpoint: Option<String>,
+        alias: String,
+        collection: String,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct UpsertVectorsInput {
+        endpoint: Option<String>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct WriteBatchInput {
+        vectors: Vec<VectorRecord>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct SetPayloadInput {
+        endpoint: Option<String>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct UpdateVectorsInput {
+        endpoint: Option<String>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct GetVectorsInput {
+        endpoint: Option<String>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct DeleteVectorsInput {
+        endpoint: Option<String>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct SearchVectorsInput {
+        endpoint: Option<String>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct QueryBatchInput {
+        endpoint: Option<String>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct SearchByIdInput {
+        endpoint: Option<String>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct RecommendInput {
+        endpoint: Option<String>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct CountInput {
+        endpoint: Option<String>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct FetchPageInput {
+        cursor: Option<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, IntoValue)]
+    struct EndpointInput {
+        endpoint: Option<String>,
+    }
+
+    /// A `PointPage` as saved in the oplog
+    #[derive(Debug, Clone, FromValueAndType, IntoValue)]
+    struct PersistedPage {
+        points: Vec<Point>,
+        next_cursor: Option<String>,
+    }
+
+    #[derive(Debug, Clone, FromValueAndType, IntoValue)]
+    struct NoOutput;
+}
@@ -0,0 +1,49 @@
+use crate::golem::vector::types::VectorError;
+use golem_ai_common::error::{classify_status, StatusClass};
+use reqwest::StatusCode;
+use std::fmt::{Display, Formatter};
+
+/// Creates a `VectorError` value representing that something is unsupported
+pub fn unsupported(what: impl AsRef<str>) -> VectorError {
+    VectorError::UnsupportedFeature(format!("Unsupported: {}", what.as_ref()))
+}
+
+pub fn from_reqwest_error(details: impl AsRef<str>, err: reqwest::Error) -> VectorError {
+    VectorError::ConnectionError(format!("{}: {err}", details.as_ref()))
+}
+
+/// Maps a failed HTTP response of a provider to a `VectorError`, keeping the response body
+/// in the error message
+pub fn from_http_status(status: StatusCode, body: impl AsRef<str>) -> VectorError {
+    let message = format!("Request failed with {status}: {}", body.as_ref());
+    match classify_status(status) {
+        StatusClass::RateLimited => VectorError::RateLimited(message),
+        StatusClass::Unauthorized => VectorError::Unauthorized(message),
+        StatusClass::NotFound => VectorError::NotFound(message),
+        StatusClass::Conflict => VectorError::AlreadyExists(message),
+        StatusClass::InvalidRequest => VectorError::InvalidParams(message),
+        StatusClass::ProviderError => VectorError::ProviderError(message),
+    }
+}
+
+impl Display for VectorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VectorError::NotFound(msg) => write!(f, "Not found: {msg}"),
+            VectorError::AlreadyExists(msg) => write!(f, "Already exists: {msg}"),
+            VectorError::InvalidParams(msg) => write!(f, "Invalid parameters: {msg}"),
+            VectorError::UnsupportedFeature(msg) => write!(f, "Unsupported feature: {msg}"),
+            VectorError::DimensionMismatch(msg) => write!(f, "Dimension mismatch: {msg}"),
+            VectorError::Unauthorized(msg) => write!(f, "Unauthorized: {msg}"),
+            VectorError::RateLimited(msg) => write!(f, "Rate limited: {msg}"),
+            VectorError::ProviderError(msg) => write!(f, "Provider error: {msg}"),
+            VectorError::ConnectionError(msg) => write!(f, "Connection error: {msg}"),
+        }
+    }
+}
+
+impl From<&VectorError> for VectorError {
+    fn from(error: &VectorError) -> Self {
+        error.clone()
+    }
+}
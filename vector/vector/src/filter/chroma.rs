@@ -0,0 +1,110 @@
+use crate::filter::{invalid_filter, unsupported_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, MetadataValue, RangeCondition, VectorError};
+use crate::metadata::metadata_value_to_json;
+use serde_json::{json, Value};
+
+/// Translates a filter to Chroma's `where` filter format.
+///
+/// Chroma has no negation operator, so `not` nodes are pushed down to the conditions using
+/// De Morgan's laws and the negated comparison operators. `$and` and `$or` need at least two
+/// operands, so single operands are used on their own.
+///
+/// See https://docs.trychroma.com/docs/querying-collections/metadata-filtering
+pub fn to_chroma_where(filter: &Filter) -> Result<Value, VectorError> {
+    translate(filter, false)
+}
+
+fn translate(filter: &Filter, negated: bool) -> Result<Value, VectorError> {
+    match filter {
+        Filter::And(children) => {
+            let operator = if negated { "$or" } else { "$and" };
+            Ok(composite(operator, translate_all(children, negated)?))
+        }
+        Filter::Or(children) => {
+            let operator = if negated { "$and" } else { "$or" };
+            Ok(composite(operator, translate_all(children, negated)?))
+        }
+        Filter::Not(inner) => translate(inner, !negated),
+        Filter::Condition(condition) => translate_condition(condition, negated),
+    }
+}
+
+fn translate_all(filters: &[Filter], negated: bool) -> Result<Vec<Value>, VectorError> {
+    filters
+        .iter()
+        .map(|filter| translate(filter, negated))
+        .collect()
+}
+
+fn composite(operator: &str, mut operands: Vec<Value>) -> Value {
+    if operands.len() == 1 {
+        operands.remove(0)
+    } else {
+        json!({ operator: operands })
+    }
+}
+
+fn translate_condition(condition: &FilterCondition, negated: bool) -> Result<Value, VectorError> {
+    match condition {
+        FilterCondition::Eq((field, value)) => equality(field, value, negated),
+        FilterCondition::Neq((field, value)) => equality(field, value, !negated),
+        FilterCondition::In((field, values)) => {
+            let operator = if negated { "$nin" } else { "$in" };
+            let values = values
+                .iter()
+                .map(|value| scalar_value(field, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(json!({ field: { operator: values } }))
+        }
+        FilterCondition::Range(range) => {
+            let operator = if negated { "$or" } else { "$and" };
+            Ok(composite(operator, range_bounds(range, negated)))
+        }
+        FilterCondition::Exists(_) => Err(unsupported_filter("Chroma", "exists conditions")),
+        FilterCondition::GeoRadius(_) => Err(unsupported_filter("Chroma", "geo conditions")),
+    }
+}
+
+fn equality(field: &str, value: &MetadataValue, negated: bool) -> Result<Value, VectorError> {
+    let operator = if negated { "$ne" } else { "$eq" };
+    Ok(json!({ field: { operator: scalar_value(field, value)? } }))
+}
+
+fn scalar_value(field: &str, value: &MetadataValue) -> Result<Value, VectorError> {
+    match value {
+        MetadataValue::StringVal(_)
+        | MetadataValue::NumberVal(_)
+        | MetadataValue::IntegerVal(_)
+        | MetadataValue::BooleanVal(_) => Ok(metadata_value_to_json(value)),
+        MetadataValue::NullVal => Err(unsupported_filter("Chroma", "null values")),
+        MetadataValue::StringList(_) | MetadataValue::GeoVal(_) => Err(invalid_filter(format!(
+            "Chroma only supports scalar values in conditions on '{field}'"
+        ))),
+    }
+}
+
+/// Chroma allows a single operator per field condition, so every bound is a condition of its
+/// own. The negation of a range is the union of the complements of its bounds.
+fn range_bounds(range: &RangeCondition, negated: bool) -> Vec<Value> {
+    let bounds = if negated {
+        [
+            ("$lte", range.gt),
+            ("$lt", range.gte),
+            ("$gte", range.lt),
+            ("$gt", range.lte),
+        ]
+    } else {
+        [
+            ("$gt", range.gt),
+            ("$gte", range.gte),
+            ("$lt", range.lt),
+            ("$lte", range.lte),
+        ]
+    };
+    bounds
+        .into_iter()
+        .filter_map(|(operator, bound)| {
+            bound.map(|bound| json!({ range.field.clone(): { operator: bound } }))
+        })
+        .collect()
+}
@@ -0,0 +1,113 @@
+use crate::filter::{invalid_filter, unsupported_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, MetadataValue, VectorError};
+
+/// Translates a filter to a Milvus boolean expression.
+///
+/// Metadata is stored in the dynamic field of Milvus collections, whose keys are referred to
+/// by their name. Field names are therefore restricted to identifiers, and string values are
+/// escaped, so user input cannot change the structure of the expression.
+///
+/// See https://milvus.io/docs/boolean.md
+pub fn to_milvus_expr(filter: &Filter) -> Result<String, VectorError> {
+    match filter {
+        Filter::And(children) => join(children, " and "),
+        Filter::Or(children) => join(children, " or "),
+        Filter::Not(inner) => Ok(format!("(not {})", to_milvus_expr(inner)?)),
+        Filter::Condition(condition) => translate_condition(condition),
+    }
+}
+
+/// Quotes a string as a Milvus string literal
+pub fn milvus_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn join(filters: &[Filter], separator: &str) -> Result<String, VectorError> {
+    let parts = filters
+        .iter()
+        .map(to_milvus_expr)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(format!("({})", parts.join(separator)))
+}
+
+fn translate_condition(condition: &FilterCondition) -> Result<String, VectorError> {
+    match condition {
+        FilterCondition::Eq((field, value)) => {
+            let field = identifier(field)?;
+            match value {
+                MetadataValue::NullVal => Ok(format!("({field} is null)")),
+                value => Ok(format!("({field} == {})", literal(field, value)?)),
+            }
+        }
+        FilterCondition::Neq((field, value)) => {
+            let field = identifier(field)?;
+            match value {
+                MetadataValue::NullVal => Ok(format!("({field} is not null)")),
+                value => Ok(format!("({field} != {})", literal(field, value)?)),
+            }
+        }
+        FilterCondition::In((field, values)) => {
+            let field = identifier(field)?;
+            let values = values
+                .iter()
+                .map(|value| literal(field, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("({field} in [{}])", values.join(", ")))
+        }
+        FilterCondition::Range(range) => {
+            let field = identifier(&range.field)?;
+            let parts = [
+                (">", range.gt),
+                (">=", range.gte),
+                ("<", range.lt),
+                ("<=", range.lte),
+            ]
+            .into_iter()
+            .filter_map(|(operator, bound)| {
+                bound.map(|bound| format!("{field} {operator} {bound}"))
+            })
+            .collect::<Vec<_>>();
+            Ok(format!("({})", parts.join(" and ")))
+        }
+        FilterCondition::Exists(field) => Ok(format!("({} is not null)", identifier(field)?)),
+        FilterCondition::GeoRadius(_) => Err(unsupported_filter("Milvus", "geo conditions")),
+    }
+}
+
+fn identifier(field: &str) -> Result<&str, VectorError> {
+    let mut chars = field.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(field)
+    } else {
+        Err(invalid_filter(format!(
+            "Milvus field names can only contain letters, digits and underscores, got '{field}'"
+        )))
+    }
+}
+
+fn literal(field: &str, value: &MetadataValue) -> Result<String, VectorError> {
+    match value {
+        MetadataValue::StringVal(s) => Ok(milvus_string(s)),
+        MetadataValue::NumberVal(n) if n.is_finite() => Ok(n.to_string()),
+        MetadataValue::IntegerVal(i) => Ok(i.to_string()),
+        MetadataValue::BooleanVal(b) => Ok(b.to_string()),
+        _ => Err(invalid_filter(format!(
+            "Milvus only supports string, finite number and boolean values in conditions on '{field}'"
+        ))),
+    }
+}
@@ -0,0 +1,396 @@
+pub mod chroma;
+pub mod milvus;
+pub mod pgvector;
+pub mod pinecone;
+pub mod qdrant;
+pub mod weaviate;
+
+use crate::golem::vector::types::{FilterCondition, FilterExpression, FilterNode, VectorError};
+
+/// A decoded filter expression tree.
+///
+/// The WIT representation of filters is a flat list of nodes, as recursive types are not
+/// supported. Providers convert it to this tree first using `Filter::try_from`, and then
+/// translate the tree to their own filter language.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    Condition(FilterCondition),
+}
+
+impl TryFrom<FilterExpression> for Filter {
+    type Error = VectorError;
+
+    fn try_from(expression: FilterExpression) -> Result<Self, Self::Error> {
+        if expression.nodes.is_empty() {
+            Err(invalid_filter("Filter expression has no nodes"))
+        } else {
+            decode_node(&expression.nodes, 0)
+        }
+    }
+}
+
+impl From<Filter> for FilterExpression {
+    fn from(filter: Filter) -> Self {
+        let mut nodes = Vec::new();
+        encode_node(filter, &mut nodes);
+        FilterExpression { nodes }
+    }
+}
+
+/// Decodes an optional filter expression coming from a WIT function parameter
+pub fn decode_filter(expression: Option<FilterExpression>) -> Result<Option<Filter>, VectorError> {
+    expression.map(Filter::try_from).transpose()
+}
+
+fn decode_node(nodes: &[FilterNode], index: usize) -> Result<Filter, VectorError> {
+    let node = nodes
+        .get(index)
+        .ok_or_else(|| invalid_filter(format!("Filter node {index} does not exist")))?;
+    match node {
+        FilterNode::And(children) => Ok(Filter::And(decode_children(nodes, index, children)?)),
+        FilterNode::Or(children) => Ok(Filter::Or(decode_children(nodes, index, children)?)),
+        FilterNode::Not(child) => Ok(Filter::Not(Box::new(decode_child(nodes, index, *child)?))),
+        FilterNode::Condition(condition) => {
+            validate_condition(condition)?;
+            Ok(Filter::Condition(condition.clone()))
+        }
+    }
+}
+
+fn decode_children(
+    nodes: &[FilterNode],
+    parent: usize,
+    children: &[u32],
+) -> Result<Vec<Filter>, VectorError> {
+    if children.is_empty() {
+        Err(invalid_filter(format!(
+            "Filter node {parent} must have at least one child"
+        )))
+    } else {
+        children
+            .iter()
+            .map(|child| decode_child(nodes, parent, *child))
+            .collect()
+    }
+}
+
+/// Children are required to have a higher index than their parent, which guarantees
+/// that the decoded expression is a finite tree.
+fn decode_child(nodes: &[FilterNode], parent: usize, child: u32) -> Result<Filter, VectorError> {
+    let child = child as usize;
+    if child <= parent {
+        Err(invalid_filter(format!(
+            "Filter node {parent} refers to node {child}, but children must come after their parent"
+        )))
+    } else {
+        decode_node(nodes, child)
+    }
+}
+
+fn validate_condition(condition: &FilterCondition) -> Result<(), VectorError> {
+    match condition {
+        FilterCondition::Range(range)
+            if range.gt.is_none()
+                && range.gte.is_none()
+                && range.lt.is_none()
+                && range.lte.is_none() =>
+        {
+            Err(invalid_filter(format!(
+                "Range condition on '{}' has no bounds",
+                range.field
+            )))
+        }
+        FilterCondition::GeoRadius(geo) if geo.radius_meters < 0.0 => Err(invalid_filter(format!(
+            "Geo radius condition on '{}' has a negative radius",
+            geo.field
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn encode_node(filter: Filter, nodes: &mut Vec<FilterNode>) -> u32 {
+    let index = nodes.len();
+    match filter {
+        Filter::Condition(condition) => nodes.push(FilterNode::Condition(condition)),
+        Filter::Not(inner) => {
+            nodes.push(FilterNode::Not(0));
+            let child = encode_node(*inner, nodes);
+            nodes[index] = FilterNode::Not(child);
+        }
+        Filter::And(children) => {
+            nodes.push(FilterNode::And(Vec::new()));
+            let children = children
+                .into_iter()
+                .map(|child| encode_node(child, nodes))
+                .collect();
+            nodes[index] = FilterNode::And(children);
+        }
+        Filter::Or(children) => {
+            nodes.push(FilterNode::Or(Vec::new()));
+            let children = children
+                .into_iter()
+                .map(|child| encode_node(child, nodes))
+                .collect();
+            nodes[index] = FilterNode::Or(children);
+        }
+    }
+    index as u32
+}
+
+pub(crate) fn invalid_filter(message: impl AsRef<str>) -> VectorError {
+    VectorError::InvalidParams(format!("Invalid filter: {}", message.as_ref()))
+}
+
+pub(crate) fn unsupported_filter(provider: &str, what: impl AsRef<str>) -> VectorError {
+    VectorError::UnsupportedFeature(format!(
+        "{provider} does not support {} in filters",
+        what.as_ref()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::Filter;
+    use crate::golem::vector::types::{
+        FilterCondition, FilterExpression, FilterNode, GeoPoint, GeoRadiusCondition, MetadataValue,
+        RangeCondition, VectorError,
+    };
+    use serde_json::json;
+
+    fn eq(field: &str, value: MetadataValue) -> Filter {
+        Filter::Condition(FilterCondition::Eq((field.to_string(), value)))
+    }
+
+    fn sample_filter() -> Filter {
+        Filter::And(vec![
+            eq("genre", MetadataValue::StringVal("drama".to_string())),
+            Filter::Or(vec![
+                Filter::Condition(FilterCondition::Range(RangeCondition {
+                    field: "year".to_string(),
+                    gt: None,
+                    gte: Some(2000.0),
+                    lt: Some(2010.0),
+                    lte: None,
+                })),
+                Filter::Not(Box::new(Filter::Condition(FilterCondition::Exists(
+                    "archived".to_string(),
+                )))),
+            ]),
+        ])
+    }
+
+    #[test]
+    fn expression_roundtrip() {
+        let filter = sample_filter();
+        let expression = FilterExpression::from(filter.clone());
+        assert_eq!(expression.nodes.len(), 6);
+        assert_eq!(Filter::try_from(expression).unwrap(), filter);
+    }
+
+    #[test]
+    fn rejects_backward_references() {
+        let expression = FilterExpression {
+            nodes: vec![
+                FilterNode::Not(1),
+                FilterNode::And(vec![0]),
+                FilterNode::Condition(FilterCondition::Exists("x".to_string())),
+            ],
+        };
+        assert!(matches!(
+            Filter::try_from(expression),
+            Err(VectorError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_nodes_and_empty_composites() {
+        let missing = FilterExpression {
+            nodes: vec![FilterNode::Not(3)],
+        };
+        assert!(Filter::try_from(missing).is_err());
+
+        let empty_and = FilterExpression {
+            nodes: vec![FilterNode::And(vec![])],
+        };
+        assert!(Filter::try_from(empty_and).is_err());
+
+        assert!(Filter::try_from(FilterExpression { nodes: vec![] }).is_err());
+    }
+
+    #[test]
+    fn qdrant_translation() {
+        let result = super::qdrant::to_qdrant_filter(&sample_filter()).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "must": [
+                    { "key": "genre", "match": { "value": "drama" } },
+                    { "should": [
+                        { "key": "year", "range": { "gte": 2000.0, "lt": 2010.0 } },
+                        { "must_not": [ { "must_not": [ { "is_empty": { "key": "archived" } } ] } ] }
+                    ] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn pinecone_translation_pushes_down_negation() {
+        let result = super::pinecone::to_pinecone_filter(&sample_filter()).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "$and": [
+                    { "genre": { "$eq": "drama" } },
+                    { "$or": [
+                        { "year": { "$gte": 2000.0, "$lt": 2010.0 } },
+                        { "archived": { "$exists": false } }
+                    ] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn neq_conditions() {
+        let filter = Filter::Condition(FilterCondition::Neq((
+            "genre".to_string(),
+            MetadataValue::StringVal("drama".to_string()),
+        )));
+        assert_eq!(
+            super::qdrant::to_qdrant_filter(&filter).unwrap(),
+            json!({
+                "must": [ { "must_not": [ { "key": "genre", "match": { "value": "drama" } } ] } ]
+            })
+        );
+        assert_eq!(
+            super::pinecone::to_pinecone_filter(&Filter::Not(Box::new(filter.clone()))).unwrap(),
+            json!({ "genre": { "$eq": "drama" } })
+        );
+        assert_eq!(
+            super::milvus::to_milvus_expr(&filter).unwrap(),
+            "(genre != \"drama\")"
+        );
+        assert_eq!(
+            super::weaviate::to_weaviate_where(&filter).unwrap(),
+            json!({ "path": ["genre"], "operator": "NotEqual", "valueText": "drama" })
+        );
+    }
+
+    #[test]
+    fn pinecone_rejects_geo() {
+        let filter = Filter::Condition(FilterCondition::GeoRadius(GeoRadiusCondition {
+            field: "location".to_string(),
+            center: GeoPoint {
+                latitude: 47.5,
+                longitude: 19.0,
+            },
+            radius_meters: 1000.0,
+        }));
+        assert!(matches!(
+            super::pinecone::to_pinecone_filter(&filter),
+            Err(VectorError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn weaviate_translation() {
+        let result = super::weaviate::to_weaviate_where(&sample_filter()).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "operator": "And",
+                "operands": [
+                    { "path": ["genre"], "operator": "Equal", "valueText": "drama" },
+                    { "operator": "Or", "operands": [
+                        { "operator": "And", "operands": [
+                            { "path": ["year"], "operator": "GreaterThanEqual", "valueNumber": 2000.0 },
+                            { "path": ["year"], "operator": "LessThan", "valueNumber": 2010.0 }
+                        ] },
+                        { "path": ["archived"], "operator": "IsNull", "valueBoolean": true }
+                    ] }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn milvus_translation() {
+        let result = super::milvus::to_milvus_expr(&sample_filter()).unwrap();
+        assert_eq!(
+            result,
+            "((genre == \"drama\") and ((year >= 2000 and year < 2010) or (not (archived is not null))))"
+        );
+    }
+
+    #[test]
+    fn milvus_escapes_strings_and_rejects_invalid_fields() {
+        let quoted = eq(
+            "name",
+            MetadataValue::StringVal("a\" or 1 == 1 or \"".to_string()),
+        );
+        assert_eq!(
+            super::milvus::to_milvus_expr(&quoted).unwrap(),
+            "(name == \"a\\\" or 1 == 1 or \\\"\")"
+        );
+
+        let invalid = eq("a b", MetadataValue::IntegerVal(1));
+        assert!(matches!(
+            super::milvus::to_milvus_expr(&invalid),
+            Err(VectorError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn chroma_translation_unwraps_single_operands() {
+        let filter = Filter::And(vec![
+            eq("genre", MetadataValue::StringVal("drama".to_string())),
+            Filter::Not(Box::new(Filter::Condition(FilterCondition::Range(
+                RangeCondition {
+                    field: "year".to_string(),
+                    gt: None,
+                    gte: Some(2000.0),
+                    lt: None,
+                    lte: None,
+                },
+            )))),
+        ]);
+        assert_eq!(
+            super::chroma::to_chroma_where(&filter).unwrap(),
+            json!({
+                "$and": [
+                    { "genre": { "$eq": "drama" } },
+                    { "year": { "$lt": 2000.0 } }
+                ]
+            })
+        );
+        assert!(matches!(
+            super::chroma::to_chroma_where(&sample_filter()),
+            Err(VectorError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn pgvector_translation_uses_parameters() {
+        let result = super::pgvector::to_pgvector_where(&sample_filter(), "metadata", 3).unwrap();
+        assert_eq!(
+            result.clause,
+            "((metadata -> $3) = $4::jsonb AND (((metadata ->> $5)::double precision >= $6::double precision AND (metadata ->> $7)::double precision < $8::double precision) OR (NOT ((metadata -> $9) IS NOT NULL))))"
+        );
+        assert_eq!(
+            result.params,
+            vec![
+                "genre",
+                "\"drama\"",
+                "year",
+                "2000",
+                "year",
+                "2010",
+                "archived"
+            ]
+        );
+    }
+}
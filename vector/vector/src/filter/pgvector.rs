@@ -0,0 +1,118 @@
+use crate::filter::{unsupported_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, VectorError};
+use crate::metadata::metadata_value_to_json;
+
+/// A parameterized SQL `WHERE` clause.
+///
+/// Every field name and value is passed as a query parameter, the clause refers to them
+/// as `$n` placeholders starting from the `first_param` given to `to_pgvector_where`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlFilter {
+    pub clause: String,
+    pub params: Vec<String>,
+}
+
+/// Translates a filter to a SQL condition over a `jsonb` metadata column
+pub fn to_pgvector_where(
+    filter: &Filter,
+    column: &str,
+    first_param: usize,
+) -> Result<SqlFilter, VectorError> {
+    let mut builder = SqlBuilder {
+        column,
+        next_param: first_param,
+        params: Vec::new(),
+    };
+    let clause = builder.translate(filter)?;
+    Ok(SqlFilter {
+        clause,
+        params: builder.params,
+    })
+}
+
+struct SqlBuilder<'a> {
+    column: &'a str,
+    next_param: usize,
+    params: Vec<String>,
+}
+
+impl SqlBuilder<'_> {
+    fn param(&mut self, value: String) -> String {
+        let placeholder = format!("${}", self.next_param);
+        self.next_param += 1;
+        self.params.push(value);
+        placeholder
+    }
+
+    fn translate(&mut self, filter: &Filter) -> Result<String, VectorError> {
+        match filter {
+            Filter::And(children) => self.join(children, " AND "),
+            Filter::Or(children) => self.join(children, " OR "),
+            Filter::Not(inner) => Ok(format!("(NOT ({}))", self.translate(inner)?)),
+            Filter::Condition(condition) => self.condition(condition),
+        }
+    }
+
+    fn join(&mut self, filters: &[Filter], separator: &str) -> Result<String, VectorError> {
+        let parts = filters
+            .iter()
+            .map(|filter| self.translate(filter))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(format!("({})", parts.join(separator)))
+    }
+
+    fn condition(&mut self, condition: &FilterCondition) -> Result<String, VectorError> {
+        let column = self.column;
+        match condition {
+            FilterCondition::Eq((field, value)) => {
+                let field = self.param(field.clone());
+                let value = self.param(metadata_value_to_json(value).to_string());
+                Ok(format!("({column} -> {field}) = {value}::jsonb"))
+            }
+            FilterCondition::Neq((field, value)) => {
+                let field = self.param(field.clone());
+                let value = self.param(metadata_value_to_json(value).to_string());
+                Ok(format!(
+                    "({column} -> {field}) IS DISTINCT FROM {value}::jsonb"
+                ))
+            }
+            FilterCondition::In((field, values)) => {
+                if values.is_empty() {
+                    return Ok("FALSE".to_string());
+                }
+                let field = self.param(field.clone());
+                let values = values
+                    .iter()
+                    .map(|value| {
+                        let value = self.param(metadata_value_to_json(value).to_string());
+                        format!("{value}::jsonb")
+                    })
+                    .collect::<Vec<_>>();
+                Ok(format!("({column} -> {field}) IN ({})", values.join(", ")))
+            }
+            FilterCondition::Range(range) => {
+                let mut parts = Vec::new();
+                for (operator, bound) in [
+                    (">", range.gt),
+                    (">=", range.gte),
+                    ("<", range.lt),
+                    ("<=", range.lte),
+                ] {
+                    if let Some(bound) = bound {
+                        let field = self.param(range.field.clone());
+                        let bound = self.param(bound.to_string());
+                        parts.push(format!(
+                            "({column} ->> {field})::double precision {operator} {bound}::double precision"
+                        ));
+                    }
+                }
+                Ok(format!("({})", parts.join(" AND ")))
+            }
+            FilterCondition::Exists(field) => {
+                let field = self.param(field.clone());
+                Ok(format!("({column} -> {field}) IS NOT NULL"))
+            }
+            FilterCondition::GeoRadius(_) => Err(unsupported_filter("pgvector", "geo conditions")),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use crate::filter::{invalid_filter, unsupported_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, MetadataValue, RangeCondition, VectorError};
+use crate::metadata::metadata_value_to_json;
+use serde_json::{json, Map, Value};
+
+/// Translates a filter to Pinecone's metadata filter format.
+///
+/// Pinecone has no negation operator, so `not` nodes are pushed down to the conditions
+/// using De Morgan's laws and the negated comparison operators.
+///
+/// See https://docs.pinecone.io/guides/data/filter-with-metadata
+pub fn to_pinecone_filter(filter: &Filter) -> Result<Value, VectorError> {
+    translate(filter, false)
+}
+
+fn translate(filter: &Filter, negated: bool) -> Result<Value, VectorError> {
+    match filter {
+        Filter::And(children) => {
+            let operator = if negated { "$or" } else { "$and" };
+            Ok(json!({ operator: translate_all(children, negated)? }))
+        }
+        Filter::Or(children) => {
+            let operator = if negated { "$and" } else { "$or" };
+            Ok(json!({ operator: translate_all(children, negated)? }))
+        }
+        Filter::Not(inner) => translate(inner, !negated),
+        Filter::Condition(condition) => translate_condition(condition, negated),
+    }
+}
+
+fn translate_all(filters: &[Filter], negated: bool) -> Result<Vec<Value>, VectorError> {
+    filters
+        .iter()
+        .map(|filter| translate(filter, negated))
+        .collect()
+}
+
+fn translate_condition(condition: &FilterCondition, negated: bool) -> Result<Value, VectorError> {
+    match condition {
+        FilterCondition::Eq((field, value)) => equality(field, value, negated),
+        FilterCondition::Neq((field, value)) => equality(field, value, !negated),
+        FilterCondition::In((field, values)) => {
+            let operator = if negated { "$nin" } else { "$in" };
+            let values = values
+                .iter()
+                .map(|value| scalar_value(field, value))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(json!({ field: { operator: values } }))
+        }
+        FilterCondition::Range(range) => {
+            if negated {
+                Ok(json!({ "$or": negated_range(range) }))
+            } else {
+                Ok(json!({ range.field.clone(): range_bounds(range) }))
+            }
+        }
+        FilterCondition::Exists(field) => Ok(json!({ field: { "$exists": !negated } })),
+        FilterCondition::GeoRadius(_) => Err(unsupported_filter("Pinecone", "geo conditions")),
+    }
+}
+
+fn equality(field: &str, value: &MetadataValue, negated: bool) -> Result<Value, VectorError> {
+    let operator = if negated { "$ne" } else { "$eq" };
+    Ok(json!({ field: { operator: scalar_value(field, value)? } }))
+}
+
+fn scalar_value(field: &str, value: &MetadataValue) -> Result<Value, VectorError> {
+    match value {
+        MetadataValue::StringVal(_)
+        | MetadataValue::NumberVal(_)
+        | MetadataValue::IntegerVal(_)
+        | MetadataValue::BooleanVal(_) => Ok(metadata_value_to_json(value)),
+        MetadataValue::NullVal => Err(unsupported_filter("Pinecone", "null values")),
+        MetadataValue::StringList(_) | MetadataValue::GeoVal(_) => Err(invalid_filter(format!(
+            "Pinecone only supports scalar values in conditions on '{field}'"
+        ))),
+    }
+}
+
+fn range_bounds(range: &RangeCondition) -> Value {
+    let mut bounds = Map::new();
+    for (operator, bound) in [
+        ("$gt", range.gt),
+        ("$gte", range.gte),
+        ("$lt", range.lt),
+        ("$lte", range.lte),
+    ] {
+        if let Some(bound) = bound {
+            bounds.insert(operator.to_string(), json!(bound));
+        }
+    }
+    Value::Object(bounds)
+}
+
+/// The negation of a range is the union of the complements of its bounds
+fn negated_range(range: &RangeCondition) -> Vec<Value> {
+    [
+        ("$lte", range.gt),
+        ("$lt", range.gte),
+        ("$gte", range.lt),
+        ("$gt", range.lte),
+    ]
+    .into_iter()
+    .filter_map(|(operator, bound)| {
+        bound.map(|bound| json!({ range.field.clone(): { operator: bound } }))
+    })
+    .collect()
+}
@@ -0,0 +1,95 @@
+use crate::filter::{invalid_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, MetadataValue, RangeCondition, VectorError};
+use crate::metadata::metadata_value_to_json;
+use serde_json::{json, Map, Value};
+
+/// Translates a filter to Qdrant's filter JSON format.
+///
+/// See https://qdrant.tech/documentation/concepts/filtering/
+pub fn to_qdrant_filter(filter: &Filter) -> Result<Value, VectorError> {
+    match filter {
+        Filter::And(children) => Ok(json!({ "must": to_conditions(children)? })),
+        Filter::Or(children) => Ok(json!({ "should": to_conditions(children)? })),
+        Filter::Not(inner) => Ok(json!({ "must_not": [to_condition(inner)?] })),
+        Filter::Condition(condition) => Ok(json!({ "must": [field_condition(condition)?] })),
+    }
+}
+
+fn to_conditions(filters: &[Filter]) -> Result<Vec<Value>, VectorError> {
+    filters.iter().map(to_condition).collect()
+}
+
+/// Qdrant accepts nested filter objects in place of conditions
+fn to_condition(filter: &Filter) -> Result<Value, VectorError> {
+    match filter {
+        Filter::Condition(condition) => field_condition(condition),
+        other => to_qdrant_filter(other),
+    }
+}
+
+fn field_condition(condition: &FilterCondition) -> Result<Value, VectorError> {
+    match condition {
+        FilterCondition::Eq((field, value)) => eq_condition(field, value),
+        FilterCondition::Neq((field, value)) => {
+            Ok(json!({ "must_not": [eq_condition(field, value)?] }))
+        }
+        FilterCondition::In((field, values)) => {
+            let values = values
+                .iter()
+                .map(|value| match value {
+                    MetadataValue::StringVal(_) | MetadataValue::IntegerVal(_) => {
+                        Ok(metadata_value_to_json(value))
+                    }
+                    _ => Err(invalid_filter(format!(
+                        "Qdrant only supports string and integer values in 'in' conditions on '{field}'"
+                    ))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(json!({ "key": field, "match": { "any": values } }))
+        }
+        FilterCondition::Range(range) => {
+            Ok(json!({ "key": range.field, "range": range_bounds(range) }))
+        }
+        FilterCondition::Exists(field) => {
+            Ok(json!({ "must_not": [ { "is_empty": { "key": field } } ] }))
+        }
+        FilterCondition::GeoRadius(geo) => Ok(json!({
+            "key": geo.field,
+            "geo_radius": {
+                "center": { "lat": geo.center.latitude, "lon": geo.center.longitude },
+                "radius": geo.radius_meters,
+            }
+        })),
+    }
+}
+
+fn eq_condition(field: &str, value: &MetadataValue) -> Result<Value, VectorError> {
+    match value {
+        MetadataValue::StringVal(_)
+        | MetadataValue::IntegerVal(_)
+        | MetadataValue::BooleanVal(_) => {
+            Ok(json!({ "key": field, "match": { "value": metadata_value_to_json(value) } }))
+        }
+        // Qdrant can only match keywords, integers and booleans exactly, floats are matched by a closed range
+        MetadataValue::NumberVal(n) => Ok(json!({ "key": field, "range": { "gte": n, "lte": n } })),
+        MetadataValue::NullVal => Ok(json!({ "is_null": { "key": field } })),
+        MetadataValue::StringList(_) | MetadataValue::GeoVal(_) => Err(invalid_filter(format!(
+            "Qdrant does not support equality conditions with list or geo values on '{field}'"
+        ))),
+    }
+}
+
+fn range_bounds(range: &RangeCondition) -> Value {
+    let mut bounds = Map::new();
+    for (key, bound) in [
+        ("gt", range.gt),
+        ("gte", range.gte),
+        ("lt", range.lt),
+        ("lte", range.lte),
+    ] {
+        if let Some(bound) = bound {
+            bounds.insert(key.to_string(), json!(bound));
+        }
+    }
+    Value::Object(bounds)
+}
@@ -0,0 +1,125 @@
+use crate::filter::{invalid_filter, unsupported_filter, Filter};
+use crate::golem::vector::types::{FilterCondition, MetadataValue, RangeCondition, VectorError};
+use serde_json::{json, Value};
+
+/// Translates a filter to Weaviate's `where` filter format.
+///
+/// Weaviate has no general negation operator, so `not` nodes are pushed down to the conditions
+/// using De Morgan's laws and the negated comparison operators.
+///
+/// See https://weaviate.io/developers/weaviate/api/graphql/filters
+pub fn to_weaviate_where(filter: &Filter) -> Result<Value, VectorError> {
+    translate(filter, false)
+}
+
+fn translate(filter: &Filter, negated: bool) -> Result<Value, VectorError> {
+    match filter {
+        Filter::And(children) => {
+            let operator = if negated { "Or" } else { "And" };
+            Ok(composite(operator, translate_all(children, negated)?))
+        }
+        Filter::Or(children) => {
+            let operator = if negated { "And" } else { "Or" };
+            Ok(composite(operator, translate_all(children, negated)?))
+        }
+        Filter::Not(inner) => translate(inner, !negated),
+        Filter::Condition(condition) => translate_condition(condition, negated),
+    }
+}
+
+fn translate_all(filters: &[Filter], negated: bool) -> Result<Vec<Value>, VectorError> {
+    filters
+        .iter()
+        .map(|filter| translate(filter, negated))
+        .collect()
+}
+
+fn composite(operator: &str, operands: Vec<Value>) -> Value {
+    json!({ "operator": operator, "operands": operands })
+}
+
+fn translate_condition(condition: &FilterCondition, negated: bool) -> Result<Value, VectorError> {
+    match condition {
+        FilterCondition::Eq((field, value)) => equality(field, value, negated),
+        FilterCondition::Neq((field, value)) => equality(field, value, !negated),
+        FilterCondition::In((field, values)) => {
+            let operands = values
+                .iter()
+                .map(|value| equality(field, value, negated))
+                .collect::<Result<Vec<_>, _>>()?;
+            let operator = if negated { "And" } else { "Or" };
+            Ok(composite(operator, operands))
+        }
+        FilterCondition::Range(range) => {
+            let operator = if negated { "Or" } else { "And" };
+            Ok(composite(operator, range_operands(range, negated)))
+        }
+        FilterCondition::Exists(field) => Ok(json!({
+            "path": [field],
+            "operator": "IsNull",
+            "valueBoolean": negated,
+        })),
+        FilterCondition::GeoRadius(geo) => {
+            if negated {
+                Err(unsupported_filter("Weaviate", "negated geo conditions"))
+            } else {
+                Ok(json!({
+                    "path": [geo.field],
+                    "operator": "WithinGeoRange",
+                    "valueGeoRange": {
+                        "geoCoordinates": {
+                            "latitude": geo.center.latitude,
+                            "longitude": geo.center.longitude,
+                        },
+                        "distance": { "max": geo.radius_meters },
+                    }
+                }))
+            }
+        }
+    }
+}
+
+fn equality(field: &str, value: &MetadataValue, negated: bool) -> Result<Value, VectorError> {
+    let operator = if negated { "NotEqual" } else { "Equal" };
+    let (value_key, value) = match value {
+        MetadataValue::StringVal(s) => ("valueText", json!(s)),
+        MetadataValue::NumberVal(n) => ("valueNumber", json!(n)),
+        MetadataValue::IntegerVal(i) => ("valueInt", json!(i)),
+        MetadataValue::BooleanVal(b) => ("valueBoolean", json!(b)),
+        MetadataValue::NullVal => {
+            return Ok(json!({ "path": [field], "operator": "IsNull", "valueBoolean": !negated }))
+        }
+        MetadataValue::StringList(_) | MetadataValue::GeoVal(_) => {
+            return Err(invalid_filter(format!(
+                "Weaviate only supports scalar values in equality conditions on '{field}'"
+            )))
+        }
+    };
+    Ok(json!({ "path": [field], "operator": operator, value_key: value }))
+}
+
+fn range_operands(range: &RangeCondition, negated: bool) -> Vec<Value> {
+    let bounds = if negated {
+        [
+            ("LessThanEqual", range.gt),
+            ("LessThan", range.gte),
+            ("GreaterThanEqual", range.lt),
+            ("GreaterThan", range.lte),
+        ]
+    } else {
+        [
+            ("GreaterThan", range.gt),
+            ("GreaterThanEqual", range.gte),
+            ("LessThan", range.lt),
+            ("LessThanEqual", range.lte),
+        ]
+    };
+    bounds
+        .into_iter()
+        .filter_map(|(operator, bound)| {
+            bound.map(|bound| {
+                json!({ "path": [range.field], "operator": operator, "valueNumber": bound })
+            })
+        })
+        .collect()
+}
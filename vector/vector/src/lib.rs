@@ -0,0 +1,40 @@
+pub mod alias;
+pub mod batch;
+pub mod config;
+pub mod connection;
+pub mod durability;
+pub mod error;
+pub mod filter;
+pub mod metadata;
+pub mod named;
+pub mod namespace;
+pub mod payload_index;
+pub mod point_stream;
+pub mod quantization;
+pub mod recommend;
+pub mod search;
+pub mod sparse;
+pub mod task;
+pub mod upsert_stream;
+pub mod write_options;
+
+wit_bindgen::generate!({
+    path: "../wit",
+    world: "vector-library",
+    generate_all,
+    generate_unused_types: true,
+    additional_derives: [PartialEq, golem_rust::FromValueAndType, golem_rust::IntoValue],
+    pub_export_macro: true,
+});
+
+pub use crate::exports::golem;
+pub use __export_vector_library_impl as export_vector;
+pub use golem_ai_common::logging::LoggingState;
+pub use golem_ai_common::retry;
+use std::cell::RefCell;
+
+thread_local! {
+    /// This holds the state of our application.
+    pub static LOGGING_STATE: RefCell<LoggingState> =
+        const { RefCell::new(LoggingState::new("GOLEM_VECTOR_LOG")) };
+}
@@ -0,0 +1,120 @@
+use crate::golem::vector::types::{GeoPoint, Metadata, MetadataValue};
+use serde_json::{json, Map, Number, Value};
+
+/// Converts a single metadata value to its JSON representation.
+///
+/// Geo points are encoded as `{ "lat": ..., "lon": ... }` objects which is the format most vector
+/// databases accept for geo fields.
+pub fn metadata_value_to_json(value: &MetadataValue) -> Value {
+    match value {
+        MetadataValue::StringVal(s) => Value::String(s.clone()),
+        MetadataValue::NumberVal(n) => Number::from_f64(*n).map_or(Value::Null, Value::Number),
+        MetadataValue::IntegerVal(i) => Value::Number(Number::from(*i)),
+        MetadataValue::BooleanVal(b) => Value::Bool(*b),
+        MetadataValue::StringList(list) => {
+            Value::Array(list.iter().cloned().map(Value::String).collect())
+        }
+        MetadataValue::GeoVal(point) => json!({ "lat": point.latitude, "lon": point.longitude }),
+        MetadataValue::NullVal => Value::Null,
+    }
+}
+
+/// Converts a list of metadata fields to a JSON object
+pub fn metadata_to_json(metadata: &Metadata) -> Value {
+    let mut map = Map::new();
+    for (key, value) in metadata {
+        map.insert(key.clone(), metadata_value_to_json(value));
+    }
+    Value::Object(map)
+}
+
+/// Converts a JSON value returned by a provider back to a metadata value.
+///
+/// Values which cannot be represented exactly (nested objects, mixed arrays) are kept as their
+/// serialized JSON string.
+pub fn json_to_metadata_value(value: &Value) -> MetadataValue {
+    match value {
+        Value::Null => MetadataValue::NullVal,
+        Value::Bool(b) => MetadataValue::BooleanVal(*b),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                MetadataValue::IntegerVal(i)
+            } else {
+                MetadataValue::NumberVal(n.as_f64().unwrap_or_default())
+            }
+        }
+        Value::String(s) => MetadataValue::StringVal(s.clone()),
+        Value::Array(items) if items.iter().all(|item| item.is_string()) => {
+            MetadataValue::StringList(
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str().map(|s| s.to_string()))
+                    .collect(),
+            )
+        }
+        Value::Object(obj) => match (
+            obj.get("lat").and_then(|v| v.as_f64()),
+            obj.get("lon").and_then(|v| v.as_f64()),
+        ) {
+            (Some(latitude), Some(longitude)) if obj.len() == 2 => {
+                MetadataValue::GeoVal(GeoPoint {
+                    latitude,
+                    longitude,
+                })
+            }
+            _ => MetadataValue::StringVal(value.to_string()),
+        },
+        Value::Array(_) => MetadataValue::StringVal(value.to_string()),
+    }
+}
+
+/// Converts a JSON object returned by a provider to a list of metadata fields.
+/// Anything other than an object results in empty metadata.
+pub fn json_to_metadata(value: &Value) -> Metadata {
+    match value {
+        Value::Object(obj) => obj
+            .iter()
+            .map(|(key, value)| (key.clone(), json_to_metadata_value(value)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Sets the fields of `updates` in `existing` metadata, replacing fields with the same key.
+/// Used to emulate partial metadata updates on providers that can only replace whole points.
+pub fn merge_metadata(existing: Option<Metadata>, updates: Metadata) -> Metadata {
+    let mut result = existing.unwrap_or_default();
+    for (key, value) in updates {
+        match result.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing_value)) => *existing_value = value,
+            None => result.push((key, value)),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::MetadataValue;
+    use crate::metadata::merge_metadata;
+
+    #[test]
+    fn merge_replaces_and_appends_fields() {
+        let existing = vec![
+            ("a".to_string(), MetadataValue::IntegerVal(1)),
+            ("b".to_string(), MetadataValue::BooleanVal(true)),
+        ];
+        let updates = vec![
+            ("b".to_string(), MetadataValue::BooleanVal(false)),
+            ("c".to_string(), MetadataValue::NullVal),
+        ];
+        assert_eq!(
+            merge_metadata(Some(existing), updates),
+            vec![
+                ("a".to_string(), MetadataValue::IntegerVal(1)),
+                ("b".to_string(), MetadataValue::BooleanVal(false)),
+                ("c".to_string(), MetadataValue::NullVal),
+            ]
+        );
+    }
+}
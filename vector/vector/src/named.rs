@@ -0,0 +1,100 @@
+//! Emulation of named vectors for providers which can only store a single vector per point.
+//!
+//! Each named vector is stored in its own collection called `<collection>__<name>`, with the
+//! point's id, sparse vector and metadata duplicated into every one of them.
+
+use crate::golem::vector::types::{VectorData, VectorError, VectorRecord};
+use std::collections::BTreeMap;
+
+/// Separator between the collection name and the vector name of an emulated named vector
+pub const NAMED_VECTOR_SEPARATOR: &str = "__";
+
+/// Returns the collection that holds the given named vector of `collection`
+pub fn vector_collection_name(collection: &str, vector_name: Option<&str>) -> String {
+    match vector_name {
+        Some(name) => format!("{collection}{NAMED_VECTOR_SEPARATOR}{name}"),
+        None => collection.to_string(),
+    }
+}
+
+/// Splits records into per-collection lists of records having a single dense vector each
+pub fn split_by_vector_name(
+    collection: &str,
+    records: &[VectorRecord],
+) -> Result<BTreeMap<String, Vec<VectorRecord>>, VectorError> {
+    let mut result: BTreeMap<String, Vec<VectorRecord>> = BTreeMap::new();
+    for record in records {
+        match &record.vector {
+            VectorData::Dense(_) => result
+                .entry(collection.to_string())
+                .or_default()
+                .push(record.clone()),
+            VectorData::Named(vectors) => {
+                if vectors.is_empty() {
+                    return Err(VectorError::InvalidParams(format!(
+                        "Point '{}' has no vectors",
+                        record.id
+                    )));
+                }
+                for (name, vector) in vectors {
+                    result
+                        .entry(vector_collection_name(collection, Some(name)))
+                        .or_default()
+                        .push(VectorRecord {
+                            id: record.id.clone(),
+                            vector: VectorData::Dense(vector.clone()),
+                            sparse: record.sparse.clone(),
+                            metadata: record.metadata.clone(),
+                        });
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the single dense vector of a record, failing for named vectors
+pub fn expect_dense<'a>(
+    record: &'a VectorRecord,
+    provider: &str,
+) -> Result<&'a [f32], VectorError> {
+    match &record.vector {
+        VectorData::Dense(vector) => Ok(vector),
+        VectorData::Named(_) => Err(VectorError::UnsupportedFeature(format!(
+            "{provider} does not support named vectors"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::{VectorData, VectorRecord};
+    use crate::named::split_by_vector_name;
+
+    #[test]
+    fn named_vectors_are_split_into_collections() {
+        let records = vec![
+            VectorRecord {
+                id: "1".to_string(),
+                vector: VectorData::Named(vec![
+                    ("title".to_string(), vec![1.0]),
+                    ("body".to_string(), vec![2.0]),
+                ]),
+                sparse: None,
+                metadata: None,
+            },
+            VectorRecord {
+                id: "2".to_string(),
+                vector: VectorData::Dense(vec![3.0]),
+                sparse: None,
+                metadata: None,
+            },
+        ];
+        let split = split_by_vector_name("docs", &records).unwrap();
+        assert_eq!(
+            split.keys().collect::<Vec<_>>(),
+            vec!["docs", "docs__body", "docs__title"]
+        );
+        assert_eq!(split["docs__title"][0].vector, VectorData::Dense(vec![1.0]));
+    }
+}
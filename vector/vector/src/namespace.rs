@@ -0,0 +1,88 @@
+//! Emulation of namespaces for providers without native multi-tenancy support.
+//!
+//! The namespace of a point is stored in a reserved metadata field, which is added to every
+//! written point and to the filter of every query.
+
+use crate::filter::Filter;
+use crate::golem::vector::types::{FilterCondition, Metadata, MetadataValue};
+
+/// Reserved metadata field holding the namespace of a point
+pub const NAMESPACE_FIELD: &str = "_golem_namespace";
+
+/// Adds the namespace field to a point's metadata
+pub fn add_namespace(metadata: Option<Metadata>, namespace: Option<&str>) -> Option<Metadata> {
+    match namespace {
+        Some(namespace) => {
+            let mut metadata = strip_namespace(metadata).unwrap_or_default();
+            metadata.push((
+                NAMESPACE_FIELD.to_string(),
+                MetadataValue::StringVal(namespace.to_string()),
+            ));
+            Some(metadata)
+        }
+        None => metadata,
+    }
+}
+
+/// Removes the namespace field from metadata returned by the provider
+pub fn strip_namespace(metadata: Option<Metadata>) -> Option<Metadata> {
+    metadata.map(|metadata| {
+        metadata
+            .into_iter()
+            .filter(|(key, _)| key != NAMESPACE_FIELD)
+            .collect()
+    })
+}
+
+/// Restricts a filter to the points of the given namespace
+pub fn namespace_filter(filter: Option<Filter>, namespace: Option<&str>) -> Option<Filter> {
+    match namespace {
+        Some(namespace) => {
+            let condition = Filter::Condition(FilterCondition::Eq((
+                NAMESPACE_FIELD.to_string(),
+                MetadataValue::StringVal(namespace.to_string()),
+            )));
+            match filter {
+                Some(Filter::And(mut children)) => {
+                    children.insert(0, condition);
+                    Some(Filter::And(children))
+                }
+                Some(filter) => Some(Filter::And(vec![condition, filter])),
+                None => Some(condition),
+            }
+        }
+        None => filter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::filter::Filter;
+    use crate::golem::vector::types::{FilterCondition, MetadataValue};
+    use crate::namespace::{add_namespace, namespace_filter, strip_namespace, NAMESPACE_FIELD};
+
+    #[test]
+    fn namespace_metadata_roundtrip() {
+        let metadata = vec![("a".to_string(), MetadataValue::IntegerVal(1))];
+        let with_namespace = add_namespace(Some(metadata.clone()), Some("tenant-1")).unwrap();
+        assert_eq!(with_namespace.len(), 2);
+        assert_eq!(strip_namespace(Some(with_namespace)), Some(metadata));
+    }
+
+    #[test]
+    fn namespace_is_added_to_filters() {
+        let exists = Filter::Condition(FilterCondition::Exists("a".to_string()));
+        let filter = namespace_filter(Some(exists.clone()), Some("tenant-1")).unwrap();
+        assert_eq!(
+            filter,
+            Filter::And(vec![
+                Filter::Condition(FilterCondition::Eq((
+                    NAMESPACE_FIELD.to_string(),
+                    MetadataValue::StringVal("tenant-1".to_string())
+                ))),
+                exists.clone()
+            ])
+        );
+        assert_eq!(namespace_filter(Some(exists.clone()), None), Some(exists));
+    }
+}
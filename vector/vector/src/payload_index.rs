@@ -0,0 +1,62 @@
+use crate::golem::vector::types::{PayloadIndexType, VectorError};
+
+/// Returns the Qdrant `field_schema` of a payload index
+pub fn qdrant_field_schema(index_type: PayloadIndexType) -> &'static str {
+    match index_type {
+        PayloadIndexType::Keyword => "keyword",
+        PayloadIndexType::Integer => "integer",
+        PayloadIndexType::Float => "float",
+        PayloadIndexType::Boolean => "bool",
+        PayloadIndexType::Geo => "geo",
+        PayloadIndexType::Text => "text",
+    }
+}
+
+/// Parses a Qdrant `data_type` reported in the collection info's `payload_schema`
+pub fn from_qdrant_field_schema(schema: &str) -> Option<PayloadIndexType> {
+    match schema {
+        "keyword" | "uuid" => Some(PayloadIndexType::Keyword),
+        "integer" => Some(PayloadIndexType::Integer),
+        "float" | "datetime" => Some(PayloadIndexType::Float),
+        "bool" => Some(PayloadIndexType::Boolean),
+        "geo" => Some(PayloadIndexType::Geo),
+        "text" => Some(PayloadIndexType::Text),
+        _ => None,
+    }
+}
+
+/// Returns the Milvus scalar index type used for a metadata field
+pub fn milvus_scalar_index_type(index_type: PayloadIndexType) -> Result<&'static str, VectorError> {
+    match index_type {
+        PayloadIndexType::Keyword | PayloadIndexType::Boolean | PayloadIndexType::Text => {
+            Ok("INVERTED")
+        }
+        PayloadIndexType::Integer | PayloadIndexType::Float => Ok("STL_SORT"),
+        PayloadIndexType::Geo => Err(VectorError::UnsupportedFeature(
+            "Milvus does not support geo indexes".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::PayloadIndexType;
+    use crate::payload_index::{from_qdrant_field_schema, qdrant_field_schema};
+
+    #[test]
+    fn qdrant_field_schema_roundtrip() {
+        for index_type in [
+            PayloadIndexType::Keyword,
+            PayloadIndexType::Integer,
+            PayloadIndexType::Float,
+            PayloadIndexType::Boolean,
+            PayloadIndexType::Geo,
+            PayloadIndexType::Text,
+        ] {
+            assert_eq!(
+                from_qdrant_field_schema(qdrant_field_schema(index_type)),
+                Some(index_type)
+            );
+        }
+    }
+}
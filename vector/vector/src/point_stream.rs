@@ -0,0 +1,62 @@
+use crate::exports::golem::vector::search::GuestPointStream;
+use crate::golem::vector::types::{Point, VectorError};
+use std::cell::RefCell;
+
+/// A single page of points returned by a provider
+pub struct PointPage {
+    pub points: Vec<Point>,
+    /// Opaque cursor of the next page, `None` if this was the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Provider specific way of fetching pages of points.
+///
+/// The cursor is whatever the provider needs to continue the iteration: Qdrant's
+/// `next_page_offset`, Pinecone's pagination token, or the last seen id for keyset
+/// pagination in SQL based providers.
+pub trait PointPager: 'static {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError>;
+}
+
+enum PointStreamState {
+    Start,
+    Next(String),
+    Finished,
+}
+
+/// Implements the `point-stream` resource on top of a `PointPager`
+pub struct VectorPointStream<T> {
+    pager: T,
+    state: RefCell<PointStreamState>,
+}
+
+impl<T: PointPager> VectorPointStream<T> {
+    pub fn new(pager: T) -> Self {
+        Self {
+            pager,
+            state: RefCell::new(PointStreamState::Start),
+        }
+    }
+}
+
+impl<T: PointPager> GuestPointStream for VectorPointStream<T> {
+    fn get_next(&self) -> Result<Option<Vec<Point>>, VectorError> {
+        let cursor = match &*self.state.borrow() {
+            PointStreamState::Start => None,
+            PointStreamState::Next(cursor) => Some(cursor.clone()),
+            PointStreamState::Finished => return Ok(None),
+        };
+
+        let page = self.pager.fetch_page(cursor.as_deref())?;
+        *self.state.borrow_mut() = match page.next_cursor {
+            Some(cursor) => PointStreamState::Next(cursor),
+            None => PointStreamState::Finished,
+        };
+
+        if page.points.is_empty() && matches!(*self.state.borrow(), PointStreamState::Finished) {
+            Ok(None)
+        } else {
+            Ok(Some(page.points))
+        }
+    }
+}
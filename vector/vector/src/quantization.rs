@@ -0,0 +1,179 @@
+use crate::golem::vector::types::{
+    DistanceMetric, QuantizationConfig, ScalarQuantizationType, VectorError,
+};
+use serde_json::{json, Map, Value};
+
+/// Number of clusters used for IVF based Milvus indexes
+const MILVUS_NLIST: u32 = 1024;
+
+/// Translates a quantization config to the `quantization_config` of a Qdrant collection
+pub fn to_qdrant_quantization(config: &QuantizationConfig) -> Result<Value, VectorError> {
+    match config {
+        QuantizationConfig::Scalar(scalar) => match scalar.element_type {
+            ScalarQuantizationType::Int8 => {
+                let mut scalar_config = Map::new();
+                scalar_config.insert("type".to_string(), json!("int8"));
+                if let Some(quantile) = scalar.quantile {
+                    scalar_config.insert("quantile".to_string(), json!(quantile));
+                }
+                scalar_config.insert("always_ram".to_string(), json!(scalar.always_ram));
+                Ok(json!({ "scalar": scalar_config }))
+            }
+            ScalarQuantizationType::Float16 => Err(unsupported_quantization(
+                "Qdrant",
+                "float16 scalar quantization",
+            )),
+        },
+        QuantizationConfig::Product(product) => match product.compression_ratio {
+            4 | 8 | 16 | 32 | 64 => Ok(json!({
+                "product": {
+                    "compression": format!("x{}", product.compression_ratio),
+                    "always_ram": product.always_ram,
+                }
+            })),
+            ratio => Err(VectorError::InvalidParams(format!(
+                "Qdrant supports product quantization compression ratios of 4, 8, 16, 32 and 64, got {ratio}"
+            ))),
+        },
+        QuantizationConfig::Binary(binary) => {
+            Ok(json!({ "binary": { "always_ram": binary.always_ram } }))
+        }
+    }
+}
+
+/// Returns the pgvector column type storing vectors of the given dimension.
+///
+/// Float16 scalar quantization is mapped to `halfvec` and binary quantization to `bit` columns.
+pub fn pgvector_column_type(
+    dimension: u32,
+    quantization: Option<&QuantizationConfig>,
+) -> Result<String, VectorError> {
+    match quantization {
+        None => Ok(format!("vector({dimension})")),
+        Some(QuantizationConfig::Scalar(scalar)) => match scalar.element_type {
+            ScalarQuantizationType::Float16 => Ok(format!("halfvec({dimension})")),
+            ScalarQuantizationType::Int8 => Err(unsupported_quantization(
+                "pgvector",
+                "int8 scalar quantization",
+            )),
+        },
+        Some(QuantizationConfig::Binary(_)) => Ok(format!("bit({dimension})")),
+        Some(QuantizationConfig::Product(_)) => {
+            Err(unsupported_quantization("pgvector", "product quantization"))
+        }
+    }
+}
+
+/// Builds the index parameters of a Milvus vector field
+pub fn to_milvus_index_params(
+    field_name: &str,
+    dimension: u32,
+    metric: DistanceMetric,
+    quantization: Option<&QuantizationConfig>,
+) -> Result<Value, VectorError> {
+    let metric_type = match metric {
+        DistanceMetric::Cosine => "COSINE",
+        DistanceMetric::Euclidean => "L2",
+        DistanceMetric::DotProduct => "IP",
+    };
+    let (index_type, params) = match quantization {
+        None => ("AUTOINDEX", json!({})),
+        Some(QuantizationConfig::Scalar(scalar)) => match scalar.element_type {
+            ScalarQuantizationType::Int8 => ("IVF_SQ8", json!({ "nlist": MILVUS_NLIST })),
+            ScalarQuantizationType::Float16 => {
+                return Err(unsupported_quantization(
+                    "Milvus",
+                    "float16 scalar quantization",
+                ))
+            }
+        },
+        Some(QuantizationConfig::Product(product)) => {
+            // Every sub-quantizer encodes a group of 4-byte floats into a single byte
+            let ratio = product.compression_ratio;
+            let m = if ratio > 0 && (dimension * 4).is_multiple_of(ratio) {
+                (dimension * 4) / ratio
+            } else {
+                0
+            };
+            if m == 0 || !dimension.is_multiple_of(m) {
+                return Err(VectorError::InvalidParams(format!(
+                    "Compression ratio {ratio} is not applicable to vectors of dimension {dimension}"
+                )));
+            }
+            (
+                "IVF_PQ",
+                json!({ "nlist": MILVUS_NLIST, "m": m, "nbits": 8 }),
+            )
+        }
+        Some(QuantizationConfig::Binary(_)) => {
+            return Err(unsupported_quantization(
+                "Milvus",
+                "binary quantization of float vectors",
+            ))
+        }
+    };
+    Ok(json!({
+        "fieldName": field_name,
+        "indexName": field_name,
+        "metricType": metric_type,
+        "indexType": index_type,
+        "params": params,
+    }))
+}
+
+fn unsupported_quantization(provider: &str, what: &str) -> VectorError {
+    VectorError::UnsupportedFeature(format!("{provider} does not support {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::{
+        DistanceMetric, ProductQuantization, QuantizationConfig, ScalarQuantization,
+        ScalarQuantizationType, VectorError,
+    };
+    use crate::quantization::{
+        pgvector_column_type, to_milvus_index_params, to_qdrant_quantization,
+    };
+    use serde_json::json;
+
+    fn scalar(element_type: ScalarQuantizationType) -> QuantizationConfig {
+        QuantizationConfig::Scalar(ScalarQuantization {
+            element_type,
+            quantile: Some(0.99),
+            always_ram: true,
+        })
+    }
+
+    #[test]
+    fn qdrant_scalar_quantization() {
+        assert_eq!(
+            to_qdrant_quantization(&scalar(ScalarQuantizationType::Int8)).unwrap(),
+            json!({ "scalar": { "type": "int8", "quantile": 0.9900000095367432, "always_ram": true } })
+        );
+        assert!(matches!(
+            to_qdrant_quantization(&scalar(ScalarQuantizationType::Float16)),
+            Err(VectorError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn pgvector_half_precision_column() {
+        assert_eq!(
+            pgvector_column_type(768, Some(&scalar(ScalarQuantizationType::Float16))).unwrap(),
+            "halfvec(768)"
+        );
+        assert_eq!(pgvector_column_type(768, None).unwrap(), "vector(768)");
+    }
+
+    #[test]
+    fn milvus_product_quantization() {
+        let product = QuantizationConfig::Product(ProductQuantization {
+            compression_ratio: 16,
+            always_ram: false,
+        });
+        let params =
+            to_milvus_index_params("vector", 128, DistanceMetric::Cosine, Some(&product)).unwrap();
+        assert_eq!(params["indexType"], "IVF_PQ");
+        assert_eq!(params["params"]["m"], 32);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::golem::vector::types::{Id, SearchResult, VectorError};
+
+/// Calculates the query vector of a recommendation for providers without a native
+/// recommendation API.
+///
+/// Uses the same strategy as Qdrant's `average_vector`: the average of the positive examples
+/// moved away from the average of the negative examples.
+pub fn recommendation_vector(
+    positive: &[Vec<f32>],
+    negative: &[Vec<f32>],
+) -> Result<Vec<f32>, VectorError> {
+    let positive_average = average(positive).ok_or_else(|| {
+        VectorError::InvalidParams("At least one positive example is required".to_string())
+    })?;
+    let dimension = positive_average.len();
+    if positive
+        .iter()
+        .chain(negative)
+        .any(|vector| vector.len() != dimension)
+    {
+        return Err(VectorError::DimensionMismatch(
+            "The example points have vectors of different dimensions".to_string(),
+        ));
+    }
+
+    match average(negative) {
+        Some(negative_average) => Ok(positive_average
+            .iter()
+            .zip(negative_average)
+            .map(|(pos, neg)| pos + (pos - neg))
+            .collect()),
+        None => Ok(positive_average),
+    }
+}
+
+/// Removes the example points from the search results of an emulated recommendation.
+///
+/// The search has to be performed with a limit increased by the number of examples, so
+/// there are still `limit` results left after the examples are removed.
+pub fn exclude_examples(
+    results: Vec<SearchResult>,
+    positive: &[Id],
+    negative: &[Id],
+    limit: u32,
+) -> Vec<SearchResult> {
+    results
+        .into_iter()
+        .filter(|result| !positive.contains(&result.id) && !negative.contains(&result.id))
+        .take(limit as usize)
+        .collect()
+}
+
+fn average(vectors: &[Vec<f32>]) -> Option<Vec<f32>> {
+    let first = vectors.first()?;
+    let mut sum = vec![0.0; first.len()];
+    for vector in vectors {
+        for (total, value) in sum.iter_mut().zip(vector) {
+            *total += value;
+        }
+    }
+    Some(
+        sum.into_iter()
+            .map(|total| total / vectors.len() as f32)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::recommend::recommendation_vector;
+
+    #[test]
+    fn moves_away_from_negative_examples() {
+        let result =
+            recommendation_vector(&[vec![1.0, 0.0], vec![3.0, 0.0]], &[vec![0.0, 1.0]]).unwrap();
+        assert_eq!(result, vec![4.0, -1.0]);
+    }
+
+    #[test]
+    fn requires_positive_examples() {
+        assert!(recommendation_vector(&[], &[vec![1.0]]).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::golem::vector::types::{Id, SearchQuery, SearchResult, VectorError};
+
+/// Implements `query-batch` for providers without a batch search endpoint, by running
+/// `search` for each query one after the other. Fails on the first failing query.
+pub fn search_each<F>(
+    queries: Vec<SearchQuery>,
+    search: F,
+) -> Result<Vec<Vec<SearchResult>>, VectorError>
+where
+    F: FnMut(SearchQuery) -> Result<Vec<SearchResult>, VectorError>,
+{
+    queries.into_iter().map(search).collect()
+}
+
+/// Fails for hybrid searches on providers which only support vector similarity search
+pub fn reject_keywords(provider: &str, query: &SearchQuery) -> Result<(), VectorError> {
+    if query.keywords.is_some() {
+        Err(VectorError::UnsupportedFeature(format!(
+            "{provider} does not support hybrid search"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Implements the `offset` of a search on providers without native pagination, by running
+/// `search` with the limit increased by the offset and skipping the first results
+pub fn search_with_offset<F>(
+    mut query: SearchQuery,
+    search: F,
+) -> Result<Vec<SearchResult>, VectorError>
+where
+    F: FnOnce(SearchQuery) -> Result<Vec<SearchResult>, VectorError>,
+{
+    let offset = query.offset.take().unwrap_or_default();
+    query.limit += offset;
+    Ok(search(query)?.into_iter().skip(offset as usize).collect())
+}
+
+/// Implements `search-by-id` for providers without a native search by point id, by searching
+/// with the vector of the point and removing the point itself from the results.
+///
+/// `vector` fetches the point's vector, failing with `not-found` if it does not exist.
+pub fn search_by_vector_of<V, F>(
+    id: &Id,
+    limit: u32,
+    vector: V,
+    search: F,
+) -> Result<Vec<SearchResult>, VectorError>
+where
+    V: FnOnce(&Id) -> Result<Vec<f32>, VectorError>,
+    F: FnOnce(Vec<f32>, u32) -> Result<Vec<SearchResult>, VectorError>,
+{
+    let results = search(vector(id)?, limit + 1)?;
+    Ok(results
+        .into_iter()
+        .filter(|result| result.id != *id)
+        .take(limit as usize)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::{SearchQuery, SearchResult};
+    use crate::search::{search_by_vector_of, search_with_offset};
+
+    fn result(id: &str) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            score: 1.0,
+            vector: None,
+            metadata: None,
+        }
+    }
+
+    fn ids(results: Vec<SearchResult>) -> Vec<String> {
+        results.into_iter().map(|result| result.id).collect()
+    }
+
+    #[test]
+    fn skips_the_offset() {
+        let query = SearchQuery {
+            vector: vec![1.0],
+            vector_name: None,
+            limit: 2,
+            offset: Some(1),
+            filter: None,
+            include_vectors: false,
+            include_metadata: false,
+            keywords: None,
+            sparse: None,
+        };
+        let results = search_with_offset(query, |query| {
+            assert_eq!(query.limit, 3);
+            assert_eq!(query.offset, None);
+            Ok(vec![result("a"), result("b"), result("c")])
+        })
+        .unwrap();
+        assert_eq!(ids(results), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn excludes_the_searched_point() {
+        let results = search_by_vector_of(
+            &"b".to_string(),
+            2,
+            |_| Ok(vec![1.0]),
+            |vector, limit| {
+                assert_eq!(vector, vec![1.0]);
+                assert_eq!(limit, 3);
+                Ok(vec![result("b"), result("a"), result("c")])
+            },
+        )
+        .unwrap();
+        assert_eq!(ids(results), vec!["a", "c"]);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::golem::vector::types::{SearchQuery, SparseVector, VectorError, VectorRecord};
+
+/// Checks that a sparse vector has a value for each of its indices
+pub fn validate_sparse(sparse: &SparseVector) -> Result<(), VectorError> {
+    if sparse.indices.len() != sparse.values.len() {
+        Err(VectorError::InvalidParams(format!(
+            "Sparse vector has {} indices but {} values",
+            sparse.indices.len(),
+            sparse.values.len()
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails for records with a sparse vector on providers which only store dense vectors
+pub fn reject_sparse_records(provider: &str, records: &[VectorRecord]) -> Result<(), VectorError> {
+    match records.iter().find(|record| record.sparse.is_some()) {
+        Some(record) => Err(VectorError::UnsupportedFeature(format!(
+            "{provider} does not support sparse vectors, but point '{}' has one",
+            record.id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Fails for hybrid searches with a sparse vector on providers which only store dense vectors
+pub fn reject_sparse_query(provider: &str, query: &SearchQuery) -> Result<(), VectorError> {
+    if query.sparse.is_some() {
+        Err(VectorError::UnsupportedFeature(format!(
+            "{provider} does not support sparse vectors"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::{SparseVector, VectorData, VectorError, VectorRecord};
+    use crate::sparse::{reject_sparse_records, validate_sparse};
+
+    #[test]
+    fn rejects_mismatched_lengths() {
+        let sparse = SparseVector {
+            indices: vec![1, 5],
+            values: vec![0.5],
+        };
+        assert!(matches!(
+            validate_sparse(&sparse),
+            Err(VectorError::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn names_the_point_with_a_sparse_vector() {
+        let records = vec![
+            VectorRecord {
+                id: "a".to_string(),
+                vector: VectorData::Dense(vec![1.0]),
+                sparse: None,
+                metadata: None,
+            },
+            VectorRecord {
+                id: "b".to_string(),
+                vector: VectorData::Dense(vec![1.0]),
+                sparse: Some(SparseVector {
+                    indices: vec![3],
+                    values: vec![0.25],
+                }),
+                metadata: None,
+            },
+        ];
+        match reject_sparse_records("Chroma", &records) {
+            Err(VectorError::UnsupportedFeature(message)) => assert!(message.contains("'b'")),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::golem::vector::types::{TaskId, VectorError};
+
+/// The kind of long running operation a task id refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    CreateSnapshot,
+    RestoreSnapshot,
+}
+
+impl TaskKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::CreateSnapshot => "create-snapshot",
+            TaskKind::RestoreSnapshot => "restore-snapshot",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "create-snapshot" => Some(TaskKind::CreateSnapshot),
+            "restore-snapshot" => Some(TaskKind::RestoreSnapshot),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded task id.
+///
+/// Task ids are opaque for the users of the interface, but providers have to know which
+/// operation and collection they belong to when polling them. These are encoded in the id
+/// together with the provider's own identifier of the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub kind: TaskKind,
+    pub collection: String,
+    pub provider_id: String,
+}
+
+impl Task {
+    pub fn new(
+        kind: TaskKind,
+        collection: impl Into<String>,
+        provider_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            collection: collection.into(),
+            provider_id: provider_id.into(),
+        }
+    }
+
+    pub fn encode(&self) -> TaskId {
+        format!(
+            "{}:{}:{}:{}",
+            self.kind.as_str(),
+            self.collection.len(),
+            self.collection,
+            self.provider_id
+        )
+    }
+
+    pub fn decode(task_id: &str) -> Result<Self, VectorError> {
+        let invalid = || VectorError::InvalidParams(format!("Invalid task id: {task_id}"));
+
+        let (kind, rest) = task_id.split_once(':').ok_or_else(invalid)?;
+        let kind = TaskKind::parse(kind).ok_or_else(invalid)?;
+        let (length, rest) = rest.split_once(':').ok_or_else(invalid)?;
+        let length: usize = length.parse().map_err(|_| invalid())?;
+        if rest.len() < length + 1 || !rest.is_char_boundary(length) {
+            return Err(invalid());
+        }
+        let (collection, rest) = rest.split_at(length);
+        let provider_id = rest.strip_prefix(':').ok_or_else(invalid)?;
+
+        Ok(Self::new(kind, collection, provider_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{Task, TaskKind};
+
+    #[test]
+    fn task_id_roundtrip() {
+        let task = Task::new(
+            TaskKind::CreateSnapshot,
+            "my:collection",
+            "snapshot-2024.snapshot",
+        );
+        assert_eq!(Task::decode(&task.encode()).unwrap(), task);
+    }
+
+    #[test]
+    fn invalid_task_ids_are_rejected() {
+        assert!(Task::decode("unknown:1:a:b").is_err());
+        assert!(Task::decode("create-snapshot:10:a:b").is_err());
+        assert!(Task::decode("create-snapshot").is_err());
+    }
+}
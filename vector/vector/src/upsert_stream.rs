@@ -0,0 +1,159 @@
+use crate::batch::write_in_batches;
+use crate::exports::golem::vector::vectors::GuestUpsertStream;
+use crate::golem::vector::types::{BatchResult, VectorError, VectorRecord};
+use std::cell::RefCell;
+
+/// Provider specific way of writing a batch of points, used both by `upsert-vectors` and
+/// by upsert streams.
+pub trait BatchWriter: 'static {
+    /// The number of points written in a single request
+    fn batch_size(&self) -> usize;
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError>;
+}
+
+struct UpsertStreamState {
+    /// Points added to the stream but not written yet, always less than a batch
+    buffer: Vec<VectorRecord>,
+    /// The number of points taken from the buffer so far, the stream index of its first point
+    flushed: u32,
+    finished: bool,
+}
+
+/// Implements the `upsert-stream` resource on top of a `BatchWriter`.
+///
+/// Errors of setting up the writer are reported by the first `write` or `finish` call, as
+/// opening an upsert stream cannot fail.
+pub struct VectorUpsertStream<T> {
+    writer: Result<T, VectorError>,
+    state: RefCell<UpsertStreamState>,
+}
+
+impl<T: BatchWriter> VectorUpsertStream<T> {
+    pub fn new(writer: Result<T, VectorError>) -> Self {
+        Self {
+            writer,
+            state: RefCell::new(UpsertStreamState {
+                buffer: Vec::new(),
+                flushed: 0,
+                finished: false,
+            }),
+        }
+    }
+
+    /// Writes the buffered points in batches, leaving the last incomplete batch in the buffer
+    /// unless `all` is set. Failures are reported with their index in the whole stream.
+    fn flush(&self, all: bool) -> Result<BatchResult, VectorError> {
+        let writer = self.writer.as_ref().map_err(Clone::clone)?;
+        let mut state = self.state.borrow_mut();
+        if state.finished {
+            return Err(VectorError::InvalidParams(
+                "Upsert stream is already finished".to_string(),
+            ));
+        }
+
+        let batch_size = writer.batch_size().max(1);
+        let count = if all {
+            state.buffer.len()
+        } else {
+            state.buffer.len() - state.buffer.len() % batch_size
+        };
+        let batches = state.buffer.drain(..count).collect::<Vec<_>>();
+        let mut result = write_in_batches(&batches, batch_size, |batch| writer.write_batch(batch));
+        for failure in &mut result.failures {
+            failure.start_index += state.flushed;
+        }
+        state.flushed += count as u32;
+        state.finished = all;
+        Ok(result)
+    }
+}
+
+impl<T: BatchWriter> GuestUpsertStream for VectorUpsertStream<T> {
+    fn write(&self, vectors: Vec<VectorRecord>) -> Result<BatchResult, VectorError> {
+        if self.state.borrow().finished {
+            return Err(VectorError::InvalidParams(
+                "Upsert stream is already finished".to_string(),
+            ));
+        }
+        self.state.borrow_mut().buffer.extend(vectors);
+        self.flush(false)
+    }
+
+    fn finish(&self) -> Result<BatchResult, VectorError> {
+        self.flush(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exports::golem::vector::vectors::GuestUpsertStream;
+    use crate::golem::vector::types::{VectorData, VectorError, VectorRecord};
+    use crate::upsert_stream::{BatchWriter, VectorUpsertStream};
+    use std::cell::RefCell;
+
+    struct TestWriter {
+        batches: RefCell<Vec<Vec<String>>>,
+    }
+
+    impl BatchWriter for TestWriter {
+        fn batch_size(&self) -> usize {
+            3
+        }
+
+        fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+            self.batches
+                .borrow_mut()
+                .push(batch.iter().map(|record| record.id.clone()).collect());
+            if batch[0].id == "3" {
+                Err(VectorError::RateLimited("slow down".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn records(ids: std::ops::Range<usize>) -> Vec<VectorRecord> {
+        ids.map(|i| VectorRecord {
+            id: i.to_string(),
+            vector: VectorData::Dense(vec![i as f32]),
+            sparse: None,
+            metadata: None,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn writes_full_batches_and_the_rest_on_finish() {
+        let stream = VectorUpsertStream::new(Ok(TestWriter {
+            batches: RefCell::new(Vec::new()),
+        }));
+
+        let result = stream.write(records(0..2)).unwrap();
+        assert_eq!(result.success_count, 0);
+        let result = stream.write(records(2..7)).unwrap();
+        assert_eq!(result.success_count, 3);
+        assert_eq!(result.failures.len(), 1);
+        assert_eq!(result.failures[0].start_index, 3);
+        let result = stream.finish().unwrap();
+        assert_eq!(result.success_count, 1);
+
+        let writer = stream.writer.as_ref().unwrap();
+        assert_eq!(
+            *writer.batches.borrow(),
+            vec![vec!["0", "1", "2"], vec!["3", "4", "5"], vec!["6"]]
+        );
+        assert!(stream.write(records(7..8)).is_err());
+    }
+
+    #[test]
+    fn reports_setup_errors() {
+        let stream = VectorUpsertStream::<TestWriter>::new(Err(VectorError::NotFound(
+            "Collection docs does not exist".to_string(),
+        )));
+        assert!(matches!(
+            stream.write(records(0..1)),
+            Err(VectorError::NotFound(_))
+        ));
+    }
+}
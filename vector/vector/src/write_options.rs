@@ -0,0 +1,69 @@
+use crate::golem::vector::types::{ConsistencyLevel, WriteOptions, WriteOrdering};
+
+/// Returns the query parameters of a Qdrant point write request
+pub fn qdrant_write_params(options: Option<&WriteOptions>) -> Vec<(&'static str, String)> {
+    let mut params = Vec::new();
+    if let Some(options) = options {
+        if let Some(wait) = options.wait {
+            params.push(("wait", wait.to_string()));
+        }
+        if let Some(ordering) = options.ordering {
+            let ordering = match ordering {
+                WriteOrdering::Weak => "weak",
+                WriteOrdering::Medium => "medium",
+                WriteOrdering::Strong => "strong",
+            };
+            params.push(("ordering", ordering.to_string()));
+        }
+    }
+    params
+}
+
+/// Returns the Milvus `consistencyLevel` value of the given options
+pub fn milvus_consistency_level(options: Option<&WriteOptions>) -> Option<&'static str> {
+    options
+        .and_then(|options| options.consistency)
+        .map(|consistency| match consistency {
+            ConsistencyLevel::Strong => "Strong",
+            ConsistencyLevel::Session => "Session",
+            ConsistencyLevel::Bounded => "Bounded",
+            ConsistencyLevel::Eventually => "Eventually",
+        })
+}
+
+/// Returns the Weaviate `consistency_level` query parameter of the given options.
+///
+/// Weaviate only has replica count based levels, session and bounded consistency are
+/// mapped to a quorum.
+pub fn weaviate_consistency_level(options: Option<&WriteOptions>) -> Option<&'static str> {
+    options
+        .and_then(|options| options.consistency)
+        .map(|consistency| match consistency {
+            ConsistencyLevel::Strong => "ALL",
+            ConsistencyLevel::Session | ConsistencyLevel::Bounded => "QUORUM",
+            ConsistencyLevel::Eventually => "ONE",
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::golem::vector::types::{WriteOptions, WriteOrdering};
+    use crate::write_options::qdrant_write_params;
+
+    #[test]
+    fn qdrant_params_only_contain_given_options() {
+        assert!(qdrant_write_params(None).is_empty());
+        let options = WriteOptions {
+            wait: Some(true),
+            ordering: Some(WriteOrdering::Strong),
+            consistency: None,
+        };
+        assert_eq!(
+            qdrant_write_params(Some(&options)),
+            vec![
+                ("wait", "true".to_string()),
+                ("ordering", "strong".to_string())
+            ]
+        );
+    }
+}
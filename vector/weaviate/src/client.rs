@@ -0,0 +1,515 @@
+use golem_vector::error::{from_http_status, from_reqwest_error};
+use golem_vector::golem::vector::types::VectorError;
+use golem_vector::retry::send_with_retry;
+use log::trace;
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// The Weaviate REST and GraphQL API client
+///
+/// Based on https://weaviate.io/developers/weaviate/api/rest
+pub struct WeaviateApi {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    client: Client,
+}
+
+impl WeaviateApi {
+    pub fn new(endpoint: String, headers: Vec<(String, String)>) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to initialize HTTP client");
+        Self {
+            endpoint,
+            headers,
+            client,
+        }
+    }
+
+    pub fn create_class(&self, class: &Value) -> Result<(), VectorError> {
+        self.call::<Value>(Method::POST, "/v1/schema", &[], Some(class))
+            .map(|_| ())
+    }
+
+    pub fn delete_class(&self, class: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::DELETE,
+            &format!("/v1/schema/{class}"),
+            &[],
+            None::<&()>,
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_classes(&self) -> Result<Vec<ClassSchema>, VectorError> {
+        self.call::<Schema>(Method::GET, "/v1/schema", &[], None::<&()>)
+            .map(|schema| schema.classes)
+    }
+
+    pub fn get_class(&self, class: &str) -> Result<ClassSchema, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/v1/schema/{class}"),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn add_property(&self, class: &str, property: &Property) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::POST,
+            &format!("/v1/schema/{class}/properties"),
+            &[],
+            Some(property),
+        )
+        .map(|_| ())
+    }
+
+    pub fn create_backup(
+        &self,
+        backend: &str,
+        request: &BackupRequest,
+    ) -> Result<BackupStatus, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/v1/backups/{backend}"),
+            &[],
+            Some(request),
+        )
+    }
+
+    pub fn backup_status(&self, backend: &str, id: &str) -> Result<BackupStatus, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/v1/backups/{backend}/{id}"),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn restore_backup(
+        &self,
+        backend: &str,
+        id: &str,
+        request: &BackupRequest,
+    ) -> Result<BackupStatus, VectorError> {
+        self.call(
+            Method::POST,
+            &format!("/v1/backups/{backend}/{id}/restore"),
+            &[],
+            Some(request),
+        )
+    }
+
+    pub fn restore_status(&self, backend: &str, id: &str) -> Result<BackupStatus, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/v1/backups/{backend}/{id}/restore"),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn list_backups(&self, backend: &str) -> Result<Vec<BackupStatus>, VectorError> {
+        self.call(
+            Method::GET,
+            &format!("/v1/backups/{backend}"),
+            &[],
+            None::<&()>,
+        )
+    }
+
+    pub fn create_alias(&self, alias: &Alias) -> Result<(), VectorError> {
+        self.call::<Value>(Method::POST, "/v1/aliases", &[], Some(alias))
+            .map(|_| ())
+    }
+
+    pub fn replace_alias(&self, alias: &Alias) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::PUT,
+            &format!("/v1/aliases/{}", alias.alias),
+            &[],
+            Some(&Map::from_iter([(
+                "class".to_string(),
+                Value::String(alias.class.clone()),
+            )])),
+        )
+        .map(|_| ())
+    }
+
+    pub fn delete_alias(&self, alias: &str) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::DELETE,
+            &format!("/v1/aliases/{alias}"),
+            &[],
+            None::<&()>,
+        )
+        .map(|_| ())
+    }
+
+    pub fn list_aliases(&self) -> Result<Vec<Alias>, VectorError> {
+        self.call::<AliasList>(Method::GET, "/v1/aliases", &[], None::<&()>)
+            .map(|list| list.aliases)
+    }
+
+    /// Creates or replaces objects. Weaviate reports the errors of single objects in the
+    /// response, these fail the whole batch.
+    pub fn batch_objects(
+        &self,
+        objects: Vec<WeaviateObject>,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        let results: Vec<BatchObjectResult> = self.call(
+            Method::POST,
+            "/v1/batch/objects",
+            params,
+            Some(&BatchObjectsRequest { objects }),
+        )?;
+        match results
+            .into_iter()
+            .flat_map(|result| result.result.errors.error)
+            .next()
+        {
+            Some(error) => Err(from_weaviate_message(error.message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Gets an object with its vectors, or `None` if it does not exist
+    pub fn get_object(
+        &self,
+        class: &str,
+        id: &str,
+        params: &[(&str, String)],
+    ) -> Result<Option<WeaviateObject>, VectorError> {
+        let mut params = params.to_vec();
+        params.push(("include", "vector".to_string()));
+        match self.call(
+            Method::GET,
+            &format!("/v1/objects/{class}/{id}"),
+            &params,
+            None::<&()>,
+        ) {
+            Ok(object) => Ok(Some(object)),
+            Err(VectorError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Merges the given properties and vectors into an existing object
+    pub fn patch_object(
+        &self,
+        object: &WeaviateObject,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(
+            Method::PATCH,
+            &format!("/v1/objects/{}/{}", object.class, object.id),
+            params,
+            Some(object),
+        )
+        .map(|_| ())
+    }
+
+    pub fn batch_delete(
+        &self,
+        request: &BatchDeleteRequest,
+        params: &[(&str, String)],
+    ) -> Result<(), VectorError> {
+        self.call::<Value>(Method::DELETE, "/v1/batch/objects", params, Some(request))
+            .map(|_| ())
+    }
+
+    /// Runs a GraphQL query, returning its `data`
+    pub fn graphql(&self, query: String) -> Result<Value, VectorError> {
+        trace!("Sending GraphQL query to Weaviate: {query}");
+        let response: GraphqlResponse = self.call(
+            Method::POST,
+            "/v1/graphql",
+            &[],
+            Some(&GraphqlRequest { query }),
+        )?;
+        match response.errors.into_iter().next() {
+            Some(error) => Err(from_weaviate_message(error.message)),
+            None => Ok(response.data.unwrap_or(Value::Null)),
+        }
+    }
+
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: &[(&str, String)],
+        body: Option<&impl Serialize>,
+    ) -> Result<T, VectorError> {
+        trace!("Sending {method} {path} request to Weaviate");
+
+        let response = send_with_retry(|| {
+            let mut builder = self
+                .client
+                .request(method.clone(), format!("{}{path}", self.endpoint));
+            if !params.is_empty() {
+                builder = builder.query(params);
+            }
+            for (name, value) in &self.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = body {
+                builder = builder.json(body);
+            }
+            builder.send()
+        })
+        .map_err(|err| from_reqwest_error("Request failed", err))?;
+        parse_response(response)
+    }
+}
+
+/// Successful writes are often answered with an empty body, which is decoded as `null`
+fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, VectorError> {
+    let status = response.status();
+    let raw_body = response
+        .text()
+        .map_err(|err| from_reqwest_error("Failed to receive response body", err))?;
+    if status.is_success() {
+        let body = if raw_body.trim().is_empty() {
+            "null"
+        } else {
+            &raw_body
+        };
+        serde_json::from_str(body).map_err(|err| {
+            VectorError::ProviderError(format!("Failed to decode response body: {err}: {raw_body}"))
+        })
+    } else {
+        trace!("Received {status} response from Weaviate: {raw_body:?}");
+        Err(from_weaviate_error(status, raw_body))
+    }
+}
+
+/// Weaviate reports conflicts and vector length mismatches as unprocessable entities, which
+/// are told apart by their message
+fn from_weaviate_error(status: StatusCode, body: String) -> VectorError {
+    let message = serde_json::from_str::<ErrorResponse>(&body)
+        .ok()
+        .and_then(|response| response.error.into_iter().next())
+        .map(|error| error.message)
+        .unwrap_or(body);
+    if message.contains("already exists") {
+        VectorError::AlreadyExists(message)
+    } else if is_dimension_mismatch(&message) {
+        VectorError::DimensionMismatch(message)
+    } else {
+        from_http_status(status, message)
+    }
+}
+
+/// Maps the errors of GraphQL queries and batch objects, which are reported with a success
+/// status
+fn from_weaviate_message(message: String) -> VectorError {
+    let lowercase = message.to_lowercase();
+    if is_dimension_mismatch(&lowercase) {
+        VectorError::DimensionMismatch(message)
+    } else if lowercase.contains("not found")
+        || lowercase.contains("does not exist")
+        || lowercase.contains("cannot query field")
+    {
+        VectorError::NotFound(message)
+    } else if lowercase.contains("invalid") || lowercase.contains("syntax error") {
+        VectorError::InvalidParams(message)
+    } else {
+        VectorError::ProviderError(message)
+    }
+}
+
+fn is_dimension_mismatch(message: &str) -> bool {
+    message.contains("vector lengths don't match") || message.contains("dimension")
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Schema {
+    #[serde(default)]
+    classes: Vec<ClassSchema>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassSchema {
+    pub class: String,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+    #[serde(default)]
+    pub vector_index_config: Option<VectorIndexConfig>,
+    /// The named vectors of the class, if it has any
+    #[serde(default)]
+    pub vector_config: Option<BTreeMap<String, NamedVectorConfig>>,
+    #[serde(default)]
+    pub multi_tenancy_config: Option<MultiTenancyConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VectorIndexConfig {
+    #[serde(default)]
+    pub distance: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedVectorConfig {
+    #[serde(default)]
+    pub vector_index_config: Option<VectorIndexConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MultiTenancyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Property {
+    pub name: String,
+    pub data_type: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenization: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_filterable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_searchable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_range_filters: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub include: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStatus {
+    pub id: String,
+    #[serde(default)]
+    pub classes: Vec<String>,
+    pub status: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alias {
+    pub alias: String,
+    pub class: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AliasList {
+    #[serde(default)]
+    aliases: Vec<Alias>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeaviateObject {
+    pub class: String,
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub properties: Map<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vectors: Option<BTreeMap<String, Vec<f32>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BatchObjectsRequest {
+    objects: Vec<WeaviateObject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BatchObjectResult {
+    #[serde(default)]
+    result: BatchResultErrors,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct BatchResultErrors {
+    #[serde(default)]
+    errors: ErrorResponse,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchDeleteRequest {
+    #[serde(rename = "match")]
+    pub selection: BatchDeleteMatch,
+    pub output: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchDeleteMatch {
+    pub class: String,
+    #[serde(rename = "where")]
+    pub filter: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GraphqlRequest {
+    query: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphqlResponse {
+    #[serde(default)]
+    data: Option<Value>,
+    #[serde(default)]
+    errors: Vec<ErrorMessage>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ErrorResponse {
+    #[serde(default)]
+    error: Vec<ErrorMessage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ErrorMessage {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{from_weaviate_error, from_weaviate_message};
+    use golem_vector::golem::vector::types::VectorError;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn maps_error_messages() {
+        assert!(matches!(
+            from_weaviate_error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                r#"{"error":[{"message":"class name \"Docs\" already exists"}]}"#.to_string()
+            ),
+            VectorError::AlreadyExists(_)
+        ));
+        assert!(matches!(
+            from_weaviate_error(StatusCode::UNAUTHORIZED, "denied".to_string()),
+            VectorError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            from_weaviate_message("vector lengths don't match: 3 vs 2".to_string()),
+            VectorError::DimensionMismatch(_)
+        ));
+        assert!(matches!(
+            from_weaviate_message("Cannot query field \"Docs\" on type \"GetObjectsObj\".".into()),
+            VectorError::NotFound(_)
+        ));
+    }
+}
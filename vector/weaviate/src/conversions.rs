@@ -0,0 +1,548 @@
+use crate::client::{ClassSchema, Property, WeaviateObject};
+use golem_vector::golem::vector::types::{
+    CollectionConfig, DistanceMetric, GeoPoint, Id, Metadata, MetadataValue, PayloadIndexInfo,
+    PayloadIndexType, Point, QuantizationConfig, ScalarQuantizationType, SearchResult, VectorData,
+    VectorError,
+};
+use golem_vector::metadata::{json_to_metadata_value, metadata_value_to_json};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// The tenant of operations without a namespace, on classes with multi-tenancy enabled
+pub const DEFAULT_TENANT: &str = "default";
+
+/// The property types which are returned as metadata. References and nested objects are not
+/// selected, as they would need their own subselections.
+const METADATA_TYPES: [&str; 13] = [
+    "text",
+    "text[]",
+    "int",
+    "int[]",
+    "number",
+    "number[]",
+    "boolean",
+    "boolean[]",
+    "date",
+    "date[]",
+    "uuid",
+    "uuid[]",
+    "geoCoordinates",
+];
+
+/// Converts a collection name to a Weaviate class name, which always starts with a capital
+/// letter. Class names are also used in GraphQL queries, so anything else than letters, digits
+/// and underscores is rejected.
+pub fn class_name(collection: &str) -> Result<String, VectorError> {
+    let mut chars = collection.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(VectorError::InvalidParams(format!(
+            "Invalid Weaviate collection name '{collection}', expected letters, digits and underscores"
+        )));
+    }
+    let mut class = collection.to_string();
+    class[..1].make_ascii_uppercase();
+    Ok(class)
+}
+
+/// Weaviate object ids are UUIDs, which it returns in lowercase
+pub fn object_id(id: &str) -> Result<String, VectorError> {
+    let groups = id.split('-').map(|group| group.len()).collect::<Vec<_>>();
+    let valid = groups == [8, 4, 4, 4, 12] && id.chars().all(|c| c == '-' || c.is_ascii_hexdigit());
+    if valid {
+        Ok(id.to_ascii_lowercase())
+    } else {
+        Err(VectorError::InvalidParams(format!(
+            "Weaviate point ids must be UUIDs, got '{id}'"
+        )))
+    }
+}
+
+pub fn distance_name(metric: DistanceMetric) -> &'static str {
+    match metric {
+        DistanceMetric::Cosine => "cosine",
+        DistanceMetric::Euclidean => "l2-squared",
+        DistanceMetric::DotProduct => "dot",
+    }
+}
+
+fn metric_from_distance(distance: &str) -> Option<DistanceMetric> {
+    match distance {
+        "cosine" => Some(DistanceMetric::Cosine),
+        "l2-squared" => Some(DistanceMetric::Euclidean),
+        "dot" => Some(DistanceMetric::DotProduct),
+        _ => None,
+    }
+}
+
+/// The metric of a class or of one of its named vectors, cosine being Weaviate's default
+pub fn class_metric(schema: &ClassSchema, vector_name: Option<&str>) -> Option<DistanceMetric> {
+    let config = match (vector_name, &schema.vector_config) {
+        (Some(name), Some(vectors)) => vectors
+            .get(name)
+            .and_then(|vector| vector.vector_index_config.as_ref()),
+        (None, Some(vectors)) if schema.vector_index_config.is_none() => vectors
+            .values()
+            .next()
+            .and_then(|vector| vector.vector_index_config.as_ref()),
+        _ => schema.vector_index_config.as_ref(),
+    };
+    match config.and_then(|config| config.distance.as_deref()) {
+        Some(distance) => metric_from_distance(distance),
+        None => Some(DistanceMetric::Cosine),
+    }
+}
+
+/// Converts a distance reported by Weaviate to a score. Cosine and dot product scores are
+/// similarities as in the other providers, Euclidean scores are squared distances.
+pub fn score(metric: Option<DistanceMetric>, distance: f32) -> f32 {
+    match metric {
+        Some(DistanceMetric::Cosine) => 1.0 - distance,
+        Some(DistanceMetric::DotProduct) => -distance,
+        Some(DistanceMetric::Euclidean) | None => distance,
+    }
+}
+
+/// The vector index config of a class, or of each of its named vectors. Weaviate keeps
+/// compressed vectors in memory, so `always-ram` has no effect.
+fn vector_index_config(config: &CollectionConfig) -> Result<Value, VectorError> {
+    let mut index_config = Map::new();
+    index_config.insert("distance".to_string(), json!(distance_name(config.metric)));
+    match &config.quantization {
+        Some(QuantizationConfig::Scalar(scalar)) => match scalar.element_type {
+            ScalarQuantizationType::Int8 => {
+                index_config.insert("sq".to_string(), json!({ "enabled": true }));
+            }
+            ScalarQuantizationType::Float16 => {
+                return Err(VectorError::UnsupportedFeature(
+                    "Weaviate does not support float16 scalar quantization".to_string(),
+                ))
+            }
+        },
+        Some(QuantizationConfig::Product(product)) => {
+            let segments = pq_segments(config.dimension, product.compression_ratio)?;
+            index_config.insert(
+                "pq".to_string(),
+                json!({ "enabled": true, "segments": segments }),
+            );
+        }
+        Some(QuantizationConfig::Binary(_)) => {
+            index_config.insert("bq".to_string(), json!({ "enabled": true }));
+        }
+        None => {}
+    }
+    Ok(Value::Object(index_config))
+}
+
+/// Every product quantization segment is encoded in a single byte, so the number of segments
+/// giving a compression ratio is the size of the original vector divided by the ratio
+fn pq_segments(dimension: u32, compression_ratio: u32) -> Result<u32, VectorError> {
+    let bytes = dimension * 4;
+    if compression_ratio == 0 || bytes % compression_ratio != 0 {
+        return Err(VectorError::InvalidParams(format!(
+            "Compression ratio {compression_ratio} does not divide {bytes} byte vectors into segments"
+        )));
+    }
+    let segments = bytes / compression_ratio;
+    if segments == 0 || dimension % segments != 0 {
+        return Err(VectorError::InvalidParams(format!(
+            "Dimension {dimension} is not divisible by {segments} product quantization segments"
+        )));
+    }
+    Ok(segments)
+}
+
+/// Builds the definition of a new class. Vectors are always provided by the user, and
+/// multi-tenancy is enabled so namespaces can be mapped to tenants, which are created on their
+/// first write.
+pub fn class_definition(
+    class: &str,
+    config: &CollectionConfig,
+    vector_names: &[String],
+) -> Result<Value, VectorError> {
+    let index_config = vector_index_config(config)?;
+    let mut definition = json!({
+        "class": class,
+        "multiTenancyConfig": {
+            "enabled": true,
+            "autoTenantCreation": true,
+            "autoTenantActivation": true,
+        },
+    });
+    if vector_names.is_empty() {
+        definition["vectorizer"] = json!("none");
+        definition["vectorIndexType"] = json!("hnsw");
+        definition["vectorIndexConfig"] = index_config;
+    } else {
+        let vectors = vector_names
+            .iter()
+            .map(|name| {
+                (
+                    name.clone(),
+                    json!({
+                        "vectorizer": { "none": {} },
+                        "vectorIndexType": "hnsw",
+                        "vectorIndexConfig": index_config,
+                    }),
+                )
+            })
+            .collect::<Map<_, _>>();
+        definition["vectorConfig"] = Value::Object(vectors);
+    }
+    Ok(definition)
+}
+
+/// Converts metadata to object properties. Geo points use Weaviate's `geoCoordinates` format,
+/// and null values are left out, as properties cannot be null.
+pub fn properties(metadata: Option<&Metadata>) -> Map<String, Value> {
+    metadata
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                MetadataValue::NullVal => return None,
+                MetadataValue::GeoVal(point) => json!({
+                    "latitude": point.latitude,
+                    "longitude": point.longitude,
+                }),
+                value => metadata_value_to_json(value),
+            };
+            Some((key.clone(), value))
+        })
+        .collect()
+}
+
+/// Converts object properties back to metadata, skipping the properties the object does not
+/// have, which GraphQL returns as null
+pub fn metadata(properties: Map<String, Value>) -> Metadata {
+    properties
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| {
+            let geo = value.as_object().and_then(|object| {
+                let latitude = object.get("latitude")?.as_f64()?;
+                let longitude = object.get("longitude")?.as_f64()?;
+                Some(MetadataValue::GeoVal(GeoPoint {
+                    latitude,
+                    longitude,
+                }))
+            });
+            (key, geo.unwrap_or_else(|| json_to_metadata_value(&value)))
+        })
+        .collect()
+}
+
+pub fn object(
+    class: &str,
+    id: &str,
+    vector: &VectorData,
+    metadata: Option<&Metadata>,
+    tenant: Option<&str>,
+) -> Result<WeaviateObject, VectorError> {
+    let mut object = WeaviateObject {
+        class: class.to_string(),
+        id: object_id(id)?,
+        properties: properties(metadata),
+        tenant: tenant.map(|tenant| tenant.to_string()),
+        ..Default::default()
+    };
+    match vector {
+        VectorData::Dense(values) => object.vector = Some(values.clone()),
+        VectorData::Named(vectors) => {
+            object.vectors = Some(vectors.iter().cloned().collect::<BTreeMap<_, _>>())
+        }
+    }
+    Ok(object)
+}
+
+pub fn vector_data(
+    vector: Option<Vec<f32>>,
+    vectors: Option<BTreeMap<String, Vec<f32>>>,
+) -> Option<VectorData> {
+    match (vector, vectors) {
+        (Some(vector), _) if !vector.is_empty() => Some(VectorData::Dense(vector)),
+        (_, Some(vectors)) if !vectors.is_empty() => {
+            Some(VectorData::Named(vectors.into_iter().collect()))
+        }
+        _ => None,
+    }
+}
+
+pub fn point_from_object(
+    object: WeaviateObject,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Point {
+    Point {
+        id: object.id,
+        vector: vector_data(object.vector, object.vectors).filter(|_| include_vectors),
+        sparse: None,
+        metadata: include_metadata.then(|| metadata(object.properties)),
+    }
+}
+
+/// The `where` filter selecting objects by id
+pub fn ids_where(ids: &[Id]) -> Result<Value, VectorError> {
+    let ids = ids
+        .iter()
+        .map(|id| object_id(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(json!({ "path": ["id"], "operator": "ContainsAny", "valueTextArray": ids }))
+}
+
+/// Renders a JSON value as a GraphQL input value. Filter operators are enum values, so they
+/// are written without quotes.
+pub fn graphql_value(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(key, value)| match value {
+                    Value::String(operator) if key == "operator" => format!("{key}: {operator}"),
+                    value => format!("{key}: {}", graphql_value(value)),
+                })
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Array(items) => {
+            let items = items.iter().map(graphql_value).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Builds the selection set of a `Get` query. The id is always selected, `additional` lists
+/// the other `_additional` fields such as the distance of search hits.
+pub fn selection(
+    schema: &ClassSchema,
+    additional: &[&str],
+    include_vectors: bool,
+    include_metadata: bool,
+) -> String {
+    let mut fields = Vec::new();
+    if include_metadata {
+        for property in &schema.properties {
+            match property.data_type.first().map(String::as_str) {
+                Some("geoCoordinates") => {
+                    fields.push(format!("{} {{ latitude longitude }}", property.name))
+                }
+                Some(data_type) if METADATA_TYPES.contains(&data_type) => {
+                    fields.push(property.name.clone())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut additional_fields = vec!["id".to_string()];
+    additional_fields.extend(additional.iter().map(|field| field.to_string()));
+    if include_vectors {
+        match &schema.vector_config {
+            Some(vectors) if !vectors.is_empty() => {
+                let names = vectors.keys().cloned().collect::<Vec<_>>();
+                additional_fields.push(format!("vectors {{ {} }}", names.join(" ")));
+            }
+            _ => additional_fields.push("vector".to_string()),
+        }
+    }
+    fields.push(format!("_additional {{ {} }}", additional_fields.join(" ")));
+    format!("{{ {} }}", fields.join(" "))
+}
+
+/// Converts an object returned by a `Get` query
+pub fn point_from_hit(
+    mut hit: Map<String, Value>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> Point {
+    let additional = hit
+        .remove("_additional")
+        .and_then(|additional| serde_json::from_value::<Additional>(additional).ok())
+        .unwrap_or_default();
+    Point {
+        id: additional.id,
+        vector: vector_data(additional.vector, additional.vectors).filter(|_| include_vectors),
+        sparse: None,
+        metadata: include_metadata.then(|| metadata(hit)),
+    }
+}
+
+/// Converts a search hit. Hybrid search hits have a fused `score`, vector search hits a
+/// `distance` which is converted to a score by the metric of the searched vector.
+pub fn search_result_from_hit(
+    hit: Map<String, Value>,
+    metric: Option<DistanceMetric>,
+    include_vectors: bool,
+    include_metadata: bool,
+) -> SearchResult {
+    let additional = hit.get("_additional");
+    let number = |field: &str| {
+        additional
+            .and_then(|additional| additional.get(field))
+            .and_then(|value| match value {
+                Value::String(value) => value.parse::<f32>().ok(),
+                value => value.as_f64().map(|value| value as f32),
+            })
+    };
+    let score = match (number("score"), number("distance")) {
+        (Some(score), _) => score,
+        (None, Some(distance)) => score(metric, distance),
+        (None, None) => 0.0,
+    };
+    let point = point_from_hit(hit, include_vectors, include_metadata);
+    SearchResult {
+        id: point.id,
+        score,
+        vector: point.vector,
+        metadata: point.metadata,
+    }
+}
+
+/// The property added by `create-payload-index`. Keywords are tokenized as a whole, so that
+/// equality filters match complete values.
+pub fn index_property(field: String, index_type: PayloadIndexType) -> Property {
+    let (data_type, tokenization) = match index_type {
+        PayloadIndexType::Keyword => ("text", Some("field")),
+        PayloadIndexType::Text => ("text", Some("word")),
+        PayloadIndexType::Integer => ("int", None),
+        PayloadIndexType::Float => ("number", None),
+        PayloadIndexType::Boolean => ("boolean", None),
+        PayloadIndexType::Geo => ("geoCoordinates", None),
+    };
+    let numeric = matches!(
+        index_type,
+        PayloadIndexType::Integer | PayloadIndexType::Float
+    );
+    Property {
+        name: field,
+        data_type: vec![data_type.to_string()],
+        tokenization: tokenization.map(|tokenization| tokenization.to_string()),
+        index_filterable: Some(true),
+        index_searchable: (index_type == PayloadIndexType::Text).then_some(true),
+        index_range_filters: numeric.then_some(true),
+    }
+}
+
+/// Maps a filterable property of a class back to a payload index
+pub fn index_info(property: &Property) -> Option<PayloadIndexInfo> {
+    if property.index_filterable == Some(false) {
+        return None;
+    }
+    let data_type = property.data_type.first()?.trim_end_matches("[]");
+    let index_type = match data_type {
+        "text" if property.tokenization.as_deref() == Some("field") => PayloadIndexType::Keyword,
+        "text" => PayloadIndexType::Text,
+        "int" => PayloadIndexType::Integer,
+        "number" => PayloadIndexType::Float,
+        "boolean" => PayloadIndexType::Boolean,
+        "geoCoordinates" => PayloadIndexType::Geo,
+        _ => return None,
+    };
+    Some(PayloadIndexInfo {
+        field: property.name.clone(),
+        index_type,
+    })
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+struct Additional {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    vector: Option<Vec<f32>>,
+    #[serde(default)]
+    vectors: Option<BTreeMap<String, Vec<f32>>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conversions::{
+        class_name, graphql_value, metadata, object_id, pq_segments, properties,
+        search_result_from_hit,
+    };
+    use golem_vector::golem::vector::types::{DistanceMetric, GeoPoint, MetadataValue, VectorData};
+    use serde_json::json;
+
+    #[test]
+    fn capitalizes_class_names() {
+        assert_eq!(class_name("docs_v2").unwrap(), "Docs_v2");
+        assert!(class_name("2docs").is_err());
+        assert!(class_name("docs) { __schema").is_err());
+    }
+
+    #[test]
+    fn validates_uuids() {
+        assert_eq!(
+            object_id("6A5B0C1D-1234-4ABC-8DEF-0123456789AB").unwrap(),
+            "6a5b0c1d-1234-4abc-8def-0123456789ab"
+        );
+        assert!(object_id("a").is_err());
+    }
+
+    #[test]
+    fn computes_pq_segments() {
+        assert_eq!(pq_segments(768, 16).unwrap(), 192);
+        assert!(pq_segments(768, 0).is_err());
+        assert!(pq_segments(100, 64).is_err());
+    }
+
+    #[test]
+    fn renders_graphql_values() {
+        let filter = json!({ "operands": [{ "operator": "Equal" }, { "valueText": "a \"b\"" }] });
+        assert_eq!(
+            graphql_value(&filter),
+            r#"{operands: [{operator: Equal}, {valueText: "a \"b\""}]}"#
+        );
+    }
+
+    #[test]
+    fn converts_geo_properties() {
+        let geo = vec![(
+            "location".to_string(),
+            MetadataValue::GeoVal(GeoPoint {
+                latitude: 52.5,
+                longitude: 13.4,
+            }),
+        )];
+        let properties = properties(Some(&geo));
+        assert_eq!(
+            properties.get("location"),
+            Some(&json!({ "latitude": 52.5, "longitude": 13.4 }))
+        );
+        assert_eq!(metadata(properties), geo);
+    }
+
+    #[test]
+    fn converts_search_hits() {
+        let hit = json!({
+            "title": "Dune",
+            "year": null,
+            "_additional": { "id": "a", "distance": 0.25, "vector": [1.0, 0.0] },
+        });
+        let result = search_result_from_hit(
+            hit.as_object().unwrap().clone(),
+            Some(DistanceMetric::Cosine),
+            true,
+            true,
+        );
+        assert_eq!(result.id, "a");
+        assert_eq!(result.score, 0.75);
+        assert_eq!(result.vector, Some(VectorData::Dense(vec![1.0, 0.0])));
+        assert_eq!(
+            result.metadata,
+            Some(vec![(
+                "title".to_string(),
+                MetadataValue::StringVal("Dune".to_string())
+            )])
+        );
+
+        let hybrid = json!({ "_additional": { "id": "b", "score": "0.5" } });
+        let result =
+            search_result_from_hit(hybrid.as_object().unwrap().clone(), None, false, false);
+        assert_eq!(result.score, 0.5);
+    }
+}
@@ -0,0 +1,960 @@
+use crate::client::{
+    Alias, BackupRequest, BackupStatus, BatchDeleteMatch, BatchDeleteRequest, ClassSchema,
+    WeaviateApi, WeaviateObject,
+};
+use crate::conversions::{
+    class_definition, class_metric, class_name, graphql_value, ids_where, index_info,
+    index_property, object, object_id, point_from_hit, point_from_object, properties,
+    search_result_from_hit, selection, DEFAULT_TENANT,
+};
+use golem_vector::batch::write_in_batches;
+use golem_vector::connection::{ApiKeyStyle, VectorConnection};
+use golem_vector::durability::{DurableVector, ExtendedGuest};
+use golem_vector::filter::decode_filter;
+use golem_vector::filter::weaviate::to_weaviate_where;
+use golem_vector::golem::vector::collections::Guest as CollectionsGuest;
+use golem_vector::golem::vector::connection::ConnectionBorrow;
+use golem_vector::golem::vector::search::{Guest as SearchGuest, PointStream};
+use golem_vector::golem::vector::types::{
+    AliasInfo, BatchResult, CollectionConfig, CollectionInfo, FilterExpression, Id, Metadata,
+    PayloadIndexInfo, PayloadIndexType, Point, ScrollOptions, SearchQuery, SearchResult,
+    SnapshotInfo, TaskId, TaskStatus, VectorData, VectorError, VectorRecord, WriteOptions,
+};
+use golem_vector::golem::vector::vectors::{Guest as VectorsGuest, UpsertStream};
+use golem_vector::point_stream::{PointPage, PointPager, VectorPointStream};
+use golem_vector::recommend::{exclude_examples, recommendation_vector};
+use golem_vector::search::search_by_vector_of;
+use golem_vector::sparse::{reject_sparse_query, reject_sparse_records};
+use golem_vector::task::{Task, TaskKind};
+use golem_vector::upsert_stream::{BatchWriter, VectorUpsertStream};
+use golem_vector::write_options::weaviate_consistency_level;
+use golem_vector::LOGGING_STATE;
+use log::warn;
+use serde_json::{json, Map, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod client;
+mod conversions;
+
+/// Weaviate recommends batches of around a hundred objects
+const UPSERT_BATCH_SIZE: usize = 100;
+
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+struct WeaviateComponent;
+
+impl WeaviateComponent {
+    const ENDPOINT_ENV_VAR: &'static str = "WEAVIATE_URL";
+    const API_KEY_ENV_VAR: &'static str = "WEAVIATE_API_KEY";
+    const DEFAULT_ENDPOINT: &'static str = "http://localhost:8080";
+
+    /// Provider option listing the names of the vectors of a new class, separated by commas.
+    /// Every named vector gets the dimension and metric of the collection config.
+    const VECTOR_NAMES_OPTION: &'static str = "vector_names";
+
+    /// Provider option selecting the backup backend used for snapshots, `filesystem` by default
+    const BACKUP_BACKEND_OPTION: &'static str = "backup_backend";
+    const DEFAULT_BACKUP_BACKEND: &'static str = "filesystem";
+
+    fn api(connection: &ConnectionBorrow<'_>) -> Result<WeaviateApi, VectorError> {
+        LOGGING_STATE.with_borrow_mut(|state| state.init());
+
+        let connection = connection.get::<VectorConnection>();
+        let endpoint =
+            connection.endpoint_or_env(Self::ENDPOINT_ENV_VAR, Some(Self::DEFAULT_ENDPOINT))?;
+        let headers =
+            connection.auth_headers("Weaviate", ApiKeyStyle::Bearer, Self::API_KEY_ENV_VAR)?;
+        Ok(WeaviateApi::new(endpoint, headers))
+    }
+
+    fn vector_names(connection: &ConnectionBorrow<'_>) -> Vec<String> {
+        connection
+            .get::<VectorConnection>()
+            .provider_option(Self::VECTOR_NAMES_OPTION)
+            .map(|names| {
+                names
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn backup_backend(connection: &ConnectionBorrow<'_>) -> String {
+        connection
+            .get::<VectorConnection>()
+            .provider_option(Self::BACKUP_BACKEND_OPTION)
+            .unwrap_or(Self::DEFAULT_BACKUP_BACKEND)
+            .to_string()
+    }
+}
+
+/// A class with the tenant an operation is run on
+struct Target {
+    schema: ClassSchema,
+    tenant: Option<String>,
+}
+
+impl Target {
+    /// Looks up the schema of a class. Namespaces are the tenants of classes with multi-tenancy
+    /// enabled, operations without a namespace use the default tenant.
+    fn new(
+        api: &WeaviateApi,
+        collection: &str,
+        namespace: Option<&str>,
+    ) -> Result<Self, VectorError> {
+        let schema = api.get_class(&class_name(collection)?)?;
+        let multi_tenant = schema
+            .multi_tenancy_config
+            .as_ref()
+            .is_some_and(|config| config.enabled);
+        let tenant = match (multi_tenant, namespace) {
+            (true, namespace) => Some(namespace.unwrap_or(DEFAULT_TENANT).to_string()),
+            (false, None) => None,
+            (false, Some(_)) => {
+                return Err(VectorError::InvalidParams(format!(
+                    "Class {} does not use multi-tenancy, so it has no namespaces",
+                    schema.class
+                )))
+            }
+        };
+        Ok(Self { schema, tenant })
+    }
+
+    fn class(&self) -> &str {
+        &self.schema.class
+    }
+
+    /// The query parameters of object requests
+    fn params(&self, options: Option<&WriteOptions>) -> Vec<(&'static str, String)> {
+        let mut params = consistency_params(options);
+        if let Some(tenant) = &self.tenant {
+            params.push(("tenant", tenant.clone()));
+        }
+        params
+    }
+
+    /// The arguments of GraphQL queries selecting the tenant
+    fn tenant_argument(&self) -> Option<String> {
+        self.tenant
+            .as_ref()
+            .map(|tenant| format!("tenant: {}", Value::String(tenant.clone())))
+    }
+}
+
+/// The consistency level of a write. Batch requests take the tenant from each object instead
+/// of a parameter.
+fn consistency_params(options: Option<&WriteOptions>) -> Vec<(&'static str, String)> {
+    weaviate_consistency_level(options)
+        .map(|level| ("consistency_level", level.to_string()))
+        .into_iter()
+        .collect()
+}
+
+fn weaviate_where(filter: Option<FilterExpression>) -> Result<Option<Value>, VectorError> {
+    decode_filter(filter)?
+        .map(|filter| to_weaviate_where(&filter))
+        .transpose()
+}
+
+/// Weaviate writes are applied by the time they are acknowledged by the replicas of the
+/// consistency level, so only the consistency option is used
+fn ignore_write_ordering(options: Option<&WriteOptions>) {
+    if options.is_some_and(|options| options.ordering.is_some()) {
+        warn!("Weaviate does not support write ordering options, ignoring them");
+    }
+}
+
+/// Builds the aliased `Get` field of a search. Hybrid searches rank by both the keywords and
+/// the vector, and return a fused score instead of a distance. Their keyword ranking uses
+/// Weaviate's own BM25 index, so sparse query vectors are not supported.
+fn search_field(alias: &str, target: &Target, query: &SearchQuery) -> Result<String, VectorError> {
+    reject_sparse_query("Weaviate", query)?;
+    let mut search = Map::new();
+    search.insert("vector".to_string(), json!(query.vector));
+    if let Some(name) = &query.vector_name {
+        search.insert("targetVectors".to_string(), json!([name]));
+    }
+    let (operator, additional) = match &query.keywords {
+        Some(keywords) => {
+            search.insert("query".to_string(), json!(keywords.text));
+            if let Some(alpha) = keywords.alpha {
+                search.insert("alpha".to_string(), json!(alpha));
+            }
+            ("hybrid", "score")
+        }
+        None => ("nearVector", "distance"),
+    };
+
+    let mut arguments = vec![
+        format!("{operator}: {}", graphql_value(&Value::Object(search))),
+        format!("limit: {}", query.limit),
+    ];
+    if let Some(offset) = query.offset {
+        arguments.push(format!("offset: {offset}"));
+    }
+    if let Some(filter) = weaviate_where(query.filter.clone())? {
+        arguments.push(format!("where: {}", graphql_value(&filter)));
+    }
+    arguments.extend(target.tenant_argument());
+
+    Ok(format!(
+        "{alias}: {}({}) {}",
+        target.class(),
+        arguments.join(", "),
+        selection(
+            &target.schema,
+            &[additional],
+            query.include_vectors,
+            query.include_metadata
+        )
+    ))
+}
+
+/// Returns the objects of an aliased `Get` field
+fn get_results(data: &mut Value, alias: &str) -> Vec<Map<String, Value>> {
+    match data
+        .get_mut("Get")
+        .and_then(|get| get.get_mut(alias))
+        .map(Value::take)
+    {
+        Some(Value::Array(hits)) => hits
+            .into_iter()
+            .filter_map(|hit| match hit {
+                Value::Object(hit) => Some(hit),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Runs all searches in a single GraphQL request, as aliased fields of the same `Get` query
+fn search(
+    api: &WeaviateApi,
+    target: &Target,
+    queries: Vec<SearchQuery>,
+) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+    if queries.is_empty() {
+        return Ok(vec![]);
+    }
+    let fields = queries
+        .iter()
+        .enumerate()
+        .map(|(index, query)| search_field(&format!("q{index}"), target, query))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut data = api.graphql(format!("{{ Get {{ {} }} }}", fields.join(" ")))?;
+
+    Ok(queries
+        .into_iter()
+        .enumerate()
+        .map(|(index, query)| {
+            let metric = class_metric(&target.schema, query.vector_name.as_deref());
+            get_results(&mut data, &format!("q{index}"))
+                .into_iter()
+                .map(|hit| {
+                    search_result_from_hit(
+                        hit,
+                        metric,
+                        query.include_vectors,
+                        query.include_metadata,
+                    )
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Counts the objects of a class with an `Aggregate` query
+fn aggregate_count(
+    api: &WeaviateApi,
+    class: &str,
+    arguments: Vec<String>,
+) -> Result<u64, VectorError> {
+    let arguments = if arguments.is_empty() {
+        String::new()
+    } else {
+        format!("({})", arguments.join(", "))
+    };
+    let data = api.graphql(format!(
+        "{{ Aggregate {{ {class}{arguments} {{ meta {{ count }} }} }} }}"
+    ))?;
+    data.get("Aggregate")
+        .and_then(|aggregate| aggregate.get(class))
+        .and_then(|groups| groups.get(0))
+        .and_then(|group| group.pointer("/meta/count"))
+        .and_then(Value::as_u64)
+        .ok_or_else(|| VectorError::ProviderError("Weaviate did not return the count".to_string()))
+}
+
+fn backup_task_status(status: BackupStatus, result: Option<String>) -> TaskStatus {
+    match status.status.as_str() {
+        "SUCCESS" => TaskStatus::Succeeded(result),
+        "FAILED" | "CANCELED" => TaskStatus::Failed(
+            status
+                .error
+                .unwrap_or_else(|| format!("Backup {} {}", status.id, status.status)),
+        ),
+        _ => TaskStatus::InProgress,
+    }
+}
+
+impl CollectionsGuest for WeaviateComponent {
+    /// Creates a class with user provided vectors. Weaviate takes the dimension of the vectors
+    /// from the first object written to the class, so it is only used for product quantization.
+    fn create_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+        config: CollectionConfig,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let class = class_name(&name)?;
+        api.create_class(&class_definition(
+            &class,
+            &config,
+            &Self::vector_names(&connection),
+        )?)
+    }
+
+    fn delete_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.delete_class(&class_name(&name)?)
+    }
+
+    fn list_collections(connection: ConnectionBorrow<'_>) -> Result<Vec<String>, VectorError> {
+        let classes = Self::api(&connection)?.list_classes()?;
+        Ok(classes.into_iter().map(|class| class.class).collect())
+    }
+
+    /// Weaviate takes the dimension from the first object, so it is not known from the
+    /// schema. The objects of multi-tenant classes can only be counted per tenant, so their
+    /// point count is not known either.
+    fn describe_collection(
+        connection: ConnectionBorrow<'_>,
+        name: String,
+    ) -> Result<CollectionInfo, VectorError> {
+        let api = Self::api(&connection)?;
+        let schema = api.get_class(&class_name(&name)?)?;
+        let multi_tenant = schema
+            .multi_tenancy_config
+            .as_ref()
+            .is_some_and(|config| config.enabled);
+        let point_count = if multi_tenant {
+            None
+        } else {
+            Some(aggregate_count(&api, &schema.class, vec![])?)
+        };
+        Ok(CollectionInfo {
+            name: schema.class.clone(),
+            dimension: None,
+            metric: class_metric(&schema, None),
+            point_count,
+        })
+    }
+
+    /// Snapshots are Weaviate backups of a single class, stored in the configured backend
+    fn create_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<TaskId, VectorError> {
+        let api = Self::api(&connection)?;
+        let class = class_name(&collection)?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+        let status = api.create_backup(
+            &Self::backup_backend(&connection),
+            &BackupRequest {
+                id: Some(format!("{}-{timestamp}", class.to_lowercase())),
+                include: vec![class],
+            },
+        )?;
+        Ok(Task::new(TaskKind::CreateSnapshot, collection, status.id).encode())
+    }
+
+    /// Weaviate restores a backup into a class of the same name, which must not exist
+    fn restore_snapshot(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        snapshot: String,
+    ) -> Result<TaskId, VectorError> {
+        let api = Self::api(&connection)?;
+        let status = api.restore_backup(
+            &Self::backup_backend(&connection),
+            &snapshot,
+            &BackupRequest {
+                id: None,
+                include: vec![class_name(&collection)?],
+            },
+        )?;
+        Ok(Task::new(TaskKind::RestoreSnapshot, collection, status.id).encode())
+    }
+
+    fn list_snapshots(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<SnapshotInfo>, VectorError> {
+        let api = Self::api(&connection)?;
+        let class = class_name(&collection)?;
+        let backups = api.list_backups(&Self::backup_backend(&connection))?;
+        Ok(backups
+            .into_iter()
+            .filter(|backup| backup.status == "SUCCESS" && backup.classes.contains(&class))
+            .map(|backup| SnapshotInfo {
+                name: backup.id,
+                created_at: backup.started_at,
+                size_bytes: None,
+            })
+            .collect())
+    }
+
+    fn get_task_status(
+        connection: ConnectionBorrow<'_>,
+        task: TaskId,
+    ) -> Result<TaskStatus, VectorError> {
+        let api = Self::api(&connection)?;
+        let backend = Self::backup_backend(&connection);
+        let task = Task::decode(&task)?;
+        match task.kind {
+            TaskKind::CreateSnapshot => {
+                let status = api.backup_status(&backend, &task.provider_id)?;
+                Ok(backup_task_status(status, Some(task.provider_id)))
+            }
+            TaskKind::RestoreSnapshot => {
+                let status = api.restore_status(&backend, &task.provider_id)?;
+                Ok(backup_task_status(status, None))
+            }
+        }
+    }
+
+    /// Weaviate indexes every property when it is created, automatically on the first write
+    /// of a field. Creating an index adds the property with the given type ahead of time, so it
+    /// has to be done before the field is written.
+    fn create_payload_index(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        field: String,
+        index_type: PayloadIndexType,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.add_property(
+            &class_name(&collection)?,
+            &index_property(field, index_type),
+        )
+    }
+
+    /// Lists the filterable properties of the class
+    fn list_payload_indexes(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+    ) -> Result<Vec<PayloadIndexInfo>, VectorError> {
+        let schema = Self::api(&connection)?.get_class(&class_name(&collection)?)?;
+        Ok(schema.properties.iter().filter_map(index_info).collect())
+    }
+
+    fn create_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.create_alias(&Alias {
+            alias: class_name(&alias)?,
+            class: class_name(&collection)?,
+        })
+    }
+
+    /// Weaviate moves an existing alias to another class atomically
+    fn switch_alias(
+        connection: ConnectionBorrow<'_>,
+        alias: String,
+        collection: String,
+    ) -> Result<(), VectorError> {
+        Self::api(&connection)?.replace_alias(&Alias {
+            alias: class_name(&alias)?,
+            class: class_name(&collection)?,
+        })
+    }
+
+    fn delete_alias(connection: ConnectionBorrow<'_>, alias: String) -> Result<(), VectorError> {
+        Self::api(&connection)?.delete_alias(&class_name(&alias)?)
+    }
+
+    fn list_aliases(connection: ConnectionBorrow<'_>) -> Result<Vec<AliasInfo>, VectorError> {
+        let aliases = Self::api(&connection)?.list_aliases()?;
+        Ok(aliases
+            .into_iter()
+            .map(|alias| AliasInfo {
+                alias: alias.alias,
+                collection: alias.class,
+            })
+            .collect())
+    }
+}
+
+/// Writes the objects of a batch with a single batch request
+struct WeaviateUpsert {
+    api: WeaviateApi,
+    target: Target,
+    params: Vec<(&'static str, String)>,
+}
+
+impl BatchWriter for WeaviateUpsert {
+    fn batch_size(&self) -> usize {
+        UPSERT_BATCH_SIZE
+    }
+
+    fn write_batch(&self, batch: &[VectorRecord]) -> Result<(), VectorError> {
+        reject_sparse_records("Weaviate", batch)?;
+        let objects = batch
+            .iter()
+            .map(|record| {
+                object(
+                    self.target.class(),
+                    &record.id,
+                    &record.vector,
+                    record.metadata.as_ref(),
+                    self.target.tenant.as_deref(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.api.batch_objects(objects, &self.params)
+    }
+}
+
+impl VectorsGuest for WeaviateComponent {
+    type UpsertStream = VectorUpsertStream<WeaviateUpsert>;
+
+    fn upsert_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        vectors: Vec<VectorRecord>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<BatchResult, VectorError> {
+        let writer = Self::batch_writer(connection, collection, namespace, options)?;
+        Ok(write_in_batches(&vectors, writer.batch_size(), |batch| {
+            writer.write_batch(batch)
+        }))
+    }
+
+    fn open_upsert_stream(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> UpsertStream {
+        UpsertStream::new(VectorUpsertStream::new(Self::batch_writer(
+            connection, collection, namespace, options,
+        )))
+    }
+
+    /// Merges the metadata into the properties of every point. Ids that do not exist are
+    /// skipped.
+    fn set_payload(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        metadata: Metadata,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        ignore_write_ordering(options.as_ref());
+        let params = target.params(options.as_ref());
+
+        for id in ids {
+            let patch = WeaviateObject {
+                class: target.class().to_string(),
+                id: object_id(&id)?,
+                properties: properties(Some(&metadata)),
+                tenant: target.tenant.clone(),
+                ..Default::default()
+            };
+            match api.patch_object(&patch, &params) {
+                Ok(()) | Err(VectorError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the vectors of a point, keeping its properties. Named vectors which are not
+    /// given keep their current values.
+    fn update_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        vector: VectorData,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        ignore_write_ordering(options.as_ref());
+
+        let mut patch = object(target.class(), &id, &vector, None, target.tenant.as_deref())?;
+        if let Some(updates) = patch.vectors.take() {
+            let existing = api
+                .get_object(target.class(), &patch.id, &target.params(None))?
+                .ok_or_else(|| VectorError::NotFound(format!("Point {id} does not exist")))?;
+            let mut vectors = existing.vectors.unwrap_or_default();
+            vectors.extend(updates);
+            patch.vectors = Some(vectors);
+        }
+        api.patch_object(&patch, &target.params(options.as_ref()))
+    }
+
+    /// Gets the objects with a `Get` query filtered by their ids
+    fn get_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        include_vectors: bool,
+        include_metadata: bool,
+    ) -> Result<Vec<Point>, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let ids = ids
+            .iter()
+            .map(|id| object_id(id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut arguments = vec![
+            format!("limit: {}", ids.len()),
+            format!("where: {}", graphql_value(&ids_where(&ids)?)),
+        ];
+        arguments.extend(target.tenant_argument());
+        let mut data = api.graphql(format!(
+            "{{ Get {{ points: {}({}) {} }} }}",
+            target.class(),
+            arguments.join(", "),
+            selection(&target.schema, &[], include_vectors, include_metadata)
+        ))?;
+        let mut points: Vec<Point> = get_results(&mut data, "points")
+            .into_iter()
+            .map(|hit| point_from_hit(hit, include_vectors, include_metadata))
+            .collect();
+        points.sort_by_key(|point| ids.iter().position(|id| *id == point.id));
+        Ok(points)
+    }
+
+    fn delete_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        ids: Vec<Id>,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<(), VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        ignore_write_ordering(options.as_ref());
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        api.batch_delete(
+            &BatchDeleteRequest {
+                selection: BatchDeleteMatch {
+                    class: target.class().to_string(),
+                    filter: ids_where(&ids)?,
+                },
+                output: "minimal".to_string(),
+            },
+            &target.params(options.as_ref()),
+        )
+    }
+}
+
+impl SearchGuest for WeaviateComponent {
+    type PointStream = VectorPointStream<WeaviatePointPager>;
+
+    /// Searches with `nearVector`, or with `hybrid` when the query has keywords
+    fn search_vectors(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        query: SearchQuery,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        Ok(search(&api, &target, vec![query])?
+            .pop()
+            .unwrap_or_default())
+    }
+
+    fn query_batch(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        queries: Vec<SearchQuery>,
+        namespace: Option<String>,
+    ) -> Result<Vec<Vec<SearchResult>>, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        search(&api, &target, queries)
+    }
+
+    /// Weaviate can only search near a single object, so the examples' vectors are combined
+    /// into a query vector, which is searched with the example points excluded from the results
+    fn recommend(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        positive: Vec<Id>,
+        negative: Vec<Id>,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        let params = target.params(None);
+
+        let examples = |ids: &[Id]| -> Result<Vec<Vec<f32>>, VectorError> {
+            ids.iter()
+                .map(|id| {
+                    let object = api
+                        .get_object(target.class(), &object_id(id)?, &params)?
+                        .ok_or_else(|| {
+                            VectorError::NotFound(format!("Example point {id} does not exist"))
+                        })?;
+                    match point_from_object(object, true, false).vector {
+                        Some(VectorData::Dense(values)) => Ok(values),
+                        _ => Err(VectorError::UnsupportedFeature(
+                            "Weaviate recommendations are only supported for single vectors"
+                                .to_string(),
+                        )),
+                    }
+                })
+                .collect()
+        };
+        let vector = recommendation_vector(&examples(&positive)?, &examples(&negative)?)?;
+
+        let results = search(
+            &api,
+            &target,
+            vec![SearchQuery {
+                vector,
+                vector_name: None,
+                limit: limit + (positive.len() + negative.len()) as u32,
+                offset: None,
+                filter,
+                include_vectors: false,
+                include_metadata: true,
+                keywords: None,
+                sparse: None,
+            }],
+        )?
+        .pop()
+        .unwrap_or_default();
+        Ok(exclude_examples(results, &positive, &negative, limit))
+    }
+
+    /// Searches with the vector of the object, for classes with a single vector
+    fn search_by_id(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        id: Id,
+        limit: u32,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<Vec<SearchResult>, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        let id = object_id(&id)?;
+
+        search_by_vector_of(
+            &id,
+            limit,
+            |id| {
+                let object = api
+                    .get_object(target.class(), id, &target.params(None))?
+                    .ok_or_else(|| VectorError::NotFound(format!("Point {id} does not exist")))?;
+                match point_from_object(object, true, false).vector {
+                    Some(VectorData::Dense(values)) => Ok(values),
+                    _ => Err(VectorError::UnsupportedFeature(
+                        "Weaviate searches by id are only supported for single vectors".to_string(),
+                    )),
+                }
+            },
+            |vector, limit| {
+                Ok(search(
+                    &api,
+                    &target,
+                    vec![SearchQuery {
+                        vector,
+                        vector_name: None,
+                        limit,
+                        offset: None,
+                        filter,
+                        include_vectors: false,
+                        include_metadata: true,
+                        keywords: None,
+                        sparse: None,
+                    }],
+                )?
+                .pop()
+                .unwrap_or_default())
+            },
+        )
+    }
+
+    fn count(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        filter: Option<FilterExpression>,
+        namespace: Option<String>,
+    ) -> Result<u64, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+
+        let mut arguments = Vec::new();
+        if let Some(filter) = weaviate_where(filter)? {
+            arguments.push(format!("where: {}", graphql_value(&filter)));
+        }
+        arguments.extend(target.tenant_argument());
+        aggregate_count(&api, target.class(), arguments)
+    }
+
+    fn scroll_points(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> PointStream {
+        PointStream::new(VectorPointStream::new(Self::point_pager(
+            connection, collection, options, namespace,
+        )))
+    }
+}
+
+impl ExtendedGuest for WeaviateComponent {
+    type BatchWriter = WeaviateUpsert;
+    type PointPager = WeaviatePointPager;
+
+    fn batch_writer(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        namespace: Option<String>,
+        options: Option<WriteOptions>,
+    ) -> Result<WeaviateUpsert, VectorError> {
+        let api = Self::api(&connection)?;
+        let target = Target::new(&api, &collection, namespace.as_deref())?;
+        ignore_write_ordering(options.as_ref());
+        Ok(WeaviateUpsert {
+            api,
+            target,
+            params: consistency_params(options.as_ref()),
+        })
+    }
+
+    fn point_pager(
+        connection: ConnectionBorrow<'_>,
+        collection: String,
+        options: ScrollOptions,
+        namespace: Option<String>,
+    ) -> WeaviatePointPager {
+        let scroll = Self::api(&connection).and_then(|api| {
+            let target = Target::new(&api, &collection, namespace.as_deref())?;
+            let filter = weaviate_where(options.filter.clone())?;
+            Ok(WeaviateScroll {
+                api,
+                target,
+                filter,
+                options,
+            })
+        });
+        WeaviatePointPager { scroll }
+    }
+}
+
+struct WeaviateScroll {
+    api: WeaviateApi,
+    target: Target,
+    filter: Option<Value>,
+    options: ScrollOptions,
+}
+
+/// Pages through objects with `Get` queries. Without a filter the cursor is the id of the
+/// last object of the previous page, which Weaviate's cursor API continues from. The cursor
+/// API cannot be combined with filters, so filtered scrolls page by offset, which Weaviate
+/// limits to its `QUERY_MAXIMUM_RESULTS` setting.
+///
+/// Errors of setting up the scroll are reported by the first `get-next` call, as opening
+/// a point stream cannot fail.
+struct WeaviatePointPager {
+    scroll: Result<WeaviateScroll, VectorError>,
+}
+
+impl PointPager for WeaviatePointPager {
+    fn fetch_page(&self, cursor: Option<&str>) -> Result<PointPage, VectorError> {
+        let scroll = self.scroll.as_ref().map_err(Clone::clone)?;
+        let limit = scroll.options.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+
+        let mut arguments = vec![format!("limit: {limit}")];
+        let offset = match &scroll.filter {
+            Some(filter) => {
+                arguments.push(format!("where: {}", graphql_value(filter)));
+                let offset = cursor
+                    .map(|cursor| cursor.parse::<u32>())
+                    .transpose()
+                    .map_err(|_| VectorError::InvalidParams(format!("Invalid cursor {cursor:?}")))?
+                    .unwrap_or_default();
+                arguments.push(format!("offset: {offset}"));
+                Some(offset)
+            }
+            None => {
+                if let Some(after) = cursor {
+                    arguments.push(format!("after: {}", Value::String(after.to_string())));
+                }
+                None
+            }
+        };
+        arguments.extend(scroll.target.tenant_argument());
+
+        let mut data = scroll.api.graphql(format!(
+            "{{ Get {{ page: {}({}) {} }} }}",
+            scroll.target.class(),
+            arguments.join(", "),
+            selection(
+                &scroll.target.schema,
+                &[],
+                scroll.options.include_vectors,
+                scroll.options.include_metadata
+            )
+        ))?;
+        let points: Vec<Point> = get_results(&mut data, "page")
+            .into_iter()
+            .map(|hit| {
+                point_from_hit(
+                    hit,
+                    scroll.options.include_vectors,
+                    scroll.options.include_metadata,
+                )
+            })
+            .collect();
+
+        let next_cursor = if points.len() as u32 >= limit {
+            match offset {
+                Some(offset) => Some((offset + limit).to_string()),
+                None => points.last().map(|point| point.id.clone()),
+            }
+        } else {
+            None
+        };
+        Ok(PointPage {
+            points,
+            next_cursor,
+        })
+    }
+}
+
+type DurableWeaviateComponent = DurableVector<WeaviateComponent>;
+
+golem_vector::export_vector!(DurableWeaviateComponent with_types_in golem_vector);